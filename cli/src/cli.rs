@@ -17,6 +17,8 @@
 //! Selendra CLI library.
 
 use clap::Parser;
+use selendra_primitives::election::{MinerSettings, MinerSolver};
+use std::path::PathBuf;
 
 #[allow(missing_docs)]
 #[derive(Debug, Parser)]
@@ -57,10 +59,25 @@ pub enum Subcommand {
 	#[clap(name = "benchmark-storage", about = "Benchmark storage speed.")]
 	BenchmarkStorage(frame_benchmarking_cli::StorageCmd),
 
+	/// Benchmarks the pallets of a runtime with the release settings, against the compiled WASM
+	/// runtime on a machine passing the host performance checks, and writes the weights into the
+	/// `weights/` directory of the runtime.
+	#[clap(name = "benchmark-pallet")]
+	BenchmarkPallet(BenchmarkPalletCmd),
+
 	/// Runs performance checks such as PVF compilation in order to measure machine
 	/// capabilities of running a validator.
 	HostPerfCheck,
 
+	/// Prepares and executes a PVF against a given PoV, reporting the time and memory
+	/// spent, so parachain teams can profile their candidates against validator limits.
+	#[clap(name = "pvf-execute")]
+	PvfExecute(PvfExecuteCmd),
+
+	/// Connects to a running node and checks its health, printing what operators should look
+	/// into: peers, session keys, disk headroom, clock drift and finality lag.
+	Doctor(DoctorCmd),
+
 	/// Try some command against runtime state.
 	#[cfg(feature = "try-runtime")]
 	TryRuntime(try_runtime_cli::TryRuntimeCmd),
@@ -72,6 +89,47 @@ pub enum Subcommand {
 	/// Key management CLI utilities
 	#[clap(subcommand)]
 	Key(sc_cli::KeySubcommand),
+
+	/// Database utilities.
+	#[clap(subcommand)]
+	Db(DbSubcommand),
+}
+
+/// Database utilities.
+#[derive(Debug, clap::Subcommand)]
+pub enum DbSubcommand {
+	/// Snapshot the parachains database of a stopped node: the availability, approval voting,
+	/// chain selection and dispute data, so a validator can be migrated to new hardware.
+	ExportParachains(ParachainsDbCmd),
+
+	/// Restore the parachains database of a node from a snapshot taken by `export-parachains`.
+	ImportParachains(ParachainsDbCmd),
+}
+
+#[allow(missing_docs)]
+#[derive(Debug, Parser)]
+pub struct ParachainsDbCmd {
+	/// The directory of the snapshot.
+	#[clap(parse(from_os_str))]
+	pub path: PathBuf,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: sc_cli::SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub database_params: sc_cli::DatabaseParams,
+}
+
+impl sc_cli::CliConfiguration for ParachainsDbCmd {
+	fn shared_params(&self) -> &sc_cli::SharedParams {
+		&self.shared_params
+	}
+
+	fn database_params(&self) -> Option<&sc_cli::DatabaseParams> {
+		Some(&self.database_params)
+	}
 }
 
 #[allow(missing_docs)]
@@ -81,6 +139,90 @@ pub struct ValidationWorkerCommand {
 	pub socket_path: String,
 }
 
+#[allow(missing_docs)]
+#[derive(Debug, Parser)]
+pub struct BenchmarkPalletCmd {
+	/// The chain to benchmark the runtime of.
+	#[clap(long, default_value = "selendra-dev")]
+	pub chain: String,
+
+	/// The pallet to benchmark, or `*` for all of them.
+	#[clap(long, default_value = "*")]
+	pub pallet: String,
+
+	/// The extrinsic of the pallet to benchmark, or `*` for all of them.
+	#[clap(long, default_value = "*")]
+	pub extrinsic: String,
+
+	/// The number of samples taken across the range of each component.
+	#[clap(long, default_value = "50")]
+	pub steps: u32,
+
+	/// The number of times each sample is repeated.
+	#[clap(long, default_value = "20")]
+	pub repeat: u32,
+
+	/// The directory the weight files are written to, one per pallet. Defaults to the `weights/`
+	/// directory of the runtime, relative to the root of the repository.
+	#[clap(long, parse(from_os_str))]
+	pub output: Option<PathBuf>,
+
+	/// Run the benchmarks even if the machine fails the host performance checks, in which case
+	/// the weights do not reflect the reference hardware.
+	#[clap(long)]
+	pub skip_host_perf_check: bool,
+}
+
+#[allow(missing_docs)]
+#[derive(Debug, Parser)]
+pub struct PvfExecuteCmd {
+	/// The id of the para the candidate belongs to.
+	#[clap(long)]
+	pub para: u32,
+
+	/// Path to the SCALE-encoded PoV, optionally compressed.
+	#[clap(long, parse(from_os_str))]
+	pub pov: PathBuf,
+
+	/// Path to the validation code of the para, optionally compressed.
+	#[clap(long, parse(from_os_str))]
+	pub code: PathBuf,
+
+	/// Path to the SCALE-encoded persisted validation data of the candidate.
+	#[clap(long, parse(from_os_str))]
+	pub validation_data: PathBuf,
+}
+
+#[allow(missing_docs)]
+#[derive(Debug, Parser)]
+pub struct DoctorCmd {
+	/// The WebSocket RPC endpoint of the node to check.
+	#[clap(long, default_value = "ws://127.0.0.1:9944")]
+	pub url: String,
+
+	/// The SS58 address of the validator stash, to check that the node has the session keys
+	/// registered on chain.
+	#[clap(long)]
+	pub validator: Option<String>,
+
+	/// The database path of the node, to check the disk headroom of the availability store.
+	#[clap(long, parse(from_os_str))]
+	pub database_path: Option<PathBuf>,
+
+	/// The free disk space in MiB below which the disk headroom is reported.
+	#[clap(long, default_value = "10240")]
+	pub min_free_space: u64,
+
+	/// The finality lag in blocks above which it is reported.
+	#[clap(long, default_value = "10")]
+	pub max_finality_lag: u32,
+
+	/// The difference in milliseconds between the local clock and the timestamp of the best
+	/// block above which it is reported.
+	#[clap(long, default_value = "12000")]
+	pub max_clock_drift: u64,
+}
+
 #[allow(missing_docs)]
 #[derive(Debug, Parser)]
 pub struct RunCmd {
@@ -118,6 +260,56 @@ pub struct RunCmd {
 	/// commonly `127.0.0.1:4040`.
 	#[clap(long)]
 	pub pyroscope_server: Option<String>,
+
+	/// Limit in MiB of the memory held in flight for PoVs and availability data.
+	///
+	/// Once exceeded, data of the oldest relay parents is shed. Defaults to 1024 MiB.
+	#[clap(long, value_name = "MiB")]
+	pub availability_memory_budget: Option<usize>,
+
+	/// The solver the offchain worker mines unsigned election solutions with.
+	///
+	/// PhragMMS tends to find better solutions than sequential Phragmén, at a higher cost.
+	/// Defaults to `seq-phragmen`.
+	#[clap(long, value_name = "SOLVER", possible_values = &["seq-phragmen", "phragmms"])]
+	pub election_solver: Option<String>,
+
+	/// The maximum number of balancing iterations run on mined election solutions.
+	///
+	/// Balancing evens out the stake behind the winners at the cost of computation. Defaults to 0,
+	/// which disables balancing.
+	#[clap(long, value_name = "COUNT")]
+	pub election_max_iterations: Option<u32>,
+
+	/// Stop balancing mined election solutions once no stake moves by more than this amount.
+	///
+	/// Defaults to 0.
+	#[clap(long, value_name = "BALANCE")]
+	pub election_balancing_tolerance: Option<u128>,
+}
+
+impl RunCmd {
+	/// The election miner settings passed to the offchain worker, `None` if none of the flags were
+	/// given and the runtime defaults apply.
+	pub fn election_miner_settings(&self) -> Option<MinerSettings> {
+		if self.election_solver.is_none() &&
+			self.election_max_iterations.is_none() &&
+			self.election_balancing_tolerance.is_none()
+		{
+			return None
+		}
+
+		let defaults = MinerSettings::default();
+		Some(MinerSettings {
+			solver: match self.election_solver.as_deref() {
+				Some("phragmms") => MinerSolver::PhragMMS,
+				Some(_) => MinerSolver::SeqPhragmen,
+				None => defaults.solver,
+			},
+			max_iterations: self.election_max_iterations.unwrap_or(defaults.max_iterations),
+			tolerance: self.election_balancing_tolerance.unwrap_or(defaults.tolerance),
+		})
+	}
 }
 
 #[allow(missing_docs)]
@@ -14,11 +14,11 @@
 // You should have received a copy of the GNU General Public License
 // along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
 
-use crate::cli::{Cli, Subcommand};
+use crate::cli::{Cli, DbSubcommand, Subcommand};
 use futures::future::TryFutureExt;
 use log::info;
 use sc_cli::{Role, RuntimeVersion, SubstrateCli};
-use service::{self, IdentifyVariant};
+use service::{self, ExecutionStrategy, IdentifyVariant};
 use sp_core::crypto::Ss58AddressFormat;
 use std::net::ToSocketAddrs;
 
@@ -163,6 +163,43 @@ fn host_perf_check() -> Result<()> {
 	}
 }
 
+/// Runs the benchmarks of the runtime pallets, which is only allowed for development chains. With
+/// `check_host`, the machine must pass the host performance checks first.
+fn benchmark(
+	cli: &Cli,
+	cmd: &frame_benchmarking_cli::BenchmarkCmd,
+	check_host: bool,
+) -> Result<()> {
+	let runner = cli.create_runner(cmd)?;
+	let chain_spec = &runner.config().chain_spec;
+	set_default_ss58_version(chain_spec);
+
+	ensure_dev(chain_spec).map_err(Error::Other)?;
+
+	if check_host {
+		host_perf_check()?;
+	}
+
+	#[cfg(feature = "cardamom-native")]
+	if chain_spec.is_cardamom() {
+		return Ok(runner.sync_run(|config| {
+			cmd.run::<service::cardamom_runtime::Block, service::CardamomExecutorDispatch>(config)
+				.map_err(|e| Error::SubstrateCli(e))
+		})?)
+	}
+
+	// else we assume it is selendra.
+	#[cfg(feature = "selendra-native")]
+	{
+		return Ok(runner.sync_run(|config| {
+			cmd.run::<service::selendra_runtime::Block, service::SelendraExecutorDispatch>(config)
+				.map_err(|e| Error::SubstrateCli(e))
+		})?)
+	}
+	#[cfg(not(feature = "selendra-native"))]
+	panic!("No runtime feature (selendra, cardamom) is enabled")
+}
+
 /// Launch a node, accepting arguments just like a regular node,
 /// accepts an alternative overseer generator, to adjust behavior
 /// for integration tests as needed.
@@ -210,7 +247,10 @@ where
 		None
 	};
 
-	runner.run_node_until_exit(move |config| async move {
+	let election_miner_settings = cli.run.election_miner_settings();
+
+	runner.run_node_until_exit(move |mut config| async move {
+		set_default_block_construction(&cli, &mut config);
 		let role = config.role.clone();
 
 		match role {
@@ -222,15 +262,39 @@ where
 				cli.run.beefy,
 				jaeger_agent,
 				None,
+				cli.run.availability_memory_budget.map(|mib| mib * 1024 * 1024),
 				false,
 				overseer_gen,
 			)
-			.map(|full| full.task_manager)
+			.map(|full| {
+				service::election_miner::store_miner_settings(
+					&full.backend,
+					election_miner_settings,
+				);
+				full.task_manager
+			})
 			.map_err(Into::into),
 		}
 	})
 }
 
+/// Build blocks with the Wasm runtime unless an execution strategy for block construction is given,
+/// even with `--dev`, which otherwise defaults all strategies to native.
+///
+/// The native runtime may diverge from the on-chain Wasm runtime the rest of the network imports
+/// blocks with, getting the blocks built with it rejected. Building blocks natively stays possible
+/// for debugging with `--execution-block-construction native`.
+fn set_default_block_construction(cli: &Cli, config: &mut sc_service::Configuration) {
+	let execution = &cli.run.base.import_params.execution_strategies;
+	if execution.execution_block_construction.is_none() && execution.execution.is_none() {
+		config.execution_strategies.block_construction = ExecutionStrategy::AlwaysWasm;
+	} else if config.execution_strategies.block_construction != ExecutionStrategy::AlwaysWasm {
+		log::warn!(
+			"⚠️  Building blocks with the native runtime, which may diverge from the Wasm one"
+		);
+	}
+}
+
 /// Parses selendra specific CLI arguments and run the service.
 pub fn run() -> Result<()> {
 	let cli: Cli = Cli::from_args();
@@ -366,35 +430,38 @@ pub fn run() -> Result<()> {
 				Ok(())
 			}
 		},
-		Some(Subcommand::Benchmark(cmd)) => {
-			let runner = cli.create_runner(cmd)?;
-			let chain_spec = &runner.config().chain_spec;
-			set_default_ss58_version(chain_spec);
-
-			ensure_dev(chain_spec).map_err(Error::Other)?;
-
-			#[cfg(feature = "cardamom-native")]
-			if chain_spec.is_cardamom() {
-				return Ok(runner.sync_run(|config| {
-					cmd.run::<service::cardamom_runtime::Block, service::CardamomExecutorDispatch>(
-						config,
-					)
-					.map_err(|e| Error::SubstrateCli(e))
-				})?)
+		Some(Subcommand::Benchmark(cmd)) => benchmark(&cli, cmd, false),
+		Some(Subcommand::BenchmarkPallet(cmd)) => {
+			let runtime = if cmd.chain.starts_with("cardamom") { "cardamom" } else { "selendra" };
+			let output = cmd
+				.output
+				.clone()
+				.unwrap_or_else(|| ["runtime", runtime, "src", "weights"].iter().collect());
+			if !output.is_dir() {
+				return Err(Error::Other(format!(
+					"The weights directory {} does not exist, run from the root of the repository or \
+					 pass `--output`",
+					output.display()
+				)))
 			}
 
-			// else we assume it is selendra.
-			#[cfg(feature = "selendra-native")]
-			{
-				return Ok(runner.sync_run(|config| {
-					cmd.run::<service::selendra_runtime::Block, service::SelendraExecutorDispatch>(
-						config,
-					)
-					.map_err(|e| Error::SubstrateCli(e))
-				})?)
-			}
-			#[cfg(not(feature = "selendra-native"))]
-			panic!("No runtime feature (selendra, cardamom) is enabled")
+			let args = [
+				"benchmark".to_string(),
+				format!("--chain={}", cmd.chain),
+				format!("--steps={}", cmd.steps),
+				format!("--repeat={}", cmd.repeat),
+				format!("--pallet={}", cmd.pallet),
+				format!("--extrinsic={}", cmd.extrinsic),
+				"--execution=wasm".to_string(),
+				"--wasm-execution=compiled".to_string(),
+				"--heap-pages=4096".to_string(),
+				format!("--output={}", output.display()),
+			];
+			let bench_cmd =
+				<frame_benchmarking_cli::BenchmarkCmd as clap::Parser>::try_parse_from(args)
+					.map_err(|e| Error::Other(e.to_string()))?;
+
+			benchmark(&cli, &bench_cmd, !cmd.skip_host_perf_check)
 		},
 		Some(Subcommand::BenchmarkStorage(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
@@ -419,7 +486,35 @@ pub fn run() -> Result<()> {
 
 			host_perf_check()
 		},
+		Some(Subcommand::PvfExecute(cmd)) => {
+			let mut builder = sc_cli::LoggerBuilder::new("");
+			builder.with_colors(true);
+			builder.init()?;
+
+			crate::pvf_execute::pvf_execute(cmd)
+		},
+		Some(Subcommand::Doctor(cmd)) => {
+			let mut builder = sc_cli::LoggerBuilder::new("");
+			builder.with_colors(true);
+			builder.init()?;
+
+			crate::doctor::doctor(cmd)
+		},
 		Some(Subcommand::Key(cmd)) => Ok(cmd.run(&cli)?),
+		Some(Subcommand::Db(DbSubcommand::ExportParachains(cmd))) => {
+			let runner = cli.create_runner(cmd)?;
+			Ok(runner.sync_run(|config| {
+				service::export_parachains_db(&config.database, &cmd.path)
+					.map_err(service::Error::from)
+			})?)
+		},
+		Some(Subcommand::Db(DbSubcommand::ImportParachains(cmd))) => {
+			let runner = cli.create_runner(cmd)?;
+			Ok(runner.sync_run(|config| {
+				service::import_parachains_db(&config.database, &cmd.path)
+					.map_err(service::Error::from)
+			})?)
+		},
 		#[cfg(feature = "try-runtime")]
 		Some(Subcommand::TryRuntime(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
@@ -0,0 +1,270 @@
+// Copyright 2017-2020 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Health checks of a running node, for operators.
+
+use crate::{cli::DoctorCmd, error::Error};
+use jsonrpsee::{
+	core::client::ClientT,
+	rpc_params,
+	types::ParamsSer,
+	ws_client::{WsClient, WsClientBuilder},
+};
+use log::{info, warn};
+use parity_scale_codec::Decode;
+use selendra_primitives::v1::{Hash, Header};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use sp_core::{
+	crypto::{AccountId32, Ss58Codec},
+	hashing::{twox_128, twox_64},
+	Bytes,
+};
+use std::{
+	path::Path,
+	time::{SystemTime, UNIX_EPOCH},
+};
+
+/// The outcome of a health check.
+enum Finding {
+	Healthy(String),
+	Problem(String),
+}
+
+/// Run the health checks given by `cmd` against a node, logging a finding per check.
+///
+/// Fails if any of the checks finds a problem.
+pub fn doctor(cmd: &DoctorCmd) -> Result<(), Error> {
+	let runtime = tokio::runtime::Builder::new_current_thread()
+		.enable_all()
+		.build()
+		.map_err(|e| Error::Other(format!("Failed to start the async runtime: {}", e)))?;
+
+	runtime.block_on(run_checks(cmd))
+}
+
+async fn run_checks(cmd: &DoctorCmd) -> Result<(), Error> {
+	let client = WsClientBuilder::default()
+		.build(&cmd.url)
+		.await
+		.map_err(|e| Error::Other(format!("Failed to connect to {}: {}", cmd.url, e)))?;
+
+	info!("Checking the node at {}", cmd.url);
+
+	let mut findings = vec![
+		check_sync(&client).await?,
+		check_validator_peers(&client).await?,
+		check_finality_lag(&client, cmd.max_finality_lag).await?,
+		check_clock_drift(&client, cmd.max_clock_drift).await?,
+	];
+	if let Some(ref validator) = cmd.validator {
+		findings.push(check_session_keys(&client, validator).await?);
+	}
+	if let Some(ref path) = cmd.database_path {
+		findings.push(check_disk_headroom(path, cmd.min_free_space)?);
+	}
+
+	let mut problems = 0;
+	for finding in findings {
+		match finding {
+			Finding::Healthy(message) => info!("🟢 {}", message),
+			Finding::Problem(message) => {
+				problems += 1;
+				warn!("🔴 {}", message);
+			},
+		}
+	}
+
+	if problems == 0 {
+		info!("All health checks passed");
+		Ok(())
+	} else {
+		Err(Error::Other(format!("{} health checks found problems", problems)))
+	}
+}
+
+/// The node is connected to peers and done syncing.
+async fn check_sync(client: &WsClient) -> Result<Finding, Error> {
+	let health: Value = request(client, "system_health", None).await?;
+	let peers = health["peers"].as_u64().unwrap_or_default();
+	let is_syncing = health["isSyncing"].as_bool().unwrap_or_default();
+
+	Ok(if peers == 0 {
+		Finding::Problem(
+			"The node has no peers, check its network configuration and bootnodes".into(),
+		)
+	} else if is_syncing {
+		Finding::Problem(format!("The node is still syncing, with {} peers", peers))
+	} else {
+		Finding::Healthy(format!("The node is synced, with {} peers", peers))
+	})
+}
+
+/// A validator is connected to the other validators of the active set, which it gossips with.
+async fn check_validator_peers(client: &WsClient) -> Result<Finding, Error> {
+	let roles: Value = request(client, "system_nodeRoles", None).await?;
+	let validators: Vec<AccountId32> =
+		storage(client, storage_value_key(b"Session", b"Validators"))
+			.await?
+			.unwrap_or_default();
+	let peers: Value = request(client, "system_peers", None).await?;
+	let authority_peers = peers
+		.as_array()
+		.map_or(0, |peers| peers.iter().filter(|peer| peer["roles"] == "AUTHORITY").count());
+
+	if !roles
+		.as_array()
+		.map_or(false, |roles| roles.iter().any(|role| role == "Authority"))
+	{
+		return Ok(Finding::Healthy(format!(
+			"The node is not a validator and is connected to {} authority peers",
+			authority_peers,
+		)))
+	}
+
+	let other_validators = validators.len().saturating_sub(1);
+	Ok(if authority_peers * 3 < other_validators * 2 {
+		Finding::Problem(format!(
+			"The node is connected to only {} authority peers, while the active set has {} other \
+			validators. Check that its public address is reachable and advertised.",
+			authority_peers, other_validators,
+		))
+	} else {
+		Finding::Healthy(format!(
+			"The node is connected to {} authority peers, the active set has {} other validators",
+			authority_peers, other_validators,
+		))
+	})
+}
+
+/// The finalized block does not lag far behind the best block.
+async fn check_finality_lag(client: &WsClient, max_lag: u32) -> Result<Finding, Error> {
+	let best: Header = request(client, "chain_getHeader", None).await?;
+	let finalized_hash: Hash = request(client, "chain_getFinalizedHead", None).await?;
+	let finalized: Header = request(client, "chain_getHeader", rpc_params![finalized_hash]).await?;
+	let lag = best.number.saturating_sub(finalized.number);
+
+	Ok(if lag > max_lag {
+		Finding::Problem(format!(
+			"Finality lags {} blocks behind the best block #{}, the last finalized block is #{}",
+			lag, best.number, finalized.number,
+		))
+	} else {
+		Finding::Healthy(format!("Finality lags {} blocks behind the best block", lag))
+	})
+}
+
+/// The local clock agrees with the timestamp of the best block.
+async fn check_clock_drift(client: &WsClient, max_drift: u64) -> Result<Finding, Error> {
+	let timestamp: u64 = storage(client, storage_value_key(b"Timestamp", b"Now"))
+		.await?
+		.ok_or_else(|| Error::Other("The best block has no timestamp".into()))?;
+	let now = SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.map_err(|e| Error::Other(format!("The local clock is before the epoch: {}", e)))?
+		.as_millis() as u64;
+
+	Ok(if now + max_drift < timestamp {
+		Finding::Problem(format!(
+			"The local clock is {} ms behind the timestamp of the best block, check NTP",
+			timestamp - now,
+		))
+	} else if now > timestamp + max_drift {
+		Finding::Problem(format!(
+			"The local clock is {} ms ahead of the timestamp of the best block, check NTP and \
+			whether blocks are being produced",
+			now - timestamp,
+		))
+	} else {
+		Finding::Healthy(format!(
+			"The local clock is within {} ms of the timestamp of the best block",
+			max_drift,
+		))
+	})
+}
+
+/// The node has the session keys registered on chain for the validator.
+async fn check_session_keys(client: &WsClient, validator: &str) -> Result<Finding, Error> {
+	let stash = AccountId32::from_ss58check(validator)
+		.map_err(|e| Error::Other(format!("Invalid validator address {}: {:?}", validator, e)))?;
+	let stash: &[u8] = stash.as_ref();
+	let key = [storage_value_key(b"Session", b"NextKeys"), twox_64(stash).to_vec(), stash.to_vec()]
+		.concat();
+	let keys: Option<Bytes> = request(client, "state_getStorage", rpc_params![Bytes(key)]).await?;
+
+	let keys = match keys {
+		Some(keys) => keys,
+		None =>
+			return Ok(Finding::Problem(format!(
+				"No session keys are registered on chain for {}, rotate the keys of the node and \
+				set them on chain",
+				validator,
+			))),
+	};
+
+	let has_keys: bool = request(client, "author_hasSessionKeys", rpc_params![keys]).await?;
+	Ok(if has_keys {
+		Finding::Healthy(format!("The node has the session keys registered for {}", validator))
+	} else {
+		Finding::Problem(format!(
+			"The keystore of the node lacks the session keys registered on chain for {}, rotate \
+			the keys of the node and set them on chain",
+			validator,
+		))
+	})
+}
+
+/// The disk holding the database has room for the availability store to grow.
+fn check_disk_headroom(path: &Path, min_free_space: u64) -> Result<Finding, Error> {
+	let free_space = fs2::available_space(path).map_err(|e| {
+		Error::Other(format!("Failed to query the free space of {}: {}", path.display(), e))
+	})? / (1024 * 1024);
+
+	Ok(if free_space < min_free_space {
+		Finding::Problem(format!(
+			"Only {} MiB are free on the disk of {}, the availability store needs at least {} MiB",
+			free_space,
+			path.display(),
+			min_free_space,
+		))
+	} else {
+		Finding::Healthy(format!("{} MiB are free on the disk of {}", free_space, path.display()))
+	})
+}
+
+async fn request<R: DeserializeOwned>(
+	client: &WsClient,
+	method: &str,
+	params: Option<ParamsSer<'_>>,
+) -> Result<R, Error> {
+	client
+		.request(method, params)
+		.await
+		.map_err(|e| Error::Other(format!("RPC call {} failed: {}", method, e)))
+}
+
+/// Fetch and decode the storage item at `key` of the best block.
+async fn storage<T: Decode>(client: &WsClient, key: Vec<u8>) -> Result<Option<T>, Error> {
+	let data: Option<Bytes> = request(client, "state_getStorage", rpc_params![Bytes(key)]).await?;
+
+	data.map(|data| T::decode(&mut &data[..]))
+		.transpose()
+		.map_err(|e| Error::Other(format!("Failed to decode storage item: {}", e)))
+}
+
+fn storage_value_key(pallet: &[u8], item: &[u8]) -> Vec<u8> {
+	[twox_128(pallet), twox_128(item)].concat()
+}
@@ -23,9 +23,13 @@ mod cli;
 #[cfg(feature = "cli")]
 mod command;
 #[cfg(feature = "cli")]
+mod doctor;
+#[cfg(feature = "cli")]
 mod error;
 #[cfg(all(feature = "cli", build_type = "release"))]
 mod host_perf_check;
+#[cfg(feature = "cli")]
+mod pvf_execute;
 
 #[cfg(feature = "full-node")]
 pub use service::RuntimeApiCollection;
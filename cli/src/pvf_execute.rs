@@ -0,0 +1,134 @@
+// Copyright 2017-2020 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Offline profiling of a PVF against a given candidate.
+
+use crate::{cli::PvfExecuteCmd, error::Error};
+use log::{info, warn};
+use parity_scale_codec::{Decode, Encode};
+use selendra_node_core_pvf::{sp_maybe_compressed_blob, ExecutorParams};
+use selendra_node_primitives::{
+	PoV, APPROVAL_EXECUTION_TIMEOUT, BACKING_EXECUTION_TIMEOUT, POV_BOMB_LIMIT,
+	VALIDATION_CODE_BOMB_LIMIT,
+};
+use selendra_parachain::primitives::{ValidationParams, ValidationResult};
+use selendra_performance_test::PVF_PREPARE_TIME_LIMIT;
+use selendra_primitives::v1::PersistedValidationData;
+use std::{
+	path::Path,
+	time::{Duration, Instant},
+};
+
+/// Prepare and execute the PVF given by `cmd`, logging the time and memory spent.
+pub fn pvf_execute(cmd: &PvfExecuteCmd) -> Result<(), Error> {
+	let code = read_file(&cmd.code)?;
+	let code = sp_maybe_compressed_blob::decompress(&code, VALIDATION_CODE_BOMB_LIMIT)
+		.map_err(|e| Error::Other(format!("Failed to decompress validation code: {:?}", e)))?;
+
+	let raw_pov = read_file(&cmd.pov)?;
+	let encoded_pov = sp_maybe_compressed_blob::decompress(&raw_pov, POV_BOMB_LIMIT)
+		.map_err(|e| Error::Other(format!("Failed to decompress PoV: {:?}", e)))?;
+	let pov = PoV::decode(&mut &encoded_pov[..])
+		.map_err(|e| Error::Other(format!("Failed to decode PoV: {}", e)))?;
+
+	let validation_data = read_file(&cmd.validation_data)?;
+	let validation_data = PersistedValidationData::decode(&mut &validation_data[..])
+		.map_err(|e| Error::Other(format!("Failed to decode persisted validation data: {}", e)))?;
+
+	info!("Profiling PVF of para {} ({} bytes of code)", cmd.para, code.len());
+
+	let pov_size = pov.encoded_size();
+	if pov_size > validation_data.max_pov_size as usize {
+		warn!(
+			"🔴 PoV size of {} bytes exceeds the maximum of {} bytes",
+			pov_size, validation_data.max_pov_size,
+		);
+	} else {
+		info!("PoV size: {} bytes (maximum {} bytes)", pov_size, validation_data.max_pov_size);
+	}
+
+	let params = ValidationParams {
+		parent_head: validation_data.parent_head.clone(),
+		block_data: pov.block_data,
+		relay_parent_number: validation_data.relay_parent_number,
+		relay_parent_storage_root: validation_data.relay_parent_storage_root,
+	};
+
+	// The parameters of a session are not known offline, so the defaults are profiled.
+	let executor_params = ExecutorParams::default();
+
+	let start = Instant::now();
+	let blob = selendra_node_core_pvf::prevalidate(&code)
+		.map_err(|e| Error::Other(format!("PVF prevalidation failed: {:?}", e)))?;
+	let artifact = selendra_node_core_pvf::prepare(blob, &executor_params)
+		.map_err(|e| Error::Other(format!("PVF preparation failed: {:?}", e)))?;
+	report_time("Preparation", start.elapsed(), PVF_PREPARE_TIME_LIMIT);
+
+	let executor = selendra_node_core_pvf::TaskExecutor::new().map_err(Error::Other)?;
+	let start = Instant::now();
+	let result = unsafe {
+		// SAFETY: This is safe since the artifact is obtained by calling `prepare`.
+		selendra_node_core_pvf::execute(&artifact, &params.encode(), &executor_params, executor)
+	}
+	.map_err(|e| Error::Other(format!("PVF execution failed: {:?}", e)))?;
+	let elapsed = start.elapsed();
+
+	report_time("Execution (backing)", elapsed, BACKING_EXECUTION_TIMEOUT);
+	report_time("Execution (approval)", elapsed, APPROVAL_EXECUTION_TIMEOUT);
+
+	match peak_memory_usage() {
+		Some(kib) => info!("Peak memory usage: {} KiB", kib),
+		None => info!("Peak memory usage: unavailable on this platform"),
+	}
+
+	let result = ValidationResult::decode(&mut &result[..])
+		.map_err(|e| Error::Other(format!("Failed to decode validation result: {}", e)))?;
+
+	info!(
+		"Validation result: head data of {} bytes, {} upward and {} horizontal messages, \
+		{} processed downward messages, new code: {}",
+		result.head_data.0.len(),
+		result.upward_messages.len(),
+		result.horizontal_messages.len(),
+		result.processed_downward_messages,
+		result.new_validation_code.is_some(),
+	);
+
+	Ok(())
+}
+
+fn read_file(path: &Path) -> Result<Vec<u8>, Error> {
+	std::fs::read(path)
+		.map_err(|e| Error::Other(format!("Failed to read {}: {}", path.display(), e)))
+}
+
+fn report_time(stage: &str, elapsed: Duration, limit: Duration) {
+	if elapsed <= limit {
+		info!("🟢 {} took {:?}, limit {:?}", stage, elapsed, limit);
+	} else {
+		warn!("🔴 {} took {:?}, exceeding the limit of {:?}", stage, elapsed, limit);
+	}
+}
+
+/// The peak resident set size of this process in KiB, if the platform exposes it.
+fn peak_memory_usage() -> Option<u64> {
+	let status = std::fs::read_to_string("/proc/self/status").ok()?;
+	status
+		.lines()
+		.find(|line| line.starts_with("VmHWM:"))
+		.and_then(|line| line.split_whitespace().nth(1))
+		.and_then(|kib| kib.parse().ok())
+}
@@ -160,6 +160,13 @@ pub struct RunCmd {
 		conflicts_with = "ferdie"
 	)]
 	pub relay_chain_rpc_url: Option<Url>,
+
+	/// Secret URI of the key the collator signs its collations with.
+	///
+	/// The key is put into the keystore under the `coll` key type. Without this flag the collator
+	/// key already in the keystore is used, or a new one is generated there.
+	#[clap(long, value_name = "SURI")]
+	pub collator_key: Option<String>,
 }
 
 /// Options only relevant for collator nodes
@@ -167,6 +174,8 @@ pub struct RunCmd {
 pub struct CollatorOptions {
 	/// Location of relay chain full node
 	pub relay_chain_rpc_url: Option<Url>,
+	/// Secret URI of the collator key
+	pub collator_key: Option<String>,
 }
 
 /// A non-redundant version of the `RunCmd` that sets the `validator` field when the
@@ -189,7 +198,10 @@ impl RunCmd {
 
 	/// Create [`CollatorOptions`] representing options only relevant to parachain collator nodes
 	pub fn collator_options(&self) -> CollatorOptions {
-		CollatorOptions { relay_chain_rpc_url: self.relay_chain_rpc_url.clone() }
+		CollatorOptions {
+			relay_chain_rpc_url: self.relay_chain_rpc_url.clone(),
+			collator_key: self.collator_key.clone(),
+		}
 	}
 }
 
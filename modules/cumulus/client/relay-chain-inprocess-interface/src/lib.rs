@@ -19,6 +19,7 @@ use std::{pin::Pin, sync::Arc, time::Duration};
 use async_trait::async_trait;
 use cumulus_primitives_core::{
 	relay_chain::{
+		relay_state_proof::RelayStateProofApi,
 		v1::{CommittedCandidateReceipt, OccupiedCoreAssumption, SessionIndex, ValidatorId},
 		v2::ParachainHost,
 		Block as PBlock, BlockId, Hash as PHash, Header as PHeader, InboundHrmpMessage,
@@ -39,7 +40,7 @@ use sc_client_api::{
 use sc_telemetry::TelemetryWorkerHandle;
 use sp_api::ProvideRuntimeApi;
 use sp_consensus::SyncOracle;
-use sp_core::{sp_std::collections::btree_map::BTreeMap, Pair};
+use sp_core::sp_std::collections::btree_map::BTreeMap;
 use sp_state_machine::{Backend as StateBackend, StorageValue};
 
 /// The timeout in seconds after that the waiting for a block should be aborted.
@@ -85,7 +86,7 @@ where
 		+ UsageProvider<PBlock>
 		+ Sync
 		+ Send,
-	Client::Api: ParachainHost<PBlock> + BabeApi<PBlock>,
+	Client::Api: ParachainHost<PBlock> + BabeApi<PBlock> + RelayStateProofApi<PBlock>,
 {
 	async fn retrieve_dmq_contents(
 		&self,
@@ -198,6 +199,19 @@ where
 			.map_err(RelayChainError::StateMachineError)
 	}
 
+	async fn relay_state_proof(
+		&self,
+		relay_parent: PHash,
+		para_id: ParaId,
+	) -> RelayChainResult<StorageProof> {
+		let relevant_keys = self
+			.full_client
+			.runtime_api()
+			.relay_state_proof_keys(&BlockId::Hash(relay_parent), para_id)?;
+
+		self.prove_read(relay_parent, &relevant_keys).await
+	}
+
 	/// Wait for a given relay chain block in an async way.
 	///
 	/// The caller needs to pass the hash of a block it waits for and the function will return when the
@@ -310,7 +324,7 @@ impl ExecuteWithClient for RelayChainInProcessInterfaceBuilder {
 			+ 'static
 			+ Sync
 			+ Send,
-		Client::Api: ParachainHost<PBlock> + BabeApi<PBlock>,
+		Client::Api: ParachainHost<PBlock> + BabeApi<PBlock> + RelayStateProofApi<PBlock>,
 	{
 		Arc::new(RelayChainInProcessInterface::new(
 			client,
@@ -322,10 +336,14 @@ impl ExecuteWithClient for RelayChainInProcessInterfaceBuilder {
 }
 
 /// Build the Selendra full node using the given `config`.
+///
+/// If the parachain node is an authority, its collator key is loaded from the parachain keystore,
+/// see [`selendra_service::collator_key::collator_key`].
 #[sc_tracing::logging::prefix_logs_with("Relaychain")]
 fn build_selendra_full_node(
 	config: Configuration,
 	parachain_config: &Configuration,
+	collator_key_suri: Option<&str>,
 	telemetry_worker_handle: Option<TelemetryWorkerHandle>,
 ) -> Result<(NewFull<selendra_client::Client>, Option<CollatorPair>), selendra_service::Error> {
 	let is_light = matches!(config.role, Role::Light);
@@ -333,7 +351,10 @@ fn build_selendra_full_node(
 		Err(selendra_service::Error::Sub("Light client not supported.".into()))
 	} else {
 		let (is_collator, maybe_collator_key) = if parachain_config.role.is_authority() {
-			let collator_key = CollatorPair::generate().0;
+			let collator_key = selendra_service::collator_key::collator_key(
+				&parachain_config.keystore,
+				collator_key_suri,
+			)?;
 			(selendra_service::IsCollator::Yes(collator_key.clone()), Some(collator_key))
 		} else {
 			(selendra_service::IsCollator::No, None)
@@ -346,6 +367,7 @@ fn build_selendra_full_node(
 			true,
 			None,
 			telemetry_worker_handle,
+			None,
 			true,
 			selendra_service::RealOverseerGen,
 		)?;
@@ -358,11 +380,16 @@ fn build_selendra_full_node(
 pub fn build_inprocess_relay_chain(
 	selendra_config: Configuration,
 	parachain_config: &Configuration,
+	collator_key_suri: Option<&str>,
 	telemetry_worker_handle: Option<TelemetryWorkerHandle>,
 	task_manager: &mut TaskManager,
 ) -> RelayChainResult<(Arc<(dyn RelayChainInterface + 'static)>, Option<CollatorPair>)> {
-	let (full_node, collator_key) =
-		build_selendra_full_node(selendra_config, parachain_config, telemetry_worker_handle)?;
+	let (full_node, collator_key) = build_selendra_full_node(
+		selendra_config,
+		parachain_config,
+		collator_key_suri,
+		telemetry_worker_handle,
+	)?;
 
 	let sync_oracle: Box<dyn SyncOracle + Send + Sync> = Box::new(full_node.network.clone());
 	let sync_oracle = Arc::new(Mutex::new(sync_oracle));
@@ -161,6 +161,17 @@ pub trait RelayChainInterface: Send + Sync {
 		relay_parent: PHash,
 		relevant_keys: &Vec<Vec<u8>>,
 	) -> RelayChainResult<StorageProof>;
+
+	/// Generate the proof of the relay chain state the validation data inherent of the para
+	/// `para_id` needs, at `relay_parent`.
+	///
+	/// The proven keys are the ones the relay chain runtime reports through its
+	/// `RelayStateProofApi`.
+	async fn relay_state_proof(
+		&self,
+		relay_parent: PHash,
+		para_id: ParaId,
+	) -> RelayChainResult<StorageProof>;
 }
 
 #[async_trait]
@@ -251,6 +262,14 @@ where
 		(**self).prove_read(relay_parent, relevant_keys).await
 	}
 
+	async fn relay_state_proof(
+		&self,
+		relay_parent: PHash,
+		para_id: ParaId,
+	) -> RelayChainResult<StorageProof> {
+		(**self).relay_state_proof(relay_parent, para_id).await
+	}
+
 	async fn wait_for_block(&self, hash: PHash) -> RelayChainResult<()> {
 		(**self).wait_for_block(hash).await
 	}
@@ -259,6 +259,19 @@ impl RelayChainRPCClient {
 			.await
 	}
 
+	async fn relay_state_proof_keys(
+		&self,
+		at: PHash,
+		para_id: ParaId,
+	) -> Result<Vec<Vec<u8>>, RelayChainError> {
+		self.call_remote_runtime_function(
+			"RelayStateProofApi_relay_state_proof_keys",
+			at,
+			Some(para_id),
+		)
+		.await
+	}
+
 	async fn subscribe_all_heads(&self) -> Result<Subscription<PHeader>, RelayChainError> {
 		self.subscribe::<PHeader>("chain_subscribeAllHeads", "chain_unsubscribeAllHeads", None)
 			.await
@@ -420,6 +433,16 @@ impl RelayChainInterface for RelayChainRPCInterface {
 			})
 	}
 
+	async fn relay_state_proof(
+		&self,
+		relay_parent: PHash,
+		para_id: ParaId,
+	) -> RelayChainResult<StorageProof> {
+		let relevant_keys = self.rpc_client.relay_state_proof_keys(relay_parent, para_id).await?;
+
+		self.prove_read(relay_parent, &relevant_keys).await
+	}
+
 	/// Wait for a given relay chain block
 	///
 	/// The hash of the block to wait for is passed. We wait for the block to arrive or return after a timeout.
@@ -35,7 +35,7 @@ pub use selendra_primitives::v1::{
 /// A module that re-exports relevant relay chain definitions.
 pub mod relay_chain {
 	pub use selendra_core_primitives::*;
-	pub use selendra_primitives::{v1, v1::well_known_keys, v2};
+	pub use selendra_primitives::{relay_state_proof, v1, v1::well_known_keys, v2};
 }
 
 /// An inbound HRMP message.
@@ -17,11 +17,7 @@
 //! Client side code for generating the parachain inherent.
 
 use crate::ParachainInherentData;
-use codec::Decode;
-use cumulus_primitives_core::{
-	relay_chain::{self, v1::HrmpChannelId, Hash as PHash},
-	ParaId, PersistedValidationData,
-};
+use cumulus_primitives_core::{relay_chain::Hash as PHash, ParaId, PersistedValidationData};
 use cumulus_relay_chain_interface::RelayChainInterface;
 
 const LOG_TARGET: &str = "parachain-inherent";
@@ -33,90 +29,15 @@ async fn collect_relay_storage_proof(
 	para_id: ParaId,
 	relay_parent: PHash,
 ) -> Option<sp_state_machine::StorageProof> {
-	use relay_chain::well_known_keys as relay_well_known_keys;
-
-	let ingress_channels = relay_chain_interface
-		.get_storage_by_key(
-			relay_parent,
-			&relay_well_known_keys::hrmp_ingress_channel_index(para_id),
-		)
-		.await
-		.map_err(|e| {
-			tracing::error!(
-				target: LOG_TARGET,
-				relay_parent = ?relay_parent,
-				error = ?e,
-				"Cannot obtain the hrmp ingress channel."
-			)
-		})
-		.ok()?;
-
-	let ingress_channels = ingress_channels
-		.map(|raw| <Vec<ParaId>>::decode(&mut &raw[..]))
-		.transpose()
-		.map_err(|e| {
-			tracing::error!(
-				target: LOG_TARGET,
-				error = ?e,
-				"Cannot decode the hrmp ingress channel index.",
-			)
-		})
-		.ok()?
-		.unwrap_or_default();
-
-	let egress_channels = relay_chain_interface
-		.get_storage_by_key(
-			relay_parent,
-			&relay_well_known_keys::hrmp_egress_channel_index(para_id),
-		)
-		.await
-		.map_err(|e| {
-			tracing::error!(
-				target: LOG_TARGET,
-				error = ?e,
-				"Cannot obtain the hrmp egress channel.",
-			)
-		})
-		.ok()?;
-
-	let egress_channels = egress_channels
-		.map(|raw| <Vec<ParaId>>::decode(&mut &raw[..]))
-		.transpose()
-		.map_err(|e| {
-			tracing::error!(
-				target: LOG_TARGET,
-				error = ?e,
-				"Cannot decode the hrmp egress channel index.",
-			)
-		})
-		.ok()?
-		.unwrap_or_default();
-
-	let mut relevant_keys = Vec::new();
-	relevant_keys.push(relay_well_known_keys::CURRENT_SLOT.to_vec());
-	relevant_keys.push(relay_well_known_keys::ACTIVE_CONFIG.to_vec());
-	relevant_keys.push(relay_well_known_keys::dmq_mqc_head(para_id));
-	relevant_keys.push(relay_well_known_keys::relay_dispatch_queue_size(para_id));
-	relevant_keys.push(relay_well_known_keys::hrmp_ingress_channel_index(para_id));
-	relevant_keys.push(relay_well_known_keys::hrmp_egress_channel_index(para_id));
-	relevant_keys.push(relay_well_known_keys::upgrade_go_ahead_signal(para_id));
-	relevant_keys.push(relay_well_known_keys::upgrade_restriction_signal(para_id));
-	relevant_keys.extend(ingress_channels.into_iter().map(|sender| {
-		relay_well_known_keys::hrmp_channels(HrmpChannelId { sender, recipient: para_id })
-	}));
-	relevant_keys.extend(egress_channels.into_iter().map(|recipient| {
-		relay_well_known_keys::hrmp_channels(HrmpChannelId { sender: para_id, recipient })
-	}));
-
 	relay_chain_interface
-		.prove_read(relay_parent, &relevant_keys)
+		.relay_state_proof(relay_parent, para_id)
 		.await
 		.map_err(|e| {
 			tracing::error!(
 				target: LOG_TARGET,
 				relay_parent = ?relay_parent,
 				error = ?e,
-				"Cannot obtain read proof from relay chain.",
+				"Cannot obtain the relay chain state proof.",
 			);
 		})
 		.ok()
@@ -568,8 +568,10 @@ impl TestNodeBuilder {
 			false,
 		);
 
-		let collator_options =
-			CollatorOptions { relay_chain_rpc_url: self.relay_chain_full_node_url };
+		let collator_options = CollatorOptions {
+			relay_chain_rpc_url: self.relay_chain_full_node_url,
+			collator_key: None,
+		};
 
 		relay_chain_config.network.node_name =
 			format!("{} (relay chain)", relay_chain_config.network.node_name);
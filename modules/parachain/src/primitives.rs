@@ -441,3 +441,52 @@ pub struct ValidationResult {
 	/// The mark which specifies the block number up to which all inbound HRMP messages are processed.
 	pub hrmp_watermark: RelayChainBlockNumber,
 }
+
+/// The default number of wasm pages available to the heap of a validation function.
+pub const DEFAULT_HEAP_PAGES: u32 = 2048;
+
+/// The default maximum number of logical items on the wasm stack of a validation function.
+pub const DEFAULT_STACK_LOGICAL_MAX: u32 = 65536;
+
+/// The default maximum size of the native stack used for executing a validation function, in
+/// bytes.
+pub const DEFAULT_STACK_NATIVE_MAX: u32 = 256 * 1024 * 1024;
+
+/// Parameters of the environment parachain validation functions are prepared and executed in.
+///
+/// These are set by the relay chain per session, so that execution limits can be tuned across all
+/// validators at once.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize, Hash))]
+pub struct ExecutorParams {
+	/// The number of wasm pages available to the heap of a validation function.
+	pub heap_pages: u32,
+	/// The maximum number of logical items on the wasm stack before execution traps.
+	///
+	/// The code gets instrumented accordingly when it is prepared.
+	pub stack_logical_max: u32,
+	/// The maximum size of the native stack used for executing a validation function, in bytes.
+	pub stack_native_max: u32,
+	/// The percentage the execution timeouts of the nodes get scaled by.
+	///
+	/// 100 leaves the timeouts of the nodes as they are.
+	pub execution_timeout_percent: u32,
+}
+
+impl Default for ExecutorParams {
+	fn default() -> Self {
+		Self {
+			heap_pages: DEFAULT_HEAP_PAGES,
+			stack_logical_max: DEFAULT_STACK_LOGICAL_MAX,
+			stack_native_max: DEFAULT_STACK_NATIVE_MAX,
+			execution_timeout_percent: 100,
+		}
+	}
+}
+
+impl ExecutorParams {
+	/// Returns the hash of the encoded parameters.
+	pub fn hash(&self) -> Hash {
+		sp_runtime::traits::BlakeTwo256::hash_of(self)
+	}
+}
@@ -0,0 +1,149 @@
+// Copyright 2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Integration test following a candidate of the adder parachain from its collation through
+//! backing, availability and inclusion to the approval of the relay chain block including it, on a
+//! network of four validators.
+
+use futures::StreamExt;
+use parity_scale_codec::Decode;
+use sc_client_api::{BlockchainEvents, StorageProvider};
+use selendra_primitives::v1::{Hash, Id as ParaId};
+use selendra_runtime_parachains::inclusion::Event as InclusionEvent;
+use selendra_test_runtime::{Event, Runtime};
+use selendra_test_service::SelendraTestNode;
+use sp_core::{storage::StorageKey, twox_128};
+use sp_keyring::Sr25519Keyring;
+use sp_runtime::{generic::BlockId, traits::Header as _};
+
+const PUPPET_EXE: &str = env!("CARGO_BIN_EXE_adder_collator_puppet_worker");
+
+const VALIDATORS: [Sr25519Keyring; 4] =
+	[Sr25519Keyring::Alice, Sr25519Keyring::Bob, Sr25519Keyring::Charlie, Sr25519Keyring::Dave];
+
+/// The events of the inclusion pallet about candidates of `para_id` in block `hash` of `node`.
+fn inclusion_events(
+	node: &SelendraTestNode,
+	hash: Hash,
+	para_id: ParaId,
+) -> Vec<InclusionEvent<Runtime>> {
+	let key = StorageKey([twox_128(b"System"), twox_128(b"Events")].concat());
+	let records = match node.client.storage(&BlockId::Hash(hash), &key).unwrap() {
+		Some(events) =>
+			Vec::<frame_system::EventRecord<Event, Hash>>::decode(&mut &events.0[..]).unwrap(),
+		None => Vec::new(),
+	};
+
+	records
+		.into_iter()
+		.filter_map(|record| match record.event {
+			Event::ParaInclusion(event) => Some(event),
+			_ => None,
+		})
+		.filter(|event| match event {
+			InclusionEvent::CandidateBacked(receipt, ..) |
+			InclusionEvent::CandidateIncluded(receipt, ..) |
+			InclusionEvent::CandidateTimedOut(receipt, ..) => receipt.descriptor.para_id == para_id,
+			_ => false,
+		})
+		.collect()
+}
+
+#[substrate_test_utils::test]
+async fn candidate_is_backed_included_and_approved() {
+	let mut builder = sc_cli::LoggerBuilder::new("");
+	builder.with_colors(false);
+	builder.init().expect("Set up logger");
+
+	let para_id = ParaId::from(100);
+	let seeds: Vec<_> = VALIDATORS.iter().map(|key| format!("{:?}", key)).collect();
+	let seeds: Vec<_> = seeds.iter().map(String::as_str).collect();
+	let node_config = |key, boot_nodes, is_validator| {
+		selendra_test_service::node_config_with_spec(
+			selendra_test_service::selendra_local_testnet_config_with_validators(&seeds),
+			|| {},
+			tokio::runtime::Handle::current(),
+			key,
+			boot_nodes,
+			is_validator,
+		)
+	};
+
+	// Every validator boots from the ones started before it.
+	let mut validators = Vec::new();
+	for key in VALIDATORS {
+		let boot_nodes =
+			validators.iter().map(|node: &SelendraTestNode| node.addr.clone()).collect();
+		let config = node_config(key, boot_nodes, true);
+		validators.push(selendra_test_service::run_validator_node(config, Some(PUPPET_EXE.into())));
+	}
+	let alice = &validators[0];
+	let mut imported = alice.client.import_notification_stream();
+	let mut finalized = alice.client.finality_notification_stream();
+
+	let collator = test_parachain_adder_collator::Collator::new();
+	alice
+		.register_parachain(para_id, collator.validation_code().to_vec(), collator.genesis_head())
+		.await
+		.unwrap();
+
+	let boot_nodes = validators.iter().map(|node| node.addr.clone()).collect();
+	let mut ferdie = selendra_test_service::run_collator_node_with_config(
+		node_config(Sr25519Keyring::Ferdie, boot_nodes, false),
+		collator.collator_key(),
+	);
+	ferdie
+		.register_collator(
+			collator.collator_key(),
+			para_id,
+			collator.create_collation_function(ferdie.task_manager.spawn_handle()),
+		)
+		.await;
+
+	// Collation generation and seconding.
+	collator.wait_for_seconded_collations(1).await;
+
+	// Backing, then inclusion once the candidate is available.
+	let mut backed = None;
+	let included = loop {
+		let notification = imported.next().await.expect("blocks keep being imported");
+		let mut included = None;
+		for event in inclusion_events(alice, notification.hash, para_id) {
+			match event {
+				InclusionEvent::CandidateBacked(receipt, ..) => {
+					backed.get_or_insert(receipt.hash());
+				},
+				InclusionEvent::CandidateIncluded(receipt, ..)
+					if Some(receipt.hash()) == backed =>
+					included = Some(*notification.header.number()),
+				InclusionEvent::CandidateTimedOut(receipt, ..)
+					if Some(receipt.hash()) == backed =>
+					panic!("Candidate {:?} timed out before becoming available", receipt.hash()),
+				_ => {},
+			}
+		}
+		if let Some(included) = included {
+			break included
+		}
+	};
+
+	// Approval: blocks are only finalized once the candidates included in them are approved.
+	while alice.client.chain_info().finalized_number < included {
+		finalized.next().await.expect("blocks keep being finalized");
+	}
+
+	collator.wait_for_blocks(1).await;
+}
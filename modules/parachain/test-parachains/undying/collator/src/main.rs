@@ -68,6 +68,7 @@ fn main() -> Result<()> {
 							true,
 							None,
 							None,
+							None,
 							false,
 							selendra_service::RealOverseerGen,
 						)
@@ -22,6 +22,15 @@
 use sc_client_api::{AuxStore, Backend as BackendT, BlockchainEvents, KeyIterator, UsageProvider};
 use sc_executor::NativeElseWasmExecutor;
 use selendra_primitives::{
+	address::AddressFormatApi,
+	build_info::BuildInfoApi,
+	disputes::DisputeStatementSubmissionApi,
+	equivocation::EquivocationReportsApi,
+	events::{BlockEventsApi, EventsSchemaApi},
+	executor_params::ExecutorParamsApi,
+	fees::FeeMultiplierApi,
+	para_usage::ParaUsageApi,
+	relay_state_proof::RelayStateProofApi,
 	v1::{AccountId, Balance, Block, BlockNumber, Hash, Header, Nonce},
 	v2::ParachainHost,
 };
@@ -89,6 +98,16 @@ pub trait RuntimeApiCollection:
 	+ frame_system_rpc_runtime_api::AccountNonceApi<Block, AccountId, Nonce>
 	+ pallet_mmr_primitives::MmrApi<Block, <Block as BlockT>::Hash>
 	+ pallet_transaction_payment_rpc_runtime_api::TransactionPaymentApi<Block, Balance>
+	+ FeeMultiplierApi<Block, Balance, BlockNumber>
+	+ BuildInfoApi<Block>
+	+ AddressFormatApi<Block>
+	+ EquivocationReportsApi<Block>
+	+ ParaUsageApi<Block>
+	+ ExecutorParamsApi<Block>
+	+ RelayStateProofApi<Block>
+	+ EventsSchemaApi<Block>
+	+ BlockEventsApi<Block>
+	+ DisputeStatementSubmissionApi<Block>
 	+ sp_api::Metadata<Block>
 	+ sp_offchain::OffchainWorkerApi<Block>
 	+ sp_session::SessionKeys<Block>
@@ -110,6 +129,16 @@ where
 		+ frame_system_rpc_runtime_api::AccountNonceApi<Block, AccountId, Nonce>
 		+ pallet_mmr_primitives::MmrApi<Block, <Block as BlockT>::Hash>
 		+ pallet_transaction_payment_rpc_runtime_api::TransactionPaymentApi<Block, Balance>
+		+ FeeMultiplierApi<Block, Balance, BlockNumber>
+		+ BuildInfoApi<Block>
+		+ AddressFormatApi<Block>
+		+ EquivocationReportsApi<Block>
+		+ ParaUsageApi<Block>
+		+ ExecutorParamsApi<Block>
+		+ RelayStateProofApi<Block>
+		+ EventsSchemaApi<Block>
+		+ BlockEventsApi<Block>
+		+ DisputeStatementSubmissionApi<Block>
 		+ sp_api::Metadata<Block>
 		+ sp_offchain::OffchainWorkerApi<Block>
 		+ sp_session::SessionKeys<Block>
@@ -27,6 +27,8 @@ pub enum Error {
 	#[error(transparent)]
 	Util(#[from] selendra_node_subsystem_util::Error),
 	#[error(transparent)]
+	UtilRuntime(#[from] selendra_node_subsystem_util::runtime::Error),
+	#[error(transparent)]
 	Erasure(#[from] selendra_erasure_coding::Error),
 }
 
@@ -15,6 +15,9 @@
 // along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
 
 //! The collation generation subsystem is the interface between selendra and the collators.
+//!
+//! It is initialized once per para it collates for, so that a single collator can serve several
+//! paras, e.g. on test networks.
 
 #![deny(missing_docs)]
 
@@ -23,13 +26,13 @@ use parity_scale_codec::Encode;
 use selendra_node_primitives::{AvailableData, CollationGenerationConfig, PoV};
 use selendra_node_subsystem::{
 	messages::{AllMessages, CollationGenerationMessage, CollatorProtocolMessage},
-	overseer, ActiveLeavesUpdate, FromOverseer, OverseerSignal, SpawnedSubsystem, SubsystemContext,
-	SubsystemError, SubsystemResult, SubsystemSender,
+	overseer, FromOverseer, OverseerSignal, SpawnedSubsystem, SubsystemContext, SubsystemError,
+	SubsystemResult, SubsystemSender,
 };
 use selendra_node_subsystem_util::{
 	metrics::{self, prometheus},
-	request_availability_cores, request_persisted_validation_data, request_validation_code,
-	request_validation_code_hash, request_validators,
+	request_availability_cores, request_validation_code_hash, request_validators,
+	runtime::ValidationDataCache,
 };
 use selendra_primitives::v1::{
 	collator_signature_payload, CandidateCommitments, CandidateDescriptor, CandidateReceipt,
@@ -37,7 +40,10 @@ use selendra_primitives::v1::{
 	ValidationCodeHash,
 };
 use sp_core::crypto::Pair;
-use std::sync::Arc;
+use std::{
+	collections::{hash_map::Entry, HashMap},
+	sync::Arc,
+};
 
 mod error;
 
@@ -48,14 +54,17 @@ const LOG_TARGET: &'static str = "parachain::collation-generation";
 
 /// Collation Generation Subsystem
 pub struct CollationGenerationSubsystem {
-	config: Option<Arc<CollationGenerationConfig>>,
+	/// The configurations of the paras we collate for.
+	configs: HashMap<ParaId, Arc<CollationGenerationConfig>>,
+	/// Validation data and code fetched for the active leaves.
+	validation_data_cache: ValidationDataCache,
 	metrics: Metrics,
 }
 
 impl CollationGenerationSubsystem {
 	/// Create a new instance of the `CollationGenerationSubsystem`.
 	pub fn new(metrics: Metrics) -> Self {
-		Self { config: None, metrics }
+		Self { configs: HashMap::new(), validation_data_cache: ValidationDataCache::new(), metrics }
 	}
 
 	/// Run this subsystem
@@ -113,16 +122,15 @@ impl CollationGenerationSubsystem {
 		Context: overseer::SubsystemContext<Message = CollationGenerationMessage>,
 	{
 		match incoming {
-			Ok(FromOverseer::Signal(OverseerSignal::ActiveLeaves(ActiveLeavesUpdate {
-				activated,
-				..
-			}))) => {
+			Ok(FromOverseer::Signal(OverseerSignal::ActiveLeaves(update))) => {
+				self.validation_data_cache.on_active_leaves_update(&update);
 				// follow the procedure from the guide
-				if let Some(config) = &self.config {
+				if !self.configs.is_empty() {
 					let metrics = self.metrics.clone();
 					if let Err(err) = handle_new_activations(
-						config.clone(),
-						activated.into_iter().map(|v| v.hash),
+						&self.configs,
+						update.activated.into_iter().map(|v| v.hash),
+						&mut self.validation_data_cache,
 						ctx,
 						metrics,
 						sender,
@@ -139,10 +147,15 @@ impl CollationGenerationSubsystem {
 			Ok(FromOverseer::Communication {
 				msg: CollationGenerationMessage::Initialize(config),
 			}) => {
-				if self.config.is_some() {
-					tracing::error!(target: LOG_TARGET, "double initialization");
-				} else {
-					self.config = Some(Arc::new(config));
+				match self.configs.entry(config.para_id) {
+					Entry::Occupied(_) => tracing::error!(
+						target: LOG_TARGET,
+						para_id = %config.para_id,
+						"double initialization",
+					),
+					Entry::Vacant(entry) => {
+						entry.insert(Arc::new(config));
+					},
 				}
 				false
 			},
@@ -177,8 +190,9 @@ where
 }
 
 async fn handle_new_activations<Context: SubsystemContext>(
-	config: Arc<CollationGenerationConfig>,
+	configs: &HashMap<ParaId, Arc<CollationGenerationConfig>>,
 	activated: impl IntoIterator<Item = Hash>,
+	validation_data_cache: &mut ValidationDataCache,
 	ctx: &mut Context,
 	metrics: Metrics,
 	sender: &mpsc::Sender<AllMessages>,
@@ -224,30 +238,32 @@ async fn handle_new_activations<Context: SubsystemContext>(
 				},
 			};
 
-			if scheduled_core.para_id != config.para_id {
-				tracing::trace!(
-					target: LOG_TARGET,
-					core_idx = %core_idx,
-					relay_parent = ?relay_parent,
-					our_para = %config.para_id,
-					their_para = %scheduled_core.para_id,
-					"core is not assigned to our para. Keep going.",
-				);
-				continue
-			}
+			let config = match configs.get(&scheduled_core.para_id) {
+				Some(config) => config,
+				None => {
+					tracing::trace!(
+						target: LOG_TARGET,
+						core_idx = %core_idx,
+						relay_parent = ?relay_parent,
+						their_para = %scheduled_core.para_id,
+						"core is not assigned to any of our paras. Keep going.",
+					);
+					continue
+				},
+			};
 
 			// we get validation data and validation code synchronously for each core instead of
 			// within the subtask loop, because we have only a single mutable handle to the
 			// context, so the work can't really be distributed
 
-			let validation_data = match request_persisted_validation_data(
-				relay_parent,
-				scheduled_core.para_id,
-				assumption,
-				ctx.sender(),
-			)
-			.await
-			.await??
+			let validation_data = match validation_data_cache
+				.get_persisted_validation_data(
+					ctx.sender(),
+					relay_parent,
+					scheduled_core.para_id,
+					assumption,
+				)
+				.await?
 			{
 				Some(v) => v,
 				None => {
@@ -255,8 +271,7 @@ async fn handle_new_activations<Context: SubsystemContext>(
 						target: LOG_TARGET,
 						core_idx = %core_idx,
 						relay_parent = ?relay_parent,
-						our_para = %config.para_id,
-						their_para = %scheduled_core.para_id,
+						para_id = %scheduled_core.para_id,
 						"validation data is not available",
 					);
 					continue
@@ -267,6 +282,7 @@ async fn handle_new_activations<Context: SubsystemContext>(
 				relay_parent,
 				scheduled_core.para_id,
 				assumption,
+				validation_data_cache,
 				ctx.sender(),
 			)
 			.await?
@@ -277,8 +293,7 @@ async fn handle_new_activations<Context: SubsystemContext>(
 						target: LOG_TARGET,
 						core_idx = %core_idx,
 						relay_parent = ?relay_parent,
-						our_para = %config.para_id,
-						their_para = %scheduled_core.para_id,
+						para_id = %scheduled_core.para_id,
 						"validation code hash is not found.",
 					);
 					continue
@@ -414,6 +429,7 @@ async fn obtain_current_validation_code_hash(
 	relay_parent: Hash,
 	para_id: ParaId,
 	assumption: OccupiedCoreAssumption,
+	validation_data_cache: &mut ValidationDataCache,
 	sender: &mut impl SubsystemSender,
 ) -> Result<Option<ValidationCodeHash>, crate::error::Error> {
 	use selendra_node_subsystem::RuntimeApiError;
@@ -425,15 +441,12 @@ async fn obtain_current_validation_code_hash(
 		Ok(Some(v)) => Ok(Some(v)),
 		Ok(None) => Ok(None),
 		Err(RuntimeApiError::NotSupported { .. }) => {
-			match request_validation_code(relay_parent, para_id, assumption, sender).await.await? {
-				Ok(Some(v)) => Ok(Some(v.hash())),
-				Ok(None) => Ok(None),
-				Err(e) => {
-					// We assume that the `validation_code` API is always available, so any error
-					// is unexpected.
-					Err(e.into())
-				},
-			}
+			// We assume that the `validation_code` API is always available, so any error is
+			// unexpected.
+			let code = validation_data_cache
+				.get_validation_code(sender, relay_parent, para_id, assumption)
+				.await?;
+			Ok(code.map(|v| v.hash()))
 		},
 		Err(e @ RuntimeApiError::Execution { .. }) => Err(e.into()),
 	}
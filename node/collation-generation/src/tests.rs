@@ -83,6 +83,12 @@ mod handle_new_activations {
 		})
 	}
 
+	fn configs_of(
+		configs: &[Arc<CollationGenerationConfig>],
+	) -> HashMap<ParaId, Arc<CollationGenerationConfig>> {
+		configs.iter().map(|config| (config.para_id, config.clone())).collect()
+	}
+
 	fn scheduled_core_for<Id: Into<ParaId>>(para_id: Id) -> ScheduledCore {
 		ScheduledCore { para_id: para_id.into(), collator: None }
 	}
@@ -116,8 +122,9 @@ mod handle_new_activations {
 		let subsystem_activated_hashes = activated_hashes.clone();
 		subsystem_test_harness(overseer, |mut ctx| async move {
 			handle_new_activations(
-				test_config(123u32),
+				&configs_of(&[test_config(123u32)]),
 				subsystem_activated_hashes,
+				&mut ValidationDataCache::new(),
 				&mut ctx,
 				Metrics(None),
 				&tx,
@@ -193,9 +200,16 @@ mod handle_new_activations {
 		let (tx, _rx) = mpsc::channel(0);
 
 		subsystem_test_harness(overseer, |mut ctx| async move {
-			handle_new_activations(test_config(16), activated_hashes, &mut ctx, Metrics(None), &tx)
-				.await
-				.unwrap();
+			handle_new_activations(
+				&configs_of(&[test_config(16)]),
+				activated_hashes,
+				&mut ValidationDataCache::new(),
+				&mut ctx,
+				Metrics(None),
+				&tx,
+			)
+			.await
+			.unwrap();
 		});
 
 		let requested_validation_data = Arc::try_unwrap(requested_validation_data)
@@ -272,7 +286,7 @@ mod handle_new_activations {
 		};
 
 		let config = test_config(16);
-		let subsystem_config = config.clone();
+		let subsystem_configs = configs_of(&[config.clone()]);
 
 		let (tx, rx) = mpsc::channel(0);
 
@@ -281,8 +295,9 @@ mod handle_new_activations {
 		let subsystem_sent_messages = sent_messages.clone();
 		subsystem_test_harness(overseer, |mut ctx| async move {
 			handle_new_activations(
-				subsystem_config,
+				&subsystem_configs,
 				activated_hashes,
+				&mut ValidationDataCache::new(),
 				&mut ctx,
 				Metrics(None),
 				&tx,
@@ -439,7 +454,7 @@ mod handle_new_activations {
 		};
 
 		let config = test_config(16u32);
-		let subsystem_config = config.clone();
+		let subsystem_configs = configs_of(&[config.clone()]);
 
 		let (tx, rx) = mpsc::channel(0);
 
@@ -448,8 +463,9 @@ mod handle_new_activations {
 		let subsystem_sent_messages = sent_messages.clone();
 		subsystem_test_harness(overseer, |mut ctx| async move {
 			handle_new_activations(
-				subsystem_config,
+				&subsystem_configs,
 				activated_hashes,
+				&mut ValidationDataCache::new(),
 				&mut ctx,
 				Metrics(None),
 				&tx,
@@ -480,4 +496,106 @@ mod handle_new_activations {
 			_ => panic!("received wrong message type"),
 		}
 	}
+
+	#[test]
+	fn collates_for_every_configured_para() {
+		let activated_hashes: Vec<Hash> = vec![Hash::repeat_byte(1), Hash::repeat_byte(4)];
+
+		let overseer = |mut handle: TestSubsystemContextHandle<CollationGenerationMessage>| async move {
+			loop {
+				match handle.try_recv().await {
+					None => break,
+					Some(AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+						hash,
+						RuntimeApiRequest::AvailabilityCores(tx),
+					))) => {
+						tx.send(Ok(vec![
+							CoreState::Scheduled(scheduled_core_for(
+								(hash.as_fixed_bytes()[0] * 4) as u32,
+							)),
+							CoreState::Scheduled(scheduled_core_for(
+								(hash.as_fixed_bytes()[0] * 5) as u32,
+							)),
+						]))
+						.unwrap();
+					},
+					Some(AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+						_hash,
+						RuntimeApiRequest::PersistedValidationData(
+							_para_id,
+							_occupied_core_assumption,
+							tx,
+						),
+					))) => {
+						tx.send(Ok(Some(test_validation_data()))).unwrap();
+					},
+					Some(AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+						_hash,
+						RuntimeApiRequest::Validators(tx),
+					))) => {
+						tx.send(Ok(vec![dummy_validator(); 3])).unwrap();
+					},
+					Some(AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+						_hash,
+						RuntimeApiRequest::ValidationCodeHash(
+							_para_id,
+							OccupiedCoreAssumption::Free,
+							tx,
+						),
+					))) => {
+						tx.send(Ok(Some(ValidationCode(vec![1, 2, 3]).hash()))).unwrap();
+					},
+					Some(msg) => {
+						panic!("didn't expect any other overseer requests; got {:?}", msg)
+					},
+				}
+			}
+		};
+
+		// The 4 hash schedules both paras, the 1 hash neither of them.
+		let configs = configs_of(&[test_config(16u32), test_config(20u32)]);
+
+		let (tx, rx) = mpsc::channel(0);
+
+		let sent_messages = Arc::new(Mutex::new(Vec::new()));
+		let subsystem_sent_messages = sent_messages.clone();
+		subsystem_test_harness(overseer, |mut ctx| async move {
+			handle_new_activations(
+				&configs,
+				activated_hashes,
+				&mut ValidationDataCache::new(),
+				&mut ctx,
+				Metrics(None),
+				&tx,
+			)
+			.await
+			.unwrap();
+
+			std::mem::drop(tx);
+
+			*subsystem_sent_messages.lock().await = rx.collect().await;
+		});
+
+		let sent_messages = Arc::try_unwrap(sent_messages)
+			.expect("subsystem should have shut down by now")
+			.into_inner();
+
+		let mut collated: Vec<(ParaId, Hash)> = sent_messages
+			.iter()
+			.map(|msg| match msg {
+				AllMessages::CollatorProtocol(CollatorProtocolMessage::DistributeCollation(
+					CandidateReceipt { descriptor, .. },
+					_pov,
+					..,
+				)) => (descriptor.para_id, descriptor.relay_parent),
+				_ => panic!("received wrong message type"),
+			})
+			.collect();
+		collated.sort();
+
+		assert_eq!(
+			collated,
+			vec![(16u32.into(), Hash::repeat_byte(4)), (20u32.into(), Hash::repeat_byte(4))],
+		);
+	}
 }
@@ -36,6 +36,7 @@ use selendra_node_primitives::{
 };
 use selendra_node_subsystem_util::{
 	self as util,
+	memory_budget::MemoryBudget,
 	metrics::{self, prometheus},
 	request_from_runtime, request_session_index_for_child, request_validator_groups,
 	request_validators, FromJobCommand, JobSender, Validator,
@@ -166,6 +167,8 @@ pub struct CandidateBackingJob {
 	/// that we've sent the provisioner the backed candidate.
 	backed: HashSet<CandidateHash>,
 	keystore: SyncCryptoStorePtr,
+	/// Budget the PoVs are accounted against while being validated.
+	budget: MemoryBudget,
 	table: Table<TableContext>,
 	table_context: TableContext,
 	background_validation: mpsc::Receiver<ValidatedCandidateCommand>,
@@ -412,6 +415,7 @@ struct BackgroundValidationParams<S: overseer::SubsystemSender<AllMessages>, F>
 	relay_parent: Hash,
 	pov: PoVData,
 	n_validators: usize,
+	budget: MemoryBudget,
 	span: Option<jaeger::Span>,
 	make_command: F,
 }
@@ -429,12 +433,21 @@ async fn validate_and_make_available(
 		relay_parent,
 		pov,
 		n_validators,
+		budget,
 		span,
 		make_command,
 	} = params;
 
 	let pov = match pov {
 		PoVData::Ready(pov) => pov,
+		PoVData::FetchFromValidator { .. } if budget.is_shed(&relay_parent) => {
+			tracing::debug!(
+				target: LOG_TARGET,
+				candidate_hash = ?candidate.hash(),
+				"Not fetching PoV, relay parent has been shed for exceeding the memory budget",
+			);
+			return Ok(())
+		},
 		PoVData::FetchFromValidator { from_validator, candidate_hash, pov_hash } => {
 			let _span = span.as_ref().map(|s| s.child("request-pov"));
 			match request_pov(&mut sender, relay_parent, from_validator, candidate_hash, pov_hash)
@@ -453,6 +466,19 @@ async fn validate_and_make_available(
 		},
 	};
 
+	// Account for the PoV while we validate it and make it available.
+	let _reservation = match budget.reserve(relay_parent, pov.block_data.0.len()) {
+		Some(reservation) => reservation,
+		None => {
+			tracing::debug!(
+				target: LOG_TARGET,
+				candidate_hash = ?candidate.hash(),
+				"Not validating, relay parent has been shed for exceeding the memory budget",
+			);
+			return Ok(())
+		},
+	};
+
 	let v = {
 		let _span = span.as_ref().map(|s| {
 			s.child("request-validation")
@@ -719,6 +745,7 @@ impl CandidateBackingJob {
 				relay_parent: self.parent,
 				pov: PoVData::Ready(pov),
 				n_validators: self.table_context.validators.len(),
+				budget: self.budget.clone(),
 				span,
 				make_command: ValidatedCandidateCommand::Second,
 			},
@@ -1041,6 +1068,7 @@ impl CandidateBackingJob {
 				relay_parent: self.parent,
 				pov,
 				n_validators: self.table_context.validators.len(),
+				budget: self.budget.clone(),
 				span,
 				make_command: ValidatedCandidateCommand::Attest,
 			},
@@ -1180,14 +1208,14 @@ impl CandidateBackingJob {
 impl util::JobTrait for CandidateBackingJob {
 	type ToJob = CandidateBackingMessage;
 	type Error = Error;
-	type RunArgs = SyncCryptoStorePtr;
+	type RunArgs = (SyncCryptoStorePtr, MemoryBudget);
 	type Metrics = Metrics;
 
 	const NAME: &'static str = "candidate-backing-job";
 
 	fn run<S: SubsystemSender>(
 		leaf: ActivatedLeaf,
-		keystore: SyncCryptoStorePtr,
+		(keystore, budget): (SyncCryptoStorePtr, MemoryBudget),
 		metrics: Metrics,
 		rx_to: mpsc::Receiver<Self::ToJob>,
 		mut sender: JobSender<S>,
@@ -1307,6 +1335,7 @@ impl util::JobTrait for CandidateBackingJob {
 				unbacked_candidates: HashMap::new(),
 				backed: HashSet::new(),
 				keystore,
+				budget,
 				table: Table::default(),
 				table_context,
 				background_validation: background_rx,
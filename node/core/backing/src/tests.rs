@@ -153,7 +153,8 @@ fn test_harness<T: Future<Output = VirtualOverseer>>(
 	let (context, virtual_overseer) = test_helpers::make_subsystem_context(pool.clone());
 
 	let subsystem =
-		CandidateBackingSubsystem::new(pool.clone(), keystore, Metrics(None)).run(context);
+		CandidateBackingSubsystem::new(pool.clone(), (keystore, Default::default()), Metrics(None))
+			.run(context);
 
 	let test_fut = test(virtual_overseer);
 
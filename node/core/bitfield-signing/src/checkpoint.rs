@@ -0,0 +1,108 @@
+// Copyright 2020 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! The last bitfield signed in each session, persisted across restarts.
+//!
+//! A job started after a restart for a relay parent we already signed a bitfield for distributes
+//! the stored bitfield right away, instead of going silent until the availability store has been
+//! queried again. This also makes sure we never sign a second, possibly different, bitfield for
+//! the same relay parent.
+
+use parity_scale_codec::{Decode, Encode};
+use selendra_node_subsystem_util::database::{DBTransaction, Database};
+use selendra_primitives::v1::{Hash, SessionIndex, UncheckedSignedAvailabilityBitfield};
+use std::sync::Arc;
+
+use crate::LOG_TARGET;
+
+/// Prefix of the keys the last signed bitfields are stored under, followed by the session.
+const LAST_SIGNED_PREFIX: &[u8] = b"LastSignedBitfield";
+
+/// Configuration for persisting the last signed bitfields.
+#[derive(Debug, Clone)]
+pub struct Config {
+	/// The column in the database the bitfields are stored in.
+	pub col_data: u32,
+}
+
+#[derive(Encode, Decode)]
+struct LastSigned {
+	relay_parent: Hash,
+	bitfield: UncheckedSignedAvailabilityBitfield,
+}
+
+/// The last bitfield signed in each session, backed by the database.
+#[derive(Clone)]
+pub struct Checkpoints {
+	db: Arc<dyn Database>,
+	config: Config,
+}
+
+impl Checkpoints {
+	/// Create a new handle to the checkpoints stored in `db`.
+	pub fn new(db: Arc<dyn Database>, config: Config) -> Self {
+		Checkpoints { db, config }
+	}
+
+	/// The bitfield signed for `relay_parent`, if it was the last one signed in `session`.
+	pub(crate) fn signed_at(
+		&self,
+		session: SessionIndex,
+		relay_parent: Hash,
+	) -> Option<UncheckedSignedAvailabilityBitfield> {
+		let raw = match self.db.get(self.config.col_data, &last_signed_key(session)) {
+			Ok(raw) => raw?,
+			Err(err) => {
+				tracing::warn!(target: LOG_TARGET, ?err, "Reading last signed bitfield failed");
+				return None
+			},
+		};
+
+		match LastSigned::decode(&mut &raw[..]) {
+			Ok(last) if last.relay_parent == relay_parent => Some(last.bitfield),
+			Ok(_) => None,
+			Err(err) => {
+				tracing::warn!(target: LOG_TARGET, ?err, "Corrupted last signed bitfield in db");
+				None
+			},
+		}
+	}
+
+	/// Store `bitfield` as the last one signed in `session`, dropping the previous session's.
+	pub(crate) fn note_signed(
+		&self,
+		session: SessionIndex,
+		relay_parent: Hash,
+		bitfield: UncheckedSignedAvailabilityBitfield,
+	) {
+		let mut tx = DBTransaction::new();
+		if let Some(previous) = session.checked_sub(1) {
+			tx.delete(self.config.col_data, &last_signed_key(previous));
+		}
+		tx.put_vec(
+			self.config.col_data,
+			&last_signed_key(session),
+			LastSigned { relay_parent, bitfield }.encode(),
+		);
+		if let Err(err) = self.db.write(tx) {
+			tracing::warn!(target: LOG_TARGET, ?err, "Writing last signed bitfield failed");
+		}
+	}
+}
+
+fn last_signed_key(session: SessionIndex) -> Vec<u8> {
+	(LAST_SIGNED_PREFIX, session).encode()
+}
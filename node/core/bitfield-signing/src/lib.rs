@@ -46,6 +46,10 @@ use sp_keystore::{Error as KeystoreError, SyncCryptoStorePtr};
 use std::{iter::FromIterator, pin::Pin, time::Duration};
 use wasm_timer::{Delay, Instant};
 
+/// The last signed bitfields persisted across restarts.
+mod checkpoint;
+pub use checkpoint::{Checkpoints, Config};
+
 #[cfg(test)]
 mod tests;
 
@@ -230,7 +234,7 @@ impl metrics::Metrics for Metrics {
 impl JobTrait for BitfieldSigningJob {
 	type ToJob = BitfieldSigningMessage;
 	type Error = Error;
-	type RunArgs = SyncCryptoStorePtr;
+	type RunArgs = (SyncCryptoStorePtr, Checkpoints);
 	type Metrics = Metrics;
 
 	const NAME: &'static str = "bitfield-signing-job";
@@ -238,7 +242,7 @@ impl JobTrait for BitfieldSigningJob {
 	/// Run a job for the parent block indicated
 	fn run<S: SubsystemSender>(
 		leaf: ActivatedLeaf,
-		keystore: Self::RunArgs,
+		(keystore, checkpoints): Self::RunArgs,
 		metrics: Self::Metrics,
 		_receiver: mpsc::Receiver<BitfieldSigningMessage>,
 		mut sender: JobSender<S>,
@@ -267,6 +271,33 @@ impl JobTrait for BitfieldSigningJob {
 				Err(err) => return Err(Error::Util(err)),
 			};
 
+			// If we signed a bitfield for this leaf before a restart, distribute that one right
+			// away instead of signing another one.
+			let session = validator.signing_context().session_index;
+			if let Some(bitfield) = checkpoints.signed_at(session, leaf.hash) {
+				match bitfield.try_into_checked(validator.signing_context(), &validator.id()) {
+					Ok(signed_bitfield) => {
+						tracing::debug!(
+							target: LOG_TARGET,
+							hash = ?leaf.hash,
+							"Distributing bitfield signed before restart",
+						);
+						sender
+							.send_message(BitfieldDistributionMessage::DistributeBitfield(
+								leaf.hash,
+								signed_bitfield,
+							))
+							.await;
+						return Ok(())
+					},
+					Err(_) => tracing::debug!(
+						target: LOG_TARGET,
+						hash = ?leaf.hash,
+						"Bitfield signed before restart was signed with another key",
+					),
+				}
+			}
+
 			// wait a bit before doing anything else
 			Delay::new_at(wait_until).await?;
 
@@ -313,6 +344,7 @@ impl JobTrait for BitfieldSigningJob {
 			};
 
 			metrics.on_bitfield_signed();
+			checkpoints.note_signed(session, leaf.hash, signed_bitfield.clone().into_unchecked());
 
 			drop(_span);
 			let _span = span.child("gossip");
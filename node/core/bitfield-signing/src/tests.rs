@@ -16,8 +16,13 @@
 
 use super::*;
 use futures::{executor::block_on, pin_mut};
+use parity_scale_codec::Decode;
 use selendra_node_subsystem::messages::AllMessages;
-use selendra_primitives::v1::{CandidateHash, OccupiedCore};
+use selendra_node_subsystem_util::database::kvdb_impl::DbAdapter;
+use selendra_primitives::v1::{
+	CandidateHash, OccupiedCore, UncheckedSignedAvailabilityBitfield, ValidatorSignature,
+};
+use std::sync::Arc;
 use test_helpers::dummy_candidate_descriptor;
 
 fn occupied_core(para_id: u32, candidate_hash: CandidateHash) -> CoreState {
@@ -83,3 +88,33 @@ fn construct_availability_bitfield_works() {
 		}
 	});
 }
+
+#[test]
+fn checkpoints_keep_last_signed_bitfield_per_session() {
+	let db = Arc::new(DbAdapter::new(kvdb_memorydb::create(1), &[]));
+	let checkpoints = Checkpoints::new(db.clone(), Config { col_data: 0 });
+
+	let bitfield = |validator| {
+		UncheckedSignedAvailabilityBitfield::new(
+			AvailabilityBitfield(FromIterator::from_iter(vec![true, false])),
+			ValidatorIndex(validator),
+			ValidatorSignature::decode(&mut &[0u8; 64][..]).unwrap(),
+		)
+	};
+	let (leaf_a, leaf_b) = (Hash::repeat_byte(1), Hash::repeat_byte(2));
+
+	checkpoints.note_signed(1, leaf_a, bitfield(0));
+	assert_eq!(checkpoints.signed_at(1, leaf_a), Some(bitfield(0)));
+	assert_eq!(checkpoints.signed_at(1, leaf_b), None);
+
+	// A later bitfield of the same session replaces the earlier one.
+	checkpoints.note_signed(1, leaf_b, bitfield(1));
+	assert_eq!(checkpoints.signed_at(1, leaf_a), None);
+
+	// Checkpoints survive a restart, until the next session's bitfield is signed.
+	let checkpoints = Checkpoints::new(db, Config { col_data: 0 });
+	assert_eq!(checkpoints.signed_at(1, leaf_b), Some(bitfield(1)));
+	checkpoints.note_signed(2, leaf_a, bitfield(2));
+	assert_eq!(checkpoints.signed_at(1, leaf_b), None);
+	assert_eq!(checkpoints.signed_at(2, leaf_a), Some(bitfield(2)));
+}
@@ -41,7 +41,7 @@ use selendra_node_subsystem::{
 use selendra_node_subsystem_util::metrics::{self, prometheus};
 use selendra_parachain::primitives::{ValidationParams, ValidationResult as WasmValidationResult};
 use selendra_primitives::v1::{
-	CandidateCommitments, CandidateDescriptor, Hash, OccupiedCoreAssumption,
+	CandidateCommitments, CandidateDescriptor, ExecutorParams, Hash, OccupiedCoreAssumption,
 	PersistedValidationData, ValidationCode, ValidationCodeHash,
 };
 
@@ -146,12 +146,15 @@ where
 
 						async move {
 							let _timer = metrics.time_validate_from_chain_state();
+							let executor_params =
+								request_executor_params(&mut sender, descriptor.relay_parent).await;
 							let res = validate_from_chain_state(
 								&mut sender,
 								validation_host,
 								descriptor,
 								pov,
 								timeout,
+								executor_params,
 								&metrics,
 							)
 							.await;
@@ -172,11 +175,14 @@ where
 					response_sender,
 				) => {
 					let bg = {
+						let mut sender = ctx.sender().clone();
 						let metrics = metrics.clone();
 						let validation_host = validation_host.clone();
 
 						async move {
 							let _timer = metrics.time_validate_from_exhaustive();
+							let executor_params =
+								request_executor_params(&mut sender, descriptor.relay_parent).await;
 							let res = validate_candidate_exhaustive(
 								validation_host,
 								persisted_validation_data,
@@ -184,6 +190,7 @@ where
 								descriptor,
 								pov,
 								timeout,
+								executor_params,
 								&metrics,
 							)
 							.await;
@@ -205,11 +212,14 @@ where
 						let validation_host = validation_host.clone();
 
 						async move {
+							let executor_params =
+								request_executor_params(&mut sender, relay_parent).await;
 							let precheck_result = precheck_pvf(
 								&mut sender,
 								validation_host,
 								relay_parent,
 								validation_code_hash,
+								executor_params,
 							)
 							.await;
 
@@ -278,11 +288,63 @@ where
 	.await
 }
 
+/// Requests the executor parameters of the session a child of the given relay parent belongs to.
+///
+/// Falls back to the default parameters if the runtime doesn't provide them, e.g. because it
+/// predates executor parameters or the state of the relay parent is not available anymore.
+async fn request_executor_params<Sender>(sender: &mut Sender, relay_parent: Hash) -> ExecutorParams
+where
+	Sender: SubsystemSender,
+{
+	let (tx, rx) = oneshot::channel();
+	let session_index = match runtime_api_request(
+		sender,
+		relay_parent,
+		RuntimeApiRequest::SessionIndexForChild(tx),
+		rx,
+	)
+	.await
+	{
+		Ok(session_index) => session_index,
+		Err(RuntimeRequestFailed) => return ExecutorParams::default(),
+	};
+
+	let (tx, rx) = oneshot::channel();
+	match runtime_api_request(
+		sender,
+		relay_parent,
+		RuntimeApiRequest::SessionExecutorParams(session_index, tx),
+		rx,
+	)
+	.await
+	{
+		Ok(Some(executor_params)) => executor_params,
+		Ok(None) | Err(RuntimeRequestFailed) => {
+			tracing::debug!(
+				target: LOG_TARGET,
+				?relay_parent,
+				session_index,
+				"No executor params for session, using the defaults",
+			);
+			ExecutorParams::default()
+		},
+	}
+}
+
+/// Scales the given execution timeout as configured in the executor parameters.
+fn scale_timeout(timeout: Duration, executor_params: &ExecutorParams) -> Duration {
+	let millis = (timeout.as_millis() as u64)
+		.saturating_mul(executor_params.execution_timeout_percent as u64) /
+		100;
+	Duration::from_millis(millis)
+}
+
 async fn precheck_pvf<Sender>(
 	sender: &mut Sender,
 	mut validation_backend: impl ValidationBackend,
 	relay_parent: Hash,
 	validation_code_hash: ValidationCodeHash,
+	executor_params: ExecutorParams,
 ) -> PreCheckOutcome
 where
 	Sender: SubsystemSender,
@@ -309,7 +371,7 @@ where
 		&validation_code.0,
 		VALIDATION_CODE_BOMB_LIMIT,
 	) {
-		Ok(code) => Pvf::from_code(code.into_owned()),
+		Ok(code) => Pvf::from_code(code.into_owned()).with_executor_params(executor_params),
 		Err(e) => {
 			tracing::debug!(target: LOG_TARGET, err=?e, "precheck: cannot decompress validation code");
 			return PreCheckOutcome::Invalid
@@ -361,14 +423,23 @@ where
 	let persisted_validation_data_hash = validation_data.hash();
 
 	if descriptor.persisted_validation_data_hash == persisted_validation_data_hash {
-		let (code_tx, code_rx) = oneshot::channel();
-		let validation_code = runtime_api_request(
+		// Only fetch the hash for the para, the code itself is cached by its hash.
+		let (code_hash_tx, code_hash_rx) = oneshot::channel();
+		let validation_code_hash = match runtime_api_request(
 			sender,
 			descriptor.relay_parent,
-			RuntimeApiRequest::ValidationCode(descriptor.para_id, assumption, code_tx),
-			code_rx,
+			RuntimeApiRequest::ValidationCodeHash(descriptor.para_id, assumption, code_hash_tx),
+			code_hash_rx,
 		)
-		.await;
+		.await
+		{
+			Ok(None) | Err(RuntimeRequestFailed) => return AssumptionCheckOutcome::BadRequest,
+			Ok(Some(validation_code_hash)) => validation_code_hash,
+		};
+
+		let validation_code =
+			request_validation_code_by_hash(sender, descriptor.relay_parent, validation_code_hash)
+				.await;
 
 		match validation_code {
 			Ok(None) | Err(RuntimeRequestFailed) => AssumptionCheckOutcome::BadRequest,
@@ -419,6 +490,7 @@ async fn validate_from_chain_state<Sender>(
 	descriptor: CandidateDescriptor,
 	pov: Arc<PoV>,
 	timeout: Duration,
+	executor_params: ExecutorParams,
 	metrics: &Metrics,
 ) -> Result<ValidationResult, ValidationFailed>
 where
@@ -445,6 +517,7 @@ where
 		descriptor.clone(),
 		pov,
 		timeout,
+		executor_params,
 		metrics,
 	)
 	.await;
@@ -476,6 +549,7 @@ async fn validate_candidate_exhaustive(
 	descriptor: CandidateDescriptor,
 	pov: Arc<PoV>,
 	timeout: Duration,
+	executor_params: ExecutorParams,
 	metrics: &Metrics,
 ) -> Result<ValidationResult, ValidationFailed> {
 	let _timer = metrics.time_validate_candidate_exhaustive();
@@ -529,7 +603,12 @@ async fn validate_candidate_exhaustive(
 	};
 
 	let result = validation_backend
-		.validate_candidate(raw_validation_code.to_vec(), timeout, params)
+		.validate_candidate(
+			raw_validation_code.to_vec(),
+			executor_params,
+			scale_timeout(timeout, &executor_params),
+			params,
+		)
 		.await;
 
 	if let Err(ref e) = result {
@@ -576,6 +655,7 @@ trait ValidationBackend {
 	async fn validate_candidate(
 		&mut self,
 		raw_validation_code: Vec<u8>,
+		executor_params: ExecutorParams,
 		timeout: Duration,
 		params: ValidationParams,
 	) -> Result<WasmValidationResult, ValidationError>;
@@ -588,13 +668,14 @@ impl ValidationBackend for ValidationHost {
 	async fn validate_candidate(
 		&mut self,
 		raw_validation_code: Vec<u8>,
+		executor_params: ExecutorParams,
 		timeout: Duration,
 		params: ValidationParams,
 	) -> Result<WasmValidationResult, ValidationError> {
 		let (tx, rx) = oneshot::channel();
 		if let Err(err) = self
 			.execute_pvf(
-				Pvf::from_code(raw_validation_code),
+				Pvf::from_code(raw_validation_code).with_executor_params(executor_params),
 				timeout,
 				params.encode(),
 				selendra_node_core_pvf::Priority::Normal,
@@ -79,11 +79,24 @@ fn correctly_checks_included_assumption() {
 			ctx_handle.recv().await,
 			AllMessages::RuntimeApi(RuntimeApiMessage::Request(
 				rp,
-				RuntimeApiRequest::ValidationCode(p, OccupiedCoreAssumption::Included, tx)
+				RuntimeApiRequest::ValidationCodeHash(p, OccupiedCoreAssumption::Included, tx)
 			)) => {
 				assert_eq!(rp, relay_parent);
 				assert_eq!(p, para_id);
 
+				let _ = tx.send(Ok(Some(validation_code.hash())));
+			}
+		);
+
+		assert_matches!(
+			ctx_handle.recv().await,
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+				rp,
+				RuntimeApiRequest::ValidationCodeByHash(vch, tx)
+			)) => {
+				assert_eq!(rp, relay_parent);
+				assert_eq!(vch, validation_code.hash());
+
 				let _ = tx.send(Ok(Some(validation_code.clone())));
 			}
 		);
@@ -151,11 +164,24 @@ fn correctly_checks_timed_out_assumption() {
 			ctx_handle.recv().await,
 			AllMessages::RuntimeApi(RuntimeApiMessage::Request(
 				rp,
-				RuntimeApiRequest::ValidationCode(p, OccupiedCoreAssumption::TimedOut, tx)
+				RuntimeApiRequest::ValidationCodeHash(p, OccupiedCoreAssumption::TimedOut, tx)
 			)) => {
 				assert_eq!(rp, relay_parent);
 				assert_eq!(p, para_id);
 
+				let _ = tx.send(Ok(Some(validation_code.hash())));
+			}
+		);
+
+		assert_matches!(
+			ctx_handle.recv().await,
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+				rp,
+				RuntimeApiRequest::ValidationCodeByHash(vch, tx)
+			)) => {
+				assert_eq!(rp, relay_parent);
+				assert_eq!(vch, validation_code.hash());
+
 				let _ = tx.send(Ok(Some(validation_code.clone())));
 			}
 		);
@@ -275,7 +301,7 @@ fn check_is_bad_request_if_no_validation_code() {
 			ctx_handle.recv().await,
 			AllMessages::RuntimeApi(RuntimeApiMessage::Request(
 				rp,
-				RuntimeApiRequest::ValidationCode(p, OccupiedCoreAssumption::TimedOut, tx)
+				RuntimeApiRequest::ValidationCodeHash(p, OccupiedCoreAssumption::TimedOut, tx)
 			)) => {
 				assert_eq!(rp, relay_parent);
 				assert_eq!(p, para_id);
@@ -359,6 +385,7 @@ impl ValidationBackend for MockValidateCandidateBackend {
 	async fn validate_candidate(
 		&mut self,
 		_raw_validation_code: Vec<u8>,
+		_executor_params: ExecutorParams,
 		_timeout: Duration,
 		_params: ValidationParams,
 	) -> Result<WasmValidationResult, ValidationError> {
@@ -413,6 +440,7 @@ fn candidate_validation_ok_is_ok() {
 		descriptor,
 		Arc::new(pov),
 		Duration::from_secs(0),
+		ExecutorParams::default(),
 		&Default::default(),
 	))
 	.unwrap();
@@ -462,6 +490,7 @@ fn candidate_validation_bad_return_is_invalid() {
 		descriptor,
 		Arc::new(pov),
 		Duration::from_secs(0),
+		ExecutorParams::default(),
 		&Default::default(),
 	))
 	.unwrap();
@@ -504,6 +533,7 @@ fn candidate_validation_timeout_is_internal_error() {
 		descriptor,
 		Arc::new(pov),
 		Duration::from_secs(0),
+		ExecutorParams::default(),
 		&Default::default(),
 	));
 
@@ -545,6 +575,7 @@ fn candidate_validation_code_mismatch_is_invalid() {
 		descriptor,
 		Arc::new(pov),
 		Duration::from_secs(0),
+		ExecutorParams::default(),
 		&Default::default(),
 	))
 	.unwrap();
@@ -590,6 +621,7 @@ fn compressed_code_works() {
 		descriptor,
 		Arc::new(pov),
 		Duration::from_secs(0),
+		ExecutorParams::default(),
 		&Default::default(),
 	));
 
@@ -635,6 +667,7 @@ fn code_decompression_failure_is_invalid() {
 		descriptor,
 		Arc::new(pov),
 		Duration::from_secs(0),
+		ExecutorParams::default(),
 		&Default::default(),
 	));
 
@@ -681,6 +714,7 @@ fn pov_decompression_failure_is_invalid() {
 		descriptor,
 		Arc::new(pov),
 		Duration::from_secs(0),
+		ExecutorParams::default(),
 		&Default::default(),
 	));
 
@@ -702,6 +736,7 @@ impl ValidationBackend for MockPreCheckBackend {
 	async fn validate_candidate(
 		&mut self,
 		_raw_validation_code: Vec<u8>,
+		_executor_params: ExecutorParams,
 		_timeout: Duration,
 		_params: ValidationParams,
 	) -> Result<WasmValidationResult, ValidationError> {
@@ -728,6 +763,7 @@ fn precheck_works() {
 		MockPreCheckBackend::with_hardcoded_result(Ok(())),
 		relay_parent,
 		validation_code_hash,
+		ExecutorParams::default(),
 	)
 	.remote_handle();
 
@@ -774,6 +810,7 @@ fn precheck_invalid_pvf_blob_compression() {
 		MockPreCheckBackend::with_hardcoded_result(Ok(())),
 		relay_parent,
 		validation_code_hash,
+		ExecutorParams::default(),
 	)
 	.remote_handle();
 
@@ -816,6 +853,7 @@ fn precheck_properly_classifies_outcomes() {
 			MockPreCheckBackend::with_hardcoded_result(prepare_result),
 			relay_parent,
 			validation_code_hash,
+			ExecutorParams::default(),
 		)
 		.remote_handle();
 
@@ -849,3 +887,24 @@ fn precheck_properly_classifies_outcomes() {
 	inner(Err(PrepareError::TimedOut), PreCheckOutcome::Failed);
 	inner(Err(PrepareError::DidNotMakeIt), PreCheckOutcome::Failed);
 }
+
+#[test]
+fn execution_timeout_gets_scaled() {
+	let timeout = Duration::from_secs(2);
+
+	assert_eq!(scale_timeout(timeout, &ExecutorParams::default()), timeout);
+	assert_eq!(
+		scale_timeout(
+			timeout,
+			&ExecutorParams { execution_timeout_percent: 150, ..Default::default() }
+		),
+		Duration::from_secs(3),
+	);
+	assert_eq!(
+		scale_timeout(
+			timeout,
+			&ExecutorParams { execution_timeout_percent: 50, ..Default::default() }
+		),
+		Duration::from_secs(1),
+	);
+}
@@ -184,36 +184,31 @@ impl BlockEntry {
 	}
 }
 
-#[derive(Debug, thiserror::Error)]
 #[allow(missing_docs)]
+#[fatality::fatality]
 pub enum Error {
+	/// The block is most likely unknown to the chain API, the subsystem carries on once restarted.
 	#[error(transparent)]
 	ChainApi(#[from] ChainApiError),
 
+	#[fatal]
 	#[error(transparent)]
 	Io(#[from] std::io::Error),
 
+	/// The chain API is down, which means we're shutting down.
+	#[fatal]
 	#[error(transparent)]
 	Oneshot(#[from] oneshot::Canceled),
 
+	#[fatal(forward)]
 	#[error(transparent)]
 	Subsystem(#[from] SubsystemError),
 
+	#[fatal]
 	#[error(transparent)]
 	Codec(#[from] CodecError),
 }
 
-impl Error {
-	fn trace(&self) {
-		match self {
-			// don't spam the log with spurious errors
-			Self::Oneshot(_) => tracing::debug!(target: LOG_TARGET, err = ?self),
-			// it's worth reporting otherwise
-			_ => tracing::warn!(target: LOG_TARGET, err = ?self),
-		}
-	}
-}
-
 /// A clock used for fetching the current timestamp.
 pub trait Clock {
 	/// Get the current timestamp.
@@ -330,7 +325,7 @@ where
 
 		SpawnedSubsystem {
 			future: run(ctx, backend, self.config.stagnant_check_interval, Box::new(SystemClock))
-				.map(Ok)
+				.map_err(|e| SubsystemError::with_fatality("chain-selection", e))
 				.boxed(),
 			name: "chain-selection-subsystem",
 		}
@@ -338,29 +333,29 @@ where
 }
 
 async fn run<Context, B>(
-	mut ctx: Context,
-	mut backend: B,
+	ctx: Context,
+	backend: B,
 	stagnant_check_interval: StagnantCheckInterval,
 	clock: Box<dyn Clock + Send + Sync>,
-) where
+) -> Result<(), Error>
+where
 	Context: SubsystemContext<Message = ChainSelectionMessage>,
 	Context: overseer::SubsystemContext<Message = ChainSelectionMessage>,
-	B: Backend,
+	B: Backend + Send,
 {
-	loop {
-		let res = run_until_error(&mut ctx, &mut backend, &stagnant_check_interval, &*clock).await;
-		match res {
-			Err(e) => {
-				e.trace();
-				// All errors right now are considered fatal:
-				break
-			},
-			Ok(()) => {
-				tracing::info!(target: LOG_TARGET, "received `Conclude` signal, exiting");
-				break
-			},
-		}
-	}
+	// Everything but the stagnant check timer lives in the backend, so restarts lose nothing.
+	let mut state = (ctx, backend, stagnant_check_interval, clock);
+	overseer::restart_on_recoverable(
+		"chain-selection",
+		&mut state,
+		|(ctx, backend, stagnant_check_interval, clock)| {
+			run_until_error(ctx, backend, stagnant_check_interval, &**clock).boxed()
+		},
+	)
+	.await?;
+
+	tracing::info!(target: LOG_TARGET, "received `Conclude` signal, exiting");
+	Ok(())
 }
 
 // Run the subsystem until an error is encountered or a `conclude` signal is received.
@@ -424,6 +419,9 @@ where
 
 							let _ = tx.send(best_containing);
 						}
+						ChainSelectionMessage::RevertBlocks(blocks) => {
+							handle_revert_blocks(backend, blocks)?
+						}
 					}
 				}
 			}
@@ -636,6 +634,22 @@ fn handle_approved_block(backend: &mut impl Backend, approved_block: Hash) -> Re
 	backend.write(ops)
 }
 
+// Handle a request to revert blocks.
+fn handle_revert_blocks(
+	backend: &mut impl Backend,
+	blocks: Vec<(BlockNumber, Hash)>,
+) -> Result<(), Error> {
+	let ops = {
+		let mut overlay = OverlayedBackend::new(&*backend);
+
+		crate::tree::revert_blocks(&mut overlay, blocks)?;
+
+		overlay.into_write_ops()
+	};
+
+	backend.write(ops)
+}
+
 fn detect_stagnant(backend: &mut impl Backend, now: Timestamp) -> Result<(), Error> {
 	let ops = {
 		let overlay = crate::tree::detect_stagnant(&*backend, now)?;
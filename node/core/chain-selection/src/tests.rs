@@ -252,7 +252,9 @@ fn test_harness<T: Future<Output = VirtualOverseer>>(
 		// Ensure no messages are pending when the subsystem shuts down.
 		assert!(virtual_overseer.try_recv().await.is_none());
 	};
-	futures::executor::block_on(futures::future::join(subsystem, test_and_conclude));
+	let (result, ()) =
+		futures::executor::block_on(futures::future::join(subsystem, test_and_conclude));
+	result.unwrap();
 }
 
 // Answer requests from the subsystem about the finalized block.
@@ -633,6 +635,19 @@ async fn approve_block(
 	write_rx.await.unwrap()
 }
 
+async fn revert_blocks(
+	virtual_overseer: &mut VirtualOverseer,
+	backend: &TestBackend,
+	blocks: Vec<(BlockNumber, Hash)>,
+) {
+	let (_, write_rx) = backend.await_next_write();
+	virtual_overseer
+		.send(FromOverseer::Communication { msg: ChainSelectionMessage::RevertBlocks(blocks) })
+		.await;
+
+	write_rx.await.unwrap()
+}
+
 #[test]
 fn no_op_subsystem_run() {
 	test_harness(|_, _, virtual_overseer| async move { virtual_overseer });
@@ -1077,6 +1092,48 @@ fn reversion_affects_viability_of_all_subtrees() {
 	});
 }
 
+#[test]
+fn reverting_blocks_removes_viability_of_their_subtrees() {
+	test_harness(|backend, _, mut virtual_overseer| async move {
+		let finalized_number = 0;
+		let finalized_hash = Hash::repeat_byte(0);
+
+		// F <- A1 <- A2 <- A3.
+		//       A1 <- B2
+		//
+		// A2 is reverted.
+
+		let (a3_hash, chain_a) =
+			construct_chain_on_base(vec![1, 2, 3], finalized_number, finalized_hash, |_| {});
+
+		let (_, a1_hash, _) = extract_info_from_chain(0, &chain_a);
+		let (_, a2_hash, _) = extract_info_from_chain(1, &chain_a);
+
+		let (b2_hash, chain_b) = construct_chain_on_base(vec![2], 1, a1_hash, |h| {
+			salt_header(h, b"b");
+		});
+
+		import_chains_into_empty(
+			&mut virtual_overseer,
+			&backend,
+			finalized_number,
+			finalized_hash,
+			vec![chain_a.clone(), chain_b.clone()],
+		)
+		.await;
+
+		assert_leaves(&backend, vec![a3_hash, b2_hash]);
+
+		revert_blocks(&mut virtual_overseer, &backend, vec![(2, a2_hash)]).await;
+
+		assert_backend_contains(&backend, chain_a.iter().map(|&(ref h, _)| h));
+		assert_leaves(&backend, vec![b2_hash]);
+		assert_leaves_query(&mut virtual_overseer, vec![b2_hash]).await;
+
+		virtual_overseer
+	});
+}
+
 #[test]
 fn finalize_viable_prunes_subtrees() {
 	test_harness(|backend, _, mut virtual_overseer| async move {
@@ -393,6 +393,47 @@ fn apply_reversions(
 	Ok(())
 }
 
+/// Explicitly revert the given blocks, e.g. because they include a candidate which was concluded
+/// invalid in a dispute. The blocks and all of their descendants become non-viable.
+///
+/// Blocks without an entry are either finalized or were never imported and are ignored.
+pub(super) fn revert_blocks(
+	backend: &mut OverlayedBackend<impl Backend>,
+	blocks: Vec<(BlockNumber, Hash)>,
+) -> Result<(), Error> {
+	for (block_number, block_hash) in blocks {
+		let mut entry = match backend.load_block_entry(&block_hash)? {
+			None => {
+				tracing::warn!(
+					target: LOG_TARGET,
+					?block_hash,
+					block_number,
+					"Missing entry for block to be reverted. It is probably finalized already.",
+				);
+
+				continue
+			},
+			Some(entry) => entry,
+		};
+
+		if entry.viability.explicitly_reverted {
+			continue
+		}
+
+		tracing::info!(
+			target: LOG_TARGET,
+			?block_hash,
+			block_number,
+			"Reverting block which includes a candidate disputed as invalid.",
+		);
+
+		entry.viability.explicitly_reverted = true;
+		propagate_viability_update(backend, entry)?;
+	}
+
+	Ok(())
+}
+
 /// Finalize a block with the given number and hash.
 ///
 /// This will prune all sub-trees not descending from the given block,
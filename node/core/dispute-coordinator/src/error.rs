@@ -85,6 +85,7 @@ pub enum Error {
 	#[error("Dispute import confirmation send failed (receiver canceled)")]
 	DisputeImportOneshotSend,
 
+	#[fatal(forward)]
 	#[error(transparent)]
 	Subsystem(#[from] SubsystemError),
 
@@ -26,6 +26,8 @@ struct MetricsInner {
 	concluded: prometheus::CounterVec<prometheus::U64>,
 	/// Number of participations that have been queued.
 	queued_participations: prometheus::CounterVec<prometheus::U64>,
+	/// Number of unfinalized blocks reverted for including a candidate concluded invalid.
+	reverted_blocks: prometheus::Counter<prometheus::U64>,
 }
 
 /// Candidate validation metrics.
@@ -74,6 +76,12 @@ impl Metrics {
 			metrics.queued_participations.with_label_values(&["best-effort"]).inc();
 		}
 	}
+
+	pub(crate) fn on_blocks_reverted(&self, count: usize) {
+		if let Some(metrics) = &self.0 {
+			metrics.reverted_blocks.inc_by(count as u64);
+		}
+	}
 }
 
 impl metrics::Metrics for Metrics {
@@ -116,6 +124,13 @@ impl metrics::Metrics for Metrics {
 				)?,
 				registry,
 			)?,
+			reverted_blocks: prometheus::register(
+				prometheus::Counter::with_opts(prometheus::Opts::new(
+					"selendra_parachain_dispute_reverted_blocks_total",
+					"Number of unfinalized blocks reverted for including a candidate concluded invalid.",
+				))?,
+				registry,
+			)?,
 		};
 		Ok(Metrics(Some(metrics)))
 	}
@@ -35,8 +35,8 @@ use selendra_node_primitives::{
 };
 use selendra_node_subsystem::{
 	messages::{
-		BlockDescription, DisputeCoordinatorMessage, DisputeDistributionMessage,
-		ImportStatementsResult, RuntimeApiMessage, RuntimeApiRequest,
+		BlockDescription, ChainSelectionMessage, DisputeCoordinatorMessage,
+		DisputeDistributionMessage, ImportStatementsResult, RuntimeApiMessage, RuntimeApiRequest,
 	},
 	overseer, ActivatedLeaf, ActiveLeavesUpdate, FromOverseer, OverseerSignal, SubsystemContext,
 };
@@ -1013,6 +1013,19 @@ impl Initialized {
 					"Dispute on candidate concluded with 'invalid' result",
 				);
 				self.metrics.on_concluded_invalid();
+
+				// Move away from all unfinalized chains including the invalid candidate.
+				let blocks_including = self.ordering_provider.blocks_including(&candidate_hash);
+				if !blocks_including.is_empty() {
+					tracing::info!(
+						target: LOG_TARGET,
+						?candidate_hash,
+						?blocks_including,
+						"Reverting blocks including candidate concluded invalid",
+					);
+					self.metrics.on_blocks_reverted(blocks_including.len());
+					ctx.send_message(ChainSelectionMessage::RevertBlocks(blocks_including)).await;
+				}
 			}
 
 			// Only write when updated:
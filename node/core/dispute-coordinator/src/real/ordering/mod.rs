@@ -16,7 +16,7 @@
 
 use std::{
 	cmp::{Ord, Ordering, PartialOrd},
-	collections::{BTreeMap, HashSet},
+	collections::{BTreeMap, HashMap, HashSet},
 };
 
 use futures::channel::oneshot;
@@ -40,8 +40,9 @@ const LRU_OBSERVED_BLOCKS_CAPACITY: usize = 20;
 
 /// Provider of `CandidateComparator` for candidates.
 pub struct OrderingProvider {
-	/// All candidates we have seen included, which not yet have been finalized.
-	included_candidates: HashSet<CandidateHash>,
+	/// All candidates we have seen included, which not yet have been finalized, together with
+	/// the blocks including them.
+	included_candidates: HashMap<CandidateHash, Vec<(BlockNumber, Hash)>>,
 	/// including block -> `CandidateHash`
 	///
 	/// We need this to clean up `included_candidates` on `ActiveLeavesUpdate`.
@@ -137,7 +138,7 @@ impl OrderingProvider {
 		initial_head: ActivatedLeaf,
 	) -> Result<Self> {
 		let mut s = Self {
-			included_candidates: HashSet::new(),
+			included_candidates: HashMap::new(),
 			candidates_by_block_number: BTreeMap::new(),
 			last_observed_blocks: LruCache::new(LRU_OBSERVED_BLOCKS_CAPACITY),
 		};
@@ -157,7 +158,7 @@ impl OrderingProvider {
 		candidate: &CandidateReceipt,
 	) -> FatalResult<Option<CandidateComparator>> {
 		let candidate_hash = candidate.hash();
-		if !self.included_candidates.contains(&candidate_hash) {
+		if !self.included_candidates.contains_key(&candidate_hash) {
 			return Ok(None)
 		}
 		let n = match get_block_number(sender, candidate.descriptor().relay_parent).await? {
@@ -175,6 +176,11 @@ impl OrderingProvider {
 		Ok(Some(CandidateComparator { relay_parent_block_number: n, candidate_hash }))
 	}
 
+	/// The unfinalized blocks we have seen including the given candidate.
+	pub fn blocks_including(&self, candidate_hash: &CandidateHash) -> Vec<(BlockNumber, Hash)> {
+		self.included_candidates.get(candidate_hash).cloned().unwrap_or_default()
+	}
+
 	/// Query active leaves for any candidate `CandidateEvent::CandidateIncluded` events.
 	///
 	/// and updates current heads, so we can query candidates for all non finalized blocks.
@@ -237,7 +243,11 @@ impl OrderingProvider {
 					});
 				for receipt in included {
 					let candidate_hash = receipt.hash();
-					self.included_candidates.insert(candidate_hash);
+					let including_blocks =
+						self.included_candidates.entry(candidate_hash).or_default();
+					if !including_blocks.contains(&(block_num, block_hash)) {
+						including_blocks.push((block_num, block_hash));
+					}
 					self.candidates_by_block_number
 						.entry(block_num)
 						.or_default()
@@ -353,3 +353,34 @@ fn ordering_provider_requests_candidates_of_non_finalized_ancestors() {
 			.await;
 	});
 }
+
+#[test]
+fn ordering_provider_tracks_blocks_including_candidates_until_finalized() {
+	let candidate = make_candidate_receipt(get_block_number_hash(1));
+	futures::executor::block_on(async {
+		let (state, mut virtual_overseer) = TestState::new().await;
+
+		let TestState { mut chain, mut ordering, mut ctx } = state;
+
+		assert!(ordering.blocks_including(&candidate.hash()).is_empty());
+
+		let next_update = next_leaf(&mut chain);
+
+		let finalized_block_number = 0;
+		let expected_ancestry_len = 1;
+		let overseer_fut = overseer_process_active_leaves_update(
+			&mut virtual_overseer,
+			&chain,
+			finalized_block_number,
+			expected_ancestry_len,
+		);
+		join(process_active_leaves_update(ctx.sender(), &mut ordering, next_update), overseer_fut)
+			.await;
+
+		assert_eq!(ordering.blocks_including(&candidate.hash()), vec![(1, chain[1])]);
+
+		// Finalized blocks can't be reverted anymore.
+		ordering.process_finalized_block(&2);
+		assert!(ordering.blocks_including(&candidate.hash()).is_empty());
+	});
+}
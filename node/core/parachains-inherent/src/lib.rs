@@ -28,7 +28,10 @@ use futures::{select, FutureExt};
 use selendra_node_subsystem::{
 	errors::SubsystemError, messages::ProvisionerMessage, overseer::Handle,
 };
-use selendra_primitives::v1::{Block, Hash, InherentData as ParachainsInherentData};
+use selendra_primitives::v1::{
+	Block, BlockNumber, Hash, InherentData as ParachainsInherentData,
+	FINALIZED_NUMBER_INHERENT_IDENTIFIER,
+};
 use sp_blockchain::HeaderBackend;
 use sp_runtime::generic::BlockId;
 use std::time;
@@ -39,6 +42,7 @@ const PROVISIONER_TIMEOUT: time::Duration = core::time::Duration::from_millis(25
 /// Provides the parachains inherent data.
 pub struct ParachainsInherentDataProvider {
 	inherent_data: ParachainsInherentData,
+	finalized_number: BlockNumber,
 }
 
 impl ParachainsInherentDataProvider {
@@ -101,7 +105,10 @@ impl ParachainsInherentDataProvider {
 			},
 		};
 
-		Ok(Self { inherent_data })
+		// The runtime stops accepting backed candidates once finality lags too far behind.
+		let finalized_number = client.info().finalized_number;
+
+		Ok(Self { inherent_data, finalized_number })
 	}
 }
 
@@ -111,8 +118,11 @@ impl sp_inherents::InherentDataProvider for ParachainsInherentDataProvider {
 		&self,
 		dst_inherent_data: &mut sp_inherents::InherentData,
 	) -> Result<(), sp_inherents::Error> {
-		dst_inherent_data
-			.put_data(selendra_primitives::v1::PARACHAINS_INHERENT_IDENTIFIER, &self.inherent_data)
+		dst_inherent_data.put_data(
+			selendra_primitives::v1::PARACHAINS_INHERENT_IDENTIFIER,
+			&self.inherent_data,
+		)?;
+		dst_inherent_data.put_data(FINALIZED_NUMBER_INHERENT_IDENTIFIER, &self.finalized_number)
 	}
 
 	async fn try_handle_error(
@@ -391,6 +391,11 @@ fn select_availability_bitfields(
 }
 
 /// Determine which cores are free, and then to the degree possible, pick a candidate appropriate to each free core.
+///
+/// The selection is deterministic given the same inputs: candidates are returned in ascending
+/// order of core index, and where several backed candidates are eligible for the same core, the
+/// one with the lowest candidate hash is chosen. This way validators building on the same parent
+/// produce comparable inherents regardless of the order in which candidates were backed.
 async fn select_candidates(
 	availability_cores: &[CoreState],
 	bitfields: &[SignedAvailabilityBitfield],
@@ -444,13 +449,18 @@ async fn select_candidates(
 
 		let computed_validation_data_hash = validation_data.hash();
 
-		// we arbitrarily pick the first of the backed candidates which match the appropriate selection criteria
-		if let Some(candidate) = candidates.iter().find(|backed_candidate| {
-			let descriptor = &backed_candidate.descriptor;
-			descriptor.para_id == scheduled_core.para_id &&
-				descriptor.persisted_validation_data_hash == computed_validation_data_hash
-		}) {
-			let candidate_hash = candidate.hash();
+		// of the backed candidates which match the appropriate selection criteria, we pick the one
+		// with the lowest hash, so that the choice doesn't depend on the order they were backed in.
+		if let Some((candidate_hash, candidate)) = candidates
+			.iter()
+			.filter(|backed_candidate| {
+				let descriptor = &backed_candidate.descriptor;
+				descriptor.para_id == scheduled_core.para_id &&
+					descriptor.persisted_validation_data_hash == computed_validation_data_hash
+			})
+			.map(|candidate| (candidate.hash(), candidate))
+			.min_by_key(|(candidate_hash, _)| *candidate_hash)
+		{
 			tracing::trace!(
 				target: LOG_TARGET,
 				"Selecting candidate {}. para_id={} core={}",
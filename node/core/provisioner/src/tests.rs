@@ -481,4 +481,106 @@ mod select_candidates {
 			},
 		)
 	}
+
+	// backing lookup which answers `GetBackedCandidates` with the known receipts matching the
+	// requested hashes, in the requested order.
+	async fn mock_overseer_with_lookup(
+		mut receiver: mpsc::UnboundedReceiver<AllMessages>,
+		known: Vec<CommittedCandidateReceipt>,
+	) {
+		use ChainApiMessage::BlockNumber;
+		use RuntimeApiMessage::Request;
+
+		while let Some(from_job) = receiver.next().await {
+			match from_job {
+				AllMessages::CandidateBacking(CandidateBackingMessage::GetBackedCandidates(
+					_,
+					hashes,
+					sender,
+				)) => {
+					let backed = hashes
+						.iter()
+						.filter_map(|hash| known.iter().find(|r| r.hash() == *hash))
+						.map(|receipt| BackedCandidate {
+							candidate: receipt.clone(),
+							validity_votes: Vec::new(),
+							validator_indices: default_bitvec(known.len()),
+						})
+						.collect();
+					let _ = sender.send(backed);
+				},
+				AllMessages::ChainApi(BlockNumber(_relay_parent, tx)) =>
+					tx.send(Ok(Some(BLOCK_UNDER_PRODUCTION - 1))).unwrap(),
+				AllMessages::RuntimeApi(Request(
+					_parent_hash,
+					PersistedValidationDataReq(_para_id, _assumption, tx),
+				)) => tx.send(Ok(Some(Default::default()))).unwrap(),
+				AllMessages::RuntimeApi(Request(_parent_hash, AvailabilityCores(tx))) =>
+					tx.send(Ok(mock_availability_cores())).unwrap(),
+				_ => panic!("Unexpected message: {:?}", from_job),
+			}
+		}
+	}
+
+	// this tests that when several backed candidates are eligible for the same core, the one with
+	// the lowest hash is selected, and that the result is ordered by core index, no matter the
+	// order the candidates are supplied in.
+	#[test]
+	fn selection_is_deterministic() {
+		let mock_cores = mock_availability_cores();
+
+		let empty_hash = PersistedValidationData::<Hash, BlockNumber>::default().hash();
+
+		// why those particular indices? see the comments on mock_availability_cores()
+		let cores = [1, 4, 7, 8, 10];
+
+		let committed_receipts: Vec<_> = cores
+			.iter()
+			.flat_map(|&i| {
+				(0..2u8).map(move |n| {
+					let mut descriptor = dummy_candidate_descriptor(dummy_hash());
+					descriptor.para_id = i.into();
+					descriptor.persisted_validation_data_hash = empty_hash;
+					CommittedCandidateReceipt {
+						descriptor,
+						commitments: CandidateCommitments {
+							head_data: vec![n].into(),
+							..Default::default()
+						},
+					}
+				})
+			})
+			.collect();
+
+		let expected: Vec<CandidateHash> = committed_receipts
+			.chunks(2)
+			.map(|pair| pair.iter().map(|r| r.hash()).min().unwrap())
+			.collect();
+
+		let candidates: Vec<_> = committed_receipts.iter().map(|r| r.to_plain()).collect();
+		let mut reversed = candidates.clone();
+		reversed.reverse();
+
+		for candidates in [candidates, reversed] {
+			let expected = expected.clone();
+			let mock_cores = mock_cores.clone();
+			test_harness(
+				|r| mock_overseer_with_lookup(r, committed_receipts.clone()),
+				|mut tx: TestSubsystemSender| async move {
+					let result = select_candidates(
+						&mock_cores,
+						&[],
+						&candidates,
+						Default::default(),
+						&mut tx,
+					)
+					.await
+					.unwrap();
+
+					let selected: Vec<_> = result.iter().map(|c| c.hash()).collect();
+					assert_eq!(selected, expected);
+				},
+			)
+		}
+	}
 }
@@ -18,6 +18,7 @@ use crate::{error::PrepareError, host::PrepareResultSender};
 use always_assert::always;
 use async_std::path::{Path, PathBuf};
 use parity_scale_codec::{Decode, Encode};
+use selendra_core_primitives::Hash;
 use selendra_parachain::primitives::ValidationCodeHash;
 use std::{
 	collections::HashMap,
@@ -40,36 +41,40 @@ impl AsRef<[u8]> for CompiledArtifact {
 	}
 }
 
-/// Identifier of an artifact. Right now it only encodes a code hash of the PVF. But if we get to
-/// multiple engine implementations the artifact ID should include the engine type as well.
+/// Identifier of an artifact. Right now it encodes a code hash of the PVF and the hash of the
+/// executor parameters it was prepared with. But if we get to multiple engine implementations the
+/// artifact ID should include the engine type as well.
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct ArtifactId {
 	pub(crate) code_hash: ValidationCodeHash,
+	pub(crate) executor_params_hash: Hash,
 }
 
 impl ArtifactId {
 	const PREFIX: &'static str = "wasmtime_";
 
-	/// Creates a new artifact ID with the given hash.
-	pub fn new(code_hash: ValidationCodeHash) -> Self {
-		Self { code_hash }
+	/// Creates a new artifact ID with the given hashes.
+	pub fn new(code_hash: ValidationCodeHash, executor_params_hash: Hash) -> Self {
+		Self { code_hash, executor_params_hash }
 	}
 
 	/// Tries to recover the artifact id from the given file name.
 	#[cfg(test)]
 	pub fn from_file_name(file_name: &str) -> Option<Self> {
-		use selendra_core_primitives::Hash;
 		use std::str::FromStr as _;
 
 		let file_name = file_name.strip_prefix(Self::PREFIX)?;
-		let code_hash = Hash::from_str(file_name).ok()?.into();
+		let (code_hash, executor_params_hash) = file_name.split_once('_')?;
+		let code_hash = Hash::from_str(code_hash).ok()?.into();
+		let executor_params_hash = Hash::from_str(executor_params_hash).ok()?;
 
-		Some(Self { code_hash })
+		Some(Self { code_hash, executor_params_hash })
 	}
 
 	/// Returns the expected path to this artifact given the root of the cache.
 	pub fn path(&self, cache_path: &Path) -> PathBuf {
-		let file_name = format!("{}{:#x}", Self::PREFIX, self.code_hash);
+		let file_name =
+			format!("{}{:#x}_{:#x}", Self::PREFIX, self.code_hash, self.executor_params_hash);
 		cache_path.join(file_name)
 	}
 }
@@ -206,16 +211,25 @@ mod tests {
 	fn from_file_name() {
 		assert!(ArtifactId::from_file_name("").is_none());
 		assert!(ArtifactId::from_file_name("junk").is_none());
+		assert!(ArtifactId::from_file_name(
+			"wasmtime_0x0022800000000000000000000000000000000000000000000000000000000000"
+		)
+		.is_none());
 
 		assert_eq!(
 			ArtifactId::from_file_name(
-				"wasmtime_0x0022800000000000000000000000000000000000000000000000000000000000"
+				"wasmtime_0x0022800000000000000000000000000000000000000000000000000000000000_\
+				0x0033900000000000000000000000000000000000000000000000000000000000"
 			),
 			Some(ArtifactId::new(
 				hex_literal::hex![
 					"0022800000000000000000000000000000000000000000000000000000000000"
 				]
-				.into()
+				.into(),
+				hex_literal::hex![
+					"0033900000000000000000000000000000000000000000000000000000000000"
+				]
+				.into(),
 			)),
 		);
 	}
@@ -225,13 +239,13 @@ mod tests {
 		let path = Path::new("/test");
 		let hash =
 			H256::from_str("1234567890123456789012345678901234567890123456789012345678901234")
-				.unwrap()
-				.into();
+				.unwrap();
 
 		assert_eq!(
-			ArtifactId::new(hash).path(path).to_str(),
+			ArtifactId::new(hash.into(), hash).path(path).to_str(),
 			Some(
-				"/test/wasmtime_0x1234567890123456789012345678901234567890123456789012345678901234"
+				"/test/wasmtime_0x1234567890123456789012345678901234567890123456789012345678901234_\
+				0x1234567890123456789012345678901234567890123456789012345678901234"
 			),
 		);
 	}
@@ -31,6 +31,7 @@ use futures::{
 	stream::{FuturesUnordered, StreamExt as _},
 	Future, FutureExt,
 };
+use selendra_parachain::primitives::ExecutorParams;
 use slotmap::HopSlotMap;
 use std::{collections::VecDeque, fmt, time::Duration};
 
@@ -40,6 +41,7 @@ slotmap::new_key_type! { struct Worker; }
 pub enum ToQueue {
 	Enqueue {
 		artifact: ArtifactPathId,
+		executor_params: ExecutorParams,
 		execution_timeout: Duration,
 		params: Vec<u8>,
 		result_tx: ResultSender,
@@ -48,6 +50,7 @@ pub enum ToQueue {
 
 struct ExecuteJob {
 	artifact: ArtifactPathId,
+	executor_params: ExecutorParams,
 	execution_timeout: Duration,
 	params: Vec<u8>,
 	result_tx: ResultSender,
@@ -173,14 +176,15 @@ async fn purge_dead(metrics: &Metrics, workers: &mut Workers) {
 }
 
 fn handle_to_queue(queue: &mut Queue, to_queue: ToQueue) {
-	let ToQueue::Enqueue { artifact, execution_timeout, params, result_tx } = to_queue;
+	let ToQueue::Enqueue { artifact, executor_params, execution_timeout, params, result_tx } =
+		to_queue;
 	tracing::debug!(
 		target: LOG_TARGET,
 		validation_code_hash = ?artifact.id.code_hash,
 		"enqueueing an artifact for execution",
 	);
 	queue.metrics.execute_enqueued();
-	let job = ExecuteJob { artifact, execution_timeout, params, result_tx };
+	let job = ExecuteJob { artifact, executor_params, execution_timeout, params, result_tx };
 
 	if let Some(available) = queue.workers.find_available() {
 		assign(queue, available, job);
@@ -335,6 +339,7 @@ fn assign(queue: &mut Queue, worker: Worker, job: ExecuteJob) {
 			let outcome = super::worker::start_work(
 				idle,
 				job.artifact.clone(),
+				job.executor_params,
 				job.execution_timeout,
 				job.params,
 			)
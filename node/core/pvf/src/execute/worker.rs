@@ -31,7 +31,7 @@ use async_std::{
 use futures::FutureExt;
 use futures_timer::Delay;
 use parity_scale_codec::{Decode, Encode};
-use selendra_parachain::primitives::ValidationResult;
+use selendra_parachain::primitives::{ExecutorParams, ValidationResult};
 use std::time::{Duration, Instant};
 
 /// Spawns a new worker with the given program path that acts as the worker and the spawn timeout.
@@ -67,6 +67,7 @@ pub enum Outcome {
 pub async fn start_work(
 	worker: IdleWorker,
 	artifact: ArtifactPathId,
+	executor_params: ExecutorParams,
 	execution_timeout: Duration,
 	validation_params: Vec<u8>,
 ) -> Outcome {
@@ -80,7 +81,9 @@ pub async fn start_work(
 		artifact.path.display(),
 	);
 
-	if let Err(error) = send_request(&mut stream, &artifact.path, &validation_params).await {
+	if let Err(error) =
+		send_request(&mut stream, &artifact.path, executor_params, &validation_params).await
+	{
 		tracing::warn!(
 			target: LOG_TARGET,
 			worker_pid = %pid,
@@ -131,13 +134,15 @@ pub async fn start_work(
 async fn send_request(
 	stream: &mut UnixStream,
 	artifact_path: &Path,
+	executor_params: ExecutorParams,
 	validation_params: &[u8],
 ) -> io::Result<()> {
 	framed_send(stream, path_to_bytes(artifact_path)).await?;
+	framed_send(stream, &executor_params.encode()).await?;
 	framed_send(stream, validation_params).await
 }
 
-async fn recv_request(stream: &mut UnixStream) -> io::Result<(PathBuf, Vec<u8>)> {
+async fn recv_request(stream: &mut UnixStream) -> io::Result<(PathBuf, ExecutorParams, Vec<u8>)> {
 	let artifact_path = framed_recv(stream).await?;
 	let artifact_path = bytes_to_path(&artifact_path).ok_or_else(|| {
 		io::Error::new(
@@ -145,8 +150,15 @@ async fn recv_request(stream: &mut UnixStream) -> io::Result<(PathBuf, Vec<u8>)>
 			"execute pvf recv_request: non utf-8 artifact path".to_string(),
 		)
 	})?;
+	let executor_params = framed_recv(stream).await?;
+	let executor_params = ExecutorParams::decode(&mut &executor_params[..]).map_err(|_| {
+		io::Error::new(
+			io::ErrorKind::Other,
+			"execute pvf recv_request: failed to decode executor params".to_string(),
+		)
+	})?;
 	let params = framed_recv(stream).await?;
-	Ok((artifact_path, params))
+	Ok((artifact_path, executor_params, params))
 }
 
 async fn send_response(stream: &mut UnixStream, response: Response) -> io::Result<()> {
@@ -188,14 +200,15 @@ pub fn worker_entrypoint(socket_path: &str) {
 			io::Error::new(io::ErrorKind::Other, format!("cannot create task executor: {}", e))
 		})?;
 		loop {
-			let (artifact_path, params) = recv_request(&mut stream).await?;
+			let (artifact_path, executor_params, params) = recv_request(&mut stream).await?;
 			tracing::debug!(
 				target: LOG_TARGET,
 				worker_pid = %std::process::id(),
 				"worker: validating artifact {}",
 				artifact_path.display(),
 			);
-			let response = validate_using_artifact(&artifact_path, &params, &executor).await;
+			let response =
+				validate_using_artifact(&artifact_path, &executor_params, &params, &executor).await;
 			send_response(&mut stream, response).await?;
 		}
 	});
@@ -203,6 +216,7 @@ pub fn worker_entrypoint(socket_path: &str) {
 
 async fn validate_using_artifact(
 	artifact_path: &Path,
+	executor_params: &ExecutorParams,
 	params: &[u8],
 	spawner: &TaskExecutor,
 ) -> Response {
@@ -228,7 +242,7 @@ async fn validate_using_artifact(
 		// SAFETY: this should be safe since the compiled artifact passed here comes from the
 		//         file created by the prepare workers. These files are obtained by calling
 		//         [`executor_intf::prepare`].
-		crate::executor_intf::execute(compiled_artifact, params, spawner.clone())
+		crate::executor_intf::execute(compiled_artifact, params, executor_params, spawner.clone())
 	} {
 		Err(err) => return Response::format_invalid("execute", &err.to_string()),
 		Ok(d) => d,
@@ -21,39 +21,52 @@ use sc_executor_common::{
 	wasm_runtime::{InvokeMethod, WasmModule as _},
 };
 use sc_executor_wasmtime::{Config, DeterministicStackLimit, Semantics};
+use selendra_parachain::primitives::ExecutorParams;
 use sp_core::storage::{ChildInfo, TrackedStorageKey};
 use std::any::{Any, TypeId};
 
-const CONFIG: Config = Config {
-	// Memory configuration
-	//
-	// When Substrate Runtime is instantiated, a number of wasm pages are mounted for the Substrate
-	// Runtime instance. The number of pages is specified by `heap_pages`.
-	//
-	// Besides `heap_pages` linear memory requests an initial number of pages. Those pages are
-	// typically used for placing the so-called shadow stack and the data section.
-	//
-	// By default, rustc (or `lld` specifically) allocates 1 MiB for the shadow stack. That is, 16
-	// wasm pages.
-	//
-	// Data section for runtimes are typically rather small and can fit in a single digit number of
-	// wasm pages.
-	//
-	// Thus let's assume that 32 pages or 2 MiB are used for these needs.
-	//
-	// Note that the memory limit is specified in bytes, so we multiply this value
-	// by wasm page size -- 64 KiB.
-	max_memory_size: Some((2048 + 32) * 65536),
-	heap_pages: 2048,
-
-	allow_missing_func_imports: true,
-	cache_path: None,
-	semantics: Semantics {
+/// Returns the configuration of the executor for the given executor parameters.
+///
+/// The defaults of [`ExecutorParams`] give the configuration described below.
+fn config(executor_params: &ExecutorParams) -> Config {
+	Config {
+		// Memory configuration
+		//
+		// When Substrate Runtime is instantiated, a number of wasm pages are mounted for the
+		// Substrate Runtime instance. The number of pages is specified by `heap_pages`.
+		//
+		// Besides `heap_pages` linear memory requests an initial number of pages. Those pages are
+		// typically used for placing the so-called shadow stack and the data section.
+		//
+		// By default, rustc (or `lld` specifically) allocates 1 MiB for the shadow stack. That is,
+		// 16 wasm pages.
+		//
+		// Data section for runtimes are typically rather small and can fit in a single digit number
+		// of wasm pages.
+		//
+		// Thus let's assume that 32 pages or 2 MiB are used for these needs.
+		//
+		// Note that the memory limit is specified in bytes, so we multiply this value
+		// by wasm page size -- 64 KiB.
+		max_memory_size: Some((executor_params.heap_pages as usize + 32) * 65536),
+		heap_pages: executor_params.heap_pages as u64,
+
+		allow_missing_func_imports: true,
+		cache_path: None,
+		semantics: semantics(executor_params),
+	}
+}
+
+/// Returns the semantics of the executor for the given executor parameters.
+///
+/// Those need to be the same for preparing and executing an artifact.
+fn semantics(executor_params: &ExecutorParams) -> Semantics {
+	Semantics {
 		fast_instance_reuse: false,
 		// Enable deterministic stack limit to pin down the exact number of items the wasmtime stack
 		// can contain before it traps with stack overflow.
 		//
-		// Here is how the values below were chosen.
+		// Here is how the default values were chosen.
 		//
 		// At the moment of writing, the default native stack size limit is 1 MiB. Assuming a logical item
 		// (see the docs about the field and the instrumentation algorithm) is 8 bytes, 1 MiB can
@@ -63,8 +76,8 @@ const CONFIG: Config = Config {
 		// also increase the native 256x. This hopefully should preclude wasm code from reaching
 		// the stack limit set by the wasmtime.
 		deterministic_stack_limit: Some(DeterministicStackLimit {
-			logical_max: 65536,
-			native_stack_max: 256 * 1024 * 1024,
+			logical_max: executor_params.stack_logical_max,
+			native_stack_max: executor_params.stack_native_max,
 		}),
 		canonicalize_nans: true,
 		// Rationale for turning the multi-threaded compilation off is to make the preparation time
@@ -74,8 +87,8 @@ const CONFIG: Config = Config {
 		// On the one hand, it simplifies the code, on the other, however, slows down compile times
 		// for execute requests. This behavior may change in future.
 		parallel_compilation: false,
-	},
-};
+	}
+}
 
 /// Runs the prevalidation on the given code. Returns a [`RuntimeBlob`] if it succeeds.
 pub fn prevalidate(code: &[u8]) -> Result<RuntimeBlob, sc_executor_common::error::WasmError> {
@@ -89,8 +102,11 @@ pub fn prevalidate(code: &[u8]) -> Result<RuntimeBlob, sc_executor_common::error
 
 /// Runs preparation on the given runtime blob. If successful, it returns a serialized compiled
 /// artifact which can then be used to pass into [`execute`].
-pub fn prepare(blob: RuntimeBlob) -> Result<Vec<u8>, sc_executor_common::error::WasmError> {
-	sc_executor_wasmtime::prepare_runtime_artifact(blob, &CONFIG.semantics)
+pub fn prepare(
+	blob: RuntimeBlob,
+	executor_params: &ExecutorParams,
+) -> Result<Vec<u8>, sc_executor_common::error::WasmError> {
+	sc_executor_wasmtime::prepare_runtime_artifact(blob, &semantics(executor_params))
 }
 
 /// Executes the given PVF in the form of a compiled artifact and returns the result of execution
@@ -98,11 +114,12 @@ pub fn prepare(blob: RuntimeBlob) -> Result<Vec<u8>, sc_executor_common::error::
 ///
 /// # Safety
 ///
-/// The compiled artifact must be produced with [`prepare`]. Not following this guidance can lead
-/// to arbitrary code execution.
+/// The compiled artifact must be produced with [`prepare`], using the same executor parameters.
+/// Not following this guidance can lead to arbitrary code execution.
 pub unsafe fn execute(
 	compiled_artifact: &[u8],
 	params: &[u8],
+	executor_params: &ExecutorParams,
 	spawner: impl sp_core::traits::SpawnNamed + 'static,
 ) -> Result<Vec<u8>, sc_executor_common::error::Error> {
 	let mut extensions = sp_externalities::Extensions::new();
@@ -115,7 +132,7 @@ pub unsafe fn execute(
 	sc_executor::with_externalities_safe(&mut ext, || {
 		let runtime = sc_executor_wasmtime::create_runtime_from_artifact::<HostFunctions>(
 			compiled_artifact,
-			CONFIG,
+			config(executor_params),
 		)?;
 		runtime.new_instance()?.call(InvokeMethod::Export("validate_block"), params)
 	})?
@@ -264,10 +281,11 @@ impl sp_externalities::ExtensionStore for ValidationExternalities {
 ///
 /// This is a light handle meaning it will only clone the handle not create a new thread pool.
 #[derive(Clone)]
-pub(crate) struct TaskExecutor(futures::executor::ThreadPool);
+pub struct TaskExecutor(futures::executor::ThreadPool);
 
 impl TaskExecutor {
-	pub(crate) fn new() -> Result<Self, String> {
+	/// Create a new task executor.
+	pub fn new() -> Result<Self, String> {
 		futures::executor::ThreadPoolBuilder::new()
 			.pool_size(4)
 			.name_prefix("pvf-task-executor")
@@ -32,7 +32,7 @@ use futures::{
 	channel::{mpsc, oneshot},
 	Future, FutureExt, SinkExt, StreamExt,
 };
-use selendra_parachain::primitives::ValidationResult;
+use selendra_parachain::primitives::{ExecutorParams, ValidationResult};
 use std::{
 	collections::HashMap,
 	time::{Duration, SystemTime},
@@ -234,6 +234,7 @@ pub fn start(config: Config, metrics: Metrics) -> (ValidationHost, impl Future<O
 /// to the given result sender.
 #[derive(Debug)]
 struct PendingExecutionRequest {
+	executor_params: ExecutorParams,
 	execution_timeout: Duration,
 	params: Vec<u8>,
 	result_tx: ResultSender,
@@ -248,11 +249,13 @@ impl AwaitingPrepare {
 	fn add(
 		&mut self,
 		artifact_id: ArtifactId,
+		executor_params: ExecutorParams,
 		execution_timeout: Duration,
 		params: Vec<u8>,
 		result_tx: ResultSender,
 	) {
 		self.0.entry(artifact_id).or_default().push(PendingExecutionRequest {
+			executor_params,
 			execution_timeout,
 			params,
 			result_tx,
@@ -468,6 +471,7 @@ async fn handle_execute_pvf(
 	result_tx: ResultSender,
 ) -> Result<(), Fatal> {
 	let artifact_id = pvf.as_artifact_id();
+	let executor_params = pvf.executor_params;
 
 	if let Some(state) = artifacts.artifact_state_mut(&artifact_id) {
 		match state {
@@ -478,6 +482,7 @@ async fn handle_execute_pvf(
 					execute_queue,
 					execute::ToQueue::Enqueue {
 						artifact: ArtifactPathId::new(artifact_id, cache_path),
+						executor_params,
 						execution_timeout,
 						params,
 						result_tx,
@@ -486,7 +491,13 @@ async fn handle_execute_pvf(
 				.await?;
 			},
 			ArtifactState::Preparing { waiting_for_response: _ } => {
-				awaiting_prepare.add(artifact_id, execution_timeout, params, result_tx);
+				awaiting_prepare.add(
+					artifact_id,
+					executor_params,
+					execution_timeout,
+					params,
+					result_tx,
+				);
 			},
 			ArtifactState::FailedToProcess(error) => {
 				let _ = result_tx.send(Err(ValidationError::from(error.clone())));
@@ -498,7 +509,7 @@ async fn handle_execute_pvf(
 		artifacts.insert_preparing(artifact_id.clone(), Vec::new());
 		send_prepare(prepare_queue, prepare::ToQueue::Enqueue { priority, pvf }).await?;
 
-		awaiting_prepare.add(artifact_id, execution_timeout, params, result_tx);
+		awaiting_prepare.add(artifact_id, executor_params, execution_timeout, params, result_tx);
 	}
 
 	return Ok(())
@@ -585,7 +596,9 @@ async fn handle_prepare_done(
 	// It's finally time to dispatch all the execution requests that were waiting for this artifact
 	// to be prepared.
 	let pending_requests = awaiting_prepare.take(&artifact_id);
-	for PendingExecutionRequest { execution_timeout, params, result_tx } in pending_requests {
+	for PendingExecutionRequest { executor_params, execution_timeout, params, result_tx } in
+		pending_requests
+	{
 		if result_tx.is_canceled() {
 			// Preparation could've taken quite a bit of time and the requester may be not interested
 			// in execution anymore, in which case we just skip the request.
@@ -602,6 +615,7 @@ async fn handle_prepare_done(
 			execute_queue,
 			execute::ToQueue::Enqueue {
 				artifact: ArtifactPathId::new(artifact_id.clone(), cache_path),
+				executor_params,
 				execution_timeout,
 				params,
 				result_tx,
@@ -1010,6 +1024,65 @@ mod tests {
 		);
 	}
 
+	#[async_std::test]
+	async fn executor_params_get_separate_artifacts() {
+		let mut test = Builder::default().build();
+		let mut host = test.host_handle();
+
+		let executor_params = ExecutorParams { heap_pages: 1024, ..Default::default() };
+		let pvf = Pvf::from_discriminator(1).with_executor_params(executor_params);
+		assert_ne!(pvf.as_artifact_id(), artifact_id(1));
+
+		let (result_tx, _result_rx_default) = oneshot::channel();
+		host.execute_pvf(
+			Pvf::from_discriminator(1),
+			TEST_EXECUTION_TIMEOUT,
+			b"pvf1".to_vec(),
+			Priority::Normal,
+			result_tx,
+		)
+		.await
+		.unwrap();
+
+		let (result_tx, _result_rx_custom) = oneshot::channel();
+		host.execute_pvf(
+			pvf.clone(),
+			TEST_EXECUTION_TIMEOUT,
+			b"pvf1".to_vec(),
+			Priority::Normal,
+			result_tx,
+		)
+		.await
+		.unwrap();
+
+		// The same code gets prepared once per set of executor parameters.
+		assert_matches!(
+			test.poll_and_recv_to_prepare_queue().await,
+			prepare::ToQueue::Enqueue { pvf, .. } => {
+				assert_eq!(pvf.executor_params, ExecutorParams::default());
+			}
+		);
+		assert_matches!(
+			test.poll_and_recv_to_prepare_queue().await,
+			prepare::ToQueue::Enqueue { pvf, .. } => {
+				assert_eq!(pvf.executor_params, executor_params);
+			}
+		);
+
+		test.from_prepare_queue_tx
+			.send(prepare::FromQueue { artifact_id: pvf.as_artifact_id(), result: Ok(()) })
+			.await
+			.unwrap();
+		assert_matches!(
+			test.poll_and_recv_to_execute_queue().await,
+			execute::ToQueue::Enqueue { artifact, executor_params: params, .. } => {
+				assert_eq!(artifact.id, pvf.as_artifact_id());
+				assert_eq!(params, executor_params);
+			}
+		);
+		test.poll_ensure_to_execute_queue_is_empty().await;
+	}
+
 	#[async_std::test]
 	async fn precheck_pvf() {
 		let mut test = Builder::default().build();
@@ -102,7 +102,8 @@ pub use metrics::Metrics;
 pub use execute::worker_entrypoint as execute_worker_entrypoint;
 pub use prepare::worker_entrypoint as prepare_worker_entrypoint;
 
-pub use executor_intf::{prepare, prevalidate};
+pub use executor_intf::{execute, prepare, prevalidate, TaskExecutor};
+pub use selendra_parachain::primitives::ExecutorParams;
 
 pub use sc_executor_common;
 pub use sp_maybe_compressed_blob;
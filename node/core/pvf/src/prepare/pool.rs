@@ -27,6 +27,7 @@ use async_std::path::{Path, PathBuf};
 use futures::{
 	channel::mpsc, future::BoxFuture, stream::FuturesUnordered, Future, FutureExt, StreamExt,
 };
+use selendra_parachain::primitives::ExecutorParams;
 use slotmap::HopSlotMap;
 use std::{fmt, sync::Arc, task::Poll, time::Duration};
 
@@ -61,7 +62,12 @@ pub enum ToPool {
 	///
 	/// In either case, the worker is considered busy and no further `StartWork` messages should be
 	/// sent until either `Concluded` or `Rip` message is received.
-	StartWork { worker: Worker, code: Arc<Vec<u8>>, artifact_path: PathBuf },
+	StartWork {
+		worker: Worker,
+		code: Arc<Vec<u8>>,
+		executor_params: ExecutorParams,
+		artifact_path: PathBuf,
+	},
 }
 
 /// A message sent from pool to its client.
@@ -205,7 +211,7 @@ fn handle_to_pool(
 			metrics.prepare_worker().on_begin_spawn();
 			mux.push(spawn_worker_task(program_path.to_owned(), spawn_timeout).boxed());
 		},
-		ToPool::StartWork { worker, code, artifact_path } => {
+		ToPool::StartWork { worker, code, executor_params, artifact_path } => {
 			if let Some(data) = spawned.get_mut(worker) {
 				if let Some(idle) = data.idle.take() {
 					let preparation_timer = metrics.time_preparation();
@@ -214,6 +220,7 @@ fn handle_to_pool(
 							worker,
 							idle,
 							code,
+							executor_params,
 							cache_path.to_owned(),
 							artifact_path,
 							preparation_timer,
@@ -261,11 +268,12 @@ async fn start_work_task<Timer>(
 	worker: Worker,
 	idle: IdleWorker,
 	code: Arc<Vec<u8>>,
+	executor_params: ExecutorParams,
 	cache_path: PathBuf,
 	artifact_path: PathBuf,
 	_preparation_timer: Option<Timer>,
 ) -> PoolEvent {
-	let outcome = worker::start_work(idle, code, &cache_path, artifact_path).await;
+	let outcome = worker::start_work(idle, code, executor_params, &cache_path, artifact_path).await;
 	PoolEvent::StartWork(worker, outcome)
 }
 
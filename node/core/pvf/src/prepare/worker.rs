@@ -29,6 +29,7 @@ use async_std::{
 	path::{Path, PathBuf},
 };
 use parity_scale_codec::{Decode, Encode};
+use selendra_parachain::primitives::ExecutorParams;
 use sp_core::hexdisplay::HexDisplay;
 use std::{any::Any, panic, sync::Arc, time::Duration};
 
@@ -67,6 +68,7 @@ pub enum Outcome {
 pub async fn start_work(
 	worker: IdleWorker,
 	code: Arc<Vec<u8>>,
+	executor_params: ExecutorParams,
 	cache_path: &Path,
 	artifact_path: PathBuf,
 ) -> Outcome {
@@ -80,7 +82,7 @@ pub async fn start_work(
 	);
 
 	with_tmp_file(pid, cache_path, |tmp_file| async move {
-		if let Err(err) = send_request(&mut stream, code, &tmp_file).await {
+		if let Err(err) = send_request(&mut stream, code, executor_params, &tmp_file).await {
 			tracing::warn!(
 				target: LOG_TARGET,
 				worker_pid = %pid,
@@ -220,15 +222,24 @@ where
 async fn send_request(
 	stream: &mut UnixStream,
 	code: Arc<Vec<u8>>,
+	executor_params: ExecutorParams,
 	tmp_file: &Path,
 ) -> io::Result<()> {
 	framed_send(stream, &*code).await?;
+	framed_send(stream, &executor_params.encode()).await?;
 	framed_send(stream, path_to_bytes(tmp_file)).await?;
 	Ok(())
 }
 
-async fn recv_request(stream: &mut UnixStream) -> io::Result<(Vec<u8>, PathBuf)> {
+async fn recv_request(stream: &mut UnixStream) -> io::Result<(Vec<u8>, ExecutorParams, PathBuf)> {
 	let code = framed_recv(stream).await?;
+	let executor_params = framed_recv(stream).await?;
+	let executor_params = ExecutorParams::decode(&mut &executor_params[..]).map_err(|_| {
+		io::Error::new(
+			io::ErrorKind::Other,
+			"prepare pvf recv_request: failed to decode executor params".to_string(),
+		)
+	})?;
 	let tmp_file = framed_recv(stream).await?;
 	let tmp_file = bytes_to_path(&tmp_file).ok_or_else(|| {
 		io::Error::new(
@@ -236,7 +247,7 @@ async fn recv_request(stream: &mut UnixStream) -> io::Result<(Vec<u8>, PathBuf)>
 			"prepare pvf recv_request: non utf-8 artifact path".to_string(),
 		)
 	})?;
-	Ok((code, tmp_file))
+	Ok((code, executor_params, tmp_file))
 }
 
 /// The entrypoint that the spawned prepare worker should start with. The `socket_path` specifies
@@ -244,7 +255,7 @@ async fn recv_request(stream: &mut UnixStream) -> io::Result<(Vec<u8>, PathBuf)>
 pub fn worker_entrypoint(socket_path: &str) {
 	worker_event_loop("prepare", socket_path, |mut stream| async move {
 		loop {
-			let (code, dest) = recv_request(&mut stream).await?;
+			let (code, executor_params, dest) = recv_request(&mut stream).await?;
 
 			tracing::debug!(
 				target: LOG_TARGET,
@@ -252,7 +263,7 @@ pub fn worker_entrypoint(socket_path: &str) {
 				"worker: preparing artifact",
 			);
 
-			let result = match prepare_artifact(&code) {
+			let result = match prepare_artifact(&code, &executor_params) {
 				Err(err) => {
 					// Serialized error will be written into the socket.
 					Err(err)
@@ -284,14 +295,17 @@ pub fn worker_entrypoint(socket_path: &str) {
 	});
 }
 
-fn prepare_artifact(code: &[u8]) -> Result<CompiledArtifact, PrepareError> {
+fn prepare_artifact(
+	code: &[u8],
+	executor_params: &ExecutorParams,
+) -> Result<CompiledArtifact, PrepareError> {
 	panic::catch_unwind(|| {
 		let blob = match crate::executor_intf::prevalidate(code) {
 			Err(err) => return Err(PrepareError::Prevalidation(format!("{:?}", err))),
 			Ok(b) => b,
 		};
 
-		match crate::executor_intf::prepare(blob) {
+		match crate::executor_intf::prepare(blob, executor_params) {
 			Ok(compiled_artifact) => Ok(CompiledArtifact::new(compiled_artifact)),
 			Err(err) => Err(PrepareError::Preparation(format!("{:?}", err))),
 		}
@@ -15,31 +15,46 @@
 // along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
 
 use crate::artifacts::ArtifactId;
-use selendra_parachain::primitives::ValidationCodeHash;
+use selendra_parachain::primitives::{ExecutorParams, ValidationCodeHash};
 use sp_core::blake2_256;
 use std::{fmt, sync::Arc};
 
-/// A struct that carries code of a parachain validation function and it's hash.
+/// A struct that carries code of a parachain validation function, it's hash and the parameters of
+/// the environment it should be prepared and executed in.
 ///
 /// Should be cheap to clone.
 #[derive(Clone)]
 pub struct Pvf {
 	pub(crate) code: Arc<Vec<u8>>,
 	pub(crate) code_hash: ValidationCodeHash,
+	pub(crate) executor_params: ExecutorParams,
 }
 
 impl fmt::Debug for Pvf {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-		write!(f, "Pvf {{ code, code_hash: {:?} }}", self.code_hash)
+		write!(
+			f,
+			"Pvf {{ code, code_hash: {:?}, executor_params: {:?} }}",
+			self.code_hash, self.executor_params,
+		)
 	}
 }
 
 impl Pvf {
 	/// Returns an instance of the PVF out of the given PVF code.
+	///
+	/// The PVF is prepared and executed with the default executor parameters, unless
+	/// [`Pvf::with_executor_params`] is used.
 	pub fn from_code(code: Vec<u8>) -> Self {
 		let code = Arc::new(code);
 		let code_hash = blake2_256(&code).into();
-		Self { code, code_hash }
+		Self { code, code_hash, executor_params: ExecutorParams::default() }
+	}
+
+	/// Sets the parameters of the environment the PVF is prepared and executed in.
+	pub fn with_executor_params(mut self, executor_params: ExecutorParams) -> Self {
+		self.executor_params = executor_params;
+		self
 	}
 
 	/// Creates a new PVF which artifact id can be uniquely identified by the given number.
@@ -51,6 +66,6 @@ impl Pvf {
 
 	/// Returns the artifact ID that corresponds to this PVF.
 	pub(crate) fn as_artifact_id(&self) -> ArtifactId {
-		ArtifactId::new(self.code_hash)
+		ArtifactId::new(self.code_hash, self.executor_params.hash())
 	}
 }
@@ -30,16 +30,18 @@ pub fn validate_candidate(
 	params: &[u8],
 ) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
 	use crate::executor_intf::{execute, prepare, prevalidate, TaskExecutor};
+	use selendra_parachain::primitives::ExecutorParams;
 
 	let code = sp_maybe_compressed_blob::decompress(code, 10 * 1024 * 1024)
 		.expect("Decompressing code failed");
 
 	let blob = prevalidate(&*code)?;
-	let artifact = prepare(blob)?;
+	let executor_params = ExecutorParams::default();
+	let artifact = prepare(blob, &executor_params)?;
 	let executor = TaskExecutor::new()?;
 	let result = unsafe {
 		// SAFETY: This is trivially safe since the artifact is obtained by calling `prepare`.
-		execute(&artifact, params, executor)?
+		execute(&artifact, params, &executor_params, executor)?
 	};
 
 	Ok(result)
@@ -23,8 +23,8 @@ use sp_consensus_babe::Epoch;
 use selendra_primitives::{
 	v1::{
 		AuthorityDiscoveryId, BlockNumber, CandidateCommitments, CandidateEvent,
-		CommittedCandidateReceipt, CoreState, GroupRotationInfo, Hash, Id as ParaId,
-		InboundDownwardMessage, InboundHrmpMessage, OccupiedCoreAssumption,
+		CommittedCandidateReceipt, CoreState, ExecutorParams, GroupRotationInfo, Hash,
+		Id as ParaId, InboundDownwardMessage, InboundHrmpMessage, OccupiedCoreAssumption,
 		PersistedValidationData, ScrapedOnChainVotes, SessionIndex, ValidationCode,
 		ValidationCodeHash, ValidatorId, ValidatorIndex, ValidatorSignature,
 	},
@@ -40,6 +40,7 @@ const ASSUMED_VALIDATION_DATA_CACHE_SIZE: usize = 64 * 1024;
 const CHECK_VALIDATION_OUTPUTS_CACHE_SIZE: usize = 64 * 1024;
 const SESSION_INDEX_FOR_CHILD_CACHE_SIZE: usize = 64 * 1024;
 const VALIDATION_CODE_CACHE_SIZE: usize = 10 * 1024 * 1024;
+const VALIDATION_CODE_BY_HASH_CACHE_SIZE: usize = 64 * 1024 * 1024;
 const CANDIDATE_PENDING_AVAILABILITY_CACHE_SIZE: usize = 64 * 1024;
 const CANDIDATE_EVENTS_CACHE_SIZE: usize = 64 * 1024;
 const SESSION_INFO_CACHE_SIZE: usize = 64 * 1024;
@@ -49,6 +50,7 @@ const CURRENT_BABE_EPOCH_CACHE_SIZE: usize = 64 * 1024;
 const ON_CHAIN_VOTES_CACHE_SIZE: usize = 3 * 1024;
 const PVFS_REQUIRE_PRECHECK_SIZE: usize = 1024;
 const VALIDATION_CODE_HASH_CACHE_SIZE: usize = 64 * 1024;
+const SESSION_EXECUTOR_PARAMS_CACHE_SIZE: usize = 16 * 1024;
 
 struct ResidentSizeOf<T>(T);
 
@@ -97,8 +99,9 @@ pub(crate) struct RequestResultCache {
 		(Hash, ParaId, OccupiedCoreAssumption),
 		ResidentSizeOf<Option<ValidationCode>>,
 	>,
-	validation_code_by_hash:
-		MemoryLruCache<ValidationCodeHash, ResidentSizeOf<Option<ValidationCode>>>,
+	/// Code is immutable once it has a hash, so it is shared across relay parents. Unknown hashes
+	/// are not cached, as the code may still become known.
+	validation_code_by_hash: MemoryLruCache<ValidationCodeHash, ResidentSizeOf<ValidationCode>>,
 	candidate_pending_availability:
 		MemoryLruCache<(Hash, ParaId), ResidentSizeOf<Option<CommittedCandidateReceipt>>>,
 	candidate_events: MemoryLruCache<Hash, ResidentSizeOf<Vec<CandidateEvent>>>,
@@ -116,6 +119,7 @@ pub(crate) struct RequestResultCache {
 		(Hash, ParaId, OccupiedCoreAssumption),
 		ResidentSizeOf<Option<ValidationCodeHash>>,
 	>,
+	session_executor_params: MemoryLruCache<SessionIndex, DoesNotAllocate<ExecutorParams>>,
 }
 
 impl Default for RequestResultCache {
@@ -130,7 +134,7 @@ impl Default for RequestResultCache {
 			check_validation_outputs: MemoryLruCache::new(CHECK_VALIDATION_OUTPUTS_CACHE_SIZE),
 			session_index_for_child: MemoryLruCache::new(SESSION_INDEX_FOR_CHILD_CACHE_SIZE),
 			validation_code: MemoryLruCache::new(VALIDATION_CODE_CACHE_SIZE),
-			validation_code_by_hash: MemoryLruCache::new(VALIDATION_CODE_CACHE_SIZE),
+			validation_code_by_hash: MemoryLruCache::new(VALIDATION_CODE_BY_HASH_CACHE_SIZE),
 			candidate_pending_availability: MemoryLruCache::new(
 				CANDIDATE_PENDING_AVAILABILITY_CACHE_SIZE,
 			),
@@ -142,6 +146,7 @@ impl Default for RequestResultCache {
 			on_chain_votes: MemoryLruCache::new(ON_CHAIN_VOTES_CACHE_SIZE),
 			pvfs_require_precheck: MemoryLruCache::new(PVFS_REQUIRE_PRECHECK_SIZE),
 			validation_code_hash: MemoryLruCache::new(VALIDATION_CODE_HASH_CACHE_SIZE),
+			session_executor_params: MemoryLruCache::new(SESSION_EXECUTOR_PARAMS_CACHE_SIZE),
 		}
 	}
 }
@@ -269,15 +274,15 @@ impl RequestResultCache {
 	// but we keep the interface that way to keep the macro simple
 	pub(crate) fn validation_code_by_hash(
 		&mut self,
-		key: (Hash, ValidationCodeHash),
-	) -> Option<&Option<ValidationCode>> {
-		self.validation_code_by_hash.get(&key.1).map(|v| &v.0)
+		key: ValidationCodeHash,
+	) -> Option<&ValidationCode> {
+		self.validation_code_by_hash.get(&key).map(|v| &v.0)
 	}
 
 	pub(crate) fn cache_validation_code_by_hash(
 		&mut self,
 		key: ValidationCodeHash,
-		value: Option<ValidationCode>,
+		value: ValidationCode,
 	) {
 		self.validation_code_by_hash.insert(key, ResidentSizeOf(value));
 	}
@@ -399,6 +404,18 @@ impl RequestResultCache {
 	) {
 		self.validation_code_hash.insert(key, ResidentSizeOf(value));
 	}
+
+	pub(crate) fn session_executor_params(&mut self, key: SessionIndex) -> Option<&ExecutorParams> {
+		self.session_executor_params.get(&key).map(|v| &v.0)
+	}
+
+	pub(crate) fn cache_session_executor_params(
+		&mut self,
+		key: SessionIndex,
+		value: ExecutorParams,
+	) {
+		self.session_executor_params.insert(key, DoesNotAllocate(value));
+	}
 }
 
 pub(crate) enum RequestResult {
@@ -433,4 +450,5 @@ pub(crate) enum RequestResult {
 	// This is a request with side-effects and no result, hence ().
 	SubmitPvfCheckStatement(Hash, PvfCheckStatement, ValidatorSignature, ()),
 	ValidationCodeHash(Hash, ParaId, OccupiedCoreAssumption, Option<ValidationCodeHash>),
+	SessionExecutorParams(Hash, SessionIndex, Option<ExecutorParams>),
 }
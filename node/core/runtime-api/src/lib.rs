@@ -24,12 +24,13 @@
 
 use selendra_node_subsystem_util::metrics::{self, prometheus};
 use selendra_primitives::{
+	executor_params::ExecutorParamsApi,
 	v1::{Block, BlockId, Hash},
 	v2::ParachainHost,
 };
 use selendra_subsystem::{
 	errors::RuntimeApiError,
-	messages::{RuntimeApiMessage, RuntimeApiRequest as Request},
+	messages::{RuntimeApiMessage, RuntimeApiRequest as Request, RuntimeApiRequestClass},
 	overseer, FromOverseer, OverseerSignal, SpawnedSubsystem, SubsystemContext, SubsystemError,
 	SubsystemResult,
 };
@@ -53,21 +54,42 @@ const LOG_TARGET: &str = "parachain::runtime-api";
 /// The number of maximum runtime API requests can be executed in parallel. Further requests will be buffered.
 const MAX_PARALLEL_REQUESTS: usize = 4;
 
+/// The number of maximum non-critical runtime API requests that can be executed in parallel, out
+/// of [`MAX_PARALLEL_REQUESTS`].
+const MAX_PARALLEL_NON_CRITICAL_REQUESTS: usize = 1;
+
+/// The number of maximum non-critical runtime API requests that are buffered. Further requests are
+/// dropped.
+const MAX_WAITING_NON_CRITICAL_REQUESTS: usize = 64;
+
 /// The name of the blocking task that executes a runtime API request.
 const API_REQUEST_TASK_NAME: &str = "selendra-runtime-api-request";
 
+/// A runtime API request waiting to be executed, with the receiver of its result.
+type WaitingRequest =
+	(Pin<Box<dyn Future<Output = ()> + Send>>, oneshot::Receiver<Option<RequestResult>>);
+
+/// The class and result of an executed runtime API request.
+type ActiveRequestOutput =
+	(RuntimeApiRequestClass, Result<Option<RequestResult>, oneshot::Canceled>);
+
+/// A runtime API request being executed.
+type ActiveRequest = Pin<Box<dyn Future<Output = ActiveRequestOutput> + Send>>;
+
 /// The `RuntimeApiSubsystem`. See module docs for more details.
 pub struct RuntimeApiSubsystem<Client> {
 	client: Arc<Client>,
 	metrics: Metrics,
 	spawn_handle: Box<dyn SpawnNamed>,
 	/// If there are [`MAX_PARALLEL_REQUESTS`] requests being executed, we buffer them in here until they can be executed.
-	waiting_requests: VecDeque<(
-		Pin<Box<dyn Future<Output = ()> + Send>>,
-		oneshot::Receiver<Option<RequestResult>>,
-	)>,
+	waiting_requests: VecDeque<WaitingRequest>,
+	/// Non-critical requests are buffered in here until they can be executed without delaying
+	/// consensus-critical ones.
+	waiting_non_critical_requests: VecDeque<WaitingRequest>,
 	/// All the active runtime API requests that are currently being executed.
-	active_requests: FuturesUnordered<oneshot::Receiver<Option<RequestResult>>>,
+	active_requests: FuturesUnordered<ActiveRequest>,
+	/// The number of non-critical requests in `active_requests`.
+	active_non_critical_requests: usize,
 	/// Requests results cache
 	requests_cache: RequestResultCache,
 }
@@ -84,7 +106,9 @@ impl<Client> RuntimeApiSubsystem<Client> {
 			metrics,
 			spawn_handle: Box::new(spawn_handle),
 			waiting_requests: Default::default(),
+			waiting_non_critical_requests: Default::default(),
 			active_requests: Default::default(),
+			active_non_critical_requests: 0,
 			requests_cache: RequestResultCache::default(),
 		}
 	}
@@ -93,7 +117,10 @@ impl<Client> RuntimeApiSubsystem<Client> {
 impl<Client, Context> overseer::Subsystem<Context, SubsystemError> for RuntimeApiSubsystem<Client>
 where
 	Client: ProvideRuntimeApi<Block> + Send + 'static + Sync,
-	Client::Api: ParachainHost<Block> + BabeApi<Block> + AuthorityDiscoveryApi<Block>,
+	Client::Api: ParachainHost<Block>
+		+ BabeApi<Block>
+		+ AuthorityDiscoveryApi<Block>
+		+ ExecutorParamsApi<Block>,
 	Context: SubsystemContext<Message = RuntimeApiMessage>,
 	Context: overseer::SubsystemContext<Message = RuntimeApiMessage>,
 {
@@ -105,7 +132,10 @@ where
 impl<Client> RuntimeApiSubsystem<Client>
 where
 	Client: ProvideRuntimeApi<Block> + Send + 'static + Sync,
-	Client::Api: ParachainHost<Block> + BabeApi<Block> + AuthorityDiscoveryApi<Block>,
+	Client::Api: ParachainHost<Block>
+		+ BabeApi<Block>
+		+ AuthorityDiscoveryApi<Block>
+		+ ExecutorParamsApi<Block>,
 {
 	fn store_cache(&mut self, result: RequestResult) {
 		use RequestResult::*;
@@ -136,11 +166,18 @@ where
 				.cache_check_validation_outputs((relay_parent, para_id, commitments), b),
 			SessionIndexForChild(relay_parent, session_index) =>
 				self.requests_cache.cache_session_index_for_child(relay_parent, session_index),
-			ValidationCode(relay_parent, para_id, assumption, code) => self
-				.requests_cache
-				.cache_validation_code((relay_parent, para_id, assumption), code),
+			ValidationCode(relay_parent, para_id, assumption, code) => {
+				// Spare fetching the same code by its hash later on.
+				if let Some(ref code) = code {
+					self.requests_cache.cache_validation_code_by_hash(code.hash(), code.clone());
+				}
+				self.requests_cache
+					.cache_validation_code((relay_parent, para_id, assumption), code)
+			},
 			ValidationCodeByHash(_relay_parent, validation_code_hash, code) =>
-				self.requests_cache.cache_validation_code_by_hash(validation_code_hash, code),
+				if let Some(code) = code {
+					self.requests_cache.cache_validation_code_by_hash(validation_code_hash, code);
+				},
 			CandidatePendingAvailability(relay_parent, para_id, candidate) => self
 				.requests_cache
 				.cache_candidate_pending_availability((relay_parent, para_id), candidate),
@@ -165,6 +202,10 @@ where
 			ValidationCodeHash(relay_parent, para_id, assumption, hash) => self
 				.requests_cache
 				.cache_validation_code_hash((relay_parent, para_id, assumption), hash),
+			SessionExecutorParams(_relay_parent, session_index, params) =>
+				if let Some(params) = params {
+					self.requests_cache.cache_session_executor_params(session_index, params);
+				},
 		}
 	}
 
@@ -229,9 +270,19 @@ where
 			Request::ValidationCode(para, assumption, sender) =>
 				query!(validation_code(para, assumption), sender)
 					.map(|sender| Request::ValidationCode(para, assumption, sender)),
-			Request::ValidationCodeByHash(validation_code_hash, sender) =>
-				query!(validation_code_by_hash(validation_code_hash), sender)
-					.map(|sender| Request::ValidationCodeByHash(validation_code_hash, sender)),
+			Request::ValidationCodeByHash(validation_code_hash, sender) => {
+				if let Some(code) =
+					self.requests_cache.validation_code_by_hash(validation_code_hash)
+				{
+					self.metrics.on_cached_request();
+					self.metrics.on_validation_code_cache_lookup(true);
+					let _ = sender.send(Ok(Some(code.clone())));
+					None
+				} else {
+					self.metrics.on_validation_code_cache_lookup(false);
+					Some(Request::ValidationCodeByHash(validation_code_hash, sender))
+				}
+			},
 			Request::CandidatePendingAvailability(para, sender) =>
 				query!(candidate_pending_availability(para), sender)
 					.map(|sender| Request::CandidatePendingAvailability(para, sender)),
@@ -264,13 +315,29 @@ where
 			Request::ValidationCodeHash(para, assumption, sender) =>
 				query!(validation_code_hash(para, assumption), sender)
 					.map(|sender| Request::ValidationCodeHash(para, assumption, sender)),
+			Request::SessionExecutorParams(index, sender) => {
+				if let Some(params) = self.requests_cache.session_executor_params(index) {
+					self.metrics.on_cached_request();
+					let _ = sender.send(Ok(Some(*params)));
+					None
+				} else {
+					Some(Request::SessionExecutorParams(index, sender))
+				}
+			},
 		}
 	}
 
 	/// Spawn a runtime API request.
 	///
 	/// If there are already [`MAX_PARALLEL_REQUESTS`] requests being executed, the request will be buffered.
-	fn spawn_request(&mut self, relay_parent: Hash, request: Request) {
+	/// Non-critical requests are buffered as well while consensus-critical ones are waiting, or
+	/// [`MAX_PARALLEL_NON_CRITICAL_REQUESTS`] of them are being executed.
+	fn spawn_request(
+		&mut self,
+		relay_parent: Hash,
+		request: Request,
+		class: RuntimeApiRequestClass,
+	) {
 		let client = self.client.clone();
 		let metrics = self.metrics.clone();
 		let (sender, receiver) = oneshot::channel();
@@ -286,21 +353,59 @@ where
 		}
 		.boxed();
 
-		if self.active_requests.len() >= MAX_PARALLEL_REQUESTS {
-			self.waiting_requests.push_back((request, receiver));
+		match class {
+			RuntimeApiRequestClass::ConsensusCritical =>
+				if self.active_requests.len() >= MAX_PARALLEL_REQUESTS {
+					self.waiting_requests.push_back((request, receiver));
+
+					if self.waiting_requests.len() > MAX_PARALLEL_REQUESTS * 10 {
+						tracing::warn!(
+							target: LOG_TARGET,
+							"{} runtime API requests waiting to be executed.",
+							self.waiting_requests.len(),
+						)
+					}
+				} else {
+					self.spawn_active(request, receiver, class);
+				},
+			RuntimeApiRequestClass::NonCritical =>
+				if self.can_spawn_non_critical() {
+					self.spawn_active(request, receiver, class);
+				} else if self.waiting_non_critical_requests.len() >=
+					MAX_WAITING_NON_CRITICAL_REQUESTS
+				{
+					tracing::debug!(
+						target: LOG_TARGET,
+						"Dropping non-critical runtime API request, {} are waiting already.",
+						self.waiting_non_critical_requests.len(),
+					);
+					self.metrics.on_dropped_request();
+				} else {
+					self.waiting_non_critical_requests.push_back((request, receiver));
+				},
+		}
+	}
 
-			if self.waiting_requests.len() > MAX_PARALLEL_REQUESTS * 10 {
-				tracing::warn!(
-					target: LOG_TARGET,
-					"{} runtime API requests waiting to be executed.",
-					self.waiting_requests.len(),
-				)
-			}
-		} else {
-			self.spawn_handle
-				.spawn_blocking(API_REQUEST_TASK_NAME, Some("runtime-api"), request);
-			self.active_requests.push(receiver);
+	/// Whether a non-critical request can be executed right away.
+	fn can_spawn_non_critical(&self) -> bool {
+		self.active_requests.len() < MAX_PARALLEL_REQUESTS &&
+			self.waiting_requests.is_empty() &&
+			self.active_non_critical_requests < MAX_PARALLEL_NON_CRITICAL_REQUESTS
+	}
+
+	/// Execute a request and track it as active.
+	fn spawn_active(
+		&mut self,
+		request: Pin<Box<dyn Future<Output = ()> + Send>>,
+		receiver: oneshot::Receiver<Option<RequestResult>>,
+		class: RuntimeApiRequestClass,
+	) {
+		if class == RuntimeApiRequestClass::NonCritical {
+			self.active_non_critical_requests += 1;
 		}
+		self.spawn_handle
+			.spawn_blocking(API_REQUEST_TASK_NAME, Some("runtime-api"), request);
+		self.active_requests.push(receiver.map(move |result| (class, result)).boxed());
 	}
 
 	/// Poll the active runtime API requests.
@@ -311,14 +416,25 @@ where
 		}
 
 		// If there are active requests, this will always resolve to `Some(_)` when a request is finished.
-		if let Some(Ok(Some(result))) = self.active_requests.next().await {
-			self.store_cache(result);
+		if let Some((class, result)) = self.active_requests.next().await {
+			if class == RuntimeApiRequestClass::NonCritical {
+				self.active_non_critical_requests -= 1;
+			}
+			if let Ok(Some(result)) = result {
+				self.store_cache(result);
+			}
 		}
 
-		if let Some((req, recv)) = self.waiting_requests.pop_front() {
-			self.spawn_handle
-				.spawn_blocking(API_REQUEST_TASK_NAME, Some("runtime-api"), req);
-			self.active_requests.push(recv);
+		if self.active_requests.len() < MAX_PARALLEL_REQUESTS {
+			if let Some((req, recv)) = self.waiting_requests.pop_front() {
+				self.spawn_active(req, recv, RuntimeApiRequestClass::ConsensusCritical);
+			}
+		}
+
+		if self.can_spawn_non_critical() {
+			if let Some((req, recv)) = self.waiting_non_critical_requests.pop_front() {
+				self.spawn_active(req, recv, RuntimeApiRequestClass::NonCritical);
+			}
 		}
 	}
 }
@@ -329,7 +445,10 @@ async fn run<Client, Context>(
 ) -> SubsystemResult<()>
 where
 	Client: ProvideRuntimeApi<Block> + Send + Sync + 'static,
-	Client::Api: ParachainHost<Block> + BabeApi<Block> + AuthorityDiscoveryApi<Block>,
+	Client::Api: ParachainHost<Block>
+		+ BabeApi<Block>
+		+ AuthorityDiscoveryApi<Block>
+		+ ExecutorParamsApi<Block>,
 	Context: SubsystemContext<Message = RuntimeApiMessage>,
 	Context: overseer::SubsystemContext<Message = RuntimeApiMessage>,
 {
@@ -339,11 +458,15 @@ where
 				FromOverseer::Signal(OverseerSignal::Conclude) => return Ok(()),
 				FromOverseer::Signal(OverseerSignal::ActiveLeaves(_)) => {},
 				FromOverseer::Signal(OverseerSignal::BlockFinalized(..)) => {},
-				FromOverseer::Communication { msg } => match msg {
-					RuntimeApiMessage::Request(relay_parent, request) => {
-						subsystem.spawn_request(relay_parent, request);
-					},
-				}
+				FromOverseer::Communication { msg } => {
+					let class = msg.class();
+					match msg {
+						RuntimeApiMessage::Request(relay_parent, request) |
+						RuntimeApiMessage::NonCriticalRequest(relay_parent, request) => {
+							subsystem.spawn_request(relay_parent, request, class);
+						},
+					}
+				},
 			},
 			_ = subsystem.poll_requests().fuse() => {},
 		}
@@ -358,7 +481,10 @@ fn make_runtime_api_request<Client>(
 ) -> Option<RequestResult>
 where
 	Client: ProvideRuntimeApi<Block>,
-	Client::Api: ParachainHost<Block> + BabeApi<Block> + AuthorityDiscoveryApi<Block>,
+	Client::Api: ParachainHost<Block>
+		+ BabeApi<Block>
+		+ AuthorityDiscoveryApi<Block>
+		+ ExecutorParamsApi<Block>,
 {
 	let _timer = metrics.time_make_runtime_api_request();
 
@@ -503,6 +629,29 @@ where
 		},
 		Request::ValidationCodeHash(para, assumption, sender) =>
 			query!(ValidationCodeHash, validation_code_hash(para, assumption), ver = 2, sender),
+		Request::SessionExecutorParams(index, sender) => {
+			use sp_api::ApiExt;
+
+			let api = client.runtime_api();
+			let block_id = BlockId::Hash(relay_parent);
+
+			let res = match api.has_api::<dyn ExecutorParamsApi<Block>>(&block_id) {
+				Ok(true) => api.session_executor_params(&block_id, index).map_err(|e| {
+					RuntimeApiError::Execution {
+						runtime_api_name: "session_executor_params",
+						source: std::sync::Arc::new(e),
+					}
+				}),
+				_ => Err(RuntimeApiError::NotSupported {
+					runtime_api_name: "session_executor_params",
+				}),
+			};
+			metrics.on_request(res.is_ok());
+			let _ = sender.send(res.clone());
+
+			res.ok()
+				.map(|res| RequestResult::SessionExecutorParams(relay_parent, index, res))
+		},
 	}
 }
 
@@ -510,6 +659,7 @@ where
 struct MetricsInner {
 	chain_api_requests: prometheus::CounterVec<prometheus::U64>,
 	make_runtime_api_request: prometheus::Histogram,
+	validation_code_cache: prometheus::CounterVec<prometheus::U64>,
 }
 
 /// Runtime API metrics.
@@ -533,6 +683,19 @@ impl Metrics {
 			.map(|metrics| metrics.chain_api_requests.with_label_values(&["cached"]).inc());
 	}
 
+	fn on_dropped_request(&self) {
+		self.0
+			.as_ref()
+			.map(|metrics| metrics.chain_api_requests.with_label_values(&["dropped"]).inc());
+	}
+
+	fn on_validation_code_cache_lookup(&self, hit: bool) {
+		if let Some(metrics) = &self.0 {
+			let label = if hit { "hit" } else { "miss" };
+			metrics.validation_code_cache.with_label_values(&[label]).inc();
+		}
+	}
+
 	/// Provide a timer for `make_runtime_api_request` which observes on drop.
 	fn time_make_runtime_api_request(
 		&self,
@@ -561,6 +724,16 @@ impl metrics::Metrics for Metrics {
 				))?,
 				registry,
 			)?,
+			validation_code_cache: prometheus::register(
+				prometheus::CounterVec::new(
+					prometheus::Opts::new(
+						"selendra_parachain_runtime_api_validation_code_cache_total",
+						"Number of validation code by hash lookups in the cache.",
+					),
+					&["result"],
+				)?,
+				registry,
+			)?,
 		};
 		Ok(Metrics(Some(metrics)))
 	}
@@ -22,7 +22,7 @@ use selendra_node_primitives::{BabeAllowedSlots, BabeEpoch, BabeEpochConfigurati
 use selendra_node_subsystem_test_helpers::make_subsystem_context;
 use selendra_primitives::{
 	v1::{
-		AuthorityDiscoveryId, CandidateEvent, CommittedCandidateReceipt, CoreState,
+		AuthorityDiscoveryId, CandidateEvent, CommittedCandidateReceipt, CoreState, ExecutorParams,
 		GroupRotationInfo, Id as ParaId, InboundDownwardMessage, InboundHrmpMessage,
 		OccupiedCoreAssumption, PersistedValidationData, ScrapedOnChainVotes, SessionIndex,
 		ValidationCode, ValidationCodeHash, ValidatorId, ValidatorIndex, ValidatorSignature,
@@ -57,6 +57,7 @@ struct MockRuntimeApi {
 	submitted_pvf_check_statement: Arc<Mutex<Vec<(PvfCheckStatement, ValidatorSignature)>>>,
 	pvfs_require_precheck: Vec<ValidationCodeHash>,
 	validation_code_hash: HashMap<ParaId, ValidationCodeHash>,
+	session_executor_params: HashMap<SessionIndex, ExecutorParams>,
 }
 
 impl ProvideRuntimeApi<Block> for MockRuntimeApi {
@@ -231,6 +232,12 @@ sp_api::mock_impl_runtime_apis! {
 			self.authorities.clone()
 		}
 	}
+
+	impl ExecutorParamsApi<Block> for MockRuntimeApi {
+		fn session_executor_params(&self, index: SessionIndex) -> Option<ExecutorParams> {
+			self.session_executor_params.get(&index).cloned()
+		}
+	}
 }
 
 #[test]
@@ -830,6 +837,42 @@ fn requests_validation_code_by_hash() {
 	futures::executor::block_on(future::join(subsystem_task, test_task));
 }
 
+#[test]
+fn validation_code_is_cached_by_hash() {
+	let mut subsystem = RuntimeApiSubsystem::new(
+		Arc::new(MockRuntimeApi::default()),
+		Metrics(None),
+		sp_core::testing::TaskExecutor::new(),
+	);
+	let relay_parent = [1; 32].into();
+	let para = 5.into();
+	let code = ValidationCode::from(vec![1; 32]);
+	let unknown_code = ValidationCode::from(vec![2; 32]);
+
+	// Unknown code is not cached, as it may still become known.
+	subsystem.store_cache(cache::RequestResult::ValidationCodeByHash(
+		relay_parent,
+		unknown_code.hash(),
+		None,
+	));
+	assert!(subsystem.requests_cache.validation_code_by_hash(unknown_code.hash()).is_none());
+
+	// Code fetched for a para can be served by its hash afterwards.
+	subsystem.store_cache(cache::RequestResult::ValidationCode(
+		relay_parent,
+		para,
+		OccupiedCoreAssumption::Included,
+		Some(code.clone()),
+	));
+	assert_eq!(subsystem.requests_cache.validation_code_by_hash(code.hash()), Some(&code));
+
+	let (tx, mut rx) = oneshot::channel();
+	assert!(subsystem
+		.query_cache(relay_parent, Request::ValidationCodeByHash(code.hash(), tx))
+		.is_none());
+	assert_eq!(rx.try_recv().unwrap().unwrap().unwrap(), Some(code));
+}
+
 #[test]
 fn multiple_requests_in_parallel_are_working() {
 	let (ctx, mut ctx_handle) = make_subsystem_context(TaskExecutor::new());
@@ -872,6 +915,64 @@ fn multiple_requests_in_parallel_are_working() {
 	futures::executor::block_on(future::join(subsystem_task, test_task));
 }
 
+#[test]
+fn non_critical_requests_do_not_starve_critical_ones() {
+	let (ctx, mut ctx_handle) = make_subsystem_context(TaskExecutor::new());
+	let runtime_api = Arc::new(MockRuntimeApi::default());
+	let relay_parent = [1; 32].into();
+	let spawner = sp_core::testing::TaskExecutor::new();
+	let mutex = runtime_api.availability_cores_wait.clone();
+
+	let subsystem = RuntimeApiSubsystem::new(runtime_api.clone(), Metrics(None), spawner);
+	let subsystem_task = run(ctx, subsystem).map(|x| x.unwrap());
+	let test_task = async move {
+		// Make all non-critical requests block until we release this mutex.
+		let lock = mutex.lock().unwrap();
+
+		let mut receivers = Vec::new();
+
+		// One is executed, the following are buffered and the last ones are dropped.
+		for _ in 0..MAX_PARALLEL_NON_CRITICAL_REQUESTS + MAX_WAITING_NON_CRITICAL_REQUESTS + 5 {
+			let (tx, rx) = oneshot::channel();
+
+			ctx_handle
+				.send(FromOverseer::Communication {
+					msg: RuntimeApiMessage::NonCriticalRequest(
+						relay_parent,
+						Request::AvailabilityCores(tx),
+					),
+				})
+				.await;
+
+			receivers.push(rx);
+		}
+
+		let (tx, rx) = oneshot::channel();
+		ctx_handle
+			.send(FromOverseer::Communication {
+				msg: RuntimeApiMessage::Request(relay_parent, Request::Authorities(tx)),
+			})
+			.await;
+
+		// Served while the non-critical requests are still blocked.
+		assert_eq!(rx.await.unwrap().unwrap(), runtime_api.authorities);
+
+		drop(lock);
+
+		let results = future::join_all(receivers).await;
+		let served = results.iter().filter(|r| r.is_ok()).count();
+		assert_eq!(served, MAX_PARALLEL_NON_CRITICAL_REQUESTS + MAX_WAITING_NON_CRITICAL_REQUESTS);
+		results
+			.into_iter()
+			.flatten()
+			.for_each(|r| assert_eq!(r.unwrap(), runtime_api.availability_cores));
+
+		ctx_handle.send(FromOverseer::Signal(OverseerSignal::Conclude)).await;
+	};
+
+	futures::executor::block_on(future::join(subsystem_task, test_task));
+}
+
 #[test]
 fn requests_babe_epoch() {
 	let (ctx, mut ctx_handle) = make_subsystem_context(TaskExecutor::new());
@@ -1044,3 +1145,49 @@ fn requests_validation_code_hash() {
 
 	futures::executor::block_on(future::join(subsystem_task, test_task));
 }
+
+#[test]
+fn requests_session_executor_params() {
+	let (ctx, mut ctx_handle) = make_subsystem_context(TaskExecutor::new());
+	let mut runtime_api = MockRuntimeApi::default();
+	let session_index = 1;
+	let params = ExecutorParams { heap_pages: 1024, ..Default::default() };
+	runtime_api.session_executor_params.insert(session_index, params);
+	let runtime_api = Arc::new(runtime_api);
+	let spawner = sp_core::testing::TaskExecutor::new();
+
+	let relay_parent = [1; 32].into();
+
+	let subsystem = RuntimeApiSubsystem::new(runtime_api.clone(), Metrics(None), spawner);
+	let subsystem_task = run(ctx, subsystem).map(|x| x.unwrap());
+	let test_task = async move {
+		let (tx, rx) = oneshot::channel();
+
+		ctx_handle
+			.send(FromOverseer::Communication {
+				msg: RuntimeApiMessage::Request(
+					relay_parent,
+					Request::SessionExecutorParams(session_index, tx),
+				),
+			})
+			.await;
+
+		assert_eq!(rx.await.unwrap().unwrap(), Some(params));
+
+		let (tx, rx) = oneshot::channel();
+		ctx_handle
+			.send(FromOverseer::Communication {
+				msg: RuntimeApiMessage::Request(
+					relay_parent,
+					Request::SessionExecutorParams(session_index + 1, tx),
+				),
+			})
+			.await;
+
+		assert_eq!(rx.await.unwrap().unwrap(), None);
+
+		ctx_handle.send(FromOverseer::Signal(OverseerSignal::Conclude)).await;
+	};
+
+	futures::executor::block_on(future::join(subsystem_task, test_task));
+}
@@ -22,8 +22,8 @@
 use selendra_cli::{
 	prepared_overseer_builder,
 	service::{
-		AuthorityDiscoveryApi, AuxStore, BabeApi, Block, Error, HeaderBackend, Overseer,
-		OverseerConnector, OverseerGen, OverseerGenArgs, OverseerHandle, ParachainHost,
+		AuthorityDiscoveryApi, AuxStore, BabeApi, Block, Error, ExecutorParamsApi, HeaderBackend,
+		Overseer, OverseerConnector, OverseerGen, OverseerGenArgs, OverseerHandle, ParachainHost,
 		ProvideRuntimeApi, SpawnNamed,
 	},
 };
@@ -202,7 +202,10 @@ impl OverseerGen for BackGarbageCandidate {
 	) -> Result<(Overseer<Spawner, Arc<RuntimeClient>>, OverseerHandle), Error>
 	where
 		RuntimeClient: 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block> + AuxStore,
-		RuntimeClient::Api: ParachainHost<Block> + BabeApi<Block> + AuthorityDiscoveryApi<Block>,
+		RuntimeClient::Api: ParachainHost<Block>
+			+ BabeApi<Block>
+			+ AuthorityDiscoveryApi<Block>
+			+ ExecutorParamsApi<Block>,
 		Spawner: 'static + SpawnNamed + Clone + Unpin,
 	{
 		let candidate_validation_config = args.candidate_validation_config.clone();
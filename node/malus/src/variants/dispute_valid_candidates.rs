@@ -24,8 +24,8 @@
 use selendra_cli::{
 	prepared_overseer_builder,
 	service::{
-		AuthorityDiscoveryApi, AuxStore, BabeApi, Block, Error, HeaderBackend, Overseer,
-		OverseerConnector, OverseerGen, OverseerGenArgs, OverseerHandle, ParachainHost,
+		AuthorityDiscoveryApi, AuxStore, BabeApi, Block, Error, ExecutorParamsApi, HeaderBackend,
+		Overseer, OverseerConnector, OverseerGen, OverseerGenArgs, OverseerHandle, ParachainHost,
 		ProvideRuntimeApi, SpawnNamed,
 	},
 };
@@ -101,17 +101,25 @@ impl OverseerGen for DisputeValidCandidates {
 	) -> Result<(Overseer<Spawner, Arc<RuntimeClient>>, OverseerHandle), Error>
 	where
 		RuntimeClient: 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block> + AuxStore,
-		RuntimeClient::Api: ParachainHost<Block> + BabeApi<Block> + AuthorityDiscoveryApi<Block>,
+		RuntimeClient::Api: ParachainHost<Block>
+			+ BabeApi<Block>
+			+ AuthorityDiscoveryApi<Block>
+			+ ExecutorParamsApi<Block>,
 		Spawner: 'static + SpawnNamed + Clone + Unpin,
 	{
 		let spawner = args.spawner.clone();
 		let crypto_store_ptr = args.keystore.clone() as SyncCryptoStorePtr;
+		let memory_budget = args.memory_budget.clone();
 		let filter = ReplaceApprovalsWithDisputes;
 
 		prepared_overseer_builder(args)?
 			.replace_candidate_backing(move |cb| {
 				InterceptedSubsystem::new(
-					CandidateBackingSubsystem::new(spawner, crypto_store_ptr, cb.params.metrics),
+					CandidateBackingSubsystem::new(
+						spawner,
+						(crypto_store_ptr, memory_budget),
+						cb.params.metrics,
+					),
 					filter,
 				)
 			})
@@ -25,8 +25,8 @@
 use selendra_cli::{
 	prepared_overseer_builder,
 	service::{
-		AuthorityDiscoveryApi, AuxStore, BabeApi, Block, Error, HeaderBackend, Overseer,
-		OverseerConnector, OverseerGen, OverseerGenArgs, OverseerHandle, ParachainHost,
+		AuthorityDiscoveryApi, AuxStore, BabeApi, Block, Error, ExecutorParamsApi, HeaderBackend,
+		Overseer, OverseerConnector, OverseerGen, OverseerGenArgs, OverseerHandle, ParachainHost,
 		ProvideRuntimeApi, SpawnNamed,
 	},
 };
@@ -104,12 +104,16 @@ impl OverseerGen for SuggestGarbageCandidate {
 	) -> Result<(Overseer<Spawner, Arc<RuntimeClient>>, OverseerHandle), Error>
 	where
 		RuntimeClient: 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block> + AuxStore,
-		RuntimeClient::Api: ParachainHost<Block> + BabeApi<Block> + AuthorityDiscoveryApi<Block>,
+		RuntimeClient::Api: ParachainHost<Block>
+			+ BabeApi<Block>
+			+ AuthorityDiscoveryApi<Block>
+			+ ExecutorParamsApi<Block>,
 		Spawner: 'static + SpawnNamed + Clone + Unpin,
 	{
 		let spawner = args.spawner.clone();
 		let (sink, source) = metered::unbounded();
 		let keystore = args.keystore.clone() as SyncCryptoStorePtr;
+		let memory_budget = args.memory_budget.clone();
 
 		let filter = ReplacePoVBytes { queue: sink };
 
@@ -119,7 +123,11 @@ impl OverseerGen for SuggestGarbageCandidate {
 		let result = prepared_overseer_builder(args)?
 			.replace_candidate_backing(move |cb| {
 				InterceptedSubsystem::new(
-					CandidateBackingSubsystem::new(spawner2, keystore2, cb.params.metrics),
+					CandidateBackingSubsystem::new(
+						spawner2,
+						(keystore2, memory_budget),
+						cb.params.metrics,
+					),
 					filter,
 				)
 			})
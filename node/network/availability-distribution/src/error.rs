@@ -39,6 +39,10 @@ pub enum Error {
 	#[error("Erasure chunk requester stream exhausted")]
 	RequesterExhausted,
 
+	#[fatal]
+	#[error("Erasure chunk sampler stream exhausted")]
+	SamplerExhausted,
+
 	#[fatal]
 	#[error("Receive channel closed: {0}")]
 	IncomingMessageChannel(#[source] SubsystemError),
@@ -82,6 +86,9 @@ pub enum Error {
 
 	#[error("Given validator index could not be found in current session")]
 	InvalidValidatorIndex,
+
+	#[error("Relay parent has been shed for exceeding the memory budget")]
+	RelayParentShed,
 }
 
 /// General result abbreviation type alias.
@@ -105,6 +112,7 @@ pub fn log_error(result: Result<()>, ctx: &'static str) -> std::result::Result<(
 				JfyiError::FetchPoV(_) |
 				JfyiError::SendResponse |
 				JfyiError::NoSuchPoV |
+				JfyiError::RelayParentShed |
 				JfyiError::Runtime(_) => tracing::debug!(target: LOG_TARGET, error = ?jfyi, ctx),
 			}
 			Ok(())
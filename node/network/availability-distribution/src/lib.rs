@@ -20,7 +20,7 @@ use sp_keystore::SyncCryptoStorePtr;
 
 use selendra_node_network_protocol::request_response::{v1, IncomingRequestReceiver};
 use selendra_subsystem::{
-	messages::AvailabilityDistributionMessage, overseer, FromOverseer, OverseerSignal,
+	messages::AvailabilityDistributionMessage, overseer, FromOverseer, LeafStatus, OverseerSignal,
 	SpawnedSubsystem, SubsystemContext, SubsystemError,
 };
 
@@ -28,7 +28,7 @@ use selendra_subsystem::{
 mod error;
 use error::{log_error, FatalError, Result};
 
-use selendra_node_subsystem_util::runtime::RuntimeInfo;
+use selendra_node_subsystem_util::{memory_budget::MemoryBudget, runtime::RuntimeInfo};
 
 /// `Requester` taking care of requesting chunks for candidates pending availability.
 mod requester;
@@ -37,6 +37,10 @@ use requester::Requester;
 /// Handing requests for PoVs during backing.
 mod pov_requester;
 
+/// Sampling chunks of included candidates from other validators.
+mod sampler;
+use sampler::Sampler;
+
 /// Responding to erasure chunk requests:
 mod responder;
 use responder::{run_chunk_receiver, run_pov_receiver};
@@ -56,6 +60,8 @@ pub struct AvailabilityDistributionSubsystem {
 	runtime: RuntimeInfo,
 	/// Receivers to receive messages from.
 	recvs: IncomingRequestReceivers,
+	/// Budget for the chunks and PoVs held in flight.
+	budget: MemoryBudget,
 	/// Prometheus metrics.
 	metrics: Metrics,
 }
@@ -88,10 +94,11 @@ impl AvailabilityDistributionSubsystem {
 	pub fn new(
 		keystore: SyncCryptoStorePtr,
 		recvs: IncomingRequestReceivers,
+		budget: MemoryBudget,
 		metrics: Metrics,
 	) -> Self {
 		let runtime = RuntimeInfo::new(Some(keystore));
-		Self { runtime, recvs, metrics }
+		Self { runtime, recvs, budget, metrics }
 	}
 
 	/// Start processing work as passed on from the Overseer.
@@ -100,10 +107,11 @@ impl AvailabilityDistributionSubsystem {
 		Context: SubsystemContext<Message = AvailabilityDistributionMessage>,
 		Context: overseer::SubsystemContext<Message = AvailabilityDistributionMessage>,
 	{
-		let Self { mut runtime, recvs, metrics } = self;
+		let Self { mut runtime, recvs, budget, metrics } = self;
 
 		let IncomingRequestReceivers { pov_req_receiver, chunk_req_receiver } = recvs;
-		let mut requester = Requester::new(metrics.clone()).fuse();
+		let mut requester = Requester::new(budget.clone(), metrics.clone()).fuse();
+		let mut sampler = Sampler::new(metrics.clone()).fuse();
 
 		{
 			let sender = ctx.sender().clone();
@@ -125,7 +133,8 @@ impl AvailabilityDistributionSubsystem {
 				let mut subsystem_next = ctx.recv().fuse();
 				futures::select! {
 					subsystem_msg = subsystem_next => Either::Left(subsystem_msg),
-					from_task = requester.next() => Either::Right(from_task),
+					from_task = requester.next() => Either::Right(Either::Left(from_task)),
+					sampled = sampler.next() => Either::Right(Either::Right(sampled)),
 				}
 			};
 
@@ -133,14 +142,23 @@ impl AvailabilityDistributionSubsystem {
 			let message = match action {
 				Either::Left(subsystem_msg) =>
 					subsystem_msg.map_err(|e| FatalError::IncomingMessageChannel(e))?,
-				Either::Right(from_task) => {
+				Either::Right(Either::Left(from_task)) => {
 					let from_task = from_task.ok_or(FatalError::RequesterExhausted)?;
 					ctx.send_message(from_task).await;
 					continue
 				},
+				Either::Right(Either::Right(sampled)) => {
+					sampled.ok_or(FatalError::SamplerExhausted)?;
+					continue
+				},
 			};
 			match message {
 				FromOverseer::Signal(OverseerSignal::ActiveLeaves(update)) => {
+					let fresh_leaf = update
+						.activated
+						.as_ref()
+						.filter(|leaf| leaf.status == LeafStatus::Fresh)
+						.map(|leaf| leaf.hash);
 					log_error(
 						requester
 							.get_mut()
@@ -148,6 +166,12 @@ impl AvailabilityDistributionSubsystem {
 							.await,
 						"Error in Requester::update_fetching_heads",
 					)?;
+					if let Some(leaf) = fresh_leaf {
+						log_error(
+							sampler.get_mut().sample_leaf(&mut ctx, &mut runtime, leaf).await,
+							"Error in Sampler::sample_leaf",
+						)?;
+					}
 				},
 				FromOverseer::Signal(OverseerSignal::BlockFinalized(..)) => {},
 				FromOverseer::Signal(OverseerSignal::Conclude) => return Ok(()),
@@ -170,6 +194,7 @@ impl AvailabilityDistributionSubsystem {
 							candidate_hash,
 							pov_hash,
 							tx,
+							&budget,
 							metrics.clone(),
 						)
 						.await,
@@ -31,6 +31,9 @@ pub const FAILED: &'static str = "failed";
 /// Label for chunks/PoVs that could not be served, because they were not available.
 pub const NOT_FOUND: &'static str = "not-found";
 
+/// Label for sampled chunks, for which the validator did not respond at all.
+pub const NO_RESPONSE: &'static str = "no-response";
+
 /// Availability Distribution metrics.
 #[derive(Clone, Default)]
 pub struct Metrics(Option<MetricsInner>);
@@ -56,6 +59,12 @@ struct MetricsInner {
 	/// Number of times our first set of validators did not provide the needed chunk and we had to
 	/// query further validators.
 	retries: Counter<U64>,
+
+	/// Number of chunks sampled from other validators.
+	sampled_chunks: CounterVec<U64>,
+
+	/// Number of times a validator reached the threshold of failed samples within a session.
+	unreliable_validators: Counter<U64>,
 }
 
 impl Metrics {
@@ -98,6 +107,20 @@ impl Metrics {
 			metrics.retries.inc()
 		}
 	}
+
+	/// Increment counter on sampled chunks.
+	pub fn on_sampled_chunk(&self, label: &'static str) {
+		if let Some(metrics) = &self.0 {
+			metrics.sampled_chunks.with_label_values(&[label]).inc()
+		}
+	}
+
+	/// Increment counter of validators failing too many samples.
+	pub fn on_unreliable_validator(&self) {
+		if let Some(metrics) = &self.0 {
+			metrics.unreliable_validators.inc()
+		}
+	}
 }
 
 impl metrics::Metrics for Metrics {
@@ -150,6 +173,23 @@ impl metrics::Metrics for Metrics {
 				)?,
 				registry,
 			)?,
+			sampled_chunks: prometheus::register(
+				CounterVec::new(
+					Opts::new(
+						"selendra_parachain_sampled_chunks_total",
+						"Total number of chunks sampled from other validators.",
+					),
+					&["success"]
+				)?,
+				registry,
+			)?,
+			unreliable_validators: prometheus::register(
+				Counter::new(
+					"selendra_parachain_unreliable_validators_total",
+					"Number of times a validator repeatedly failed to provide sampled chunks within a session.",
+				)?,
+				registry,
+			)?,
 		};
 		Ok(Metrics(Some(metrics)))
 	}
@@ -24,7 +24,7 @@ use selendra_node_network_protocol::request_response::{
 	OutgoingRequest, Recipient,
 };
 use selendra_node_primitives::PoV;
-use selendra_node_subsystem_util::runtime::RuntimeInfo;
+use selendra_node_subsystem_util::{memory_budget::MemoryBudget, runtime::RuntimeInfo};
 use selendra_primitives::v1::{AuthorityDiscoveryId, CandidateHash, Hash, ValidatorIndex};
 use selendra_subsystem::{
 	jaeger,
@@ -39,6 +39,8 @@ use crate::{
 };
 
 /// Start background worker for taking care of fetching the requested `PoV` from the network.
+///
+/// PoVs for relay parents shed by the memory budget are not fetched anymore.
 pub async fn fetch_pov<Context>(
 	ctx: &mut Context,
 	runtime: &mut RuntimeInfo,
@@ -47,11 +49,16 @@ pub async fn fetch_pov<Context>(
 	candidate_hash: CandidateHash,
 	pov_hash: Hash,
 	tx: oneshot::Sender<PoV>,
+	budget: &MemoryBudget,
 	metrics: Metrics,
 ) -> Result<()>
 where
 	Context: SubsystemContext,
 {
+	if budget.is_shed(&parent) {
+		return Err(Error::RelayParentShed)
+	}
+
 	let info = &runtime.get_session_info(ctx.sender(), parent).await?.session_info;
 	let authority_id = info
 		.discovery_keys
@@ -157,6 +164,37 @@ mod tests {
 		test_run(pov.hash(), pov);
 	}
 
+	#[test]
+	fn refuses_shed_relay_parent() {
+		let pool = TaskExecutor::new();
+		let (mut context, _virtual_overseer) = test_helpers::make_subsystem_context::<
+			AvailabilityDistributionMessage,
+			TaskExecutor,
+		>(pool.clone());
+		let mut runtime = selendra_node_subsystem_util::runtime::RuntimeInfo::new(None);
+
+		let budget = MemoryBudget::new(10, Default::default());
+		let shed = Hash::repeat_byte(1);
+		let _old = budget.reserve(shed, 10).unwrap();
+		let _new = budget.reserve(Hash::repeat_byte(2), 10).unwrap();
+
+		let (tx, rx) = oneshot::channel();
+		let result = executor::block_on(fetch_pov(
+			&mut context,
+			&mut runtime,
+			shed,
+			ValidatorIndex(0),
+			CandidateHash::default(),
+			Hash::default(),
+			tx,
+			&budget,
+			Metrics::new_dummy(),
+		));
+
+		assert_matches!(result, Err(Error::RelayParentShed));
+		assert_eq!(executor::block_on(rx), Err(oneshot::Canceled));
+	}
+
 	fn test_run(pov_hash: Hash, pov: PoV) {
 		let pool = TaskExecutor::new();
 		let (mut context, mut virtual_overseer) = test_helpers::make_subsystem_context::<
@@ -176,6 +214,7 @@ mod tests {
 				CandidateHash::default(),
 				pov_hash,
 				tx,
+				&Default::default(),
 				Metrics::new_dummy(),
 			)
 			.await
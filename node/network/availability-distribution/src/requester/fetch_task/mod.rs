@@ -28,6 +28,7 @@ use selendra_node_network_protocol::request_response::{
 	v1::{ChunkFetchingRequest, ChunkFetchingResponse},
 };
 use selendra_node_primitives::ErasureChunk;
+use selendra_node_subsystem_util::memory_budget::MemoryBudget;
 use selendra_primitives::v1::{
 	AuthorityDiscoveryId, BlakeTwo256, CandidateHash, GroupIndex, Hash, HashT, OccupiedCore,
 	SessionIndex,
@@ -55,6 +56,7 @@ mod tests;
 pub struct FetchTaskConfig {
 	prepared_running: Option<RunningTask>,
 	live_in: HashSet<Hash>,
+	relay_parent: Hash,
 }
 
 /// Information about a task fetching an erasure chunk.
@@ -66,6 +68,9 @@ pub struct FetchTask {
 	/// stop keeping track of that candidate/chunk.
 	pub(crate) live_in: HashSet<Hash>,
 
+	/// Relay parent of the candidate, for shedding the task once it exceeds the memory budget.
+	pub(crate) relay_parent: Hash,
+
 	/// We keep the task around in until `live_in` becomes empty, to make
 	/// sure we won't re-fetch an already fetched candidate.
 	state: FetchedState,
@@ -123,6 +128,9 @@ struct RunningTask {
 	/// Sender for communicating with other subsystems and reporting results.
 	sender: mpsc::Sender<FromFetchTask>,
 
+	/// Budget the fetched chunk is accounted against until it is stored.
+	budget: MemoryBudget,
+
 	/// Prometheus metrics for reporting results.
 	metrics: Metrics,
 
@@ -138,14 +146,16 @@ impl FetchTaskConfig {
 		leaf: Hash,
 		core: &OccupiedCore,
 		sender: mpsc::Sender<FromFetchTask>,
+		budget: MemoryBudget,
 		metrics: Metrics,
 		session_info: &SessionInfo,
 	) -> Self {
 		let live_in = vec![leaf].into_iter().collect();
+		let relay_parent = core.candidate_descriptor.relay_parent;
 
 		// Don't run tasks for our backing group:
 		if session_info.our_group == Some(core.group_responsible) {
-			return FetchTaskConfig { live_in, prepared_running: None }
+			return FetchTaskConfig { live_in, relay_parent, prepared_running: None }
 		}
 
 		let span = jaeger::Span::new(core.candidate_hash, "availability-distribution")
@@ -162,12 +172,13 @@ impl FetchTaskConfig {
 				index: session_info.our_index,
 			},
 			erasure_root: core.candidate_descriptor.erasure_root,
-			relay_parent: core.candidate_descriptor.relay_parent,
+			relay_parent,
 			metrics,
 			sender,
+			budget,
 			span,
 		};
-		FetchTaskConfig { live_in, prepared_running: Some(prepared_running) }
+		FetchTaskConfig { live_in, relay_parent, prepared_running: Some(prepared_running) }
 	}
 }
 
@@ -179,7 +190,7 @@ impl FetchTask {
 	where
 		Context: SubsystemContext,
 	{
-		let FetchTaskConfig { prepared_running, live_in } = config;
+		let FetchTaskConfig { prepared_running, live_in, relay_parent } = config;
 
 		if let Some(running) = prepared_running {
 			let (handle, kill) = oneshot::channel();
@@ -187,9 +198,9 @@ impl FetchTask {
 			ctx.spawn("chunk-fetcher", running.run(kill).boxed())
 				.map_err(|e| FatalError::SpawnTask(e))?;
 
-			Ok(FetchTask { live_in, state: FetchedState::Started(handle) })
+			Ok(FetchTask { live_in, relay_parent, state: FetchedState::Started(handle) })
 		} else {
-			Ok(FetchTask { live_in, state: FetchedState::Canceled })
+			Ok(FetchTask { live_in, relay_parent, state: FetchedState::Canceled })
 		}
 	}
 
@@ -302,6 +313,19 @@ impl RunningTask {
 				continue
 			}
 
+			// Account for the chunk until it is stored:
+			let _reservation = match self.budget.reserve(self.relay_parent, chunk.chunk.len()) {
+				Some(reservation) => reservation,
+				None => {
+					tracing::debug!(
+						target: LOG_TARGET,
+						relay_parent = ?self.relay_parent,
+						"Relay parent has been shed for exceeding the memory budget"
+					);
+					break
+				},
+			};
+
 			// Ok, let's store it and be happy:
 			self.store_chunk(chunk).await;
 			succeeded = true;
@@ -285,6 +285,7 @@ fn get_test_running_task() -> (RunningTask, mpsc::Receiver<FromFetchTask>) {
 			erasure_root: Hash::repeat_byte(99),
 			relay_parent: Hash::repeat_byte(71),
 			sender: tx,
+			budget: Default::default(),
 			metrics: Metrics::new_dummy(),
 			span: jaeger::Span::Disabled,
 		},
@@ -32,7 +32,10 @@ use futures::{
 	Stream,
 };
 
-use selendra_node_subsystem_util::runtime::{get_occupied_cores, RuntimeInfo};
+use selendra_node_subsystem_util::{
+	memory_budget::MemoryBudget,
+	runtime::{get_occupied_cores, RuntimeInfo},
+};
 use selendra_primitives::v1::{CandidateHash, Hash, OccupiedCore};
 use selendra_subsystem::{
 	messages::{AllMessages, ChainApiMessage},
@@ -74,6 +77,9 @@ pub struct Requester {
 	/// Receive messages from `FetchTask`.
 	rx: mpsc::Receiver<FromFetchTask>,
 
+	/// Budget for the chunks held in flight, shedding fetches of the oldest relay parents.
+	budget: MemoryBudget,
+
 	/// Prometheus Metrics
 	metrics: Metrics,
 }
@@ -86,9 +92,16 @@ impl Requester {
 	///
 	/// You must feed it with `ActiveLeavesUpdate` via `update_fetching_heads` and make it progress
 	/// by advancing the stream.
-	pub fn new(metrics: Metrics) -> Self {
+	pub fn new(budget: MemoryBudget, metrics: Metrics) -> Self {
 		let (tx, rx) = mpsc::channel(1);
-		Requester { fetches: HashMap::new(), session_cache: SessionCache::new(), tx, rx, metrics }
+		Requester {
+			fetches: HashMap::new(),
+			session_cache: SessionCache::new(),
+			tx,
+			rx,
+			budget,
+			metrics,
+		}
 	}
 
 	/// Update heads that need availability distribution.
@@ -113,6 +126,7 @@ impl Requester {
 		}
 
 		self.stop_requesting_chunks(deactivated.into_iter());
+		self.shed_fetches();
 		Ok(())
 	}
 
@@ -177,6 +191,12 @@ impl Requester {
 		})
 	}
 
+	/// Stop fetching chunks of candidates whose relay parent has been shed by the memory budget.
+	fn shed_fetches(&mut self) {
+		let budget = &self.budget;
+		self.fetches.retain(|_, task| !budget.is_shed(&task.relay_parent))
+	}
+
 	/// Add candidates corresponding for a particular relay parent.
 	///
 	/// Starting requests where necessary.
@@ -195,6 +215,9 @@ impl Requester {
 		Context: SubsystemContext,
 	{
 		for core in cores {
+			if self.budget.is_shed(&core.candidate_descriptor.relay_parent) {
+				continue
+			}
 			match self.fetches.entry(core.candidate_hash) {
 				Entry::Occupied(mut e) =>
 				// Just book keeping - we are already requesting that chunk:
@@ -203,6 +226,7 @@ impl Requester {
 				},
 				Entry::Vacant(e) => {
 					let tx = self.tx.clone();
+					let budget = self.budget.clone();
 					let metrics = self.metrics.clone();
 
 					let task_cfg = self
@@ -215,7 +239,7 @@ impl Requester {
 							// at session boundaries. At the same time, only leaves are guaranteed to
 							// be fetchable by the state trie.
 							leaf,
-							|info| FetchTaskConfig::new(leaf, &core, tx, metrics, info),
+							|info| FetchTaskConfig::new(leaf, &core, tx, budget, metrics, info),
 						)
 						.await
 						.map_err(|err| {
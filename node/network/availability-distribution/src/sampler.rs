@@ -0,0 +1,326 @@
+// Copyright 2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Sampler checking that validators actually hold the erasure chunks they attested to.
+//!
+//! By signing an availability bitfield a validator claims to hold its chunk of the candidates it
+//! marks as available, but nothing checks the claim. For a random subset of the candidates
+//! included in every fresh leaf, the `Sampler` asks a random validator for its chunk and verifies
+//! the merkle proof against the erasure root of the candidate. Failed samples are accounted per
+//! validator and session, and validators failing repeatedly get logged.
+
+use std::{collections::HashMap, pin::Pin};
+
+use futures::{
+	channel::mpsc,
+	future::BoxFuture,
+	task::{Context, Poll},
+	FutureExt, SinkExt, Stream,
+};
+use lru::LruCache;
+use rand::{thread_rng, Rng};
+
+use selendra_erasure_coding::branch_hash;
+use selendra_node_network_protocol::request_response::{
+	outgoing::{OutgoingRequest, Recipient, RequestError, Requests},
+	v1::{ChunkFetchingRequest, ChunkFetchingResponse},
+};
+use selendra_node_primitives::ErasureChunk;
+use selendra_node_subsystem_util::runtime::{get_candidate_events, RuntimeInfo};
+use selendra_primitives::v1::{
+	BlakeTwo256, CandidateEvent, CandidateHash, Hash, HashT, SessionIndex, ValidatorIndex,
+};
+use selendra_subsystem::{
+	messages::{IfDisconnected, NetworkBridgeMessage},
+	SubsystemContext,
+};
+
+use crate::{
+	error::{FatalError, Result},
+	metrics::{Metrics, FAILED, NOT_FOUND, NO_RESPONSE, SUCCEEDED},
+	LOG_TARGET,
+};
+
+/// Included candidates get sampled with a probability of one in `SAMPLE_RATE`.
+const SAMPLE_RATE: u32 = 4;
+
+/// Number of failed samples in a session after which a validator gets logged as not holding its
+/// chunks.
+const FAILURE_THRESHOLD: u32 = 3;
+
+/// Outcome of a single sample, as reported back by a sampling job.
+#[derive(Debug)]
+struct SampleOutcome {
+	/// Session the sampled validator index belongs to.
+	session_index: SessionIndex,
+	/// The validator that was asked for its chunk.
+	validator_index: ValidatorIndex,
+	/// The candidate the chunk was requested for.
+	candidate_hash: CandidateHash,
+	/// Metrics label describing the result.
+	result: &'static str,
+}
+
+/// Sampler takes care of requesting chunks of included candidates from random validators.
+///
+/// It implements a stream that needs to be advanced for processing the outcome of samples.
+pub struct Sampler {
+	/// Number of failed samples per validator, for the sessions we are interested in.
+	failures: LruCache<SessionIndex, HashMap<ValidatorIndex, u32>>,
+
+	/// Sender to be cloned for sampling jobs.
+	tx: mpsc::Sender<SampleOutcome>,
+
+	/// Receive outcomes from sampling jobs.
+	rx: mpsc::Receiver<SampleOutcome>,
+
+	/// Prometheus Metrics
+	metrics: Metrics,
+}
+
+impl Sampler {
+	/// Create a new `Sampler`.
+	///
+	/// You must feed it with fresh leaves via `sample_leaf` and make it progress by advancing the
+	/// stream.
+	pub fn new(metrics: Metrics) -> Self {
+		let (tx, rx) = mpsc::channel(1);
+		Sampler {
+			// We need to keep the current and the last session the most:
+			failures: LruCache::new(2),
+			tx,
+			rx,
+			metrics,
+		}
+	}
+
+	/// Sample some of the candidates included in the given leaf.
+	///
+	/// Nothing is sampled if this node is not a validator.
+	pub async fn sample_leaf<Context>(
+		&mut self,
+		ctx: &mut Context,
+		runtime: &mut RuntimeInfo,
+		leaf: Hash,
+	) -> Result<()>
+	where
+		Context: SubsystemContext,
+	{
+		let events = get_candidate_events(ctx.sender(), leaf).await?;
+		let sampled = events
+			.into_iter()
+			.filter_map(|event| match event {
+				CandidateEvent::CandidateIncluded(receipt, ..) => Some(receipt),
+				_ => None,
+			})
+			.filter(|_| thread_rng().gen_ratio(1, SAMPLE_RATE))
+			.collect::<Vec<_>>();
+
+		for receipt in sampled {
+			// Chunks are indexed by the validators of the session the candidate got backed in:
+			let relay_parent = receipt.descriptor.relay_parent;
+			let session_index =
+				runtime.get_session_index_for_child(ctx.sender(), relay_parent).await?;
+			let info = runtime
+				.get_session_info_by_index(ctx.sender(), relay_parent, session_index)
+				.await?;
+			let our_index = match info.validator_info.our_index {
+				Some(index) => index,
+				None => return Ok(()),
+			};
+			let discovery_keys = &info.session_info.discovery_keys;
+			if discovery_keys.len() < 2 {
+				continue
+			}
+
+			// Pick anybody but ourselves:
+			let mut index = thread_rng().gen_range(0..discovery_keys.len() as u32 - 1);
+			if index >= our_index.0 {
+				index += 1;
+			}
+			let authority_id = match discovery_keys.get(index as usize) {
+				Some(authority_id) => authority_id.clone(),
+				None => continue,
+			};
+
+			let request = ChunkFetchingRequest {
+				candidate_hash: receipt.hash(),
+				index: ValidatorIndex(index),
+			};
+			let (full_request, pending_response) =
+				OutgoingRequest::new(Recipient::Authority(authority_id), request);
+
+			// Not being connected to the sampled validator is no fault of theirs:
+			ctx.send_message(NetworkBridgeMessage::SendRequests(
+				vec![Requests::ChunkFetching(full_request)],
+				IfDisconnected::TryConnect,
+			))
+			.await;
+
+			ctx.spawn(
+				"chunk-sampler",
+				sample_job(
+					session_index,
+					request,
+					receipt.descriptor.erasure_root,
+					pending_response.boxed(),
+					self.tx.clone(),
+				)
+				.boxed(),
+			)
+			.map_err(FatalError::SpawnTask)?;
+		}
+		Ok(())
+	}
+
+	/// Account for the outcome of a sample.
+	fn note_outcome(&mut self, outcome: SampleOutcome) {
+		self.metrics.on_sampled_chunk(outcome.result);
+		if outcome.result == SUCCEEDED {
+			return
+		}
+
+		if self.failures.get(&outcome.session_index).is_none() {
+			self.failures.put(outcome.session_index, HashMap::new());
+		}
+		let failures = self
+			.failures
+			.get_mut(&outcome.session_index)
+			.expect("Entry got inserted above. qed.")
+			.entry(outcome.validator_index)
+			.or_default();
+		*failures += 1;
+
+		tracing::debug!(
+			target: LOG_TARGET,
+			session_index = outcome.session_index,
+			validator_index = ?outcome.validator_index,
+			candidate_hash = ?outcome.candidate_hash,
+			result = outcome.result,
+			"Sampled validator failed to provide its chunk",
+		);
+		if *failures == FAILURE_THRESHOLD {
+			tracing::warn!(
+				target: LOG_TARGET,
+				session_index = outcome.session_index,
+				validator_index = ?outcome.validator_index,
+				failures = *failures,
+				"Validator repeatedly failed to provide sampled chunks",
+			);
+			self.metrics.on_unreliable_validator();
+		}
+	}
+}
+
+impl Stream for Sampler {
+	type Item = ();
+
+	fn poll_next(mut self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Option<()>> {
+		match Pin::new(&mut self.rx).poll_next(ctx) {
+			Poll::Ready(Some(outcome)) => {
+				self.note_outcome(outcome);
+				Poll::Ready(Some(()))
+			},
+			Poll::Ready(None) => Poll::Ready(None),
+			Poll::Pending => Poll::Pending,
+		}
+	}
+}
+
+/// Future to be spawned for awaiting and checking a sampled chunk.
+async fn sample_job(
+	session_index: SessionIndex,
+	request: ChunkFetchingRequest,
+	erasure_root: Hash,
+	pending_response: BoxFuture<'static, std::result::Result<ChunkFetchingResponse, RequestError>>,
+	mut tx: mpsc::Sender<SampleOutcome>,
+) {
+	let result = match pending_response.await {
+		Ok(ChunkFetchingResponse::Chunk(resp)) =>
+			if validate_chunk(&erasure_root, &resp.recombine_into_chunk(&request)) {
+				SUCCEEDED
+			} else {
+				FAILED
+			},
+		Ok(ChunkFetchingResponse::NoSuchChunk) => NOT_FOUND,
+		Err(RequestError::InvalidResponse(_)) => FAILED,
+		Err(RequestError::NetworkError(_)) | Err(RequestError::Canceled(_)) => NO_RESPONSE,
+	};
+
+	let outcome = SampleOutcome {
+		session_index,
+		validator_index: request.index,
+		candidate_hash: request.candidate_hash,
+		result,
+	};
+	if let Err(err) = tx.send(outcome).await {
+		tracing::debug!(target: LOG_TARGET, ?err, "Sending sample outcome failed");
+	}
+}
+
+/// Check the given chunk against the erasure root of its candidate.
+fn validate_chunk(erasure_root: &Hash, chunk: &ErasureChunk) -> bool {
+	match branch_hash(erasure_root, chunk.proof(), chunk.index.0 as usize) {
+		Ok(hash) => hash == BlakeTwo256::hash(&chunk.chunk),
+		Err(_) => false,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use selendra_node_primitives::{BlockData, PoV};
+
+	use super::*;
+	use crate::tests::mock::get_valid_chunk_data;
+
+	fn outcome(session_index: SessionIndex, result: &'static str) -> SampleOutcome {
+		SampleOutcome {
+			session_index,
+			validator_index: ValidatorIndex(1),
+			candidate_hash: CandidateHash::default(),
+			result,
+		}
+	}
+
+	#[test]
+	fn validates_chunk_proofs() {
+		let pov = PoV { block_data: BlockData(vec![1, 2, 3]) };
+		let (root, mut chunk) = get_valid_chunk_data(pov);
+		assert!(validate_chunk(&root, &chunk));
+		assert!(!validate_chunk(&Hash::repeat_byte(1), &chunk));
+
+		chunk.chunk[0] ^= 1;
+		assert!(!validate_chunk(&root, &chunk));
+	}
+
+	#[test]
+	fn failures_get_counted_per_session() {
+		let mut sampler = Sampler::new(Metrics::new_dummy());
+
+		sampler.note_outcome(outcome(1, SUCCEEDED));
+		assert!(sampler.failures.get(&1).is_none());
+
+		sampler.note_outcome(outcome(1, NOT_FOUND));
+		sampler.note_outcome(outcome(1, NO_RESPONSE));
+		sampler.note_outcome(outcome(2, FAILED));
+		assert_eq!(sampler.failures.get(&1).unwrap().get(&ValidatorIndex(1)), Some(&2));
+		assert_eq!(sampler.failures.get(&2).unwrap().get(&ValidatorIndex(1)), Some(&1));
+
+		// Only the two most recent sessions are kept:
+		sampler.note_outcome(outcome(3, FAILED));
+		assert!(sampler.failures.get(&1).is_none());
+	}
+}
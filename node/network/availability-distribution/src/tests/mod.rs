@@ -48,6 +48,7 @@ fn test_harness<T: Future<Output = ()>>(
 		keystore,
 		IncomingRequestReceivers { pov_req_receiver, chunk_req_receiver },
 		Default::default(),
+		Default::default(),
 	);
 	let subsystem = subsystem.run(context);
 
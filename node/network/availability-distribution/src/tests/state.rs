@@ -268,6 +268,10 @@ impl TestState {
 							tx.send(Ok(Some(self.session_info.clone())))
 								.expect("Receiver should be alive.");
 						},
+						RuntimeApiRequest::CandidateEvents(tx) => {
+							// Nothing got included, so nothing gets sampled:
+							tx.send(Ok(Vec::new())).expect("Receiver should still be alive");
+						},
 						RuntimeApiRequest::AvailabilityCores(tx) => {
 							tracing::trace!(target: LOG_TARGET, cores= ?self.cores[&hash], hash = ?hash, "Sending out cores for hash");
 							tx.send(Ok(self.cores[&hash].clone()))
@@ -0,0 +1,175 @@
+// Copyright 2020 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Bandwidth budgets of the protocol families.
+//!
+//! The network bridge tallies what it sends per protocol family over windows of one second:
+//! notification bytes on the validation and collation peer sets and the number of requests. Each
+//! family can be given a soft budget. A family over its budget is only shed while a family of
+//! higher priority has sent something in the same window, so budgets only bite when the link is
+//! actually shared and the traffic shed first is the one of the lowest priority. From the highest
+//! priority to the lowest, the families are the validation gossip, the requests, e.g. fetching
+//! availability chunks, and the collation traffic.
+//!
+//! View updates and dispute requests are never shed.
+
+use std::time::{Duration, Instant};
+
+use selendra_node_network_protocol::{peer_set::PeerSet, request_response::Protocol};
+
+/// The length of the windows traffic is tallied over.
+const WINDOW: Duration = Duration::from_secs(1);
+
+/// A family of protocols sharing a bandwidth budget.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProtocolFamily {
+	/// Statement, bitfield and approval gossip on the validation peer set.
+	Validation,
+	/// Requests of all request/response protocols.
+	Requests,
+	/// Notifications on the collation peer set.
+	Collation,
+}
+
+impl ProtocolFamily {
+	/// All families, from the highest priority to the lowest.
+	const ALL: [ProtocolFamily; 3] =
+		[ProtocolFamily::Validation, ProtocolFamily::Requests, ProtocolFamily::Collation];
+
+	/// The family of the notifications sent on `peer_set`.
+	pub fn of_peer_set(peer_set: PeerSet) -> Self {
+		match peer_set {
+			PeerSet::Validation => ProtocolFamily::Validation,
+			PeerSet::Collation => ProtocolFamily::Collation,
+		}
+	}
+
+	/// The family of the requests of `protocol`, or `None` if they must never be shed.
+	pub fn of_request(protocol: Protocol) -> Option<Self> {
+		match protocol {
+			Protocol::DisputeSending => None,
+			_ => Some(ProtocolFamily::Requests),
+		}
+	}
+
+	/// The name of the family, as used in metrics.
+	pub fn name(self) -> &'static str {
+		match self {
+			ProtocolFamily::Validation => "validation",
+			ProtocolFamily::Requests => "requests",
+			ProtocolFamily::Collation => "collation",
+		}
+	}
+
+	fn priority(self) -> usize {
+		match self {
+			ProtocolFamily::Validation => 0,
+			ProtocolFamily::Requests => 1,
+			ProtocolFamily::Collation => 2,
+		}
+	}
+}
+
+/// Soft budgets of the protocol families, per second. `None` leaves a family unbudgeted, which is
+/// the default for all of them.
+#[derive(Debug, Clone, Default)]
+pub struct BandwidthBudgets {
+	/// Bytes of notifications sent on the validation peer set.
+	pub validation: Option<u64>,
+	/// Number of requests sent.
+	pub requests: Option<u64>,
+	/// Bytes of notifications sent on the collation peer set.
+	pub collation: Option<u64>,
+}
+
+impl BandwidthBudgets {
+	fn of(&self, family: ProtocolFamily) -> Option<u64> {
+		match family {
+			ProtocolFamily::Validation => self.validation,
+			ProtocolFamily::Requests => self.requests,
+			ProtocolFamily::Collation => self.collation,
+		}
+	}
+}
+
+/// Tally of the traffic of the current window.
+pub(crate) struct Bandwidth {
+	budgets: BandwidthBudgets,
+	window_start: Instant,
+	/// Traffic sent in the current window, by priority of the family.
+	sent: [u64; 3],
+}
+
+impl Bandwidth {
+	pub fn new(budgets: BandwidthBudgets) -> Self {
+		Bandwidth { budgets, window_start: Instant::now(), sent: [0; 3] }
+	}
+
+	/// Account for `amount` of traffic of `family` to be sent at `now`. Returns `false` if the
+	/// traffic is to be shed instead, in which case it is not accounted for.
+	pub fn admit(&mut self, family: ProtocolFamily, amount: u64, now: Instant) -> bool {
+		if now.saturating_duration_since(self.window_start) >= WINDOW {
+			self.window_start = now;
+			self.sent = [0; 3];
+		}
+
+		let priority = family.priority();
+		let sent = self.sent[priority].saturating_add(amount);
+		let over_budget = self.budgets.of(family).map_or(false, |budget| sent > budget);
+		let contended = ProtocolFamily::ALL[..priority].iter().any(|f| self.sent[f.priority()] > 0);
+
+		if over_budget && contended {
+			return false
+		}
+
+		self.sent[priority] = sent;
+		true
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn only_contended_traffic_over_budget_is_shed() {
+		let now = Instant::now();
+		let mut bandwidth = Bandwidth::new(BandwidthBudgets {
+			validation: Some(10),
+			requests: Some(2),
+			collation: Some(100),
+		});
+
+		// Without higher priority traffic, budgets are not enforced.
+		assert!(bandwidth.admit(ProtocolFamily::Requests, 1, now));
+		assert!(bandwidth.admit(ProtocolFamily::Requests, 1, now));
+		assert!(bandwidth.admit(ProtocolFamily::Requests, 1, now));
+		assert!(bandwidth.admit(ProtocolFamily::Validation, 50, now));
+
+		// Now that there is validation traffic, requests over the budget are shed...
+		assert!(!bandwidth.admit(ProtocolFamily::Requests, 1, now));
+		// ... as is collation traffic over its budget, but not below it.
+		assert!(bandwidth.admit(ProtocolFamily::Collation, 100, now));
+		assert!(!bandwidth.admit(ProtocolFamily::Collation, 1, now));
+		// The highest priority is never shed.
+		assert!(bandwidth.admit(ProtocolFamily::Validation, 50, now));
+
+		// The next window starts afresh.
+		let later = now + WINDOW;
+		assert!(bandwidth.admit(ProtocolFamily::Collation, 200, later));
+		assert!(bandwidth.admit(ProtocolFamily::Requests, 5, later));
+	}
+}
@@ -22,19 +22,26 @@
 use futures::{prelude::*, stream::BoxStream};
 use parity_scale_codec::{Decode, Encode};
 use parking_lot::Mutex;
-use sc_network::Event as NetworkEvent;
+use sc_network::{Event as NetworkEvent, RequestFailure};
 use sp_consensus::SyncOracle;
 
 use selendra_node_network_protocol::{
-	peer_set::PeerSet, v1 as protocol_v1, ObservedRole, OurView, PeerId,
-	UnifiedReputationChange as Rep, View,
+	peer_set::PeerSet,
+	request_response::{OutgoingRequest, Protocol},
+	v1 as protocol_v1, ObservedRole, OurView, PeerId, UnifiedReputationChange as Rep, View,
+};
+use selendra_node_subsystem_util::{
+	database::Database,
+	metrics::{self, prometheus},
 };
-use selendra_node_subsystem_util::metrics::{self, prometheus};
 use selendra_overseer::gen::{OverseerError, Subsystem};
-use selendra_primitives::v1::{BlockNumber, Hash};
+use selendra_primitives::v1::{AuthorityDiscoveryId, BlockNumber, Hash};
 use selendra_subsystem::{
 	errors::{SubsystemError, SubsystemResult},
-	messages::{AllMessages, CollatorProtocolMessage, NetworkBridgeEvent, NetworkBridgeMessage},
+	messages::{
+		AllMessages, CollatorProtocolMessage, GossipSubsystem, NetworkBridgeEvent,
+		NetworkBridgeMessage,
+	},
 	overseer, ActivatedLeaf, ActiveLeavesUpdate, FromOverseer, OverseerSignal, SpawnedSubsystem,
 	SubsystemContext, SubsystemSender,
 };
@@ -47,6 +54,7 @@ pub use selendra_node_network_protocol::peer_set::{peer_sets_info, IsAuthority};
 use std::{
 	collections::{hash_map, HashMap, HashSet},
 	sync::Arc,
+	time::Instant,
 };
 
 mod validator_discovery;
@@ -59,6 +67,15 @@ use network::{send_message, Network};
 
 use crate::network::get_peer_id_by_authority_id;
 
+/// Peer reputations persisted across restarts.
+mod reputation;
+use reputation::{Recording, Reputations};
+
+/// Bandwidth budgets of the protocol families.
+mod bandwidth;
+use bandwidth::Bandwidth;
+pub use bandwidth::{BandwidthBudgets, ProtocolFamily};
+
 #[cfg(test)]
 mod tests;
 
@@ -71,10 +88,21 @@ const MALFORMED_MESSAGE_COST: Rep = Rep::CostMajor("Malformed Network-bridge mes
 const UNCONNECTED_PEERSET_COST: Rep = Rep::CostMinor("Message sent to un-connected peer-set");
 const MALFORMED_VIEW_COST: Rep = Rep::CostMajor("Malformed view");
 const EMPTY_VIEW_COST: Rep = Rep::CostMajor("Peer sent us an empty view");
+const UNAUTHENTICATED_VALIDATION_MESSAGE_COST: Rep =
+	Rep::CostMinor("Validation protocol message from a peer not known to be an authority");
 
 // network bridge log target
 const LOG_TARGET: &'static str = "parachain::network-bridge";
 
+/// Configuration of the network bridge.
+#[derive(Debug, Clone)]
+pub struct Config {
+	/// The column in the database the peer reputations are stored in.
+	pub col_data: u32,
+	/// The soft bandwidth budgets of the protocol families.
+	pub bandwidth: BandwidthBudgets,
+}
+
 /// Metrics for the network bridge.
 #[derive(Clone, Default)]
 pub struct Metrics(Option<MetricsInner>);
@@ -135,6 +163,18 @@ impl Metrics {
 		}
 	}
 
+	fn on_request_sent(&self, protocol: Protocol) {
+		self.0.as_ref().map(|metrics| {
+			metrics.requests_sent.with_label_values(&[&protocol.into_protocol_name()]).inc()
+		});
+	}
+
+	fn on_traffic_shed(&self, family: ProtocolFamily) {
+		self.0
+			.as_ref()
+			.map(|metrics| metrics.traffic_shed.with_label_values(&[family.name()]).inc());
+	}
+
 	fn note_desired_peer_count(&self, peer_set: PeerSet, size: usize) {
 		self.0.as_ref().map(|metrics| {
 			metrics
@@ -157,6 +197,9 @@ struct MetricsInner {
 
 	bytes_received: prometheus::CounterVec<prometheus::U64>,
 	bytes_sent: prometheus::CounterVec<prometheus::U64>,
+
+	requests_sent: prometheus::CounterVec<prometheus::U64>,
+	traffic_shed: prometheus::CounterVec<prometheus::U64>,
 }
 
 impl metrics::Metrics for Metrics {
@@ -244,6 +287,26 @@ impl metrics::Metrics for Metrics {
 				)?,
 				registry,
 			)?,
+			requests_sent: prometheus::register(
+				prometheus::CounterVec::new(
+					prometheus::Opts::new(
+						"selendra_parachain_requests_sent_total",
+						"The number of requests sent on a parachain request/response protocol",
+					),
+					&["protocol"]
+				)?,
+				registry,
+			)?,
+			traffic_shed: prometheus::register(
+				prometheus::CounterVec::new(
+					prometheus::Opts::new(
+						"selendra_parachain_traffic_shed_total",
+						"The number of messages and requests not sent for exceeding the bandwidth budget of their protocol family",
+					),
+					&["family"]
+				)?,
+				registry,
+			)?,
 		};
 
 		Ok(Metrics(Some(metrics)))
@@ -270,6 +333,15 @@ pub struct NetworkBridge<N, AD> {
 	authority_discovery_service: AD,
 	sync_oracle: Box<dyn SyncOracle + Send>,
 	metrics: Metrics,
+	/// Whether we are an authority ourselves.
+	///
+	/// Authorities only process validation protocol messages coming from peers which
+	/// authority discovery resolves to an authority.
+	is_authority: IsAuthority,
+	/// Database the peer reputations are persisted in.
+	db: Arc<dyn Database>,
+	/// Configuration for persisting peer reputations.
+	config: Config,
 }
 
 impl<N, AD> NetworkBridge<N, AD> {
@@ -282,8 +354,19 @@ impl<N, AD> NetworkBridge<N, AD> {
 		authority_discovery_service: AD,
 		sync_oracle: Box<dyn SyncOracle + Send>,
 		metrics: Metrics,
+		is_authority: IsAuthority,
+		db: Arc<dyn Database>,
+		config: Config,
 	) -> Self {
-		NetworkBridge { network_service, authority_discovery_service, sync_oracle, metrics }
+		NetworkBridge {
+			network_service,
+			authority_discovery_service,
+			sync_oracle,
+			metrics,
+			is_authority,
+			db,
+			config,
+		}
 	}
 }
 
@@ -311,6 +394,8 @@ where
 struct PeerData {
 	/// The Latest view sent by the peer.
 	view: View,
+	/// The authority ids of the peer, as far as authority discovery knows them.
+	authority_ids: Option<HashSet<AuthorityDiscoveryId>>,
 }
 
 #[derive(Debug)]
@@ -341,6 +426,14 @@ struct SharedInner {
 	local_view: Option<View>,
 	validation_peers: HashMap<PeerId, PeerData>,
 	collation_peers: HashMap<PeerId, PeerData>,
+	paused_gossip: HashSet<GossipSubsystem>,
+}
+
+impl Shared {
+	/// Whether `message` belongs to a gossip subsystem which is paused.
+	fn is_paused(&self, message: &protocol_v1::ValidationProtocol) -> bool {
+		self.0.lock().paused_gossip.contains(&GossipSubsystem::of(message))
+	}
 }
 
 enum Mode {
@@ -355,6 +448,8 @@ async fn handle_subsystem_messages<Context, N, AD>(
 	shared: Shared,
 	sync_oracle: Box<dyn SyncOracle + Send>,
 	metrics: Metrics,
+	reputations: Reputations,
+	mut bandwidth: Bandwidth,
 ) -> Result<(), UnexpectedAbort>
 where
 	Context: SubsystemContext<Message = NetworkBridgeMessage>,
@@ -424,8 +519,11 @@ where
 					// otherwise it might break assumptions of some of the subsystems
 					// that we never send the same `ActiveLeavesUpdate`
 					finalized_number = number;
+
+					reputations.persist(false);
 				}
 				Ok(FromOverseer::Signal(OverseerSignal::Conclude)) => {
+					reputations.persist(true);
 					return Ok(());
 				}
 				Ok(FromOverseer::Communication { msg }) => match msg {
@@ -456,13 +554,16 @@ where
 							num_messages = 1,
 						);
 
-						send_message(
-							&mut network_service,
-							peers,
-							PeerSet::Validation,
-							WireMessage::ProtocolMessage(msg),
-							&metrics,
-						);
+						if !shared.is_paused(&msg) {
+							send_budgeted_message(
+								&mut network_service,
+								peers,
+								PeerSet::Validation,
+								WireMessage::ProtocolMessage(msg),
+								&metrics,
+								&mut bandwidth,
+							);
+						}
 					}
 					NetworkBridgeMessage::SendValidationMessages(msgs) => {
 						tracing::trace!(
@@ -471,13 +572,15 @@ where
 							num_messages = %msgs.len(),
 						);
 
+						let msgs = msgs.into_iter().filter(|(_, msg)| !shared.is_paused(msg));
 						for (peers, msg) in msgs {
-							send_message(
+							send_budgeted_message(
 								&mut network_service,
 								peers,
 								PeerSet::Validation,
 								WireMessage::ProtocolMessage(msg),
 								&metrics,
+								&mut bandwidth,
 							);
 						}
 					}
@@ -488,12 +591,13 @@ where
 							num_messages = 1,
 						);
 
-						send_message(
+						send_budgeted_message(
 							&mut network_service,
 							peers,
 							PeerSet::Collation,
 							WireMessage::ProtocolMessage(msg),
 							&metrics,
+							&mut bandwidth,
 						);
 					}
 					NetworkBridgeMessage::SendCollationMessages(msgs) => {
@@ -504,12 +608,13 @@ where
 						);
 
 						for (peers, msg) in msgs {
-							send_message(
+							send_budgeted_message(
 								&mut network_service,
 								peers,
 								PeerSet::Collation,
 								WireMessage::ProtocolMessage(msg),
 								&metrics,
+								&mut bandwidth,
 							);
 						}
 					}
@@ -521,6 +626,24 @@ where
 						);
 
 						for req in reqs {
+							let protocol = req.get_protocol();
+							let shed = ProtocolFamily::of_request(protocol)
+								.filter(|family| !bandwidth.admit(*family, 1, Instant::now()));
+							if let Some(family) = shed {
+								tracing::debug!(
+									target: LOG_TARGET,
+									?protocol,
+									"Request shed for exceeding the bandwidth budget",
+								);
+								metrics.on_traffic_shed(family);
+
+								let (_, OutgoingRequest { pending_response, .. }) =
+									req.encode_request();
+								let _ = pending_response.send(Err(RequestFailure::Refused));
+								continue
+							}
+
+							metrics.on_request_sent(protocol);
 							network_service
 								.start_request(&mut authority_discovery_service, req, if_disconnected)
 								.await;
@@ -601,6 +724,42 @@ where
 							ctx.sender(),
 						);
 					}
+					NetworkBridgeMessage::SetGossipPaused { subsystem, paused } => {
+						tracing::info!(
+							target: LOG_TARGET,
+							action = "SetGossipPaused",
+							?subsystem,
+							paused,
+						);
+
+						let mut shared = shared.0.lock();
+						if paused {
+							shared.paused_gossip.insert(subsystem);
+						} else {
+							shared.paused_gossip.remove(&subsystem);
+						}
+					}
+					NetworkBridgeMessage::RefreshView => {
+						tracing::info!(
+							target: LOG_TARGET,
+							action = "RefreshView",
+						);
+
+						// While syncing, the view is not sent at all.
+						if let Mode::Active = mode {
+							// Forget our view, so that the current one is considered new.
+							shared.0.lock().local_view = None;
+
+							update_our_view(
+								&mut network_service,
+								&mut ctx,
+								&live_heads,
+								&shared,
+								finalized_number,
+								&metrics,
+							);
+						}
+					}
 				}
 				Err(e) => return Err(e.into()),
 			},
@@ -608,6 +767,31 @@ where
 	}
 }
 
+/// Send a message unless its protocol family is over its bandwidth budget, see [`bandwidth`].
+fn send_budgeted_message<M: Encode + Clone>(
+	net: &mut impl Network,
+	peers: Vec<PeerId>,
+	peer_set: PeerSet,
+	message: WireMessage<M>,
+	metrics: &Metrics,
+	bandwidth: &mut Bandwidth,
+) {
+	let family = ProtocolFamily::of_peer_set(peer_set);
+	let bytes = (message.encoded_size() * peers.len()) as u64;
+
+	if bandwidth.admit(family, bytes, Instant::now()) {
+		send_message(net, peers, peer_set, message, metrics);
+	} else {
+		tracing::debug!(
+			target: LOG_TARGET,
+			?peer_set,
+			bytes,
+			"Message shed for exceeding the bandwidth budget",
+		);
+		metrics.on_traffic_shed(family);
+	}
+}
+
 async fn handle_network_messages<AD: validator_discovery::AuthorityDiscovery>(
 	mut sender: impl SubsystemSender,
 	mut network_service: impl Network,
@@ -615,6 +799,7 @@ async fn handle_network_messages<AD: validator_discovery::AuthorityDiscovery>(
 	mut authority_discovery_service: AD,
 	metrics: Metrics,
 	shared: Shared,
+	is_authority: IsAuthority,
 ) -> Result<(), UnexpectedAbort> {
 	let mut network_stream = network_stream.fuse();
 	loop {
@@ -650,7 +835,7 @@ async fn handle_network_messages<AD: validator_discovery::AuthorityDiscovery>(
 					match peer_map.entry(peer.clone()) {
 						hash_map::Entry::Occupied(_) => continue,
 						hash_map::Entry::Vacant(vacant) => {
-							vacant.insert(PeerData { view: View::default() });
+							vacant.insert(PeerData { view: View::default(), authority_ids: None });
 						},
 					}
 
@@ -663,6 +848,12 @@ async fn handle_network_messages<AD: validator_discovery::AuthorityDiscovery>(
 				let maybe_authority =
 					authority_discovery_service.get_authority_ids_by_peer_id(peer).await;
 
+				if peer_set == PeerSet::Validation {
+					if let Some(peer_data) = shared.0.lock().validation_peers.get_mut(&peer) {
+						peer_data.authority_ids = maybe_authority.clone();
+					}
+				}
+
 				match peer_set {
 					PeerSet::Validation => {
 						dispatch_validation_events_to_all(
@@ -801,6 +992,46 @@ async fn handle_network_messages<AD: validator_discovery::AuthorityDiscovery>(
 								num_collation_messages = %c_messages.len()
 							);
 
+							let v_messages = if is_authority == IsAuthority::Yes &&
+								!v_messages.is_empty()
+							{
+								let is_known_authority = ensure_validation_peer_authenticated(
+									&remote,
+									&shared,
+									&mut authority_discovery_service,
+								)
+								.await;
+
+								if is_known_authority {
+									v_messages
+								} else {
+									let total = v_messages.len();
+									let views_only: Vec<_> = v_messages
+										.into_iter()
+										.filter(|(m, _)| matches!(m, WireMessage::ViewUpdate(_)))
+										.collect();
+
+									if views_only.len() != total {
+										tracing::debug!(
+											target: LOG_TARGET,
+											action = "ReportPeer",
+											peer = ?remote,
+											dropped = %(total - views_only.len()),
+											"Dropping validation protocol messages from unauthenticated peer",
+										);
+
+										network_service.report_peer(
+											remote.clone(),
+											UNAUTHENTICATED_VALIDATION_MESSAGE_COST,
+										);
+									}
+
+									views_only
+								}
+							} else {
+								v_messages
+							};
+
 							if !v_messages.is_empty() {
 								let (events, reports) = handle_peer_messages(
 									remote.clone(),
@@ -814,6 +1045,11 @@ async fn handle_network_messages<AD: validator_discovery::AuthorityDiscovery>(
 									network_service.report_peer(remote.clone(), report);
 								}
 
+								let events = events.into_iter().filter(|event| match event {
+									NetworkBridgeEvent::PeerMessage(_, m) => !shared.is_paused(m),
+									_ => true,
+								});
+
 								dispatch_validation_events_to_all(events, &mut sender).await;
 							}
 
@@ -839,6 +1075,39 @@ async fn handle_network_messages<AD: validator_discovery::AuthorityDiscovery>(
 	}
 }
 
+/// Check whether a peer on the validation peer-set is known to be an authority.
+///
+/// Authority discovery might only resolve a peer after it connected, so a peer not yet known
+/// to be an authority is looked up again.
+async fn ensure_validation_peer_authenticated<AD: validator_discovery::AuthorityDiscovery>(
+	peer: &PeerId,
+	shared: &Shared,
+	authority_discovery_service: &mut AD,
+) -> bool {
+	let known = shared
+		.0
+		.lock()
+		.validation_peers
+		.get(peer)
+		.map_or(false, |d| d.authority_ids.is_some());
+
+	if known {
+		return true
+	}
+
+	let authority_ids =
+		match authority_discovery_service.get_authority_ids_by_peer_id(peer.clone()).await {
+			None => return false,
+			Some(ids) => ids,
+		};
+
+	if let Some(peer_data) = shared.0.lock().validation_peers.get_mut(peer) {
+		peer_data.authority_ids = Some(authority_ids);
+	}
+
+	true
+}
+
 /// Main driver, processing network events and messages from other subsystems.
 ///
 /// THIS IS A HACK. We need to ensure we never hold the mutex across a `.await` boundary
@@ -856,15 +1125,27 @@ async fn run_network<N, AD, Context>(
 	network_stream: BoxStream<'static, NetworkEvent>,
 ) -> SubsystemResult<()>
 where
-	N: Network,
+	N: Network + Sync,
 	AD: validator_discovery::AuthorityDiscovery + Clone,
 	Context: SubsystemContext<Message = NetworkBridgeMessage>
 		+ overseer::SubsystemContext<Message = NetworkBridgeMessage>,
 {
 	let shared = Shared::default();
 
-	let NetworkBridge { network_service, authority_discovery_service, metrics, sync_oracle } =
-		bridge;
+	let NetworkBridge {
+		network_service,
+		authority_discovery_service,
+		metrics,
+		sync_oracle,
+		is_authority,
+		db,
+		config,
+	} = bridge;
+
+	let bandwidth = Bandwidth::new(config.bandwidth.clone());
+	let reputations = Reputations::load(db, config);
+	reputations.restore(&network_service);
+	let network_service = Recording::new(network_service, reputations.clone());
 
 	let (remote, network_event_handler) = handle_network_messages(
 		ctx.sender().clone(),
@@ -873,6 +1154,7 @@ where
 		authority_discovery_service.clone(),
 		metrics.clone(),
 		shared.clone(),
+		is_authority,
 	)
 	.remote_handle();
 
@@ -885,6 +1167,8 @@ where
 		shared,
 		sync_oracle,
 		metrics,
+		reputations,
+		bandwidth,
 	);
 
 	futures::pin_mut!(subsystem_event_handler);
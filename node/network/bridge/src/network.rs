@@ -29,7 +29,7 @@ use sc_network::{
 use selendra_node_network_protocol::{
 	peer_set::PeerSet,
 	request_response::{OutgoingRequest, Recipient, Requests},
-	PeerId, UnifiedReputationChange as Rep,
+	PeerId, ReputationChange, UnifiedReputationChange as Rep,
 };
 use selendra_primitives::v1::{AuthorityDiscoveryId, Block, Hash};
 
@@ -101,6 +101,9 @@ pub trait Network: Clone + Send + 'static {
 	/// Report a given peer as either beneficial (+) or costly (-) according to the given scalar.
 	fn report_peer(&self, who: PeerId, cost_benefit: Rep);
 
+	/// Apply a reputation persisted from a previous run to a given peer.
+	fn restore_reputation(&self, who: PeerId, value: i32);
+
 	/// Disconnect a given peer from the peer set specified without harming reputation.
 	fn disconnect_peer(&self, who: PeerId, peer_set: PeerSet);
 
@@ -130,6 +133,14 @@ impl Network for Arc<NetworkService<Block, Hash>> {
 		sc_network::NetworkService::report_peer(&**self, who, cost_benefit.into_base_rep());
 	}
 
+	fn restore_reputation(&self, who: PeerId, value: i32) {
+		sc_network::NetworkService::report_peer(
+			&**self,
+			who,
+			ReputationChange::new(value, "Restored reputation"),
+		);
+	}
+
 	fn disconnect_peer(&self, who: PeerId, peer_set: PeerSet) {
 		sc_network::NetworkService::disconnect_peer(&**self, who, peer_set.into_protocol_name());
 	}
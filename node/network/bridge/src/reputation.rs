@@ -0,0 +1,314 @@
+// Copyright 2020 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Peer reputations persisted across restarts.
+//!
+//! The network bridge keeps a tally of the reputation changes it reports for every peer. Peers
+//! which misbehaved are written to the parachains database and their reputation is restored on
+//! startup, decayed by the time that has passed since, so restarting a node does not reset the
+//! protection against known spammers. Reputations below the ban threshold of the peer set ban the
+//! peer again once restored.
+
+use std::{
+	borrow::Cow,
+	collections::{HashMap, HashSet},
+	sync::Arc,
+	time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use parity_scale_codec::{Decode, Encode};
+use parking_lot::Mutex;
+use sc_network::{multiaddr::Multiaddr, Event as NetworkEvent, IfDisconnected};
+
+use selendra_node_network_protocol::{
+	peer_set::PeerSet, request_response::Requests, PeerId, UnifiedReputationChange as Rep,
+};
+use selendra_node_subsystem_util::database::{DBTransaction, Database};
+
+use crate::{network::Network, validator_discovery::AuthorityDiscovery, Config, LOG_TARGET};
+
+/// Key the reputations are stored under.
+const REPUTATIONS_KEY: &[u8] = b"Reputations";
+
+/// Time after which a persisted reputation has decayed to half its value.
+const REPUTATION_HALF_LIFE: Duration = Duration::from_secs(60 * 60);
+
+/// Reputations above this value are not worth persisting.
+const MAX_PERSISTED_REPUTATION: i32 = -100_000;
+
+/// Maximum number of peers to persist, the worst ones are kept.
+const MAX_PERSISTED_PEERS: usize = 1000;
+
+/// Minimum time between two writes of the reputations, unless forced.
+const PERSIST_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Encode, Decode)]
+struct StoredReputations {
+	/// Seconds since the unix epoch at which the reputations have been written.
+	saved_at: u64,
+	/// Encoded peer ids and their reputations.
+	peers: Vec<(Vec<u8>, i32)>,
+}
+
+/// Tally of the reputation changes reported per peer, backed by the database.
+///
+/// Cloning is cheap, all clones share the same tally.
+#[derive(Clone)]
+pub(crate) struct Reputations(Arc<Mutex<Inner>>);
+
+struct Inner {
+	db: Arc<dyn Database>,
+	config: Config,
+	peers: HashMap<PeerId, i32>,
+	/// Time the tally was decayed to.
+	decayed_at: SystemTime,
+	/// Time the tally was last written.
+	persisted_at: SystemTime,
+	dirty: bool,
+}
+
+impl Reputations {
+	/// Load the persisted reputations, decayed by the time passed since they were written.
+	pub fn load(db: Arc<dyn Database>, config: Config) -> Self {
+		let now = SystemTime::now();
+		let stored = match db.get(config.col_data, REPUTATIONS_KEY) {
+			Ok(Some(raw)) => StoredReputations::decode(&mut &raw[..])
+				.map_err(
+					|err| tracing::warn!(target: LOG_TARGET, ?err, "Corrupted peer reputations in db"),
+				)
+				.ok(),
+			Ok(None) => None,
+			Err(err) => {
+				tracing::warn!(target: LOG_TARGET, ?err, "Reading peer reputations failed");
+				None
+			},
+		};
+
+		let peers = stored
+			.map(|stored| {
+				let elapsed = now
+					.duration_since(UNIX_EPOCH + Duration::from_secs(stored.saved_at))
+					.unwrap_or_default();
+				stored
+					.peers
+					.into_iter()
+					.filter_map(|(peer, value)| {
+						let peer = PeerId::from_bytes(&peer).ok()?;
+						Some((peer, decay(value, elapsed)))
+					})
+					.filter(|(_, value)| *value <= MAX_PERSISTED_REPUTATION)
+					.collect()
+			})
+			.unwrap_or_default();
+
+		Reputations(Arc::new(Mutex::new(Inner {
+			db,
+			config,
+			peers,
+			decayed_at: now,
+			persisted_at: now,
+			dirty: false,
+		})))
+	}
+
+	/// Report the restored reputations to the network.
+	pub fn restore(&self, network: &impl Network) {
+		let inner = self.0.lock();
+		for (peer, value) in inner.peers.iter() {
+			network.restore_reputation(peer.clone(), *value);
+		}
+		if !inner.peers.is_empty() {
+			tracing::info!(
+				target: LOG_TARGET,
+				peers = inner.peers.len(),
+				"Restored reputations of misbehaving peers",
+			);
+		}
+	}
+
+	/// Account for a reputation change reported for a peer.
+	pub fn note(&self, peer: &PeerId, rep: &Rep) {
+		let mut inner = self.0.lock();
+		let value = inner.peers.entry(peer.clone()).or_insert(0);
+		*value = value.saturating_add(rep.cost_or_benefit());
+		inner.dirty = true;
+	}
+
+	/// Write the reputations to the database, if they changed.
+	///
+	/// Unless `force` is set, this is done at most once per [`PERSIST_INTERVAL`].
+	pub fn persist(&self, force: bool) {
+		let mut inner = self.0.lock();
+		let now = SystemTime::now();
+		if !inner.dirty ||
+			(!force &&
+				now.duration_since(inner.persisted_at).unwrap_or_default() < PERSIST_INTERVAL)
+		{
+			return
+		}
+
+		let elapsed = now.duration_since(inner.decayed_at).unwrap_or_default();
+		inner.peers.values_mut().for_each(|value| *value = decay(*value, elapsed));
+		inner.peers.retain(|_, value| *value < 0);
+		inner.decayed_at = now;
+
+		let mut peers: Vec<_> = inner
+			.peers
+			.iter()
+			.filter(|(_, value)| **value <= MAX_PERSISTED_REPUTATION)
+			.map(|(peer, value)| (peer.to_bytes(), *value))
+			.collect();
+		peers.sort_by_key(|(_, value)| *value);
+		peers.truncate(MAX_PERSISTED_PEERS);
+
+		let saved_at = now.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+		let mut tx = DBTransaction::new();
+		tx.put_vec(
+			inner.config.col_data,
+			REPUTATIONS_KEY,
+			StoredReputations { saved_at, peers }.encode(),
+		);
+		if let Err(err) = inner.db.write(tx) {
+			tracing::warn!(target: LOG_TARGET, ?err, "Writing peer reputations failed");
+			return
+		}
+
+		inner.persisted_at = now;
+		inner.dirty = false;
+	}
+}
+
+/// Decay a reputation by the time elapsed, halving it every [`REPUTATION_HALF_LIFE`].
+fn decay(value: i32, elapsed: Duration) -> i32 {
+	let half_lives = elapsed.as_secs_f64() / REPUTATION_HALF_LIFE.as_secs_f64();
+	(value as f64 * 0.5f64.powf(half_lives)) as i32
+}
+
+/// [`Network`] noting every reported reputation change in the given [`Reputations`].
+#[derive(Clone)]
+pub(crate) struct Recording<N> {
+	network: N,
+	reputations: Reputations,
+}
+
+impl<N> Recording<N> {
+	pub fn new(network: N, reputations: Reputations) -> Self {
+		Recording { network, reputations }
+	}
+}
+
+#[async_trait]
+impl<N: Network + Sync> Network for Recording<N> {
+	fn event_stream(&mut self) -> BoxStream<'static, NetworkEvent> {
+		self.network.event_stream()
+	}
+
+	async fn set_reserved_peers(
+		&mut self,
+		protocol: Cow<'static, str>,
+		multiaddresses: HashSet<Multiaddr>,
+	) -> Result<(), String> {
+		self.network.set_reserved_peers(protocol, multiaddresses).await
+	}
+
+	async fn remove_from_peers_set(&mut self, protocol: Cow<'static, str>, peers: Vec<PeerId>) {
+		self.network.remove_from_peers_set(protocol, peers).await
+	}
+
+	async fn start_request<AD: AuthorityDiscovery>(
+		&self,
+		authority_discovery: &mut AD,
+		req: Requests,
+		if_disconnected: IfDisconnected,
+	) {
+		self.network.start_request(authority_discovery, req, if_disconnected).await
+	}
+
+	fn report_peer(&self, who: PeerId, cost_benefit: Rep) {
+		self.reputations.note(&who, &cost_benefit);
+		self.network.report_peer(who, cost_benefit)
+	}
+
+	fn restore_reputation(&self, who: PeerId, value: i32) {
+		self.network.restore_reputation(who, value)
+	}
+
+	fn disconnect_peer(&self, who: PeerId, peer_set: PeerSet) {
+		self.network.disconnect_peer(who, peer_set)
+	}
+
+	fn write_notification(&self, who: PeerId, peer_set: PeerSet, message: Vec<u8>) {
+		self.network.write_notification(who, peer_set, message)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const COST: Rep = Rep::CostMajor("test");
+
+	fn reputations(db: Arc<dyn Database>) -> Reputations {
+		Reputations::load(db, Config { col_data: 0, bandwidth: Default::default() })
+	}
+
+	fn db() -> Arc<dyn Database> {
+		Arc::new(selendra_node_subsystem_util::database::kvdb_impl::DbAdapter::new(
+			kvdb_memorydb::create(1),
+			&[],
+		))
+	}
+
+	#[test]
+	fn reputations_survive_a_restart() {
+		let db = db();
+		let spammer = PeerId::random();
+		let honest = PeerId::random();
+
+		let reputations = reputations(db.clone());
+		reputations.note(&spammer, &COST);
+		reputations.note(&honest, &Rep::BenefitMajor("test"));
+		reputations.persist(true);
+
+		let restored = reputations(db);
+		let peers = &restored.0.lock().peers;
+		assert_eq!(peers.len(), 1);
+		assert!(peers[&spammer] <= COST.cost_or_benefit() / 2);
+	}
+
+	#[test]
+	fn reputations_decay() {
+		assert_eq!(decay(-400_000, Duration::ZERO), -400_000);
+		assert_eq!(decay(-400_000, REPUTATION_HALF_LIFE), -200_000);
+		assert_eq!(decay(-400_000, REPUTATION_HALF_LIFE * 2), -100_000);
+		assert_eq!(decay(i32::MIN, REPUTATION_HALF_LIFE * 40), 0);
+	}
+
+	#[test]
+	fn persisting_is_rate_limited() {
+		let db = db();
+		let reputations = reputations(db.clone());
+		reputations.note(&PeerId::random(), &COST);
+
+		reputations.persist(false);
+		assert!(db.get(0, REPUTATIONS_KEY).unwrap().is_none());
+
+		reputations.persist(true);
+		assert!(db.get(0, REPUTATIONS_KEY).unwrap().is_some());
+	}
+}
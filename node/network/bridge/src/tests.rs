@@ -22,7 +22,7 @@ use async_trait::async_trait;
 use parking_lot::Mutex;
 use std::{
 	borrow::Cow,
-	collections::HashSet,
+	collections::{HashMap, HashSet},
 	sync::atomic::{AtomicBool, Ordering},
 };
 
@@ -52,6 +52,8 @@ use crate::{network::Network, validator_discovery::AuthorityDiscovery, Rep};
 pub enum NetworkAction {
 	/// Note a change in reputation for a peer.
 	ReputationChange(PeerId, Rep),
+	/// Restore a persisted reputation of a peer.
+	RestoreReputation(PeerId, i32),
 	/// Disconnect a peer from the given peer-set.
 	DisconnectPeer(PeerId, PeerSet),
 	/// Write a notification to a given peer on the given peer-set.
@@ -65,8 +67,10 @@ struct TestNetwork {
 	action_tx: Arc<Mutex<metered::UnboundedMeteredSender<NetworkAction>>>,
 }
 
-#[derive(Clone, Debug)]
-struct TestAuthorityDiscovery;
+#[derive(Clone, Debug, Default)]
+struct TestAuthorityDiscovery {
+	by_peer_id: HashMap<PeerId, HashSet<AuthorityDiscoveryId>>,
+}
 
 // The test's view of the network. This receives updates from the subsystem in the form
 // of `NetworkAction`s.
@@ -85,7 +89,7 @@ fn new_test_network() -> (TestNetwork, TestNetworkHandle, TestAuthorityDiscovery
 			action_tx: Arc::new(Mutex::new(action_tx)),
 		},
 		TestNetworkHandle { action_rx, net_tx },
-		TestAuthorityDiscovery,
+		TestAuthorityDiscovery::default(),
 	)
 }
 
@@ -124,6 +128,13 @@ impl Network for TestNetwork {
 			.unwrap();
 	}
 
+	fn restore_reputation(&self, who: PeerId, value: i32) {
+		self.action_tx
+			.lock()
+			.unbounded_send(NetworkAction::RestoreReputation(who, value))
+			.unwrap();
+	}
+
 	fn disconnect_peer(&self, who: PeerId, peer_set: PeerSet) {
 		self.action_tx
 			.lock()
@@ -150,9 +161,9 @@ impl validator_discovery::AuthorityDiscovery for TestAuthorityDiscovery {
 
 	async fn get_authority_ids_by_peer_id(
 		&mut self,
-		_peer_id: PeerId,
+		peer_id: PeerId,
 	) -> Option<HashSet<AuthorityDiscoveryId>> {
-		None
+		self.by_peer_id.get(&peer_id).cloned()
 	}
 }
 
@@ -275,9 +286,19 @@ struct TestHarness {
 fn test_harness<T: Future<Output = VirtualOverseer>>(
 	sync_oracle: Box<dyn SyncOracle + Send>,
 	test: impl FnOnce(TestHarness) -> T,
+) {
+	test_harness_with_authorities(sync_oracle, IsAuthority::No, HashMap::new(), test)
+}
+
+fn test_harness_with_authorities<T: Future<Output = VirtualOverseer>>(
+	sync_oracle: Box<dyn SyncOracle + Send>,
+	is_authority: IsAuthority,
+	authorities: HashMap<PeerId, HashSet<AuthorityDiscoveryId>>,
+	test: impl FnOnce(TestHarness) -> T,
 ) {
 	let pool = sp_core::testing::TaskExecutor::new();
-	let (mut network, network_handle, discovery) = new_test_network();
+	let (mut network, network_handle, mut discovery) = new_test_network();
+	discovery.by_peer_id = authorities;
 	let (context, virtual_overseer) =
 		selendra_node_subsystem_test_helpers::make_subsystem_context(pool);
 	let network_stream = network.event_stream();
@@ -287,6 +308,12 @@ fn test_harness<T: Future<Output = VirtualOverseer>>(
 		authority_discovery_service: discovery,
 		metrics: Metrics(None),
 		sync_oracle,
+		is_authority,
+		db: Arc::new(selendra_node_subsystem_util::database::kvdb_impl::DbAdapter::new(
+			kvdb_memorydb::create(1),
+			&[],
+		)),
+		config: Config { col_data: 0, bandwidth: Default::default() },
 	};
 
 	let network_bridge = run_network(bridge, context, network_stream)
@@ -1188,6 +1215,149 @@ fn send_messages_to_peers() {
 	});
 }
 
+#[test]
+fn paused_gossip_subsystems_exchange_no_messages() {
+	test_harness(done_syncing_oracle(), |test_harness| async move {
+		let TestHarness { mut network_handle, mut virtual_overseer } = test_harness;
+
+		let peer = PeerId::random();
+
+		network_handle
+			.connect_peer(peer.clone(), PeerSet::Validation, ObservedRole::Full)
+			.await;
+		network_handle
+			.connect_peer(peer.clone(), PeerSet::Collation, ObservedRole::Full)
+			.await;
+
+		// bridge will inform about all connected peers.
+		{
+			assert_sends_validation_event_to_all(
+				NetworkBridgeEvent::PeerConnected(peer.clone(), ObservedRole::Full, None),
+				&mut virtual_overseer,
+			)
+			.await;
+
+			assert_sends_validation_event_to_all(
+				NetworkBridgeEvent::PeerViewChange(peer.clone(), View::default()),
+				&mut virtual_overseer,
+			)
+			.await;
+
+			assert_sends_collation_event_to_all(
+				NetworkBridgeEvent::PeerConnected(peer.clone(), ObservedRole::Full, None),
+				&mut virtual_overseer,
+			)
+			.await;
+
+			assert_sends_collation_event_to_all(
+				NetworkBridgeEvent::PeerViewChange(peer.clone(), View::default()),
+				&mut virtual_overseer,
+			)
+			.await;
+		}
+
+		// consume peer view changes
+		{
+			let _peer_view_changes = network_handle.next_network_actions(2).await;
+		}
+
+		virtual_overseer
+			.send(FromOverseer::Communication {
+				msg: NetworkBridgeMessage::SetGossipPaused {
+					subsystem: GossipSubsystem::ApprovalDistribution,
+					paused: true,
+				},
+			})
+			.await;
+
+		let approval_distribution_message =
+			protocol_v1::ApprovalDistributionMessage::Approvals(Vec::new());
+		let message =
+			protocol_v1::ValidationProtocol::ApprovalDistribution(approval_distribution_message);
+
+		virtual_overseer
+			.send(FromOverseer::Communication {
+				msg: NetworkBridgeMessage::SendValidationMessage(
+					vec![peer.clone()],
+					message.clone(),
+				),
+			})
+			.await;
+
+		let collator_protocol_message = protocol_v1::CollatorProtocolMessage::Declare(
+			Sr25519Keyring::Alice.public().into(),
+			0_u32.into(),
+			dummy_collator_signature(),
+		);
+		let collation_message =
+			protocol_v1::CollationProtocol::CollatorProtocol(collator_protocol_message);
+
+		virtual_overseer
+			.send(FromOverseer::Communication {
+				msg: NetworkBridgeMessage::SendCollationMessage(
+					vec![peer.clone()],
+					collation_message.clone(),
+				),
+			})
+			.await;
+
+		// The approval distribution message is not sent.
+		assert_eq!(
+			network_handle.next_network_action().await,
+			NetworkAction::WriteNotification(
+				peer.clone(),
+				PeerSet::Collation,
+				WireMessage::ProtocolMessage(collation_message).encode(),
+			)
+		);
+
+		network_handle
+			.peer_message(
+				peer.clone(),
+				PeerSet::Validation,
+				WireMessage::ProtocolMessage(message.clone()).encode(),
+			)
+			.await;
+
+		network_handle.disconnect_peer(peer.clone(), PeerSet::Validation).await;
+
+		// The approval distribution message is not received either.
+		assert_sends_validation_event_to_all(
+			NetworkBridgeEvent::PeerDisconnected(peer.clone()),
+			&mut virtual_overseer,
+		)
+		.await;
+
+		virtual_overseer
+			.send(FromOverseer::Communication {
+				msg: NetworkBridgeMessage::SetGossipPaused {
+					subsystem: GossipSubsystem::ApprovalDistribution,
+					paused: false,
+				},
+			})
+			.await;
+
+		virtual_overseer
+			.send(FromOverseer::Communication {
+				msg: NetworkBridgeMessage::SendValidationMessage(
+					vec![peer.clone()],
+					message.clone(),
+				),
+			})
+			.await;
+
+		assert_eq!(
+			network_handle.next_network_action().await,
+			NetworkAction::WriteNotification(
+				peer,
+				PeerSet::Validation,
+				WireMessage::ProtocolMessage(message).encode(),
+			)
+		);
+		virtual_overseer
+	});
+}
+
 #[test]
 fn spread_event_to_subsystems_is_up_to_date() {
 	// Number of subsystems expected to be interested in a network event,
@@ -1286,3 +1456,103 @@ fn our_view_updates_decreasing_order_and_limited_to_max() {
 		virtual_overseer
 	});
 }
+
+#[test]
+fn authority_drops_validation_messages_from_unknown_peers() {
+	let authority_peer = PeerId::random();
+	let unknown_peer = PeerId::random();
+	let authority_ids: HashSet<AuthorityDiscoveryId> =
+		vec![Sr25519Keyring::Alice.public().into()].into_iter().collect();
+	let authorities = vec![(authority_peer.clone(), authority_ids.clone())].into_iter().collect();
+
+	test_harness_with_authorities(
+		done_syncing_oracle(),
+		IsAuthority::Yes,
+		authorities,
+		|test_harness| async move {
+			let TestHarness { mut network_handle, mut virtual_overseer } = test_harness;
+
+			network_handle
+				.connect_peer(authority_peer.clone(), PeerSet::Validation, ObservedRole::Authority)
+				.await;
+			network_handle
+				.connect_peer(unknown_peer.clone(), PeerSet::Validation, ObservedRole::Full)
+				.await;
+
+			assert_sends_validation_event_to_all(
+				NetworkBridgeEvent::PeerConnected(
+					authority_peer.clone(),
+					ObservedRole::Authority,
+					Some(authority_ids),
+				),
+				&mut virtual_overseer,
+			)
+			.await;
+
+			assert_sends_validation_event_to_all(
+				NetworkBridgeEvent::PeerViewChange(authority_peer.clone(), View::default()),
+				&mut virtual_overseer,
+			)
+			.await;
+
+			assert_sends_validation_event_to_all(
+				NetworkBridgeEvent::PeerConnected(unknown_peer.clone(), ObservedRole::Full, None),
+				&mut virtual_overseer,
+			)
+			.await;
+
+			assert_sends_validation_event_to_all(
+				NetworkBridgeEvent::PeerViewChange(unknown_peer.clone(), View::default()),
+				&mut virtual_overseer,
+			)
+			.await;
+
+			let approval_distribution_message =
+				protocol_v1::ApprovalDistributionMessage::Approvals(Vec::new());
+
+			let message = protocol_v1::ValidationProtocol::ApprovalDistribution(
+				approval_distribution_message.clone(),
+			);
+
+			network_handle
+				.peer_message(
+					unknown_peer.clone(),
+					PeerSet::Validation,
+					WireMessage::ProtocolMessage(message.clone()).encode(),
+				)
+				.await;
+
+			network_handle
+				.peer_message(
+					authority_peer.clone(),
+					PeerSet::Validation,
+					WireMessage::ProtocolMessage(message.clone()).encode(),
+				)
+				.await;
+
+			// Only the message of the authority makes it to the subsystems.
+			assert_matches!(
+				virtual_overseer.recv().await,
+				AllMessages::ApprovalDistribution(
+					ApprovalDistributionMessage::NetworkBridgeUpdateV1(
+						NetworkBridgeEvent::PeerMessage(p, m)
+					)
+				) => {
+					assert_eq!(p, authority_peer);
+					assert_eq!(m, approval_distribution_message);
+				}
+			);
+
+			let actions = network_handle.next_network_actions(3).await;
+			assert_network_actions_contains(
+				&actions,
+				&NetworkAction::ReputationChange(
+					unknown_peer.clone(),
+					UNAUTHENTICATED_VALIDATION_MESSAGE_COST,
+				),
+			);
+
+			virtual_overseer
+		},
+	);
+}
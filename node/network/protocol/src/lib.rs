@@ -460,3 +460,192 @@ pub mod v1 {
 		payload
 	}
 }
+
+/// v2 protocol types.
+///
+/// Version 2 of the validation protocol batches the statements and bitfields gossiped under the
+/// same relay parent into a single notification and sends bitfields in a compact form, which
+/// cuts the per-message overhead at high validator counts. Nodes keep speaking v1 until the
+/// validation peer-set negotiates the protocol version with each peer.
+pub mod v2 {
+	use parity_scale_codec::{Compact, Decode, Encode};
+	use std::convert::TryFrom;
+
+	use selendra_primitives::v1::{
+		AvailabilityBitfield, Hash, UncheckedSignedAvailabilityBitfield, ValidatorIndex,
+		ValidatorSignature,
+	};
+
+	use selendra_node_primitives::UncheckedSignedFullStatement;
+
+	pub use super::v1::{ApprovalDistributionMessage, StatementMetadata};
+
+	/// The name of the v2 validation protocol.
+	pub const VALIDATION_PROTOCOL_NAME: &str = "/selendra/validation/2";
+
+	/// An availability bitfield in the shorter of two encodings.
+	#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+	pub enum CompactBitfield {
+		/// The bitfield as is.
+		#[codec(index = 0)]
+		Plain(AvailabilityBitfield),
+		/// The lengths of the alternating runs of unset and set bits, starting with unset bits.
+		///
+		/// Each run length is the distance between two positions the bitfield flips at, so
+		/// bitfields which are mostly set or mostly unset encode into a few bytes regardless of
+		/// the number of cores.
+		#[codec(index = 1)]
+		Runs(Vec<Compact<u32>>),
+	}
+
+	impl CompactBitfield {
+		/// Compact the given bitfield.
+		pub fn new(bitfield: &AvailabilityBitfield) -> Self {
+			let mut runs = Vec::new();
+			let (mut current, mut run) = (false, 0u32);
+			for bit in bitfield.0.iter().map(|bit| *bit) {
+				if bit != current {
+					runs.push(Compact(run));
+					current = bit;
+					run = 0;
+				}
+				run += 1;
+			}
+			runs.push(Compact(run));
+
+			let runs = CompactBitfield::Runs(runs);
+			let plain = CompactBitfield::Plain(bitfield.clone());
+			if runs.encoded_size() < plain.encoded_size() {
+				runs
+			} else {
+				plain
+			}
+		}
+
+		/// Expand into the original bitfield, unless it would be longer than `max_len` bits.
+		pub fn expand(self, max_len: usize) -> Option<AvailabilityBitfield> {
+			match self {
+				CompactBitfield::Plain(bitfield) =>
+					if bitfield.0.len() <= max_len {
+						Some(bitfield)
+					} else {
+						None
+					},
+				CompactBitfield::Runs(runs) => {
+					let len = runs.iter().try_fold(0usize, |len, run| {
+						len.checked_add(run.0 as usize).filter(|len| *len <= max_len)
+					})?;
+					let mut bits = Vec::with_capacity(len);
+					for (i, run) in runs.into_iter().enumerate() {
+						bits.extend(std::iter::repeat(i % 2 == 1).take(run.0 as usize));
+					}
+					Some(AvailabilityBitfield(bits.into_iter().collect()))
+				},
+			}
+		}
+	}
+
+	/// A signed availability bitfield with a compact payload.
+	///
+	/// The signature still covers the full bitfield, which receivers restore before checking it.
+	#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+	pub struct CompactSignedBitfield {
+		/// The compacted bitfield.
+		pub bitfield: CompactBitfield,
+		/// The index of the validator which signed the bitfield.
+		pub validator_index: ValidatorIndex,
+		/// The signature of the full bitfield.
+		pub signature: ValidatorSignature,
+	}
+
+	impl CompactSignedBitfield {
+		/// Compact a signed bitfield.
+		pub fn new(signed: &UncheckedSignedAvailabilityBitfield) -> Self {
+			CompactSignedBitfield {
+				bitfield: CompactBitfield::new(signed.unchecked_payload()),
+				validator_index: signed.unchecked_validator_index(),
+				signature: signed.unchecked_signature().clone(),
+			}
+		}
+
+		/// Restore the signed bitfield, unless it would be longer than `max_len` bits.
+		pub fn expand(self, max_len: usize) -> Option<UncheckedSignedAvailabilityBitfield> {
+			Some(UncheckedSignedAvailabilityBitfield::new(
+				self.bitfield.expand(max_len)?,
+				self.validator_index,
+				self.signature,
+			))
+		}
+	}
+
+	/// Network messages used by the bitfield distribution subsystem.
+	#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+	pub enum BitfieldDistributionMessage {
+		/// Signed availability bitfields for a given relay-parent hash.
+		#[codec(index = 0)]
+		Bitfields(Hash, Vec<CompactSignedBitfield>),
+	}
+
+	/// Network messages used by the statement distribution subsystem.
+	#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+	pub enum StatementDistributionMessage {
+		/// Signed full statements under a given relay-parent.
+		#[codec(index = 0)]
+		Statements(Hash, Vec<UncheckedSignedFullStatement>),
+		/// Seconded statement with large payload (e.g. containing a runtime upgrade).
+		///
+		/// We only gossip the hash in that case, actual payloads can be fetched from sending node
+		/// via request/response.
+		#[codec(index = 1)]
+		LargeStatement(StatementMetadata),
+	}
+
+	/// All network messages on the validation peer-set.
+	#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+	pub enum ValidationProtocol {
+		/// Bitfield distribution messages
+		#[codec(index = 1)]
+		BitfieldDistribution(BitfieldDistributionMessage),
+		/// Statement distribution messages
+		#[codec(index = 3)]
+		StatementDistribution(StatementDistributionMessage),
+		/// Approval distribution messages
+		#[codec(index = 4)]
+		ApprovalDistribution(ApprovalDistributionMessage),
+	}
+
+	impl_try_from!(ValidationProtocol, BitfieldDistribution, BitfieldDistributionMessage);
+	impl_try_from!(ValidationProtocol, StatementDistribution, StatementDistributionMessage);
+	impl_try_from!(ValidationProtocol, ApprovalDistribution, ApprovalDistributionMessage);
+
+	#[cfg(test)]
+	mod tests {
+		use super::*;
+
+		fn bitfield(bits: &[bool]) -> AvailabilityBitfield {
+			AvailabilityBitfield(bits.iter().copied().collect())
+		}
+
+		#[test]
+		fn compact_bitfield_round_trips() {
+			let mostly_set = bitfield(&[true; 300]);
+			let compact = CompactBitfield::new(&mostly_set);
+			assert_eq!(compact, CompactBitfield::Runs(vec![Compact(0), Compact(300)]));
+			assert_eq!(compact.expand(300), Some(mostly_set));
+
+			let alternating: Vec<_> = (0..16).map(|i| i % 2 == 0).collect();
+			let compact = CompactBitfield::new(&bitfield(&alternating));
+			assert_eq!(compact, CompactBitfield::Plain(bitfield(&alternating)));
+			assert_eq!(compact.expand(16), Some(bitfield(&alternating)));
+
+			assert_eq!(CompactBitfield::new(&bitfield(&[])).expand(0), Some(bitfield(&[])));
+		}
+
+		#[test]
+		fn compact_bitfield_is_bounded() {
+			let compact = CompactBitfield::Runs(vec![Compact(u32::MAX), Compact(u32::MAX)]);
+			assert_eq!(compact.expand(1000), None);
+			assert_eq!(CompactBitfield::new(&bitfield(&[false; 8])).expand(7), None);
+		}
+	}
+}
@@ -71,6 +71,8 @@ pub enum Protocol {
 	StatementFetching,
 	/// Sending of dispute statements with application level confirmations.
 	DisputeSending,
+	/// Querying `ParachainHost` runtime APIs from a trusted full node, used by light collators.
+	RuntimeApiProxying,
 }
 
 /// Minimum bandwidth we expect for validators - 500Mbit/s is the recommendation, so approximately
@@ -82,7 +84,6 @@ const MIN_BANDWIDTH_BYTES: u64 = 50 * 1024 * 1024;
 /// When decreasing this value, take into account that the very first request might need to open a
 /// connection, which can be slow. If this causes problems, we should ensure connectivity via peer
 /// sets.
-#[allow(dead_code)]
 const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(3);
 
 /// Request timeout where we can assume the connection is already open (e.g. we have peers in a
@@ -189,6 +190,14 @@ impl Protocol {
 				request_timeout: Duration::from_secs(12),
 				inbound_queue: Some(tx),
 			},
+			Protocol::RuntimeApiProxying => RequestResponseConfig {
+				name: p_name,
+				max_request_size: 1_000,
+				// The downward message queue of a para dominates the response size.
+				max_response_size: POV_RESPONSE_SIZE,
+				request_timeout: DEFAULT_REQUEST_TIMEOUT,
+				inbound_queue: Some(tx),
+			},
 		};
 		(rx, cfg)
 	}
@@ -234,6 +243,8 @@ impl Protocol {
 			// average, so something in the ballpark of 100 should be fine. Nodes will retry on
 			// failure, so having a good value here is mostly about performance tuning.
 			Protocol::DisputeSending => 100,
+			// Collators query a few calls per relay chain block.
+			Protocol::RuntimeApiProxying => 100,
 		}
 	}
 
@@ -251,6 +262,7 @@ impl Protocol {
 			Protocol::AvailableDataFetching => "/selendra/req_available_data/1",
 			Protocol::StatementFetching => "/selendra/req_statement/1",
 			Protocol::DisputeSending => "/selendra/send_dispute/1",
+			Protocol::RuntimeApiProxying => "/selendra/req_runtime_api/1",
 		}
 	}
 }
@@ -40,6 +40,8 @@ pub enum Requests {
 	StatementFetching(OutgoingRequest<v1::StatementFetchingRequest>),
 	/// Requests for notifying about an ongoing dispute.
 	DisputeSending(OutgoingRequest<v1::DisputeRequest>),
+	/// Query a `ParachainHost` runtime API from a trusted full node.
+	RuntimeApiProxying(OutgoingRequest<v1::RuntimeApiProxyRequest>),
 }
 
 impl Requests {
@@ -52,6 +54,7 @@ impl Requests {
 			Self::AvailableDataFetching(_) => Protocol::AvailableDataFetching,
 			Self::StatementFetching(_) => Protocol::StatementFetching,
 			Self::DisputeSending(_) => Protocol::DisputeSending,
+			Self::RuntimeApiProxying(_) => Protocol::RuntimeApiProxying,
 		}
 	}
 
@@ -70,6 +73,7 @@ impl Requests {
 			Self::AvailableDataFetching(r) => r.encode_request(),
 			Self::StatementFetching(r) => r.encode_request(),
 			Self::DisputeSending(r) => r.encode_request(),
+			Self::RuntimeApiProxying(r) => r.encode_request(),
 		}
 	}
 }
@@ -22,8 +22,11 @@ use selendra_node_primitives::{
 	AvailableData, DisputeMessage, ErasureChunk, PoV, Proof, UncheckedDisputeMessage,
 };
 use selendra_primitives::v1::{
-	CandidateHash, CandidateReceipt, CommittedCandidateReceipt, Hash, Id as ParaId, ValidatorIndex,
+	BlockNumber, CandidateHash, CandidateReceipt, CommittedCandidateReceipt, Hash, Id as ParaId,
+	InboundDownwardMessage, OccupiedCoreAssumption, PersistedValidationData, ValidatorId,
+	ValidatorIndex,
 };
+use sp_core::{ed25519, Pair};
 
 use super::{IsRequest, Protocol};
 
@@ -215,3 +218,115 @@ impl IsRequest for DisputeRequest {
 	type Response = DisputeResponse;
 	const PROTOCOL: Protocol = Protocol::DisputeSending;
 }
+
+/// Query a `ParachainHost` runtime API call from a trusted full node.
+#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+pub struct RuntimeApiProxyRequest {
+	/// Block whose state the call should be executed on.
+	pub relay_parent: Hash,
+	/// The call to execute.
+	pub call: RuntimeApiCall,
+}
+
+/// `ParachainHost` runtime API calls that can be proxied.
+#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+pub enum RuntimeApiCall {
+	/// The current validators.
+	#[codec(index = 0)]
+	Validators,
+	/// The persisted validation data of a para under the given assumption.
+	#[codec(index = 1)]
+	PersistedValidationData(ParaId, OccupiedCoreAssumption),
+	/// The pending messages in the downward message queue of a para.
+	#[codec(index = 2)]
+	DmqContents(ParaId),
+}
+
+impl RuntimeApiCall {
+	/// Whether the given answer is of the kind this call returns.
+	pub fn is_answered_by(&self, answer: &RuntimeApiAnswer) -> bool {
+		match (self, answer) {
+			(RuntimeApiCall::Validators, RuntimeApiAnswer::Validators(_)) |
+			(
+				RuntimeApiCall::PersistedValidationData(..),
+				RuntimeApiAnswer::PersistedValidationData(_),
+			) |
+			(RuntimeApiCall::DmqContents(_), RuntimeApiAnswer::DmqContents(_)) => true,
+			_ => false,
+		}
+	}
+}
+
+/// The result of a proxied runtime API call.
+#[derive(Debug, Clone, Encode, Decode, PartialEq)]
+pub enum RuntimeApiAnswer {
+	/// Answer to [`RuntimeApiCall::Validators`].
+	#[codec(index = 0)]
+	Validators(Vec<ValidatorId>),
+	/// Answer to [`RuntimeApiCall::PersistedValidationData`].
+	#[codec(index = 1)]
+	PersistedValidationData(Option<PersistedValidationData>),
+	/// Answer to [`RuntimeApiCall::DmqContents`].
+	#[codec(index = 2)]
+	DmqContents(Vec<InboundDownwardMessage<BlockNumber>>),
+}
+
+/// Responses to a `RuntimeApiProxyRequest`.
+#[derive(Debug, Clone, Encode, Decode)]
+pub enum RuntimeApiProxyResponse {
+	/// The answer to the call, signed by the responding node.
+	#[codec(index = 0)]
+	Answer {
+		/// The result of the call.
+		answer: RuntimeApiAnswer,
+		/// Key of the responding node.
+		signer: ed25519::Public,
+		/// Signature of the signer over the request and the answer.
+		signature: ed25519::Signature,
+	},
+	/// The call could not be executed, e.g. because the state of the block is not available.
+	#[codec(index = 1)]
+	Unavailable,
+}
+
+impl RuntimeApiProxyRequest {
+	fn signing_payload(&self, answer: &RuntimeApiAnswer) -> Vec<u8> {
+		(b"runtime-api-proxy", self, answer).encode()
+	}
+
+	/// Answer this request, signing the answer with the given key.
+	pub fn answer(&self, answer: RuntimeApiAnswer, key: &ed25519::Pair) -> RuntimeApiProxyResponse {
+		let signature = key.sign(&self.signing_payload(&answer));
+		RuntimeApiProxyResponse::Answer { answer, signer: key.public(), signature }
+	}
+}
+
+impl RuntimeApiProxyResponse {
+	/// Check that the response answers the given request and is signed by one of the trusted keys.
+	///
+	/// Returns the answer if so.
+	pub fn verify(
+		self,
+		request: &RuntimeApiProxyRequest,
+		trusted: &[ed25519::Public],
+	) -> Option<RuntimeApiAnswer> {
+		match self {
+			RuntimeApiProxyResponse::Answer { answer, signer, signature } => {
+				let valid = request.call.is_answered_by(&answer) &&
+					trusted.contains(&signer) &&
+					ed25519::Pair::verify(
+						&signature,
+						&request.signing_payload(&answer),
+						&signer,
+					);
+				valid.then(|| answer)
+			},
+			RuntimeApiProxyResponse::Unavailable => None,
+		}
+	}
+}
+
+impl IsRequest for RuntimeApiProxyRequest {
+	type Response = RuntimeApiProxyResponse;
+	const PROTOCOL: Protocol = Protocol::RuntimeApiProxying;
+}
@@ -0,0 +1,103 @@
+// Copyright 2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Our own statements persisted per active leaf across restarts.
+//!
+//! Every statement we share is written to the parachains database, keyed by the relay parent it
+//! was made at. When a leaf is activated again after a restart, the statements we made at it are
+//! circulated again, instead of the node going silent until the next leaf. Statements are dropped
+//! once their leaf is no longer active.
+
+use parity_scale_codec::{Decode, Encode};
+use selendra_node_primitives::{SignedFullStatement, UncheckedSignedFullStatement};
+use selendra_node_subsystem_util::database::{DBTransaction, Database};
+use selendra_primitives::v1::Hash;
+use std::{collections::BTreeMap, sync::Arc};
+
+use crate::LOG_TARGET;
+
+/// Key our own statements are stored under.
+const OWN_STATEMENTS_KEY: &[u8] = b"OwnStatements";
+
+/// Configuration for persisting our own statements.
+#[derive(Debug, Clone)]
+pub struct Config {
+	/// The column in the database the statements are stored in.
+	pub col_data: u32,
+}
+
+/// The statements we shared at each active leaf, backed by the database.
+pub(crate) struct OwnStatements {
+	db: Arc<dyn Database>,
+	config: Config,
+	by_leaf: BTreeMap<Hash, Vec<UncheckedSignedFullStatement>>,
+}
+
+impl OwnStatements {
+	/// Load the statements persisted before a restart.
+	pub fn load(db: Arc<dyn Database>, config: Config) -> Self {
+		let by_leaf = match db.get(config.col_data, OWN_STATEMENTS_KEY) {
+			Ok(Some(raw)) => Decode::decode(&mut &raw[..])
+				.map_err(
+					|err| tracing::warn!(target: LOG_TARGET, ?err, "Corrupted own statements in db"),
+				)
+				.unwrap_or_default(),
+			Ok(None) => BTreeMap::new(),
+			Err(err) => {
+				tracing::warn!(target: LOG_TARGET, ?err, "Reading own statements failed");
+				BTreeMap::new()
+			},
+		};
+
+		OwnStatements { db, config, by_leaf }
+	}
+
+	/// The statements we made at `relay_parent`.
+	pub fn at(&self, relay_parent: &Hash) -> Vec<UncheckedSignedFullStatement> {
+		self.by_leaf.get(relay_parent).cloned().unwrap_or_default()
+	}
+
+	/// Note a statement we shared at `relay_parent`.
+	pub fn note(&mut self, relay_parent: Hash, statement: &SignedFullStatement) {
+		let statement = statement.as_unchecked();
+		let statements = self.by_leaf.entry(relay_parent).or_default();
+		if !statements.contains(statement) {
+			statements.push(statement.clone());
+			self.persist();
+		}
+	}
+
+	/// Drop the statements of all leaves which are no longer active.
+	pub fn retain_active(&mut self, is_active: impl Fn(&Hash) -> bool) {
+		let before = self.by_leaf.len();
+		self.by_leaf.retain(|relay_parent, _| is_active(relay_parent));
+		if self.by_leaf.len() != before {
+			self.persist();
+		}
+	}
+
+	fn persist(&self) {
+		let mut tx = DBTransaction::new();
+		if self.by_leaf.is_empty() {
+			tx.delete(self.config.col_data, OWN_STATEMENTS_KEY);
+		} else {
+			tx.put_vec(self.config.col_data, OWN_STATEMENTS_KEY, self.by_leaf.encode());
+		}
+		if let Err(err) = self.db.write(tx) {
+			tracing::warn!(target: LOG_TARGET, ?err, "Writing own statements failed");
+		}
+	}
+}
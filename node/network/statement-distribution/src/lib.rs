@@ -32,7 +32,7 @@ use selendra_node_network_protocol::{
 	IfDisconnected, PeerId, UnifiedReputationChange as Rep, View,
 };
 use selendra_node_primitives::{SignedFullStatement, Statement, UncheckedSignedFullStatement};
-use selendra_node_subsystem_util::{self as util, MIN_GOSSIP_PEERS};
+use selendra_node_subsystem_util::{self as util, database::Database, MIN_GOSSIP_PEERS};
 
 use selendra_primitives::v1::{
 	AuthorityDiscoveryId, CandidateHash, CommittedCandidateReceipt, CompactStatement, Hash,
@@ -54,10 +54,14 @@ use futures::{
 	prelude::*,
 };
 use indexmap::{map::Entry as IEntry, IndexMap};
+use lru::LruCache;
 use sp_keystore::SyncCryptoStorePtr;
 use util::runtime::RuntimeInfo;
 
-use std::collections::{hash_map::Entry, HashMap, HashSet};
+use std::{
+	collections::{hash_map::Entry, HashMap, HashSet},
+	sync::Arc,
+};
 
 use fatality::Nested;
 
@@ -76,6 +80,11 @@ use responder::{respond, ResponderMessage};
 pub(crate) mod metrics;
 use metrics::Metrics;
 
+/// Our own statements persisted across restarts.
+mod checkpoint;
+pub use checkpoint::Config;
+use checkpoint::OwnStatements;
+
 #[cfg(test)]
 mod tests;
 
@@ -113,6 +122,20 @@ const LOG_TARGET: &str = "parachain::statement-distribution";
 /// Large statements should be rare.
 const MAX_LARGE_STATEMENTS_PER_SENDER: usize = 20;
 
+/// The maximum number of relay parents we track the knowledge of per peer.
+///
+/// The views of peers are bounded by the network bridge already, this keeps our state bounded
+/// independently of it. Beyond it, the knowledge of the relay parent the peer advertised the
+/// longest ago is evicted.
+const MAX_RELAY_PARENTS_PER_PEER: usize = 8;
+
+/// The maximum number of statements we track per peer and relay parent, in either direction, and
+/// of candidates we count the received statements of.
+///
+/// This is well above what honest peers exchange, given the flood protection. Beyond it, the
+/// least recently used entries are evicted.
+const MAX_KNOWN_STATEMENTS_PER_RELAY_PARENT: usize = 4096;
+
 /// The statement distribution subsystem.
 pub struct StatementDistributionSubsystem {
 	/// Pointer to a keystore, which is required for determining this node's validator index.
@@ -121,6 +144,10 @@ pub struct StatementDistributionSubsystem {
 	req_receiver: Option<IncomingRequestReceiver<request_v1::StatementFetchingRequest>>,
 	/// Prometheus metrics
 	metrics: Metrics,
+	/// Database our own statements are persisted in.
+	db: Arc<dyn Database>,
+	/// Configuration for persisting our own statements.
+	config: Config,
 }
 
 impl<Context> overseer::Subsystem<Context, SubsystemError> for StatementDistributionSubsystem
@@ -147,8 +174,10 @@ impl StatementDistributionSubsystem {
 		keystore: SyncCryptoStorePtr,
 		req_receiver: IncomingRequestReceiver<request_v1::StatementFetchingRequest>,
 		metrics: Metrics,
+		db: Arc<dyn Database>,
+		config: Config,
 	) -> Self {
-		Self { keystore, req_receiver: Some(req_receiver), metrics }
+		Self { keystore, req_receiver: Some(req_receiver), metrics, db, config }
 	}
 }
 
@@ -202,6 +231,54 @@ fn note_hash(
 	observed.try_push(h).is_ok()
 }
 
+/// Knowledge of at most `MAX_KNOWN_STATEMENTS_PER_RELAY_PARENT` entries, evicting the least
+/// recently used one when full.
+struct BoundedKnowledge<K: std::hash::Hash + Eq, V> {
+	entries: LruCache<K, V>,
+	/// The number of entries evicted since the last `take_evicted`.
+	evicted: usize,
+}
+
+impl<K: std::hash::Hash + Eq, V> Default for BoundedKnowledge<K, V> {
+	fn default() -> Self {
+		// The bound is enforced on insertion, so that no memory is reserved up front.
+		Self { entries: LruCache::unbounded(), evicted: 0 }
+	}
+}
+
+impl<K: std::hash::Hash + Eq, V> BoundedKnowledge<K, V> {
+	fn contains(&self, key: &K) -> bool {
+		self.entries.contains(key)
+	}
+
+	fn get(&self, key: &K) -> Option<&V> {
+		self.entries.peek(key)
+	}
+
+	fn len(&self) -> usize {
+		self.entries.len()
+	}
+
+	fn is_empty(&self) -> bool {
+		self.entries.is_empty()
+	}
+
+	/// Insert or update an entry, making it the most recently used one.
+	fn insert(&mut self, key: K, value: V) {
+		if !self.entries.contains(&key) &&
+			self.entries.len() >= MAX_KNOWN_STATEMENTS_PER_RELAY_PARENT
+		{
+			self.entries.pop_lru();
+			self.evicted += 1;
+		}
+		self.entries.put(key, value);
+	}
+
+	fn take_evicted(&mut self) -> usize {
+		std::mem::take(&mut self.evicted)
+	}
+}
+
 /// knowledge that a peer has about goings-on in a relay parent.
 #[derive(Default)]
 struct PeerRelayParentKnowledge {
@@ -212,14 +289,14 @@ struct PeerRelayParentKnowledge {
 	received_candidates: HashSet<CandidateHash>,
 	/// fingerprints of all statements a peer should be aware of: those that
 	/// were sent to the peer by us.
-	sent_statements: HashSet<(CompactStatement, ValidatorIndex)>,
+	sent_statements: BoundedKnowledge<(CompactStatement, ValidatorIndex), ()>,
 	/// fingerprints of all statements a peer should be aware of: those that
 	/// were sent to us by the peer.
-	received_statements: HashSet<(CompactStatement, ValidatorIndex)>,
+	received_statements: BoundedKnowledge<(CompactStatement, ValidatorIndex), ()>,
 	/// How many candidates this peer is aware of for each given validator index.
 	seconded_counts: HashMap<ValidatorIndex, VcPerPeerTracker>,
 	/// How many statements we've received for each candidate that we're aware of.
-	received_message_count: HashMap<CandidateHash, usize>,
+	received_message_count: BoundedKnowledge<CandidateHash, usize>,
 
 	/// How many large statements this peer already sent us.
 	///
@@ -272,7 +349,7 @@ impl PeerRelayParentKnowledge {
 			CompactStatement::Valid(_) => false,
 		};
 
-		self.sent_statements.insert(fingerprint.clone());
+		self.sent_statements.insert(fingerprint.clone(), ());
 
 		new_known
 	}
@@ -347,18 +424,15 @@ impl PeerRelayParentKnowledge {
 			},
 		};
 
-		{
-			let received_per_candidate =
-				self.received_message_count.entry(*candidate_hash).or_insert(0);
-
-			if *received_per_candidate >= max_message_count {
-				return Err(COST_APPARENT_FLOOD)
-			}
+		let received_per_candidate =
+			self.received_message_count.get(candidate_hash).copied().unwrap_or(0);
 
-			*received_per_candidate += 1;
+		if received_per_candidate >= max_message_count {
+			return Err(COST_APPARENT_FLOOD)
 		}
 
-		self.received_statements.insert(fingerprint.clone());
+		self.received_message_count.insert(*candidate_hash, received_per_candidate + 1);
+		self.received_statements.insert(fingerprint.clone(), ());
 		Ok(self.received_candidates.insert(candidate_hash.clone()))
 	}
 
@@ -421,11 +495,19 @@ impl PeerRelayParentKnowledge {
 	fn is_known_candidate(&self, candidate: &CandidateHash) -> bool {
 		self.sent_candidates.contains(candidate) || self.received_candidates.contains(candidate)
 	}
+
+	/// The number of statements and statement counts evicted since the last call.
+	fn take_evicted(&mut self) -> usize {
+		self.sent_statements.take_evicted() +
+			self.received_statements.take_evicted() +
+			self.received_message_count.take_evicted()
+	}
 }
 
 struct PeerData {
 	view: View,
-	view_knowledge: HashMap<Hash, PeerRelayParentKnowledge>,
+	/// Knowledge per relay parent, in the order the peer advertised them.
+	view_knowledge: IndexMap<Hash, PeerRelayParentKnowledge>,
 	/// Peer might be known as authority with the given ids.
 	maybe_authority: Option<HashSet<AuthorityDiscoveryId>>,
 }
@@ -444,15 +526,19 @@ impl PeerData {
 		&mut self,
 		relay_parent: &Hash,
 		fingerprint: &(CompactStatement, ValidatorIndex),
+		metrics: &Metrics,
 	) -> bool {
 		debug_assert!(
 			self.can_send(relay_parent, fingerprint),
 			"send is only called after `can_send` returns true; qed",
 		);
-		self.view_knowledge
+		let knowledge = self
+			.view_knowledge
 			.get_mut(relay_parent)
-			.expect("send is only called after `can_send` returns true; qed")
-			.send(fingerprint)
+			.expect("send is only called after `can_send` returns true; qed");
+		let new_known = knowledge.send(fingerprint);
+		metrics.on_statements_evicted(knowledge.take_evicted());
+		new_known
 	}
 
 	/// This returns `None` if the peer cannot accept this statement, without altering internal
@@ -486,11 +572,15 @@ impl PeerData {
 		relay_parent: &Hash,
 		fingerprint: &(CompactStatement, ValidatorIndex),
 		max_message_count: usize,
+		metrics: &Metrics,
 	) -> std::result::Result<bool, Rep> {
-		self.view_knowledge
+		let knowledge = self
+			.view_knowledge
 			.get_mut(relay_parent)
-			.ok_or(COST_UNEXPECTED_STATEMENT_MISSING_KNOWLEDGE)?
-			.receive(fingerprint, max_message_count)
+			.ok_or(COST_UNEXPECTED_STATEMENT_MISSING_KNOWLEDGE)?;
+		let result = knowledge.receive(fingerprint, max_message_count);
+		metrics.on_statements_evicted(knowledge.take_evicted());
+		result
 	}
 
 	/// This method does the same checks as `receive` without modifying the internal state.
@@ -898,8 +988,16 @@ async fn circulate_statement_and_dependents(
 		match active_head.note_statement(statement) {
 			NotedStatement::Fresh(stored) => Some((
 				*stored.compact().candidate_hash(),
-				circulate_statement(gossip_peers, peers, ctx, relay_parent, stored, priority_peers)
-					.await,
+				circulate_statement(
+					gossip_peers,
+					peers,
+					ctx,
+					relay_parent,
+					stored,
+					priority_peers,
+					metrics,
+				)
+				.await,
 			)),
 			_ => None,
 		}
@@ -976,6 +1074,7 @@ async fn circulate_statement<'a>(
 	relay_parent: Hash,
 	stored: StoredStatement<'a>,
 	mut priority_peers: Vec<PeerId>,
+	metrics: &Metrics,
 ) -> Vec<PeerId> {
 	let fingerprint = stored.fingerprint();
 
@@ -1023,7 +1122,7 @@ async fn circulate_statement<'a>(
 			let new = peers
 				.get_mut(&peer_id)
 				.expect("a subset is taken above, so it exists; qed")
-				.send(&relay_parent, &fingerprint);
+				.send(&relay_parent, &fingerprint, metrics);
 			(peer_id, new)
 		})
 		.collect();
@@ -1066,7 +1165,7 @@ async fn send_statements_about(
 		if !peer_data.can_send(&relay_parent, &fingerprint) {
 			continue
 		}
-		peer_data.send(&relay_parent, &fingerprint);
+		peer_data.send(&relay_parent, &fingerprint, metrics);
 		let payload = statement_message(relay_parent, statement.statement.clone());
 
 		tracing::trace!(
@@ -1101,7 +1200,7 @@ async fn send_statements(
 		if !peer_data.can_send(&relay_parent, &fingerprint) {
 			continue
 		}
-		peer_data.send(&relay_parent, &fingerprint);
+		peer_data.send(&relay_parent, &fingerprint, metrics);
 		let payload = statement_message(relay_parent, statement.statement.clone());
 
 		tracing::trace!(
@@ -1121,6 +1220,71 @@ async fn send_statements(
 	}
 }
 
+/// Send the statements we made at the active heads to all peers which have not seen them yet,
+/// along with the statements depending on them.
+async fn rebroadcast_own_statements(
+	peers: &mut HashMap<PeerId, PeerData>,
+	active_heads: &HashMap<Hash, ActiveHeadData>,
+	own_statements: &OwnStatements,
+	ctx: &mut (impl SubsystemContext + overseer::SubsystemContext),
+	metrics: &Metrics,
+) {
+	for (relay_parent, active_head) in active_heads {
+		let own: HashSet<_> = own_statements
+			.at(relay_parent)
+			.iter()
+			.map(|s| (s.unchecked_payload().to_compact(), s.unchecked_validator_index()))
+			.collect();
+
+		for statement in active_head.statements().filter(|s| own.contains(&s.fingerprint())) {
+			let fingerprint = statement.fingerprint();
+			let peers_to_send: Vec<(PeerId, bool)> = peers
+				.iter_mut()
+				.filter(|(_, data)| data.can_send(relay_parent, &fingerprint))
+				.map(|(peer, data)| (peer.clone(), data.send(relay_parent, &fingerprint)))
+				.collect();
+
+			if peers_to_send.is_empty() {
+				continue
+			}
+
+			tracing::debug!(
+				target: LOG_TARGET,
+				?peers_to_send,
+				?relay_parent,
+				statement = ?statement.statement,
+				"Rebroadcasting own statement",
+			);
+			let payload = statement_message(*relay_parent, statement.statement.clone());
+			ctx.send_message(AllMessages::NetworkBridge(
+				NetworkBridgeMessage::SendValidationMessage(
+					peers_to_send.iter().map(|(p, _)| p.clone()).collect(),
+					payload,
+				),
+			))
+			.await;
+			metrics.on_statement_distributed();
+
+			// Peers learning about the candidate only now may receive the statements about it.
+			let candidate_hash = *fingerprint.0.candidate_hash();
+			for (peer, _) in peers_to_send.into_iter().filter(|(_, new)| *new) {
+				if let Some(peer_data) = peers.get_mut(&peer) {
+					send_statements_about(
+						peer,
+						peer_data,
+						ctx,
+						*relay_parent,
+						candidate_hash,
+						active_head,
+						metrics,
+					)
+					.await;
+				}
+			}
+		}
+	}
+}
+
 async fn report_peer(
 	ctx: &mut (impl SubsystemContext + overseer::SubsystemContext),
 	peer: PeerId,
@@ -1286,8 +1450,16 @@ async fn handle_incoming_message_and_circulate<'a>(
 		// statement before a `Seconded` statement. `Seconded` statements are the only ones
 		// that require dependents. Thus, if this is a `Seconded` statement for a candidate we
 		// were not aware of before, we cannot have any dependent statements from the candidate.
-		let _ = circulate_statement(gossip_peers, peers, ctx, relay_parent, statement, Vec::new())
-			.await;
+		let _ = circulate_statement(
+			gossip_peers,
+			peers,
+			ctx,
+			relay_parent,
+			statement,
+			Vec::new(),
+			metrics,
+		)
+		.await;
 	}
 }
 
@@ -1413,7 +1585,7 @@ async fn handle_incoming_message<'a>(
 	//
 	// Note that if the peer is sending us something that is not within their view,
 	// it will not be kept within their log.
-	match peer_data.receive(&relay_parent, &fingerprint, max_message_count) {
+	match peer_data.receive(&relay_parent, &fingerprint, max_message_count, metrics) {
 		Err(_) => {
 			unreachable!("checked in `check_can_receive` above; qed");
 		},
@@ -1473,7 +1645,7 @@ async fn update_peer_view_and_maybe_send_unlocked(
 
 	// Remove entries for all relay-parents in the old view but not the new.
 	for removed in old_view.difference(&peer_data.view) {
-		let _ = peer_data.view_knowledge.remove(removed);
+		let _ = peer_data.view_knowledge.shift_remove(removed);
 	}
 
 	let is_gossip_peer = gossip_peers.contains(&peer);
@@ -1488,6 +1660,10 @@ async fn update_peer_view_and_maybe_send_unlocked(
 	let new_view = peer_data.view.difference(&old_view).copied().collect::<Vec<_>>();
 	for new in new_view.iter().copied() {
 		peer_data.view_knowledge.insert(new, Default::default());
+		if peer_data.view_knowledge.len() > MAX_RELAY_PARENTS_PER_PEER {
+			peer_data.view_knowledge.shift_remove_index(0);
+			metrics.on_relay_parent_evicted();
+		}
 		if !lucky {
 			continue
 		}
@@ -1599,6 +1775,7 @@ impl StatementDistributionSubsystem {
 		let mut active_heads: HashMap<Hash, ActiveHeadData> = HashMap::new();
 
 		let mut runtime = RuntimeInfo::new(Some(self.keystore.clone()));
+		let mut own_statements = OwnStatements::load(self.db.clone(), self.config.clone());
 
 		// Sender/Receiver for getting news from our statement fetching tasks.
 		let (req_sender, mut req_receiver) = mpsc::channel(1);
@@ -1628,6 +1805,7 @@ impl StatementDistributionSubsystem {
 							&mut gossip_peers,
 							&mut authorities,
 							&mut active_heads,
+							&mut own_statements,
 							&req_sender,
 							result?,
 						)
@@ -1818,6 +1996,7 @@ impl StatementDistributionSubsystem {
 		gossip_peers: &mut HashSet<PeerId>,
 		authorities: &mut HashMap<AuthorityDiscoveryId, PeerId>,
 		active_heads: &mut HashMap<Hash, ActiveHeadData>,
+		own_statements: &mut OwnStatements,
 		req_sender: &mpsc::Sender<RequesterMessage>,
 		message: FromOverseer<StatementDistributionMessage>,
 	) -> Result<bool> {
@@ -1857,12 +2036,46 @@ impl StatementDistributionSubsystem {
 						.await?;
 					let session_info = &info.session_info;
 
+					let signing_context =
+						SigningContext { session_index, parent_hash: relay_parent };
+					let restored: Vec<_> = own_statements
+						.at(&relay_parent)
+						.into_iter()
+						.filter_map(|statement| {
+							let index = statement.unchecked_validator_index().0 as usize;
+							let key = session_info.validators.get(index)?;
+							statement.try_into_checked(&signing_context, key).ok()
+						})
+						.collect();
+
 					active_heads.entry(relay_parent).or_insert(ActiveHeadData::new(
 						session_info.validators.clone(),
 						session_index,
 						span,
 					));
+
+					// Circulate the statements we made at this leaf before a restart again.
+					for statement in restored {
+						tracing::debug!(
+							target: LOG_TARGET,
+							hash = ?relay_parent,
+							"Sharing statement made before restart",
+						);
+						self.share_statement(
+							ctx,
+							runtime,
+							peers,
+							gossip_peers,
+							authorities,
+							active_heads,
+							relay_parent,
+							statement,
+						)
+						.await?;
+					}
 				}
+
+				own_statements.retain_active(|hash| active_heads.contains_key(hash));
 			},
 			FromOverseer::Signal(OverseerSignal::BlockFinalized(..)) => {
 				// do nothing
@@ -1872,56 +2085,22 @@ impl StatementDistributionSubsystem {
 				StatementDistributionMessage::Share(relay_parent, statement) => {
 					let _timer = metrics.time_share();
 
-					// Make sure we have data in cache:
-					if is_statement_large(&statement) {
-						if let Statement::Seconded(committed) = &statement.payload() {
-							let active_head = active_heads
-								.get_mut(&relay_parent)
-								// This should never be out-of-sync with our view if the view
-								// updates correspond to actual `StartWork` messages.
-								.ok_or(JfyiError::NoSuchHead(relay_parent))?;
-							active_head.waiting_large_statements.insert(
-								statement.payload().candidate_hash(),
-								LargeStatementStatus::FetchedOrShared(committed.clone()),
-							);
-						}
-					}
-
-					let info = runtime.get_session_info(ctx.sender(), relay_parent).await?;
-					let session_info = &info.session_info;
-					let validator_info = &info.validator_info;
-
-					// Get peers in our group, so we can make sure they get our statement
-					// directly:
-					let group_peers = {
-						if let Some(our_group) = validator_info.our_group {
-							let our_group = &session_info.validator_groups[our_group.0 as usize];
-
-							our_group
-								.into_iter()
-								.filter_map(|i| {
-									if Some(*i) == validator_info.our_index {
-										return None
-									}
-									let authority_id = &session_info.discovery_keys[i.0 as usize];
-									authorities.get(authority_id).map(|p| *p)
-								})
-								.collect()
-						} else {
-							Vec::new()
-						}
-					};
-					circulate_statement_and_dependents(
-						gossip_peers,
+					own_statements.note(relay_parent, &statement);
+					self.share_statement(
+						ctx,
+						runtime,
 						peers,
+						gossip_peers,
+						authorities,
 						active_heads,
-						ctx,
 						relay_parent,
 						statement,
-						group_peers,
-						metrics,
 					)
-					.await;
+					.await?;
+				},
+				StatementDistributionMessage::RebroadcastOwn => {
+					rebroadcast_own_statements(peers, active_heads, own_statements, ctx, metrics)
+						.await;
 				},
 				StatementDistributionMessage::NetworkBridgeUpdateV1(event) => {
 					let _timer = metrics.time_network_bridge_update_v1();
@@ -1942,6 +2121,74 @@ impl StatementDistributionSubsystem {
 		}
 		Ok(false)
 	}
+
+	/// Circulate a statement of our own to our peers.
+	async fn share_statement(
+		&self,
+		ctx: &mut (impl SubsystemContext + overseer::SubsystemContext),
+		runtime: &mut RuntimeInfo,
+		peers: &mut HashMap<PeerId, PeerData>,
+		gossip_peers: &mut HashSet<PeerId>,
+		authorities: &mut HashMap<AuthorityDiscoveryId, PeerId>,
+		active_heads: &mut HashMap<Hash, ActiveHeadData>,
+		relay_parent: Hash,
+		statement: SignedFullStatement,
+	) -> Result<()> {
+		let metrics = &self.metrics;
+
+		// Make sure we have data in cache:
+		if is_statement_large(&statement) {
+			if let Statement::Seconded(committed) = &statement.payload() {
+				let active_head = active_heads
+					.get_mut(&relay_parent)
+					// This should never be out-of-sync with our view if the view
+					// updates correspond to actual `StartWork` messages.
+					.ok_or(JfyiError::NoSuchHead(relay_parent))?;
+				active_head.waiting_large_statements.insert(
+					statement.payload().candidate_hash(),
+					LargeStatementStatus::FetchedOrShared(committed.clone()),
+				);
+			}
+		}
+
+		let info = runtime.get_session_info(ctx.sender(), relay_parent).await?;
+		let session_info = &info.session_info;
+		let validator_info = &info.validator_info;
+
+		// Get peers in our group, so we can make sure they get our statement
+		// directly:
+		let group_peers = {
+			if let Some(our_group) = validator_info.our_group {
+				let our_group = &session_info.validator_groups[our_group.0 as usize];
+
+				our_group
+					.into_iter()
+					.filter_map(|i| {
+						if Some(*i) == validator_info.our_index {
+							return None
+						}
+						let authority_id = &session_info.discovery_keys[i.0 as usize];
+						authorities.get(authority_id).map(|p| *p)
+					})
+					.collect()
+			} else {
+				Vec::new()
+			}
+		};
+		circulate_statement_and_dependents(
+			gossip_peers,
+			peers,
+			active_heads,
+			ctx,
+			relay_parent,
+			statement,
+			group_peers,
+			metrics,
+		)
+		.await;
+
+		Ok(())
+	}
 }
 
 /// Check whether a peer knows about a candidate from us.
@@ -25,6 +25,7 @@ struct MetricsInner {
 	share: prometheus::Histogram,
 	network_bridge_update_v1: prometheus::Histogram,
 	statements_unexpected: prometheus::CounterVec<prometheus::U64>,
+	peer_knowledge_evicted: prometheus::CounterVec<prometheus::U64>,
 }
 
 /// Statement Distribution metrics.
@@ -97,6 +98,25 @@ impl Metrics {
 			metrics.statements_unexpected.with_label_values(&["large"]).inc();
 		}
 	}
+
+	/// Update the counter of statements evicted from the knowledge of peers
+	pub fn on_statements_evicted(&self, count: usize) {
+		if let Some(metrics) = &self.0 {
+			if count > 0 {
+				metrics
+					.peer_knowledge_evicted
+					.with_label_values(&["statements"])
+					.inc_by(count as u64);
+			}
+		}
+	}
+
+	/// Update the counter of relay parents evicted from the knowledge of peers
+	pub fn on_relay_parent_evicted(&self) {
+		if let Some(metrics) = &self.0 {
+			metrics.peer_knowledge_evicted.with_label_values(&["relay_parents"]).inc();
+		}
+	}
 }
 
 impl metrics::Metrics for Metrics {
@@ -159,6 +179,16 @@ impl metrics::Metrics for Metrics {
 				)?,
 				registry,
 			)?,
+			peer_knowledge_evicted: prometheus::register(
+				prometheus::CounterVec::new(
+					prometheus::Opts::new(
+						"selendra_parachain_statement_distribution_peer_knowledge_evicted_total",
+						"Number of entries evicted from the knowledge of peers to bound it.",
+					),
+					&["type"],
+				)?,
+				registry,
+			)?,
 		};
 		Ok(Metrics(Some(metrics)))
 	}
@@ -41,6 +41,13 @@ use sp_keyring::Sr25519Keyring;
 use sp_keystore::{CryptoStore, SyncCryptoStore, SyncCryptoStorePtr};
 use std::{iter::FromIterator as _, sync::Arc, time::Duration};
 
+fn test_db() -> Arc<dyn Database> {
+	Arc::new(selendra_node_subsystem_util::database::kvdb_impl::DbAdapter::new(
+		kvdb_memorydb::create(1),
+		&[],
+	))
+}
+
 #[test]
 fn active_head_accepts_only_2_seconded_per_validator() {
 	let validators = vec![
@@ -251,6 +258,32 @@ fn per_peer_relay_parent_knowledge_send() {
 	assert!(knowledge.received_message_count.get(&hash_a).is_none());
 }
 
+#[test]
+fn per_peer_relay_parent_knowledge_evicts_least_recently_used() {
+	let mut knowledge = PeerRelayParentKnowledge::default();
+
+	let hash_a = CandidateHash([1; 32].into());
+	let seconded = (CompactStatement::Seconded(hash_a), ValidatorIndex(0));
+	let valid = |i: usize| (CompactStatement::Valid(hash_a), ValidatorIndex(i as u32));
+
+	assert!(knowledge.send(&seconded));
+	for i in 1..=MAX_KNOWN_STATEMENTS_PER_RELAY_PARENT {
+		knowledge.send(&valid(i));
+	}
+
+	// The statement sent first made way for the last one.
+	assert_eq!(knowledge.sent_statements.len(), MAX_KNOWN_STATEMENTS_PER_RELAY_PARENT);
+	assert!(!knowledge.sent_statements.contains(&seconded));
+	assert!(knowledge
+		.sent_statements
+		.contains(&valid(MAX_KNOWN_STATEMENTS_PER_RELAY_PARENT)));
+	assert_eq!(knowledge.take_evicted(), 1);
+	assert_eq!(knowledge.take_evicted(), 0);
+
+	// The candidate stays known, so that statements about it can still be exchanged.
+	assert!(knowledge.is_known_candidate(&hash_a));
+}
+
 #[test]
 fn cant_send_after_receiving() {
 	let mut knowledge = PeerRelayParentKnowledge::default();
@@ -470,7 +503,7 @@ fn peer_view_update_sends_messages() {
 	let mut peer_data = PeerData {
 		view: old_view,
 		view_knowledge: {
-			let mut k = HashMap::new();
+			let mut k = IndexMap::new();
 
 			k.insert(hash_a, Default::default());
 			k.insert(hash_b, Default::default());
@@ -624,6 +657,7 @@ fn circulated_statement_goes_to_all_peers_with_view() {
 			hash_b,
 			statement,
 			Vec::new(),
+			&Metrics::default(),
 		)
 		.await;
 
@@ -707,6 +741,8 @@ fn receiving_from_one_sends_to_another_and_to_candidate_backing() {
 			Arc::new(LocalKeystore::in_memory()),
 			statement_req_receiver,
 			Default::default(),
+			test_db(),
+			Config { col_data: 0 },
 		);
 		s.run(ctx).await.unwrap();
 	};
@@ -899,6 +935,8 @@ fn receiving_large_statement_from_one_sends_to_another_and_to_candidate_backing(
 			make_ferdie_keystore(),
 			statement_req_receiver,
 			Default::default(),
+			test_db(),
+			Config { col_data: 0 },
 		);
 		s.run(ctx).await.unwrap();
 	};
@@ -1398,6 +1436,8 @@ fn share_prioritizes_backing_group() {
 			make_ferdie_keystore(),
 			statement_req_receiver,
 			Default::default(),
+			test_db(),
+			Config { col_data: 0 },
 		);
 		s.run(ctx).await.unwrap();
 	};
@@ -1683,6 +1723,8 @@ fn peer_cant_flood_with_large_statements() {
 			make_ferdie_keystore(),
 			statement_req_receiver,
 			Default::default(),
+			test_db(),
+			Config { col_data: 0 },
 		);
 		s.run(ctx).await.unwrap();
 	};
@@ -1856,3 +1898,48 @@ fn make_session_info(validators: Vec<Pair>, groups: Vec<Vec<u32>>) -> SessionInf
 		random_seed: [0u8; 32],
 	}
 }
+
+#[test]
+fn own_statements_are_kept_per_active_leaf() {
+	let keystore: SyncCryptoStorePtr = Arc::new(LocalKeystore::in_memory());
+	let alice_public = SyncCryptoStore::sr25519_generate_new(
+		&*keystore,
+		ValidatorId::ID,
+		Some(&Sr25519Keyring::Alice.to_seed()),
+	)
+	.unwrap();
+
+	let (leaf_a, leaf_b) = (Hash::repeat_byte(1), Hash::repeat_byte(2));
+	let seconded = |relay_parent: Hash| {
+		let mut candidate = dummy_committed_candidate_receipt(dummy_hash());
+		candidate.descriptor.relay_parent = relay_parent;
+		block_on(SignedFullStatement::sign(
+			&keystore,
+			Statement::Seconded(candidate),
+			&SigningContext { parent_hash: relay_parent, session_index: 1 },
+			ValidatorIndex(0),
+			&alice_public.into(),
+		))
+		.ok()
+		.flatten()
+		.expect("should be signed")
+	};
+	let (statement_a, statement_b) = (seconded(leaf_a), seconded(leaf_b));
+
+	let db = test_db();
+	let mut own_statements = OwnStatements::load(db.clone(), Config { col_data: 0 });
+	own_statements.note(leaf_a, &statement_a);
+	own_statements.note(leaf_a, &statement_a);
+	own_statements.note(leaf_b, &statement_b);
+
+	// Statements survive a restart.
+	let mut own_statements = OwnStatements::load(db.clone(), Config { col_data: 0 });
+	assert_eq!(own_statements.at(&leaf_a), vec![statement_a.as_unchecked().clone()]);
+	assert_eq!(own_statements.at(&leaf_b), vec![statement_b.as_unchecked().clone()]);
+
+	// Statements of deactivated leaves are dropped.
+	own_statements.retain_active(|hash| *hash == leaf_a);
+	let own_statements = OwnStatements::load(db, Config { col_data: 0 });
+	assert_eq!(own_statements.at(&leaf_a), vec![statement_a.as_unchecked().clone()]);
+	assert!(own_statements.at(&leaf_b).is_empty());
+}
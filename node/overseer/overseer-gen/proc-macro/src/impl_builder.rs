@@ -616,6 +616,7 @@ pub(crate) fn impl_task_kind(info: &OverseerInfo) -> proc_macro2::TokenStream {
 			TK: TaskKind,
 			Ctx: #support_crate ::SubsystemContext<Message=M>,
 			E: std::error::Error + Send + Sync + 'static + From<#support_crate ::OverseerError>,
+			#error_ty: From<E>,
 			SubSys: #support_crate ::Subsystem<Ctx, E>,
 		{
 			let #support_crate ::SpawnedSubsystem::<E> { future, name } = s.start(ctx);
@@ -623,20 +624,25 @@ pub(crate) fn impl_task_kind(info: &OverseerInfo) -> proc_macro2::TokenStream {
 			let (tx, rx) = #support_crate ::oneshot::channel();
 
 			let fut = Box::pin(async move {
-				if let Err(e) = future.await {
+				let result = future.await;
+				if let Err(ref e) = result {
 					#support_crate ::tracing::error!(subsystem=name, err = ?e, "subsystem exited with error");
 				} else {
 					#support_crate ::tracing::debug!(subsystem=name, "subsystem exited without an error");
 				}
-				let _ = tx.send(());
+				let _ = tx.send(result);
 			});
 
 			<TK as TaskKind>::launch_task(spawner, name, subsystem_name, fut);
 
+			// Pass the error of the subsystem on, so the overseer can tell fatal errors apart.
 			futures.push(Box::pin(
-				rx.map(|e| {
-					tracing::warn!(err = ?e, "dropping error");
-					Ok(())
+				rx.map(|result| match result {
+					Ok(result) => result.map_err(<#error_ty as From<E>>::from),
+					Err(e) => {
+						tracing::warn!(err = ?e, "dropping error");
+						Ok(())
+					},
 				})
 			));
 
@@ -84,12 +84,12 @@ use selendra_node_subsystem_types::messages::{
 	AvailabilityRecoveryMessage, AvailabilityStoreMessage, BitfieldDistributionMessage,
 	BitfieldSigningMessage, CandidateBackingMessage, CandidateValidationMessage, ChainApiMessage,
 	ChainSelectionMessage, CollationGenerationMessage, CollatorProtocolMessage,
-	DisputeCoordinatorMessage, DisputeDistributionMessage, GossipSupportMessage,
+	DisputeCoordinatorMessage, DisputeDistributionMessage, GossipSubsystem, GossipSupportMessage,
 	NetworkBridgeEvent, NetworkBridgeMessage, ProvisionerMessage, PvfCheckerMessage,
 	RuntimeApiMessage, StatementDistributionMessage,
 };
 pub use selendra_node_subsystem_types::{
-	errors::{SubsystemError, SubsystemResult},
+	errors::{Fatality, SubsystemError, SubsystemResult},
 	jaeger, ActivatedLeaf, ActiveLeavesUpdate, LeafStatus, OverseerSignal,
 };
 
@@ -100,6 +100,9 @@ pub use self::metrics::Metrics as OverseerMetrics;
 pub mod dummy;
 pub use self::dummy::DummySubsystem;
 
+pub mod restart;
+pub use self::restart::restart_on_recoverable;
+
 pub use selendra_node_metrics::{
 	metrics::{prometheus, Metrics as MetricsTrait},
 	Metronome,
@@ -195,6 +198,22 @@ impl Handle {
 		.await;
 	}
 
+	/// Stop or resume exchanging the messages of a gossip subsystem with the peers.
+	pub async fn set_gossip_paused(&mut self, subsystem: GossipSubsystem, paused: bool) {
+		self.send_msg(NetworkBridgeMessage::SetGossipPaused { subsystem, paused }, "operator")
+			.await
+	}
+
+	/// Send the statements we made at the active leaves to the peers which have not seen them.
+	pub async fn rebroadcast_statements(&mut self) {
+		self.send_msg(StatementDistributionMessage::RebroadcastOwn, "operator").await
+	}
+
+	/// Send our current view to all peers again.
+	pub async fn refresh_view(&mut self) {
+		self.send_msg(NetworkBridgeMessage::RefreshView, "operator").await
+	}
+
 	/// Tell `Overseer` to shutdown.
 	pub async fn stop(&mut self) {
 		self.send_and_log_error(Event::Stop).await;
@@ -638,11 +657,23 @@ where
 					}
 				},
 				res = self.running_subsystems.select_next_some() => {
-					tracing::error!(
-						target: LOG_TARGET,
-						subsystem = ?res,
-						"subsystem finished unexpectedly",
-					);
+					match res {
+						Err(ref err) if err.is_fatal() => tracing::error!(
+							target: LOG_TARGET,
+							?err,
+							"subsystem failed with a fatal error, shutting down",
+						),
+						// Recoverable errors are meant to be handled by restarting the subsystem.
+						Err(ref err) => tracing::error!(
+							target: LOG_TARGET,
+							?err,
+							"subsystem exited after a recoverable error instead of restarting",
+						),
+						Ok(()) => tracing::error!(
+							target: LOG_TARGET,
+							"subsystem finished unexpectedly",
+						),
+					}
 					self.stop().await;
 					return res;
 				},
@@ -33,6 +33,7 @@ struct MetricsInner {
 	to_subsystem_unbounded_received: prometheus::GaugeVec<prometheus::U64>,
 	signals_sent: prometheus::GaugeVec<prometheus::U64>,
 	signals_received: prometheus::GaugeVec<prometheus::U64>,
+	queue_depth: prometheus::GaugeVec<prometheus::U64>,
 
 	memory_stats_resident: prometheus::Gauge<prometheus::U64>,
 	memory_stats_allocated: prometheus::Gauge<prometheus::U64>,
@@ -105,6 +106,17 @@ impl Metrics {
 						.signals_received
 						.with_label_values(&[name])
 						.set(readouts.signals.received as u64);
+
+					for (queue, readout) in [
+						("bounded", &readouts.bounded),
+						("unbounded", &readouts.unbounded),
+						("signals", &readouts.signals),
+					] {
+						metrics
+							.queue_depth
+							.with_label_values(&[name, queue])
+							.set(readout.sent.saturating_sub(readout.received) as u64);
+					}
 				});
 		}
 	}
@@ -194,6 +206,16 @@ impl MetricsTrait for Metrics {
 				)?,
 				registry,
 			)?,
+			queue_depth: prometheus::register(
+				prometheus::GaugeVec::<prometheus::U64>::new(
+					prometheus::Opts::new(
+						"selendra_parachain_subsystem_queue_depth",
+						"Number of elements waiting in subsystems' bounded, unbounded and signal queues",
+					),
+					&["subsystem_name", "queue"],
+				)?,
+				registry,
+			)?,
 
 			memory_stats_allocated: prometheus::register(
 				prometheus::Gauge::<prometheus::U64>::new(
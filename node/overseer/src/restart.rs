@@ -0,0 +1,55 @@
+// Copyright 2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Restarting subsystems after recoverable errors.
+//!
+//! The overseer shuts the node down once any subsystem exits. Subsystems whose main loop may fail
+//! with errors they can recover from run it through [`restart_on_recoverable`], so only fatal
+//! errors reach the overseer.
+
+use futures::future::BoxFuture;
+use selendra_node_subsystem_types::errors::Fatality;
+
+use crate::LOG_TARGET;
+
+/// Runs the main loop of the subsystem `name` built by `run` until it concludes or fails fatally,
+/// starting it over after every recoverable error.
+///
+/// `state` holds the context of the subsystem, along with anything else the main loop is passed
+/// again on a restart. Whatever else the main loop keeps in memory is lost.
+pub async fn restart_on_recoverable<State, E, F>(
+	name: &'static str,
+	state: &mut State,
+	mut run: F,
+) -> Result<(), E>
+where
+	E: Fatality,
+	F: for<'a> FnMut(&'a mut State) -> BoxFuture<'a, Result<(), E>>,
+{
+	loop {
+		match run(state).await {
+			Err(err) if !err.is_fatal() => {
+				tracing::warn!(
+					target: LOG_TARGET,
+					subsystem = name,
+					?err,
+					"Restarting subsystem after a recoverable error",
+				);
+			},
+			result => return result,
+		}
+	}
+}
@@ -307,6 +307,88 @@ fn overseer_ends_on_subsystem_exit() {
 	})
 }
 
+struct Restarting {
+	runs: std::sync::Arc<atomic::AtomicUsize>,
+	recoverable: usize,
+	fatal: bool,
+}
+
+impl<C> overseer::Subsystem<C, SubsystemError> for Restarting
+where
+	C: overseer::SubsystemContext<
+		Message = CandidateBackingMessage,
+		Signal = OverseerSignal,
+		AllMessages = AllMessages,
+	>,
+{
+	fn start(self, mut ctx: C) -> SpawnedSubsystem {
+		SpawnedSubsystem {
+			name: "test-subsystem-restarting",
+			future: Box::pin(async move {
+				restart_on_recoverable("test-subsystem-restarting", &mut ctx, |_| {
+					let run = self.runs.fetch_add(1, atomic::Ordering::SeqCst);
+					let result = if run < self.recoverable {
+						let err = std::io::Error::from(std::io::ErrorKind::Interrupted);
+						Err(SubsystemError::recoverable("test-subsystem-restarting", err))
+					} else if self.fatal {
+						Err(SubsystemError::Context("fail".to_owned()))
+					} else {
+						Ok(())
+					};
+					futures::future::ready(result).boxed()
+				})
+				.await
+			}),
+		}
+	}
+}
+
+// Spawn a subsystem failing with recoverable errors before exiting.
+//
+// Should restart the subsystem on every recoverable error, then conclude the overseer.
+#[test]
+fn subsystems_restart_after_recoverable_errors() {
+	let spawner = sp_core::testing::TaskExecutor::new();
+	let runs = std::sync::Arc::new(atomic::AtomicUsize::new(0));
+	let subsystem = Restarting { runs: runs.clone(), recoverable: 2, fatal: false };
+
+	executor::block_on(async move {
+		let (overseer, _handle) = dummy_overseer_builder(spawner, MockSupportsParachains, None)
+			.unwrap()
+			.replace_candidate_backing(|_| subsystem)
+			.build()
+			.unwrap();
+
+		overseer.run().await.unwrap();
+	});
+
+	assert_eq!(runs.load(atomic::Ordering::SeqCst), 3);
+}
+
+// Spawn a subsystem failing with a fatal error after a recoverable one.
+//
+// Should not restart the subsystem after the fatal error and end the overseer with it.
+#[test]
+fn overseer_ends_with_the_fatal_error_of_a_subsystem() {
+	let spawner = sp_core::testing::TaskExecutor::new();
+	let runs = std::sync::Arc::new(atomic::AtomicUsize::new(0));
+	let subsystem = Restarting { runs: runs.clone(), recoverable: 1, fatal: true };
+
+	executor::block_on(async move {
+		let (overseer, _handle) = dummy_overseer_builder(spawner, MockSupportsParachains, None)
+			.unwrap()
+			.replace_candidate_backing(|_| subsystem)
+			.build()
+			.unwrap();
+
+		let err = overseer.run().await.unwrap_err();
+		assert!(err.is_fatal());
+		assert_matches!(err, SubsystemError::Context(_));
+	});
+
+	assert_eq!(runs.load(atomic::Ordering::SeqCst), 2);
+}
+
 struct TestSubsystem5(metered::MeteredSender<OverseerSignal>);
 
 impl<C> overseer::Subsystem<C, SubsystemError> for TestSubsystem5
@@ -0,0 +1,108 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! RPC validating addresses and converting them between the SS58 formats of the chain.
+
+use std::sync::Arc;
+
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use selendra_primitives::{
+	address::AddressFormatApi,
+	v0::{Block, Hash},
+};
+use serde::{Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::{
+	crypto::{AccountId32, Ss58AddressFormat, Ss58Codec},
+	Bytes,
+};
+use sp_runtime::generic::BlockId;
+
+/// An address decoded with one of the SS58 prefixes of the chain.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressInfo {
+	/// The account the address refers to.
+	pub account_id: Bytes,
+	/// The prefix the address was encoded with.
+	pub ss58_prefix: u16,
+	/// Whether that prefix is a legacy one, so the address should be displayed as `address`.
+	pub legacy: bool,
+	/// The address encoded with the current prefix of the chain.
+	pub address: String,
+}
+
+/// Address RPC methods.
+#[rpc]
+pub trait AddressApi {
+	/// Decode an address encoded with the current or a legacy SS58 prefix of the chain as of
+	/// block `at`, or the best block, and convert it to the current prefix.
+	#[rpc(name = "address_convert")]
+	fn convert(&self, address: String, at: Option<Hash>) -> Result<AddressInfo>;
+}
+
+/// Implements [`AddressApi`] using the [`AddressFormatApi`] of the runtime.
+pub struct Address<C> {
+	client: Arc<C>,
+}
+
+impl<C> Address<C> {
+	/// Create a new instance of the address RPC.
+	pub fn new(client: Arc<C>) -> Self {
+		Address { client }
+	}
+}
+
+fn runtime_error(error: impl std::fmt::Debug) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(1),
+		message: "Unable to query the address formats.".into(),
+		data: Some(format!("{:?}", error).into()),
+	}
+}
+
+impl<C> AddressApi for Address<C>
+where
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: AddressFormatApi<Block>,
+{
+	fn convert(&self, address: String, at: Option<Hash>) -> Result<AddressInfo> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		let current = api.ss58_prefix(&at).map_err(runtime_error)?;
+		let legacy = api.legacy_ss58_prefixes(&at).map_err(runtime_error)?;
+
+		let (account, format) = AccountId32::from_ss58check_with_version(&address)
+			.map_err(|error| RpcError::invalid_params(format!("Invalid address: {:?}", error)))?;
+		let prefix = u16::from(format);
+		if prefix != current && !legacy.contains(&prefix) {
+			return Err(RpcError::invalid_params(format!(
+				"Address has the prefix {}, expected {} or one of {:?}",
+				prefix, current, legacy,
+			)))
+		}
+
+		Ok(AddressInfo {
+			account_id: AsRef::<[u8]>::as_ref(&account).to_vec().into(),
+			ss58_prefix: prefix,
+			legacy: prefix != current,
+			address: account.to_ss58check_with_version(Ss58AddressFormat::custom(current)),
+		})
+	}
+}
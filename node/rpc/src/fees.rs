@@ -0,0 +1,144 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! RPC reporting the fee multiplier, its recent history, the fees it implies and the fees of recent
+//! blocks.
+
+use std::sync::Arc;
+
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use selendra_primitives::{
+	fees::{BlockFees, FeeMultiplierApi, Multiplier, Perquintill, StandardTransaction},
+	v0::{Balance, Block, BlockNumber, Hash},
+};
+use serde::{Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_rpc::number::NumberOrHex;
+use sp_runtime::generic::BlockId;
+
+/// The fee multiplier of a block and the fees it implies.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeMultiplierInfo {
+	/// The multiplier of the weight fee of extrinsics in the next block.
+	pub next_fee_multiplier: Multiplier,
+	/// The block fullness the multiplier is adjusted towards.
+	pub target_block_fullness: Perquintill,
+	/// The fee each of the standard transactions would pay in the next block, without tip.
+	pub projected_fees: Vec<(StandardTransaction, NumberOrHex)>,
+}
+
+/// The fee multiplier, weight and fees of a recent block.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockFeesInfo {
+	/// The number of the block.
+	pub block: BlockNumber,
+	/// The multiplier of the weight fee the extrinsics of the block paid with.
+	pub multiplier: Multiplier,
+	/// The total weight of the block, of all dispatch classes.
+	pub weight: u64,
+	/// The fees and tips paid by the extrinsics of the block.
+	pub fees: NumberOrHex,
+}
+
+impl From<BlockFees<BlockNumber, Balance>> for BlockFeesInfo {
+	fn from(block_fees: BlockFees<BlockNumber, Balance>) -> Self {
+		BlockFeesInfo {
+			block: block_fees.block,
+			multiplier: block_fees.multiplier,
+			weight: block_fees.weight,
+			fees: block_fees.fees.into(),
+		}
+	}
+}
+
+/// Fee multiplier RPC methods.
+#[rpc]
+pub trait FeesApi {
+	/// Get the fee multiplier and the projected fees as of block `at`, or the best block.
+	#[rpc(name = "fees_multiplier")]
+	fn multiplier(&self, at: Option<Hash>) -> Result<FeeMultiplierInfo>;
+
+	/// Get the multiplier each of the recent blocks before `at`, or the best block, was executed
+	/// with, oldest first.
+	#[rpc(name = "fees_multiplierHistory")]
+	fn multiplier_history(&self, at: Option<Hash>) -> Result<Vec<(BlockNumber, Multiplier)>>;
+
+	/// Get the multiplier, weight and fees of at most `count` of the recent blocks before `at`, or
+	/// the best block, oldest first.
+	#[rpc(name = "fees_history")]
+	fn history(&self, count: u32, at: Option<Hash>) -> Result<Vec<BlockFeesInfo>>;
+}
+
+/// Implements [`FeesApi`] using the [`FeeMultiplierApi`] of the runtime.
+pub struct Fees<C> {
+	client: Arc<C>,
+}
+
+impl<C> Fees<C> {
+	/// Create a new instance of the fees RPC.
+	pub fn new(client: Arc<C>) -> Self {
+		Fees { client }
+	}
+}
+
+fn runtime_error(error: impl std::fmt::Debug) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(1),
+		message: "Unable to query the fee multiplier.".into(),
+		data: Some(format!("{:?}", error).into()),
+	}
+}
+
+impl<C> FeesApi for Fees<C>
+where
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: FeeMultiplierApi<Block, Balance, BlockNumber>,
+{
+	fn multiplier(&self, at: Option<Hash>) -> Result<FeeMultiplierInfo> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		let next_fee_multiplier = api.next_fee_multiplier(&at).map_err(runtime_error)?;
+		let target_block_fullness = api.target_block_fullness(&at).map_err(runtime_error)?;
+		let projected_fees = api
+			.projected_fees(&at)
+			.map_err(runtime_error)?
+			.into_iter()
+			.map(|(transaction, fee)| (transaction, fee.into()))
+			.collect();
+
+		Ok(FeeMultiplierInfo { next_fee_multiplier, target_block_fullness, projected_fees })
+	}
+
+	fn multiplier_history(&self, at: Option<Hash>) -> Result<Vec<(BlockNumber, Multiplier)>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.multiplier_history(&at).map_err(runtime_error)
+	}
+
+	fn history(&self, count: u32, at: Option<Hash>) -> Result<Vec<BlockFeesInfo>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		let history = api.fee_history(&at, count).map_err(runtime_error)?;
+		Ok(history.into_iter().map(Into::into).collect())
+	}
+}
@@ -0,0 +1,470 @@
+// Copyright 2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! RPC streaming the events and a summary of the storage changes of each imported or finalized
+//! block, so indexers don't have to execute blocks themselves.
+
+use std::{
+	collections::{BTreeMap, HashMap},
+	marker::PhantomData,
+	sync::Arc,
+};
+
+use futures::{future, stream, FutureExt, SinkExt, StreamExt};
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use jsonrpc_pubsub::{manager::SubscriptionManager, typed::Subscriber, SubscriptionId};
+use parity_scale_codec::Decode;
+use sc_client_api::{
+	Backend, BlockchainEvents, FinalityNotification, StorageChangeSet, StorageNotification,
+	StorageProvider,
+};
+use sc_rpc::SubscriptionTaskExecutor;
+use selendra_primitives::{
+	events::{BlockEvent, BlockEventsApi, EventPhase, EventsSchema, EventsSchemaApi},
+	v0::{Block, BlockNumber, Hash},
+	v1::{CandidateReceipt, CoreIndex, GroupIndex, HeadData},
+};
+use serde::{Deserialize, Serialize};
+use sp_api::{CallApiAt, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_core::{hashing::twox_128, hexdisplay::HexDisplay, storage::StorageKey, Bytes};
+use sp_runtime::{generic::BlockId, traits::Header as _};
+
+const LOG_TARGET: &str = "rpc::indexer";
+
+/// An event deposited in a block, named after the pallet and variant it was encoded with.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct NamedEvent {
+	/// The phase the event was deposited in.
+	pub phase: EventPhase,
+	/// The name of the pallet that deposited the event, or its index if the schema lacks it.
+	pub pallet: String,
+	/// The name of the event, or its index if the schema lacks it.
+	pub event: String,
+	/// The SCALE-encoded fields of the event.
+	pub fields: Bytes,
+	/// The candidate of the event, if it is a candidate event of the inclusion pallet.
+	pub candidate: Option<CandidateEvent>,
+}
+
+impl NamedEvent {
+	fn new(schema: &EventsSchema, event: BlockEvent) -> Self {
+		let pallet = schema.pallet(event.pallet);
+		let variant = pallet
+			.and_then(|pallet| pallet.events.iter().find(|variant| variant.index == event.variant));
+
+		let pallet = pallet.map_or_else(|| event.pallet.to_string(), |pallet| pallet.name.clone());
+		let variant =
+			variant.map_or_else(|| event.variant.to_string(), |variant| variant.name.clone());
+		let candidate = CandidateEvent::decode(&pallet, &variant, &event.fields);
+
+		NamedEvent {
+			phase: event.phase,
+			pallet,
+			event: variant,
+			fields: event.fields.into(),
+			candidate,
+		}
+	}
+}
+
+/// A candidate backed, included or timed out, decoded from the events of the inclusion pallet so
+/// parachain dashboards don't need the metadata of the runtime to follow candidates.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CandidateEvent {
+	/// The para the candidate is of.
+	pub para_id: u32,
+	/// The hash of the candidate receipt.
+	pub candidate_hash: Hash,
+	/// The relay parent the candidate was built on.
+	pub relay_parent: Hash,
+	/// The hash of the para head the candidate produced.
+	pub para_head: Hash,
+	/// The availability core the candidate occupies.
+	pub core_index: u32,
+	/// The group that backed the candidate, `None` for timed out candidates.
+	pub group_index: Option<u32>,
+}
+
+impl CandidateEvent {
+	/// Decode the candidate of the `event` of `pallet` from its `fields`, if it is a candidate
+	/// event of the inclusion pallet.
+	fn decode(pallet: &str, event: &str, mut fields: &[u8]) -> Option<Self> {
+		let (receipt, head_data, core_index, group_index) = match (pallet, event) {
+			("ParaInclusion", "CandidateBacked" | "CandidateIncluded") => {
+				let (receipt, head_data, core_index, group_index) =
+					<(CandidateReceipt<Hash>, HeadData, CoreIndex, GroupIndex)>::decode(
+						&mut fields,
+					)
+					.ok()?;
+				(receipt, head_data, core_index, Some(group_index))
+			},
+			("ParaInclusion", "CandidateTimedOut") => {
+				let (receipt, head_data, core_index) =
+					<(CandidateReceipt<Hash>, HeadData, CoreIndex)>::decode(&mut fields).ok()?;
+				(receipt, head_data, core_index, None)
+			},
+			_ => return None,
+		};
+
+		Some(CandidateEvent {
+			para_id: receipt.descriptor.para_id.into(),
+			candidate_hash: receipt.hash().0,
+			relay_parent: receipt.descriptor.relay_parent,
+			para_head: head_data.hash(),
+			core_index: core_index.0,
+			group_index: group_index.map(|group_index| group_index.0),
+		})
+	}
+}
+
+/// The number of top-level storage items a pallet wrote and removed in a block.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PalletStorageChanges {
+	/// The name of the pallet, or the hex-encoded prefix of the keys if the schema lacks it.
+	pub pallet: String,
+	/// The number of items written.
+	pub written: u32,
+	/// The number of items removed.
+	pub removed: u32,
+}
+
+/// The events and the storage changes of a block.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockChanges {
+	/// The hash of the block.
+	pub hash: Hash,
+	/// The number of the block.
+	pub number: BlockNumber,
+	/// The spec version of the runtime the block was executed with.
+	pub spec_version: u32,
+	/// The events deposited in the block, in order.
+	pub events: Vec<NamedEvent>,
+	/// The storage changes of the block by pallet, `None` if the node did not see them, i.e. the
+	/// block was imported before the subscription.
+	pub storage_changes: Option<Vec<PalletStorageChanges>>,
+}
+
+/// Indexer RPC methods.
+#[rpc]
+pub trait IndexerApi {
+	/// RPC metadata
+	type Metadata;
+
+	/// Subscribe to the changes of every imported block, or of every finalized block if
+	/// `finalized` is set.
+	#[pubsub(
+		subscription = "indexer_blockChanges",
+		subscribe,
+		name = "indexer_subscribeBlockChanges"
+	)]
+	fn subscribe_block_changes(
+		&self,
+		metadata: Self::Metadata,
+		subscriber: Subscriber<BlockChanges>,
+		finalized: Option<bool>,
+	);
+
+	/// Unsubscribe from the changes of blocks.
+	#[pubsub(
+		subscription = "indexer_blockChanges",
+		unsubscribe,
+		name = "indexer_unsubscribeBlockChanges"
+	)]
+	fn unsubscribe_block_changes(
+		&self,
+		metadata: Option<Self::Metadata>,
+		id: SubscriptionId,
+	) -> Result<bool>;
+}
+
+/// Implements [`IndexerApi`] using the [`BlockEventsApi`] and [`EventsSchemaApi`] of the runtime.
+pub struct Indexer<C, B> {
+	client: Arc<C>,
+	manager: SubscriptionManager,
+	_phantom: PhantomData<B>,
+}
+
+impl<C, B> Indexer<C, B> {
+	/// Create a new instance of the indexer RPC.
+	pub fn new(client: Arc<C>, executor: SubscriptionTaskExecutor) -> Self {
+		Indexer {
+			client,
+			manager: SubscriptionManager::new(Arc::new(executor)),
+			_phantom: PhantomData,
+		}
+	}
+}
+
+fn client_error(error: impl std::fmt::Debug) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(1),
+		message: "Unable to subscribe to block changes.".into(),
+		data: Some(format!("{:?}", error).into()),
+	}
+}
+
+/// Log that the changes of block `hash` are left out of a subscription.
+fn skipped(hash: Hash) -> impl FnOnce(String) {
+	move |error| log::debug!(target: LOG_TARGET, "Leaving out the changes of block {}: {}", hash, error)
+}
+
+/// A notification feeding a subscription to the changes of finalized blocks.
+enum Notification {
+	Storage(StorageNotification<Hash>),
+	Finality(FinalityNotification<Block>),
+}
+
+impl<C, B> IndexerApi for Indexer<C, B>
+where
+	C: ProvideRuntimeApi<Block>
+		+ CallApiAt<Block>
+		+ HeaderBackend<Block>
+		+ BlockchainEvents<Block>
+		+ StorageProvider<Block, B>
+		+ Send
+		+ Sync
+		+ 'static,
+	C::Api: EventsSchemaApi<Block> + BlockEventsApi<Block>,
+	B: Backend<Block> + Send + Sync + 'static,
+{
+	type Metadata = sc_rpc::Metadata;
+
+	fn subscribe_block_changes(
+		&self,
+		_metadata: Self::Metadata,
+		subscriber: Subscriber<BlockChanges>,
+		finalized: Option<bool>,
+	) {
+		let storage_notifications =
+			match self.client.storage_changes_notification_stream(None, None) {
+				Ok(stream) => stream,
+				Err(error) => {
+					let _ = subscriber.reject(client_error(error));
+					return
+				},
+			};
+		let mut decoder = Decoder::<C, B>::new(self.client.clone());
+
+		let stream = if finalized.unwrap_or(false) {
+			let client = self.client.clone();
+			// Storage changes are only notified on import, so they are kept until their block is
+			// finalized or pruned as a fork.
+			let mut pending = HashMap::<Hash, (BlockNumber, Vec<PalletStorageChanges>)>::new();
+			let mut last_finalized = None;
+
+			stream::select(
+				storage_notifications.map(Notification::Storage),
+				self.client.finality_notification_stream().map(Notification::Finality),
+			)
+			.map(move |notification| match notification {
+				Notification::Storage(StorageNotification { block, changes }) => {
+					let number = client.number(block).ok().flatten();
+					match (number, decoder.storage_changes(block, &changes)) {
+						(Some(number), Ok(changes)) => {
+							pending.insert(block, (number, changes));
+						},
+						(_, Err(error)) => skipped(block)(error),
+						(None, _) => {},
+					}
+					Vec::new()
+				},
+				Notification::Finality(notification) => {
+					let number = *notification.header.number();
+					let route = finalized_route(&*client, notification.header, last_finalized)
+						.unwrap_or_else(|error| {
+							log::warn!(
+								target: LOG_TARGET,
+								"Failed to find the blocks finalized by {}: {}",
+								notification.hash,
+								error,
+							);
+							vec![notification.hash]
+						});
+					last_finalized = Some(number);
+
+					let changes = route
+						.into_iter()
+						.filter_map(|hash| {
+							let storage_changes = pending.remove(&hash).map(|(_, changes)| changes);
+							decoder.block_changes(hash, storage_changes).map_err(skipped(hash)).ok()
+						})
+						.collect();
+					pending.retain(|_, (pending_number, _)| *pending_number > number);
+					changes
+				},
+			})
+			.flat_map(stream::iter)
+			.boxed()
+		} else {
+			storage_notifications
+				.filter_map(move |StorageNotification { block, changes }| {
+					let changes = decoder
+						.storage_changes(block, &changes)
+						.and_then(|changes| decoder.block_changes(block, Some(changes)))
+						.map_err(skipped(block));
+					future::ready(changes.ok())
+				})
+				.boxed()
+		};
+
+		self.manager.add(subscriber, |sink| {
+			stream
+				.map(|changes| Ok::<_, ()>(Ok(changes)))
+				.forward(sink.sink_map_err(
+					|error| log::warn!(target: LOG_TARGET, "Error sending block changes: {:?}", error),
+				))
+				.map(|_| ())
+		});
+	}
+
+	fn unsubscribe_block_changes(
+		&self,
+		_metadata: Option<Self::Metadata>,
+		id: SubscriptionId,
+	) -> Result<bool> {
+		Ok(self.manager.cancel(id))
+	}
+}
+
+/// The hashes of the blocks finalized by finalizing `header`, oldest first, given the number of the
+/// previously finalized block.
+fn finalized_route<C: HeaderBackend<Block>>(
+	client: &C,
+	mut header: <Block as sp_runtime::traits::Block>::Header,
+	last_finalized: Option<BlockNumber>,
+) -> sp_blockchain::Result<Vec<Hash>> {
+	let mut route = vec![header.hash()];
+	if let Some(last_finalized) = last_finalized {
+		while *header.number() > last_finalized + 1 {
+			let parent = *header.parent_hash();
+			header = client
+				.header(BlockId::hash(parent))?
+				.ok_or_else(|| sp_blockchain::Error::UnknownBlock(parent.to_string()))?;
+			route.push(parent);
+		}
+	}
+	route.reverse();
+	Ok(route)
+}
+
+/// Decodes the changes of blocks, keeping the events schema of the last runtime seen.
+struct Decoder<C, B> {
+	client: Arc<C>,
+	schema: Option<EventsSchema>,
+	_phantom: PhantomData<B>,
+}
+
+impl<C, B> Decoder<C, B>
+where
+	C: ProvideRuntimeApi<Block>
+		+ CallApiAt<Block>
+		+ HeaderBackend<Block>
+		+ StorageProvider<Block, B>,
+	C::Api: EventsSchemaApi<Block> + BlockEventsApi<Block>,
+	B: Backend<Block>,
+{
+	fn new(client: Arc<C>) -> Self {
+		Decoder { client, schema: None, _phantom: PhantomData }
+	}
+
+	/// The events schema of the runtime of block `hash`.
+	fn schema(&mut self, hash: Hash) -> std::result::Result<&EventsSchema, String> {
+		let at = BlockId::hash(hash);
+		let spec_version =
+			self.client.runtime_version_at(&at).map_err(|e| e.to_string())?.spec_version;
+
+		let schema = match self.schema.take() {
+			Some(schema) if schema.spec_version == spec_version => schema,
+			_ => self.client.runtime_api().events_schema(&at).map_err(|e| e.to_string())?,
+		};
+		Ok(self.schema.insert(schema))
+	}
+
+	/// The events of block `hash`, along with the given summary of its storage changes.
+	fn block_changes(
+		&mut self,
+		hash: Hash,
+		storage_changes: Option<Vec<PalletStorageChanges>>,
+	) -> std::result::Result<BlockChanges, String> {
+		let at = BlockId::hash(hash);
+		let number = self
+			.client
+			.number(hash)
+			.map_err(|e| e.to_string())?
+			.ok_or_else(|| format!("Unknown block {}", hash))?;
+
+		let events_key = StorageKey([twox_128(b"System"), twox_128(b"Events")].concat());
+		let events = match self.client.storage(&at, &events_key).map_err(|e| e.to_string())? {
+			Some(events) => self
+				.client
+				.runtime_api()
+				.block_events(&at, events.0)
+				.map_err(|e| e.to_string())?
+				.ok_or_else(|| format!("Failed to decode the events of {}", hash))?,
+			None => Vec::new(),
+		};
+
+		let schema = self.schema(hash)?;
+		Ok(BlockChanges {
+			hash,
+			number,
+			spec_version: schema.spec_version,
+			events: events.into_iter().map(|event| NamedEvent::new(schema, event)).collect(),
+			storage_changes,
+		})
+	}
+
+	/// Summarize the top-level storage `changes` of block `hash` by the pallet owning the keys.
+	fn storage_changes(
+		&mut self,
+		hash: Hash,
+		changes: &StorageChangeSet,
+	) -> std::result::Result<Vec<PalletStorageChanges>, String> {
+		// The keys of pallet storage start with the hash of the pallet name.
+		let pallets: BTreeMap<[u8; 16], &str> = self
+			.schema(hash)?
+			.pallets
+			.iter()
+			.map(|pallet| (twox_128(pallet.name.as_bytes()), pallet.name.as_str()))
+			.collect();
+
+		let mut summary = BTreeMap::<String, PalletStorageChanges>::new();
+		for (_, key, value) in changes.iter().filter(|(child, _, _)| child.is_none()) {
+			let prefix = key.0.get(..16).unwrap_or(&key.0);
+			let pallet = match pallets.get(prefix) {
+				Some(name) => name.to_string(),
+				None => format!("0x{}", HexDisplay::from(&prefix)),
+			};
+			let entry = summary.entry(pallet.clone()).or_insert(PalletStorageChanges {
+				pallet,
+				written: 0,
+				removed: 0,
+			});
+			match value {
+				Some(_) => entry.written += 1,
+				None => entry.removed += 1,
+			}
+		}
+
+		Ok(summary.into_values().collect())
+	}
+}
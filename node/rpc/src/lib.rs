@@ -20,13 +20,13 @@
 
 use std::sync::Arc;
 
-use sc_client_api::AuxStore;
+use sc_client_api::{AuxStore, BlockchainEvents, StorageProvider};
 use sc_consensus_babe::Epoch;
 use sc_finality_grandpa::FinalityProofProvider;
 pub use sc_rpc::{DenyUnsafe, SubscriptionTaskExecutor};
 use sc_sync_state_rpc::{SyncStateRpcApi, SyncStateRpcHandler};
 use selendra_primitives::v0::{AccountId, Balance, Block, BlockNumber, Hash, Nonce};
-use sp_api::ProvideRuntimeApi;
+use sp_api::{CallApiAt, ProvideRuntimeApi};
 use sp_block_builder::BlockBuilder;
 use sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata};
 use sp_consensus::SelectChain;
@@ -34,6 +34,12 @@ use sp_consensus_babe::BabeApi;
 use sp_keystore::SyncCryptoStorePtr;
 use txpool_api::TransactionPool;
 
+pub mod address;
+pub mod fees;
+pub mod indexer;
+pub mod operator;
+pub mod upgrade;
+
 /// A type representing all RPC extensions.
 pub type RpcExtension = jsonrpc_core::IoHandler<sc_rpc::Metadata>;
 
@@ -100,6 +106,9 @@ where
 	C: ProvideRuntimeApi<Block>
 		+ HeaderBackend<Block>
 		+ AuxStore
+		+ BlockchainEvents<Block>
+		+ StorageProvider<Block, B>
+		+ CallApiAt<Block>
 		+ HeaderMetadata<Block, Error = BlockChainError>
 		+ Send
 		+ Sync
@@ -107,6 +116,10 @@ where
 	C::Api: frame_rpc_system::AccountNonceApi<Block, AccountId, Nonce>,
 	C::Api: pallet_mmr_rpc::MmrRuntimeApi<Block, <Block as sp_runtime::traits::Block>::Hash>,
 	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
+	C::Api: selendra_primitives::fees::FeeMultiplierApi<Block, Balance, BlockNumber>,
+	C::Api: selendra_primitives::address::AddressFormatApi<Block>,
+	C::Api: selendra_primitives::events::EventsSchemaApi<Block>,
+	C::Api: selendra_primitives::events::BlockEventsApi<Block>,
 	C::Api: BabeApi<Block>,
 	C::Api: BlockBuilder<Block>,
 	P: TransactionPool + Sync + Send + 'static,
@@ -114,7 +127,10 @@ where
 	B: sc_client_api::Backend<Block> + Send + Sync + 'static,
 	B::State: sc_client_api::StateBackend<sp_runtime::traits::HashFor<Block>>,
 {
+	use address::{Address, AddressApi};
+	use fees::{Fees, FeesApi};
 	use frame_rpc_system::{FullSystem, SystemApi};
+	use indexer::{Indexer, IndexerApi};
 	use pallet_mmr_rpc::{Mmr, MmrApi};
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApi};
 	use sc_consensus_babe_rpc::BabeRpcHandler;
@@ -134,6 +150,12 @@ where
 
 	io.extend_with(SystemApi::to_delegate(FullSystem::new(client.clone(), pool, deny_unsafe)));
 	io.extend_with(TransactionPaymentApi::to_delegate(TransactionPayment::new(client.clone())));
+	io.extend_with(FeesApi::to_delegate(Fees::new(client.clone())));
+	io.extend_with(AddressApi::to_delegate(Address::new(client.clone())));
+	io.extend_with(IndexerApi::to_delegate(Indexer::new(
+		client.clone(),
+		subscription_executor.clone(),
+	)));
 	io.extend_with(MmrApi::to_delegate(Mmr::new(client.clone())));
 	io.extend_with(sc_consensus_babe_rpc::BabeApi::to_delegate(BabeRpcHandler::new(
 		client.clone(),
@@ -0,0 +1,127 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Unsafe RPC giving the operator of a validator control over its gossip subsystems, for incident
+//! response without a restart.
+
+use futures::{future, FutureExt};
+use jsonrpc_core::{BoxFuture, Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use sc_rpc::DenyUnsafe;
+use selendra_node_subsystem_types::messages::GossipSubsystem;
+use selendra_overseer::Handle;
+
+/// Operator RPC methods.
+#[rpc]
+pub trait OperatorApi {
+	/// Stop exchanging the messages of a gossip subsystem with the peers. The subsystem is one of
+	/// `bitfield-distribution`, `statement-distribution` and `approval-distribution`.
+	#[rpc(name = "operator_pauseGossip")]
+	fn pause_gossip(&self, subsystem: String) -> BoxFuture<Result<()>>;
+
+	/// Resume exchanging the messages of a gossip subsystem paused with `operator_pauseGossip`.
+	#[rpc(name = "operator_resumeGossip")]
+	fn resume_gossip(&self, subsystem: String) -> BoxFuture<Result<()>>;
+
+	/// Send the statements the validator made at the active leaves to the peers which have not
+	/// seen them yet.
+	#[rpc(name = "operator_rebroadcastStatements")]
+	fn rebroadcast_statements(&self) -> BoxFuture<Result<()>>;
+
+	/// Send the current view of the validator to all peers again.
+	#[rpc(name = "operator_refreshView")]
+	fn refresh_view(&self) -> BoxFuture<Result<()>>;
+}
+
+/// Implements [`OperatorApi`] by sending messages to the overseer.
+pub struct Operator {
+	overseer: Option<Handle>,
+	deny_unsafe: DenyUnsafe,
+}
+
+impl Operator {
+	/// Create a new instance of the operator RPC. Without an `overseer`, i.e. if the node does not
+	/// run the parachain subsystems, all calls fail.
+	pub fn new(overseer: Option<Handle>, deny_unsafe: DenyUnsafe) -> Self {
+		Operator { overseer, deny_unsafe }
+	}
+
+	/// The overseer handle, if the call is allowed.
+	fn overseer(&self) -> Result<Handle> {
+		self.deny_unsafe.check_if_safe()?;
+		self.overseer.clone().ok_or_else(|| RpcError {
+			code: ErrorCode::ServerError(1),
+			message: "The node does not run the parachain subsystems.".into(),
+			data: None,
+		})
+	}
+
+	fn set_gossip_paused(&self, subsystem: String, paused: bool) -> BoxFuture<Result<()>> {
+		let subsystem = match parse_gossip_subsystem(&subsystem) {
+			Ok(subsystem) => subsystem,
+			Err(e) => return future::err(e).boxed(),
+		};
+		match self.overseer() {
+			Ok(mut overseer) => async move {
+				overseer.set_gossip_paused(subsystem, paused).await;
+				Ok(())
+			}
+			.boxed(),
+			Err(e) => future::err(e).boxed(),
+		}
+	}
+}
+
+fn parse_gossip_subsystem(name: &str) -> Result<GossipSubsystem> {
+	match name {
+		"bitfield-distribution" => Ok(GossipSubsystem::BitfieldDistribution),
+		"statement-distribution" => Ok(GossipSubsystem::StatementDistribution),
+		"approval-distribution" => Ok(GossipSubsystem::ApprovalDistribution),
+		_ => Err(RpcError::invalid_params(format!("Unknown gossip subsystem: {}", name))),
+	}
+}
+
+impl OperatorApi for Operator {
+	fn pause_gossip(&self, subsystem: String) -> BoxFuture<Result<()>> {
+		self.set_gossip_paused(subsystem, true)
+	}
+
+	fn resume_gossip(&self, subsystem: String) -> BoxFuture<Result<()>> {
+		self.set_gossip_paused(subsystem, false)
+	}
+
+	fn rebroadcast_statements(&self) -> BoxFuture<Result<()>> {
+		match self.overseer() {
+			Ok(mut overseer) => async move {
+				overseer.rebroadcast_statements().await;
+				Ok(())
+			}
+			.boxed(),
+			Err(e) => future::err(e).boxed(),
+		}
+	}
+
+	fn refresh_view(&self) -> BoxFuture<Result<()>> {
+		match self.overseer() {
+			Ok(mut overseer) => async move {
+				overseer.refresh_view().await;
+				Ok(())
+			}
+			.boxed(),
+			Err(e) => future::err(e).boxed(),
+		}
+	}
+}
@@ -0,0 +1,180 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Unsafe RPC dry-running a runtime upgrade, so that the blob of an upgrade proposal can be checked
+//! before voting on it.
+//!
+//! The candidate runtime is executed in wasm on top of the state of a block and all of its changes
+//! are thrown away afterwards.
+
+use std::sync::Arc;
+
+use frame_try_runtime::TryRuntime;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use parity_scale_codec::Decode;
+use sc_client_api::Backend;
+use sc_rpc::DenyUnsafe;
+use selendra_primitives::v0::{Block, Hash};
+use serde::{Deserialize, Serialize};
+use sp_api::RuntimeApiInfo;
+use sp_blockchain::HeaderBackend;
+use sp_core::{
+	traits::{CodeExecutor, RuntimeCode, SpawnNamed, WrappedRuntimeCode},
+	Bytes,
+};
+use sp_runtime::generic::BlockId;
+use sp_state_machine::{ExecutionStrategy, Extensions, OverlayedChanges, StateMachine};
+use sp_version::RuntimeVersion;
+
+/// The outcome of the pre- and post-upgrade checks of the migrations.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum TryStateOutcome {
+	/// The migrations ran and all checks passed.
+	Passed,
+	/// The migrations or one of the checks failed, with the error of the runtime.
+	Failed(String),
+	/// The runtime was not built with the `try-runtime` feature, so the migrations were not run.
+	Unavailable,
+}
+
+/// The result of dry-running a runtime upgrade.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UpgradeDryRun {
+	/// The version the candidate runtime reports.
+	pub version: RuntimeVersion,
+	/// The weight of the migrations, if they ran successfully.
+	pub migration_weight: Option<u64>,
+	/// The maximum weight of a block of the candidate runtime, if the migrations ran successfully.
+	pub max_block_weight: Option<u64>,
+	/// The outcome of the migrations and their checks.
+	pub try_state: TryStateOutcome,
+}
+
+/// Upgrade RPC methods.
+#[rpc]
+pub trait UpgradeApi {
+	/// Execute `Core_version` and `TryRuntime_on_runtime_upgrade` of the runtime `code` against the
+	/// state of block `at`, or the best block. The state is left untouched.
+	#[rpc(name = "upgrade_dryRun")]
+	fn dry_run(&self, code: Bytes, at: Option<Hash>) -> Result<UpgradeDryRun>;
+}
+
+/// Implements [`UpgradeApi`] by executing the candidate runtime with `executor`, always in wasm.
+pub struct Upgrade<C, B, E, S> {
+	client: Arc<C>,
+	backend: Arc<B>,
+	executor: E,
+	spawn_handle: S,
+	deny_unsafe: DenyUnsafe,
+}
+
+impl<C, B, E, S> Upgrade<C, B, E, S> {
+	/// Create a new instance of the upgrade RPC.
+	pub fn new(
+		client: Arc<C>,
+		backend: Arc<B>,
+		executor: E,
+		spawn_handle: S,
+		deny_unsafe: DenyUnsafe,
+	) -> Self {
+		Upgrade { client, backend, executor, spawn_handle, deny_unsafe }
+	}
+}
+
+fn execution_error(error: impl std::fmt::Display) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(1),
+		message: "Unable to execute the candidate runtime.".into(),
+		data: Some(error.to_string().into()),
+	}
+}
+
+impl<C, B, E, S> Upgrade<C, B, E, S>
+where
+	B: Backend<Block>,
+	E: CodeExecutor + Clone + 'static,
+	S: SpawnNamed + Clone + Send + 'static,
+{
+	/// Call `method` of `code` on `state`, discarding the changes.
+	fn call(
+		&self,
+		state: &B::State,
+		runtime_code: &RuntimeCode,
+		method: &str,
+	) -> std::result::Result<Vec<u8>, String> {
+		let mut overlay = OverlayedChanges::default();
+		StateMachine::new(
+			state,
+			&mut overlay,
+			&self.executor,
+			method,
+			&[],
+			Extensions::default(),
+			runtime_code,
+			self.spawn_handle.clone(),
+		)
+		.execute(ExecutionStrategy::AlwaysWasm)
+		.map_err(|e| e.to_string())
+	}
+}
+
+impl<C, B, E, S> UpgradeApi for Upgrade<C, B, E, S>
+where
+	C: HeaderBackend<Block> + Send + Sync + 'static,
+	B: Backend<Block> + Send + Sync + 'static,
+	E: CodeExecutor + Clone + Send + Sync + 'static,
+	S: SpawnNamed + Clone + Send + Sync + 'static,
+{
+	fn dry_run(&self, code: Bytes, at: Option<Hash>) -> Result<UpgradeDryRun> {
+		self.deny_unsafe.check_if_safe()?;
+
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		let state = self.backend.state_at(at).map_err(execution_error)?;
+		let runtime_code = RuntimeCode {
+			code_fetcher: &WrappedRuntimeCode(code.0.as_slice().into()),
+			heap_pages: None,
+			hash: sp_core::blake2_256(&code.0).to_vec(),
+		};
+
+		let version = self.call(&state, &runtime_code, "Core_version").map_err(execution_error)?;
+		let version = RuntimeVersion::decode(&mut &version[..]).map_err(execution_error)?;
+
+		if !version.has_api_with(&<dyn TryRuntime<Block>>::ID, |_| true) {
+			return Ok(UpgradeDryRun {
+				version,
+				migration_weight: None,
+				max_block_weight: None,
+				try_state: TryStateOutcome::Unavailable,
+			})
+		}
+
+		// A failing migration or check panics, which ends the execution with an error.
+		let (migration_weight, max_block_weight, try_state) =
+			match self.call(&state, &runtime_code, "TryRuntime_on_runtime_upgrade") {
+				Ok(weights) => {
+					let (weight, max_block) =
+						<(u64, u64)>::decode(&mut &weights[..]).map_err(execution_error)?;
+					(Some(weight), Some(max_block), TryStateOutcome::Passed)
+				},
+				Err(e) => (None, None, TryStateOutcome::Failed(e)),
+			};
+
+		Ok(UpgradeDryRun { version, migration_weight, max_block_weight, try_state })
+	}
+}
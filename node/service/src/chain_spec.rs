@@ -51,57 +51,6 @@ pub struct Extensions {
 // Dummy chain spec, in case when we don't have the native runtime.
 pub type DummyChainSpec = service::GenericChainSpec<(), Extensions>;
 
-/// The default parachains host configuration.
-#[cfg(any(feature = "selendra-native", feature = "cardamom-native",))]
-fn default_parachains_host_configuration(
-) -> selendra_runtime_parachains::configuration::HostConfiguration<
-	selendra_primitives::v1::BlockNumber,
-> {
-	use selendra_primitives::v1::{MAX_CODE_SIZE, MAX_POV_SIZE};
-
-	selendra_runtime_parachains::configuration::HostConfiguration {
-		validation_upgrade_cooldown: 2u32,
-		validation_upgrade_delay: 2,
-		code_retention_period: 1200,
-		max_code_size: MAX_CODE_SIZE,
-		max_pov_size: MAX_POV_SIZE,
-		max_head_data_size: 32 * 1024,
-		group_rotation_frequency: 20,
-		chain_availability_period: 4,
-		thread_availability_period: 4,
-		max_upward_queue_count: 8,
-		max_upward_queue_size: 1024 * 1024,
-		max_downward_message_size: 1024 * 1024,
-		ump_service_total_weight: 100_000_000_000,
-		max_upward_message_size: 50 * 1024,
-		max_upward_message_num_per_candidate: 5,
-		hrmp_sender_deposit: 0,
-		hrmp_recipient_deposit: 0,
-		hrmp_channel_max_capacity: 8,
-		hrmp_channel_max_total_size: 8 * 1024,
-		hrmp_max_parachain_inbound_channels: 4,
-		hrmp_max_parathread_inbound_channels: 4,
-		hrmp_channel_max_message_size: 1024 * 1024,
-		hrmp_max_parachain_outbound_channels: 4,
-		hrmp_max_parathread_outbound_channels: 4,
-		hrmp_max_message_num_per_candidate: 5,
-		dispute_period: 6,
-		no_show_slots: 2,
-		n_delay_tranches: 25,
-		needed_approvals: 2,
-		relay_vrf_modulo_samples: 2,
-		zeroth_delay_tranche_width: 0,
-		minimum_validation_upgrade_delay: 5,
-		..Default::default()
-	}
-}
-
-#[cfg(any(feature = "selendra-native", feature = "cardamom-native"))]
-#[test]
-fn default_parachains_host_configuration_is_consistent() {
-	default_parachains_host_configuration().panic_if_not_consistent();
-}
-
 /// Helper function to generate a crypto pair from seed
 pub fn get_from_seed<TPublic: Public>(seed: &str) -> <TPublic::Pair as Pair>::Public {
 	TPublic::Pair::from_string(&format!("//{}", seed), None)
@@ -160,23 +109,6 @@ pub fn get_authority_keys_from_seed_no_beefy(
 	)
 }
 
-fn testnet_accounts() -> Vec<AccountId> {
-	vec![
-		get_account_id_from_seed::<sr25519::Public>("Alice"),
-		get_account_id_from_seed::<sr25519::Public>("Bob"),
-		get_account_id_from_seed::<sr25519::Public>("Charlie"),
-		get_account_id_from_seed::<sr25519::Public>("Dave"),
-		get_account_id_from_seed::<sr25519::Public>("Eve"),
-		get_account_id_from_seed::<sr25519::Public>("Ferdie"),
-		get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
-		get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
-		get_account_id_from_seed::<sr25519::Public>("Charlie//stash"),
-		get_account_id_from_seed::<sr25519::Public>("Dave//stash"),
-		get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
-		get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
-	]
-}
-
 // Selendra-navtive chain spec
 #[cfg(feature = "selendra-native")]
 use selendra_runtime as selendra;
@@ -401,7 +333,7 @@ fn selendra_staging_testnet_config_genesis(wasm_binary: &[u8]) -> selendra::Gene
 		treasury: Default::default(),
 		hrmp: Default::default(),
 		configuration: selendra::ConfigurationConfig {
-			config: default_parachains_host_configuration(),
+			config: selendra::genesis_config_presets::default_parachains_host_configuration(),
 		},
 		paras: Default::default(),
 		xcm_pallet: Default::default(),
@@ -439,110 +371,22 @@ pub fn selendra_staging_testnet_config() -> Result<SelendraChainSpec, String> {
 	))
 }
 
-/// Helper function to create selendra `GenesisConfig` for testing
+/// Selendra genesis config of the given preset, built by the runtime.
 #[cfg(feature = "selendra-native")]
-pub fn selendra_testnet_genesis(
-	wasm_binary: &[u8],
-	initial_authorities: Vec<(
-		AccountId,
-		AccountId,
-		BabeId,
-		GrandpaId,
-		ImOnlineId,
-		ValidatorId,
-		AssignmentId,
-		AuthorityDiscoveryId,
-	)>,
-	_root_key: AccountId,
-	endowed_accounts: Option<Vec<AccountId>>,
-) -> selendra::GenesisConfig {
-	let endowed_accounts: Vec<AccountId> = endowed_accounts.unwrap_or_else(testnet_accounts);
-
-	const ENDOWMENT: u128 = 1_000_000_000_000 * SEL;
-	const STASH: u128 = 100 * SEL;
-
-	selendra::GenesisConfig {
-		system: selendra::SystemConfig { code: wasm_binary.to_vec() },
-		indices: selendra::IndicesConfig { indices: vec![] },
-		balances: selendra::BalancesConfig {
-			balances: endowed_accounts.iter().map(|k| (k.clone(), ENDOWMENT)).collect(),
-		},
-		session: selendra::SessionConfig {
-			keys: initial_authorities
-				.iter()
-				.map(|x| {
-					(
-						x.0.clone(),
-						x.0.clone(),
-						selendra_session_keys(
-							x.2.clone(),
-							x.3.clone(),
-							x.4.clone(),
-							x.5.clone(),
-							x.6.clone(),
-							x.7.clone(),
-						),
-					)
-				})
-				.collect::<Vec<_>>(),
-		},
-		staking: selendra::StakingConfig {
-			minimum_validator_count: 1,
-			validator_count: initial_authorities.len() as u32,
-			stakers: initial_authorities
-				.iter()
-				.map(|x| (x.0.clone(), x.1.clone(), STASH, selendra::StakerStatus::Validator))
-				.collect(),
-			invulnerables: initial_authorities.iter().map(|x| x.0.clone()).collect(),
-			slash_reward_fraction: Perbill::from_percent(10),
-			..Default::default()
-		},
-		phragmen_election: Default::default(),
-		democracy: selendra::DemocracyConfig::default(),
-		council: selendra::CouncilConfig { members: vec![], phantom: Default::default() },
-		technical_committee: selendra::TechnicalCommitteeConfig {
-			members: vec![],
-			phantom: Default::default(),
-		},
-		technical_membership: Default::default(),
-		babe: selendra::BabeConfig {
-			authorities: Default::default(),
-			epoch_config: Some(selendra::BABE_GENESIS_EPOCH_CONFIG),
-		},
-		grandpa: Default::default(),
-		im_online: Default::default(),
-		authority_discovery: selendra::AuthorityDiscoveryConfig { keys: vec![] },
-		vesting: selendra::VestingConfig { vesting: vec![] },
-		treasury: Default::default(),
-		hrmp: Default::default(),
-		configuration: selendra::ConfigurationConfig {
-			config: default_parachains_host_configuration(),
-		},
-		paras: Default::default(),
-		xcm_pallet: Default::default(),
-	}
-}
-
-#[cfg(feature = "selendra-native")]
-fn selendra_development_config_genesis(wasm_binary: &[u8]) -> selendra::GenesisConfig {
-	selendra_testnet_genesis(
-		wasm_binary,
-		vec![get_authority_keys_from_seed_no_beefy("Alice")],
-		get_account_id_from_seed::<sr25519::Public>("Alice"),
-		None,
-	)
+fn selendra_preset(name: &str) -> selendra::GenesisConfig {
+	selendra::genesis_config_presets::get_preset(name).expect("wasm binary and preset exist; qed")
 }
 
 /// Selendra development config (single validator Alice)
 #[cfg(feature = "selendra-native")]
 pub fn selendra_development_config() -> Result<SelendraChainSpec, String> {
-	let wasm_binary = selendra::WASM_BINARY.ok_or("Selendra development wasm not available")?;
+	selendra::WASM_BINARY.ok_or("Selendra development wasm not available")?;
 
 	Ok(SelendraChainSpec::from_genesis(
 		"Development",
 		"selendra_dev",
 		ChainType::Development,
-		move || selendra_development_config_genesis(wasm_binary),
+		|| selendra_preset(selendra::genesis_config_presets::DEVELOPMENT),
 		vec![],
 		None,
 		Some(DEFAULT_PROTOCOL_ID),
@@ -560,29 +404,16 @@ pub fn selendra_development_config() -> Result<SelendraChainSpec, String> {
 	))
 }
 
-#[cfg(feature = "selendra-native")]
-fn selendra_local_testnet_genesis(wasm_binary: &[u8]) -> selendra::GenesisConfig {
-	selendra_testnet_genesis(
-		wasm_binary,
-		vec![
-			get_authority_keys_from_seed_no_beefy("Alice"),
-			get_authority_keys_from_seed_no_beefy("Bob"),
-		],
-		get_account_id_from_seed::<sr25519::Public>("Alice"),
-		None,
-	)
-}
-
 /// Selendra local testnet config (multivalidator Alice + Bob)
 #[cfg(feature = "selendra-native")]
 pub fn selendra_local_testnet_config() -> Result<SelendraChainSpec, String> {
-	let wasm_binary = selendra::WASM_BINARY.ok_or("Selendra development wasm not available")?;
+	selendra::WASM_BINARY.ok_or("Selendra development wasm not available")?;
 
 	Ok(SelendraChainSpec::from_genesis(
 		"Selendra Local Testnet",
 		"selendra_local_testnet",
 		ChainType::Local,
-		move || selendra_local_testnet_genesis(wasm_binary),
+		|| selendra_preset(selendra::genesis_config_presets::LOCAL_TESTNET),
 		vec![],
 		None,
 		Some(DEFAULT_PROTOCOL_ID),
@@ -800,7 +631,7 @@ fn cardamom_staging_testnet_config_genesis(wasm_binary: &[u8]) -> cardamom::Gene
 		treasury: Default::default(),
 		hrmp: Default::default(),
 		configuration: cardamom::ConfigurationConfig {
-			config: default_parachains_host_configuration(),
+			config: cardamom::genesis_config_presets::default_parachains_host_configuration(),
 		},
 		paras: Default::default(),
 		xcm_pallet: Default::default(),
@@ -838,111 +669,22 @@ pub fn cardamom_staging_testnet_config() -> Result<CardamomChainSpec, String> {
 	))
 }
 
-/// Helper function to create cardamom `GenesisConfig` for testing
+/// Cardamom genesis config of the given preset, built by the runtime.
 #[cfg(feature = "cardamom-native")]
-pub fn cardamom_testnet_genesis(
-	wasm_binary: &[u8],
-	initial_authorities: Vec<(
-		AccountId,
-		AccountId,
-		BabeId,
-		GrandpaId,
-		ImOnlineId,
-		ValidatorId,
-		AssignmentId,
-		AuthorityDiscoveryId,
-	)>,
-	root_key: AccountId,
-	endowed_accounts: Option<Vec<AccountId>>,
-) -> cardamom::GenesisConfig {
-	let endowed_accounts: Vec<AccountId> = endowed_accounts.unwrap_or_else(testnet_accounts);
-
-	const ENDOWMENT: u128 = 1_000_000 * CDM;
-	const STASH: u128 = 100 * CDM;
-
-	cardamom::GenesisConfig {
-		system: cardamom::SystemConfig { code: wasm_binary.to_vec() },
-		balances: cardamom::BalancesConfig {
-			balances: endowed_accounts.iter().map(|k| (k.clone(), ENDOWMENT)).collect(),
-		},
-		indices: cardamom::IndicesConfig { indices: vec![] },
-		session: cardamom::SessionConfig {
-			keys: initial_authorities
-				.iter()
-				.map(|x| {
-					(
-						x.0.clone(),
-						x.0.clone(),
-						cardamom_session_keys(
-							x.2.clone(),
-							x.3.clone(),
-							x.4.clone(),
-							x.5.clone(),
-							x.6.clone(),
-							x.7.clone(),
-						),
-					)
-				})
-				.collect::<Vec<_>>(),
-		},
-		staking: cardamom::StakingConfig {
-			validator_count: 4,
-			minimum_validator_count: 4,
-			stakers: initial_authorities
-				.iter()
-				.map(|x| (x.0.clone(), x.1.clone(), STASH, cardamom::StakerStatus::Validator))
-				.collect(),
-			invulnerables: initial_authorities.iter().map(|x| x.0.clone()).collect(),
-			slash_reward_fraction: Perbill::from_percent(10),
-			..Default::default()
-		},
-		phragmen_election: Default::default(),
-		democracy: Default::default(),
-		council: cardamom::CouncilConfig { members: vec![], phantom: Default::default() },
-		technical_committee: cardamom::TechnicalCommitteeConfig {
-			members: vec![],
-			phantom: Default::default(),
-		},
-		technical_membership: Default::default(),
-		babe: cardamom::BabeConfig {
-			authorities: Default::default(),
-			epoch_config: Some(cardamom::BABE_GENESIS_EPOCH_CONFIG),
-		},
-		grandpa: Default::default(),
-		im_online: Default::default(),
-		authority_discovery: cardamom::AuthorityDiscoveryConfig { keys: vec![] },
-		vesting: cardamom::VestingConfig { vesting: vec![] },
-		treasury: Default::default(),
-		sudo: cardamom::SudoConfig { key: Some(root_key) },
-		hrmp: Default::default(),
-		configuration: cardamom::ConfigurationConfig {
-			config: default_parachains_host_configuration(),
-		},
-		paras: Default::default(),
-		xcm_pallet: Default::default(),
-	}
-}
-
-#[cfg(feature = "cardamom-native")]
-fn cardamom_development_config_genesis(wasm_binary: &[u8]) -> cardamom::GenesisConfig {
-	cardamom_testnet_genesis(
-		wasm_binary,
-		vec![get_authority_keys_from_seed_no_beefy("Alice")],
-		get_account_id_from_seed::<sr25519::Public>("Alice"),
-		None,
-	)
+fn cardamom_preset(name: &str) -> cardamom::GenesisConfig {
+	cardamom::genesis_config_presets::get_preset(name).expect("wasm binary and preset exist; qed")
 }
 
 /// Cardamom development config (single validator Alice)
 #[cfg(feature = "cardamom-native")]
 pub fn cardamom_development_config() -> Result<CardamomChainSpec, String> {
-	let wasm_binary = cardamom::WASM_BINARY.ok_or("Cardamom development wasm not available")?;
+	cardamom::WASM_BINARY.ok_or("Cardamom development wasm not available")?;
 
 	Ok(CardamomChainSpec::from_genesis(
 		"Development",
 		"cardamom_dev",
 		ChainType::Development,
-		move || cardamom_development_config_genesis(wasm_binary),
+		|| cardamom_preset(cardamom::genesis_config_presets::DEVELOPMENT),
 		vec![],
 		None,
 		Some(DEFAULT_PROTOCOL_ID),
@@ -960,29 +702,16 @@ pub fn cardamom_development_config() -> Result<CardamomChainSpec, String> {
 	))
 }
 
-#[cfg(feature = "cardamom-native")]
-fn cardamom_local_testnet_genesis(wasm_binary: &[u8]) -> cardamom::GenesisConfig {
-	cardamom_testnet_genesis(
-		wasm_binary,
-		vec![
-			get_authority_keys_from_seed_no_beefy("Alice"),
-			get_authority_keys_from_seed_no_beefy("Bob"),
-		],
-		get_account_id_from_seed::<sr25519::Public>("Alice"),
-		None,
-	)
-}
-
 /// Cardamom local testnet config (multivalidator Alice + Bob)
 #[cfg(feature = "cardamom-native")]
 pub fn cardamom_local_testnet_config() -> Result<CardamomChainSpec, String> {
-	let wasm_binary = cardamom::WASM_BINARY.ok_or("Cardamom development wasm not available")?;
+	cardamom::WASM_BINARY.ok_or("Cardamom development wasm not available")?;
 
 	Ok(CardamomChainSpec::from_genesis(
 		"Cardamom Local Testnet",
 		"cardamom_local_testnet",
 		ChainType::Local,
-		move || cardamom_local_testnet_genesis(wasm_binary),
+		|| cardamom_preset(cardamom::genesis_config_presets::LOCAL_TESTNET),
 		vec![],
 		None,
 		Some(DEFAULT_PROTOCOL_ID),
@@ -0,0 +1,121 @@
+// Copyright 2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! The key a collator declares itself and signs its collations with, kept in the keystore.
+
+use sc_keystore::LocalKeystore;
+use selendra_primitives::v1::{CollatorId, CollatorPair, COLLATOR_KEY_TYPE_ID};
+use service::config::KeystoreConfig;
+use sp_core::Pair;
+use sp_keystore::SyncCryptoStore;
+
+/// An error loading the collator key.
+#[derive(Debug, thiserror::Error)]
+pub enum CollatorKeyError {
+	#[error("Failed to open the keystore: {0}")]
+	Keystore(#[from] sc_keystore::Error),
+
+	#[error("Keystore operation failed: {0}")]
+	KeystoreOperation(#[from] sp_keystore::Error),
+
+	#[error("Invalid collator key secret URI")]
+	InvalidSuri,
+
+	#[error("Collator key {0} is not in the keystore")]
+	NotFound(CollatorId),
+}
+
+/// Load the collator key from the keystore described by `config`.
+///
+/// If `suri` is given, the key derived from it is put into the keystore and used. Otherwise the
+/// collator key already in the keystore is used, or a new one is generated into the keystore if
+/// there is none, so the collator keeps its identity across restarts.
+pub fn collator_key(
+	config: &KeystoreConfig,
+	suri: Option<&str>,
+) -> Result<CollatorPair, CollatorKeyError> {
+	let keystore = match config {
+		KeystoreConfig::Path { path, password } =>
+			LocalKeystore::open(path.clone(), password.clone())?,
+		KeystoreConfig::InMemory => LocalKeystore::in_memory(),
+	};
+
+	collator_key_from_keystore(&keystore, suri)
+}
+
+/// Load the collator key from `keystore`, see [`collator_key`].
+pub fn collator_key_from_keystore(
+	keystore: &LocalKeystore,
+	suri: Option<&str>,
+) -> Result<CollatorPair, CollatorKeyError> {
+	let public: CollatorId = match suri {
+		Some(suri) => {
+			let public = CollatorPair::from_string(suri, None)
+				.map_err(|_| CollatorKeyError::InvalidSuri)?
+				.public();
+			SyncCryptoStore::insert_unknown(
+				keystore,
+				COLLATOR_KEY_TYPE_ID,
+				suri,
+				AsRef::<[u8]>::as_ref(&public),
+			)
+			.map_err(|()| CollatorKeyError::KeystoreOperation(sp_keystore::Error::Unavailable))?;
+			public
+		},
+		None => match SyncCryptoStore::sr25519_public_keys(keystore, COLLATOR_KEY_TYPE_ID)
+			.into_iter()
+			.next()
+		{
+			Some(public) => public.into(),
+			None =>
+				SyncCryptoStore::sr25519_generate_new(keystore, COLLATOR_KEY_TYPE_ID, None)?.into(),
+		},
+	};
+
+	keystore
+		.key_pair::<CollatorPair>(&public)?
+		.ok_or_else(|| CollatorKeyError::NotFound(public))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn collator_key_is_kept_in_the_keystore() {
+		let keystore = LocalKeystore::in_memory();
+
+		let generated = collator_key_from_keystore(&keystore, None).unwrap();
+		assert_eq!(SyncCryptoStore::sr25519_public_keys(&keystore, COLLATOR_KEY_TYPE_ID).len(), 1,);
+		let loaded = collator_key_from_keystore(&keystore, None).unwrap();
+		assert_eq!(generated.public(), loaded.public());
+	}
+
+	#[test]
+	fn collator_key_can_be_set_by_suri() {
+		let keystore = LocalKeystore::in_memory();
+
+		let set = collator_key_from_keystore(&keystore, Some("//Alice")).unwrap();
+		assert_eq!(set.public(), CollatorPair::from_string("//Alice", None).unwrap().public());
+
+		let signature = set.sign(b"collation");
+		assert!(CollatorPair::verify(&signature, b"collation", &set.public()));
+		assert_eq!(
+			SyncCryptoStore::sr25519_public_keys(&keystore, COLLATOR_KEY_TYPE_ID),
+			vec![set.public().into()],
+		);
+	}
+}
@@ -0,0 +1,44 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Passing the election miner settings of the node to the offchain worker of the runtime.
+
+use parity_scale_codec::Encode;
+use sc_client_api::Backend as _;
+use selendra_primitives::election::{MinerSettings, MINER_SETTINGS_KEY};
+use sp_core::offchain::{OffchainStorage, STORAGE_PREFIX};
+
+use crate::FullBackend;
+
+/// Store the settings the offchain worker mines unsigned election solutions with, or remove the
+/// stored ones if `settings` is `None`, so that the runtime falls back to its defaults.
+pub fn store_miner_settings(backend: &FullBackend, settings: Option<MinerSettings>) {
+	let mut storage = match backend.offchain_storage() {
+		Some(storage) => storage,
+		None => {
+			tracing::warn!("Offchain storage unavailable, election miner settings are ignored");
+			return
+		},
+	};
+
+	match settings {
+		Some(settings) => {
+			tracing::info!(?settings, "Mining unsigned election solutions with custom settings");
+			storage.set(STORAGE_PREFIX, MINER_SETTINGS_KEY, &settings.encode());
+		},
+		None => storage.remove(STORAGE_PREFIX, MINER_SETTINGS_KEY),
+	}
+}
@@ -19,9 +19,21 @@
 #![deny(unused_results)]
 
 pub mod chain_spec;
+#[cfg(feature = "full-node")]
+pub mod collator_key;
+#[cfg(feature = "full-node")]
+pub mod election_miner;
 mod grandpa_support;
+#[cfg(feature = "full-node")]
+mod parachain_telemetry;
 mod parachains_db;
 mod relay_chain_selection;
+#[cfg(feature = "full-node")]
+mod runtime_api_proxy;
+#[cfg(feature = "full-node")]
+mod runtime_build;
+#[cfg(feature = "full-node")]
+mod runtime_compat;
 
 #[cfg(feature = "full-node")]
 pub mod overseer;
@@ -29,6 +41,9 @@ pub mod overseer;
 #[cfg(feature = "full-node")]
 pub use self::overseer::{OverseerGen, OverseerGenArgs, RealOverseerGen};
 
+#[cfg(feature = "full-node")]
+pub use self::parachains_db::{export_parachains_db, import_parachains_db};
+
 #[cfg(test)]
 mod tests;
 
@@ -56,7 +71,7 @@ pub use {
 	relay_chain_selection::SelectRelayChain,
 	sc_client_api::AuxStore,
 	selendra_overseer::{Handle, Overseer, OverseerConnector, OverseerHandle},
-	selendra_primitives::v2::ParachainHost,
+	selendra_primitives::{executor_params::ExecutorParamsApi, v2::ParachainHost},
 	sp_authority_discovery::AuthorityDiscoveryApi,
 	sp_blockchain::HeaderBackend,
 	sp_consensus_babe::BabeApi,
@@ -215,6 +230,10 @@ pub enum Error {
 	#[error(transparent)]
 	Availability(#[from] AvailabilityError),
 
+	#[cfg(feature = "full-node")]
+	#[error(transparent)]
+	CollatorKey(#[from] collator_key::CollatorKeyError),
+
 	#[error("Authorities require the real overseer implementation")]
 	AuthoritiesRequireRealOverseer,
 
@@ -225,6 +244,10 @@ pub enum Error {
 	#[cfg(feature = "full-node")]
 	#[error("Expected at least one of polkadot, cardamom, runtime feature")]
 	NoRuntime,
+
+	#[cfg(feature = "full-node")]
+	#[error("ParachainHost API version {found:?} is unsupported, {required} or newer required")]
+	UnsupportedParachainHost { found: Option<u32>, required: u32 },
 }
 
 /// Can be called for a `Configuration` to identify which network the configuration targets.
@@ -635,6 +658,9 @@ where
 /// `overseer_enable_anyways` always enables the overseer, based on the provided `OverseerGenerator`,
 /// regardless of the role the node has. The relay chain selection (longest or disputes-aware) is
 /// still determined based on the role of the node. Likewise for authority discovery.
+///
+/// `availability_memory_budget` limits the bytes held in flight for PoVs and availability data,
+/// falling back to the default `memory_budget::DEFAULT_MEMORY_BUDGET` of the subsystem util.
 #[cfg(feature = "full-node")]
 pub fn new_full<RuntimeApi, ExecutorDispatch, OverseerGenerator>(
 	mut config: Configuration,
@@ -644,6 +670,7 @@ pub fn new_full<RuntimeApi, ExecutorDispatch, OverseerGenerator>(
 	jaeger_agent: Option<std::net::SocketAddr>,
 	telemetry_worker_handle: Option<TelemetryWorkerHandle>,
 	program_path: Option<std::path::PathBuf>,
+	availability_memory_budget: Option<usize>,
 	overseer_enable_anyways: bool,
 	overseer_gen: OverseerGenerator,
 ) -> Result<NewFull<Arc<FullClient<RuntimeApi, ExecutorDispatch>>>, Error>
@@ -686,7 +713,32 @@ where
 	let auth_or_collator = role.is_authority() || is_collator.is_collator();
 	let requires_overseer_for_chain_sel = local_keystore.is_some() && auth_or_collator;
 
-	let disputes_enabled = chain_spec.is_cardamom();
+	let mut disputes_enabled = chain_spec.is_cardamom();
+
+	let parachain_host = if requires_overseer_for_chain_sel {
+		let best_hash = basics.client.info().best_hash;
+		let compatibility = runtime_compat::Compatibility::at(&*basics.client, best_hash);
+		match compatibility {
+			runtime_compat::Compatibility::Full => {},
+			runtime_compat::Compatibility::WithoutDisputes(version) => {
+				if disputes_enabled {
+					tracing::warn!(
+						version,
+						"The ParachainHost API of the runtime predates disputes, disabling them.",
+					);
+				}
+				disputes_enabled = false;
+			},
+			runtime_compat::Compatibility::Unsupported(found) =>
+				return Err(Error::UnsupportedParachainHost {
+					found,
+					required: runtime_compat::MIN_PARACHAIN_HOST_VERSION,
+				}),
+		}
+		Some(compatibility)
+	} else {
+		None
+	};
 
 	let pvf_checker_enabled = false;
 
@@ -739,11 +791,15 @@ where
 		&config.chain_spec,
 	);
 
-	{
-		use selendra_network_bridge::{peer_sets_info, IsAuthority};
-		let is_authority = if role.is_authority() { IsAuthority::Yes } else { IsAuthority::No };
-		config.network.extra_sets.extend(peer_sets_info(is_authority));
-	}
+	let is_authority = if role.is_authority() {
+		selendra_network_bridge::IsAuthority::Yes
+	} else {
+		selendra_network_bridge::IsAuthority::No
+	};
+	config
+		.network
+		.extra_sets
+		.extend(selendra_network_bridge::peer_sets_info(is_authority));
 
 	let (pov_req_receiver, cfg) = IncomingRequest::get_config_receiver();
 	config.network.request_response_protocols.push(cfg);
@@ -757,6 +813,9 @@ where
 	config.network.request_response_protocols.push(cfg);
 	let (dispute_req_receiver, cfg) = IncomingRequest::get_config_receiver();
 	config.network.request_response_protocols.push(cfg);
+	let (runtime_api_proxy_receiver, cfg) = IncomingRequest::get_config_receiver();
+	config.network.request_response_protocols.push(cfg);
+	let runtime_api_proxy_key = runtime_api_proxy::signing_key(config.network.node_key.clone());
 
 	let grandpa_hard_forks = if config.chain_spec.is_cardamom() {
 		grandpa_support::cardamom_hard_forks()
@@ -781,6 +840,45 @@ where
 			warp_sync: Some(warp_sync),
 		})?;
 
+	match runtime_api_proxy_key {
+		Some(key) => task_manager.spawn_handle().spawn(
+			"runtime-api-proxy",
+			None,
+			runtime_api_proxy::run(client.clone(), runtime_api_proxy_receiver, key),
+		),
+		None => tracing::warn!("Node key is not ed25519, not answering runtime API proxy requests"),
+	}
+
+	runtime_build::report(&*client, telemetry.as_ref().map(|x| x.handle()));
+	if let Some(parachain_host) = parachain_host {
+		task_manager.spawn_handle().spawn(
+			"parachain-host-compatibility",
+			None,
+			runtime_compat::watch_upgrades(client.clone(), parachain_host),
+		);
+	}
+
+	parachain_telemetry::report_features(
+		telemetry.as_ref().map(|x| x.handle()),
+		parachain_telemetry::Features {
+			authority: role.is_authority(),
+			collator: is_collator.is_collator(),
+			disputes: disputes_enabled,
+			pvf_checker: pvf_checker_enabled,
+		},
+	);
+	if let (true, Some(telemetry)) = (role.is_authority(), telemetry.as_ref()) {
+		task_manager.spawn_handle().spawn(
+			"parachain-assignment-telemetry",
+			None,
+			parachain_telemetry::report_assignments(
+				client.clone(),
+				keystore_container.sync_keystore(),
+				telemetry.handle(),
+			),
+		);
+	}
+
 	if config.offchain_worker.enabled {
 		let offchain_workers = Arc::new(sc_offchain::OffchainWorkers::new_with_options(
 			client.clone(),
@@ -858,6 +956,57 @@ where
 		col_data: crate::parachains_db::REAL_COLUMNS.col_dispute_coordinator_data,
 	};
 
+	let network_bridge_config = selendra_network_bridge::Config {
+		col_data: crate::parachains_db::REAL_COLUMNS.col_network_bridge_data,
+		bandwidth: Default::default(),
+	};
+
+	let bitfield_signing_config = selendra_node_core_bitfield_signing::Config {
+		col_data: crate::parachains_db::REAL_COLUMNS.col_bitfield_signing_data,
+	};
+
+	let statement_distribution_config = selendra_statement_distribution::Config {
+		col_data: crate::parachains_db::REAL_COLUMNS.col_statement_distribution_data,
+	};
+
+	// The operator RPC needs the overseer, which only exists once the partial components are built.
+	// The upgrade RPC executes candidate runtimes with an executor of its own, always in wasm.
+	let rpc_extensions_builder = {
+		let overseer_handle = requires_overseer_for_chain_sel.then(|| overseer_handle.clone());
+		let client = client.clone();
+		let backend = backend.clone();
+		let upgrade_executor = NativeElseWasmExecutor::<ExecutorDispatch>::new(
+			config.wasm_method,
+			config.default_heap_pages,
+			config.max_runtime_instances,
+			config.runtime_cache_size,
+		);
+		let spawn_handle = task_manager.spawn_handle();
+
+		move |deny_unsafe,
+		      subscription_executor: selendra_rpc::SubscriptionTaskExecutor|
+		      -> Result<selendra_rpc::RpcExtension, service::Error> {
+			use selendra_rpc::{
+				operator::{Operator, OperatorApi},
+				upgrade::{Upgrade, UpgradeApi},
+			};
+
+			let mut io = rpc_extensions_builder(deny_unsafe, subscription_executor)?;
+			io.extend_with(OperatorApi::to_delegate(Operator::new(
+				overseer_handle.clone(),
+				deny_unsafe,
+			)));
+			io.extend_with(UpgradeApi::to_delegate(Upgrade::new(
+				client.clone(),
+				backend.clone(),
+				upgrade_executor.clone(),
+				spawn_handle.clone(),
+				deny_unsafe,
+			)));
+			Ok(io)
+		}
+	};
+
 	let rpc_handlers = service::spawn_tasks(service::SpawnTasksParams {
 		config,
 		backend: backend.clone(),
@@ -926,6 +1075,12 @@ where
 	let maybe_params =
 		local_keystore.and_then(move |k| authority_discovery_service.map(|a| (a, k)));
 
+	let memory_budget = selendra_node_subsystem_util::memory_budget::MemoryBudget::new(
+		availability_memory_budget
+			.unwrap_or(selendra_node_subsystem_util::memory_budget::DEFAULT_MEMORY_BUDGET),
+		selendra_node_subsystem_util::metrics::Metrics::register(prometheus_registry.as_ref())?,
+	);
+
 	let overseer_handle = if let Some((authority_discovery_service, keystore)) = maybe_params {
 		let (overseer, overseer_handle) = overseer_gen
 			.generate::<service::SpawnTaskHandle, FullClient<RuntimeApi, ExecutorDispatch>>(
@@ -937,6 +1092,9 @@ where
 					parachains_db,
 					network_service: network.clone(),
 					authority_discovery_service,
+					network_bridge_config,
+					bitfield_signing_config,
+					statement_distribution_config,
 					pov_req_receiver,
 					chunk_req_receiver,
 					collation_req_receiver,
@@ -946,6 +1104,7 @@ where
 					registry: prometheus_registry.as_ref(),
 					spawner,
 					is_collator,
+					is_authority,
 					approval_voting_config,
 					availability_config,
 					candidate_validation_config,
@@ -953,6 +1112,7 @@ where
 					dispute_coordinator_config,
 					disputes_enabled,
 					pvf_checker_enabled,
+					memory_budget,
 				},
 			)
 			.map_err(|e| {
@@ -1191,6 +1351,8 @@ pub fn new_chain_ops(
 /// `overseer_enable_anyways` always enables the overseer, based on the provided `OverseerGenerator`,
 /// regardless of the role the node has. The relay chain selection (longest or disputes-aware) is
 /// still determined based on the role of the node. Likewise for authority discovery.
+///
+/// `availability_memory_budget` limits the bytes held in flight for PoVs and availability data.
 #[cfg(feature = "full-node")]
 pub fn build_full(
 	config: Configuration,
@@ -1199,6 +1361,7 @@ pub fn build_full(
 	enable_beefy: bool,
 	jaeger_agent: Option<std::net::SocketAddr>,
 	telemetry_worker_handle: Option<TelemetryWorkerHandle>,
+	availability_memory_budget: Option<usize>,
 	overseer_enable_anyways: bool,
 	overseer_gen: impl OverseerGen,
 ) -> Result<NewFull<Client>, Error> {
@@ -1212,6 +1375,7 @@ pub fn build_full(
 			jaeger_agent,
 			telemetry_worker_handle,
 			None,
+			availability_memory_budget,
 			overseer_enable_anyways,
 			overseer_gen,
 		)
@@ -1228,6 +1392,7 @@ pub fn build_full(
 			jaeger_agent,
 			telemetry_worker_handle,
 			None,
+			availability_memory_budget,
 			overseer_enable_anyways,
 			overseer_gen,
 		)
@@ -14,16 +14,23 @@
 // You should have received a copy of the GNU General Public License
 // along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
 
-use super::{AuthorityDiscoveryApi, Block, Error, Hash, IsCollator, Registry, SpawnNamed};
+use super::{
+	AuthorityDiscoveryApi, Block, Error, ExecutorParamsApi, Hash, IsCollator, Registry, SpawnNamed,
+};
 use lru::LruCache;
 use selendra_availability_distribution::IncomingRequestReceivers;
+use selendra_network_bridge::Config as NetworkBridgeConfig;
 use selendra_node_core_approval_voting::Config as ApprovalVotingConfig;
 use selendra_node_core_av_store::Config as AvailabilityConfig;
+use selendra_node_core_bitfield_signing::{
+	Checkpoints as BitfieldCheckpoints, Config as BitfieldSigningConfig,
+};
 use selendra_node_core_candidate_validation::Config as CandidateValidationConfig;
 use selendra_node_core_chain_selection::Config as ChainSelectionConfig;
 use selendra_node_core_dispute_coordinator::Config as DisputeCoordinatorConfig;
 use selendra_node_core_provisioner::ProvisionerConfig;
 use selendra_node_network_protocol::request_response::{v1 as request_v1, IncomingRequestReceiver};
+use selendra_node_subsystem_util::memory_budget::MemoryBudget;
 #[cfg(any(feature = "malus", test))]
 pub use selendra_overseer::{
 	dummy::{dummy_overseer_builder, DummySubsystem},
@@ -38,6 +45,7 @@ use sc_authority_discovery::Service as AuthorityDiscoveryService;
 use sc_client_api::AuxStore;
 use sc_keystore::LocalKeystore;
 use selendra_primitives::v2::ParachainHost;
+use selendra_statement_distribution::Config as StatementDistributionConfig;
 use sp_api::ProvideRuntimeApi;
 use sp_blockchain::HeaderBackend;
 use sp_consensus_babe::BabeApi;
@@ -50,7 +58,7 @@ pub use selendra_availability_recovery::AvailabilityRecoverySubsystem;
 pub use selendra_collator_protocol::{CollatorProtocolSubsystem, ProtocolSide};
 pub use selendra_dispute_distribution::DisputeDistributionSubsystem;
 pub use selendra_gossip_support::GossipSupport as GossipSupportSubsystem;
-pub use selendra_network_bridge::NetworkBridge as NetworkBridgeSubsystem;
+pub use selendra_network_bridge::{IsAuthority, NetworkBridge as NetworkBridgeSubsystem};
 pub use selendra_node_collation_generation::CollationGenerationSubsystem;
 pub use selendra_node_core_approval_voting::ApprovalVotingSubsystem;
 pub use selendra_node_core_av_store::AvailabilityStoreSubsystem;
@@ -69,7 +77,10 @@ pub use selendra_statement_distribution::StatementDistributionSubsystem;
 pub struct OverseerGenArgs<'a, Spawner, RuntimeClient>
 where
 	RuntimeClient: 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block> + AuxStore,
-	RuntimeClient::Api: ParachainHost<Block> + BabeApi<Block> + AuthorityDiscoveryApi<Block>,
+	RuntimeClient::Api: ParachainHost<Block>
+		+ BabeApi<Block>
+		+ AuthorityDiscoveryApi<Block>
+		+ ExecutorParamsApi<Block>,
 	Spawner: 'static + SpawnNamed + Clone + Unpin,
 {
 	/// Set of initial relay chain leaves to track.
@@ -84,6 +95,12 @@ where
 	pub network_service: Arc<sc_network::NetworkService<Block, Hash>>,
 	/// Underlying authority discovery service.
 	pub authority_discovery_service: AuthorityDiscoveryService,
+	/// Configuration for the network bridge subsystem.
+	pub network_bridge_config: NetworkBridgeConfig,
+	/// Configuration for the bitfield signing subsystem.
+	pub bitfield_signing_config: BitfieldSigningConfig,
+	/// Configuration for the statement distribution subsystem.
+	pub statement_distribution_config: StatementDistributionConfig,
 	/// POV request receiver
 	pub pov_req_receiver: IncomingRequestReceiver<request_v1::PoVFetchingRequest>,
 	pub chunk_req_receiver: IncomingRequestReceiver<request_v1::ChunkFetchingRequest>,
@@ -98,6 +115,9 @@ where
 	pub spawner: Spawner,
 	/// Determines the behavior of the collator.
 	pub is_collator: IsCollator,
+	/// Whether the node is an authority, which restricts the peers validation protocol
+	/// messages are accepted from.
+	pub is_authority: IsAuthority,
 	/// Configuration for the approval voting subsystem.
 	pub approval_voting_config: ApprovalVotingConfig,
 	/// Configuration for the availability store subsystem.
@@ -112,6 +132,8 @@ where
 	pub disputes_enabled: bool,
 	/// Enable PVF pre-checking
 	pub pvf_checker_enabled: bool,
+	/// Budget for the PoVs and availability data held in flight.
+	pub memory_budget: MemoryBudget,
 }
 
 /// Obtain a prepared `OverseerBuilder`, that is initialized
@@ -124,6 +146,9 @@ pub fn prepared_overseer_builder<'a, Spawner, RuntimeClient>(
 		parachains_db,
 		network_service,
 		authority_discovery_service,
+		network_bridge_config,
+		bitfield_signing_config,
+		statement_distribution_config,
 		pov_req_receiver,
 		chunk_req_receiver,
 		collation_req_receiver,
@@ -133,6 +158,7 @@ pub fn prepared_overseer_builder<'a, Spawner, RuntimeClient>(
 		registry,
 		spawner,
 		is_collator,
+		is_authority,
 		approval_voting_config,
 		availability_config,
 		candidate_validation_config,
@@ -140,6 +166,7 @@ pub fn prepared_overseer_builder<'a, Spawner, RuntimeClient>(
 		dispute_coordinator_config,
 		disputes_enabled,
 		pvf_checker_enabled,
+		memory_budget,
 	}: OverseerGenArgs<'a, Spawner, RuntimeClient>,
 ) -> Result<
 	InitializedOverseerBuilder<
@@ -174,7 +201,10 @@ pub fn prepared_overseer_builder<'a, Spawner, RuntimeClient>(
 >
 where
 	RuntimeClient: 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block> + AuxStore,
-	RuntimeClient::Api: ParachainHost<Block> + BabeApi<Block> + AuthorityDiscoveryApi<Block>,
+	RuntimeClient::Api: ParachainHost<Block>
+		+ BabeApi<Block>
+		+ AuthorityDiscoveryApi<Block>
+		+ ExecutorParamsApi<Block>,
 	Spawner: 'static + SpawnNamed + Clone + Unpin,
 {
 	use selendra_node_subsystem_util::metrics::Metrics;
@@ -185,6 +215,7 @@ where
 		.availability_distribution(AvailabilityDistributionSubsystem::new(
 			keystore.clone(),
 			IncomingRequestReceivers { pov_req_receiver, chunk_req_receiver },
+			memory_budget.clone(),
 			Metrics::register(registry)?,
 		))
 		.availability_recovery(AvailabilityRecoverySubsystem::with_chunks_only(
@@ -199,12 +230,15 @@ where
 		.bitfield_distribution(BitfieldDistributionSubsystem::new(Metrics::register(registry)?))
 		.bitfield_signing(BitfieldSigningSubsystem::new(
 			spawner.clone(),
-			keystore.clone(),
+			(
+				keystore.clone(),
+				BitfieldCheckpoints::new(parachains_db.clone(), bitfield_signing_config),
+			),
 			Metrics::register(registry)?,
 		))
 		.candidate_backing(CandidateBackingSubsystem::new(
 			spawner.clone(),
-			keystore.clone(),
+			(keystore.clone(), memory_budget),
 			Metrics::register(registry)?,
 		))
 		.candidate_validation(CandidateValidationSubsystem::with_config(
@@ -240,6 +274,9 @@ where
 			authority_discovery_service.clone(),
 			Box::new(network_service.clone()),
 			Metrics::register(registry)?,
+			is_authority,
+			parachains_db.clone(),
+			network_bridge_config,
 		))
 		.provisioner(ProvisionerSubsystem::new(
 			spawner.clone(),
@@ -255,6 +292,8 @@ where
 			keystore.clone(),
 			statement_req_receiver,
 			Metrics::register(registry)?,
+			parachains_db.clone(),
+			statement_distribution_config,
 		))
 		.approval_distribution(ApprovalDistributionSubsystem::new(Metrics::register(registry)?))
 		.approval_voting(ApprovalVotingSubsystem::with_config(
@@ -314,7 +353,10 @@ pub trait OverseerGen {
 	) -> Result<(Overseer<Spawner, Arc<RuntimeClient>>, OverseerHandle), Error>
 	where
 		RuntimeClient: 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block> + AuxStore,
-		RuntimeClient::Api: ParachainHost<Block> + BabeApi<Block> + AuthorityDiscoveryApi<Block>,
+		RuntimeClient::Api: ParachainHost<Block>
+			+ BabeApi<Block>
+			+ AuthorityDiscoveryApi<Block>
+			+ ExecutorParamsApi<Block>,
 		Spawner: 'static + SpawnNamed + Clone + Unpin,
 	{
 		let gen = RealOverseerGen;
@@ -338,7 +380,10 @@ impl OverseerGen for RealOverseerGen {
 	) -> Result<(Overseer<Spawner, Arc<RuntimeClient>>, OverseerHandle), Error>
 	where
 		RuntimeClient: 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block> + AuxStore,
-		RuntimeClient::Api: ParachainHost<Block> + BabeApi<Block> + AuthorityDiscoveryApi<Block>,
+		RuntimeClient::Api: ParachainHost<Block>
+			+ BabeApi<Block>
+			+ AuthorityDiscoveryApi<Block>
+			+ ExecutorParamsApi<Block>,
 		Spawner: 'static + SpawnNamed + Clone + Unpin,
 	{
 		prepared_overseer_builder(args)?
@@ -0,0 +1,146 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Telemetry of the parachain role of the node, for dashboards of per-para validator coverage.
+
+use futures::StreamExt;
+use sc_client_api::BlockchainEvents;
+use selendra_node_subsystem_util::{find_validator_group, signing_key_and_index};
+use selendra_primitives::{
+	v1::{Block, Hash},
+	v2::ParachainHost,
+};
+use sp_api::ProvideRuntimeApi;
+use sp_keystore::SyncCryptoStorePtr;
+use sp_runtime::generic::BlockId;
+use std::sync::Arc;
+use telemetry::{telemetry, TelemetryHandle, SUBSTRATE_INFO};
+
+use crate::runtime_compat::PARACHAIN_HOST_VERSION;
+
+/// The parachain subsystem features the node was started with.
+pub(crate) struct Features {
+	/// Whether the node is a validator.
+	pub authority: bool,
+	/// Whether the node is a collator.
+	pub collator: bool,
+	/// Whether disputes are enabled.
+	pub disputes: bool,
+	/// Whether PVF pre-checking is enabled.
+	pub pvf_checker: bool,
+}
+
+/// Send the features the node was started with to telemetry.
+pub(crate) fn report_features(telemetry: Option<TelemetryHandle>, features: Features) {
+	telemetry!(
+		telemetry;
+		SUBSTRATE_INFO;
+		"node.parachain_features";
+		"authority" => features.authority,
+		"collator" => features.collator,
+		"disputes" => features.disputes,
+		"pvf_checker" => features.pvf_checker,
+		"parachain_host_version" => PARACHAIN_HOST_VERSION,
+	);
+}
+
+/// The parachain assignment of a validator at a block.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+struct Assignment {
+	/// The index of the validator in the active set, `None` if it is not active.
+	validator_index: Option<u32>,
+	/// The backing group the validator is in.
+	group: Option<u32>,
+	/// The para scheduled on or occupying the core of the group.
+	para: Option<u32>,
+}
+
+async fn assignment_at<Client>(
+	client: &Client,
+	keystore: &SyncCryptoStorePtr,
+	at: Hash,
+) -> Result<Assignment, sp_api::ApiError>
+where
+	Client: ProvideRuntimeApi<Block>,
+	Client::Api: ParachainHost<Block>,
+{
+	let api = client.runtime_api();
+	let at = BlockId::Hash(at);
+
+	let validators = api.validators(&at)?;
+	let index = match signing_key_and_index(&validators, keystore).await {
+		Some((_, index)) => index,
+		None => return Ok(Assignment::default()),
+	};
+
+	let (groups, rotation) = api.validator_groups(&at)?;
+	let group = match find_validator_group(&groups, index) {
+		Some(group) => group,
+		None => return Ok(Assignment { validator_index: Some(index.0), ..Default::default() }),
+	};
+
+	let cores = api.availability_cores(&at)?;
+	let core = rotation.core_for_group(group, cores.len());
+	let para = cores.get(core.0 as usize).and_then(|core| core.para_id());
+
+	Ok(Assignment {
+		validator_index: Some(index.0),
+		group: Some(group.0),
+		para: para.map(u32::from),
+	})
+}
+
+/// Send the parachain assignment of the validator to telemetry whenever it changes with a new best
+/// block.
+pub(crate) async fn report_assignments<Client>(
+	client: Arc<Client>,
+	keystore: SyncCryptoStorePtr,
+	telemetry: TelemetryHandle,
+) where
+	Client: ProvideRuntimeApi<Block> + BlockchainEvents<Block>,
+	Client::Api: ParachainHost<Block>,
+{
+	let telemetry = Some(telemetry);
+	let mut last = None;
+	let mut imports = client.import_notification_stream();
+	while let Some(import) = imports.next().await {
+		if !import.is_new_best {
+			continue
+		}
+
+		let assignment = match assignment_at(&*client, &keystore, import.hash).await {
+			Ok(assignment) => assignment,
+			Err(error) => {
+				tracing::debug!(?error, "Cannot determine the parachain assignment");
+				continue
+			},
+		};
+		if last.as_ref() == Some(&assignment) {
+			continue
+		}
+
+		telemetry!(
+			telemetry;
+			SUBSTRATE_INFO;
+			"node.parachain_assignment";
+			"active" => assignment.validator_index.is_some(),
+			"validator_index" => assignment.validator_index,
+			"group" => assignment.group,
+			"para" => assignment.para,
+		);
+		last = Some(assignment);
+	}
+}
@@ -15,7 +15,11 @@
 
 #[cfg(feature = "full-node")]
 use {
-	selendra_node_subsystem_util::database::Database, std::io, std::path::PathBuf, std::sync::Arc,
+	selendra_node_subsystem_util::database::Database,
+	service::config::DatabaseSource,
+	std::io,
+	std::path::{Path, PathBuf},
+	std::sync::Arc,
 };
 
 #[cfg(feature = "full-node")]
@@ -26,13 +30,22 @@ pub(crate) mod columns {
 	pub mod v0 {
 		pub const NUM_COLUMNS: u32 = 3;
 	}
-	pub const NUM_COLUMNS: u32 = 5;
+	pub mod v1 {
+		pub const NUM_COLUMNS: u32 = 5;
+	}
+	pub mod v2 {
+		pub const NUM_COLUMNS: u32 = 6;
+	}
+	pub const NUM_COLUMNS: u32 = 8;
 
 	pub const COL_AVAILABILITY_DATA: u32 = 0;
 	pub const COL_AVAILABILITY_META: u32 = 1;
 	pub const COL_APPROVAL_DATA: u32 = 2;
 	pub const COL_CHAIN_SELECTION_DATA: u32 = 3;
 	pub const COL_DISPUTE_COORDINATOR_DATA: u32 = 4;
+	pub const COL_NETWORK_BRIDGE_DATA: u32 = 5;
+	pub const COL_STATEMENT_DISTRIBUTION_DATA: u32 = 6;
+	pub const COL_BITFIELD_SIGNING_DATA: u32 = 7;
 	pub const ORDERED_COL: &[u32] = &[COL_AVAILABILITY_META, COL_CHAIN_SELECTION_DATA];
 }
 
@@ -50,6 +63,12 @@ pub struct ColumnsConfig {
 	pub col_chain_selection_data: u32,
 	/// The column used by dispute coordinator for data.
 	pub col_dispute_coordinator_data: u32,
+	/// The column used by the network bridge for data.
+	pub col_network_bridge_data: u32,
+	/// The column used by statement distribution for data.
+	pub col_statement_distribution_data: u32,
+	/// The column used by bitfield signing for data.
+	pub col_bitfield_signing_data: u32,
 }
 
 /// The real columns used by the parachains DB.
@@ -60,6 +79,9 @@ pub const REAL_COLUMNS: ColumnsConfig = ColumnsConfig {
 	col_approval_data: columns::COL_APPROVAL_DATA,
 	col_chain_selection_data: columns::COL_CHAIN_SELECTION_DATA,
 	col_dispute_coordinator_data: columns::COL_DISPUTE_COORDINATOR_DATA,
+	col_network_bridge_data: columns::COL_NETWORK_BRIDGE_DATA,
+	col_statement_distribution_data: columns::COL_STATEMENT_DISTRIBUTION_DATA,
+	col_bitfield_signing_data: columns::COL_BITFIELD_SIGNING_DATA,
 };
 
 /// The cache size for each column, in megabytes.
@@ -146,3 +168,185 @@ pub fn open_creating_paritydb(
 	);
 	Ok(Arc::new(db))
 }
+
+/// Name of the file recording the backend of a parachains database snapshot.
+#[cfg(feature = "full-node")]
+const SNAPSHOT_BACKEND_FILE_NAME: &str = "parachains_db_backend";
+
+/// The backends a parachains database is stored in.
+#[cfg(feature = "full-node")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Backend {
+	RocksDb,
+	ParityDb,
+}
+
+#[cfg(feature = "full-node")]
+impl Backend {
+	fn name(self) -> &'static str {
+		match self {
+			Backend::RocksDb => "rocksdb",
+			Backend::ParityDb => "paritydb",
+		}
+	}
+}
+
+/// The root path the parachains database of the node database `source` is opened with, and its
+/// backend. Mirrors how the database is chosen when the node starts.
+#[cfg(feature = "full-node")]
+fn db_root(source: &DatabaseSource) -> io::Result<(PathBuf, Backend)> {
+	let parent = |path: &Path| -> io::Result<PathBuf> {
+		path.parent()
+			.map(Into::into)
+			.ok_or_else(|| other_io_error(format!("Bad database path: {:?}", path)))
+	};
+
+	match source {
+		DatabaseSource::RocksDb { path, .. } => Ok((path.clone(), Backend::RocksDb)),
+		DatabaseSource::ParityDb { path, .. } => Ok((parent(path)?, Backend::ParityDb)),
+		DatabaseSource::Auto { paritydb_path, rocksdb_path, .. } =>
+			if paritydb_path.is_dir() && paritydb_path.exists() {
+				Ok((parent(paritydb_path)?, Backend::ParityDb))
+			} else {
+				Ok((rocksdb_path.clone(), Backend::RocksDb))
+			},
+		DatabaseSource::Custom { .. } =>
+			Err(other_io_error("No selendra subsystem db for custom source.".into())),
+	}
+}
+
+/// The directory holding the files of the parachains database opened with `root`.
+#[cfg(feature = "full-node")]
+fn db_dir(root: &Path, backend: Backend) -> PathBuf {
+	match backend {
+		Backend::RocksDb => root.join("parachains").join("db"),
+		Backend::ParityDb => root.join("parachains"),
+	}
+}
+
+#[cfg(feature = "full-node")]
+fn open(root: PathBuf, backend: Backend) -> io::Result<Arc<dyn Database>> {
+	match backend {
+		Backend::RocksDb => open_creating_rocksdb(root, CacheSizes::default()),
+		Backend::ParityDb => open_creating_paritydb(root, CacheSizes::default()),
+	}
+}
+
+/// Snapshot the parachains database of the node database `source` into the directory `dest`:
+/// the availability, approval voting, chain selection and dispute data.
+///
+/// The database is opened first, which fails if the node is running, and closed again before its
+/// files are copied, so the snapshot is of a consistent state.
+#[cfg(feature = "full-node")]
+pub fn export_parachains_db(source: &DatabaseSource, dest: &Path) -> io::Result<()> {
+	let (root, backend) = db_root(source)?;
+	let dir = db_dir(&root, backend);
+	if is_empty_dir(&dir) {
+		return Err(other_io_error(format!("No parachains database at {:?}", dir)))
+	}
+	if !is_empty_dir(dest) {
+		return Err(other_io_error(format!("The snapshot directory {:?} is not empty", dest)))
+	}
+
+	// Recovers the database and takes its lock, which fails if the node is running.
+	drop(open(root, backend)?);
+
+	copy_dir(&dir, dest)?;
+	std::fs::write(dest.join(SNAPSHOT_BACKEND_FILE_NAME), backend.name())
+}
+
+/// Restore the parachains database of the node database `source` from a snapshot taken by
+/// [`export_parachains_db`].
+///
+/// The node must not have a parachains database yet, it can be removed by purging the chain.
+#[cfg(feature = "full-node")]
+pub fn import_parachains_db(source: &DatabaseSource, snapshot: &Path) -> io::Result<()> {
+	let (root, backend) = db_root(source)?;
+	let dir = db_dir(&root, backend);
+	if !is_empty_dir(&dir) {
+		return Err(other_io_error(format!(
+			"A parachains database exists already at {:?}, purge the chain first",
+			dir,
+		)))
+	}
+
+	let snapshot_backend = std::fs::read_to_string(snapshot.join(SNAPSHOT_BACKEND_FILE_NAME))?;
+	if snapshot_backend != backend.name() {
+		return Err(other_io_error(format!(
+			"The snapshot is of a {} database, but the node uses {}",
+			snapshot_backend,
+			backend.name(),
+		)))
+	}
+
+	copy_dir(snapshot, &dir)?;
+	std::fs::remove_file(dir.join(SNAPSHOT_BACKEND_FILE_NAME))?;
+
+	// Check that the restored database opens, upgrading it if it is of an older version.
+	open(root, backend).map(drop)
+}
+
+#[cfg(feature = "full-node")]
+fn is_empty_dir(path: &Path) -> bool {
+	path.read_dir().map_or(true, |mut d| d.next().is_none())
+}
+
+#[cfg(feature = "full-node")]
+fn copy_dir(from: &Path, to: &Path) -> io::Result<()> {
+	std::fs::create_dir_all(to)?;
+	for entry in std::fs::read_dir(from)? {
+		let entry = entry?;
+		let to = to.join(entry.file_name());
+		if entry.file_type()?.is_dir() {
+			copy_dir(&entry.path(), &to)?;
+		} else {
+			let _ = std::fs::copy(entry.path(), to)?;
+		}
+	}
+	Ok(())
+}
+
+#[cfg(all(test, feature = "full-node"))]
+mod tests {
+	use super::*;
+	use kvdb::{DBTransaction, KeyValueDB};
+
+	fn rocksdb_source(root: &Path) -> DatabaseSource {
+		DatabaseSource::RocksDb { path: root.to_owned(), cache_size: 128 }
+	}
+
+	#[test]
+	fn export_and_import_round_trip() {
+		let old_node = tempfile::tempdir().unwrap();
+		let new_node = tempfile::tempdir().unwrap();
+		let snapshot = tempfile::tempdir().unwrap();
+
+		let db = open_creating_rocksdb(old_node.path().to_owned(), CacheSizes::default()).unwrap();
+		let mut tx = DBTransaction::new();
+		tx.put(columns::COL_APPROVAL_DATA, b"approval", b"vote");
+		tx.put(columns::COL_DISPUTE_COORDINATOR_DATA, b"dispute", b"statement");
+		db.write(tx).unwrap();
+		drop(db);
+
+		export_parachains_db(&rocksdb_source(old_node.path()), snapshot.path()).unwrap();
+		import_parachains_db(&rocksdb_source(new_node.path()), snapshot.path()).unwrap();
+
+		let db = open_creating_rocksdb(new_node.path().to_owned(), CacheSizes::default()).unwrap();
+		assert_eq!(db.get(columns::COL_APPROVAL_DATA, b"approval").unwrap().unwrap(), b"vote");
+		assert_eq!(
+			db.get(columns::COL_DISPUTE_COORDINATOR_DATA, b"dispute").unwrap().unwrap(),
+			b"statement",
+		);
+	}
+
+	#[test]
+	fn import_does_not_overwrite_existing_db() {
+		let node = tempfile::tempdir().unwrap();
+		let snapshot = tempfile::tempdir().unwrap();
+
+		drop(open_creating_rocksdb(node.path().to_owned(), CacheSizes::default()).unwrap());
+		export_parachains_db(&rocksdb_source(node.path()), snapshot.path()).unwrap();
+
+		assert!(import_parachains_db(&rocksdb_source(node.path()), snapshot.path()).is_err());
+	}
+}
@@ -0,0 +1,180 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Answering `ParachainHost` runtime API calls on behalf of light collators.
+//!
+//! Collators that do not run a relay chain full node query the few runtime API calls they need
+//! from a trusted full node. Answers are signed with a key derived from the node key, so
+//! collators can pin the full nodes they trust.
+
+use std::sync::Arc;
+
+use fatality::Nested;
+use sc_network::config::{identity::Keypair, NodeKeyConfig};
+use selendra_node_network_protocol::{
+	request_response::{
+		v1::{RuntimeApiAnswer, RuntimeApiCall, RuntimeApiProxyRequest, RuntimeApiProxyResponse},
+		IncomingRequestReceiver,
+	},
+	UnifiedReputationChange as Rep,
+};
+use selendra_primitives::{v1::Block, v2::ParachainHost};
+use sp_api::ProvideRuntimeApi;
+use sp_core::{ed25519, Pair};
+use sp_runtime::generic::BlockId;
+
+const LOG_TARGET: &str = "parachain::runtime-api-proxy";
+
+const COST_INVALID_REQUEST: Rep = Rep::CostMajor("Received message could not be decoded.");
+
+/// Derive the key answers are signed with from the node key.
+///
+/// The seed is hashed with a context, so the signatures can not be confused with those of the
+/// networking layer. Returns `None` for node keys which are not ed25519 keys.
+pub(crate) fn signing_key(node_key: NodeKeyConfig) -> Option<ed25519::Pair> {
+	match node_key.into_keypair() {
+		Ok(Keypair::Ed25519(keypair)) => {
+			let seed = sp_core::blake2_256(
+				&[&b"runtime-api-proxy"[..], keypair.secret().as_ref()].concat(),
+			);
+			Some(ed25519::Pair::from_seed(&seed))
+		},
+		Ok(_) => None,
+		Err(error) => {
+			tracing::warn!(target: LOG_TARGET, ?error, "Could not load the node key");
+			None
+		},
+	}
+}
+
+/// Answer incoming runtime API proxy requests until the network shuts down.
+pub(crate) async fn run<Client>(
+	client: Arc<Client>,
+	mut receiver: IncomingRequestReceiver<RuntimeApiProxyRequest>,
+	key: ed25519::Pair,
+) where
+	Client: ProvideRuntimeApi<Block>,
+	Client::Api: ParachainHost<Block>,
+{
+	tracing::info!(
+		target: LOG_TARGET,
+		"Answering runtime API proxy requests, signing with {}",
+		key.public(),
+	);
+
+	loop {
+		match receiver.recv(|| vec![COST_INVALID_REQUEST]).await.into_nested() {
+			Ok(Ok(req)) => {
+				let peer = req.peer;
+				let response = answer(&*client, &req.payload, &key);
+				if req.send_response(response).is_err() {
+					tracing::debug!(
+						target: LOG_TARGET,
+						?peer,
+						"Sending runtime API proxy response failed"
+					);
+				}
+			},
+			Err(fatal) => {
+				tracing::debug!(
+					target: LOG_TARGET,
+					error = ?fatal,
+					"Shutting down runtime API proxy."
+				);
+				return
+			},
+			Ok(Err(jfyi)) => {
+				tracing::debug!(
+					target: LOG_TARGET,
+					error = ?jfyi,
+					"Error decoding incoming runtime API proxy request."
+				);
+			},
+		}
+	}
+}
+
+/// Execute the requested call and sign its result.
+fn answer<Client>(
+	client: &Client,
+	request: &RuntimeApiProxyRequest,
+	key: &ed25519::Pair,
+) -> RuntimeApiProxyResponse
+where
+	Client: ProvideRuntimeApi<Block>,
+	Client::Api: ParachainHost<Block>,
+{
+	let api = client.runtime_api();
+	let at = BlockId::Hash(request.relay_parent);
+	let result = match request.call {
+		RuntimeApiCall::Validators => api.validators(&at).map(RuntimeApiAnswer::Validators),
+		RuntimeApiCall::PersistedValidationData(para_id, assumption) => api
+			.persisted_validation_data(&at, para_id, assumption)
+			.map(RuntimeApiAnswer::PersistedValidationData),
+		RuntimeApiCall::DmqContents(para_id) =>
+			api.dmq_contents(&at, para_id).map(RuntimeApiAnswer::DmqContents),
+	};
+
+	match result {
+		Ok(answer) => request.answer(answer, key),
+		Err(error) => {
+			tracing::debug!(
+				target: LOG_TARGET,
+				relay_parent = ?request.relay_parent,
+				call = ?request.call,
+				?error,
+				"Proxied runtime API call failed"
+			);
+			RuntimeApiProxyResponse::Unavailable
+		},
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sc_network::config::Secret;
+	use selendra_primitives::v1::Hash;
+
+	#[test]
+	fn signed_answers_verify_against_trusted_keys() {
+		let key = signing_key(NodeKeyConfig::Ed25519(Secret::New)).unwrap();
+		let request = RuntimeApiProxyRequest {
+			relay_parent: Hash::repeat_byte(1),
+			call: RuntimeApiCall::Validators,
+		};
+		let answer = RuntimeApiAnswer::Validators(Vec::new());
+
+		let response = request.answer(answer.clone(), &key);
+		assert_eq!(response.clone().verify(&request, &[key.public()]), Some(answer.clone()));
+		assert_eq!(response.clone().verify(&request, &[]), None);
+
+		let other = RuntimeApiProxyRequest { relay_parent: Hash::repeat_byte(2), ..request };
+		assert_eq!(response.verify(&other, &[key.public()]), None);
+	}
+
+	#[test]
+	fn answers_of_the_wrong_kind_are_rejected() {
+		let key = ed25519::Pair::from_seed(&[1; 32]);
+		let request = RuntimeApiProxyRequest {
+			relay_parent: Hash::repeat_byte(1),
+			call: RuntimeApiCall::DmqContents(1.into()),
+		};
+
+		let response = request.answer(RuntimeApiAnswer::Validators(Vec::new()), &key);
+		assert_eq!(response.verify(&request, &[key.public()]), None);
+	}
+}
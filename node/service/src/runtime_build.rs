@@ -0,0 +1,74 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Reporting the build of the runtime the node executes.
+
+use sc_client_api::{Backend, StorageProvider};
+use selendra_primitives::{build_info::BuildInfoApi, v1::Block};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::{
+	storage::{well_known_keys, StorageKey},
+	H256,
+};
+use sp_runtime::generic::BlockId;
+use telemetry::{telemetry, TelemetryHandle, SUBSTRATE_INFO};
+
+/// Log and send to telemetry the build of the runtime at the best block, with the hash of its
+/// code to compare against the digest of a reproducible build.
+pub(crate) fn report<Client, B>(client: &Client, telemetry: Option<TelemetryHandle>)
+where
+	B: Backend<Block>,
+	Client: ProvideRuntimeApi<Block> + HeaderBackend<Block> + StorageProvider<Block, B>,
+	Client::Api: BuildInfoApi<Block>,
+{
+	let at = BlockId::Hash(client.info().best_hash);
+	let code_hash = match client.storage(&at, &StorageKey(well_known_keys::CODE.to_vec())) {
+		Ok(Some(code)) => format!("{:?}", H256(sp_core::blake2_256(&code.0))),
+		_ => "unknown".into(),
+	};
+
+	let build = match client.runtime_api().build_info(&at) {
+		Ok(build) => build,
+		Err(error) => {
+			// Runtimes from before the API was added.
+			tracing::debug!(?error, "Runtime does not report its build");
+			return
+		},
+	};
+	let builder = build.builder.as_deref().unwrap_or("unknown builder");
+
+	tracing::info!(
+		git_commit = %build.git_commit,
+		profile = %build.profile,
+		builder = %builder,
+		code_hash = %code_hash,
+		"Runtime built from {} with the {} profile by {}, code hash {}",
+		build.git_commit,
+		build.profile,
+		builder,
+		code_hash,
+	);
+	telemetry!(
+		telemetry;
+		SUBSTRATE_INFO;
+		"node.runtime_build";
+		"git_commit" => &build.git_commit,
+		"profile" => &build.profile,
+		"builder" => &build.builder,
+		"code_hash" => &code_hash,
+	);
+}
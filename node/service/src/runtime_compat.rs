@@ -0,0 +1,132 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Checks of the `ParachainHost` API of the runtime against what the subsystems support.
+//!
+//! Validators and collators check the API version at startup. They refuse to start if it is too
+//! old for the subsystems to run, and disable disputes if it predates their runtime support. A
+//! runtime upgrade to an unsupported version is logged as an error, since the subsystems cannot be
+//! reconfigured while running.
+
+use futures::StreamExt;
+use sc_client_api::BlockchainEvents;
+use selendra_primitives::{
+	v1::{Block, Hash},
+	v2::ParachainHost,
+};
+use sp_api::{ApiExt, ProvideRuntimeApi};
+use sp_runtime::generic::BlockId;
+
+/// The oldest `ParachainHost` version the subsystems can run with.
+pub(crate) const MIN_PARACHAIN_HOST_VERSION: u32 = 1;
+
+/// The `ParachainHost` version the subsystems fully support. Older versions lack the session info
+/// and PVF pre-checking calls of version 2, so disputes are disabled with them.
+pub(crate) const PARACHAIN_HOST_VERSION: u32 = 2;
+
+/// How well the subsystems support a version of the `ParachainHost` API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Compatibility {
+	/// All subsystems can run.
+	Full,
+	/// The subsystems can run, except for disputes.
+	WithoutDisputes(u32),
+	/// The subsystems cannot run. `None` if the runtime lacks the API.
+	Unsupported(Option<u32>),
+}
+
+impl Compatibility {
+	/// The compatibility with the given `ParachainHost` version.
+	pub(crate) fn of(version: Option<u32>) -> Self {
+		match version {
+			Some(version) if version >= PARACHAIN_HOST_VERSION => Compatibility::Full,
+			Some(version) if version >= MIN_PARACHAIN_HOST_VERSION =>
+				Compatibility::WithoutDisputes(version),
+			version => Compatibility::Unsupported(version),
+		}
+	}
+
+	/// The compatibility with the `ParachainHost` version of the runtime at block `at`.
+	pub(crate) fn at<Client>(client: &Client, at: Hash) -> Self
+	where
+		Client: ProvideRuntimeApi<Block>,
+		Client::Api: ParachainHost<Block>,
+	{
+		let version = client
+			.runtime_api()
+			.api_version::<dyn ParachainHost<Block>>(&BlockId::Hash(at))
+			.unwrap_or_else(|error| {
+				tracing::warn!(?error, "Cannot query the ParachainHost API version");
+				None
+			});
+		Self::of(version)
+	}
+}
+
+/// Log an error whenever a new best block changes the `ParachainHost` version to one the
+/// subsystems, as configured at startup, do not support.
+pub(crate) async fn watch_upgrades<Client>(client: std::sync::Arc<Client>, started: Compatibility)
+where
+	Client: ProvideRuntimeApi<Block> + BlockchainEvents<Block>,
+	Client::Api: ParachainHost<Block>,
+{
+	let mut current = started;
+	let mut imports = client.import_notification_stream();
+	while let Some(import) = imports.next().await {
+		if !import.is_new_best {
+			continue
+		}
+
+		let compatibility = Compatibility::at(&*client, import.hash);
+		if compatibility == current {
+			continue
+		}
+		current = compatibility;
+
+		match (compatibility, started) {
+			(Compatibility::Unsupported(version), _) => tracing::error!(
+				?version,
+				required = MIN_PARACHAIN_HOST_VERSION,
+				"The runtime was upgraded to a ParachainHost API this node does not support. \
+				 Parachain subsystems will fail, upgrade the node.",
+			),
+			(Compatibility::WithoutDisputes(version), Compatibility::Full) => tracing::error!(
+				version,
+				required = PARACHAIN_HOST_VERSION,
+				"The runtime was downgraded to a ParachainHost API without support for disputes, \
+				 which are enabled. Restart the node to disable them.",
+			),
+			(_, _) => tracing::info!(
+				?compatibility,
+				"The ParachainHost API of the runtime changed, the subsystems support it.",
+			),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn compatibility_of_versions() {
+		assert_eq!(Compatibility::of(None), Compatibility::Unsupported(None));
+		assert_eq!(Compatibility::of(Some(0)), Compatibility::Unsupported(Some(0)));
+		assert_eq!(Compatibility::of(Some(1)), Compatibility::WithoutDisputes(1));
+		assert_eq!(Compatibility::of(Some(2)), Compatibility::Full);
+		assert_eq!(Compatibility::of(Some(3)), Compatibility::Full);
+	}
+}
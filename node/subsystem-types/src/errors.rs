@@ -17,6 +17,7 @@
 //! Error types for the subsystem requests.
 
 use crate::JaegerError;
+pub use fatality::Fatality;
 
 /// A description of an error causing the runtime API request to be unservable.
 #[derive(thiserror::Error, Debug, Clone)]
@@ -91,6 +92,10 @@ impl std::error::Error for RecoveryError {}
 ///   * Subsystems dying when they are not expected to
 ///   * Subsystems not dying when they are told to die
 ///   * etc.
+///
+/// Errors are either fatal, after which the node shuts down, or recoverable, after which the
+/// subsystem failing is started over, see [`fatality::Fatality`]. Errors of unknown origin are
+/// considered fatal.
 #[derive(thiserror::Error, Debug)]
 #[allow(missing_docs)]
 pub enum SubsystemError {
@@ -131,6 +136,16 @@ pub enum SubsystemError {
 		#[source]
 		source: Box<dyn 'static + std::error::Error + Send + Sync>,
 	},
+
+	/// Per origin (or subsystem) annotations to wrap an error the subsystem can recover from.
+	#[error("Recoverable error originated in {origin}")]
+	Recoverable {
+		/// An additional annotation tag for the origin of `source`.
+		origin: &'static str,
+		/// The wrapped error. Marked as source for tracking the error chain.
+		#[source]
+		source: Box<dyn 'static + std::error::Error + Send + Sync>,
+	},
 }
 
 // impl AnnotateErrorOrigin for SubsystemError {
@@ -150,7 +165,94 @@ impl SubsystemError {
 	) -> Self {
 		Self::FromOrigin { origin, source: Box::new(err) }
 	}
+
+	/// Adds a `str` as `origin` to the given recoverable error `err`.
+	pub fn recoverable<E: 'static + Send + Sync + std::error::Error>(
+		origin: &'static str,
+		err: E,
+	) -> Self {
+		Self::Recoverable { origin, source: Box::new(err) }
+	}
+
+	/// Adds a `str` as `origin` to the given error `err`, keeping whether it is fatal.
+	pub fn with_fatality<E: 'static + Send + Sync + Fatality>(
+		origin: &'static str,
+		err: E,
+	) -> Self {
+		if err.is_fatal() {
+			Self::with_origin(origin, err)
+		} else {
+			Self::recoverable(origin, err)
+		}
+	}
+}
+
+impl Fatality for SubsystemError {
+	fn is_fatal(&self) -> bool {
+		match self {
+			// The metrics and tracing endpoints are not needed to make progress.
+			Self::Prometheus(_) | Self::Jaeger(_) | Self::Recoverable { .. } => false,
+			Self::NotifyCancellation(_) |
+			Self::QueueError(_) |
+			Self::Io(_) |
+			Self::Infallible(_) |
+			Self::Context(_) |
+			Self::SubsystemStalled(_) |
+			Self::Generated(_) |
+			Self::FromOrigin { .. } => true,
+		}
+	}
 }
 
 /// Ease the use of subsystem errors.
 pub type SubsystemResult<T> = Result<T, self::SubsystemError>;
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[derive(thiserror::Error, Debug)]
+	#[error("Transient")]
+	struct Transient;
+
+	impl Fatality for Transient {
+		fn is_fatal(&self) -> bool {
+			false
+		}
+	}
+
+	#[derive(thiserror::Error, Debug)]
+	#[error("Broken")]
+	struct Broken;
+
+	impl Fatality for Broken {
+		fn is_fatal(&self) -> bool {
+			true
+		}
+	}
+
+	#[test]
+	fn closed_channels_are_fatal() {
+		let (tx, rx) = futures::channel::oneshot::channel::<()>();
+		drop(tx);
+		let canceled = futures::executor::block_on(rx).unwrap_err();
+		assert!(SubsystemError::from(canceled).is_fatal());
+		assert!(SubsystemError::SubsystemStalled("test").is_fatal());
+		assert!(SubsystemError::Context("test".into()).is_fatal());
+	}
+
+	#[test]
+	fn errors_of_unknown_origin_are_fatal() {
+		assert!(SubsystemError::with_origin("test", Transient).is_fatal());
+		assert!(!SubsystemError::recoverable("test", Broken).is_fatal());
+	}
+
+	#[test]
+	fn classified_errors_keep_their_fatality() {
+		assert!(!SubsystemError::with_fatality("test", Transient).is_fatal());
+		assert!(SubsystemError::with_fatality("test", Broken).is_fatal());
+
+		let nested = SubsystemError::with_fatality("test", Transient);
+		assert!(!SubsystemError::with_fatality("outer", nested).is_fatal());
+	}
+}
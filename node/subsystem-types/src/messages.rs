@@ -42,8 +42,8 @@ use selendra_primitives::{
 	v1::{
 		AuthorityDiscoveryId, BackedCandidate, BlockNumber, CandidateDescriptor, CandidateEvent,
 		CandidateHash, CandidateIndex, CandidateReceipt, CollatorId, CommittedCandidateReceipt,
-		CoreState, GroupIndex, GroupRotationInfo, Hash, Header as BlockHeader, Id as ParaId,
-		InboundDownwardMessage, InboundHrmpMessage, MultiDisputeStatementSet,
+		CoreState, ExecutorParams, GroupIndex, GroupRotationInfo, Hash, Header as BlockHeader,
+		Id as ParaId, InboundDownwardMessage, InboundHrmpMessage, MultiDisputeStatementSet,
 		OccupiedCoreAssumption, PersistedValidationData, SessionIndex, SignedAvailabilityBitfield,
 		SignedAvailabilityBitfields, ValidationCode, ValidationCodeHash, ValidatorId,
 		ValidatorIndex, ValidatorSignature,
@@ -385,6 +385,42 @@ pub enum NetworkBridgeMessage {
 		/// We're not necessarily connected to all of them, but we should.
 		our_neighbors: HashSet<AuthorityDiscoveryId>,
 	},
+	/// Stop or resume exchanging the messages of a gossip subsystem with our peers.
+	///
+	/// While paused, the messages of the subsystem are neither sent to nor received from peers on
+	/// the validation peer-set. Views are exchanged as usual.
+	SetGossipPaused {
+		/// The gossip subsystem.
+		subsystem: GossipSubsystem,
+		/// Whether the subsystem should be paused.
+		paused: bool,
+	},
+	/// Send our current view to all peers again and inform the subsystems about it, even if it did
+	/// not change.
+	RefreshView,
+}
+
+/// A subsystem gossiping on the validation peer-set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GossipSubsystem {
+	/// The bitfield distribution subsystem.
+	BitfieldDistribution,
+	/// The statement distribution subsystem.
+	StatementDistribution,
+	/// The approval distribution subsystem.
+	ApprovalDistribution,
+}
+
+impl GossipSubsystem {
+	/// The gossip subsystem a message on the validation peer-set belongs to.
+	pub fn of(message: &protocol_v1::ValidationProtocol) -> Self {
+		match message {
+			protocol_v1::ValidationProtocol::BitfieldDistribution(_) => Self::BitfieldDistribution,
+			protocol_v1::ValidationProtocol::StatementDistribution(_) =>
+				Self::StatementDistribution,
+			protocol_v1::ValidationProtocol::ApprovalDistribution(_) => Self::ApprovalDistribution,
+		}
+	}
 }
 
 impl NetworkBridgeMessage {
@@ -401,6 +437,8 @@ impl NetworkBridgeMessage {
 			Self::ConnectToResolvedValidators { .. } => None,
 			Self::SendRequests { .. } => None,
 			Self::NewGossipTopology { .. } => None,
+			Self::SetGossipPaused { .. } => None,
+			Self::RefreshView => None,
 		}
 	}
 }
@@ -593,6 +631,10 @@ pub enum ChainSelectionMessage {
 	/// Request the best leaf containing the given block in its ancestry. Return `None` if
 	/// there is no such leaf.
 	BestLeafContaining(Hash, oneshot::Sender<Option<Hash>>),
+	/// Revert the given blocks, as they include a candidate which was concluded invalid in a
+	/// dispute. The blocks and all their descendants become non-viable, so that block authoring
+	/// and finality move away from them. Finalized blocks can't be reverted and are ignored.
+	RevertBlocks(Vec<(BlockNumber, Hash)>),
 }
 
 impl ChainSelectionMessage {
@@ -605,6 +647,7 @@ impl ChainSelectionMessage {
 			ChainSelectionMessage::Approved(_) => None,
 			ChainSelectionMessage::Leaves(_) => None,
 			ChainSelectionMessage::BestLeafContaining(..) => None,
+			ChainSelectionMessage::RevertBlocks(_) => None,
 		}
 	}
 }
@@ -683,6 +726,18 @@ pub enum RuntimeApiRequest {
 		OccupiedCoreAssumption,
 		RuntimeApiSender<Option<ValidationCodeHash>>,
 	),
+	/// Get the executor parameters for the given session, if stored.
+	SessionExecutorParams(SessionIndex, RuntimeApiSender<Option<ExecutorParams>>),
+}
+
+/// The class of a runtime API request, deciding how it is scheduled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuntimeApiRequestClass {
+	/// Requests made on behalf of consensus, e.g. by backing or approval voting.
+	ConsensusCritical,
+	/// Requests made on behalf of RPC or telemetry. These are rate-limited, so that they
+	/// cannot starve consensus-critical requests of runtime API throughput.
+	NonCritical,
 }
 
 /// A message to the Runtime API subsystem.
@@ -690,13 +745,27 @@ pub enum RuntimeApiRequest {
 pub enum RuntimeApiMessage {
 	/// Make a request of the runtime API against the post-state of the given relay-parent.
 	Request(Hash, RuntimeApiRequest),
+	/// Make a request of the runtime API against the post-state of the given relay-parent, on
+	/// behalf of RPC or telemetry.
+	///
+	/// The request may be delayed in favour of [`RuntimeApiMessage::Request`], or dropped if too
+	/// many of them are waiting.
+	NonCriticalRequest(Hash, RuntimeApiRequest),
 }
 
 impl RuntimeApiMessage {
 	/// If the current variant contains the relay parent hash, return it.
 	pub fn relay_parent(&self) -> Option<Hash> {
 		match self {
-			Self::Request(hash, _) => Some(*hash),
+			Self::Request(hash, _) | Self::NonCriticalRequest(hash, _) => Some(*hash),
+		}
+	}
+
+	/// The class of the request.
+	pub fn class(&self) -> RuntimeApiRequestClass {
+		match self {
+			Self::Request(..) => RuntimeApiRequestClass::ConsensusCritical,
+			Self::NonCriticalRequest(..) => RuntimeApiRequestClass::NonCritical,
 		}
 	}
 }
@@ -707,6 +776,8 @@ pub enum StatementDistributionMessage {
 	/// We have originated a signed statement in the context of
 	/// given relay-parent hash and it should be distributed to other validators.
 	Share(Hash, SignedFullStatement),
+	/// Send the statements we made at the active leaves to all peers which have not seen them yet.
+	RebroadcastOwn,
 	/// Event from the network bridge.
 	#[from]
 	NetworkBridgeUpdateV1(NetworkBridgeEvent<protocol_v1::StatementDistributionMessage>),
@@ -764,7 +835,8 @@ impl BoundToRelayParent for ProvisionerMessage {
 /// Message to the Collation Generation subsystem.
 #[derive(Debug)]
 pub enum CollationGenerationMessage {
-	/// Initialize the collation generation subsystem
+	/// Initialize the collation generation subsystem for the para of the configuration. May be
+	/// sent once per para to collate for several paras.
 	Initialize(CollationGenerationConfig),
 }
 
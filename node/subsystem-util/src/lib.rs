@@ -89,6 +89,8 @@ pub mod runtime;
 
 /// Database trait for subsystem.
 pub mod database;
+/// Accounting of the memory held for PoVs and availability data.
+pub mod memory_budget;
 
 mod determine_new_blocks;
 
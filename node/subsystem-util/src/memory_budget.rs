@@ -0,0 +1,311 @@
+// Copyright 2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Accounting of the memory held in flight for PoVs and availability data.
+//!
+//! Subsystems keeping PoVs or erasure chunks around reserve the bytes they hold against a shared
+//! [`MemoryBudget`], keyed by the relay parent the data belongs to. Once the reserved bytes exceed
+//! the configured limit, the oldest relay parents are shed: no further reservations are granted for
+//! them and holders are expected to drop their data, so the memory is given back as the outstanding
+//! [`Reservation`]s are dropped.
+
+use std::{
+	collections::{HashMap, VecDeque},
+	sync::Arc,
+};
+
+use parking_lot::Mutex;
+use selendra_primitives::v1::Hash;
+
+use crate::metrics::{self, prometheus};
+
+/// Default limit of a [`MemoryBudget`] in bytes.
+pub const DEFAULT_MEMORY_BUDGET: usize = 1024 * 1024 * 1024;
+
+/// How many shed relay parents are remembered for refusing further reservations.
+const MAX_SHED_RELAY_PARENTS: usize = 64;
+
+/// Budget for the bytes held in flight for PoVs and availability data.
+///
+/// Cloning is cheap and all clones account against the same budget.
+#[derive(Clone)]
+pub struct MemoryBudget(Arc<Mutex<Inner>>);
+
+struct Inner {
+	/// Number of bytes we try to stay within.
+	limit: usize,
+	/// Number of bytes currently reserved.
+	used: usize,
+	/// Number of reserved bytes held for relay parents which have been shed.
+	shedding: usize,
+	/// Counter for ordering relay parents by the time we first saw them.
+	next_seen: u64,
+	/// Reserved bytes per relay parent.
+	relay_parents: HashMap<Hash, Usage>,
+	/// Relay parents which have been shed, oldest first.
+	shed: VecDeque<Hash>,
+	metrics: Metrics,
+}
+
+struct Usage {
+	seen: u64,
+	bytes: usize,
+}
+
+/// Bytes reserved against a [`MemoryBudget`].
+///
+/// The bytes are given back once the reservation is dropped.
+pub struct Reservation {
+	budget: MemoryBudget,
+	relay_parent: Hash,
+	bytes: usize,
+}
+
+impl MemoryBudget {
+	/// Create a new budget of `limit` bytes.
+	pub fn new(limit: usize, metrics: Metrics) -> Self {
+		MemoryBudget(Arc::new(Mutex::new(Inner {
+			limit,
+			used: 0,
+			shedding: 0,
+			next_seen: 0,
+			relay_parents: HashMap::new(),
+			shed: VecDeque::new(),
+			metrics,
+		})))
+	}
+
+	/// The number of bytes this budget tries to stay within.
+	pub fn limit(&self) -> usize {
+		self.0.lock().limit
+	}
+
+	/// The number of bytes currently reserved.
+	pub fn used(&self) -> usize {
+		self.0.lock().used
+	}
+
+	/// Whether data for the given relay parent has been shed.
+	pub fn is_shed(&self, relay_parent: &Hash) -> bool {
+		self.0.lock().shed.contains(relay_parent)
+	}
+
+	/// Reserve `bytes` for data belonging to the given relay parent.
+	///
+	/// Exceeding the limit sheds the oldest other relay parents until the bytes held for the
+	/// remaining ones fit again. Returns `None` if the relay parent has been shed already.
+	pub fn reserve(&self, relay_parent: Hash, bytes: usize) -> Option<Reservation> {
+		let mut guard = self.0.lock();
+		let inner = &mut *guard;
+		if inner.shed.contains(&relay_parent) {
+			return None
+		}
+
+		let seen = inner.next_seen;
+		let usage = inner.relay_parents.entry(relay_parent).or_insert(Usage { seen, bytes: 0 });
+		if usage.seen == seen {
+			inner.next_seen += 1;
+		}
+		usage.bytes += bytes;
+		inner.used += bytes;
+
+		while inner.used - inner.shedding > inner.limit {
+			let oldest = inner
+				.relay_parents
+				.iter()
+				.filter(|(hash, _)| **hash != relay_parent && !inner.shed.contains(hash))
+				.min_by_key(|(_, usage)| usage.seen)
+				.map(|(hash, usage)| (*hash, usage.bytes));
+
+			match oldest {
+				Some((hash, bytes)) => inner.shed_relay_parent(hash, bytes),
+				None => break,
+			}
+		}
+		inner.metrics.on_used(inner.used);
+
+		Some(Reservation { budget: self.clone(), relay_parent, bytes })
+	}
+
+	fn release(&self, relay_parent: &Hash, bytes: usize) {
+		let mut inner = self.0.lock();
+		inner.used -= bytes;
+		if inner.shed.contains(relay_parent) {
+			inner.shedding -= bytes;
+		}
+		if let Some(usage) = inner.relay_parents.get_mut(relay_parent) {
+			usage.bytes -= bytes;
+			if usage.bytes == 0 {
+				inner.relay_parents.remove(relay_parent);
+			}
+		}
+		inner.metrics.on_used(inner.used);
+	}
+}
+
+impl Default for MemoryBudget {
+	fn default() -> Self {
+		MemoryBudget::new(DEFAULT_MEMORY_BUDGET, Metrics::default())
+	}
+}
+
+impl Inner {
+	fn shed_relay_parent(&mut self, relay_parent: Hash, bytes: usize) {
+		tracing::debug!(
+			target: "parachain::memory-budget",
+			?relay_parent,
+			bytes,
+			used = self.used,
+			limit = self.limit,
+			"Memory budget exceeded, shedding relay parent",
+		);
+
+		if self.shed.len() == MAX_SHED_RELAY_PARENTS {
+			if let Some(forgotten) = self.shed.pop_front() {
+				// Bytes still held for a forgotten relay parent are accounted as regular ones.
+				if let Some(usage) = self.relay_parents.get(&forgotten) {
+					self.shedding -= usage.bytes;
+				}
+			}
+		}
+		self.shed.push_back(relay_parent);
+		self.shedding += bytes;
+		self.metrics.on_shed();
+	}
+}
+
+impl Reservation {
+	/// The relay parent the reserved bytes belong to.
+	pub fn relay_parent(&self) -> Hash {
+		self.relay_parent
+	}
+
+	/// The number of reserved bytes.
+	pub fn bytes(&self) -> usize {
+		self.bytes
+	}
+
+	/// Whether the relay parent of this reservation has been shed since.
+	pub fn is_shed(&self) -> bool {
+		self.budget.is_shed(&self.relay_parent)
+	}
+}
+
+impl Drop for Reservation {
+	fn drop(&mut self) {
+		self.budget.release(&self.relay_parent, self.bytes);
+	}
+}
+
+/// Memory budget metrics.
+#[derive(Clone, Default)]
+pub struct Metrics(Option<MetricsInner>);
+
+#[derive(Clone)]
+struct MetricsInner {
+	used: prometheus::Gauge<prometheus::U64>,
+	shed: prometheus::Counter<prometheus::U64>,
+}
+
+impl Metrics {
+	fn on_used(&self, bytes: usize) {
+		if let Some(metrics) = &self.0 {
+			metrics.used.set(bytes as u64);
+		}
+	}
+
+	fn on_shed(&self) {
+		if let Some(metrics) = &self.0 {
+			metrics.shed.inc();
+		}
+	}
+}
+
+impl metrics::Metrics for Metrics {
+	fn try_register(registry: &prometheus::Registry) -> Result<Self, prometheus::PrometheusError> {
+		let metrics = MetricsInner {
+			used: prometheus::register(
+				prometheus::Gauge::new(
+					"selendra_parachain_availability_memory_used_bytes",
+					"Bytes held in flight for PoVs and availability data.",
+				)?,
+				registry,
+			)?,
+			shed: prometheus::register(
+				prometheus::Counter::new(
+					"selendra_parachain_availability_memory_shed_total",
+					"Number of relay parents shed for exceeding the memory budget.",
+				)?,
+				registry,
+			)?,
+		};
+		Ok(Metrics(Some(metrics)))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn budget(limit: usize) -> MemoryBudget {
+		MemoryBudget::new(limit, Metrics::default())
+	}
+
+	#[test]
+	fn reservations_are_released_on_drop() {
+		let budget = budget(100);
+		let a = budget.reserve(Hash::repeat_byte(1), 40).unwrap();
+		let b = budget.reserve(Hash::repeat_byte(1), 30).unwrap();
+		assert_eq!(budget.used(), 70);
+
+		drop(a);
+		assert_eq!(budget.used(), 30);
+		drop(b);
+		assert_eq!(budget.used(), 0);
+		assert!(budget.0.lock().relay_parents.is_empty());
+	}
+
+	#[test]
+	fn exceeding_the_limit_sheds_oldest_relay_parents() {
+		let budget = budget(100);
+		let (first, second, third) =
+			(Hash::repeat_byte(1), Hash::repeat_byte(2), Hash::repeat_byte(3));
+
+		let _a = budget.reserve(first, 40).unwrap();
+		let _b = budget.reserve(second, 40).unwrap();
+		let c = budget.reserve(third, 40).unwrap();
+
+		assert!(budget.is_shed(&first));
+		assert!(!budget.is_shed(&second));
+		assert!(!c.is_shed());
+		assert!(budget.reserve(first, 1).is_none());
+
+		// Bytes of shed relay parents are not shed twice.
+		let _d = budget.reserve(third, 20).unwrap();
+		assert!(!budget.is_shed(&second));
+		let _e = budget.reserve(third, 1).unwrap();
+		assert!(budget.is_shed(&second));
+	}
+
+	#[test]
+	fn the_reserving_relay_parent_is_never_shed() {
+		let budget = budget(10);
+		let a = budget.reserve(Hash::repeat_byte(1), 40).unwrap();
+
+		assert!(!a.is_shed());
+		assert_eq!(budget.used(), 40);
+	}
+}
@@ -16,7 +16,7 @@
 
 //! Convenient interface to runtime information.
 
-use std::cmp::max;
+use std::{cmp::max, collections::HashMap};
 
 use lru::LruCache;
 
@@ -25,19 +25,21 @@ use sp_application_crypto::AppKey;
 use sp_core::crypto::ByteArray;
 use sp_keystore::{CryptoStore, SyncCryptoStorePtr};
 
-use selendra_node_subsystem::{SubsystemContext, SubsystemSender};
+use selendra_node_subsystem::{ActiveLeavesUpdate, SubsystemContext, SubsystemSender};
 use selendra_primitives::{
 	v1::{
-		CandidateEvent, CoreState, EncodeAs, GroupIndex, GroupRotationInfo, Hash, OccupiedCore,
-		SessionIndex, Signed, SigningContext, UncheckedSigned, ValidationCode, ValidationCodeHash,
-		ValidatorId, ValidatorIndex,
+		CandidateEvent, CoreState, EncodeAs, GroupIndex, GroupRotationInfo, Hash, Id as ParaId,
+		OccupiedCore, OccupiedCoreAssumption, PersistedValidationData, SessionIndex, Signed,
+		SigningContext, UncheckedSigned, ValidationCode, ValidationCodeHash, ValidatorId,
+		ValidatorIndex,
 	},
 	v2::SessionInfo,
 };
 
 use crate::{
-	request_availability_cores, request_candidate_events, request_session_index_for_child,
-	request_session_info, request_validation_code_by_hash, request_validator_groups,
+	request_availability_cores, request_candidate_events, request_persisted_validation_data,
+	request_session_index_for_child, request_session_info, request_validation_code,
+	request_validation_code_by_hash, request_validator_groups,
 };
 
 /// Errors that can happen on runtime fetches.
@@ -238,6 +240,97 @@ impl RuntimeInfo {
 	}
 }
 
+/// Caching of the persisted validation data and validation code of paras, per active leaf.
+///
+/// Subsystems working on a leaf tend to ask for the same data of a para several times, e.g. once
+/// per core. Answers are only cached for relay parents which are active leaves and dropped once
+/// the leaf gets deactivated, so the cache is bounded by the number of active leaves.
+#[derive(Default)]
+pub struct ValidationDataCache {
+	leaves: HashMap<Hash, LeafValidationData>,
+}
+
+#[derive(Default)]
+struct LeafValidationData {
+	persisted_validation_data:
+		HashMap<(ParaId, OccupiedCoreAssumption), Option<PersistedValidationData>>,
+	validation_code: HashMap<(ParaId, OccupiedCoreAssumption), Option<ValidationCode>>,
+}
+
+impl ValidationDataCache {
+	/// Create an empty cache.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Start caching for the activated leaves and drop everything cached for the deactivated ones.
+	pub fn on_active_leaves_update(&mut self, update: &ActiveLeavesUpdate) {
+		for leaf in &update.activated {
+			self.leaves.entry(leaf.hash).or_default();
+		}
+		for hash in &update.deactivated {
+			self.leaves.remove(hash);
+		}
+	}
+
+	/// Get the `PersistedValidationData` of `para_id` at `relay_parent`, under `assumption`.
+	pub async fn get_persisted_validation_data<Sender>(
+		&mut self,
+		sender: &mut Sender,
+		relay_parent: Hash,
+		para_id: ParaId,
+		assumption: OccupiedCoreAssumption,
+	) -> Result<Option<PersistedValidationData>>
+	where
+		Sender: SubsystemSender,
+	{
+		if let Some(cached) = self
+			.leaves
+			.get(&relay_parent)
+			.and_then(|leaf| leaf.persisted_validation_data.get(&(para_id, assumption)))
+		{
+			return Ok(cached.clone())
+		}
+
+		let data = recv_runtime(
+			request_persisted_validation_data(relay_parent, para_id, assumption, sender).await,
+		)
+		.await?;
+		if let Some(leaf) = self.leaves.get_mut(&relay_parent) {
+			leaf.persisted_validation_data.insert((para_id, assumption), data.clone());
+		}
+		Ok(data)
+	}
+
+	/// Get the `ValidationCode` of `para_id` at `relay_parent`, under `assumption`.
+	pub async fn get_validation_code<Sender>(
+		&mut self,
+		sender: &mut Sender,
+		relay_parent: Hash,
+		para_id: ParaId,
+		assumption: OccupiedCoreAssumption,
+	) -> Result<Option<ValidationCode>>
+	where
+		Sender: SubsystemSender,
+	{
+		if let Some(cached) = self
+			.leaves
+			.get(&relay_parent)
+			.and_then(|leaf| leaf.validation_code.get(&(para_id, assumption)))
+		{
+			return Ok(cached.clone())
+		}
+
+		let code =
+			recv_runtime(request_validation_code(relay_parent, para_id, assumption, sender).await)
+				.await?;
+		if let Some(leaf) = self.leaves.get_mut(&relay_parent) {
+			leaf.validation_code.insert((para_id, assumption), code.clone());
+		}
+		Ok(code)
+	}
+}
+
 /// Convenience function for quickly checking the signature on signed data.
 pub fn check_signature<Payload, RealPayload>(
 	session_index: SessionIndex,
@@ -245,3 +245,63 @@ fn tick_tack_metronome() {
 		)
 	});
 }
+
+#[test]
+fn validation_data_cache_is_dropped_with_its_leaf() {
+	use selendra_node_subsystem::messages::{RuntimeApiMessage, RuntimeApiRequest};
+	use selendra_primitives::v1::{Id as ParaId, OccupiedCoreAssumption, PersistedValidationData};
+
+	let pool = sp_core::testing::TaskExecutor::new();
+	let (mut ctx, mut handle) = make_subsystem_context::<CollatorProtocolMessage, _>(pool);
+
+	let leaf = ActivatedLeaf {
+		hash: dummy_hash(),
+		number: 1,
+		status: LeafStatus::Fresh,
+		span: Arc::new(jaeger::Span::Disabled),
+	};
+	let para_id = ParaId::from(100);
+	let assumption = OccupiedCoreAssumption::Free;
+	let data = PersistedValidationData { max_pov_size: 1024, ..Default::default() };
+
+	let subsystem = async move {
+		let mut cache = runtime::ValidationDataCache::new();
+		let hash = leaf.hash;
+		cache.on_active_leaves_update(&ActiveLeavesUpdate::start_work(leaf));
+
+		for _ in 0..3 {
+			let fetched = cache
+				.get_persisted_validation_data(ctx.sender(), hash, para_id, assumption)
+				.await
+				.unwrap();
+			assert_eq!(fetched.map(|d| d.max_pov_size), Some(1024));
+		}
+
+		cache.on_active_leaves_update(&ActiveLeavesUpdate::stop_work(hash));
+		cache
+			.get_persisted_validation_data(ctx.sender(), hash, para_id, assumption)
+			.await
+			.unwrap();
+	};
+
+	let overseer = async move {
+		let mut requests = 0;
+		while let Some(msg) = handle.try_recv().await {
+			assert_matches!(
+				msg,
+				AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+					_,
+					RuntimeApiRequest::PersistedValidationData(p, _, tx),
+				)) => {
+					assert_eq!(p, para_id);
+					tx.send(Ok(Some(data.clone()))).unwrap();
+				}
+			);
+			requests += 1;
+		}
+		// Once while the leaf is active, once after it was deactivated.
+		assert_eq!(requests, 2);
+	};
+
+	block_on(future::join(subsystem, overseer));
+}
@@ -17,7 +17,7 @@
 //! A Selendra performance tests utilities.
 
 use selendra_erasure_coding::{obtain_chunks, reconstruct};
-use selendra_node_core_pvf::{sc_executor_common, sp_maybe_compressed_blob};
+use selendra_node_core_pvf::{sc_executor_common, sp_maybe_compressed_blob, ExecutorParams};
 use std::time::{Duration, Instant};
 
 mod constants;
@@ -66,7 +66,8 @@ pub fn measure_pvf_prepare(wasm_code: &[u8]) -> Result<Duration, PerfCheckError>
 
 	// Recreate the pipeline from the pvf prepare worker.
 	let blob = selendra_node_core_pvf::prevalidate(code.as_ref()).map_err(PerfCheckError::from)?;
-	selendra_node_core_pvf::prepare(blob).map_err(PerfCheckError::from)?;
+	selendra_node_core_pvf::prepare(blob, &ExecutorParams::default())
+		.map_err(PerfCheckError::from)?;
 
 	Ok(start.elapsed())
 }
@@ -36,11 +36,23 @@ pub type SelendraChainSpec =
 
 /// Local testnet config (multivalidator Alice + Bob)
 pub fn selendra_local_testnet_config() -> SelendraChainSpec {
+	selendra_local_testnet_config_with_validators(&["Alice", "Bob"])
+}
+
+/// Local testnet config with the validators derived from the given seeds, e.g. `"Charlie"`.
+pub fn selendra_local_testnet_config_with_validators(seeds: &[&str]) -> SelendraChainSpec {
+	let authorities: Vec<_> = seeds.iter().map(|seed| get_authority_keys_from_seed(seed)).collect();
 	SelendraChainSpec::from_genesis(
 		"Local Testnet",
 		"local_testnet",
 		ChainType::Local,
-		|| selendra_local_testnet_genesis(),
+		move || {
+			selendra_testnet_genesis(
+				authorities.clone(),
+				get_account_id_from_seed::<sr25519::Public>("Alice"),
+				None,
+			)
+		},
 		vec![],
 		None,
 		Some(DEFAULT_PROTOCOL_ID),
@@ -141,7 +153,7 @@ fn selendra_testnet_genesis(
 		},
 		staking: runtime::StakingConfig {
 			minimum_validator_count: 1,
-			validator_count: 2,
+			validator_count: initial_authorities.len() as u32,
 			stakers: initial_authorities
 				.iter()
 				.map(|x| (x.0.clone(), x.1.clone(), STASH, runtime::StakerStatus::Validator))
@@ -95,6 +95,7 @@ pub fn new_full(
 		None,
 		None,
 		worker_program_path,
+		None,
 		false,
 		selendra_service::RealOverseerGen,
 	)
@@ -130,12 +131,31 @@ pub fn node_config(
 	key: Sr25519Keyring,
 	boot_nodes: Vec<MultiaddrWithPeerId>,
 	is_validator: bool,
+) -> Configuration {
+	node_config_with_spec(
+		selendra_local_testnet_config(),
+		storage_update_func,
+		tokio_handle,
+		key,
+		boot_nodes,
+		is_validator,
+	)
+}
+
+/// Create a Selendra `Configuration` for the chain of `spec`, e.g. one with more validators
+/// than the local testnet, see [`node_config`].
+pub fn node_config_with_spec(
+	mut spec: SelendraChainSpec,
+	storage_update_func: impl Fn(),
+	tokio_handle: tokio::runtime::Handle,
+	key: Sr25519Keyring,
+	boot_nodes: Vec<MultiaddrWithPeerId>,
+	is_validator: bool,
 ) -> Configuration {
 	let base_path = BasePath::new_temp_dir().expect("could not create temporary directory");
 	let root = base_path.path();
 	let role = if is_validator { Role::Authority } else { Role::Full };
 	let key_seed = key.to_seed();
-	let mut spec = selendra_local_testnet_config();
 	let mut storage = spec.as_storage_builder().build_storage().expect("could not build storage");
 
 	BasicExternalities::execute_with_storage(&mut storage, storage_update_func);
@@ -246,6 +266,15 @@ pub fn run_collator_node(
 	collator_pair: CollatorPair,
 ) -> SelendraTestNode {
 	let config = node_config(storage_update_func, tokio_handle, key, boot_nodes, false);
+	run_collator_node_with_config(config, collator_pair)
+}
+
+/// Run a test collator node that uses the test runtime and specified `config`, see
+/// [`run_collator_node`].
+pub fn run_collator_node_with_config(
+	config: Configuration,
+	collator_pair: CollatorPair,
+) -> SelendraTestNode {
 	let multiaddr = config.network.listen_addresses[0].clone();
 	let NewFull { task_manager, client, network, rpc_handlers, overseer_handle, .. } =
 		new_full(config, IsCollator::Yes(collator_pair), None)
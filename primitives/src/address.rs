@@ -0,0 +1,36 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime API reporting the SS58 address formats of the chain.
+//!
+//! Addresses were displayed with the generic prefix `0` before the chain moved to its registered
+//! prefix. Both decode to the same account, so wallets keep accepting the legacy format while
+//! users migrate, and display addresses with the current one.
+
+use sp_std::prelude::*;
+
+/// The SS58 prefix addresses were displayed with before the registered prefix was adopted.
+pub const LEGACY_SS58_PREFIX: u16 = 0;
+
+sp_api::decl_runtime_apis! {
+	/// The API for querying the SS58 prefixes of addresses.
+	pub trait AddressFormatApi {
+		/// The prefix addresses are displayed with.
+		fn ss58_prefix() -> u16;
+		/// The prefixes addresses were displayed with before, which are still accepted.
+		fn legacy_ss58_prefixes() -> Vec<u16>;
+	}
+}
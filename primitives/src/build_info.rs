@@ -0,0 +1,51 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime API reporting the source and build the runtime was compiled from.
+//!
+//! `RuntimeVersion` identifies the spec, but not the source it was built from. Operators verify
+//! the runtime on chain by checking the commit reported here against a tagged release, and the
+//! blake2-256 hash of the `:code` storage against the digest of a reproducible build of that tag,
+//! as reported by srtool.
+
+use parity_scale_codec::{Decode, Encode};
+use runtime_primitives::RuntimeDebug;
+use scale_info::{prelude::string::String, TypeInfo};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+/// How the runtime was built.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct BuildInfo {
+	/// The git commit set in `SELENDRA_RUNTIME_GIT_COMMIT` at build time by release builds, or
+	/// `unknown`.
+	pub git_commit: String,
+	/// The cargo profile the runtime was built with, `release` or `debug`.
+	pub profile: String,
+	/// The builder set in `SELENDRA_RUNTIME_BUILDER` at build time, e.g. the srtool image of a
+	/// reproducible build.
+	pub builder: Option<String>,
+}
+
+sp_api::decl_runtime_apis! {
+	/// The API for querying the build of the runtime.
+	pub trait BuildInfoApi {
+		/// The source and build the runtime was compiled from.
+		fn build_info() -> BuildInfo;
+	}
+}
@@ -0,0 +1,30 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime API submitting dispute statements to the chain outside of the parachains inherent.
+
+use crate::v1::DisputeStatementSet;
+
+sp_api::decl_runtime_apis! {
+	/// The API for submitting dispute statements as unsigned transactions.
+	pub trait DisputeStatementSubmissionApi {
+		/// Submits a set of signed statements on a dispute as an unsigned transaction.
+		///
+		/// NOTE: This function is only available since the offchain transaction pool extension is
+		/// registered in the calling context.
+		fn submit_dispute_statement_set(set: DisputeStatementSet);
+	}
+}
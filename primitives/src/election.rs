@@ -0,0 +1,59 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Settings of the offchain election miner, which validators pass to the offchain worker through
+//! its persistent local storage.
+
+use parity_scale_codec::{Decode, Encode};
+use runtime_primitives::RuntimeDebug;
+
+/// The key of the [`MinerSettings`] in the persistent offchain local storage.
+pub const MINER_SETTINGS_KEY: &[u8] = b"selendra::election-miner-settings";
+
+/// The algorithm used to mine unsigned election solutions.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum MinerSolver {
+	/// Sequential Phragmén. Cheaper to compute.
+	SeqPhragmen,
+	/// PhragMMS. Slower, but tends to find solutions with a higher minimal stake.
+	PhragMMS,
+}
+
+/// How the offchain worker mines unsigned election solutions.
+///
+/// Without stored settings, the miner runs sequential Phragmén without balancing.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub struct MinerSettings {
+	/// The solver to run.
+	pub solver: MinerSolver,
+	/// The maximum number of balancing iterations run on the solution. Zero disables balancing.
+	pub max_iterations: u32,
+	/// Balancing stops early once no stake moves by more than this amount in an iteration.
+	pub tolerance: u128,
+}
+
+impl Default for MinerSettings {
+	fn default() -> Self {
+		Self { solver: MinerSolver::SeqPhragmen, max_iterations: 0, tolerance: 0 }
+	}
+}
+
+impl MinerSettings {
+	/// The balancing configuration of the solvers, `None` if balancing is disabled.
+	pub fn balancing(&self) -> Option<(usize, u128)> {
+		(self.max_iterations > 0).then(|| (self.max_iterations as usize, self.tolerance))
+	}
+}
@@ -0,0 +1,29 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime API reporting the number of equivocations reported to the chain.
+
+pub use sp_staking::offence::Kind;
+use sp_std::prelude::*;
+
+sp_api::decl_runtime_apis! {
+	/// The API for monitoring the equivocation reports accepted by the chain.
+	pub trait EquivocationReportsApi {
+		/// The number of reports accepted for each kind of offence, such as
+		/// `b"babe:equivocatio"` and `b"grandpa:equivoca"`.
+		fn report_counts() -> Vec<(Kind, u32)>;
+	}
+}
@@ -0,0 +1,129 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime API describing the events and errors of the runtime, for indexers.
+//!
+//! Events and errors are encoded by the index of their pallet in the runtime followed by the index
+//! of their variant in the pallet. Both may change with a runtime upgrade, so indexers should
+//! resolve them by name through [`EventsSchemaApi`] for every spec version they decode.
+
+use parity_scale_codec::{Decode, Encode};
+use runtime_primitives::RuntimeDebug;
+use scale_info::{prelude::string::String, TypeInfo};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sp_std::prelude::*;
+
+/// A field of an event or error variant.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct FieldSchema {
+	/// The name of the field, `None` for tuple variants.
+	pub name: Option<String>,
+	/// The name of the type of the field as written in the pallet, e.g. `T::AccountId`.
+	pub type_name: Option<String>,
+}
+
+/// A variant of the event or error enum of a pallet.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct VariantSchema {
+	/// The index the variant is encoded with.
+	pub index: u8,
+	/// The name of the variant.
+	pub name: String,
+	/// The fields of the variant, in encoding order.
+	pub fields: Vec<FieldSchema>,
+}
+
+/// The events and errors of a pallet.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct PalletSchema {
+	/// The index of the pallet in the runtime, which events and errors are encoded with.
+	pub index: u8,
+	/// The name of the pallet in the runtime.
+	pub name: String,
+	/// The events the pallet deposits.
+	pub events: Vec<VariantSchema>,
+	/// The errors the pallet's calls fail with, as found in `DispatchError::Module`.
+	pub errors: Vec<VariantSchema>,
+}
+
+/// The events and errors of all pallets of a runtime.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct EventsSchema {
+	/// The spec version of the runtime the schema describes.
+	pub spec_version: u32,
+	/// The pallets with events or errors, ordered by index.
+	pub pallets: Vec<PalletSchema>,
+}
+
+impl EventsSchema {
+	/// The pallet with the given index.
+	pub fn pallet(&self, index: u8) -> Option<&PalletSchema> {
+		self.pallets.iter().find(|pallet| pallet.index == index)
+	}
+}
+
+/// The phase of block execution an event was deposited in.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub enum EventPhase {
+	/// Applying the extrinsic with the given index.
+	ApplyExtrinsic(u32),
+	/// Finalizing the block.
+	Finalization,
+	/// Initializing the block.
+	Initialization,
+}
+
+/// An event deposited in a block, split into the indices it is encoded with and its fields.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct BlockEvent {
+	/// The phase the event was deposited in.
+	pub phase: EventPhase,
+	/// The index of the pallet that deposited the event.
+	pub pallet: u8,
+	/// The index of the event variant in the pallet.
+	pub variant: u8,
+	/// The SCALE-encoded fields of the event, as described by the [`VariantSchema`].
+	pub fields: Vec<u8>,
+}
+
+sp_api::decl_runtime_apis! {
+	/// The API for describing the events and errors of the runtime.
+	pub trait EventsSchemaApi {
+		/// The events and errors of all pallets, with the indices they are encoded with.
+		fn events_schema() -> EventsSchema;
+	}
+
+	/// The API for splitting the events of a block, for indexers.
+	///
+	/// The events are passed in rather than read from storage, as the call may initialize a block
+	/// on top of the state of the block that deposited them, which clears them.
+	pub trait BlockEventsApi {
+		/// Split `events`, the SCALE-encoded `System::Events` of a block, into its events, or
+		/// `None` if they do not decode with the event types of this runtime.
+		fn block_events(events: Vec<u8>) -> Option<Vec<BlockEvent>>;
+	}
+}
@@ -0,0 +1,27 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime API exposing the parameters of the environment PVFs are executed in.
+
+use crate::v1::{ExecutorParams, SessionIndex};
+
+sp_api::decl_runtime_apis! {
+	/// The API for querying the executor parameters of a session.
+	pub trait ExecutorParamsApi {
+		/// The executor parameters in effect for the given session, if the session is known.
+		fn session_executor_params(session_index: SessionIndex) -> Option<ExecutorParams>;
+	}
+}
@@ -0,0 +1,74 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime API reporting the fee multiplier, the fees it implies and the fees of recent blocks.
+
+use parity_scale_codec::{Codec, Decode, Encode};
+use runtime_primitives::RuntimeDebug;
+use scale_info::TypeInfo;
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sp_std::prelude::*;
+
+pub use sp_arithmetic::{FixedU128 as Multiplier, Perquintill};
+
+/// A kind of transaction whose projected fee is reported by [`FeeMultiplierApi`].
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub enum StandardTransaction {
+	/// A balance transfer which keeps the sender alive.
+	Transfer,
+	/// Nominating a single validator.
+	Nominate,
+	/// A remark of 32 bytes.
+	Remark,
+}
+
+/// The fee multiplier, weight and fees of a block.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct BlockFees<BlockNumber, Balance> {
+	/// The number of the block.
+	pub block: BlockNumber,
+	/// The multiplier of the weight fee the extrinsics of the block paid with.
+	pub multiplier: Multiplier,
+	/// The total weight of the block, of all dispatch classes.
+	pub weight: u64,
+	/// The fees and tips paid by the extrinsics of the block.
+	pub fees: Balance,
+}
+
+sp_api::decl_runtime_apis! {
+	/// The API for querying the fee multiplier, its recent history and the fees it implies.
+	#[api_version(2)]
+	pub trait FeeMultiplierApi<Balance, BlockNumber> where
+		Balance: Codec,
+		BlockNumber: Codec,
+	{
+		/// The multiplier of the weight fee of extrinsics in the next block.
+		fn next_fee_multiplier() -> Multiplier;
+		/// The portion of the normal dispatch class the multiplier adjusts block fullness towards.
+		fn target_block_fullness() -> Perquintill;
+		/// The fee each of the standard transactions would pay in the next block, without tip.
+		fn projected_fees() -> Vec<(StandardTransaction, Balance)>;
+		/// The multiplier each of the recent blocks was executed with, oldest first.
+		fn multiplier_history() -> Vec<(BlockNumber, Multiplier)>;
+		/// The multiplier, weight and fees of at most `count` of the recent blocks, oldest first.
+		///
+		/// Added in version 2.
+		fn fee_history(count: u32) -> Vec<BlockFees<BlockNumber, Balance>>;
+	}
+}
@@ -19,6 +19,16 @@
 #![warn(missing_docs)]
 #![cfg_attr(not(feature = "std"), no_std)]
 
+pub mod address;
+pub mod build_info;
+pub mod disputes;
+pub mod election;
+pub mod equivocation;
+pub mod events;
+pub mod executor_params;
+pub mod fees;
+pub mod para_usage;
+pub mod relay_state_proof;
 pub mod v0;
 pub mod v1;
 pub mod v2;
@@ -0,0 +1,64 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime API reporting the relay chain resources used by the candidates of each para.
+
+use crate::v1::Id;
+use parity_scale_codec::{Decode, Encode};
+use runtime_primitives::RuntimeDebug;
+use scale_info::TypeInfo;
+use sp_std::prelude::*;
+
+/// The resources used by a backed candidate.
+///
+/// The size of the proof of validity is not known to the relay chain, so the size of the
+/// commitments stands in for the data the candidate puts on chain.
+#[derive(Encode, Decode, Clone, Copy, Default, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct CandidateUsage {
+	/// The encoded size of the commitments: head data, messages and any code upgrade.
+	pub commitments_size: u32,
+	/// The size of the validation code the candidate is validated with.
+	pub code_size: u32,
+}
+
+/// The resources used by the candidates of a para over a session.
+#[derive(Encode, Decode, Clone, Copy, Default, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct ParaUsage {
+	/// The number of backed candidates.
+	pub candidates: u32,
+	/// The total encoded size of the commitments of the candidates.
+	pub commitments_size: u64,
+	/// The total size of the validation code the candidates are validated with.
+	pub code_size: u64,
+}
+
+impl ParaUsage {
+	/// Account for a backed candidate.
+	pub fn record(&mut self, usage: CandidateUsage) {
+		self.candidates = self.candidates.saturating_add(1);
+		self.commitments_size = self.commitments_size.saturating_add(usage.commitments_size.into());
+		self.code_size = self.code_size.saturating_add(usage.code_size.into());
+	}
+}
+
+sp_api::decl_runtime_apis! {
+	/// The API for metering the relay chain resources used by paras.
+	pub trait ParaUsageApi {
+		/// The resources used by the candidates of each para backed so far in the current
+		/// session.
+		fn session_usage() -> Vec<(Id, ParaUsage)>;
+	}
+}
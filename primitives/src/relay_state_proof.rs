@@ -0,0 +1,31 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime API describing the relay chain state a parachain's validation data inherent proves.
+
+use crate::v1::Id;
+use sp_std::prelude::*;
+
+sp_api::decl_runtime_apis! {
+	/// The API for generating the relay chain state proof of a parachain's validation data
+	/// inherent.
+	pub trait RelayStateProofApi {
+		/// The storage keys the relay chain state proof of the given para has to contain: the
+		/// current slot, the active configuration, the para's message queue heads and dispatch
+		/// queue size, its upgrade signals and its HRMP channel indexes and channels.
+		fn relay_state_proof_keys(para_id: Id) -> Vec<Vec<u8>>;
+	}
+}
@@ -106,6 +106,17 @@ impl MallocSizeOf for ValidatorId {
 	}
 }
 
+/// An `AppCrypto` type to allow offchain workers to submit consensus-maintenance transactions,
+/// such as dispute statements and PVF pre-checking votes, using the parachain validator key.
+pub struct ValidatorAppCrypto;
+impl frame_system::offchain::AppCrypto<<Signature as Verify>::Signer, Signature>
+	for ValidatorAppCrypto
+{
+	type RuntimeAppPublic = ValidatorId;
+	type GenericSignature = primitives::sr25519::Signature;
+	type GenericPublic = primitives::sr25519::Public;
+}
+
 /// Index of the validator is used as a lightweight replacement of the `ValidatorId` when appropriate.
 #[derive(Eq, Ord, PartialEq, PartialOrd, Copy, Clone, Encode, Decode, TypeInfo, RuntimeDebug)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize, Hash, MallocSizeOf))]
@@ -38,14 +38,15 @@ pub use selendra_core_primitives::v1::{
 
 // Export some selendra-parachain primitives
 pub use selendra_parachain::primitives::{
-	HeadData, HrmpChannelId, Id, UpwardMessage, ValidationCode, ValidationCodeHash,
+	ExecutorParams, HeadData, HrmpChannelId, Id, UpwardMessage, ValidationCode, ValidationCodeHash,
 	LOWEST_PUBLIC_ID, LOWEST_USER_ID,
 };
 
 // Export some basic parachain primitives from v0.
 pub use crate::v0::{
-	CollatorId, CollatorSignature, CompactStatement, SigningContext, ValidatorId, ValidatorIndex,
-	ValidatorSignature, ValidityAttestation, PARACHAIN_KEY_TYPE_ID,
+	CollatorId, CollatorSignature, CompactStatement, SigningContext, ValidatorAppCrypto,
+	ValidatorId, ValidatorIndex, ValidatorSignature, ValidityAttestation, COLLATOR_KEY_TYPE_ID,
+	PARACHAIN_KEY_TYPE_ID,
 };
 
 #[cfg(feature = "std")]
@@ -230,6 +231,10 @@ pub mod well_known_keys {
 /// Unique identifier for the Parachains Inherent
 pub const PARACHAINS_INHERENT_IDENTIFIER: InherentIdentifier = *b"parachn0";
 
+/// Unique identifier for the block author's view of the latest finalized relay chain block number,
+/// provided alongside the Parachains Inherent.
+pub const FINALIZED_NUMBER_INHERENT_IDENTIFIER: InherentIdentifier = *b"finalnum";
+
 /// The key type ID for parachain assignment key.
 pub const ASSIGNMENT_KEY_TYPE_ID: KeyTypeId = KeyTypeId(*b"asgn");
 
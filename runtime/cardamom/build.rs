@@ -14,9 +14,17 @@
 // You should have received a copy of the GNU General Public License
 // along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
 
+use std::env;
 use substrate_wasm_builder::WasmBuilder;
 
 fn main() {
+	// Reported by the `BuildInfoApi` of the runtime. Release and srtool builds set the commit and
+	// the builder, so that they don't depend on the state of the working tree.
+	println!("cargo:rerun-if-env-changed=SELENDRA_RUNTIME_GIT_COMMIT");
+	println!("cargo:rerun-if-env-changed=SELENDRA_RUNTIME_BUILDER");
+	let profile = env::var("PROFILE").unwrap_or_default();
+	println!("cargo:rustc-env=SELENDRA_RUNTIME_PROFILE={}", profile);
+
 	WasmBuilder::new()
 		.with_current_project()
 		.import_memory()
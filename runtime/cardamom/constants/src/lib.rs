@@ -20,6 +20,11 @@ pub mod currency {
 	use primitives::v0::Balance;
 
 	/// The existential deposit.
+	///
+	/// This is far below `deposit(1, 0)`, so an account costs less to keep alive than the storage
+	/// it occupies. Balances of reaped accounts are moved to the treasury. Lowering this value
+	/// needs no migration; raising it leaves existing accounts below it until they are next
+	/// touched.
 	pub const EXISTENTIAL_DEPOSIT: Balance = 1 * CENTS;
 
 	pub const UNITS: Balance = 1_000_000_000_000_000_000;
@@ -52,16 +57,23 @@ pub mod time {
 /// Fee-related.
 pub mod fee {
 	use frame_support::weights::{
-		WeightToFeeCoefficient, WeightToFeeCoefficients, WeightToFeePolynomial,
+		constants::WEIGHT_PER_MICROS, Weight, WeightToFeeCoefficient, WeightToFeeCoefficients,
+		WeightToFeePolynomial,
 	};
 	use primitives::v0::Balance;
-	use runtime_common::ExtrinsicBaseWeight;
 	use smallvec::smallvec;
 	pub use sp_runtime::Perbill;
 
 	/// The block saturation level. Fees will be updates based on this value.
 	pub const TARGET_BLOCK_FULLNESS: Perbill = Perbill::from_percent(25);
 
+	/// The weight fee of a balance transfer at a fee multiplier of one.
+	pub const TRANSFER_FEE: Balance = 1 * super::currency::CENTS;
+
+	/// The weight of a `transfer_keep_alive` including the base extrinsic weight, which the weight
+	/// fee is calibrated against. The fee tests of the runtime check it against the benchmarks.
+	pub const TRANSFER_WEIGHT: Weight = 264 * WEIGHT_PER_MICROS;
+
 	/// Handles converting a weight scalar to a fee value, based on the scale and granularity of the
 	/// node's balance type.
 	///
@@ -72,12 +84,14 @@ pub mod fee {
 	/// Yet, it can be used for any other sort of change to weight-fee. Some examples being:
 	///   - Setting it to `0` will essentially disable the weight fee.
 	///   - Setting it to `1` will cause the literal `#[weight = x]` values to be charged.
+	///
+	/// The fee is linear in the weight, such that `TRANSFER_WEIGHT` costs `TRANSFER_FEE`.
 	pub struct WeightToFee;
 	impl WeightToFeePolynomial for WeightToFee {
 		type Balance = Balance;
 		fn polynomial() -> WeightToFeeCoefficients<Self::Balance> {
-			let p = 100 * super::currency::MILLICENTS;
-			let q = 10 * Balance::from(ExtrinsicBaseWeight::get());
+			let p = TRANSFER_FEE;
+			let q = Balance::from(TRANSFER_WEIGHT);
 			smallvec![WeightToFeeCoefficient {
 				degree: 1,
 				negative: false,
@@ -87,3 +101,32 @@ pub mod fee {
 		}
 	}
 }
+
+/// Staking elections.
+pub mod election {
+	/// The maximum number of validators a nominator can nominate, which is also the number of votes
+	/// a voter can have in an election solution.
+	///
+	/// It is 24 unless one of the `max-nominations-16`, `max-nominations-24` or
+	/// `max-nominations-32` features is enabled. The runtime generates its `NposCompactSolution`
+	/// with the same number of votes. Changing it is a runtime upgrade that needs care:
+	///
+	/// - The encoding of solutions changes, so signed submissions queued at the upgrade can no
+	///   longer be decoded. The upgrade has to be enacted while the election phase is `Off`.
+	/// - Nominations are bounded by it. Raising it needs no migration, but lowering it leaves the
+	///   nominations above the new bound undecodable, so they have to be truncated by a migration
+	///   in the same upgrade.
+	#[cfg(feature = "max-nominations-16")]
+	pub const MAX_NOMINATIONS: u32 = 16;
+	#[cfg(not(any(feature = "max-nominations-16", feature = "max-nominations-32")))]
+	pub const MAX_NOMINATIONS: u32 = 24;
+	#[cfg(feature = "max-nominations-32")]
+	pub const MAX_NOMINATIONS: u32 = 32;
+}
+
+#[cfg(any(
+	all(feature = "max-nominations-16", feature = "max-nominations-24"),
+	all(feature = "max-nominations-16", feature = "max-nominations-32"),
+	all(feature = "max-nominations-24", feature = "max-nominations-32"),
+))]
+compile_error!("only one of the `max-nominations-*` features can be enabled");
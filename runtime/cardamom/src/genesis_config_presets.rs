@@ -0,0 +1,146 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Genesis config presets of the Cardamom runtime.
+//!
+//! See [`runtime_common::genesis_presets`] for the names of the presets.
+
+use crate::*;
+use primitives::v1::AssignmentId;
+use runtime_common::genesis_presets::{get_account_id_from_seed, get_from_seed, testnet_accounts};
+use sp_core::sr25519;
+
+pub use runtime_common::genesis_presets::{
+	default_parachains_host_configuration, DEVELOPMENT, LOCAL_TESTNET,
+};
+
+/// The stash and controller accounts and the session keys of a validator.
+pub type AuthorityKeys = (
+	AccountId,
+	AccountId,
+	babe_primitives::AuthorityId,
+	GrandpaId,
+	ImOnlineId,
+	ValidatorId,
+	AssignmentId,
+	AuthorityDiscoveryId,
+);
+
+/// The keys of the validator derived from `seed`.
+pub fn authority_keys_from_seed(seed: &str) -> AuthorityKeys {
+	(
+		get_account_id_from_seed::<sr25519::Public>(&format!("{}//stash", seed)),
+		get_account_id_from_seed::<sr25519::Public>(seed),
+		get_from_seed::<babe_primitives::AuthorityId>(seed),
+		get_from_seed::<GrandpaId>(seed),
+		get_from_seed::<ImOnlineId>(seed),
+		get_from_seed::<ValidatorId>(seed),
+		get_from_seed::<AssignmentId>(seed),
+		get_from_seed::<AuthorityDiscoveryId>(seed),
+	)
+}
+
+/// The genesis config of a test chain with the given validators, endowing `endowed_accounts`
+/// or the well-known test accounts.
+pub fn testnet_genesis(
+	wasm_binary: &[u8],
+	initial_authorities: Vec<AuthorityKeys>,
+	root_key: AccountId,
+	endowed_accounts: Option<Vec<AccountId>>,
+) -> GenesisConfig {
+	let endowed_accounts: Vec<AccountId> = endowed_accounts.unwrap_or_else(testnet_accounts);
+
+	const ENDOWMENT: u128 = 1_000_000 * UNITS;
+	const STASH: u128 = 100 * UNITS;
+
+	GenesisConfig {
+		system: SystemConfig { code: wasm_binary.to_vec() },
+		indices: IndicesConfig { indices: vec![] },
+		balances: BalancesConfig {
+			balances: endowed_accounts.iter().map(|k| (k.clone(), ENDOWMENT)).collect(),
+		},
+		session: SessionConfig {
+			keys: initial_authorities
+				.iter()
+				.map(|x| {
+					(
+						x.0.clone(),
+						x.0.clone(),
+						SessionKeys {
+							babe: x.2.clone(),
+							grandpa: x.3.clone(),
+							im_online: x.4.clone(),
+							para_validator: x.5.clone(),
+							para_assignment: x.6.clone(),
+							authority_discovery: x.7.clone(),
+						},
+					)
+				})
+				.collect::<Vec<_>>(),
+		},
+		staking: StakingConfig {
+			validator_count: 4,
+			minimum_validator_count: 4,
+			stakers: initial_authorities
+				.iter()
+				.map(|x| (x.0.clone(), x.1.clone(), STASH, StakerStatus::Validator))
+				.collect(),
+			invulnerables: initial_authorities.iter().map(|x| x.0.clone()).collect(),
+			slash_reward_fraction: Perbill::from_percent(10),
+			..Default::default()
+		},
+		phragmen_election: Default::default(),
+		democracy: Default::default(),
+		council: CouncilConfig { members: vec![], phantom: Default::default() },
+		technical_committee: TechnicalCommitteeConfig {
+			members: vec![],
+			phantom: Default::default(),
+		},
+		technical_membership: Default::default(),
+		babe: BabeConfig {
+			authorities: Default::default(),
+			epoch_config: Some(BABE_GENESIS_EPOCH_CONFIG),
+		},
+		grandpa: Default::default(),
+		im_online: Default::default(),
+		authority_discovery: AuthorityDiscoveryConfig { keys: vec![] },
+		vesting: VestingConfig { vesting: vec![] },
+		treasury: Default::default(),
+		sudo: SudoConfig { key: Some(root_key) },
+		hrmp: Default::default(),
+		configuration: ConfigurationConfig { config: default_parachains_host_configuration() },
+		paras: Default::default(),
+		xcm_pallet: Default::default(),
+	}
+}
+
+/// The genesis config of the preset `name`.
+///
+/// Returns `None` if there is no such preset or the wasm binary of the runtime was not built.
+pub fn get_preset(name: &str) -> Option<GenesisConfig> {
+	let initial_authorities = match name {
+		DEVELOPMENT => vec![authority_keys_from_seed("Alice")],
+		LOCAL_TESTNET => vec![authority_keys_from_seed("Alice"), authority_keys_from_seed("Bob")],
+		_ => return None,
+	};
+
+	Some(testnet_genesis(
+		WASM_BINARY?,
+		initial_authorities,
+		get_account_id_from_seed::<sr25519::Public>("Alice"),
+		None,
+	))
+}
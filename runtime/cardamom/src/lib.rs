@@ -33,9 +33,14 @@ use primitives::{
 	v2::SessionInfo,
 };
 use runtime_common::{
-	impls::DealWithFees, paras_registrar, paras_sudo_wrapper, prod_or_fast, slots, BlockHashCount,
-	BlockLength, BlockWeights, CurrencyToVote, OffchainSolutionLengthLimit,
-	OffchainSolutionWeightLimit, RocksDbWeight, SlowAdjustingFeeUpdate,
+	auctions, authorship_stats, batch_transfer, bounty_expiry, candidacy_renewal, crowdloan,
+	equivocation_reports, fee_multiplier, impls::DealWithFees, indices_wind_down, mandatory_weight,
+	meta_tx, multisig_calls, paras_registrar, paras_sudo_wrapper, price_oracle, priority::Lane,
+	prod_or_fast, recovery_probation, safe_mode, scheduler, slash_simulation, slashing_pruning,
+	slots, tips_retirement, treasury_xcm_payouts, upgrade_enactment, validator_count_growth,
+	validator_disabling, validator_operators, BlockHashCount, BlockLength, BlockWeights,
+	CurrencyToVote, OffchainSolutionLengthLimit, OffchainSolutionWeightLimit, RocksDbWeight,
+	SlowAdjustingFeeUpdate,
 };
 use sp_std::{cmp::Ordering, collections::btree_map::BTreeMap, prelude::*};
 
@@ -91,7 +96,7 @@ pub use pallet_timestamp::Call as TimestampCall;
 pub use sp_runtime::BuildStorage;
 
 /// Constant values used within the runtime.
-use cardamom_runtime_constants::{currency::*, fee::*, time::*};
+use cardamom_runtime_constants::{currency::*, election::MAX_NOMINATIONS, fee::*, time::*};
 
 // Weights used in the runtime.
 mod weights;
@@ -105,6 +110,10 @@ pub mod xcm_config;
 #[cfg(test)]
 mod tests;
 
+// Genesis configs of development and test chains.
+#[cfg(feature = "std")]
+pub mod genesis_config_presets;
+
 // Make the WASM binary available.
 #[cfg(feature = "std")]
 include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
@@ -114,13 +123,13 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	spec_name: create_runtime_str!("cardamom"),
 	impl_name: create_runtime_str!("selendra-cardamom"),
 	authoring_version: 1,
-	spec_version: 205,
+	spec_version: 206,
 	impl_version: 0,
 	#[cfg(not(feature = "disable-runtime-api"))]
 	apis: RUNTIME_API_VERSIONS,
 	#[cfg(feature = "disable-runtime-api")]
 	apis: version::create_apis_vec![[]],
-	transaction_version: 4,
+	transaction_version: 5,
 	state_version: 0,
 };
 
@@ -140,8 +149,21 @@ pub fn native_version() -> NativeVersion {
 /// We currently allow all calls.
 pub struct BaseFilter;
 impl Contains<Call> for BaseFilter {
-	fn contains(_c: &Call) -> bool {
-		true
+	fn contains(c: &Call) -> bool {
+		match c {
+			// Only the calls in `SafeModeCalls` are allowed while the chain is in safe mode.
+			_ if !SafeMode::allows(c) => false,
+			// Indices are being wound down, so no new ones may be claimed.
+			Call::Indices(pallet_indices::Call::claim { .. }) => false,
+			// Large calls have to be scheduled through a preimage.
+			Call::Scheduler(call) =>
+				!scheduler::exceeds_inline_call_size(call, MaxInlineScheduledCallSize::get()),
+			// Recoveries are claimed and used on probation through `RecoveryProbation`.
+			Call::Recovery(call) => RecoveryProbation::recovery_call_allowed(call),
+			// Calls are no longer stored, signatories approve their hash instead.
+			Call::Multisig(pallet_multisig::Call::as_multi { store_call: true, .. }) => false,
+			_ => true,
+		}
 	}
 }
 
@@ -152,7 +174,9 @@ type MoreThanHalfCouncil = EnsureOneOf<
 
 parameter_types! {
 	pub const Version: RuntimeVersion = VERSION;
-	pub const SS58Prefix: u16 = 204;
+	// The registered prefix of Selendra. It lives in storage, so that governance can switch
+	// back with `system.set_storage` should wallets not be ready for it.
+	pub storage SS58Prefix: u16 = 204;
 }
 
 impl frame_system::Config for Runtime {
@@ -186,6 +210,8 @@ parameter_types! {
 	pub MaximumSchedulerWeight: Weight = Perbill::from_percent(80) *
 		BlockWeights::get().max_block;
 	pub const MaxScheduledPerBlock: u32 = 50;
+	/// Calls larger than this have to be noted as a preimage and scheduled by hash.
+	pub const MaxInlineScheduledCallSize: u32 = 128;
 	pub const NoPreimagePostponement: Option<u32> = Some(10);
 }
 
@@ -279,8 +305,11 @@ impl pallet_babe::Config for Runtime {
 
 	type KeyOwnerProofSystem = Historical;
 
-	type HandleEquivocation =
-		pallet_babe::EquivocationHandler<Self::KeyOwnerIdentification, Offences, ReportLongevity>;
+	type HandleEquivocation = pallet_babe::EquivocationHandler<
+		Self::KeyOwnerIdentification,
+		equivocation_reports::RecordReports<Self, Offences>,
+		ReportLongevity,
+	>;
 
 	type WeightInfo = ();
 
@@ -299,6 +328,15 @@ impl pallet_indices::Config for Runtime {
 	type WeightInfo = weights::pallet_indices::WeightInfo<Runtime>;
 }
 
+parameter_types! {
+	pub const MaxIndexRefundsPerBlock: u32 = 32;
+}
+
+impl indices_wind_down::Config for Runtime {
+	type Event = Event;
+	type MaxRefundsPerBlock = MaxIndexRefundsPerBlock;
+}
+
 parameter_types! {
 	pub const ExistentialDeposit: Balance = EXISTENTIAL_DEPOSIT;
 	pub const MaxLocks: u32 = 50;
@@ -307,7 +345,8 @@ parameter_types! {
 
 impl pallet_balances::Config for Runtime {
 	type Balance = Balance;
-	type DustRemoval = ();
+	// Balances of reaped accounts go to the treasury rather than being burned.
+	type DustRemoval = Treasury;
 	type Event = Event;
 	type ExistentialDeposit = ExistentialDeposit;
 	type AccountStore = System;
@@ -317,6 +356,32 @@ impl pallet_balances::Config for Runtime {
 	type WeightInfo = weights::pallet_balances::WeightInfo<Runtime>;
 }
 
+parameter_types! {
+	pub const MaxBatchTransfers: u32 = 500;
+}
+
+impl batch_transfer::Config for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type MaxTransfers = MaxBatchTransfers;
+	type WeightInfo = weights::runtime_common_batch_transfer::WeightInfo<Runtime>;
+}
+
+parameter_types! {
+	pub const MaxRelayedCalls: u32 = 10;
+	pub const MetaTxRatePeriod: BlockNumber = 1 * HOURS;
+}
+
+impl meta_tx::Config for Runtime {
+	type Event = Event;
+	type Call = Call;
+	type Signature = Signature;
+	type Signer = <Signature as Verify>::Signer;
+	type MaxRelayedCalls = MaxRelayedCalls;
+	type RatePeriod = MetaTxRatePeriod;
+	type WeightInfo = weights::runtime_common_meta_tx::WeightInfo<Runtime>;
+}
+
 parameter_types! {
 	pub const TransactionByteFee: Balance = 500 * NANO;
 	/// This value increases the priority of `Operational` transactions by adding
@@ -325,13 +390,24 @@ parameter_types! {
 }
 
 impl pallet_transaction_payment::Config for Runtime {
-	type OnChargeTransaction = CurrencyAdapter<Balances, DealWithFees<Self>>;
+	type OnChargeTransaction =
+		CurrencyAdapter<Balances, fee_multiplier::RecordFees<Self, DealWithFees<Self>>>;
 	type TransactionByteFee = TransactionByteFee;
 	type OperationalFeeMultiplier = OperationalFeeMultiplier;
 	type WeightToFee = WeightToFee;
 	type FeeMultiplierUpdate = SlowAdjustingFeeUpdate<Self>;
 }
 
+parameter_types! {
+	pub const FeeMultiplierHistoryDepth: u32 = 1 * HOURS;
+}
+
+impl fee_multiplier::Config for Runtime {
+	type Event = Event;
+	type HistoryDepth = FeeMultiplierHistoryDepth;
+	type FullnessOrigin = EnsureRoot<AccountId>;
+}
+
 parameter_types! {
 	pub const MinimumPeriod: u64 = SLOT_DURATION / 2;
 }
@@ -416,14 +492,39 @@ parameter_types! {
 	pub const VoterSnapshotPerBlock: u32 = 22_500;
 }
 
+// `generate_solution_type` takes the number of votes as a literal, so there is a solution type for
+// each value `MAX_NOMINATIONS` can take.
+#[cfg(feature = "max-nominations-16")]
+sp_npos_elections::generate_solution_type!(
+	#[compact]
+	pub struct NposCompactSolution::<
+		VoterIndex = u32,
+		TargetIndex = u16,
+		Accuracy = sp_runtime::PerU16,
+	>(16)
+);
+#[cfg(not(any(feature = "max-nominations-16", feature = "max-nominations-32")))]
 sp_npos_elections::generate_solution_type!(
 	#[compact]
-	pub struct NposCompactSolution24::<
+	pub struct NposCompactSolution::<
 		VoterIndex = u32,
 		TargetIndex = u16,
 		Accuracy = sp_runtime::PerU16,
 	>(24)
 );
+#[cfg(feature = "max-nominations-32")]
+sp_npos_elections::generate_solution_type!(
+	#[compact]
+	pub struct NposCompactSolution::<
+		VoterIndex = u32,
+		TargetIndex = u16,
+		Accuracy = sp_runtime::PerU16,
+	>(32)
+);
+
+const_assert!(
+	<NposCompactSolution as sp_npos_elections::NposSolution>::LIMIT == MAX_NOMINATIONS as usize
+);
 
 impl pallet_election_provider_multi_phase::Config for Runtime {
 	type Event = Event;
@@ -445,14 +546,13 @@ impl pallet_election_provider_multi_phase::Config for Runtime {
 	type OffchainRepeat = OffchainRepeat;
 	type MinerTxPriority = NposSolutionPriority;
 	type DataProvider = Staking;
-	type Solution = NposCompactSolution24;
+	type Solution = NposCompactSolution;
 	type Fallback = pallet_election_provider_multi_phase::NoFallback<Self>;
 	type GovernanceFallback =
 		frame_election_provider_support::onchain::OnChainSequentialPhragmen<Self>;
-	type Solver = frame_election_provider_support::SequentialPhragmen<
+	type Solver = runtime_common::elections::OffchainConfiguredSolver<
 		AccountId,
 		pallet_election_provider_multi_phase::SolutionAccuracyOf<Self>,
-		(),
 	>;
 	type BenchmarkingConfig = runtime_common::elections::BenchmarkConfig;
 	type ForceOrigin = EnsureOneOf<
@@ -494,9 +594,9 @@ parameter_types! {
 	pub const SlashDeferDuration: sp_staking::EraIndex = 12;
 	pub const RewardCurve: &'static PiecewiseLinear<'static> = &REWARD_CURVE;
 	pub const MaxNominatorRewardedPerValidator: u32 = 256;
+	// Offenders stop authoring up to this share of validators, beyond it a new era is forced.
 	pub const OffendingValidatorsThreshold: Perbill = Perbill::from_percent(17);
-	// 24
-	pub const MaxNominations: u32 = <NposCompactSolution24 as sp_npos_elections::NposSolution>::LIMIT as u32;
+	pub const MaxNominations: u32 = MAX_NOMINATIONS;
 }
 
 type SlashCancelOrigin = EnsureOneOf<
@@ -536,6 +636,45 @@ impl pallet_staking::Config for Runtime {
 	type WeightInfo = weights::pallet_staking::WeightInfo<Runtime>;
 }
 
+parameter_types! {
+	// Five more validators every 120 eras (30 days), up to 100.
+	pub const ValidatorCountStepEras: sp_staking::EraIndex = 120;
+	pub const ValidatorCountStepSize: u32 = 5;
+	pub const MaxScheduledValidatorCount: u32 = 100;
+}
+
+impl validator_count_growth::Config for Runtime {
+	type Event = Event;
+	type Staking = Staking;
+	type ControlOrigin = MoreThanHalfCouncil;
+	type StepEras = ValidatorCountStepEras;
+	type StepSize = ValidatorCountStepSize;
+	type MaxValidatorCount = MaxScheduledValidatorCount;
+}
+
+parameter_types! {
+	// As long as the staking rewards can be claimed.
+	pub const AuthorshipStatsHistoryDepth: sp_staking::EraIndex = 84;
+}
+
+impl authorship_stats::Config for Runtime {
+	type FindAuthor = Babe;
+	type ValidatorSet = Session;
+	type Staking = Staking;
+	type HistoryDepth = AuthorshipStatsHistoryDepth;
+}
+
+parameter_types! {
+	pub const MandatoryWeightHistoryDepth: u32 = 1 * HOURS;
+	pub const MandatoryWeightAlertThreshold: Perbill = Perbill::from_percent(50);
+}
+
+impl mandatory_weight::Config for Runtime {
+	type Event = Event;
+	type HistoryDepth = MandatoryWeightHistoryDepth;
+	type AlertThreshold = MandatoryWeightAlertThreshold;
+}
+
 parameter_types! {
 	pub LaunchPeriod: BlockNumber = prod_or_fast!(3 * DAYS, 1, "SEL_LAUNCH_PERIOD");
 	pub VotingPeriod: BlockNumber = prod_or_fast!(3 * DAYS, 1, "SEL_LAUNCH_PERIOD");
@@ -594,7 +733,7 @@ impl pallet_democracy::Config for Runtime {
 	type PreimageByteDeposit = PreimageByteDeposit;
 	type OperationalPreimageOrigin = pallet_collective::EnsureMember<AccountId, CouncilCollective>;
 	type Slash = Treasury;
-	type Scheduler = Scheduler;
+	type Scheduler = scheduler::PostponeOnFullAgenda<Runtime>;
 	type PalletsOrigin = OriginCaller;
 	type MaxVotes = MaxVotes;
 	type WeightInfo = weights::pallet_democracy::WeightInfo<Runtime>;
@@ -638,7 +777,7 @@ const_assert!(DesiredMembers::get() <= CouncilMaxMembers::get());
 impl pallet_elections_phragmen::Config for Runtime {
 	type Event = Event;
 	type Currency = Balances;
-	type ChangeMembers = Council;
+	type ChangeMembers = CandidacyRenewal;
 	type InitializeMembers = Council;
 	type CurrencyToVote = frame_support::traits::U128CurrencyToVote;
 	type CandidacyBond = CandidacyBond;
@@ -653,6 +792,16 @@ impl pallet_elections_phragmen::Config for Runtime {
 	type WeightInfo = weights::pallet_elections_phragmen::WeightInfo<Runtime>;
 }
 
+parameter_types! {
+	pub const MaxAutoRenewals: u32 = 64;
+}
+
+impl candidacy_renewal::Config for Runtime {
+	type Event = Event;
+	type ChangeMembers = Council;
+	type MaxAutoRenewals = MaxAutoRenewals;
+}
+
 parameter_types! {
 	pub TechnicalMotionDuration: BlockNumber = prod_or_fast!(2 * DAYS, 2 * MINUTES, "SEL_MOTION_DURATION");
 	pub const TechnicalMaxProposals: u32 = 100;
@@ -685,16 +834,107 @@ impl pallet_membership::Config<pallet_membership::Instance1> for Runtime {
 }
 
 parameter_types! {
-	pub const ProposalBond: Permill = Permill::from_percent(5);
-	pub const ProposalBondMinimum: Balance = 1 * UNITS;
-	pub const ProposalBondMaximum: Balance = 50 * UNITS;
+	pub const MaxSafeModeDuration: BlockNumber = 7 * DAYS;
+}
+
+/// The calls allowed while the chain is in safe mode: those keeping consensus, staking and the
+/// parachains running, and governance.
+pub struct SafeModeCalls;
+impl Contains<Call> for SafeModeCalls {
+	fn contains(c: &Call) -> bool {
+		matches!(
+			c,
+			Call::System(_) |
+				Call::Babe(_) |
+				Call::Timestamp(_) |
+				Call::Authorship(_) |
+				Call::Staking(_) |
+				Call::Session(_) |
+				Call::Grandpa(_) |
+				Call::ImOnline(_) |
+				Call::ElectionProviderMultiPhase(_) |
+				Call::BagsList(_) |
+				Call::Configuration(_) |
+				Call::ParasShared(_) |
+				Call::ParaInclusion(_) |
+				Call::ParaInherent(_) |
+				Call::Paras(_) |
+				Call::Initializer(_) |
+				Call::ParasDisputes(_) |
+				Call::Democracy(_) |
+				Call::Council(_) |
+				Call::TechnicalCommittee(_) |
+				Call::TechnicalMembership(_) |
+				Call::PhragmenElection(_) |
+				Call::Scheduler(_) |
+				Call::Preimage(_) |
+				Call::ParasSudoWrapper(_) |
+				Call::SafeMode(_) |
+				Call::UpgradeEnactment(_)
+		)
+	}
+}
+
+impl safe_mode::Config for Runtime {
+	type Event = Event;
+	type ForceOrigin = EnsureOneOf<
+		pallet_collective::EnsureProportionAtLeast<AccountId, TechnicalCollective, 2, 3>,
+		EnsureRoot<AccountId>,
+	>;
+	type MaxDuration = MaxSafeModeDuration;
+	type SafeCalls = SafeModeCalls;
+}
+
+parameter_types! {
+	pub UpgradeEnactmentDelay: BlockNumber = prod_or_fast!(6 * HOURS, 1 * MINUTES, "SEL_UPGRADE_ENACTMENT_DELAY");
+}
+
+impl upgrade_enactment::Config for Runtime {
+	type Event = Event;
+	type UpgradeOrigin = EnsureRoot<AccountId>;
+	type EnactmentDelay = UpgradeEnactmentDelay;
+}
+
+parameter_types! {
+	pub const MaxValidatorOperators: u32 = 50;
+	pub OperatorMotionDuration: BlockNumber = prod_or_fast!(1 * DAYS, 2 * MINUTES, "SEL_OPERATOR_MOTION_DURATION");
+	pub const OperatorMaxProposals: u32 = 10;
+}
+
+impl validator_operators::Config for Runtime {
+	type Event = Event;
+	type ValidatorSet = Session;
+	type MembershipChanged = OperatorCommittee;
+	type ManagerOrigin = MoreThanHalfCouncil;
+	type MaxOperators = MaxValidatorOperators;
+}
+
+/// The active validator operators, while they are enabled by the council.
+pub type OperatorCollective = pallet_collective::Instance3;
+impl pallet_collective::Config<OperatorCollective> for Runtime {
+	type Origin = Origin;
+	type Proposal = Call;
+	type Event = Event;
+	type MotionDuration = OperatorMotionDuration;
+	type MaxProposals = OperatorMaxProposals;
+	type MaxMembers = MaxValidatorOperators;
+	type DefaultVote = pallet_collective::MoreThanMajorityThenPrimeDefaultVote;
+	type WeightInfo = weights::pallet_collective_technical_committee::WeightInfo<Runtime>;
+}
+
+parameter_types! {
+	// The proposal bond parameters live in storage, so that governance can adjust them with
+	// `system.set_storage` without a runtime upgrade. The bond is capped at
+	// `ProposalBondMaximum` and returned to the proposer once an approved proposal is paid out.
+	pub storage ProposalBond: Permill = Permill::from_percent(5);
+	pub storage ProposalBondMinimum: Balance = 1 * UNITS;
+	pub storage ProposalBondMaximum: Balance = 50 * UNITS;
 	pub const SpendPeriod: BlockNumber = 3 * DAYS;
 	pub const Burn: Permill = Permill::from_perthousand(2);
 	pub const TreasuryPalletId: PalletId = PalletId(*b"py/trsry");
 
-	pub const TipCountdown: BlockNumber = 1 * DAYS;
+	/// The finder's fee of the retired tips pallet, used to pay out open tips on its removal.
 	pub const TipFindersFee: Percent = Percent::from_percent(20);
-	pub const TipReportDepositBase: Balance = 5000 * CENTS;
 	pub const DataDepositPerByte: Balance = 100 * CENTS;
 	pub const BountyDepositBase: Balance = 5000 * CENTS;
 	pub const BountyDepositPayoutDelay: BlockNumber = 4 * DAYS;
@@ -729,7 +969,7 @@ impl pallet_treasury::Config for Runtime {
 	type BurnDestination = ();
 	type MaxApprovals = MaxApprovals;
 	type WeightInfo = weights::pallet_treasury::WeightInfo<Runtime>;
-	type SpendFunds = Bounties;
+	type SpendFunds = (Bounties, TreasuryXcmPayouts);
 }
 
 impl pallet_bounties::Config for Runtime {
@@ -745,21 +985,33 @@ impl pallet_bounties::Config for Runtime {
 	type WeightInfo = weights::pallet_bounties::WeightInfo<Runtime>;
 }
 
-impl pallet_tips::Config for Runtime {
-	type MaximumReasonLength = MaximumReasonLength;
-	type DataDepositPerByte = DataDepositPerByte;
-	type Tippers = PhragmenElection;
-	type TipCountdown = TipCountdown;
-	type TipFindersFee = TipFindersFee;
-	type TipReportDepositBase = TipReportDepositBase;
+parameter_types! {
+	pub const ExpiredCuratorSlash: Permill = Permill::from_percent(50);
+	pub const MaxBountySweepsPerBlock: u32 = 16;
+}
+
+impl bounty_expiry::Config for Runtime {
 	type Event = Event;
-	type WeightInfo = weights::pallet_tips::WeightInfo<Runtime>;
+	type CuratorSlash = ExpiredCuratorSlash;
+	type MaxSweepsPerBlock = MaxBountySweepsPerBlock;
+	type HorizonOrigin = MoreThanHalfCouncil;
 }
 
 impl pallet_offences::Config for Runtime {
 	type Event = Event;
 	type IdentificationTuple = pallet_session::historical::IdentificationTuple<Self>;
-	type OnOffenceHandler = Staking;
+	type OnOffenceHandler =
+		validator_disabling::StagedDisabling<Self, Staking, OffendingValidatorsThreshold>;
+}
+
+parameter_types! {
+	/// Equivocation reports are valid for `ReportLongevity` slots, which is at most as many blocks.
+	pub EquivocationReportWindow: BlockNumber = ReportLongevity::get() as BlockNumber;
+}
+
+impl equivocation_reports::Config for Runtime {
+	type Event = Event;
+	type ReportWindow = EquivocationReportWindow;
 }
 
 impl pallet_authority_discovery::Config for Runtime {
@@ -801,7 +1053,7 @@ impl pallet_grandpa::Config for Runtime {
 
 	type HandleEquivocation = pallet_grandpa::EquivocationHandler<
 		Self::KeyOwnerIdentification,
-		Offences,
+		equivocation_reports::RecordReports<Self, Offences>,
 		ReportLongevity,
 	>;
 
@@ -870,6 +1122,25 @@ where
 	type OverarchingCall = Call;
 }
 
+parameter_types! {
+	pub const MaxPriceFeeders: u32 = 32;
+	pub const MaxPriceFeedAssets: u32 = 16;
+	pub const MinPriceFeeds: u32 = 1;
+	pub const PriceStalePeriod: BlockNumber = 1 * HOURS;
+}
+
+impl price_oracle::Config for Runtime {
+	type Event = Event;
+	// `0` is SEL, other assets are numbered as they are added.
+	type AssetId = u32;
+	type ManagerOrigin = MoreThanHalfCouncil;
+	type AuthorityId = price_oracle::crypto::OracleAuthId;
+	type MaxFeeders = MaxPriceFeeders;
+	type MaxFeedAssets = MaxPriceFeedAssets;
+	type MinFeeds = MinPriceFeeds;
+	type StalePeriod = PriceStalePeriod;
+}
+
 parameter_types! {
 	// Minimum 100 bytes/SEL deposited (1 CENT/byte)
 	pub const BasicDeposit: Balance = 1 * UNITS;       // 258 bytes on-chain
@@ -937,6 +1208,19 @@ impl pallet_recovery::Config for Runtime {
 	type RecoveryDeposit = RecoveryDeposit;
 }
 
+parameter_types! {
+	pub const MinRecoveryDelay: BlockNumber = 1 * DAYS;
+	pub const RecoveryProbationPeriod: BlockNumber = 7 * DAYS;
+}
+
+impl recovery_probation::Config for Runtime {
+	type Event = Event;
+	type Call = Call;
+	type ProbationPeriod = RecoveryProbationPeriod;
+	type MinDelayPeriod = MinRecoveryDelay;
+	type ProbationFilter = ProbationCalls;
+}
+
 parameter_types! {
 	pub const MinVestedTransfer: Balance = 1000 * CENTS;
 }
@@ -982,6 +1266,7 @@ pub enum ProxyType {
 	Staking,
 	IdentityJudgement,
 	CancelProxy,
+	Auction,
 }
 
 impl Default for ProxyType {
@@ -1016,7 +1301,7 @@ impl InstanceFilter<Call> for ProxyType {
 				Call::TechnicalMembership(..) |
 				Call::Treasury(..) |
 				Call::Bounties(..) |
-				Call::Tips(..) |
+				Call::TreasuryXcmPayouts(..) |
 				Call::Utility(..) |
 				Call::Identity(..) |
 				Call::Recovery(pallet_recovery::Call::as_recovered {..}) |
@@ -1025,6 +1310,7 @@ impl InstanceFilter<Call> for ProxyType {
 				Call::Recovery(pallet_recovery::Call::close_recovery {..}) |
 				Call::Recovery(pallet_recovery::Call::remove_recovery {..}) |
 				Call::Recovery(pallet_recovery::Call::cancel_recovered {..}) |
+				Call::RecoveryProbation(..) |
 				// Specifically omitting Recovery `create_recovery`, `initiate_recovery`
 				Call::Vesting(pallet_vesting::Call::vest {..}) |
 				Call::Vesting(pallet_vesting::Call::vest_other {..}) |
@@ -1037,6 +1323,8 @@ impl InstanceFilter<Call> for ProxyType {
 				// Specifically omitting Registrar `swap`
 				Call::Registrar(paras_registrar::Call::reserve {..}) |
 				Call::Slots(..) |
+				Call::Auctions(..) |
+				Call::Crowdloan(..) |
 				Call::BagsList(..)
 			),
 			ProxyType::Governance => matches!(
@@ -1045,7 +1333,8 @@ impl InstanceFilter<Call> for ProxyType {
 					Call::Council(..) | Call::TechnicalCommittee(..) |
 					Call::PhragmenElection(..) |
 					Call::Treasury(..) | Call::Bounties(..) |
-					Call::Tips(..) | Call::Utility(..)
+					Call::TreasuryXcmPayouts(..) |
+					Call::Utility(..)
 			),
 			ProxyType::Staking => {
 				matches!(c, Call::Staking(..) | Call::Session(..) | Call::Utility(..))
@@ -1054,9 +1343,15 @@ impl InstanceFilter<Call> for ProxyType {
 				c,
 				Call::Identity(pallet_identity::Call::provide_judgement { .. }) | Call::Utility(..)
 			),
-			ProxyType::CancelProxy => {
-				matches!(c, Call::Proxy(pallet_proxy::Call::reject_announcement { .. }))
-			},
+			ProxyType::CancelProxy => matches!(
+				c,
+				Call::Proxy(pallet_proxy::Call::reject_announcement { .. }) |
+					Call::RecoveryProbation(recovery_probation::Call::cancel_recovered { .. })
+			),
+			ProxyType::Auction => matches!(
+				c,
+				Call::Auctions(..) | Call::Crowdloan(..) | Call::Registrar(..) | Call::Slots(..)
+			),
 		}
 	}
 	fn is_superset(&self, o: &Self) -> bool {
@@ -1070,6 +1365,36 @@ impl InstanceFilter<Call> for ProxyType {
 	}
 }
 
+/// The calls a rescuer may dispatch as a recovered account on probation.
+///
+/// Proxies, multisigs and recoveries dispatch with a new origin which does not carry the
+/// probation filter, so they are rejected along with changes of the staking payee and
+/// controller. Calls wrapped by the utility pallet are checked one by one.
+pub struct ProbationCalls;
+impl Contains<Call> for ProbationCalls {
+	fn contains(c: &Call) -> bool {
+		match c {
+			Call::Proxy(..) |
+			Call::Multisig(..) |
+			Call::Recovery(..) |
+			Call::RecoveryProbation(recovery_probation::Call::as_recovered { .. }) |
+			Call::Staking(
+				pallet_staking::Call::bond { .. } |
+				pallet_staking::Call::set_payee { .. } |
+				pallet_staking::Call::set_controller { .. },
+			) => false,
+			Call::Utility(
+				pallet_utility::Call::batch { calls } | pallet_utility::Call::batch_all { calls },
+			) => calls.iter().all(Self::contains),
+			Call::Utility(
+				pallet_utility::Call::as_derivative { call, .. } |
+				pallet_utility::Call::dispatch_as { call, .. },
+			) => Self::contains(call),
+			_ => ProxyType::NonTransfer.filter(c),
+		}
+	}
+}
+
 impl pallet_proxy::Config for Runtime {
 	type Event = Event;
 	type Call = Call;
@@ -1095,20 +1420,34 @@ impl parachains_shared::Config for Runtime {}
 
 impl parachains_session_info::Config for Runtime {}
 
+/// Validators are rewarded with era points for backing candidates.
+#[cfg(not(feature = "parachain-liveness"))]
+type ParachainRewards = parachains_reward_points::RewardValidatorsWithEraPoints<Runtime>;
+
+/// Validators are rewarded with era points for backing candidates, and any participation in
+/// parachain consensus counts as liveness for `ImOnline`, which spares them the heartbeat.
+#[cfg(feature = "parachain-liveness")]
+type ParachainRewards = (
+	parachains_reward_points::RewardValidatorsWithEraPoints<Runtime>,
+	parachains_reward_points::NoteParticipationAsLiveness<Runtime, ImOnline>,
+);
+
 impl parachains_inclusion::Config for Runtime {
 	type Event = Event;
 	type DisputesHandler = ParasDisputes;
-	type RewardValidators = parachains_reward_points::RewardValidatorsWithEraPoints<Runtime>;
+	type RewardValidators = ParachainRewards;
 }
 
 parameter_types! {
-	pub const ParasUnsignedPriority: TransactionPriority = TransactionPriority::max_value();
+	/// The priority of unsigned transactions maintaining parachain consensus, such as PVF
+	/// pre-checking votes and dispute statements gossiped outside of the parachains inherent.
+	pub const ConsensusMaintenancePriority: TransactionPriority = TransactionPriority::max_value();
 }
 
 impl parachains_paras::Config for Runtime {
 	type Event = Event;
 	type WeightInfo = weights::runtime_parachains_paras::WeightInfo<Runtime>;
-	type UnsignedPriority = ParasUnsignedPriority;
+	type UnsignedPriority = ConsensusMaintenancePriority;
 	type NextSessionRotation = Babe;
 }
 
@@ -1149,12 +1488,20 @@ impl parachains_initializer::Config for Runtime {
 impl parachains_disputes::Config for Runtime {
 	type Event = Event;
 	type RewardValidators = ();
-	type PunishValidators = ();
+	type PunishValidators = parachains_disputes::DisableBackers<Runtime>;
+	type UnsignedPriority = ConsensusMaintenancePriority;
+	/// Two thirds of the validator operators can unfreeze the chain after a dispute, as can root.
+	type ForceOrigin = EnsureOneOf<
+		EnsureRoot<AccountId>,
+		pallet_collective::EnsureProportionAtLeast<AccountId, OperatorCollective, 2, 3>,
+	>;
 	type WeightInfo = weights::runtime_parachains_disputes::WeightInfo<Runtime>;
 }
 
 parameter_types! {
 	pub const ParaDeposit: Balance = 5 * UNITS;
+	// Enough for the name and website of a para, longer ones are better kept off chain.
+	pub const MaxParaMetadataLength: u32 = 64;
 }
 
 impl paras_registrar::Config for Runtime {
@@ -1165,9 +1512,10 @@ impl paras_registrar::Config for Runtime {
 		pallet_collective::EnsureProportionAtLeast<AccountId, TechnicalCollective, 2, 3>,
 	>;
 	type Currency = Balances;
-	type OnSwap = Slots;
+	type OnSwap = (Crowdloan, Slots);
 	type ParaDeposit = ParaDeposit;
 	type DataDepositPerByte = DataDepositPerByte;
+	type MaxMetadataLength = MaxParaMetadataLength;
 	type WeightInfo = weights::runtime_common_paras_registrar::WeightInfo<Runtime>;
 }
 
@@ -1186,6 +1534,58 @@ impl slots::Config for Runtime {
 	type WeightInfo = weights::runtime_common_slots::WeightInfo<Runtime>;
 }
 
+parameter_types! {
+	// Bids may end the auction at any point of its last 5 days.
+	pub const EndingPeriod: BlockNumber = 5 * DAYS;
+	// The winning bids are sampled every two minutes of the ending period.
+	pub const SampleLength: BlockNumber = 2 * MINUTES;
+}
+
+impl auctions::Config for Runtime {
+	type Event = Event;
+	type Leaser = Slots;
+	type Registrar = Registrar;
+	type EndingPeriod = EndingPeriod;
+	type SampleLength = SampleLength;
+	type Randomness = pallet_babe::RandomnessFromOneEpochAgo<Runtime>;
+	type InitiateOrigin = MoreThanHalfCouncil;
+	type WeightInfo = weights::runtime_common_auctions::WeightInfo<Runtime>;
+}
+
+parameter_types! {
+	pub const CrowdloanId: PalletId = PalletId(*b"py/cfund");
+	pub const SubmissionDeposit: Balance = 100 * UNITS;
+	pub const MinContribution: Balance = 5 * UNITS;
+	pub const RemoveKeysLimit: u32 = 1000;
+	// Allow 32 bytes for an additional memo to a crowdloan.
+	pub const MaxMemoLength: u8 = 32;
+}
+
+impl crowdloan::Config for Runtime {
+	type Event = Event;
+	type PalletId = CrowdloanId;
+	type SubmissionDeposit = SubmissionDeposit;
+	type MinContribution = MinContribution;
+	type RemoveKeysLimit = RemoveKeysLimit;
+	type Registrar = Registrar;
+	type Auctioneer = Auctions;
+	type MaxMemoLength = MaxMemoLength;
+	type WeightInfo = weights::runtime_common_crowdloan::WeightInfo<Runtime>;
+}
+
+parameter_types! {
+	pub const MaxTreasuryXcmPayouts: u32 = 100;
+}
+
+impl treasury_xcm_payouts::Config for Runtime {
+	type Event = Event;
+	type ApproveOrigin = ApproveOrigin;
+	type Registrar = Registrar;
+	type XcmSender = xcm_config::XcmRouter;
+	type MaxPayouts = MaxTreasuryXcmPayouts;
+	type WeightInfo = weights::runtime_common_treasury_xcm_payouts::WeightInfo<Runtime>;
+}
+
 impl paras_sudo_wrapper::Config for Runtime {}
 
 impl pallet_sudo::Config for Runtime {
@@ -1208,7 +1608,9 @@ construct_runtime! {
 		Timestamp: pallet_timestamp::{Pallet, Call, Storage, Inherent} = 2,
 		Indices: pallet_indices::{Pallet, Call, Storage, Config<T>, Event<T>} = 3,
 		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>} = 4,
+		BatchTransfer: batch_transfer::{Pallet, Call, Event<T>} = 26,
 		TransactionPayment: pallet_transaction_payment::{Pallet, Storage} = 33,
+		FeeMultiplier: fee_multiplier::{Pallet, Call, Storage, Event<T>} = 23,
 
 		// Consensus support.
 		// Authorship must be before session in order to note author in the correct session and era
@@ -1227,7 +1629,11 @@ construct_runtime! {
 		Council: pallet_collective::<Instance1>::{Pallet, Call, Storage, Origin<T>, Event<T>, Config<T>} = 14,
 		TechnicalCommittee: pallet_collective::<Instance2>::{Pallet, Call, Storage, Origin<T>, Event<T>, Config<T>} = 15,
 		PhragmenElection: pallet_elections_phragmen::{Pallet, Call, Storage, Event<T>, Config<T>} = 16,
+		// Must come after `PhragmenElection`.
+		CandidacyRenewal: candidacy_renewal::{Pallet, Call, Storage, Event<T>} = 19,
 		TechnicalMembership: pallet_membership::<Instance1>::{Pallet, Call, Storage, Event<T>, Config<T>} = 17,
+		ValidatorOperators: validator_operators::{Pallet, Call, Storage, Event<T>} = 20,
+		OperatorCommittee: pallet_collective::<Instance3>::{Pallet, Call, Storage, Origin<T>, Event<T>} = 64,
 		Treasury: pallet_treasury::{Pallet, Call, Storage, Config, Event<T>} = 18,
 
 		// Utility module.
@@ -1238,6 +1644,7 @@ construct_runtime! {
 
 		// Social recovery module.
 		Recovery: pallet_recovery::{Pallet, Call, Storage, Event<T>} = 27,
+		RecoveryProbation: recovery_probation::{Pallet, Call, Storage, Event<T>} = 22,
 
 		// Vesting. Usable initially, but removed once all vesting is finished.
 		Vesting: pallet_vesting::{Pallet, Call, Storage, Event<T>, Config<T>} = 28,
@@ -1256,9 +1663,9 @@ construct_runtime! {
 
 		// Bounties module.
 		Bounties: pallet_bounties::{Pallet, Call, Storage, Event<T>} = 35,
+		BountyExpiry: bounty_expiry::{Pallet, Call, Storage, Event<T>} = 21,
 
-		// Tips module.
-		Tips: pallet_tips::{Pallet, Call, Storage, Event<T>} = 36,
+		// Index 36 was used by the tips pallet, retired in favour of treasury spends.
 
 		// Election pallet. Only works with staking, but placed here to maintain indices.
 		ElectionProviderMultiPhase: pallet_election_provider_multi_phase::{Pallet, Call, Storage, Event<T>, ValidateUnsigned} = 37,
@@ -1266,6 +1673,38 @@ construct_runtime! {
 		// Provides a semi-sorted list of nominators for staking.
 		BagsList: pallet_bags_list::{Pallet, Call, Storage, Event<T>} = 39,
 
+		// Releases all account indices and refunds their deposits.
+		IndicesWindDown: indices_wind_down::{Pallet, Call, Storage, Event<T>} = 40,
+
+		// Restricts the chain to the calls in `SafeModeCalls` as an incident response.
+		SafeMode: safe_mode::{Pallet, Call, Storage, Event<T>} = 41,
+
+		// Grows the validator count of staking on a schedule.
+		ValidatorCountGrowth: validator_count_growth::{Pallet, Call, Storage, Event<T>} = 42,
+
+		// Index 43 was used by the EVM payee pallet, retired as the runtimes have no EVM.
+
+		// Enacts runtime upgrades a fixed delay after they are approved.
+		UpgradeEnactment: upgrade_enactment::{Pallet, Call, Storage, Event<T>} = 44,
+
+		// Counts the equivocation reports of BABE and GRANDPA and names their reporters.
+		EquivocationReports: equivocation_reports::{Pallet, Storage, Event<T>} = 45,
+
+		// Counts the blocks authored by every validator per session and era.
+		AuthorshipStats: authorship_stats::{Pallet, Storage} = 46,
+
+		// Records the weight of the mandatory work of initializing blocks.
+		MandatoryWeight: mandatory_weight::{Pallet, Storage, Event<T>} = 47,
+
+		// Dispatches calls signed by users and submitted by relayers paying their fees.
+		MetaTx: meta_tx::{Pallet, Call, Storage, Event<T>} = 48,
+
+		// Pays approved treasury spends to accounts on parachains.
+		TreasuryXcmPayouts: treasury_xcm_payouts::{Pallet, Call, Storage, Event<T>} = 49,
+
+		// Prices of SEL and other assets.
+		PriceOracle: price_oracle::{Pallet, Call, Storage, Event<T>} = 38,
+
 		// Parachains pallets. Start indices at 50 to leave room.
 		ParachainsOrigin: parachains_origin::{Pallet, Origin} = 50,
 		Configuration: parachains_configuration::{Pallet, Call, Storage, Config<T>} = 51,
@@ -1273,18 +1712,20 @@ construct_runtime! {
 		ParaInclusion: parachains_inclusion::{Pallet, Call, Storage, Event<T>} = 53,
 		ParaInherent: parachains_paras_inherent::{Pallet, Call, Storage, Inherent} = 54,
 		ParaScheduler: parachains_scheduler::{Pallet, Storage} = 55,
-		Paras: parachains_paras::{Pallet, Call, Storage, Event, Config} = 56,
+		Paras: parachains_paras::{Pallet, Call, Storage, Event, Config, ValidateUnsigned} = 56,
 		Initializer: parachains_initializer::{Pallet, Call, Storage} = 57,
 		Dmp: parachains_dmp::{Pallet, Call, Storage} = 58,
 		Ump: parachains_ump::{Pallet, Call, Storage, Event} = 59,
 		Hrmp: parachains_hrmp::{Pallet, Call, Storage, Event<T>, Config} = 60,
 		ParaSessionInfo: parachains_session_info::{Pallet, Storage} = 61,
-		ParasDisputes: parachains_disputes::{Pallet, Call, Storage, Event<T>} = 62,
+		ParasDisputes: parachains_disputes::{Pallet, Call, Storage, Event<T>, ValidateUnsigned} = 62,
 		ParasSudoWrapper: paras_sudo_wrapper::{Pallet, Call} = 63,
 
 		// Parachain Onboarding Pallets. Start indices at 70 to leave room.
 		Registrar: paras_registrar::{Pallet, Call, Storage, Event<T>} = 70,
 		Slots: slots::{Pallet, Call, Storage, Event<T>} = 71,
+		Auctions: auctions::{Pallet, Call, Storage, Event<T>} = 72,
+		Crowdloan: crowdloan::{Pallet, Call, Storage, Event<T>} = 73,
 
 		// Pallet for sending XCM.
 		XcmPallet: pallet_xcm::{Pallet, Call, Storage, Event<T>, Origin, Config} = 99,
@@ -1323,12 +1764,37 @@ pub type Executive = frame_executive::Executive<
 	Block,
 	frame_system::ChainContext<Runtime>,
 	Runtime,
-	AllPalletsWithSystem,
-	(),
+	mandatory_weight::MeasureOnInitialize<Runtime, AllPalletsWithSystem>,
+	// One-shot migrations, to be removed after the next release.
+	(
+		tips_retirement::RetireTips<Runtime, TipFindersFee>,
+		multisig_calls::RefundStoredCalls<Runtime, Balances>,
+		slashing_pruning::PruneSlashingState<Runtime, Balance, BondingDuration>,
+	),
 >;
 /// The payload being signed in the transactions.
 pub type SignedPayload = generic::SignedPayload<Call, SignedExtra>;
 
+/// The lane of the transaction pool `xt` is prioritised in.
+fn transaction_lane(xt: &UncheckedExtrinsic) -> Lane {
+	match &xt.function {
+		Call::ImOnline(pallet_im_online::Call::heartbeat { .. }) |
+		Call::Paras(parachains_paras::Call::include_pvf_check_statement { .. }) => Lane::ParachainData,
+		Call::Babe(
+			pallet_babe::Call::report_equivocation { .. } |
+			pallet_babe::Call::report_equivocation_unsigned { .. },
+		) |
+		Call::Grandpa(
+			pallet_grandpa::Call::report_equivocation { .. } |
+			pallet_grandpa::Call::report_equivocation_unsigned { .. },
+		) => Lane::Dispute,
+		Call::ElectionProviderMultiPhase(
+			EPMCall::submit { .. } | EPMCall::submit_unsigned { .. },
+		) => Lane::ElectionSolution,
+		_ => Lane::Normal,
+	}
+}
+
 #[cfg(feature = "runtime-benchmarks")]
 #[macro_use]
 extern crate frame_benchmarking;
@@ -1339,7 +1805,12 @@ mod benches {
 		// Selendra
 		// NOTE: Make sure to prefix these with `runtime_common::` so
 		// the that path resolves correctly in the generated file.
+		[runtime_common::auctions, Auctions]
+		[runtime_common::crowdloan, Crowdloan]
+		[runtime_common::batch_transfer, BatchTransfer]
+		[runtime_common::meta_tx, MetaTx]
 		[runtime_common::slots, Slots]
+		[runtime_common::treasury_xcm_payouts, TreasuryXcmPayouts]
 		[runtime_common::paras_registrar, Registrar]
 		[runtime_parachains::configuration, Configuration]
 		[runtime_parachains::hrmp, Hrmp]
@@ -1371,7 +1842,6 @@ mod benches {
 		[pallet_staking, Staking]
 		[frame_system, SystemBench::<Runtime>]
 		[pallet_timestamp, Timestamp]
-		[pallet_tips, Tips]
 		[pallet_treasury, Treasury]
 		[pallet_utility, Utility]
 		[pallet_vesting, Vesting]
@@ -1427,7 +1897,9 @@ sp_api::impl_runtime_apis! {
 			tx: <Block as BlockT>::Extrinsic,
 			block_hash: <Block as BlockT>::Hash,
 		) -> TransactionValidity {
-			Executive::validate_transaction(source, tx, block_hash)
+			let lane = transaction_lane(&tx);
+			let validity = Executive::validate_transaction(source, tx, block_hash);
+			runtime_common::priority::in_lane(validity, lane)
 		}
 	}
 
@@ -1693,6 +2165,160 @@ sp_api::impl_runtime_apis! {
 		}
 	}
 
+	impl primitives::fees::FeeMultiplierApi<Block, Balance, BlockNumber> for Runtime {
+		fn next_fee_multiplier() -> primitives::fees::Multiplier {
+			TransactionPayment::next_fee_multiplier()
+		}
+		fn target_block_fullness() -> primitives::fees::Perquintill {
+			FeeMultiplier::target_block_fullness()
+		}
+		fn projected_fees() -> Vec<(primitives::fees::StandardTransaction, Balance)> {
+			fee_multiplier::projected_fees::<Runtime>()
+		}
+		fn multiplier_history() -> Vec<(BlockNumber, primitives::fees::Multiplier)> {
+			FeeMultiplier::multiplier_history()
+		}
+		fn fee_history(count: u32) -> Vec<primitives::fees::BlockFees<BlockNumber, Balance>> {
+			FeeMultiplier::fee_history(count)
+		}
+	}
+
+	impl primitives::equivocation::EquivocationReportsApi<Block> for Runtime {
+		fn report_counts() -> Vec<(primitives::equivocation::Kind, u32)> {
+			EquivocationReports::report_counts()
+		}
+	}
+
+	impl primitives::disputes::DisputeStatementSubmissionApi<Block> for Runtime {
+		fn submit_dispute_statement_set(set: primitives::v1::DisputeStatementSet) {
+			parachains_runtime_api_impl::submit_dispute_statement_set::<Runtime>(set)
+		}
+	}
+
+	impl primitives::executor_params::ExecutorParamsApi<Block> for Runtime {
+		fn session_executor_params(
+			session_index: SessionIndex,
+		) -> Option<primitives::v1::ExecutorParams> {
+			parachains_runtime_api_impl::session_executor_params::<Runtime>(session_index)
+		}
+	}
+
+	impl primitives::relay_state_proof::RelayStateProofApi<Block> for Runtime {
+		fn relay_state_proof_keys(para_id: ParaId) -> Vec<Vec<u8>> {
+			parachains_runtime_api_impl::relay_state_proof_keys::<Runtime>(para_id)
+		}
+	}
+
+	impl primitives::para_usage::ParaUsageApi<Block> for Runtime {
+		fn session_usage() -> Vec<(ParaId, primitives::para_usage::ParaUsage)> {
+			ParaInclusion::session_usage()
+		}
+	}
+
+	impl primitives::address::AddressFormatApi<Block> for Runtime {
+		fn ss58_prefix() -> u16 {
+			SS58Prefix::get()
+		}
+		fn legacy_ss58_prefixes() -> Vec<u16> {
+			vec![primitives::address::LEGACY_SS58_PREFIX]
+		}
+	}
+
+	impl primitives::build_info::BuildInfoApi<Block> for Runtime {
+		fn build_info() -> primitives::build_info::BuildInfo {
+			primitives::build_info::BuildInfo {
+				git_commit: option_env!("SELENDRA_RUNTIME_GIT_COMMIT").unwrap_or("unknown").into(),
+				profile: env!("SELENDRA_RUNTIME_PROFILE").into(),
+				builder: option_env!("SELENDRA_RUNTIME_BUILDER").map(Into::into),
+			}
+		}
+	}
+
+	impl primitives::events::EventsSchemaApi<Block> for Runtime {
+		fn events_schema() -> primitives::events::EventsSchema {
+			runtime_common::events_schema::events_schema(Runtime::metadata(), VERSION.spec_version)
+		}
+	}
+
+	impl primitives::events::BlockEventsApi<Block> for Runtime {
+		fn block_events(events: Vec<u8>) -> Option<Vec<primitives::events::BlockEvent>> {
+			runtime_common::events_schema::block_events::<Runtime>(&events)
+		}
+	}
+
+	impl runtime_common::democracy::DemocracyApi<Block, AccountId, Balance, BlockNumber> for Runtime {
+		fn voting_power(
+			who: AccountId,
+		) -> runtime_common::democracy::VotingPower<AccountId, Balance, BlockNumber> {
+			runtime_common::democracy::voting_power::<Runtime>(who)
+		}
+	}
+
+	impl runtime_common::governance::GovernanceApi<Block, AccountId, Balance, BlockNumber, Hash> for Runtime {
+		fn governance_activity(
+		) -> runtime_common::governance::GovernanceActivity<AccountId, Balance, BlockNumber, Hash> {
+			runtime_common::governance::governance_activity::<Runtime, CouncilCollective>()
+		}
+	}
+
+	impl runtime_common::reserves::ReservesApi<Block, AccountId, [u8; 8], Balance> for Runtime {
+		fn reserves(who: AccountId) -> runtime_common::reserves::AccountReserves<[u8; 8], Balance> {
+			runtime_common::reserves::reserves::<Runtime>(&who)
+		}
+	}
+
+	impl authorship_stats::AuthorshipStatsApi<Block, AccountId> for Runtime {
+		fn session_authored_blocks(session: SessionIndex) -> Vec<(AccountId, u32)> {
+			AuthorshipStats::session_authored_blocks(session)
+		}
+
+		fn era_authored_blocks(era: sp_staking::EraIndex) -> Vec<(AccountId, u32)> {
+			AuthorshipStats::era_authored_blocks(era)
+		}
+	}
+
+	impl mandatory_weight::MandatoryWeightApi<Block, BlockNumber> for Runtime {
+		fn mandatory_weight_history() -> Vec<mandatory_weight::MandatoryWeight<BlockNumber>> {
+			MandatoryWeight::history()
+		}
+	}
+
+	impl slash_simulation::SlashSimulationApi<Block, AccountId, Balance> for Runtime {
+		fn simulate_offences(
+			offences: Vec<(AccountId, Perbill)>,
+		) -> Vec<slash_simulation::SimulatedSlash<AccountId, Balance>> {
+			slash_simulation::simulate_offences::<Runtime, Staking>(offences)
+		}
+	}
+
+	impl runtime_common::staking::StakingApi<Block, AccountId, Balance> for Runtime {
+		fn active_era() -> Option<pallet_staking::EraIndex> {
+			runtime_common::staking::active_era::<Runtime>()
+		}
+
+		fn validator_info(
+			stash: AccountId,
+		) -> Option<runtime_common::staking::ValidatorInfo<AccountId, Balance>> {
+			runtime_common::staking::validator_info::<Runtime>(stash)
+		}
+
+		fn pending_rewards(
+			stash: AccountId,
+		) -> Vec<runtime_common::staking::PendingReward<AccountId, Balance>> {
+			runtime_common::staking::pending_rewards::<Runtime>(stash)
+		}
+	}
+
+	impl paras_registrar::ParaMetadataApi<Block> for Runtime {
+		fn para_metadata(para: ParaId) -> Option<paras_registrar::ParaMetadata<Vec<u8>>> {
+			Registrar::para_metadata(para)
+		}
+
+		fn all_para_metadata() -> Vec<(ParaId, paras_registrar::ParaMetadata<Vec<u8>>)> {
+			Registrar::all_para_metadata()
+		}
+	}
+
 	#[cfg(feature = "try-runtime")]
 	impl frame_try_runtime::TryRuntime<Block> for Runtime {
 		fn on_runtime_upgrade() -> (Weight, Weight) {
@@ -22,7 +22,7 @@ use keyring::Sr25519Keyring::Charlie;
 use pallet_transaction_payment::Multiplier;
 use parity_scale_codec::Encode;
 use separator::Separatable;
-use sp_runtime::FixedPointNumber;
+use sp_runtime::{assert_eq_error_rate, FixedPointNumber};
 
 #[test]
 fn remove_keys_weight_is_sensible() {
@@ -110,6 +110,46 @@ fn transfer_cost_min_multiplier() {
 	test_with_multiplier(Multiplier::saturating_from_rational(1, 1_000_000_000u128));
 }
 
+#[test]
+fn transfer_weight_matches_fee_calibration() {
+	let call = pallet_balances::Call::<Runtime>::transfer_keep_alive {
+		dest: Charlie.to_account_id().into(),
+		value: Default::default(),
+	};
+	let weight = call.get_dispatch_info().weight + runtime_common::ExtrinsicBaseWeight::get();
+	// If this fails, the benchmarked weight of a transfer changed. Update `TRANSFER_WEIGHT`
+	// so that transfers keep costing `TRANSFER_FEE`.
+	assert_eq_error_rate!(weight, TRANSFER_WEIGHT, TRANSFER_WEIGHT / 20);
+}
+
+#[test]
+fn fees_of_representative_extrinsics() {
+	let fee_of = |call: Call| {
+		let info = call.get_dispatch_info();
+		let len = call.encoded_size() as u32;
+		sp_io::TestExternalities::new_empty()
+			.execute_with(|| TransactionPayment::compute_fee(len, &info, 0))
+	};
+
+	let transfer = fee_of(Call::Balances(pallet_balances::Call::transfer_keep_alive {
+		dest: Charlie.to_account_id().into(),
+		value: 1 * UNITS,
+	}));
+	let bond = fee_of(Call::Staking(pallet_staking::Call::bond {
+		controller: Charlie.to_account_id().into(),
+		value: 1 * UNITS,
+		payee: pallet_staking::RewardDestination::Staked,
+	}));
+	let nominate = fee_of(Call::Staking(pallet_staking::Call::nominate {
+		targets: vec![Charlie.to_account_id().into()],
+	}));
+
+	// Fees at a multiplier of one, including the length fee.
+	assert_eq_error_rate!(transfer, 102 * CENTS / 100, transfer / 20);
+	assert_eq_error_rate!(bond, 263 * CENTS / 100, bond / 20);
+	assert_eq_error_rate!(nominate, 428 * CENTS / 100, nominate / 20);
+}
+
 #[test]
 fn nominator_limit() {
 	use pallet_election_provider_multi_phase::WeightInfo;
@@ -35,12 +35,16 @@ pub mod pallet_scheduler;
 pub mod pallet_session;
 pub mod pallet_staking;
 pub mod pallet_timestamp;
-pub mod pallet_tips;
 pub mod pallet_treasury;
 pub mod pallet_utility;
 pub mod pallet_vesting;
+pub mod runtime_common_auctions;
+pub mod runtime_common_batch_transfer;
+pub mod runtime_common_crowdloan;
+pub mod runtime_common_meta_tx;
 pub mod runtime_common_paras_registrar;
 pub mod runtime_common_slots;
+pub mod runtime_common_treasury_xcm_payouts;
 pub mod runtime_parachains_configuration;
 pub mod runtime_parachains_disputes;
 pub mod runtime_parachains_hrmp;
@@ -1,82 +0,0 @@
-
-//! Autogenerated weights for `pallet_tips`
-//!
-//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 4.0.0-dev
-//! DATE: 2022-05-27, STEPS: `50`, REPEAT: 20, LOW RANGE: `[]`, HIGH RANGE: `[]`
-//! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("selendra-dev"), DB CACHE: 1024
-
-// Executed Command:
-// target/release/selendra
-// benchmark
-// --chain=selendra-dev
-// --steps=50
-// --repeat=20
-// --pallet=pallet_tips
-// --extrinsic=*
-// --execution=wasm
-// --wasm-execution=compiled
-// --heap-pages=4096
-// --output=./runtime/selendra/src/weights/pallet_tips.rs
-
-#![cfg_attr(rustfmt, rustfmt_skip)]
-#![allow(unused_parens)]
-#![allow(unused_imports)]
-
-use frame_support::{traits::Get, weights::Weight};
-use sp_std::marker::PhantomData;
-
-/// Weight functions for `pallet_tips`.
-pub struct WeightInfo<T>(PhantomData<T>);
-impl<T: frame_system::Config> pallet_tips::WeightInfo for WeightInfo<T> {
-	// Storage: Tips Reasons (r:1 w:1)
-	// Storage: Tips Tips (r:1 w:1)
-	fn report_awesome(r: u32, ) -> Weight {
-		(46_780_000 as Weight)
-			// Standard Error: 0
-			.saturating_add((5_000 as Weight).saturating_mul(r as Weight))
-			.saturating_add(T::DbWeight::get().reads(2 as Weight))
-			.saturating_add(T::DbWeight::get().writes(2 as Weight))
-	}
-	// Storage: Tips Tips (r:1 w:1)
-	// Storage: Tips Reasons (r:0 w:1)
-	fn retract_tip() -> Weight {
-		(79_028_000 as Weight)
-			.saturating_add(T::DbWeight::get().reads(1 as Weight))
-			.saturating_add(T::DbWeight::get().writes(2 as Weight))
-	}
-	// Storage: PhragmenElection Members (r:1 w:0)
-	// Storage: Tips Reasons (r:1 w:1)
-	// Storage: Tips Tips (r:0 w:1)
-	fn tip_new(r: u32, _t: u32, ) -> Weight {
-		(84_393_000 as Weight)
-			// Standard Error: 0
-			.saturating_add((1_000 as Weight).saturating_mul(r as Weight))
-			.saturating_add(T::DbWeight::get().reads(2 as Weight))
-			.saturating_add(T::DbWeight::get().writes(2 as Weight))
-	}
-	// Storage: PhragmenElection Members (r:1 w:0)
-	// Storage: Tips Tips (r:1 w:1)
-	fn tip(t: u32, ) -> Weight {
-		(17_548_000 as Weight)
-			// Standard Error: 129_000
-			.saturating_add((859_000 as Weight).saturating_mul(t as Weight))
-			.saturating_add(T::DbWeight::get().reads(2 as Weight))
-			.saturating_add(T::DbWeight::get().writes(1 as Weight))
-	}
-	// Storage: Tips Tips (r:1 w:1)
-	// Storage: PhragmenElection Members (r:1 w:0)
-	// Storage: System Account (r:1 w:1)
-	// Storage: Tips Reasons (r:0 w:1)
-	fn close_tip(_t: u32, ) -> Weight {
-		(86_249_000 as Weight)
-			.saturating_add(T::DbWeight::get().reads(3 as Weight))
-			.saturating_add(T::DbWeight::get().writes(3 as Weight))
-	}
-	// Storage: Tips Tips (r:1 w:1)
-	// Storage: Tips Reasons (r:0 w:1)
-	fn slash_tip(_t: u32, ) -> Weight {
-		(28_146_000 as Weight)
-			.saturating_add(T::DbWeight::get().reads(1 as Weight))
-			.saturating_add(T::DbWeight::get().writes(2 as Weight))
-	}
-}
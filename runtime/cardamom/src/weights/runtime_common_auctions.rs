@@ -0,0 +1,38 @@
+//! Weights for `runtime_common::auctions`
+//!
+//! THESE WEIGHTS WERE NOT BENCHMARKED: they are conservative estimates, with the database
+//! accesses counted from the code. Replace this file with the output of:
+//!
+//! target/release/selendra benchmark-pallet --chain=cardamom-dev --pallet=runtime_common::auctions
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::Weight};
+use sp_std::marker::PhantomData;
+
+/// Weight functions for `runtime_common::auctions`.
+pub struct WeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Config> runtime_common::auctions::WeightInfo for WeightInfo<T> {
+	fn new_auction() -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn bid() -> Weight {
+		(90_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(8 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	fn on_initialize() -> Weight {
+		(18_000_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3688 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3683 as Weight))
+	}
+	fn cancel_auction() -> Weight {
+		(3_500_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(73 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3673 as Weight))
+	}
+}
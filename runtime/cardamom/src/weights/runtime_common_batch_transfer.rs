@@ -0,0 +1,26 @@
+//! Weights for `runtime_common::batch_transfer`
+//!
+//! THESE WEIGHTS WERE NOT BENCHMARKED: they are conservative estimates, with the database
+//! accesses counted from the code. Replace this file with the output of:
+//!
+//! target/release/selendra benchmark-pallet --chain=cardamom-dev --pallet=runtime_common::batch_transfer
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::Weight};
+use sp_std::marker::PhantomData;
+
+/// Weight functions for `runtime_common::batch_transfer`.
+pub struct WeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Config> runtime_common::batch_transfer::WeightInfo for WeightInfo<T> {
+	fn transfer_batch(n: u32, ) -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add((75_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(n as Weight)))
+	}
+}
@@ -0,0 +1,23 @@
+//! Weights for `runtime_common::meta_tx`
+//!
+//! THESE WEIGHTS WERE NOT BENCHMARKED: they are conservative estimates, with the database
+//! accesses counted from the code. Replace this file with the output of:
+//!
+//! target/release/selendra benchmark-pallet --chain=cardamom-dev --pallet=runtime_common::meta_tx
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::Weight};
+use sp_std::marker::PhantomData;
+
+/// Weight functions for `runtime_common::meta_tx`.
+pub struct WeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Config> runtime_common::meta_tx::WeightInfo for WeightInfo<T> {
+	fn relay() -> Weight {
+		(80_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+}
@@ -0,0 +1,36 @@
+//! Weights for `runtime_common::treasury_xcm_payouts`
+//!
+//! THESE WEIGHTS WERE NOT BENCHMARKED: they are conservative estimates, with the database
+//! accesses counted from the code. Replace this file with the output of:
+//!
+//! target/release/selendra benchmark-pallet --chain=cardamom-dev --pallet=runtime_common::treasury_xcm_payouts
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::Weight};
+use sp_std::marker::PhantomData;
+
+/// Weight functions for `runtime_common::treasury_xcm_payouts`.
+pub struct WeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Config> runtime_common::treasury_xcm_payouts::WeightInfo for WeightInfo<T> {
+	fn approve_payout() -> Weight {
+		(40_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	fn remove_payout() -> Weight {
+		(35_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn spend_funds(p: u32, ) -> Weight {
+		(10_000_000 as Weight)
+			.saturating_add((90_000_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().reads((6 as Weight).saturating_mul(p as Weight)))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes((4 as Weight).saturating_mul(p as Weight)))
+	}
+}
@@ -33,4 +33,12 @@ impl<T: frame_system::Config> runtime_parachains::disputes::WeightInfo for Weigh
 		(2_068_000 as Weight)
 			.saturating_add(T::DbWeight::get().writes(1 as Weight))
 	}
+	// Not benchmarked: estimated from the per-statement cost of disputes in the parachains
+	// inherent, plus the storage of a single dispute.
+	fn include_dispute_statement_set(v: u32, ) -> Weight {
+		(31_704_000 as Weight)
+			.saturating_add((79_121_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add(T::DbWeight::get().reads(7 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
 }
@@ -0,0 +1,384 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Pallet counting the blocks authored by every validator.
+//!
+//! Every block increments a counter keyed by the session and the index of its author in the
+//! validator set of the session, so the cost per block is a single write. The validator set of
+//! every session is remembered alongside the counters. At the end of an era, the counters of its
+//! sessions are summed up per validator account into the era totals, which mirror the era reward
+//! points of staking without any slashing involved, and the counters are reset. Era totals are
+//! kept for `HistoryDepth` eras.
+//!
+//! All of this lives in storage, so the numbers can be verified with storage proofs and queried
+//! through [`AuthorshipStatsApi`] without an indexer.
+
+use frame_support::{
+	pallet_prelude::*,
+	traits::{FindAuthor, ValidatorSet},
+};
+use frame_system::pallet_prelude::*;
+pub use pallet::*;
+use pallet_staking::EraIndex;
+use parity_scale_codec::Codec;
+use sp_staking::SessionIndex;
+use sp_std::prelude::*;
+
+sp_api::decl_runtime_apis! {
+	/// The API for querying the number of blocks authored by validators.
+	pub trait AuthorshipStatsApi<AccountId> where AccountId: Codec {
+		/// The number of blocks authored by every validator in a session of the current era.
+		fn session_authored_blocks(session: SessionIndex) -> Vec<(AccountId, u32)>;
+
+		/// The number of blocks authored by every validator in one of the last `HistoryDepth`
+		/// eras.
+		fn era_authored_blocks(era: EraIndex) -> Vec<(AccountId, u32)>;
+	}
+}
+
+/// The era staking is in.
+pub trait ActiveEraProvider {
+	/// The index of the active era, if any.
+	fn active_era() -> Option<EraIndex>;
+}
+
+impl<T: pallet_staking::Config> ActiveEraProvider for pallet_staking::Pallet<T> {
+	fn active_era() -> Option<EraIndex> {
+		pallet_staking::ActiveEra::<T>::get().map(|era| era.index)
+	}
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// Finds the index of the author of a block in the validator set.
+		type FindAuthor: FindAuthor<u32>;
+
+		/// The validator set the author index refers to.
+		type ValidatorSet: ValidatorSet<Self::AccountId, ValidatorId = Self::AccountId>;
+
+		/// The staking state eras are taken from.
+		type Staking: ActiveEraProvider;
+
+		/// The number of eras the totals are kept for.
+		#[pallet::constant]
+		type HistoryDepth: Get<EraIndex>;
+	}
+
+	/// The number of blocks authored in the sessions of the current era, by session and by the
+	/// index of the author in the validator set of the session.
+	#[pallet::storage]
+	pub type SessionAuthoredBlocks<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, SessionIndex, Twox64Concat, u32, u32, ValueQuery>;
+
+	/// The validator set of each of the sessions of the current era.
+	#[pallet::storage]
+	pub type SessionValidators<T: Config> =
+		StorageMap<_, Twox64Concat, SessionIndex, Vec<T::AccountId>>;
+
+	/// The era the sessions being counted belong to.
+	#[pallet::storage]
+	pub type CountedEra<T: Config> = StorageValue<_, EraIndex, OptionQuery>;
+
+	/// The number of blocks authored by every validator in the last `HistoryDepth` eras.
+	#[pallet::storage]
+	pub type ErasAuthoredBlocks<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, EraIndex, Twox64Concat, T::AccountId, u32, ValueQuery>;
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(_: BlockNumberFor<T>) -> Weight {
+			let session = T::ValidatorSet::session_index();
+			let mut weight = T::DbWeight::get().reads(3);
+
+			if let Some(era) = T::Staking::active_era() {
+				let counted = CountedEra::<T>::get();
+				if counted != Some(era) {
+					if let Some(counted) = counted {
+						weight = weight.saturating_add(Self::end_era(counted, session));
+					}
+					CountedEra::<T>::put(era);
+					weight = weight.saturating_add(T::DbWeight::get().writes(1));
+				}
+			}
+
+			if !SessionValidators::<T>::contains_key(session) {
+				SessionValidators::<T>::insert(session, T::ValidatorSet::validators());
+				weight = weight.saturating_add(T::DbWeight::get().reads_writes(1, 1));
+			}
+
+			let digest = frame_system::Pallet::<T>::digest();
+			let pre_runtime_digests = digest.logs.iter().filter_map(|d| d.as_pre_runtime());
+			if let Some(author) = T::FindAuthor::find_author(pre_runtime_digests) {
+				SessionAuthoredBlocks::<T>::mutate(session, author, |count| {
+					*count = count.saturating_add(1)
+				});
+				weight = weight.saturating_add(T::DbWeight::get().reads_writes(1, 1));
+			}
+
+			weight
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The number of blocks authored by every validator in `session`, as long as it belongs to the
+	/// current era.
+	pub fn session_authored_blocks(session: SessionIndex) -> Vec<(T::AccountId, u32)> {
+		let validators = SessionValidators::<T>::get(session).unwrap_or_default();
+		SessionAuthoredBlocks::<T>::iter_prefix(session)
+			.filter_map(|(index, count)| {
+				validators.get(index as usize).map(|who| (who.clone(), count))
+			})
+			.collect()
+	}
+
+	/// The number of blocks authored by every validator in `era`, as long as it is one of the last
+	/// `HistoryDepth` eras.
+	pub fn era_authored_blocks(era: EraIndex) -> Vec<(T::AccountId, u32)> {
+		ErasAuthoredBlocks::<T>::iter_prefix(era).collect()
+	}
+
+	/// Add the counters of the sessions before `session` to the totals of `era`, reset them and
+	/// drop the totals which are too old.
+	fn end_era(era: EraIndex, session: SessionIndex) -> Weight {
+		let ended: Vec<_> = SessionValidators::<T>::iter_keys().filter(|s| *s < session).collect();
+
+		let mut counters = 0u64;
+		for ended_session in &ended {
+			let validators = SessionValidators::<T>::take(ended_session).unwrap_or_default();
+			for (index, count) in SessionAuthoredBlocks::<T>::drain_prefix(ended_session) {
+				counters = counters.saturating_add(1);
+				if let Some(who) = validators.get(index as usize) {
+					ErasAuthoredBlocks::<T>::mutate(era, who, |total| {
+						*total = total.saturating_add(count)
+					});
+				}
+			}
+		}
+
+		if let Some(expired) = era.checked_sub(T::HistoryDepth::get()) {
+			let _ = ErasAuthoredBlocks::<T>::remove_prefix(expired, None);
+		}
+
+		let sessions = ended.len() as u64;
+		T::DbWeight::get().reads_writes(
+			sessions.saturating_add(counters.saturating_mul(2)),
+			sessions.saturating_add(counters.saturating_mul(2)).saturating_add(1),
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use crate::authorship_stats;
+	use frame_support::{parameter_types, traits::OnInitialize};
+	use sp_core::H256;
+	use sp_runtime::{
+		testing::Header,
+		traits::{BlakeTwo256, IdentityLookup},
+		ConsensusEngineId,
+	};
+
+	type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+	type Block = frame_system::mocking::MockBlock<Test>;
+
+	frame_support::construct_runtime!(
+		pub enum Test where
+			Block = Block,
+			NodeBlock = Block,
+			UncheckedExtrinsic = UncheckedExtrinsic,
+		{
+			System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+			AuthorshipStats: authorship_stats::{Pallet, Storage},
+		}
+	);
+
+	parameter_types! {
+		pub const BlockHashCount: u32 = 250;
+	}
+
+	impl frame_system::Config for Test {
+		type BaseCallFilter = frame_support::traits::Everything;
+		type BlockWeights = ();
+		type BlockLength = ();
+		type DbWeight = ();
+		type Origin = Origin;
+		type Call = Call;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<u64>;
+		type Header = Header;
+		type Event = Event;
+		type BlockHashCount = BlockHashCount;
+		type Version = ();
+		type PalletInfo = PalletInfo;
+		type AccountData = ();
+		type OnNewAccount = ();
+		type OnKilledAccount = ();
+		type SystemWeightInfo = ();
+		type SS58Prefix = ();
+		type OnSetCode = ();
+		type MaxConsumers = frame_support::traits::ConstU32<16>;
+	}
+
+	parameter_types! {
+		pub static Author: Option<u32> = None;
+		pub static Session: SessionIndex = 0;
+		pub static Validators: Vec<u64> = vec![10, 20, 30];
+		pub static ActiveEra: Option<EraIndex> = None;
+		pub const HistoryDepth: EraIndex = 2;
+	}
+
+	pub struct TestFindAuthor;
+	impl FindAuthor<u32> for TestFindAuthor {
+		fn find_author<'a, I>(_: I) -> Option<u32>
+		where
+			I: 'a + IntoIterator<Item = (ConsensusEngineId, &'a [u8])>,
+		{
+			Author::get()
+		}
+	}
+
+	pub struct TestValidatorSet;
+	impl ValidatorSet<u64> for TestValidatorSet {
+		type ValidatorId = u64;
+		type ValidatorIdOf = sp_runtime::traits::ConvertInto;
+
+		fn session_index() -> SessionIndex {
+			Session::get()
+		}
+
+		fn validators() -> Vec<u64> {
+			Validators::get()
+		}
+	}
+
+	pub struct TestStaking;
+	impl ActiveEraProvider for TestStaking {
+		fn active_era() -> Option<EraIndex> {
+			ActiveEra::get()
+		}
+	}
+
+	impl Config for Test {
+		type FindAuthor = TestFindAuthor;
+		type ValidatorSet = TestValidatorSet;
+		type Staking = TestStaking;
+		type HistoryDepth = HistoryDepth;
+	}
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		let mut ext = sp_io::TestExternalities::new(t);
+		ext.execute_with(|| {
+			Author::set(None);
+			Session::set(0);
+			Validators::set(vec![10, 20, 30]);
+			ActiveEra::set(Some(0));
+		});
+		ext
+	}
+
+	fn author_block(author: u32) {
+		Author::set(Some(author));
+		AuthorshipStats::on_initialize(System::block_number());
+	}
+
+	fn start_session(session: SessionIndex, era: EraIndex) {
+		Session::set(session);
+		ActiveEra::set(Some(era));
+	}
+
+	#[test]
+	fn authored_blocks_are_counted_per_session() {
+		new_test_ext().execute_with(|| {
+			author_block(0);
+			author_block(2);
+			author_block(0);
+			assert_eq!(SessionAuthoredBlocks::<Test>::get(0, 0), 2);
+
+			let mut blocks = AuthorshipStats::session_authored_blocks(0);
+			blocks.sort();
+			assert_eq!(blocks, vec![(10, 2), (30, 1)]);
+
+			// The validator set changes and the indices refer to the new set.
+			start_session(1, 0);
+			Validators::set(vec![40, 10]);
+			author_block(1);
+			author_block(0);
+			let mut blocks = AuthorshipStats::session_authored_blocks(1);
+			blocks.sort();
+			assert_eq!(blocks, vec![(10, 1), (40, 1)]);
+
+			// Blocks without a known author are not counted.
+			Author::set(None);
+			AuthorshipStats::on_initialize(System::block_number());
+			assert_eq!(SessionAuthoredBlocks::<Test>::iter_prefix(1).count(), 2);
+		});
+	}
+
+	#[test]
+	fn counters_are_mirrored_into_era_totals() {
+		new_test_ext().execute_with(|| {
+			author_block(0);
+			author_block(1);
+			start_session(1, 0);
+			Validators::set(vec![40, 10]);
+			author_block(1);
+
+			start_session(2, 1);
+			author_block(0);
+
+			let mut totals = AuthorshipStats::era_authored_blocks(0);
+			totals.sort();
+			assert_eq!(totals, vec![(10, 2), (20, 1)]);
+			assert_eq!(CountedEra::<Test>::get(), Some(1));
+
+			// The counters of the ended era are reset.
+			assert!(SessionAuthoredBlocks::<Test>::iter_prefix(0).next().is_none());
+			assert!(SessionAuthoredBlocks::<Test>::iter_prefix(1).next().is_none());
+			assert_eq!(SessionValidators::<Test>::iter_keys().collect::<Vec<_>>(), vec![2]);
+			assert_eq!(AuthorshipStats::session_authored_blocks(2), vec![(40, 1)]);
+		});
+	}
+
+	#[test]
+	fn era_totals_are_kept_for_history_depth() {
+		new_test_ext().execute_with(|| {
+			for era in 0..4 {
+				start_session(era, era);
+				author_block(0);
+			}
+
+			assert!(AuthorshipStats::era_authored_blocks(0).is_empty());
+			assert_eq!(AuthorshipStats::era_authored_blocks(1), vec![(10, 1)]);
+			assert_eq!(AuthorshipStats::era_authored_blocks(2), vec![(10, 1)]);
+		});
+	}
+}
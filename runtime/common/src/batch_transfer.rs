@@ -0,0 +1,304 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Pallet for paying out many accounts with a single extrinsic.
+//!
+//! Aimed at exchanges and other services doing regular payout runs. A batch of transfers is
+//! signed and verified once and charged a flat fee per transfer, which is cheaper than the same
+//! transfers wrapped in `utility::batch`. Either all transfers of a batch succeed, or none do.
+
+use frame_support::{
+	pallet_prelude::*,
+	traits::{Currency, ExistenceRequirement},
+	transactional,
+	weights::constants::RocksDbWeight,
+};
+use frame_system::pallet_prelude::*;
+pub use pallet::*;
+use sp_runtime::traits::{Saturating, StaticLookup, Zero};
+use sp_std::prelude::*;
+
+type BalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+pub trait WeightInfo {
+	fn transfer_batch(n: u32) -> Weight;
+}
+
+/// Estimated from the weight of `pallet_balances::transfer` plus a read and write of the
+/// recipient's account, for runtimes which have not benchmarked the pallet.
+impl WeightInfo for () {
+	fn transfer_batch(n: u32) -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add((70_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads_writes(1, 1))
+			.saturating_add(RocksDbWeight::get().reads_writes(n as Weight, n as Weight))
+	}
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The currency transferred.
+		type Currency: Currency<Self::AccountId>;
+
+		/// The maximum number of transfers in a single batch.
+		#[pallet::constant]
+		type MaxTransfers: Get<u32>;
+
+		/// Weight information for the extrinsics of this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A batch of `count` transfers of `total` in sum was made from `from`.
+		BatchTransferred { from: T::AccountId, count: u32, total: BalanceOf<T> },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The batch holds more than `MaxTransfers` transfers.
+		TooManyTransfers,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Transfer the given amounts from the sender to each of the destinations.
+		///
+		/// With `keep_alive`, the batch fails rather than reaping the sender's account, like
+		/// `transfer_keep_alive` would. The whole batch fails if any of its transfers does.
+		#[pallet::weight(T::WeightInfo::transfer_batch(transfers.len() as u32))]
+		#[transactional]
+		pub fn transfer_batch(
+			origin: OriginFor<T>,
+			transfers: Vec<(<T::Lookup as StaticLookup>::Source, BalanceOf<T>)>,
+			keep_alive: bool,
+		) -> DispatchResult {
+			let from = ensure_signed(origin)?;
+			ensure!(
+				transfers.len() <= T::MaxTransfers::get() as usize,
+				Error::<T>::TooManyTransfers
+			);
+
+			let existence = if keep_alive {
+				ExistenceRequirement::KeepAlive
+			} else {
+				ExistenceRequirement::AllowDeath
+			};
+			let count = transfers.len() as u32;
+			let mut total = BalanceOf::<T>::zero();
+			for (dest, value) in transfers {
+				let dest = T::Lookup::lookup(dest)?;
+				T::Currency::transfer(&from, &dest, value, existence)?;
+				total = total.saturating_add(value);
+			}
+
+			Self::deposit_event(Event::<T>::BatchTransferred { from, count, total });
+			Ok(())
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use crate::batch_transfer;
+	use frame_support::{assert_noop, assert_ok, parameter_types};
+	use sp_core::H256;
+	use sp_runtime::{
+		testing::Header,
+		traits::{BlakeTwo256, IdentityLookup},
+	};
+
+	type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+	type Block = frame_system::mocking::MockBlock<Test>;
+
+	frame_support::construct_runtime!(
+		pub enum Test where
+			Block = Block,
+			NodeBlock = Block,
+			UncheckedExtrinsic = UncheckedExtrinsic,
+		{
+			System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+			Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+			BatchTransfer: batch_transfer::{Pallet, Call, Event<T>},
+		}
+	);
+
+	parameter_types! {
+		pub const BlockHashCount: u32 = 250;
+	}
+
+	impl frame_system::Config for Test {
+		type BaseCallFilter = frame_support::traits::Everything;
+		type BlockWeights = ();
+		type BlockLength = ();
+		type DbWeight = ();
+		type Origin = Origin;
+		type Call = Call;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<u64>;
+		type Header = Header;
+		type Event = Event;
+		type BlockHashCount = BlockHashCount;
+		type Version = ();
+		type PalletInfo = PalletInfo;
+		type AccountData = pallet_balances::AccountData<u64>;
+		type OnNewAccount = ();
+		type OnKilledAccount = ();
+		type SystemWeightInfo = ();
+		type SS58Prefix = ();
+		type OnSetCode = ();
+		type MaxConsumers = frame_support::traits::ConstU32<16>;
+	}
+
+	parameter_types! {
+		pub const ExistentialDeposit: u64 = 1;
+	}
+
+	impl pallet_balances::Config for Test {
+		type Balance = u64;
+		type Event = Event;
+		type DustRemoval = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type AccountStore = System;
+		type MaxLocks = ();
+		type MaxReserves = ();
+		type ReserveIdentifier = [u8; 8];
+		type WeightInfo = ();
+	}
+
+	parameter_types! {
+		pub const MaxTransfers: u32 = 3;
+	}
+
+	impl Config for Test {
+		type Event = Event;
+		type Currency = Balances;
+		type MaxTransfers = MaxTransfers;
+		type WeightInfo = ();
+	}
+
+	pub fn new_test_ext() -> sp_io::TestExternalities {
+		let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		pallet_balances::GenesisConfig::<Test> { balances: vec![(1, 100)] }
+			.assimilate_storage(&mut t)
+			.unwrap();
+		let mut ext = sp_io::TestExternalities::new(t);
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+
+	#[test]
+	fn transfers_are_made() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(BatchTransfer::transfer_batch(
+				Origin::signed(1),
+				vec![(2, 10), (3, 20), (2, 5)],
+				true
+			));
+
+			assert_eq!(Balances::free_balance(1), 65);
+			assert_eq!(Balances::free_balance(2), 15);
+			assert_eq!(Balances::free_balance(3), 20);
+			System::assert_last_event(Event::BatchTransfer(
+				batch_transfer::Event::BatchTransferred { from: 1, count: 3, total: 35 },
+			));
+		});
+	}
+
+	#[test]
+	fn batch_is_atomic() {
+		new_test_ext().execute_with(|| {
+			// The second transfer would reap the sender.
+			assert_noop!(
+				BatchTransfer::transfer_batch(Origin::signed(1), vec![(2, 10), (3, 90)], true),
+				pallet_balances::Error::<Test>::KeepAlive,
+			);
+			assert_eq!(Balances::free_balance(1), 100);
+
+			assert_ok!(BatchTransfer::transfer_batch(
+				Origin::signed(1),
+				vec![(2, 10), (3, 90)],
+				false
+			));
+			assert!(!System::account_exists(&1));
+		});
+	}
+
+	#[test]
+	fn batches_are_capped() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(
+				BatchTransfer::transfer_batch(
+					Origin::signed(1),
+					vec![(2, 1), (3, 1), (4, 1), (5, 1)],
+					true
+				),
+				Error::<Test>::TooManyTransfers,
+			);
+		});
+	}
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking {
+	use super::*;
+	use frame_benchmarking::{account, benchmarks, whitelisted_caller};
+	use frame_system::RawOrigin;
+	use sp_runtime::traits::Bounded;
+
+	benchmarks! {
+		// Every transfer creates a new account, the most expensive case.
+		transfer_batch {
+			let n in 1 .. T::MaxTransfers::get();
+
+			let caller: T::AccountId = whitelisted_caller();
+			T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+			let value = T::Currency::minimum_balance().saturating_mul(10u32.into());
+			let transfers = (0..n)
+				.map(|i| (T::Lookup::unlookup(account("recipient", i, 0)), value))
+				.collect::<Vec<_>>();
+		}: _(RawOrigin::Signed(caller.clone()), transfers, true)
+		verify {
+			let recipient: T::AccountId = account("recipient", n - 1, 0);
+			assert_eq!(T::Currency::free_balance(&recipient), value);
+		}
+
+		impl_benchmark_test_suite!(
+			Pallet,
+			crate::batch_transfer::tests::new_test_ext(),
+			crate::batch_transfer::tests::Test,
+		);
+	}
+}
@@ -0,0 +1,467 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Pallet to keep the bounties of `pallet-bounties` actionable.
+//!
+//! In the idle time of blocks, bounties are swept one by one:
+//! - A curator who missed the `BountyUpdatePeriod` of their bounty is unassigned. A
+//!   `CuratorSlash` fraction of their deposit goes to the treasury, the rest is returned.
+//! - A funded bounty without an active curator is closed once it stayed so for longer than the
+//!   `InactivityHorizon` set by governance, and its funds go back to the treasury.
+
+use frame_support::{
+	pallet_prelude::*,
+	storage::unhashed,
+	traits::{
+		Currency, ExistenceRequirement::AllowDeath, Imbalance, OnUnbalanced, ReservableCurrency,
+	},
+};
+use frame_system::pallet_prelude::*;
+pub use pallet::*;
+use pallet_bounties::{BountyIndex, BountyStatus};
+use sp_runtime::{
+	traits::{Saturating, Zero},
+	Permill,
+};
+
+type BalanceOf<T> = <<T as pallet_treasury::Config>::Currency as Currency<
+	<T as frame_system::Config>::AccountId,
+>>::Balance;
+
+/// The layout of `pallet_bounties::Bounty`, whose fields are private.
+#[derive(Encode, Decode)]
+struct BountyRecord<AccountId, Balance, BlockNumber> {
+	proposer: AccountId,
+	value: Balance,
+	fee: Balance,
+	curator_deposit: Balance,
+	bond: Balance,
+	status: BountyStatus<AccountId, BlockNumber>,
+}
+
+type BountyRecordOf<T> = BountyRecord<
+	<T as frame_system::Config>::AccountId,
+	BalanceOf<T>,
+	<T as frame_system::Config>::BlockNumber,
+>;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + pallet_bounties::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The fraction of the deposit of an expired curator which is slashed.
+		#[pallet::constant]
+		type CuratorSlash: Get<Permill>;
+
+		/// The maximum number of bounties swept in the idle time of a single block.
+		#[pallet::constant]
+		type MaxSweepsPerBlock: Get<u32>;
+
+		/// The origin which may set the inactivity horizon.
+		type HorizonOrigin: EnsureOrigin<Self::Origin>;
+	}
+
+	/// The index of the next bounty to sweep.
+	#[pallet::storage]
+	pub type NextToSweep<T> = StorageValue<_, BountyIndex, ValueQuery>;
+
+	/// The number of blocks a funded bounty may stay without an active curator before it is
+	/// closed. Inactive bounties are never closed if this is `None`.
+	#[pallet::storage]
+	pub type InactivityHorizon<T: Config> = StorageValue<_, T::BlockNumber, OptionQuery>;
+
+	/// The block from which on a funded bounty was first seen without an active curator.
+	#[pallet::storage]
+	pub type InactiveSince<T: Config> =
+		StorageMap<_, Twox64Concat, BountyIndex, T::BlockNumber, OptionQuery>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The curator of a bounty missed its update and was unassigned.
+		CuratorExpired { index: BountyIndex, curator: T::AccountId, slashed: BalanceOf<T> },
+		/// An inactive bounty was closed and its funds returned to the treasury.
+		BountyExpired { index: BountyIndex, returned: BalanceOf<T> },
+		/// The inactivity horizon was changed.
+		InactivityHorizonSet { horizon: Option<T::BlockNumber> },
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_idle(now: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+			let base_weight = T::DbWeight::get().reads_writes(2, 1);
+			let max = remaining_weight
+				.saturating_sub(base_weight)
+				.checked_div(Self::sweep_weight())
+				.unwrap_or(Weight::max_value())
+				.min(T::MaxSweepsPerBlock::get() as Weight);
+			let count = pallet_bounties::BountyCount::<T>::get();
+			if max == 0 || count == 0 || remaining_weight < base_weight {
+				return 0
+			}
+
+			let mut next = NextToSweep::<T>::get();
+			let swept = max.min(count as Weight);
+			for _ in 0..swept {
+				if next >= count {
+					next = 0;
+				}
+				Self::sweep(next, now);
+				next += 1;
+			}
+			NextToSweep::<T>::put(next);
+
+			Self::sweep_weight().saturating_mul(swept).saturating_add(base_weight)
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Set the number of blocks a funded bounty may stay without an active curator before it
+		/// is closed, or `None` to never close inactive bounties.
+		///
+		/// The origin must be `HorizonOrigin`.
+		#[pallet::weight(T::DbWeight::get().writes(1))]
+		pub fn set_inactivity_horizon(
+			origin: OriginFor<T>,
+			horizon: Option<T::BlockNumber>,
+		) -> DispatchResult {
+			T::HorizonOrigin::ensure_origin(origin)?;
+
+			InactivityHorizon::<T>::set(horizon);
+			Self::deposit_event(Event::<T>::InactivityHorizonSet { horizon });
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The weight of sweeping a single bounty: at worst the bounty, its inactivity, the horizon
+	/// and two accounts are read, and the bounty, its description, its inactivity and two
+	/// accounts are written.
+	fn sweep_weight() -> Weight {
+		T::DbWeight::get().reads_writes(5, 5)
+	}
+
+	/// Expire the curator of bounty `index` or close the bounty, if it is due.
+	fn sweep(index: BountyIndex, now: T::BlockNumber) {
+		let key = pallet_bounties::Bounties::<T>::hashed_key_for(index);
+		let mut bounty = match unhashed::get::<BountyRecordOf<T>>(&key) {
+			Some(bounty) => bounty,
+			None => {
+				InactiveSince::<T>::remove(index);
+				return
+			},
+		};
+
+		match bounty.status {
+			BountyStatus::Active { ref curator, update_due } if update_due < now => {
+				let curator = curator.clone();
+				let slash = T::CuratorSlash::get() * bounty.curator_deposit;
+				let imbalance = T::Currency::slash_reserved(&curator, slash).0;
+				let slashed = imbalance.peek();
+				T::OnSlash::on_unbalanced(imbalance);
+				T::Currency::unreserve(&curator, bounty.curator_deposit.saturating_sub(slash));
+
+				bounty.curator_deposit = Zero::zero();
+				bounty.status = BountyStatus::Funded;
+				unhashed::put(&key, &bounty);
+				InactiveSince::<T>::insert(index, now);
+				Self::deposit_event(Event::<T>::CuratorExpired { index, curator, slashed });
+			},
+			BountyStatus::Funded | BountyStatus::CuratorProposed { .. } => {
+				let since = match InactiveSince::<T>::get(index) {
+					Some(since) => since,
+					None => {
+						InactiveSince::<T>::insert(index, now);
+						return
+					},
+				};
+				let expired = InactivityHorizon::<T>::get()
+					.map_or(false, |horizon| now.saturating_sub(since) >= horizon);
+				if expired {
+					Self::close(index, &key);
+				}
+			},
+			_ => InactiveSince::<T>::remove(index),
+		}
+	}
+
+	/// Close bounty `index`, which has no curator deposit, and return its funds to the treasury.
+	fn close(index: BountyIndex, key: &[u8]) {
+		let bounty_account = pallet_bounties::Pallet::<T>::bounty_account_id(index);
+		let returned = T::Currency::free_balance(&bounty_account);
+		let treasury = pallet_treasury::Pallet::<T>::account_id();
+		// The bounty account only holds free funds, so this can't fail.
+		let _ = T::Currency::transfer(&bounty_account, &treasury, returned, AllowDeath);
+
+		pallet_bounties::BountyDescriptions::<T>::remove(index);
+		unhashed::kill(key);
+		InactiveSince::<T>::remove(index);
+		Self::deposit_event(Event::<T>::BountyExpired { index, returned });
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use crate::bounty_expiry;
+	use frame_support::{
+		assert_noop, assert_ok, parameter_types,
+		traits::{OnIdle, OnInitialize},
+		PalletId,
+	};
+	use frame_system::EnsureRoot;
+	use sp_core::H256;
+	use sp_runtime::{
+		testing::Header,
+		traits::{BadOrigin, BlakeTwo256, IdentityLookup},
+	};
+
+	type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+	type Block = frame_system::mocking::MockBlock<Test>;
+
+	frame_support::construct_runtime!(
+		pub enum Test where
+			Block = Block,
+			NodeBlock = Block,
+			UncheckedExtrinsic = UncheckedExtrinsic,
+		{
+			System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+			Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+			Treasury: pallet_treasury::{Pallet, Call, Storage, Config, Event<T>},
+			Bounties: pallet_bounties::{Pallet, Call, Storage, Event<T>},
+			BountyExpiry: bounty_expiry::{Pallet, Call, Storage, Event<T>},
+		}
+	);
+
+	parameter_types! {
+		pub const BlockHashCount: u32 = 250;
+	}
+
+	impl frame_system::Config for Test {
+		type BaseCallFilter = frame_support::traits::Everything;
+		type BlockWeights = ();
+		type BlockLength = ();
+		type DbWeight = ();
+		type Origin = Origin;
+		type Call = Call;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<u64>;
+		type Header = Header;
+		type Event = Event;
+		type BlockHashCount = BlockHashCount;
+		type Version = ();
+		type PalletInfo = PalletInfo;
+		type AccountData = pallet_balances::AccountData<u64>;
+		type OnNewAccount = ();
+		type OnKilledAccount = ();
+		type SystemWeightInfo = ();
+		type SS58Prefix = ();
+		type OnSetCode = ();
+		type MaxConsumers = frame_support::traits::ConstU32<16>;
+	}
+
+	parameter_types! {
+		pub const ExistentialDeposit: u64 = 1;
+	}
+
+	impl pallet_balances::Config for Test {
+		type Balance = u64;
+		type Event = Event;
+		type DustRemoval = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type AccountStore = System;
+		type MaxLocks = ();
+		type MaxReserves = ();
+		type ReserveIdentifier = [u8; 8];
+		type WeightInfo = ();
+	}
+
+	parameter_types! {
+		pub const ProposalBond: Permill = Permill::from_percent(5);
+		pub const ProposalBondMinimum: u64 = 1;
+		pub const SpendPeriod: u64 = 2;
+		pub const Burn: Permill = Permill::zero();
+		pub const TreasuryPalletId: PalletId = PalletId(*b"py/trsry");
+		pub const MaxApprovals: u32 = 100;
+	}
+
+	impl pallet_treasury::Config for Test {
+		type PalletId = TreasuryPalletId;
+		type Currency = Balances;
+		type ApproveOrigin = EnsureRoot<u64>;
+		type RejectOrigin = EnsureRoot<u64>;
+		type Event = Event;
+		type OnSlash = Treasury;
+		type ProposalBond = ProposalBond;
+		type ProposalBondMinimum = ProposalBondMinimum;
+		type ProposalBondMaximum = ();
+		type SpendPeriod = SpendPeriod;
+		type Burn = Burn;
+		type BurnDestination = ();
+		type SpendFunds = Bounties;
+		type MaxApprovals = MaxApprovals;
+		type WeightInfo = ();
+	}
+
+	parameter_types! {
+		pub const BountyDepositBase: u64 = 1;
+		pub const BountyDepositPayoutDelay: u64 = 3;
+		pub const BountyUpdatePeriod: u64 = 10;
+		pub const BountyCuratorDeposit: Permill = Permill::from_percent(50);
+		pub const BountyValueMinimum: u64 = 1;
+		pub const DataDepositPerByte: u64 = 1;
+		pub const MaximumReasonLength: u32 = 16384;
+	}
+
+	impl pallet_bounties::Config for Test {
+		type Event = Event;
+		type BountyDepositBase = BountyDepositBase;
+		type BountyDepositPayoutDelay = BountyDepositPayoutDelay;
+		type BountyUpdatePeriod = BountyUpdatePeriod;
+		type BountyCuratorDeposit = BountyCuratorDeposit;
+		type BountyValueMinimum = BountyValueMinimum;
+		type ChildBountyManager = ();
+		type DataDepositPerByte = DataDepositPerByte;
+		type MaximumReasonLength = MaximumReasonLength;
+		type WeightInfo = ();
+	}
+
+	parameter_types! {
+		pub const CuratorSlash: Permill = Permill::from_percent(20);
+		pub const MaxSweepsPerBlock: u32 = 8;
+	}
+
+	impl Config for Test {
+		type Event = Event;
+		type CuratorSlash = CuratorSlash;
+		type MaxSweepsPerBlock = MaxSweepsPerBlock;
+		type HorizonOrigin = EnsureRoot<u64>;
+	}
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		pallet_balances::GenesisConfig::<Test> {
+			balances: vec![(1, 100), (2, 100), (Treasury::account_id(), 1_001)],
+		}
+		.assimilate_storage(&mut t)
+		.unwrap();
+		let mut ext = sp_io::TestExternalities::new(t);
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+
+	fn status(index: BountyIndex) -> Option<BountyStatus<u64, u64>> {
+		unhashed::get::<BountyRecordOf<Test>>(&pallet_bounties::Bounties::<Test>::hashed_key_for(
+			index,
+		))
+		.map(|bounty| bounty.status)
+	}
+
+	fn sweep(now: u64) {
+		System::set_block_number(now);
+		BountyExpiry::on_idle(now, Weight::max_value());
+	}
+
+	/// Propose and fund a bounty of 100 and let account 2 accept it with a fee of 20.
+	fn active_bounty() -> BountyIndex {
+		assert_ok!(Bounties::propose_bounty(Origin::signed(1), 100, b"1234".to_vec()));
+		assert_ok!(Bounties::approve_bounty(Origin::root(), 0));
+		<Treasury as OnInitialize<u64>>::on_initialize(2);
+		assert_eq!(status(0), Some(BountyStatus::Funded));
+
+		assert_ok!(Bounties::propose_curator(Origin::root(), 0, 2, 20));
+		assert_ok!(Bounties::accept_curator(Origin::signed(2), 0));
+		assert_eq!(Balances::reserved_balance(2), 10);
+		0
+	}
+
+	#[test]
+	fn expired_curator_is_partially_slashed() {
+		new_test_ext().execute_with(|| {
+			let index = active_bounty();
+
+			// The update is due at block 11.
+			sweep(11);
+			assert!(matches!(status(index), Some(BountyStatus::Active { .. })));
+
+			let pot = Treasury::pot();
+			sweep(12);
+			assert_eq!(status(index), Some(BountyStatus::Funded));
+			assert_eq!(Balances::reserved_balance(2), 0);
+			assert_eq!(Balances::free_balance(2), 98);
+			assert_eq!(Treasury::pot(), pot + 2);
+			System::assert_last_event(
+				bounty_expiry::Event::CuratorExpired { index, curator: 2, slashed: 2 }.into(),
+			);
+		});
+	}
+
+	#[test]
+	fn inactive_bounty_is_closed_after_horizon() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(
+				BountyExpiry::set_inactivity_horizon(Origin::signed(1), Some(5)),
+				BadOrigin
+			);
+			assert_ok!(BountyExpiry::set_inactivity_horizon(Origin::root(), Some(5)));
+
+			let index = active_bounty();
+			assert_ok!(Bounties::unassign_curator(Origin::signed(2), index));
+
+			let pot = Treasury::pot();
+			// Inactivity is noted from the first sweep.
+			sweep(3);
+			sweep(7);
+			assert_eq!(status(index), Some(BountyStatus::Funded));
+
+			sweep(8);
+			assert_eq!(status(index), None);
+			assert_eq!(Treasury::pot(), pot + 100);
+			assert!(InactiveSince::<Test>::get(index).is_none());
+			System::assert_last_event(
+				bounty_expiry::Event::BountyExpired { index, returned: 100 }.into(),
+			);
+		});
+	}
+
+	#[test]
+	fn inactive_bounty_is_kept_without_horizon() {
+		new_test_ext().execute_with(|| {
+			let index = active_bounty();
+			assert_ok!(Bounties::unassign_curator(Origin::signed(2), index));
+
+			sweep(3);
+			sweep(1_000);
+			assert_eq!(status(index), Some(BountyStatus::Funded));
+		});
+	}
+}
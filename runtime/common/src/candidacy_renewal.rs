@@ -0,0 +1,505 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Pallet to renew council candidacies automatically.
+//!
+//! `pallet-elections-phragmen` keeps members and runners-up in the running for the next term,
+//! but every other candidate has to submit their candidacy again after each election. Accounts
+//! can opt in here to have that done for them at the start of every term. The renewals are
+//! queued when the term starts and submitted with the weight left over in `on_idle`, so they
+//! may be spread over the first blocks of the term.
+//!
+//! The pallet also sits between the elections pallet and the council, reporting every change of
+//! members which happens in the middle of a term, e.g. because a member was removed and slashed,
+//! and whether the seat was filled by a runner-up or left vacant.
+
+use frame_support::{
+	pallet_prelude::*,
+	traits::{ChangeMembers, Get},
+};
+use frame_system::{pallet_prelude::*, RawOrigin};
+pub use pallet::*;
+use pallet_elections_phragmen::WeightInfo as _;
+use sp_runtime::traits::Zero;
+use sp_std::prelude::*;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + pallet_elections_phragmen::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The members changes are passed on to, usually the council.
+		type ChangeMembers: ChangeMembers<Self::AccountId>;
+
+		/// The maximum number of accounts renewing their candidacy automatically.
+		#[pallet::constant]
+		type MaxAutoRenewals: Get<u32>;
+	}
+
+	/// Accounts which have their candidacy renewed at the start of every term.
+	#[pallet::storage]
+	#[pallet::getter(fn auto_renewing)]
+	pub type AutoRenewing<T: Config> =
+		StorageValue<_, BoundedVec<T::AccountId, T::MaxAutoRenewals>, ValueQuery>;
+
+	/// Accounts whose candidacy is still to be renewed for the current term.
+	#[pallet::storage]
+	#[pallet::getter(fn pending_renewals)]
+	pub type PendingRenewals<T: Config> =
+		StorageValue<_, BoundedVec<T::AccountId, T::MaxAutoRenewals>, ValueQuery>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// An account opted in to automatic candidacy renewal.
+		AutoRenewalEnabled { who: T::AccountId },
+		/// An account opted out of automatic candidacy renewal.
+		AutoRenewalDisabled { who: T::AccountId },
+		/// The candidacy of an account was submitted again for the new term.
+		CandidacyRenewed { who: T::AccountId },
+		/// The candidacy of an account could not be renewed, and it was opted out.
+		CandidacyRenewalFailed { who: T::AccountId, error: DispatchError },
+		/// A member was replaced in the middle of a term.
+		MemberReplaced { outgoing: T::AccountId, incoming: T::AccountId },
+		/// A member left in the middle of a term and there was nobody to replace them.
+		SeatVacated { outgoing: T::AccountId },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The account already renews its candidacy automatically.
+		AlreadyAutoRenewing,
+		/// The account does not renew its candidacy automatically.
+		NotAutoRenewing,
+		/// Too many accounts are renewing their candidacy automatically.
+		TooManyAutoRenewals,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(n: BlockNumberFor<T>) -> Weight {
+			if !Self::is_term_start(n) {
+				return 0
+			}
+
+			// Renewals left over from the previous term are queued again, if still enabled.
+			PendingRenewals::<T>::put(AutoRenewing::<T>::get());
+			T::DbWeight::get().reads_writes(1, 1)
+		}
+
+		fn on_idle(_: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+			Self::renew_candidacies(remaining_weight)
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Have the candidacy of the sender renewed at the start of every term.
+		///
+		/// The candidacy bond is reserved anew for every renewal. If that fails, the sender is
+		/// opted out again.
+		#[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+		pub fn enable_auto_renewal(origin: OriginFor<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			AutoRenewing::<T>::try_mutate(|renewing| -> DispatchResult {
+				ensure!(!renewing.contains(&who), Error::<T>::AlreadyAutoRenewing);
+				renewing.try_push(who.clone()).map_err(|_| Error::<T>::TooManyAutoRenewals)?;
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::<T>::AutoRenewalEnabled { who });
+			Ok(())
+		}
+
+		/// Stop renewing the candidacy of the sender automatically.
+		///
+		/// A candidacy which was already submitted is not affected.
+		#[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+		pub fn disable_auto_renewal(origin: OriginFor<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			AutoRenewing::<T>::try_mutate(|renewing| -> DispatchResult {
+				let position =
+					renewing.iter().position(|r| r == &who).ok_or(Error::<T>::NotAutoRenewing)?;
+				renewing.remove(position);
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::<T>::AutoRenewalDisabled { who });
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Whether a new term starts at block `n`, i.e. the elections pallet runs an election.
+	fn is_term_start(n: T::BlockNumber) -> bool {
+		let term_duration = <T as pallet_elections_phragmen::Config>::TermDuration::get();
+		!term_duration.is_zero() && (n % term_duration).is_zero()
+	}
+
+	/// The weight of reading the queue, the auto-renewing accounts and the elections pallet's
+	/// members, runners-up and candidates, and of writing the queue back.
+	fn overhead_weight() -> Weight {
+		T::DbWeight::get().reads_writes(5, 1)
+	}
+
+	/// The weight of renewing one candidacy with `candidate_count` candidates already
+	/// submitted, including opting the account out if it fails.
+	fn renewal_weight(candidate_count: u32) -> Weight {
+		<T as pallet_elections_phragmen::Config>::WeightInfo::submit_candidacy(candidate_count)
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+
+	/// Submit the candidacy of queued accounts which are not in the running already, for as long
+	/// as `limit` allows. Returns the weight consumed.
+	fn renew_candidacies(limit: Weight) -> Weight {
+		if limit < Self::overhead_weight() {
+			return 0
+		}
+
+		let mut pending = PendingRenewals::<T>::get().into_inner();
+		if pending.is_empty() {
+			return T::DbWeight::get().reads(1)
+		}
+
+		let renewing = AutoRenewing::<T>::get();
+		let members = <pallet_elections_phragmen::Pallet<T>>::members();
+		let runners_up = <pallet_elections_phragmen::Pallet<T>>::runners_up();
+		let candidates = <pallet_elections_phragmen::Pallet<T>>::candidates();
+		let mut weight = Self::overhead_weight();
+
+		let mut candidate_count = candidates.len() as u32;
+		let mut processed = 0;
+		let mut failed = Vec::new();
+		for who in pending.iter() {
+			let in_running = !renewing.contains(who) ||
+				members.iter().any(|m| &m.who == who) ||
+				runners_up.iter().any(|r| &r.who == who) ||
+				candidates.iter().any(|(c, _)| c == who);
+			if in_running {
+				processed += 1;
+				continue
+			}
+
+			let renewal_weight = Self::renewal_weight(candidate_count);
+			if weight.saturating_add(renewal_weight) > limit {
+				break
+			}
+			weight = weight.saturating_add(renewal_weight);
+			processed += 1;
+
+			let origin = RawOrigin::Signed(who.clone()).into();
+			match <pallet_elections_phragmen::Pallet<T>>::submit_candidacy(origin, candidate_count)
+			{
+				Ok(_) => {
+					candidate_count += 1;
+					Self::deposit_event(Event::<T>::CandidacyRenewed { who: who.clone() });
+				},
+				Err(e) => {
+					failed.push(who.clone());
+					Self::deposit_event(Event::<T>::CandidacyRenewalFailed {
+						who: who.clone(),
+						error: e.error,
+					});
+				},
+			}
+		}
+
+		if !failed.is_empty() {
+			AutoRenewing::<T>::mutate(|renewing| renewing.retain(|r| !failed.contains(r)));
+		}
+		// Shorter than the queue it was taken from, so it always fits.
+		let rest = pending.split_off(processed);
+		PendingRenewals::<T>::put(BoundedVec::try_from(rest).unwrap_or_default());
+
+		weight
+	}
+}
+
+impl<T: Config> ChangeMembers<T::AccountId> for Pallet<T> {
+	fn change_members_sorted(
+		incoming: &[T::AccountId],
+		outgoing: &[T::AccountId],
+		sorted_new: &[T::AccountId],
+	) {
+		T::ChangeMembers::change_members_sorted(incoming, outgoing, sorted_new);
+
+		// The elections pallet reports the members of a new term itself.
+		if Self::is_term_start(<frame_system::Pallet<T>>::block_number()) {
+			return
+		}
+
+		// Outside of term elections, members leave one at a time and are replaced by the best
+		// runner-up, if any.
+		for (i, outgoing) in outgoing.iter().enumerate() {
+			let outgoing = outgoing.clone();
+			match incoming.get(i) {
+				Some(incoming) => Self::deposit_event(Event::<T>::MemberReplaced {
+					outgoing,
+					incoming: incoming.clone(),
+				}),
+				None => Self::deposit_event(Event::<T>::SeatVacated { outgoing }),
+			}
+		}
+	}
+
+	fn set_prime(prime: Option<T::AccountId>) {
+		T::ChangeMembers::set_prime(prime)
+	}
+
+	fn get_prime() -> Option<T::AccountId> {
+		T::ChangeMembers::get_prime()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use crate::candidacy_renewal;
+	use frame_support::{
+		assert_noop, assert_ok, parameter_types,
+		traits::{OnIdle, OnInitialize},
+	};
+	use sp_core::H256;
+	use sp_runtime::{
+		testing::Header,
+		traits::{BlakeTwo256, IdentityLookup},
+	};
+
+	type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+	type Block = frame_system::mocking::MockBlock<Test>;
+
+	frame_support::construct_runtime!(
+		pub enum Test where
+			Block = Block,
+			NodeBlock = Block,
+			UncheckedExtrinsic = UncheckedExtrinsic,
+		{
+			System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+			Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+			Elections: pallet_elections_phragmen::{Pallet, Call, Storage, Event<T>, Config<T>},
+			CandidacyRenewal: candidacy_renewal::{Pallet, Call, Storage, Event<T>},
+		}
+	);
+
+	parameter_types! {
+		pub const BlockHashCount: u32 = 250;
+	}
+
+	impl frame_system::Config for Test {
+		type BaseCallFilter = frame_support::traits::Everything;
+		type BlockWeights = ();
+		type BlockLength = ();
+		type DbWeight = ();
+		type Origin = Origin;
+		type Call = Call;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<u64>;
+		type Header = Header;
+		type Event = Event;
+		type BlockHashCount = BlockHashCount;
+		type Version = ();
+		type PalletInfo = PalletInfo;
+		type AccountData = pallet_balances::AccountData<u64>;
+		type OnNewAccount = ();
+		type OnKilledAccount = ();
+		type SystemWeightInfo = ();
+		type SS58Prefix = ();
+		type OnSetCode = ();
+		type MaxConsumers = frame_support::traits::ConstU32<16>;
+	}
+
+	parameter_types! {
+		pub const ExistentialDeposit: u64 = 1;
+	}
+
+	impl pallet_balances::Config for Test {
+		type Balance = u64;
+		type Event = Event;
+		type DustRemoval = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type AccountStore = System;
+		type MaxLocks = ();
+		type MaxReserves = ();
+		type ReserveIdentifier = [u8; 8];
+		type WeightInfo = ();
+	}
+
+	parameter_types! {
+		pub const ElectionsPalletId: frame_support::traits::LockIdentifier = *b"phrelect";
+		pub const CandidacyBond: u64 = 10;
+		pub const VotingBondBase: u64 = 2;
+		pub const VotingBondFactor: u64 = 1;
+		pub const DesiredMembers: u32 = 2;
+		pub const DesiredRunnersUp: u32 = 1;
+		pub const TermDuration: u64 = 5;
+	}
+
+	impl pallet_elections_phragmen::Config for Test {
+		type Event = Event;
+		type PalletId = ElectionsPalletId;
+		type Currency = Balances;
+		type ChangeMembers = CandidacyRenewal;
+		type InitializeMembers = ();
+		type CurrencyToVote = frame_support::traits::SaturatingCurrencyToVote;
+		type CandidacyBond = CandidacyBond;
+		type VotingBondBase = VotingBondBase;
+		type VotingBondFactor = VotingBondFactor;
+		type LoserCandidate = ();
+		type KickedMember = ();
+		type DesiredMembers = DesiredMembers;
+		type DesiredRunnersUp = DesiredRunnersUp;
+		type TermDuration = TermDuration;
+		type WeightInfo = ();
+	}
+
+	parameter_types! {
+		pub const MaxAutoRenewals: u32 = 2;
+	}
+
+	impl Config for Test {
+		type Event = Event;
+		type ChangeMembers = ();
+		type MaxAutoRenewals = MaxAutoRenewals;
+	}
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		pallet_balances::GenesisConfig::<Test> { balances: vec![(1, 100), (2, 100), (3, 15)] }
+			.assimilate_storage(&mut t)
+			.unwrap();
+		let mut ext = sp_io::TestExternalities::new(t);
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+
+	#[test]
+	fn auto_renewal_can_be_toggled() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(CandidacyRenewal::enable_auto_renewal(Origin::signed(1)));
+			assert_noop!(
+				CandidacyRenewal::enable_auto_renewal(Origin::signed(1)),
+				Error::<Test>::AlreadyAutoRenewing,
+			);
+			assert_ok!(CandidacyRenewal::enable_auto_renewal(Origin::signed(2)));
+			assert_noop!(
+				CandidacyRenewal::enable_auto_renewal(Origin::signed(3)),
+				Error::<Test>::TooManyAutoRenewals,
+			);
+
+			assert_ok!(CandidacyRenewal::disable_auto_renewal(Origin::signed(1)));
+			assert_noop!(
+				CandidacyRenewal::disable_auto_renewal(Origin::signed(1)),
+				Error::<Test>::NotAutoRenewing,
+			);
+			assert_eq!(CandidacyRenewal::auto_renewing().into_inner(), vec![2]);
+		});
+	}
+
+	fn start_term(n: u64) {
+		System::set_block_number(n);
+		CandidacyRenewal::on_initialize(n);
+		CandidacyRenewal::on_idle(n, Weight::max_value());
+	}
+
+	#[test]
+	fn candidacies_are_renewed_at_term_start() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(CandidacyRenewal::enable_auto_renewal(Origin::signed(1)));
+			assert_ok!(CandidacyRenewal::enable_auto_renewal(Origin::signed(3)));
+			// Not enough free balance for a second bond.
+			assert_ok!(Balances::reserve(&3, 10));
+
+			// Nothing happens in the middle of a term.
+			start_term(4);
+			assert!(Elections::candidates().is_empty());
+
+			start_term(5);
+			assert_eq!(Elections::candidates(), vec![(1, 10)]);
+			assert_eq!(CandidacyRenewal::auto_renewing().into_inner(), vec![1]);
+			assert!(CandidacyRenewal::pending_renewals().is_empty());
+
+			// Already a candidate, so it isn't submitted twice.
+			start_term(10);
+			assert_eq!(Elections::candidates(), vec![(1, 10)]);
+		});
+	}
+
+	#[test]
+	fn renewals_are_spread_over_idle_blocks() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(CandidacyRenewal::enable_auto_renewal(Origin::signed(1)));
+			assert_ok!(CandidacyRenewal::enable_auto_renewal(Origin::signed(2)));
+
+			System::set_block_number(5);
+			CandidacyRenewal::on_initialize(5);
+			assert_eq!(CandidacyRenewal::pending_renewals().into_inner(), vec![1, 2]);
+
+			// Only enough weight left for one renewal.
+			let limit = CandidacyRenewal::renewal_weight(0);
+			assert_eq!(CandidacyRenewal::on_idle(5, limit), limit);
+			assert_eq!(Elections::candidates(), vec![(1, 10)]);
+			assert_eq!(CandidacyRenewal::pending_renewals().into_inner(), vec![2]);
+
+			// No weight left at all.
+			assert_eq!(CandidacyRenewal::on_idle(5, 0), 0);
+
+			// Accounts which opted out in the meantime are skipped.
+			assert_ok!(CandidacyRenewal::disable_auto_renewal(Origin::signed(2)));
+			CandidacyRenewal::on_idle(6, Weight::max_value());
+			assert_eq!(Elections::candidates(), vec![(1, 10)]);
+			assert!(CandidacyRenewal::pending_renewals().is_empty());
+		});
+	}
+
+	#[test]
+	fn mid_term_changes_are_reported() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(7);
+
+			<CandidacyRenewal as ChangeMembers<u64>>::change_members_sorted(&[3], &[1], &[2, 3]);
+			System::assert_last_event(Event::CandidacyRenewal(
+				candidacy_renewal::Event::MemberReplaced { outgoing: 1, incoming: 3 },
+			));
+
+			<CandidacyRenewal as ChangeMembers<u64>>::change_members_sorted(&[], &[2], &[3]);
+			System::assert_last_event(Event::CandidacyRenewal(
+				candidacy_renewal::Event::SeatVacated { outgoing: 2 },
+			));
+
+			// Term elections are not reported.
+			System::set_block_number(10);
+			System::reset_events();
+			<CandidacyRenewal as ChangeMembers<u64>>::change_members_sorted(&[1], &[3], &[1]);
+			assert!(System::events().is_empty());
+		});
+	}
+}
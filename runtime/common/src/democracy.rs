@@ -0,0 +1,196 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime API exposing the voting power and governance locks of an account in
+//! `pallet-democracy`, so that wallets don't need to replay historical events to find them.
+
+use frame_support::traits::{Get, LockIdentifier};
+use pallet_democracy::{
+	AccountVote, Conviction, Delegations, ReferendumIndex, ReferendumInfo, Voting,
+};
+use parity_scale_codec::{Codec, Decode, Encode};
+use scale_info::TypeInfo;
+use sp_runtime::{
+	traits::{AtLeast32BitUnsigned, Saturating, Zero},
+	RuntimeDebug,
+};
+use sp_std::prelude::*;
+
+/// The lock identifier used by `pallet-democracy`.
+pub const DEMOCRACY_ID: LockIdentifier = *b"democrac";
+
+/// A vote cast directly on a referendum.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct DirectVote<Balance, BlockNumber> {
+	/// The referendum voted on.
+	pub referendum: ReferendumIndex,
+	/// The vote as cast.
+	pub vote: AccountVote<Balance>,
+	/// The voting power of the vote, i.e. its balance multiplied by its conviction.
+	pub votes: Balance,
+	/// The block from which the balance of the vote can be unlocked. `None` while the referendum
+	/// is ongoing.
+	pub unlock_at: Option<BlockNumber>,
+}
+
+/// The voting power of an account delegated to another account.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct Delegation<AccountId, Balance> {
+	/// The account delegated to.
+	pub target: AccountId,
+	/// The balance delegated.
+	pub balance: Balance,
+	/// The conviction of the delegation.
+	pub conviction: Conviction,
+	/// The voting power delegated, i.e. the balance multiplied by the conviction.
+	pub votes: Balance,
+}
+
+/// The breakdown of the voting power and democracy locks of an account.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct VotingPower<AccountId, Balance, BlockNumber> {
+	/// Votes cast directly by the account. Empty if the account is delegating.
+	pub direct_votes: Vec<DirectVote<Balance, BlockNumber>>,
+	/// The delegation of the account, if it is delegating.
+	pub delegating: Option<Delegation<AccountId, Balance>>,
+	/// The voting power delegated to the account by others.
+	pub delegations_in: Delegations<Balance>,
+	/// A lock left over from votes or delegations that were removed, as the amount and the block
+	/// from which it can be unlocked.
+	pub prior_lock: (Balance, BlockNumber),
+	/// The amount currently locked by `pallet-democracy`.
+	pub locked: Balance,
+}
+
+sp_api::decl_runtime_apis! {
+	/// The API for querying the voting power of accounts in `pallet-democracy`.
+	pub trait DemocracyApi<AccountId, Balance, BlockNumber> where
+		AccountId: Codec,
+		Balance: Codec,
+		BlockNumber: Codec,
+	{
+		/// Get the votes, delegations and democracy locks of `who`.
+		fn voting_power(who: AccountId) -> VotingPower<AccountId, Balance, BlockNumber>;
+	}
+}
+
+type BalanceOf<T> = <T as pallet_balances::Config>::Balance;
+
+/// Collect the voting power of `who` from the state of `pallet-democracy`.
+pub fn voting_power<T>(who: T::AccountId) -> VotingPower<T::AccountId, BalanceOf<T>, T::BlockNumber>
+where
+	T: pallet_democracy::Config<Currency = pallet_balances::Pallet<T>> + pallet_balances::Config,
+{
+	let locked = <pallet_balances::Pallet<T>>::locks(&who)
+		.iter()
+		.find(|lock| lock.id == DEMOCRACY_ID)
+		.map_or_else(Zero::zero, |lock| lock.amount);
+
+	let locking_period = T::VoteLockingPeriod::get();
+	let (direct_votes, delegating, delegations_in, prior) =
+		match pallet_democracy::VotingOf::<T>::get(&who) {
+			Voting::Direct { votes, delegations, prior } => {
+				let votes = votes
+					.into_iter()
+					.map(|(referendum, vote)| DirectVote {
+						referendum,
+						vote,
+						votes: vote_power(vote),
+						unlock_at: pallet_democracy::Pallet::<T>::referendum_info(referendum)
+							.and_then(|info| unlock_at(vote, info, locking_period)),
+					})
+					.collect();
+				(votes, None, delegations, prior)
+			},
+			Voting::Delegating { balance, target, conviction, delegations, prior } => {
+				let votes = conviction.votes(balance).votes;
+				let delegation = Delegation { target, balance, conviction, votes };
+				(Vec::new(), Some(delegation), delegations, prior)
+			},
+		};
+
+	// `PriorLock` doesn't expose the block it expires at, but it encodes as a plain tuple.
+	let prior_lock = <(T::BlockNumber, BalanceOf<T>)>::decode(&mut &prior.encode()[..])
+		.map(|(until, amount)| (amount, until))
+		.unwrap_or_else(|_| (Zero::zero(), Zero::zero()));
+
+	VotingPower { direct_votes, delegating, delegations_in, prior_lock, locked }
+}
+
+/// The voting power of a single vote.
+fn vote_power<Balance: AtLeast32BitUnsigned + Copy>(vote: AccountVote<Balance>) -> Balance {
+	match vote {
+		AccountVote::Standard { vote, balance } => vote.conviction.votes(balance).votes,
+		// Split votes are counted without conviction.
+		AccountVote::Split { aye, nay } => Conviction::None.votes(aye.saturating_add(nay)).votes,
+	}
+}
+
+/// The block from which the balance of `vote` can be unlocked, if the referendum has ended.
+fn unlock_at<Balance, BlockNumber, Hash>(
+	vote: AccountVote<Balance>,
+	info: ReferendumInfo<BlockNumber, Hash, Balance>,
+	locking_period: BlockNumber,
+) -> Option<BlockNumber>
+where
+	BlockNumber: AtLeast32BitUnsigned + Copy,
+{
+	match info {
+		ReferendumInfo::Ongoing(_) => None,
+		ReferendumInfo::Finished { approved, end } => match vote {
+			// Only votes on the winning side stay locked for their conviction's lock periods.
+			AccountVote::Standard { vote, .. } if vote.aye == approved => Some(end.saturating_add(
+				locking_period.saturating_mul(vote.conviction.lock_periods().into()),
+			)),
+			_ => Some(end),
+		},
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pallet_democracy::Vote;
+
+	fn standard(aye: bool, conviction: Conviction, balance: u64) -> AccountVote<u64> {
+		AccountVote::Standard { vote: Vote { aye, conviction }, balance }
+	}
+
+	fn finished(approved: bool, end: u32) -> ReferendumInfo<u32, (), u64> {
+		ReferendumInfo::Finished { approved, end }
+	}
+
+	#[test]
+	fn vote_power_applies_conviction() {
+		assert_eq!(vote_power(standard(true, Conviction::None, 100)), 10);
+		assert_eq!(vote_power(standard(false, Conviction::Locked3x, 100)), 300);
+		assert_eq!(vote_power(AccountVote::Split { aye: 60, nay: 40 }), 10);
+	}
+
+	#[test]
+	fn winning_votes_stay_locked_for_their_conviction() {
+		let locked = standard(true, Conviction::Locked3x, 100);
+		// Locked3x has four lock periods.
+		assert_eq!(unlock_at(locked, finished(true, 50), 10), Some(90));
+		assert_eq!(unlock_at(locked, finished(false, 50), 10), Some(50));
+
+		let unlocked = standard(true, Conviction::None, 100);
+		assert_eq!(unlock_at(unlocked, finished(true, 50), 10), Some(50));
+
+		let split = AccountVote::Split { aye: 60, nay: 40 };
+		assert_eq!(unlock_at(split, finished(true, 50), 10), Some(50));
+	}
+}
@@ -0,0 +1,807 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Pallet for constant-product liquidity pools pairing SEL with a registered asset.
+//!
+//! Each registered asset can have one pool, created with `create_pool`. Liquidity providers deposit
+//! SEL and the asset in the ratio of the pool's reserves and get shares of the pool in return. A
+//! swap takes a `SwapFee` of the amount paid in, which stays in the pool, and pays out the amount
+//! keeping the product of the reserves constant.
+//!
+//! [`PoolPrice`] prices amounts of SEL in an asset at the price of its pool at the start of the
+//! block, so that swapping in and back out within a block does not move the price fees are paid
+//! at. It implements `BalanceConversion`, so it can price fees paid in assets, e.g. by
+//! `pallet_asset_tx_payment` or an XCM trader.
+
+use frame_support::{
+	pallet_prelude::*,
+	traits::{
+		tokens::{fungibles, BalanceConversion},
+		Currency, ExistenceRequirement,
+	},
+	weights::constants::RocksDbWeight,
+	PalletId,
+};
+use frame_system::pallet_prelude::*;
+pub use pallet::*;
+use sp_core::U256;
+use sp_runtime::{
+	traits::{AccountIdConversion, CheckedAdd, Saturating, Zero},
+	Permill,
+};
+use sp_std::marker::PhantomData;
+
+type BalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+pub trait WeightInfo {
+	fn create_pool() -> Weight;
+	fn add_liquidity() -> Weight;
+	fn remove_liquidity() -> Weight;
+	fn swap_sel_for_asset() -> Weight;
+	fn swap_asset_for_sel() -> Weight;
+}
+
+/// Estimated from the storage accessed by each call, for runtimes which have not benchmarked the
+/// pallet.
+impl WeightInfo for () {
+	fn create_pool() -> Weight {
+		(20_000_000 as Weight).saturating_add(RocksDbWeight::get().reads_writes(2, 1))
+	}
+	fn add_liquidity() -> Weight {
+		(60_000_000 as Weight).saturating_add(RocksDbWeight::get().reads_writes(8, 8))
+	}
+	fn remove_liquidity() -> Weight {
+		(60_000_000 as Weight).saturating_add(RocksDbWeight::get().reads_writes(8, 8))
+	}
+	fn swap_sel_for_asset() -> Weight {
+		(50_000_000 as Weight).saturating_add(RocksDbWeight::get().reads_writes(7, 7))
+	}
+	fn swap_asset_for_sel() -> Weight {
+		(50_000_000 as Weight).saturating_add(RocksDbWeight::get().reads_writes(7, 7))
+	}
+}
+
+/// The reserves and the shares of a pool.
+#[derive(Encode, Decode, Clone, Default, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct Pool<Balance> {
+	/// The SEL held by the pool.
+	pub sel: Balance,
+	/// The asset held by the pool.
+	pub asset: Balance,
+	/// The shares of all liquidity providers.
+	pub shares: Balance,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The identifier of an asset.
+		type AssetId: Parameter + Member + Copy + MaxEncodedLen;
+
+		/// SEL.
+		type Currency: Currency<Self::AccountId>;
+
+		/// The registered assets SEL can be paired with.
+		type Assets: fungibles::Inspect<Self::AccountId, AssetId = Self::AssetId, Balance = BalanceOf<Self>>
+			+ fungibles::Transfer<Self::AccountId>;
+
+		/// The id the accounts of the pools are derived from.
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+
+		/// The fee taken of the amount paid into a swap, which stays in the pool.
+		#[pallet::constant]
+		type SwapFee: Get<Permill>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	/// The pool of each asset.
+	#[pallet::storage]
+	#[pallet::getter(fn pool)]
+	pub type Pools<T: Config> =
+		StorageMap<_, Twox64Concat, T::AssetId, Pool<BalanceOf<T>>, OptionQuery>;
+
+	/// The block the pool of an asset last changed in, and its SEL and asset reserves at the start
+	/// of that block.
+	#[pallet::storage]
+	pub type BlockStartReserves<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::AssetId,
+		(T::BlockNumber, BalanceOf<T>, BalanceOf<T>),
+		OptionQuery,
+	>;
+
+	/// The shares each account holds in the pool of an asset.
+	#[pallet::storage]
+	pub type Shares<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::AssetId,
+		Blake2_128Concat,
+		T::AccountId,
+		BalanceOf<T>,
+		ValueQuery,
+	>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A pool for an asset was created.
+		PoolCreated { asset: T::AssetId },
+		/// Liquidity was added to a pool.
+		LiquidityAdded {
+			who: T::AccountId,
+			asset: T::AssetId,
+			sel: BalanceOf<T>,
+			asset_amount: BalanceOf<T>,
+			shares: BalanceOf<T>,
+		},
+		/// Liquidity was removed from a pool.
+		LiquidityRemoved {
+			who: T::AccountId,
+			asset: T::AssetId,
+			sel: BalanceOf<T>,
+			asset_amount: BalanceOf<T>,
+			shares: BalanceOf<T>,
+		},
+		/// SEL was swapped for an asset.
+		SwappedSelForAsset {
+			who: T::AccountId,
+			asset: T::AssetId,
+			sel_in: BalanceOf<T>,
+			asset_out: BalanceOf<T>,
+		},
+		/// An asset was swapped for SEL.
+		SwappedAssetForSel {
+			who: T::AccountId,
+			asset: T::AssetId,
+			asset_in: BalanceOf<T>,
+			sel_out: BalanceOf<T>,
+		},
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The asset is not registered.
+		UnknownAsset,
+		/// The asset has a pool already.
+		PoolExists,
+		/// The asset has no pool.
+		NoPool,
+		/// The pool has no liquidity.
+		NoLiquidity,
+		/// The amount is zero, or too small to be worth any shares or any output.
+		ZeroAmount,
+		/// The amount of the asset needed exceeds the given maximum.
+		MaxAssetExceeded,
+		/// The output is below the given minimum.
+		MinOutNotMet,
+		/// The account holds fewer shares of the pool.
+		InsufficientShares,
+		/// An amount overflowed.
+		Overflow,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Create a pool pairing SEL with the registered `asset`.
+		#[pallet::weight(T::WeightInfo::create_pool())]
+		pub fn create_pool(origin: OriginFor<T>, asset: T::AssetId) -> DispatchResult {
+			ensure_signed(origin)?;
+			// Registered assets have a non-zero minimum balance.
+			ensure!(
+				!<T::Assets as fungibles::Inspect<_>>::minimum_balance(asset).is_zero(),
+				Error::<T>::UnknownAsset,
+			);
+			ensure!(!Pools::<T>::contains_key(asset), Error::<T>::PoolExists);
+
+			Pools::<T>::insert(asset, Pool::default());
+			Self::deposit_event(Event::<T>::PoolCreated { asset });
+			Ok(())
+		}
+
+		/// Deposit `sel` and the amount of `asset` matching the ratio of the reserves into the pool
+		/// of `asset`, which must not exceed `max_asset_amount`.
+		///
+		/// Into a pool without liquidity, `max_asset_amount` is deposited, setting the price.
+		#[pallet::weight(T::WeightInfo::add_liquidity())]
+		pub fn add_liquidity(
+			origin: OriginFor<T>,
+			asset: T::AssetId,
+			sel: BalanceOf<T>,
+			max_asset_amount: BalanceOf<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!sel.is_zero() && !max_asset_amount.is_zero(), Error::<T>::ZeroAmount);
+			let mut pool = Pools::<T>::get(asset).ok_or(Error::<T>::NoPool)?;
+
+			let (asset_amount, shares) = if pool.shares.is_zero() {
+				(max_asset_amount, sel)
+			} else {
+				let asset_amount =
+					mul_div::<T>(sel, pool.asset, pool.sel, true).ok_or(Error::<T>::Overflow)?;
+				let shares =
+					mul_div::<T>(sel, pool.shares, pool.sel, false).ok_or(Error::<T>::Overflow)?;
+				ensure!(asset_amount <= max_asset_amount, Error::<T>::MaxAssetExceeded);
+				(asset_amount, shares)
+			};
+			ensure!(!shares.is_zero(), Error::<T>::ZeroAmount);
+
+			let account = Self::pool_account(asset);
+			T::Currency::transfer(&who, &account, sel, ExistenceRequirement::KeepAlive)?;
+			<T::Assets as fungibles::Transfer<_>>::transfer(
+				asset,
+				&who,
+				&account,
+				asset_amount,
+				true,
+			)?;
+
+			Self::note_block_start(asset, &pool);
+			pool.sel = pool.sel.checked_add(&sel).ok_or(Error::<T>::Overflow)?;
+			pool.asset = pool.asset.checked_add(&asset_amount).ok_or(Error::<T>::Overflow)?;
+			pool.shares = pool.shares.checked_add(&shares).ok_or(Error::<T>::Overflow)?;
+			Pools::<T>::insert(asset, pool);
+			Shares::<T>::mutate(asset, &who, |held| *held = held.saturating_add(shares));
+
+			Self::deposit_event(Event::<T>::LiquidityAdded {
+				who,
+				asset,
+				sel,
+				asset_amount,
+				shares,
+			});
+			Ok(())
+		}
+
+		/// Redeem `shares` of the pool of `asset` for the matching part of its reserves, of which
+		/// at least `min_sel` and `min_asset_amount` must be paid out.
+		#[pallet::weight(T::WeightInfo::remove_liquidity())]
+		pub fn remove_liquidity(
+			origin: OriginFor<T>,
+			asset: T::AssetId,
+			shares: BalanceOf<T>,
+			min_sel: BalanceOf<T>,
+			min_asset_amount: BalanceOf<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!shares.is_zero(), Error::<T>::ZeroAmount);
+			let mut pool = Pools::<T>::get(asset).ok_or(Error::<T>::NoPool)?;
+			let held = Shares::<T>::get(asset, &who);
+			ensure!(held >= shares, Error::<T>::InsufficientShares);
+
+			let sel =
+				mul_div::<T>(shares, pool.sel, pool.shares, false).ok_or(Error::<T>::Overflow)?;
+			let asset_amount =
+				mul_div::<T>(shares, pool.asset, pool.shares, false).ok_or(Error::<T>::Overflow)?;
+			ensure!(sel >= min_sel && asset_amount >= min_asset_amount, Error::<T>::MinOutNotMet);
+
+			// Only the last provider may empty the account of the pool.
+			let last = shares == pool.shares;
+			let existence = if last {
+				ExistenceRequirement::AllowDeath
+			} else {
+				ExistenceRequirement::KeepAlive
+			};
+			let account = Self::pool_account(asset);
+			<T::Assets as fungibles::Transfer<_>>::transfer(
+				asset,
+				&account,
+				&who,
+				asset_amount,
+				!last,
+			)?;
+			T::Currency::transfer(&account, &who, sel, existence)?;
+
+			Self::note_block_start(asset, &pool);
+			pool.sel = pool.sel.saturating_sub(sel);
+			pool.asset = pool.asset.saturating_sub(asset_amount);
+			pool.shares = pool.shares.saturating_sub(shares);
+			Pools::<T>::insert(asset, pool);
+			Shares::<T>::insert(asset, &who, held.saturating_sub(shares));
+
+			Self::deposit_event(Event::<T>::LiquidityRemoved {
+				who,
+				asset,
+				sel,
+				asset_amount,
+				shares,
+			});
+			Ok(())
+		}
+
+		/// Swap `sel_in` for at least `min_asset_out` of `asset`.
+		#[pallet::weight(T::WeightInfo::swap_sel_for_asset())]
+		pub fn swap_sel_for_asset(
+			origin: OriginFor<T>,
+			asset: T::AssetId,
+			sel_in: BalanceOf<T>,
+			min_asset_out: BalanceOf<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let mut pool = Pools::<T>::get(asset).ok_or(Error::<T>::NoPool)?;
+			let asset_out = Self::amount_out(sel_in, pool.sel, pool.asset)?;
+			ensure!(asset_out >= min_asset_out, Error::<T>::MinOutNotMet);
+
+			let account = Self::pool_account(asset);
+			T::Currency::transfer(&who, &account, sel_in, ExistenceRequirement::KeepAlive)?;
+			<T::Assets as fungibles::Transfer<_>>::transfer(
+				asset, &account, &who, asset_out, true,
+			)?;
+
+			Self::note_block_start(asset, &pool);
+			pool.sel = pool.sel.checked_add(&sel_in).ok_or(Error::<T>::Overflow)?;
+			pool.asset = pool.asset.saturating_sub(asset_out);
+			Pools::<T>::insert(asset, pool);
+
+			Self::deposit_event(Event::<T>::SwappedSelForAsset { who, asset, sel_in, asset_out });
+			Ok(())
+		}
+
+		/// Swap `asset_in` of `asset` for at least `min_sel_out`.
+		#[pallet::weight(T::WeightInfo::swap_asset_for_sel())]
+		pub fn swap_asset_for_sel(
+			origin: OriginFor<T>,
+			asset: T::AssetId,
+			asset_in: BalanceOf<T>,
+			min_sel_out: BalanceOf<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let mut pool = Pools::<T>::get(asset).ok_or(Error::<T>::NoPool)?;
+			let sel_out = Self::amount_out(asset_in, pool.asset, pool.sel)?;
+			ensure!(sel_out >= min_sel_out, Error::<T>::MinOutNotMet);
+
+			let account = Self::pool_account(asset);
+			<T::Assets as fungibles::Transfer<_>>::transfer(asset, &who, &account, asset_in, true)?;
+			T::Currency::transfer(&account, &who, sel_out, ExistenceRequirement::KeepAlive)?;
+
+			Self::note_block_start(asset, &pool);
+			pool.asset = pool.asset.checked_add(&asset_in).ok_or(Error::<T>::Overflow)?;
+			pool.sel = pool.sel.saturating_sub(sel_out);
+			Pools::<T>::insert(asset, pool);
+
+			Self::deposit_event(Event::<T>::SwappedAssetForSel { who, asset, asset_in, sel_out });
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The account holding the reserves of the pool of `asset`.
+	pub fn pool_account(asset: T::AssetId) -> T::AccountId {
+		T::PalletId::get().into_sub_account(asset)
+	}
+
+	/// The amount of `asset` worth `sel` at the price of its pool at the start of the block.
+	pub fn sel_in_asset(
+		asset: T::AssetId,
+		sel: BalanceOf<T>,
+	) -> Result<BalanceOf<T>, DispatchError> {
+		let pool = Pools::<T>::get(asset).ok_or(Error::<T>::NoPool)?;
+		let now = <frame_system::Pallet<T>>::block_number();
+		let (sel_reserve, asset_reserve) = match BlockStartReserves::<T>::get(asset) {
+			Some((block, sel_reserve, asset_reserve)) if block == now =>
+				(sel_reserve, asset_reserve),
+			_ => (pool.sel, pool.asset),
+		};
+		ensure!(!sel_reserve.is_zero(), Error::<T>::NoLiquidity);
+		Ok(mul_div::<T>(sel, asset_reserve, sel_reserve, true).ok_or(Error::<T>::Overflow)?)
+	}
+
+	/// Keep the reserves of the pool of `asset` before it changes for the first time in a block.
+	fn note_block_start(asset: T::AssetId, pool: &Pool<BalanceOf<T>>) {
+		let now = <frame_system::Pallet<T>>::block_number();
+		BlockStartReserves::<T>::mutate(asset, |start| match start {
+			Some((block, ..)) if *block == now => {},
+			_ => *start = Some((now, pool.sel, pool.asset)),
+		});
+	}
+
+	/// The amount paid out of `reserve_out` for `amount_in` paid into `reserve_in`, after the fee.
+	fn amount_out(
+		amount_in: BalanceOf<T>,
+		reserve_in: BalanceOf<T>,
+		reserve_out: BalanceOf<T>,
+	) -> Result<BalanceOf<T>, DispatchError> {
+		ensure!(!reserve_in.is_zero() && !reserve_out.is_zero(), Error::<T>::NoLiquidity);
+		let amount_in = amount_in.saturating_sub(T::SwapFee::get() * amount_in);
+		let reserve_in = reserve_in.checked_add(&amount_in).ok_or(Error::<T>::Overflow)?;
+		let amount_out =
+			mul_div::<T>(reserve_out, amount_in, reserve_in, false).ok_or(Error::<T>::Overflow)?;
+		ensure!(!amount_out.is_zero(), Error::<T>::ZeroAmount);
+		Ok(amount_out)
+	}
+}
+
+/// `a * b / c`, rounded up or down, or `None` if `c` is zero or the result overflows.
+fn mul_div<T: Config>(
+	a: BalanceOf<T>,
+	b: BalanceOf<T>,
+	c: BalanceOf<T>,
+	round_up: bool,
+) -> Option<BalanceOf<T>> {
+	let to_u256 = |x: BalanceOf<T>| x.try_into().ok().map(|x: u128| U256::from(x));
+	let (a, b, c) = (to_u256(a)?, to_u256(b)?, to_u256(c)?);
+	if c.is_zero() {
+		return None
+	}
+	let product = a.checked_mul(b)?;
+	let result = if round_up { product.checked_add(c - 1)? / c } else { product / c };
+	u128::try_from(result).ok()?.try_into().ok()
+}
+
+/// Prices amounts of SEL in an asset at the price of the asset's pool at the start of the block.
+pub struct PoolPrice<T>(PhantomData<T>);
+
+impl<T: Config> BalanceConversion<BalanceOf<T>, T::AssetId, BalanceOf<T>> for PoolPrice<T> {
+	type Error = DispatchError;
+
+	fn to_asset_balance(
+		balance: BalanceOf<T>,
+		asset_id: T::AssetId,
+	) -> Result<BalanceOf<T>, DispatchError> {
+		Pallet::<T>::sel_in_asset(asset_id, balance)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::dex;
+	use frame_support::{assert_noop, assert_ok, parameter_types};
+	use frame_system::EnsureRoot;
+	use sp_core::H256;
+	use sp_runtime::{
+		testing::Header,
+		traits::{BlakeTwo256, IdentityLookup},
+	};
+
+	type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+	type Block = frame_system::mocking::MockBlock<Test>;
+
+	frame_support::construct_runtime!(
+		pub enum Test where
+			Block = Block,
+			NodeBlock = Block,
+			UncheckedExtrinsic = UncheckedExtrinsic,
+		{
+			System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+			Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+			Assets: pallet_assets::{Pallet, Call, Storage, Event<T>},
+			Dex: dex::{Pallet, Call, Storage, Event<T>},
+		}
+	);
+
+	parameter_types! {
+		pub const BlockHashCount: u32 = 250;
+	}
+
+	impl frame_system::Config for Test {
+		type BaseCallFilter = frame_support::traits::Everything;
+		type BlockWeights = ();
+		type BlockLength = ();
+		type DbWeight = ();
+		type Origin = Origin;
+		type Call = Call;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<u64>;
+		type Header = Header;
+		type Event = Event;
+		type BlockHashCount = BlockHashCount;
+		type Version = ();
+		type PalletInfo = PalletInfo;
+		type AccountData = pallet_balances::AccountData<u64>;
+		type OnNewAccount = ();
+		type OnKilledAccount = ();
+		type SystemWeightInfo = ();
+		type SS58Prefix = ();
+		type OnSetCode = ();
+		type MaxConsumers = frame_support::traits::ConstU32<16>;
+	}
+
+	parameter_types! {
+		pub const ExistentialDeposit: u64 = 1;
+	}
+
+	impl pallet_balances::Config for Test {
+		type Balance = u64;
+		type Event = Event;
+		type DustRemoval = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type AccountStore = System;
+		type MaxLocks = ();
+		type MaxReserves = ();
+		type ReserveIdentifier = [u8; 8];
+		type WeightInfo = ();
+	}
+
+	parameter_types! {
+		pub const AssetDeposit: u64 = 0;
+		pub const MetadataDeposit: u64 = 0;
+		pub const ApprovalDeposit: u64 = 0;
+		pub const StringLimit: u32 = 16;
+	}
+
+	impl pallet_assets::Config for Test {
+		type Event = Event;
+		type Balance = u64;
+		type AssetId = u32;
+		type Currency = Balances;
+		type ForceOrigin = EnsureRoot<u64>;
+		type AssetDeposit = AssetDeposit;
+		type MetadataDepositBase = MetadataDeposit;
+		type MetadataDepositPerByte = MetadataDeposit;
+		type ApprovalDeposit = ApprovalDeposit;
+		type StringLimit = StringLimit;
+		type Freezer = ();
+		type Extra = ();
+		type WeightInfo = ();
+	}
+
+	parameter_types! {
+		pub const DexPalletId: PalletId = PalletId(*b"sel/dexp");
+		pub const SwapFee: Permill = Permill::from_percent(1);
+	}
+
+	impl Config for Test {
+		type Event = Event;
+		type AssetId = u32;
+		type Currency = Balances;
+		type Assets = Assets;
+		type PalletId = DexPalletId;
+		type SwapFee = SwapFee;
+		type WeightInfo = ();
+	}
+
+	const USD: u32 = 1;
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		pallet_balances::GenesisConfig::<Test> { balances: vec![(1, 100_000), (2, 100_000)] }
+			.assimilate_storage(&mut t)
+			.unwrap();
+		let mut ext = sp_io::TestExternalities::new(t);
+		ext.execute_with(|| {
+			assert_ok!(Assets::force_create(Origin::root(), USD, 1, true, 1));
+			assert_ok!(Assets::mint(Origin::signed(1), USD, 1, 100_000));
+			assert_ok!(Assets::mint(Origin::signed(1), USD, 2, 100_000));
+			assert_ok!(Dex::create_pool(Origin::signed(1), USD));
+		});
+		ext
+	}
+
+	#[test]
+	fn pools_are_only_created_once_for_registered_assets() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(Dex::create_pool(Origin::signed(1), 2), Error::<Test>::UnknownAsset);
+			assert_noop!(Dex::create_pool(Origin::signed(1), USD), Error::<Test>::PoolExists);
+		});
+	}
+
+	#[test]
+	fn liquidity_is_added_and_removed_in_the_ratio_of_the_reserves() {
+		new_test_ext().execute_with(|| {
+			// The first provider sets the price: 2 USD per SEL.
+			assert_ok!(Dex::add_liquidity(Origin::signed(1), USD, 10_000, 20_000));
+			assert_eq!(Dex::pool(USD), Some(Pool { sel: 10_000, asset: 20_000, shares: 10_000 }));
+
+			assert_noop!(
+				Dex::add_liquidity(Origin::signed(2), USD, 5_000, 9_999),
+				Error::<Test>::MaxAssetExceeded,
+			);
+			assert_ok!(Dex::add_liquidity(Origin::signed(2), USD, 5_000, 10_000));
+			assert_eq!(Shares::<Test>::get(USD, 2), 5_000);
+			assert_eq!(Balances::free_balance(Dex::pool_account(USD)), 15_000);
+			assert_eq!(Assets::balance(USD, Dex::pool_account(USD)), 30_000);
+
+			assert_noop!(
+				Dex::remove_liquidity(Origin::signed(2), USD, 5_001, 0, 0),
+				Error::<Test>::InsufficientShares,
+			);
+			assert_ok!(Dex::remove_liquidity(Origin::signed(2), USD, 5_000, 5_000, 10_000));
+			assert_eq!(Balances::free_balance(2), 100_000);
+			assert_eq!(Assets::balance(USD, 2), 100_000);
+
+			// The last provider empties the pool.
+			assert_ok!(Dex::remove_liquidity(Origin::signed(1), USD, 10_000, 0, 0));
+			assert_eq!(Dex::pool(USD), Some(Pool::default()));
+			assert_eq!(Balances::free_balance(Dex::pool_account(USD)), 0);
+		});
+	}
+
+	#[test]
+	fn swaps_keep_the_product_of_the_reserves_after_the_fee() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(
+				Dex::swap_sel_for_asset(Origin::signed(2), USD, 1_000, 0),
+				Error::<Test>::NoLiquidity,
+			);
+			assert_ok!(Dex::add_liquidity(Origin::signed(1), USD, 10_000, 20_000));
+
+			// 1% of the 1_000 SEL paid in is taken: 20_000 * 990 / 10_990 = 1_801.6 USD out.
+			assert_noop!(
+				Dex::swap_sel_for_asset(Origin::signed(2), USD, 1_000, 1_802),
+				Error::<Test>::MinOutNotMet,
+			);
+			assert_ok!(Dex::swap_sel_for_asset(Origin::signed(2), USD, 1_000, 1_801));
+			assert_eq!(Assets::balance(USD, 2), 101_801);
+			assert_eq!(Dex::pool(USD), Some(Pool { sel: 11_000, asset: 18_199, shares: 10_000 }));
+
+			// 11_000 * 1_782 / 19_981 = 981.03 SEL out.
+			assert_ok!(Dex::swap_asset_for_sel(Origin::signed(2), USD, 1_800, 981));
+			assert_eq!(Balances::free_balance(2), 99_981);
+			assert_eq!(Dex::pool(USD), Some(Pool { sel: 10_019, asset: 19_999, shares: 10_000 }));
+		});
+	}
+
+	#[test]
+	fn pool_price_converts_at_the_block_start_price() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			assert_noop!(PoolPrice::<Test>::to_asset_balance(100, 2), Error::<Test>::NoPool);
+			assert_ok!(Dex::add_liquidity(Origin::signed(1), USD, 10_000, 25_000));
+			// The pool had no liquidity at the start of the block.
+			assert_noop!(PoolPrice::<Test>::to_asset_balance(100, USD), Error::<Test>::NoLiquidity);
+
+			System::set_block_number(2);
+			assert_eq!(PoolPrice::<Test>::to_asset_balance(100, USD), Ok(250));
+			// Rounded up in favour of the pool.
+			assert_eq!(PoolPrice::<Test>::to_asset_balance(1, USD), Ok(3));
+
+			// Swaps in the block do not move the price until the next block.
+			assert_ok!(Dex::swap_sel_for_asset(Origin::signed(2), USD, 1_000, 0));
+			assert_eq!(PoolPrice::<Test>::to_asset_balance(100, USD), Ok(250));
+
+			// 100 * 22_748 / 11_000 = 206.8 USD.
+			System::set_block_number(3);
+			assert_eq!(PoolPrice::<Test>::to_asset_balance(100, USD), Ok(207));
+		});
+	}
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking {
+	use super::{Pallet as Dex, *};
+	use frame_benchmarking::{account, benchmarks, whitelisted_caller};
+	use frame_support::{
+		assert_ok,
+		traits::tokens::fungibles::{Create, Mutate},
+	};
+	use frame_system::RawOrigin;
+	use sp_runtime::traits::One;
+
+	const ASSET: u32 = 1;
+
+	/// The amount of SEL and of the asset the pool is seeded with.
+	fn amount<T: Config>() -> BalanceOf<T> {
+		T::Currency::minimum_balance().max(One::one()).saturating_mul(1_000u32.into())
+	}
+
+	fn fund<T: Config>(who: &T::AccountId, asset: T::AssetId)
+	where
+		T::Assets: Mutate<T::AccountId>,
+	{
+		let funds = amount::<T>().saturating_mul(10u32.into());
+		T::Currency::make_free_balance_be(who, funds);
+		assert_ok!(T::Assets::mint_into(asset, who, funds));
+	}
+
+	/// Register the asset and create a pool for it with liquidity in block 1.
+	fn pool_with_liquidity<T: Config>() -> T::AssetId
+	where
+		T::AssetId: From<u32>,
+		T::Assets: Create<T::AccountId> + Mutate<T::AccountId>,
+	{
+		let asset = T::AssetId::from(ASSET);
+		let provider: T::AccountId = account("provider", 0, 0);
+		frame_system::Pallet::<T>::set_block_number(One::one());
+		assert_ok!(T::Assets::create(asset, provider.clone(), true, One::one()));
+		fund::<T>(&provider, asset);
+		assert_ok!(Dex::<T>::create_pool(RawOrigin::Signed(provider.clone()).into(), asset));
+		let amount = amount::<T>();
+		assert_ok!(Dex::<T>::add_liquidity(
+			RawOrigin::Signed(provider).into(),
+			asset,
+			amount,
+			amount
+		));
+		asset
+	}
+
+	benchmarks! {
+		where_clause { where
+			T::AssetId: From<u32>,
+			T::Assets: Create<T::AccountId> + Mutate<T::AccountId>,
+		}
+
+		create_pool {
+			let caller: T::AccountId = whitelisted_caller();
+			let asset = T::AssetId::from(ASSET);
+			assert_ok!(T::Assets::create(asset, caller.clone(), true, One::one()));
+		}: _(RawOrigin::Signed(caller), asset)
+		verify {
+			assert!(Pools::<T>::contains_key(asset));
+		}
+
+		// All calls below change a pool with liquidity for the first time in a block, so its
+		// reserves at the start of the block are written.
+		add_liquidity {
+			let asset = pool_with_liquidity::<T>();
+			let caller: T::AccountId = whitelisted_caller();
+			fund::<T>(&caller, asset);
+			frame_system::Pallet::<T>::set_block_number(2u32.into());
+			let amount = amount::<T>();
+		}: _(RawOrigin::Signed(caller.clone()), asset, amount, amount)
+		verify {
+			assert_eq!(Shares::<T>::get(asset, &caller), amount);
+		}
+
+		remove_liquidity {
+			let asset = pool_with_liquidity::<T>();
+			let caller: T::AccountId = whitelisted_caller();
+			fund::<T>(&caller, asset);
+			let amount = amount::<T>();
+			let origin = RawOrigin::Signed(caller.clone());
+			Dex::<T>::add_liquidity(origin.into(), asset, amount, amount)?;
+			frame_system::Pallet::<T>::set_block_number(2u32.into());
+		}: _(RawOrigin::Signed(caller.clone()), asset, amount, Zero::zero(), Zero::zero())
+		verify {
+			assert!(Shares::<T>::get(asset, &caller).is_zero());
+		}
+
+		swap_sel_for_asset {
+			let asset = pool_with_liquidity::<T>();
+			let caller: T::AccountId = whitelisted_caller();
+			fund::<T>(&caller, asset);
+			frame_system::Pallet::<T>::set_block_number(2u32.into());
+			let amount = amount::<T>();
+		}: _(RawOrigin::Signed(caller), asset, amount, Zero::zero())
+		verify {
+			let (block, ..) = BlockStartReserves::<T>::get(asset).unwrap();
+			assert_eq!(block, 2u32.into());
+		}
+
+		swap_asset_for_sel {
+			let asset = pool_with_liquidity::<T>();
+			let caller: T::AccountId = whitelisted_caller();
+			fund::<T>(&caller, asset);
+			frame_system::Pallet::<T>::set_block_number(2u32.into());
+			let amount = amount::<T>();
+		}: _(RawOrigin::Signed(caller), asset, amount, Zero::zero())
+		verify {
+			let (block, ..) = BlockStartReserves::<T>::get(asset).unwrap();
+			assert_eq!(block, 2u32.into());
+		}
+	}
+}
@@ -17,13 +17,18 @@
 //! Code for elections.
 
 use super::{BlockExecutionWeight, BlockLength, BlockWeights};
-use frame_election_provider_support::{SortedListProvider, VoteWeight};
+use frame_election_provider_support::{
+	NposSolver, PhragMMS, SequentialPhragmen, SortedListProvider, VoteWeight,
+};
 use frame_support::{
 	parameter_types,
+	traits::Get,
 	weights::{DispatchClass, Weight},
 };
-use sp_runtime::Perbill;
-use sp_std::{boxed::Box, convert::From, marker::PhantomData};
+use primitives::election::{MinerSettings, MinerSolver, MINER_SETTINGS_KEY};
+use sp_npos_elections::{ElectionResult, ExtendedBalance, IdentifierT, PerThing128};
+use sp_runtime::{offchain::storage::StorageValueRef, Perbill};
+use sp_std::{boxed::Box, convert::From, marker::PhantomData, prelude::*};
 
 parameter_types! {
 	/// A limit for off-chain phragmen unsigned solution submission.
@@ -66,6 +71,53 @@ pub type OnOnChainAccuracy = sp_runtime::Perbill;
 pub type GenesisElectionOf<T> =
 	frame_election_provider_support::onchain::OnChainSequentialPhragmen<T>;
 
+/// The election miner settings the node stored in the offchain local storage, or the defaults if
+/// there are none or they cannot be decoded.
+///
+/// Reads the offchain local storage, so it must only be called from the offchain worker.
+pub fn miner_settings() -> MinerSettings {
+	StorageValueRef::persistent(MINER_SETTINGS_KEY)
+		.get::<MinerSettings>()
+		.ok()
+		.flatten()
+		.unwrap_or_default()
+}
+
+/// The balancing of the [`OffchainConfiguredSolver`], as configured by the [`miner_settings`].
+pub struct OffchainBalancing;
+impl Get<Option<(usize, ExtendedBalance)>> for OffchainBalancing {
+	fn get() -> Option<(usize, ExtendedBalance)> {
+		miner_settings().balancing()
+	}
+}
+
+/// The solver of the unsigned phase miner. Runs the solver and balancing chosen in the
+/// [`miner_settings`], so validators can trade solution quality against computation without a
+/// runtime upgrade.
+pub struct OffchainConfiguredSolver<AccountId, Accuracy>(PhantomData<(AccountId, Accuracy)>);
+impl<AccountId: IdentifierT, Accuracy: PerThing128> NposSolver
+	for OffchainConfiguredSolver<AccountId, Accuracy>
+{
+	type AccountId = AccountId;
+	type Accuracy = Accuracy;
+	type Error = sp_npos_elections::Error;
+
+	fn solve(
+		to_elect: usize,
+		targets: Vec<AccountId>,
+		voters: Vec<(AccountId, VoteWeight, impl IntoIterator<Item = AccountId>)>,
+	) -> Result<ElectionResult<AccountId, Accuracy>, Self::Error> {
+		match miner_settings().solver {
+			MinerSolver::SeqPhragmen =>
+				SequentialPhragmen::<AccountId, Accuracy, OffchainBalancing>::solve(
+					to_elect, targets, voters,
+				),
+			MinerSolver::PhragMMS =>
+				PhragMMS::<AccountId, Accuracy, OffchainBalancing>::solve(to_elect, targets, voters),
+		}
+	}
+}
+
 /// Implementation of `frame_election_provider_support::SortedListProvider` that updates the
 /// bags-list but uses [`pallet_staking::Nominators`] for `iter`. This is meant to be a transitionary
 /// implementation for runtimes to "test" out the bags-list by keeping it up to date, but not yet
@@ -121,3 +173,47 @@ impl<T: pallet_bags_list::Config + pallet_staking::Config> SortedListProvider<T:
 		pallet_bags_list::Pallet::<T>::weight_update_worst_case(who, is_increase)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::offchain::{testing::TestOffchainExt, OffchainDbExt, OffchainWorkerExt};
+
+	fn offchain_ext() -> sp_io::TestExternalities {
+		let (offchain, _) = TestOffchainExt::new();
+		let mut ext = sp_io::TestExternalities::default();
+		ext.register_extension(OffchainDbExt::new(offchain.clone()));
+		ext.register_extension(OffchainWorkerExt::new(offchain));
+		ext
+	}
+
+	#[test]
+	fn miner_settings_default_to_unbalanced_seq_phragmen() {
+		offchain_ext().execute_with(|| {
+			assert_eq!(miner_settings(), MinerSettings::default());
+			assert_eq!(miner_settings().solver, MinerSolver::SeqPhragmen);
+			assert_eq!(OffchainBalancing::get(), None);
+
+			StorageValueRef::persistent(MINER_SETTINGS_KEY).set(&b"garbage".to_vec());
+			assert_eq!(miner_settings(), MinerSettings::default());
+		});
+	}
+
+	#[test]
+	fn solver_follows_stored_miner_settings() {
+		offchain_ext().execute_with(|| {
+			let settings =
+				MinerSettings { solver: MinerSolver::PhragMMS, max_iterations: 10, tolerance: 5 };
+			StorageValueRef::persistent(MINER_SETTINGS_KEY).set(&settings);
+			assert_eq!(miner_settings(), settings);
+			assert_eq!(OffchainBalancing::get(), Some((10, 5)));
+
+			let targets = vec![10u64, 20, 30];
+			let voters =
+				vec![(1u64, 10, vec![10, 20]), (2, 20, vec![20, 30]), (3, 30, vec![10, 30])];
+			let result =
+				OffchainConfiguredSolver::<u64, Perbill>::solve(2, targets, voters).unwrap();
+			assert_eq!(result.winners.len(), 2);
+		});
+	}
+}
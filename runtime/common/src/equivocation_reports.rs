@@ -0,0 +1,296 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Pallet keeping track of the equivocation reports passed to the offences pallet.
+//!
+//! BABE and GRANDPA report equivocations through [`RecordReports`] instead of the offences pallet
+//! directly. It emits an event naming the reporters, who are rewarded by staking out of the slash
+//! of the offenders, and counts the accepted reports of every kind of offence. Accepted reports
+//! are remembered for `ReportWindow` blocks, so duplicates within the window are recognised with
+//! a single read before the offences pallet is consulted.
+
+use frame_support::pallet_prelude::*;
+use frame_system::pallet_prelude::*;
+pub use pallet::*;
+use sp_runtime::traits::{Hash, Saturating};
+use sp_staking::offence::{Kind, Offence, OffenceError, ReportOffence};
+use sp_std::prelude::*;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The number of blocks an accepted report is remembered for.
+		#[pallet::constant]
+		type ReportWindow: Get<Self::BlockNumber>;
+	}
+
+	/// The block each of the recently accepted reports is forgotten at, by the hash of its kind,
+	/// time slot and offenders.
+	#[pallet::storage]
+	pub type RecentReports<T: Config> = StorageMap<_, Identity, T::Hash, T::BlockNumber>;
+
+	/// The recently accepted reports forgotten at a block.
+	#[pallet::storage]
+	pub type ReportsExpiringAt<T: Config> =
+		StorageMap<_, Twox64Concat, T::BlockNumber, Vec<T::Hash>, ValueQuery>;
+
+	/// The number of accepted reports of every kind of offence.
+	#[pallet::storage]
+	pub type ReportCount<T: Config> = StorageMap<_, Twox64Concat, Kind, u32, ValueQuery>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// An offence of `kind` was reported by `reporters`, who share the reporter reward of its
+		/// slash.
+		EquivocationReported { kind: Kind, reporters: Vec<T::AccountId> },
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(now: BlockNumberFor<T>) -> Weight {
+			let expired = ReportsExpiringAt::<T>::take(now);
+			let count = expired.len() as Weight;
+			expired.into_iter().for_each(RecentReports::<T>::remove);
+			T::DbWeight::get().reads_writes(1, count.saturating_add(1))
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The number of accepted reports of every kind of offence.
+	pub fn report_counts() -> Vec<(Kind, u32)> {
+		ReportCount::<T>::iter().collect()
+	}
+
+	fn report_key<Offender: Encode, TimeSlot: Encode>(
+		kind: Kind,
+		offenders: &[Offender],
+		time_slot: &TimeSlot,
+	) -> T::Hash {
+		T::Hashing::hash_of(&(kind, time_slot, offenders))
+	}
+
+	fn note_report(kind: Kind, key: T::Hash, reporters: Vec<T::AccountId>) {
+		let expiry =
+			frame_system::Pallet::<T>::block_number().saturating_add(T::ReportWindow::get());
+		RecentReports::<T>::insert(key, expiry);
+		ReportsExpiringAt::<T>::append(expiry, key);
+		ReportCount::<T>::mutate(kind, |count| *count = count.saturating_add(1));
+		Self::deposit_event(Event::<T>::EquivocationReported { kind, reporters });
+	}
+}
+
+/// Reports offences to `R`, recording the accepted reports in [`Pallet`].
+pub struct RecordReports<T, R>(PhantomData<(T, R)>);
+
+impl<T, R, Offender, O> ReportOffence<T::AccountId, Offender, O> for RecordReports<T, R>
+where
+	T: Config,
+	R: ReportOffence<T::AccountId, Offender, O>,
+	Offender: Encode,
+	O: Offence<Offender>,
+{
+	fn report_offence(reporters: Vec<T::AccountId>, offence: O) -> Result<(), OffenceError> {
+		let key = Pallet::<T>::report_key(O::ID, &offence.offenders(), &offence.time_slot());
+		if RecentReports::<T>::contains_key(key) {
+			return Err(OffenceError::DuplicateReport)
+		}
+
+		R::report_offence(reporters.clone(), offence)?;
+		Pallet::<T>::note_report(O::ID, key, reporters);
+		Ok(())
+	}
+
+	fn is_known_offence(offenders: &[Offender], time_slot: &O::TimeSlot) -> bool {
+		RecentReports::<T>::contains_key(Pallet::<T>::report_key(O::ID, offenders, time_slot)) ||
+			R::is_known_offence(offenders, time_slot)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use crate::equivocation_reports;
+	use frame_support::{assert_noop, assert_ok, parameter_types, traits::OnInitialize};
+	use sp_core::H256;
+	use sp_runtime::{
+		testing::Header,
+		traits::{BlakeTwo256, IdentityLookup},
+		Perbill,
+	};
+	use sp_staking::SessionIndex;
+
+	type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+	type Block = frame_system::mocking::MockBlock<Test>;
+
+	frame_support::construct_runtime!(
+		pub enum Test where
+			Block = Block,
+			NodeBlock = Block,
+			UncheckedExtrinsic = UncheckedExtrinsic,
+		{
+			System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+			EquivocationReports: equivocation_reports::{Pallet, Storage, Event<T>},
+		}
+	);
+
+	parameter_types! {
+		pub const BlockHashCount: u32 = 250;
+	}
+
+	impl frame_system::Config for Test {
+		type BaseCallFilter = frame_support::traits::Everything;
+		type BlockWeights = ();
+		type BlockLength = ();
+		type DbWeight = ();
+		type Origin = Origin;
+		type Call = Call;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<u64>;
+		type Header = Header;
+		type Event = Event;
+		type BlockHashCount = BlockHashCount;
+		type Version = ();
+		type PalletInfo = PalletInfo;
+		type AccountData = ();
+		type OnNewAccount = ();
+		type OnKilledAccount = ();
+		type SystemWeightInfo = ();
+		type SS58Prefix = ();
+		type OnSetCode = ();
+		type MaxConsumers = frame_support::traits::ConstU32<16>;
+	}
+
+	parameter_types! {
+		pub const ReportWindow: u64 = 10;
+		pub static Reported: Vec<(Vec<u64>, Vec<u64>)> = Vec::new();
+	}
+
+	impl Config for Test {
+		type Event = Event;
+		type ReportWindow = ReportWindow;
+	}
+
+	const KIND: Kind = *b"test:equivocatio";
+
+	struct TestOffence {
+		offenders: Vec<u64>,
+		time_slot: u64,
+	}
+
+	impl Offence<u64> for TestOffence {
+		const ID: Kind = KIND;
+		type TimeSlot = u64;
+
+		fn offenders(&self) -> Vec<u64> {
+			self.offenders.clone()
+		}
+
+		fn session_index(&self) -> SessionIndex {
+			0
+		}
+
+		fn validator_set_count(&self) -> u32 {
+			10
+		}
+
+		fn time_slot(&self) -> u64 {
+			self.time_slot
+		}
+
+		fn slash_fraction(_: u32, _: u32) -> Perbill {
+			Perbill::zero()
+		}
+	}
+
+	/// Accepts every offence, keeping track of the reporters and offenders.
+	struct TestOffences;
+	impl ReportOffence<u64, u64, TestOffence> for TestOffences {
+		fn report_offence(reporters: Vec<u64>, offence: TestOffence) -> Result<(), OffenceError> {
+			let mut reported = Reported::get();
+			reported.push((reporters, offence.offenders));
+			Reported::set(reported);
+			Ok(())
+		}
+
+		fn is_known_offence(_: &[u64], _: &u64) -> bool {
+			false
+		}
+	}
+
+	type Reports = RecordReports<Test, TestOffences>;
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		let mut ext = sp_io::TestExternalities::new(t);
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+
+	fn offence(time_slot: u64) -> TestOffence {
+		TestOffence { offenders: vec![7], time_slot }
+	}
+
+	#[test]
+	fn accepted_reports_are_counted() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Reports::report_offence(vec![1], offence(1)));
+			System::assert_last_event(Event::EquivocationReports(
+				equivocation_reports::Event::EquivocationReported {
+					kind: KIND,
+					reporters: vec![1],
+				},
+			));
+			assert_ok!(Reports::report_offence(vec![2], offence(2)));
+
+			assert_eq!(Reported::get(), vec![(vec![1], vec![7]), (vec![2], vec![7])]);
+			assert_eq!(EquivocationReports::report_counts(), vec![(KIND, 2)]);
+		});
+	}
+
+	#[test]
+	fn duplicates_are_rejected_within_the_window() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Reports::report_offence(vec![1], offence(1)));
+			assert!(<Reports as ReportOffence<_, _, TestOffence>>::is_known_offence(&[7], &1));
+			assert_noop!(
+				Reports::report_offence(vec![2], offence(1)),
+				OffenceError::DuplicateReport
+			);
+			assert_eq!(Reported::get().len(), 1);
+
+			EquivocationReports::on_initialize(11);
+			assert!(!<Reports as ReportOffence<_, _, TestOffence>>::is_known_offence(&[7], &1));
+			assert!(!ReportsExpiringAt::<Test>::contains_key(11));
+		});
+	}
+}
@@ -0,0 +1,113 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Derivation of the [`EventsSchema`] of a runtime from its metadata, and splitting of the events
+//! of a block into [`BlockEvent`]s.
+
+use frame_support::metadata::{RuntimeMetadata, RuntimeMetadataPrefixed};
+use frame_system::{EventRecord, Phase};
+use parity_scale_codec::{Decode, Encode};
+use primitives::events::{
+	BlockEvent, EventPhase, EventsSchema, FieldSchema, PalletSchema, VariantSchema,
+};
+use scale_info::{PortableRegistry, TypeDef};
+use sp_std::prelude::*;
+
+/// The variants of the enum with type `id` in `types`, or none if it is not an enum.
+fn variants(types: &PortableRegistry, id: u32) -> Vec<VariantSchema> {
+	let variants = match types.resolve(id).map(|ty| ty.type_def()) {
+		Some(TypeDef::Variant(def)) => def.variants(),
+		_ => return Vec::new(),
+	};
+
+	variants
+		.iter()
+		.map(|variant| VariantSchema {
+			index: variant.index(),
+			name: variant.name().clone(),
+			fields: variant
+				.fields()
+				.iter()
+				.map(|field| FieldSchema {
+					name: field.name().cloned(),
+					type_name: field.type_name().cloned(),
+				})
+				.collect(),
+		})
+		.collect()
+}
+
+/// The events and errors described by the `metadata` of a runtime with `spec_version`.
+///
+/// Pallets without events and errors are left out. Only V14 metadata carries the types needed, so
+/// older metadata results in a schema without pallets.
+pub fn events_schema(metadata: RuntimeMetadataPrefixed, spec_version: u32) -> EventsSchema {
+	let metadata = match metadata.1 {
+		RuntimeMetadata::V14(metadata) => metadata,
+		_ => return EventsSchema { spec_version, pallets: Vec::new() },
+	};
+
+	let mut pallets: Vec<_> = metadata
+		.pallets
+		.iter()
+		.map(|pallet| PalletSchema {
+			index: pallet.index,
+			name: pallet.name.clone(),
+			events: pallet
+				.event
+				.as_ref()
+				.map_or_else(Vec::new, |event| variants(&metadata.types, event.ty.id())),
+			errors: pallet
+				.error
+				.as_ref()
+				.map_or_else(Vec::new, |error| variants(&metadata.types, error.ty.id())),
+		})
+		.filter(|pallet| !pallet.events.is_empty() || !pallet.errors.is_empty())
+		.collect();
+	pallets.sort_by_key(|pallet| pallet.index);
+
+	EventsSchema { spec_version, pallets }
+}
+
+/// Split `events`, the encoded `System::Events` of a block, into the [`BlockEvent`]s it holds.
+///
+/// Returns `None` if `events` do not decode as the events of runtime `T`.
+pub fn block_events<T: frame_system::Config>(events: &[u8]) -> Option<Vec<BlockEvent>> {
+	let records =
+		Vec::<EventRecord<<T as frame_system::Config>::Event, T::Hash>>::decode(&mut &events[..])
+			.ok()?;
+
+	records
+		.into_iter()
+		.map(|record| {
+			let phase = match record.phase {
+				Phase::ApplyExtrinsic(index) => EventPhase::ApplyExtrinsic(index),
+				Phase::Finalization => EventPhase::Finalization,
+				Phase::Initialization => EventPhase::Initialization,
+			};
+			// The outer event enum is encoded as the pallet index followed by the pallet event.
+			match record.event.encode().as_slice() {
+				[pallet, variant, fields @ ..] => Some(BlockEvent {
+					phase,
+					pallet: *pallet,
+					variant: *variant,
+					fields: fields.to_vec(),
+				}),
+				_ => None,
+			}
+		})
+		.collect()
+}
@@ -0,0 +1,459 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Pallet keeping track of the fee multiplier of `pallet-transaction-payment`.
+//!
+//! The multiplier every block is executed with, along with the weight and the fees of the block,
+//! is recorded in a ring buffer of the last `HistoryDepth` blocks. Fees are only recorded if the
+//! runtime charges them through [`RecordFees`]. The block fullness targeted by
+//! [`crate::SlowAdjustingFeeUpdate`] can be changed by governance.
+
+use frame_support::{
+	pallet_prelude::*,
+	traits::{Imbalance, OnUnbalanced},
+	weights::GetDispatchInfo,
+};
+use frame_system::pallet_prelude::*;
+pub use pallet::*;
+use pallet_transaction_payment::{BalanceOf, Multiplier};
+use primitives::fees::{BlockFees, StandardTransaction};
+use sp_runtime::{
+	traits::{Saturating, StaticLookup, TrailingZeroInput, UniqueSaturatedInto, Zero},
+	FixedPointOperand, Perquintill,
+};
+use sp_std::prelude::*;
+
+/// The approximate length of the address, signature and signed extensions which turn a call into
+/// a signed extrinsic.
+pub const SIGNED_EXTRINSIC_OVERHEAD: u32 = 110;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + pallet_transaction_payment::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The number of blocks the multiplier is recorded for.
+		#[pallet::constant]
+		type HistoryDepth: Get<u32>;
+
+		/// The origin which may set the targeted block fullness.
+		type FullnessOrigin: EnsureOrigin<Self::Origin>;
+	}
+
+	#[pallet::type_value]
+	pub fn DefaultTargetBlockFullness() -> Perquintill {
+		crate::TargetBlockFullness::get()
+	}
+
+	/// The portion of the normal dispatch class the fee multiplier adjusts block fullness
+	/// towards. Blocks filled less than this decrease the multiplier, fuller blocks increase it.
+	#[pallet::storage]
+	#[pallet::getter(fn target_block_fullness)]
+	pub type TargetBlockFullness<T> =
+		StorageValue<_, Perquintill, ValueQuery, DefaultTargetBlockFullness>;
+
+	/// The multiplier recent blocks were executed with, by block number modulo `HistoryDepth`.
+	#[pallet::storage]
+	pub type MultiplierHistory<T: Config> =
+		StorageMap<_, Twox64Concat, u32, (T::BlockNumber, Multiplier), OptionQuery>;
+
+	/// The weight and fees of recent blocks, by block number modulo `HistoryDepth`.
+	#[pallet::storage]
+	pub type UsageHistory<T: Config> =
+		StorageMap<_, Twox64Concat, u32, (T::BlockNumber, Weight, BalanceOf<T>), OptionQuery>;
+
+	/// The fees and tips paid in the current block so far.
+	#[pallet::storage]
+	pub type CurrentBlockFees<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The targeted block fullness was changed.
+		TargetBlockFullnessSet { fullness: Perquintill },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The multiplier would only ever grow with a target of empty blocks.
+		ZeroTargetFullness,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(now: BlockNumberFor<T>) -> Weight {
+			// The multiplier is updated when the previous block was finalized, so this is the one
+			// the extrinsics of `now` pay with.
+			let multiplier = pallet_transaction_payment::Pallet::<T>::next_fee_multiplier();
+			MultiplierHistory::<T>::insert(Self::history_slot(now), (now, multiplier));
+			// Includes recording the usage of the block in `on_finalize`.
+			T::DbWeight::get().reads_writes(3, 3)
+		}
+
+		fn on_finalize(now: BlockNumberFor<T>) {
+			let weight = frame_system::Pallet::<T>::block_weight().total();
+			let fees = CurrentBlockFees::<T>::take();
+			UsageHistory::<T>::insert(Self::history_slot(now), (now, weight, fees));
+		}
+
+		fn integrity_test() {
+			assert!(T::HistoryDepth::get() > 0, "`HistoryDepth` must not be zero");
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Set the portion of the normal dispatch class the fee multiplier adjusts block fullness
+		/// towards.
+		///
+		/// The origin must be `FullnessOrigin`.
+		#[pallet::weight(T::DbWeight::get().writes(1))]
+		pub fn set_target_block_fullness(
+			origin: OriginFor<T>,
+			fullness: Perquintill,
+		) -> DispatchResult {
+			T::FullnessOrigin::ensure_origin(origin)?;
+			ensure!(!fullness.is_zero(), Error::<T>::ZeroTargetFullness);
+
+			TargetBlockFullness::<T>::put(fullness);
+			Self::deposit_event(Event::<T>::TargetBlockFullnessSet { fullness });
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The multiplier each of the last `HistoryDepth` blocks was executed with, oldest first.
+	pub fn multiplier_history() -> Vec<(T::BlockNumber, Multiplier)> {
+		let now = frame_system::Pallet::<T>::block_number();
+		let depth = T::BlockNumber::from(T::HistoryDepth::get());
+		// Slots beyond the depth are left behind if it was lowered.
+		let mut history: Vec<_> = MultiplierHistory::<T>::iter_values()
+			.filter(|(block, _)| now.saturating_sub(*block) < depth)
+			.collect();
+		history.sort_by_key(|(block, _)| *block);
+		history
+	}
+
+	/// The multiplier, weight and fees of at most `count` of the last `HistoryDepth` blocks,
+	/// oldest first.
+	pub fn fee_history(count: u32) -> Vec<BlockFees<T::BlockNumber, BalanceOf<T>>> {
+		let history: Vec<_> = Self::multiplier_history()
+			.into_iter()
+			.filter_map(|(block, multiplier)| {
+				let (recorded, weight, fees) = UsageHistory::<T>::get(Self::history_slot(block))?;
+				(recorded == block).then(|| BlockFees { block, multiplier, weight, fees })
+			})
+			.collect();
+		let skip = history.len().saturating_sub(count as usize);
+		history.into_iter().skip(skip).collect()
+	}
+
+	fn note_fees(amount: BalanceOf<T>) {
+		CurrentBlockFees::<T>::mutate(|fees| *fees = fees.saturating_add(amount));
+	}
+
+	fn history_slot(block: T::BlockNumber) -> u32 {
+		let block: u32 = block.unique_saturated_into();
+		block % T::HistoryDepth::get()
+	}
+}
+
+/// The block fullness targeted by the fee multiplier, as set in [`Pallet`].
+pub struct TargetBlockFullnessOf<T>(PhantomData<T>);
+
+impl<T: Config> Get<Perquintill> for TargetBlockFullnessOf<T> {
+	fn get() -> Perquintill {
+		Pallet::<T>::target_block_fullness()
+	}
+}
+
+/// Records the fees and tips of the current block in [`Pallet`], and passes them on to `OU`.
+pub struct RecordFees<T, OU>(PhantomData<(T, OU)>);
+
+impl<T, I, OU> OnUnbalanced<I> for RecordFees<T, OU>
+where
+	T: Config,
+	I: Imbalance<BalanceOf<T>>,
+	OU: OnUnbalanced<I>,
+{
+	fn on_unbalanceds<B>(amounts: impl Iterator<Item = I>) {
+		let amounts: Vec<_> = amounts.collect();
+		amounts.iter().for_each(|amount| Pallet::<T>::note_fees(amount.peek()));
+		OU::on_unbalanceds(amounts.into_iter());
+	}
+
+	fn on_nonzero_unbalanced(amount: I) {
+		Pallet::<T>::note_fees(amount.peek());
+		OU::on_nonzero_unbalanced(amount);
+	}
+}
+
+/// The fee `call` would pay without tip in the next block, submitted as a signed extrinsic.
+pub fn projected_fee<T, C>(call: &C) -> BalanceOf<T>
+where
+	T: pallet_transaction_payment::Config,
+	BalanceOf<T>: FixedPointOperand,
+	C: GetDispatchInfo + Encode,
+{
+	let len = (call.encoded_size() as u32).saturating_add(SIGNED_EXTRINSIC_OVERHEAD);
+	pallet_transaction_payment::Pallet::<T>::compute_fee(
+		len,
+		&call.get_dispatch_info(),
+		Zero::zero(),
+	)
+}
+
+/// The fee each of the [`StandardTransaction`]s would pay without tip in the next block.
+pub fn projected_fees<T>() -> Vec<(StandardTransaction, BalanceOf<T>)>
+where
+	T: pallet_transaction_payment::Config + pallet_balances::Config + pallet_staking::Config,
+	<T as frame_system::Config>::Call: From<frame_system::Call<T>>
+		+ From<pallet_balances::Call<T>>
+		+ From<pallet_staking::Call<T>>
+		+ GetDispatchInfo
+		+ Encode,
+	BalanceOf<T>: FixedPointOperand,
+{
+	let account =
+		T::AccountId::decode(&mut TrailingZeroInput::zeroes()).expect("infinite input; qed");
+	let account = <T as frame_system::Config>::Lookup::unlookup(account);
+	let calls: [(StandardTransaction, <T as frame_system::Config>::Call); 3] = [
+		(
+			StandardTransaction::Transfer,
+			pallet_balances::Call::<T>::transfer_keep_alive {
+				dest: account.clone(),
+				value: Zero::zero(),
+			}
+			.into(),
+		),
+		(
+			StandardTransaction::Nominate,
+			pallet_staking::Call::<T>::nominate { targets: vec![account] }.into(),
+		),
+		(
+			StandardTransaction::Remark,
+			frame_system::Call::<T>::remark { remark: vec![0; 32] }.into(),
+		),
+	];
+	calls
+		.into_iter()
+		.map(|(transaction, call)| (transaction, projected_fee::<T, _>(&call)))
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use frame_support::{
+		assert_noop, assert_ok, parameter_types,
+		traits::{Currency, Everything, OnFinalize, OnInitialize},
+		weights::IdentityFee,
+	};
+	use frame_system::EnsureRoot;
+	use pallet_transaction_payment::CurrencyAdapter;
+	use sp_core::H256;
+	use sp_runtime::{
+		testing::Header,
+		traits::{BlakeTwo256, Convert, IdentityLookup, One},
+		DispatchError::BadOrigin,
+		FixedPointNumber,
+	};
+
+	type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+	type Block = frame_system::mocking::MockBlock<Test>;
+
+	frame_support::construct_runtime!(
+		pub enum Test where
+			Block = Block,
+			NodeBlock = Block,
+			UncheckedExtrinsic = UncheckedExtrinsic,
+		{
+			System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+			Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+			TransactionPayment: pallet_transaction_payment::{Pallet, Storage},
+			FeeMultiplier: crate::fee_multiplier::{Pallet, Call, Storage, Event<T>},
+		}
+	);
+
+	parameter_types! {
+		pub const BlockHashCount: u32 = 250;
+		pub BlockWeights: frame_system::limits::BlockWeights =
+			frame_system::limits::BlockWeights::simple_max(1024);
+	}
+
+	impl frame_system::Config for Test {
+		type BaseCallFilter = Everything;
+		type BlockWeights = BlockWeights;
+		type BlockLength = ();
+		type DbWeight = ();
+		type Origin = Origin;
+		type Call = Call;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<u64>;
+		type Header = Header;
+		type Event = Event;
+		type BlockHashCount = BlockHashCount;
+		type Version = ();
+		type PalletInfo = PalletInfo;
+		type AccountData = pallet_balances::AccountData<u64>;
+		type OnNewAccount = ();
+		type OnKilledAccount = ();
+		type SystemWeightInfo = ();
+		type SS58Prefix = ();
+		type OnSetCode = ();
+		type MaxConsumers = frame_support::traits::ConstU32<16>;
+	}
+
+	parameter_types! {
+		pub const ExistentialDeposit: u64 = 1;
+	}
+
+	impl pallet_balances::Config for Test {
+		type Balance = u64;
+		type Event = Event;
+		type DustRemoval = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type AccountStore = System;
+		type MaxLocks = ();
+		type MaxReserves = ();
+		type ReserveIdentifier = [u8; 8];
+		type WeightInfo = ();
+	}
+
+	parameter_types! {
+		pub const TransactionByteFee: u64 = 1;
+		pub const OperationalFeeMultiplier: u8 = 5;
+		pub const HistoryDepth: u32 = 4;
+	}
+
+	impl pallet_transaction_payment::Config for Test {
+		type OnChargeTransaction = CurrencyAdapter<Balances, RecordFees<Test, ()>>;
+		type TransactionByteFee = TransactionByteFee;
+		type OperationalFeeMultiplier = OperationalFeeMultiplier;
+		type WeightToFee = IdentityFee<u64>;
+		type FeeMultiplierUpdate = crate::SlowAdjustingFeeUpdate<Self>;
+	}
+
+	impl Config for Test {
+		type Event = Event;
+		type HistoryDepth = HistoryDepth;
+		type FullnessOrigin = EnsureRoot<u64>;
+	}
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		frame_system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
+	}
+
+	fn multiplier(n: u64) -> Multiplier {
+		Multiplier::saturating_from_rational(n, 10)
+	}
+
+	#[test]
+	fn history_keeps_the_last_blocks() {
+		new_test_ext().execute_with(|| {
+			for block in 1..=6 {
+				System::set_block_number(block);
+				pallet_transaction_payment::NextFeeMultiplier::<Test>::put(multiplier(block));
+				FeeMultiplier::on_initialize(block);
+			}
+
+			assert_eq!(
+				FeeMultiplier::multiplier_history(),
+				(3..=6).map(|block| (block, multiplier(block))).collect::<Vec<_>>(),
+			);
+		});
+	}
+
+	#[test]
+	fn history_records_the_weight_and_fees_of_blocks() {
+		new_test_ext().execute_with(|| {
+			for block in 1..=3 {
+				System::set_block_number(block);
+				pallet_transaction_payment::NextFeeMultiplier::<Test>::put(multiplier(block));
+				FeeMultiplier::on_initialize(block);
+
+				System::set_block_consumed_resources(block * 10, 0);
+				let fees_then_tips = vec![Balances::issue(block * 100), Balances::issue(1)];
+				RecordFees::<Test, ()>::on_unbalanceds(fees_then_tips.into_iter());
+				FeeMultiplier::on_finalize(block);
+			}
+
+			let block_fees = |block| BlockFees {
+				block,
+				multiplier: multiplier(block),
+				weight: block * 10,
+				fees: block * 100 + 1,
+			};
+			assert_eq!(FeeMultiplier::fee_history(2), vec![block_fees(2), block_fees(3)]);
+			assert_eq!(FeeMultiplier::fee_history(10).len(), 3);
+			assert_eq!(CurrentBlockFees::<Test>::get(), 0);
+		});
+	}
+
+	#[test]
+	fn target_block_fullness_is_set_by_governance() {
+		new_test_ext().execute_with(|| {
+			assert_eq!(FeeMultiplier::target_block_fullness(), crate::TargetBlockFullness::get());
+
+			let fullness = Perquintill::from_percent(50);
+			assert_noop!(
+				FeeMultiplier::set_target_block_fullness(Origin::signed(1), fullness),
+				BadOrigin,
+			);
+			assert_noop!(
+				FeeMultiplier::set_target_block_fullness(Origin::root(), Perquintill::zero()),
+				Error::<Test>::ZeroTargetFullness,
+			);
+			assert_ok!(FeeMultiplier::set_target_block_fullness(Origin::root(), fullness));
+			assert_eq!(TargetBlockFullnessOf::<Test>::get(), fullness);
+
+			// A block filled to the old target now lowers the multiplier.
+			let target = crate::TargetBlockFullness::get() *
+				BlockWeights::get().get(DispatchClass::Normal).max_total.unwrap();
+			System::set_block_consumed_resources(target, 0);
+			let next = crate::SlowAdjustingFeeUpdate::<Test>::convert(Multiplier::one());
+			assert!(next < Multiplier::one());
+		});
+	}
+
+	#[test]
+	fn projected_fee_includes_the_extrinsic_overhead() {
+		new_test_ext().execute_with(|| {
+			let call = Call::System(frame_system::Call::remark { remark: vec![0; 32] });
+			let info = call.get_dispatch_info();
+			let len = call.encoded_size() as u32 + SIGNED_EXTRINSIC_OVERHEAD;
+
+			assert_eq!(
+				projected_fee::<Test, _>(&call),
+				TransactionPayment::compute_fee(len, &info, 0),
+			);
+		});
+	}
+}
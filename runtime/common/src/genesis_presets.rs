@@ -0,0 +1,118 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Helpers for the genesis config presets of the runtimes.
+//!
+//! The runtimes build the genesis configs of their development and test chains themselves, so
+//! chain specs for them can be generated without the node knowing the genesis config of every
+//! pallet. Presets are only available with `std`, as `GenesisConfig` is.
+
+use primitives::v1::{AccountId, AccountPublic, BlockNumber, MAX_CODE_SIZE, MAX_POV_SIZE};
+use runtime_parachains::configuration::HostConfiguration;
+use sp_core::{sr25519, Pair, Public};
+use sp_runtime::traits::IdentifyAccount;
+
+/// A chain with the single validator Alice.
+pub const DEVELOPMENT: &str = "development";
+/// A local chain with the validators Alice and Bob.
+pub const LOCAL_TESTNET: &str = "local_testnet";
+/// The names of the presets every runtime provides.
+pub const PRESET_NAMES: &[&str] = &[DEVELOPMENT, LOCAL_TESTNET];
+
+/// Generate a crypto pair from seed and return its public key.
+pub fn get_from_seed<TPublic: Public>(seed: &str) -> <TPublic::Pair as Pair>::Public {
+	TPublic::Pair::from_string(&format!("//{}", seed), None)
+		.expect("static values are valid; qed")
+		.public()
+}
+
+/// Generate an account ID from seed.
+pub fn get_account_id_from_seed<TPublic: Public>(seed: &str) -> AccountId
+where
+	AccountPublic: From<<TPublic::Pair as Pair>::Public>,
+{
+	AccountPublic::from(get_from_seed::<TPublic>(seed)).into_account()
+}
+
+/// The well-known accounts endowed on test chains.
+pub fn testnet_accounts() -> Vec<AccountId> {
+	let names = ["Alice", "Bob", "Charlie", "Dave", "Eve", "Ferdie"];
+	names
+		.iter()
+		.map(|name| name.to_string())
+		.chain(names.iter().map(|name| format!("{}//stash", name)))
+		.map(|seed| get_account_id_from_seed::<sr25519::Public>(&seed))
+		.collect()
+}
+
+/// The parachains host configuration of test chains.
+pub fn default_parachains_host_configuration() -> HostConfiguration<BlockNumber> {
+	HostConfiguration {
+		validation_upgrade_cooldown: 2u32,
+		validation_upgrade_delay: 2,
+		code_retention_period: 1200,
+		max_code_size: MAX_CODE_SIZE,
+		max_pov_size: MAX_POV_SIZE,
+		max_head_data_size: 32 * 1024,
+		group_rotation_frequency: 20,
+		chain_availability_period: 4,
+		thread_availability_period: 4,
+		max_upward_queue_count: 8,
+		max_upward_queue_size: 1024 * 1024,
+		max_downward_message_size: 1024 * 1024,
+		ump_service_total_weight: 100_000_000_000,
+		max_upward_message_size: 50 * 1024,
+		max_upward_message_num_per_candidate: 5,
+		hrmp_sender_deposit: 0,
+		hrmp_recipient_deposit: 0,
+		hrmp_channel_max_capacity: 8,
+		hrmp_channel_max_total_size: 8 * 1024,
+		hrmp_max_parachain_inbound_channels: 4,
+		hrmp_max_parathread_inbound_channels: 4,
+		hrmp_channel_max_message_size: 1024 * 1024,
+		hrmp_max_parachain_outbound_channels: 4,
+		hrmp_max_parathread_outbound_channels: 4,
+		hrmp_max_message_num_per_candidate: 5,
+		dispute_period: 6,
+		no_show_slots: 2,
+		n_delay_tranches: 25,
+		needed_approvals: 2,
+		relay_vrf_modulo_samples: 2,
+		zeroth_delay_tranche_width: 0,
+		minimum_validation_upgrade_delay: 5,
+		..Default::default()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn default_parachains_host_configuration_is_consistent() {
+		default_parachains_host_configuration().panic_if_not_consistent();
+	}
+
+	#[test]
+	fn testnet_accounts_are_distinct() {
+		let mut accounts = testnet_accounts();
+		assert_eq!(accounts.len(), 12);
+		assert_eq!(accounts[6], get_account_id_from_seed::<sr25519::Public>("Alice//stash"));
+		accounts.sort();
+		accounts.dedup();
+		assert_eq!(accounts.len(), 12);
+	}
+}
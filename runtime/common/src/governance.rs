@@ -0,0 +1,176 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime API exporting the whole governance pipeline, from council motions to referenda to
+//! scheduled enactments, so that dashboards can render it from a single call per block.
+
+use crate::democracy::DEMOCRACY_ID;
+use frame_support::traits::schedule::{MaybeHashed, Period, Priority};
+use pallet_collective::Votes;
+use pallet_democracy::{ReferendumIndex, ReferendumInfo, ReferendumStatus};
+use parity_scale_codec::{Codec, Decode, Encode};
+use scale_info::TypeInfo;
+use sp_runtime::{traits::Hash as HashT, RuntimeDebug};
+use sp_std::prelude::*;
+
+/// A referendum that is being voted on.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct ActiveReferendum<Balance, BlockNumber, Hash> {
+	/// The index of the referendum.
+	pub index: ReferendumIndex,
+	/// The proposal, threshold, end and tally of the referendum.
+	pub status: ReferendumStatus<BlockNumber, Hash, Balance>,
+}
+
+/// A motion of the council that is being voted on.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct CouncilMotion<AccountId, BlockNumber, Hash> {
+	/// The hash of the proposal.
+	pub hash: Hash,
+	/// The index, threshold, end and the members who voted aye and nay.
+	pub votes: Votes<AccountId, BlockNumber>,
+}
+
+/// A task in the agenda of `pallet-scheduler`.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct ScheduledTask<BlockNumber, Hash> {
+	/// The block the task is due in.
+	pub when: BlockNumber,
+	/// The index of the task in the agenda of that block.
+	pub index: u32,
+	/// The name of the task, if it was scheduled as a named task.
+	pub id: Option<Vec<u8>>,
+	/// The referendum enacted by the task, if it is a democracy enactment.
+	pub referendum: Option<ReferendumIndex>,
+	/// The priority of the task.
+	pub priority: Priority,
+	/// The hash of the call dispatched by the task.
+	pub call_hash: Hash,
+	/// The period and remaining repetitions of the task, if it is periodic.
+	pub periodic: Option<Period<BlockNumber>>,
+}
+
+/// The state of the governance pipeline at a block.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct GovernanceActivity<AccountId, Balance, BlockNumber, Hash> {
+	/// The ongoing referenda, by index.
+	pub referenda: Vec<ActiveReferendum<Balance, BlockNumber, Hash>>,
+	/// The open council motions, in the order they were proposed.
+	pub council_motions: Vec<CouncilMotion<AccountId, BlockNumber, Hash>>,
+	/// The tasks due in this or later blocks, by block and index.
+	pub scheduled: Vec<ScheduledTask<BlockNumber, Hash>>,
+}
+
+sp_api::decl_runtime_apis! {
+	/// The API for exporting the activity of democracy, the council and the scheduler.
+	pub trait GovernanceApi<AccountId, Balance, BlockNumber, Hash> where
+		AccountId: Codec,
+		Balance: Codec,
+		BlockNumber: Codec,
+		Hash: Codec,
+	{
+		/// Get the ongoing referenda, open council motions and upcoming scheduled tasks.
+		fn governance_activity() -> GovernanceActivity<AccountId, Balance, BlockNumber, Hash>;
+	}
+}
+
+type BalanceOf<T> = <T as pallet_balances::Config>::Balance;
+
+/// The fields of a scheduled task, which `pallet-scheduler` keeps private. The task encodes as
+/// these fields in order, followed by the origin.
+type TaskFieldsOf<T> = (
+	Option<Vec<u8>>,
+	Priority,
+	MaybeHashed<<T as pallet_scheduler::Config>::Call, <T as frame_system::Config>::Hash>,
+	Option<Period<<T as frame_system::Config>::BlockNumber>>,
+);
+
+/// Collect the governance activity from the state of `pallet-democracy`, the council instance `I`
+/// of `pallet-collective` and `pallet-scheduler`.
+pub fn governance_activity<T, I>(
+) -> GovernanceActivity<T::AccountId, BalanceOf<T>, T::BlockNumber, T::Hash>
+where
+	T: pallet_democracy::Config<Currency = pallet_balances::Pallet<T>>
+		+ pallet_balances::Config
+		+ pallet_collective::Config<I>
+		+ pallet_scheduler::Config,
+	I: 'static,
+{
+	let mut referenda: Vec<_> = pallet_democracy::ReferendumInfoOf::<T>::iter()
+		.filter_map(|(index, info)| match info {
+			ReferendumInfo::Ongoing(status) => Some(ActiveReferendum { index, status }),
+			ReferendumInfo::Finished { .. } => None,
+		})
+		.collect();
+	referenda.sort_by_key(|referendum| referendum.index);
+
+	let council_motions = pallet_collective::Pallet::<T, I>::proposals()
+		.into_iter()
+		.filter_map(|hash| {
+			pallet_collective::Pallet::<T, I>::voting(&hash)
+				.map(|votes| CouncilMotion { hash, votes })
+		})
+		.collect();
+
+	let now = <frame_system::Pallet<T>>::block_number();
+	let mut scheduled: Vec<_> = pallet_scheduler::Agenda::<T>::iter()
+		.filter(|(when, _)| *when >= now)
+		.flat_map(|(when, agenda)| {
+			agenda.into_iter().enumerate().filter_map(move |(index, task)| {
+				let (id, priority, call, periodic) =
+					TaskFieldsOf::<T>::decode(&mut &task?.encode()[..]).ok()?;
+				let call_hash = match call {
+					MaybeHashed::Value(call) => T::Hashing::hash_of(&call),
+					MaybeHashed::Hash(hash) => hash,
+				};
+				Some(ScheduledTask {
+					when,
+					index: index as u32,
+					referendum: id.as_deref().and_then(enacted_referendum),
+					id,
+					priority,
+					call_hash,
+					periodic,
+				})
+			})
+		})
+		.collect();
+	scheduled.sort_by_key(|task| (task.when, task.index));
+
+	GovernanceActivity { referenda, council_motions, scheduled }
+}
+
+/// The referendum enacted by a task named `id`, if it is a democracy enactment. `pallet-democracy`
+/// names these tasks after its lock identifier and the referendum index.
+fn enacted_referendum(id: &[u8]) -> Option<ReferendumIndex> {
+	match <([u8; 8], ReferendumIndex)>::decode(&mut &id[..]) {
+		Ok((prefix, index)) if prefix == DEMOCRACY_ID && id.len() == 12 => Some(index),
+		_ => None,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn democracy_enactments_are_recognised() {
+		assert_eq!(enacted_referendum(&(DEMOCRACY_ID, 7u32).encode()), Some(7));
+		assert_eq!(enacted_referendum(&(*b"othertsk", 7u32).encode()), None);
+		assert_eq!(enacted_referendum(&(DEMOCRACY_ID, 7u64).encode()), None);
+		assert_eq!(enacted_referendum(b"democrac"), None);
+	}
+}
@@ -0,0 +1,314 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Pallet to wind down `pallet-indices`.
+//!
+//! Account indices are not used for address lookup by our runtimes, so the deposits held for
+//! them only lock up funds. Once new claims are filtered out by the runtime, this pallet
+//! gradually releases every index and refunds its deposit to the owner, either in the idle
+//! time of blocks or through `refund_indices`, which anyone may call to speed things up.
+
+use frame_support::{
+	pallet_prelude::*,
+	traits::{Currency, ReservableCurrency},
+};
+use frame_system::pallet_prelude::*;
+pub use pallet::*;
+
+type BalanceOf<T> = <<T as pallet_indices::Config>::Currency as Currency<
+	<T as frame_system::Config>::AccountId,
+>>::Balance;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + pallet_indices::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The maximum number of indices released in the idle time of a single block.
+		#[pallet::constant]
+		type MaxRefundsPerBlock: Get<u32>;
+	}
+
+	/// Whether all indices have been released.
+	#[pallet::storage]
+	pub type Cleared<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// An index was released and its deposit returned to the owner.
+		IndexRefunded { index: T::AccountIndex, who: T::AccountId, deposit: BalanceOf<T> },
+		/// All indices have been released.
+		IndicesCleared,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_idle(_: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+			let overhead = Self::overhead_weight();
+			if remaining_weight < overhead {
+				return 0
+			}
+
+			let max = (remaining_weight - overhead)
+				.checked_div(Self::refund_weight())
+				.unwrap_or(Weight::max_value())
+				.min(T::MaxRefundsPerBlock::get() as Weight);
+			Self::do_refund(max as u32).min(remaining_weight)
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Release up to `max` indices, returning their deposits to their owners.
+		///
+		/// Can be called by any signed origin. Only the weight of the indices actually released
+		/// is charged.
+		#[pallet::weight(
+			Pallet::<T>::refund_weight()
+				.saturating_mul(*max as Weight)
+				.saturating_add(Pallet::<T>::overhead_weight())
+		)]
+		pub fn refund_indices(origin: OriginFor<T>, max: u32) -> DispatchResultWithPostInfo {
+			ensure_signed(origin)?;
+
+			Ok(Some(Self::do_refund(max)).into())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The weight of releasing a single index: the index itself and the owner's account are both
+	/// read and written.
+	fn refund_weight() -> Weight {
+		T::DbWeight::get().reads_writes(2, 2)
+	}
+
+	/// The weight of a batch of refunds besides the indices released: reading `Cleared` and the
+	/// first index left, and setting `Cleared`.
+	fn overhead_weight() -> Weight {
+		T::DbWeight::get().reads_writes(2, 1)
+	}
+
+	/// Release up to `max` indices, returning the weight used.
+	fn do_refund(max: u32) -> Weight {
+		if Cleared::<T>::get() {
+			return T::DbWeight::get().reads(1)
+		}
+
+		let mut refunded: Weight = 0;
+		for (index, (who, deposit, _frozen)) in
+			pallet_indices::Accounts::<T>::drain().take(max as usize)
+		{
+			// Frozen indices had their deposit slashed already, so this is a no-op for them.
+			T::Currency::unreserve(&who, deposit);
+			Self::deposit_event(Event::<T>::IndexRefunded { index, who, deposit });
+			refunded += 1;
+		}
+
+		if pallet_indices::Accounts::<T>::iter_keys().next().is_none() {
+			Cleared::<T>::put(true);
+			Self::deposit_event(Event::<T>::IndicesCleared);
+		}
+
+		Self::refund_weight()
+			.saturating_mul(refunded)
+			.saturating_add(Self::overhead_weight())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use crate::indices_wind_down;
+	use frame_support::{assert_noop, assert_ok, parameter_types, traits::OnIdle};
+	use sp_core::H256;
+	use sp_runtime::{
+		testing::Header,
+		traits::{BadOrigin, BlakeTwo256, IdentityLookup},
+	};
+
+	type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+	type Block = frame_system::mocking::MockBlock<Test>;
+
+	frame_support::construct_runtime!(
+		pub enum Test where
+			Block = Block,
+			NodeBlock = Block,
+			UncheckedExtrinsic = UncheckedExtrinsic,
+		{
+			System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+			Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+			Indices: pallet_indices::{Pallet, Call, Storage, Config<T>, Event<T>},
+			IndicesWindDown: indices_wind_down::{Pallet, Call, Storage, Event<T>},
+		}
+	);
+
+	parameter_types! {
+		pub const BlockHashCount: u32 = 250;
+	}
+
+	impl frame_system::Config for Test {
+		type BaseCallFilter = frame_support::traits::Everything;
+		type BlockWeights = ();
+		type BlockLength = ();
+		type DbWeight = ();
+		type Origin = Origin;
+		type Call = Call;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<u64>;
+		type Header = Header;
+		type Event = Event;
+		type BlockHashCount = BlockHashCount;
+		type Version = ();
+		type PalletInfo = PalletInfo;
+		type AccountData = pallet_balances::AccountData<u64>;
+		type OnNewAccount = ();
+		type OnKilledAccount = ();
+		type SystemWeightInfo = ();
+		type SS58Prefix = ();
+		type OnSetCode = ();
+		type MaxConsumers = frame_support::traits::ConstU32<16>;
+	}
+
+	parameter_types! {
+		pub const ExistentialDeposit: u64 = 1;
+	}
+
+	impl pallet_balances::Config for Test {
+		type Balance = u64;
+		type Event = Event;
+		type DustRemoval = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type AccountStore = System;
+		type MaxLocks = ();
+		type MaxReserves = ();
+		type ReserveIdentifier = [u8; 8];
+		type WeightInfo = ();
+	}
+
+	parameter_types! {
+		pub const IndexDeposit: u64 = 10;
+	}
+
+	impl pallet_indices::Config for Test {
+		type AccountIndex = u32;
+		type Currency = Balances;
+		type Deposit = IndexDeposit;
+		type Event = Event;
+		type WeightInfo = ();
+	}
+
+	parameter_types! {
+		pub const MaxRefundsPerBlock: u32 = 2;
+	}
+
+	impl Config for Test {
+		type Event = Event;
+		type MaxRefundsPerBlock = MaxRefundsPerBlock;
+	}
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		pallet_balances::GenesisConfig::<Test> { balances: vec![(1, 100), (2, 100)] }
+			.assimilate_storage(&mut t)
+			.unwrap();
+		let mut ext = sp_io::TestExternalities::new(t);
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+
+	fn claim_indices(who: u64, indices: &[u32]) {
+		for index in indices {
+			assert_ok!(Indices::claim(Origin::signed(who), *index));
+		}
+	}
+
+	#[test]
+	fn refund_indices_returns_deposits() {
+		new_test_ext().execute_with(|| {
+			claim_indices(1, &[0, 1]);
+			claim_indices(2, &[2]);
+			assert_eq!(Balances::reserved_balance(1), 20);
+			assert_eq!(Balances::reserved_balance(2), 10);
+
+			assert_noop!(IndicesWindDown::refund_indices(Origin::none(), 10), BadOrigin);
+			assert_ok!(IndicesWindDown::refund_indices(Origin::signed(2), 10));
+
+			assert_eq!(Balances::reserved_balance(1), 0);
+			assert_eq!(Balances::reserved_balance(2), 0);
+			assert_eq!(pallet_indices::Accounts::<Test>::iter().count(), 0);
+			System::assert_last_event(Event::IndicesWindDown(
+				indices_wind_down::Event::IndicesCleared,
+			));
+		});
+	}
+
+	#[test]
+	fn on_idle_refunds_are_bounded() {
+		new_test_ext().execute_with(|| {
+			claim_indices(1, &[0, 1, 2]);
+			assert_ok!(Indices::freeze(Origin::signed(1), 2));
+			assert_eq!(Balances::reserved_balance(1), 20);
+
+			IndicesWindDown::on_idle(1, Weight::max_value());
+			assert_eq!(pallet_indices::Accounts::<Test>::iter().count(), 1);
+
+			IndicesWindDown::on_idle(2, Weight::max_value());
+			assert_eq!(pallet_indices::Accounts::<Test>::iter().count(), 0);
+			assert_eq!(Balances::reserved_balance(1), 0);
+			// The frozen deposit was slashed, so only the remaining two are returned.
+			assert_eq!(Balances::free_balance(1), 90);
+		});
+	}
+
+	#[test]
+	fn indices_are_cleared_by_a_full_batch() {
+		new_test_ext().execute_with(|| {
+			claim_indices(1, &[0, 1]);
+			assert_eq!(
+				pallet_indices::Accounts::<Test>::iter().count() as u32,
+				MaxRefundsPerBlock::get()
+			);
+
+			IndicesWindDown::on_idle(1, Weight::max_value());
+			assert_eq!(pallet_indices::Accounts::<Test>::iter().count(), 0);
+			assert!(Cleared::<Test>::get());
+			System::assert_last_event(Event::IndicesWindDown(
+				indices_wind_down::Event::IndicesCleared,
+			));
+
+			// Nothing is done once the indices are cleared.
+			System::reset_events();
+			IndicesWindDown::on_idle(2, Weight::max_value());
+			assert_ok!(IndicesWindDown::refund_indices(Origin::signed(2), 10));
+			assert!(System::events().is_empty());
+		});
+	}
+}
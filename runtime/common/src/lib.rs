@@ -19,14 +19,47 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 pub mod assigned_slots;
+pub mod auctions;
+pub mod authorship_stats;
+pub mod batch_transfer;
+pub mod bounty_expiry;
+pub mod candidacy_renewal;
+pub mod crowdloan;
+pub mod democracy;
+pub mod dex;
 pub mod elections;
+pub mod equivocation_reports;
+pub mod events_schema;
+pub mod fee_multiplier;
+#[cfg(feature = "std")]
+pub mod genesis_presets;
+pub mod governance;
 pub mod impls;
+pub mod indices_wind_down;
+pub mod mandatory_weight;
+pub mod meta_tx;
+pub mod multisig_calls;
 pub mod paras_registrar;
 pub mod paras_sudo_wrapper;
+pub mod price_oracle;
+pub mod priority;
 pub mod purchase;
+pub mod recovery_probation;
+pub mod reserves;
+pub mod safe_mode;
+pub mod scheduler;
+pub mod slash_simulation;
+pub mod slashing_pruning;
 pub mod slot_range;
 pub mod slots;
+pub mod staking;
+pub mod tips_retirement;
 pub mod traits;
+pub mod treasury_xcm_payouts;
+pub mod upgrade_enactment;
+pub mod validator_count_growth;
+pub mod validator_disabling;
+pub mod validator_operators;
 pub mod xcm_sender;
 
 #[cfg(test)]
@@ -69,14 +102,26 @@ pub const AVERAGE_ON_INITIALIZE_RATIO: Perbill = Perbill::from_percent(1);
 const NORMAL_DISPATCH_RATIO: Perbill = Perbill::from_percent(75);
 /// We allow for 2 seconds of compute with a 6 second average block time.
 pub const MAXIMUM_BLOCK_WEIGHT: Weight = 2 * WEIGHT_PER_SECOND;
+/// The mandatory paras inherent, whose weight grows with the number of paras and disputes, may use
+/// up to 70% of the block. The rest is left for `on_initialize` and the space reserved for
+/// `Operational` extrinsics.
+pub const PARAS_INHERENT_RATIO: Perbill = Perbill::from_percent(70);
 
 const_assert!(NORMAL_DISPATCH_RATIO.deconstruct() >= AVERAGE_ON_INITIALIZE_RATIO.deconstruct());
+// A paras inherent of maximum weight still leaves room for `Operational` extrinsics.
+const_assert!(
+	PARAS_INHERENT_RATIO.deconstruct() +
+		AVERAGE_ON_INITIALIZE_RATIO.deconstruct() +
+		(Perbill::one().deconstruct() - NORMAL_DISPATCH_RATIO.deconstruct()) <=
+		Perbill::one().deconstruct()
+);
 
 // Common constants used in all runtimes.
 parameter_types! {
 	pub const BlockHashCount: BlockNumber = 2400;
 	/// The portion of the `NORMAL_DISPATCH_RATIO` that we adjust the fees with. Blocks filled less
-	/// than this will decrease the weight and more will increase.
+	/// than this will decrease the weight and more will increase. This is the initial value of
+	/// [`fee_multiplier::TargetBlockFullness`], which governance can change.
 	pub const TargetBlockFullness: Perquintill = Perquintill::from_percent(25);
 	/// The adjustment variable of the runtime. Higher values will cause `TargetBlockFullness` to
 	/// change the fees more rapidly.
@@ -105,14 +150,22 @@ parameter_types! {
 				MAXIMUM_BLOCK_WEIGHT - NORMAL_DISPATCH_RATIO * MAXIMUM_BLOCK_WEIGHT,
 			);
 		})
+		.for_class(DispatchClass::Mandatory, |weights| {
+			// The paras inherent limits itself to this.
+			weights.max_extrinsic = Some(PARAS_INHERENT_RATIO * MAXIMUM_BLOCK_WEIGHT);
+		})
 		.avg_block_initialization(AVERAGE_ON_INITIALIZE_RATIO)
 		.build_or_panic();
 }
 
 /// Parameterized slow adjusting fee updated based on
 /// https://research.web3.foundation/en/latest/selendra/overview/2-token-economics.html#-2.-slow-adjusting-mechanism
-pub type SlowAdjustingFeeUpdate<R> =
-	TargetedFeeAdjustment<R, TargetBlockFullness, AdjustmentVariable, MinimumMultiplier>;
+pub type SlowAdjustingFeeUpdate<R> = TargetedFeeAdjustment<
+	R,
+	fee_multiplier::TargetBlockFullnessOf<R>,
+	AdjustmentVariable,
+	MinimumMultiplier,
+>;
 
 /// The type used for currency conversion.
 ///
@@ -214,6 +267,27 @@ macro_rules! prod_or_fast {
 	};
 }
 
+#[cfg(test)]
+mod block_weight_tests {
+	use super::*;
+
+	#[test]
+	fn paras_inherent_leaves_room_for_operational_extrinsics() {
+		let weights = BlockWeights::get();
+		let mandatory = weights.get(DispatchClass::Mandatory);
+		let operational = weights.get(DispatchClass::Operational);
+
+		let max_inherent = mandatory.max_extrinsic.unwrap() + mandatory.base_extrinsic;
+		let initialization = AVERAGE_ON_INITIALIZE_RATIO * weights.max_block;
+		assert!(
+			weights.base_block + initialization + max_inherent + operational.reserved.unwrap() <=
+				weights.max_block,
+		);
+		// `Normal` extrinsics may not use the space reserved for `Operational` ones.
+		assert!(weights.get(DispatchClass::Normal).max_total.unwrap() <= weights.max_block);
+	}
+}
+
 #[cfg(test)]
 mod multiplier_tests {
 	use super::*;
@@ -234,7 +308,10 @@ mod multiplier_tests {
 			NodeBlock = Block,
 			UncheckedExtrinsic = UncheckedExtrinsic,
 		{
-			System: frame_system::{Pallet, Call, Config, Storage, Event<T>}
+			System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+			Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+			TransactionPayment: pallet_transaction_payment::{Pallet, Storage},
+			FeeMultiplier: fee_multiplier::{Pallet, Call, Storage, Event<T>},
 		}
 	);
 
@@ -265,7 +342,7 @@ mod multiplier_tests {
 		type BlockHashCount = BlockHashCount;
 		type Version = ();
 		type PalletInfo = PalletInfo;
-		type AccountData = ();
+		type AccountData = pallet_balances::AccountData<u64>;
 		type OnNewAccount = ();
 		type OnKilledAccount = ();
 		type SystemWeightInfo = ();
@@ -274,6 +351,39 @@ mod multiplier_tests {
 		type MaxConsumers = frame_support::traits::ConstU32<16>;
 	}
 
+	parameter_types! {
+		pub const ExistentialDeposit: u64 = 1;
+		pub const TransactionByteFee: u64 = 1;
+		pub const OperationalFeeMultiplier: u8 = 5;
+		pub const HistoryDepth: u32 = 4;
+	}
+
+	impl pallet_balances::Config for Runtime {
+		type Balance = u64;
+		type Event = Event;
+		type DustRemoval = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type AccountStore = System;
+		type MaxLocks = ();
+		type MaxReserves = ();
+		type ReserveIdentifier = [u8; 8];
+		type WeightInfo = ();
+	}
+
+	impl pallet_transaction_payment::Config for Runtime {
+		type OnChargeTransaction = pallet_transaction_payment::CurrencyAdapter<Balances, ()>;
+		type TransactionByteFee = TransactionByteFee;
+		type OperationalFeeMultiplier = OperationalFeeMultiplier;
+		type WeightToFee = frame_support::weights::IdentityFee<u64>;
+		type FeeMultiplierUpdate = SlowAdjustingFeeUpdate<Self>;
+	}
+
+	impl fee_multiplier::Config for Runtime {
+		type Event = Event;
+		type HistoryDepth = HistoryDepth;
+		type FullnessOrigin = frame_system::EnsureRoot<u64>;
+	}
+
 	fn run_with_system_weight<F>(w: Weight, mut assertions: F)
 	where
 		F: FnMut() -> (),
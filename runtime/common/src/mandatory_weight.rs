@@ -0,0 +1,317 @@
+// Copyright 2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Pallet recording the weight of the mandatory work done when initializing blocks.
+//!
+//! The `on_initialize` hooks of the pallets, such as the parachains initializer, session rotation
+//! and the phase transitions of the election, run before any extrinsic and are executed however
+//! full the block is. [`MeasureOnInitialize`] wraps the hooks of the runtime passed to the
+//! executive and records the weight they report for each of the last `HistoryDepth` blocks, so
+//! blocks whose mandatory work nears the block weight limit are noticed before the chain stalls.
+//! Blocks above `AlertThreshold` of the limit deposit an event.
+
+use frame_support::{
+	pallet_prelude::*,
+	traits::{OffchainWorker, OnFinalize, OnIdle, OnInitialize, OnRuntimeUpgrade},
+};
+use frame_system::pallet_prelude::*;
+pub use pallet::*;
+use parity_scale_codec::Codec;
+use sp_runtime::{traits::UniqueSaturatedInto, Perbill};
+use sp_std::prelude::*;
+
+sp_api::decl_runtime_apis! {
+	/// The API for querying the weight of the mandatory work of recent blocks.
+	pub trait MandatoryWeightApi<BlockNumber> where BlockNumber: Codec {
+		/// The weight of the `on_initialize` hooks of each of the recent blocks, oldest first.
+		fn mandatory_weight_history() -> Vec<MandatoryWeight<BlockNumber>>;
+	}
+}
+
+/// The weight of the `on_initialize` hooks of a block.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct MandatoryWeight<BlockNumber> {
+	/// The number of the block.
+	pub block: BlockNumber,
+	/// The weight the hooks reported.
+	pub weight: Weight,
+	/// The maximum weight of the block.
+	pub max_block: Weight,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The number of blocks the weight is recorded for.
+		#[pallet::constant]
+		type HistoryDepth: Get<u32>;
+
+		/// The portion of the maximum block weight above which the weight of the hooks is
+		/// reported with an event.
+		#[pallet::constant]
+		type AlertThreshold: Get<Perbill>;
+	}
+
+	/// The weight of the hooks of recent blocks, by block number modulo `HistoryDepth`.
+	#[pallet::storage]
+	pub type History<T: Config> =
+		StorageMap<_, Twox64Concat, u32, MandatoryWeight<T::BlockNumber>, OptionQuery>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The `on_initialize` hooks of the block used more than `AlertThreshold` of the maximum
+		/// block weight.
+		MandatoryWeightHigh { weight: Weight, max_block: Weight },
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn integrity_test() {
+			assert!(T::HistoryDepth::get() > 0, "`HistoryDepth` must not be zero");
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The weight of the hooks of each of the last `HistoryDepth` blocks, oldest first.
+	pub fn history() -> Vec<MandatoryWeight<T::BlockNumber>> {
+		let now = frame_system::Pallet::<T>::block_number();
+		let depth = T::BlockNumber::from(T::HistoryDepth::get());
+		// Slots beyond the depth are left behind if it was lowered.
+		let mut history: Vec<_> = History::<T>::iter_values()
+			.filter(|record| now.saturating_sub(record.block) < depth)
+			.collect();
+		history.sort_by_key(|record| record.block);
+		history
+	}
+
+	/// Record the `weight` of the hooks of block `now`, returning the weight of doing so.
+	fn note_on_initialize(now: T::BlockNumber, weight: Weight) -> Weight {
+		let max_block = T::BlockWeights::get().max_block;
+		let slot: u32 = now.unique_saturated_into();
+		History::<T>::insert(
+			slot % T::HistoryDepth::get(),
+			MandatoryWeight { block: now, weight, max_block },
+		);
+
+		if weight > T::AlertThreshold::get() * max_block {
+			log::warn!(
+				target: "runtime::mandatory-weight",
+				"Initializing block {:?} took {} of the maximum block weight of {}",
+				now,
+				weight,
+				max_block,
+			);
+			Self::deposit_event(Event::<T>::MandatoryWeightHigh { weight, max_block });
+			return T::DbWeight::get().writes(2)
+		}
+		T::DbWeight::get().writes(1)
+	}
+}
+
+/// The hooks of all pallets, with the weight of their `on_initialize` recorded in [`Pallet`].
+///
+/// Meant to be passed to the executive in place of the pallets of the runtime.
+pub struct MeasureOnInitialize<T, Hooks>(PhantomData<(T, Hooks)>);
+
+impl<T: Config, Hooks: OnInitialize<T::BlockNumber>> OnInitialize<T::BlockNumber>
+	for MeasureOnInitialize<T, Hooks>
+{
+	fn on_initialize(now: T::BlockNumber) -> Weight {
+		let weight = Hooks::on_initialize(now);
+		weight.saturating_add(Pallet::<T>::note_on_initialize(now, weight))
+	}
+}
+
+impl<T: Config, Hooks: OnFinalize<T::BlockNumber>> OnFinalize<T::BlockNumber>
+	for MeasureOnInitialize<T, Hooks>
+{
+	fn on_finalize(now: T::BlockNumber) {
+		Hooks::on_finalize(now)
+	}
+}
+
+impl<T: Config, Hooks: OnIdle<T::BlockNumber>> OnIdle<T::BlockNumber>
+	for MeasureOnInitialize<T, Hooks>
+{
+	fn on_idle(now: T::BlockNumber, remaining_weight: Weight) -> Weight {
+		Hooks::on_idle(now, remaining_weight)
+	}
+}
+
+impl<T: Config, Hooks: OffchainWorker<T::BlockNumber>> OffchainWorker<T::BlockNumber>
+	for MeasureOnInitialize<T, Hooks>
+{
+	fn offchain_worker(now: T::BlockNumber) {
+		Hooks::offchain_worker(now)
+	}
+}
+
+impl<T, Hooks: OnRuntimeUpgrade> OnRuntimeUpgrade for MeasureOnInitialize<T, Hooks> {
+	fn on_runtime_upgrade() -> Weight {
+		Hooks::on_runtime_upgrade()
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn pre_upgrade() -> Result<(), &'static str> {
+		Hooks::pre_upgrade()
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade() -> Result<(), &'static str> {
+		Hooks::post_upgrade()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use crate::mandatory_weight;
+	use frame_support::parameter_types;
+	use sp_core::H256;
+	use sp_runtime::{
+		testing::Header,
+		traits::{BlakeTwo256, IdentityLookup},
+	};
+
+	type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+	type Block = frame_system::mocking::MockBlock<Test>;
+
+	frame_support::construct_runtime!(
+		pub enum Test where
+			Block = Block,
+			NodeBlock = Block,
+			UncheckedExtrinsic = UncheckedExtrinsic,
+		{
+			System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+			MandatoryWeight: mandatory_weight::{Pallet, Storage, Event<T>},
+		}
+	);
+
+	parameter_types! {
+		pub const BlockHashCount: u32 = 250;
+		pub BlockWeights: frame_system::limits::BlockWeights =
+			frame_system::limits::BlockWeights::simple_max(1000);
+	}
+
+	impl frame_system::Config for Test {
+		type BaseCallFilter = frame_support::traits::Everything;
+		type BlockWeights = BlockWeights;
+		type BlockLength = ();
+		type DbWeight = ();
+		type Origin = Origin;
+		type Call = Call;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<u64>;
+		type Header = Header;
+		type Event = Event;
+		type BlockHashCount = BlockHashCount;
+		type Version = ();
+		type PalletInfo = PalletInfo;
+		type AccountData = ();
+		type OnNewAccount = ();
+		type OnKilledAccount = ();
+		type SystemWeightInfo = ();
+		type SS58Prefix = ();
+		type OnSetCode = ();
+		type MaxConsumers = frame_support::traits::ConstU32<16>;
+	}
+
+	parameter_types! {
+		pub static HookWeight: Weight = 0;
+		pub const HistoryDepth: u32 = 3;
+		pub const AlertThreshold: Perbill = Perbill::from_percent(50);
+	}
+
+	impl Config for Test {
+		type Event = Event;
+		type HistoryDepth = HistoryDepth;
+		type AlertThreshold = AlertThreshold;
+	}
+
+	pub struct TestHooks;
+	impl OnInitialize<u64> for TestHooks {
+		fn on_initialize(_: u64) -> Weight {
+			HookWeight::get()
+		}
+	}
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		let mut ext = sp_io::TestExternalities::new(t);
+		ext.execute_with(|| HookWeight::set(0));
+		ext
+	}
+
+	fn initialize_block(block: u64, weight: Weight) -> Weight {
+		System::set_block_number(block);
+		HookWeight::set(weight);
+		MeasureOnInitialize::<Test, TestHooks>::on_initialize(block)
+	}
+
+	fn record(block: u64, weight: Weight) -> mandatory_weight::MandatoryWeight<u64> {
+		mandatory_weight::MandatoryWeight { block, weight, max_block: 1000 }
+	}
+
+	#[test]
+	fn history_keeps_the_weight_of_the_last_blocks() {
+		new_test_ext().execute_with(|| {
+			for block in 1..=5 {
+				assert_eq!(initialize_block(block, block * 10), block * 10);
+			}
+
+			assert_eq!(
+				MandatoryWeight::history(),
+				vec![record(3, 30), record(4, 40), record(5, 50)],
+			);
+			assert!(System::events().is_empty());
+		});
+	}
+
+	#[test]
+	fn heavy_initialization_is_reported() {
+		new_test_ext().execute_with(|| {
+			initialize_block(1, 500);
+			assert!(System::events().is_empty());
+
+			initialize_block(2, 501);
+			System::assert_last_event(
+				mandatory_weight::Event::<Test>::MandatoryWeightHigh {
+					weight: 501,
+					max_block: 1000,
+				}
+				.into(),
+			);
+			assert_eq!(MandatoryWeight::history(), vec![record(1, 500), record(2, 501)]);
+		});
+	}
+}
@@ -0,0 +1,428 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Pallet for meta-transactions: calls signed by a user off-chain and submitted by a relayer, who
+//! pays the fee.
+//!
+//! Aimed at onboarding flows where new users hold no SEL yet. The user signs the call together
+//! with their meta-transaction nonce, a deadline and the genesis hash of the chain, and the call is
+//! dispatched with the user as signed origin. Each user may only have `MaxRelayedCalls` calls
+//! relayed per `RatePeriod`, so that a sponsored account cannot be used to flood the chain.
+//!
+//! The nonces of meta-transactions are kept by this pallet rather than `frame_system`, as users
+//! relaying their calls need not have an account.
+
+use frame_support::{
+	dispatch::GetDispatchInfo,
+	pallet_prelude::*,
+	weights::{constants::RocksDbWeight, PostDispatchInfo},
+};
+use frame_system::pallet_prelude::*;
+pub use pallet::*;
+use sp_runtime::traits::{Dispatchable, IdentifyAccount, One, Saturating, Verify, Zero};
+use sp_std::prelude::*;
+
+/// The context signed along with relayed calls, so that signatures are not valid for other uses.
+pub const META_TX_CONTEXT: &[u8] = b"selendra/meta-tx";
+
+pub trait WeightInfo {
+	fn relay() -> Weight;
+}
+
+/// Estimated from the cost of verifying a signature plus a read and write of the nonce and the
+/// rate limit of the signer, for runtimes which have not benchmarked the pallet.
+impl WeightInfo for () {
+	fn relay() -> Weight {
+		(60_000_000 as Weight).saturating_add(RocksDbWeight::get().reads_writes(3, 2))
+	}
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The calls which can be relayed.
+		type Call: Parameter
+			+ Dispatchable<Origin = Self::Origin, PostInfo = PostDispatchInfo>
+			+ GetDispatchInfo;
+
+		/// The signature users sign their calls with.
+		type Signature: Verify<Signer = Self::Signer> + Parameter;
+
+		/// The public key of a signature, identifying the account of the user.
+		type Signer: IdentifyAccount<AccountId = Self::AccountId>;
+
+		/// The maximum number of calls of a user relayed per `RatePeriod`.
+		#[pallet::constant]
+		type MaxRelayedCalls: Get<u32>;
+
+		/// The number of blocks rate limits apply over.
+		#[pallet::constant]
+		type RatePeriod: Get<Self::BlockNumber>;
+
+		/// Weight information for the extrinsics of this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	/// The nonce of the next meta-transaction of each user.
+	#[pallet::storage]
+	#[pallet::getter(fn nonce)]
+	pub type Nonces<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, T::Index, ValueQuery>;
+
+	/// The rate period in which a user last had a call relayed, and the number of calls relayed in
+	/// it.
+	#[pallet::storage]
+	pub type RelayedCalls<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, (T::BlockNumber, u32), OptionQuery>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A call signed by `signer` was relayed by `relayer` and dispatched with `result`.
+		Relayed { signer: T::AccountId, relayer: T::AccountId, result: DispatchResult },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The deadline of the meta-transaction has passed.
+		Expired,
+		/// The nonce is not the next one of the signer.
+		BadNonce,
+		/// The signature does not match the signer and the call.
+		BadSignature,
+		/// The signer has had `MaxRelayedCalls` calls relayed in this rate period already.
+		RateLimited,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Dispatch `call` with `signer` as signed origin, the sender paying the fee.
+		///
+		/// `signature` is the signature of `signer` over the payload returned by `signing_payload`
+		/// for the call, `nonce` and `valid_until`. The nonce is used up even if `call` fails.
+		#[pallet::weight({
+			let info = call.get_dispatch_info();
+			(T::WeightInfo::relay().saturating_add(info.weight), info.class)
+		})]
+		pub fn relay(
+			origin: OriginFor<T>,
+			signer: T::AccountId,
+			call: Box<<T as Config>::Call>,
+			nonce: T::Index,
+			valid_until: T::BlockNumber,
+			signature: T::Signature,
+		) -> DispatchResult {
+			let relayer = ensure_signed(origin)?;
+
+			let now = frame_system::Pallet::<T>::block_number();
+			ensure!(now <= valid_until, Error::<T>::Expired);
+			ensure!(nonce == Nonces::<T>::get(&signer), Error::<T>::BadNonce);
+			let payload = Self::signing_payload(&call, nonce, valid_until);
+			ensure!(signature.verify(&payload[..], &signer), Error::<T>::BadSignature);
+
+			let period = Self::rate_period(now);
+			let relayed = match RelayedCalls::<T>::get(&signer) {
+				Some((last_period, count)) if last_period == period => count,
+				_ => 0,
+			};
+			ensure!(relayed < T::MaxRelayedCalls::get(), Error::<T>::RateLimited);
+
+			Nonces::<T>::insert(&signer, nonce.saturating_add(One::one()));
+			RelayedCalls::<T>::insert(&signer, (period, relayed + 1));
+
+			let origin = frame_system::RawOrigin::Signed(signer.clone()).into();
+			let result = call.dispatch(origin).map(|_| ()).map_err(|e| e.error);
+
+			Self::deposit_event(Event::<T>::Relayed { signer, relayer, result });
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The payload a user signs to have `call` relayed with `nonce` until block `valid_until`.
+	pub fn signing_payload(
+		call: &<T as Config>::Call,
+		nonce: T::Index,
+		valid_until: T::BlockNumber,
+	) -> Vec<u8> {
+		let genesis_hash = frame_system::Pallet::<T>::block_hash(T::BlockNumber::zero());
+		(META_TX_CONTEXT, genesis_hash, call, nonce, valid_until).encode()
+	}
+
+	/// The rate period block `now` is in.
+	fn rate_period(now: T::BlockNumber) -> T::BlockNumber {
+		let period = T::RatePeriod::get();
+		if period.is_zero() {
+			now
+		} else {
+			now / period
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use crate::meta_tx;
+	use frame_support::{assert_noop, assert_ok, parameter_types};
+	use sp_core::H256;
+	use sp_runtime::{
+		testing::{Header, TestSignature, UintAuthorityId},
+		traits::{BlakeTwo256, IdentityLookup},
+	};
+
+	type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+	type Block = frame_system::mocking::MockBlock<Test>;
+
+	frame_support::construct_runtime!(
+		pub enum Test where
+			Block = Block,
+			NodeBlock = Block,
+			UncheckedExtrinsic = UncheckedExtrinsic,
+		{
+			System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+			Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+			MetaTx: meta_tx::{Pallet, Call, Storage, Event<T>},
+		}
+	);
+
+	parameter_types! {
+		pub const BlockHashCount: u32 = 250;
+	}
+
+	impl frame_system::Config for Test {
+		type BaseCallFilter = frame_support::traits::Everything;
+		type BlockWeights = ();
+		type BlockLength = ();
+		type DbWeight = ();
+		type Origin = Origin;
+		type Call = Call;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<u64>;
+		type Header = Header;
+		type Event = Event;
+		type BlockHashCount = BlockHashCount;
+		type Version = ();
+		type PalletInfo = PalletInfo;
+		type AccountData = pallet_balances::AccountData<u64>;
+		type OnNewAccount = ();
+		type OnKilledAccount = ();
+		type SystemWeightInfo = ();
+		type SS58Prefix = ();
+		type OnSetCode = ();
+		type MaxConsumers = frame_support::traits::ConstU32<16>;
+	}
+
+	parameter_types! {
+		pub const ExistentialDeposit: u64 = 1;
+	}
+
+	impl pallet_balances::Config for Test {
+		type Balance = u64;
+		type Event = Event;
+		type DustRemoval = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type AccountStore = System;
+		type MaxLocks = ();
+		type MaxReserves = ();
+		type ReserveIdentifier = [u8; 8];
+		type WeightInfo = ();
+	}
+
+	parameter_types! {
+		pub const MaxRelayedCalls: u32 = 2;
+		pub const RatePeriod: u64 = 10;
+	}
+
+	impl Config for Test {
+		type Event = Event;
+		type Call = Call;
+		type Signature = TestSignature;
+		type Signer = UintAuthorityId;
+		type MaxRelayedCalls = MaxRelayedCalls;
+		type RatePeriod = RatePeriod;
+		type WeightInfo = ();
+	}
+
+	pub fn new_test_ext() -> sp_io::TestExternalities {
+		let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		pallet_balances::GenesisConfig::<Test> { balances: vec![(1, 100), (2, 100)] }
+			.assimilate_storage(&mut t)
+			.unwrap();
+		let mut ext = sp_io::TestExternalities::new(t);
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+
+	fn transfer(dest: u64, value: u64) -> Box<Call> {
+		Box::new(Call::Balances(pallet_balances::Call::transfer { dest, value }))
+	}
+
+	fn sign(signer: u64, call: &Call, nonce: u64, valid_until: u64) -> TestSignature {
+		TestSignature(signer, MetaTx::signing_payload(call, nonce, valid_until))
+	}
+
+	#[test]
+	fn calls_are_dispatched_as_the_signer() {
+		new_test_ext().execute_with(|| {
+			let call = transfer(3, 10);
+			let signature = sign(1, &call, 0, 5);
+			assert_ok!(MetaTx::relay(Origin::signed(2), 1, call, 0, 5, signature));
+
+			assert_eq!(Balances::free_balance(1), 90);
+			assert_eq!(Balances::free_balance(2), 100);
+			assert_eq!(Balances::free_balance(3), 10);
+			assert_eq!(MetaTx::nonce(1), 1);
+			System::assert_last_event(Event::MetaTx(meta_tx::Event::Relayed {
+				signer: 1,
+				relayer: 2,
+				result: Ok(()),
+			}));
+		});
+	}
+
+	#[test]
+	fn failing_calls_use_up_the_nonce() {
+		new_test_ext().execute_with(|| {
+			let call = transfer(3, 1000);
+			let signature = sign(1, &call, 0, 5);
+			assert_ok!(MetaTx::relay(Origin::signed(2), 1, call, 0, 5, signature));
+
+			assert_eq!(MetaTx::nonce(1), 1);
+			System::assert_last_event(Event::MetaTx(meta_tx::Event::Relayed {
+				signer: 1,
+				relayer: 2,
+				result: Err(pallet_balances::Error::<Test>::InsufficientBalance.into()),
+			}));
+		});
+	}
+
+	#[test]
+	fn invalid_meta_transactions_are_rejected() {
+		new_test_ext().execute_with(|| {
+			let call = transfer(3, 10);
+
+			// Signed by someone else.
+			let signature = sign(2, &call, 0, 5);
+			assert_noop!(
+				MetaTx::relay(Origin::signed(2), 1, call.clone(), 0, 5, signature),
+				Error::<Test>::BadSignature
+			);
+			// Signed for another call.
+			let signature = sign(1, &transfer(3, 20), 0, 5);
+			assert_noop!(
+				MetaTx::relay(Origin::signed(2), 1, call.clone(), 0, 5, signature),
+				Error::<Test>::BadSignature
+			);
+
+			let signature = sign(1, &call, 0, 5);
+			assert_noop!(
+				MetaTx::relay(Origin::signed(2), 1, call.clone(), 1, 5, signature.clone()),
+				Error::<Test>::BadNonce
+			);
+			assert_ok!(MetaTx::relay(Origin::signed(2), 1, call.clone(), 0, 5, signature.clone()));
+			// Replays are rejected.
+			assert_noop!(
+				MetaTx::relay(Origin::signed(2), 1, call.clone(), 0, 5, signature),
+				Error::<Test>::BadNonce
+			);
+
+			System::set_block_number(6);
+			let signature = sign(1, &call, 1, 5);
+			assert_noop!(
+				MetaTx::relay(Origin::signed(2), 1, call, 1, 5, signature),
+				Error::<Test>::Expired
+			);
+		});
+	}
+
+	#[test]
+	fn relayed_calls_are_rate_limited() {
+		new_test_ext().execute_with(|| {
+			let relay = |nonce| {
+				let call = transfer(3, 1);
+				let signature = sign(1, &call, nonce, 100);
+				MetaTx::relay(Origin::signed(2), 1, call, nonce, 100, signature)
+			};
+
+			assert_ok!(relay(0));
+			assert_ok!(relay(1));
+			assert_noop!(relay(2), Error::<Test>::RateLimited);
+
+			// The limit applies per period.
+			System::set_block_number(10);
+			assert_ok!(relay(2));
+		});
+	}
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking {
+	use super::*;
+	use frame_benchmarking::{benchmarks, whitelisted_caller};
+	use frame_system::RawOrigin;
+	use sp_core::sr25519;
+	use sp_io::crypto::{sr25519_generate, sr25519_sign};
+
+	benchmarks! {
+		where_clause { where
+			T::Signature: From<sr25519::Signature>,
+			T::Signer: From<sr25519::Public>,
+			<T as Config>::Call: From<frame_system::Call<T>>,
+		}
+
+		// The signer had a call relayed in this period already, so its rate limit is updated
+		// rather than created.
+		relay {
+			let public = sr25519_generate(0.into(), None);
+			let signer: T::AccountId = T::Signer::from(public).into_account();
+			let relayer: T::AccountId = whitelisted_caller();
+			let call: <T as Config>::Call =
+				frame_system::Call::<T>::remark { remark: Vec::new() }.into();
+			let valid_until = frame_system::Pallet::<T>::block_number();
+			RelayedCalls::<T>::insert(&signer, (Pallet::<T>::rate_period(valid_until), 0));
+			let payload = Pallet::<T>::signing_payload(&call, Zero::zero(), valid_until);
+			let signature = sr25519_sign(0.into(), &public, &payload)
+				.expect("the key was generated above; qed");
+		}: _(
+			RawOrigin::Signed(relayer),
+			signer.clone(),
+			Box::new(call),
+			Zero::zero(),
+			valid_until,
+			signature.into()
+		)
+		verify {
+			assert_eq!(Nonces::<T>::get(&signer), One::one());
+		}
+	}
+}
@@ -0,0 +1,221 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Migration away from calls stored by `pallet-multisig`.
+//!
+//! Calls may no longer be stored with `as_multi`. Signatories approve the hash of a call with
+//! `approve_as_multi` instead, and the last one supplies the call and its `max_weight` to
+//! `as_multi` for execution. The runtimes filter `as_multi` with `store_call` set, and this
+//! migration removes the calls stored before, returning their deposits. Pending multisig
+//! operations are kept, so they can still be executed by supplying the call.
+//!
+//! The migration bumps the `StorageVersion` of the pallet to 1 and is skipped once it is set. It
+//! can be removed after the next release.
+
+use frame_support::{
+	storage::{migration::storage_key_iter, storage_prefix, unhashed},
+	traits::{
+		Get, OnRuntimeUpgrade, ReservableCurrency, StorageVersion,
+		STORAGE_VERSION_STORAGE_KEY_POSTFIX,
+	},
+	weights::Weight,
+	Identity,
+};
+use sp_runtime::traits::Zero;
+use sp_std::{marker::PhantomData, prelude::*};
+
+/// The name `pallet-multisig` was declared with in `construct_runtime`.
+pub const MULTISIG_PALLET: &[u8] = b"Multisig";
+
+/// The `StorageVersion` of `pallet-multisig` without stored calls.
+pub const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
+/// The key `pallet-multisig` stores its `StorageVersion` at.
+fn storage_version_key() -> [u8; 32] {
+	storage_prefix(MULTISIG_PALLET, STORAGE_VERSION_STORAGE_KEY_POSTFIX)
+}
+
+/// The encoding of a stored call, its depositor and deposit in `pallet_multisig::Calls`.
+type StoredCall<AccountId, Balance> = (Vec<u8>, AccountId, Balance);
+
+/// Removes all calls stored by `pallet-multisig` and returns their deposits, reserved in
+/// `Currency`, unless the `StorageVersion` of the pallet is [`STORAGE_VERSION`] already.
+pub struct RefundStoredCalls<T, Currency>(PhantomData<(T, Currency)>);
+
+impl<T, Currency> OnRuntimeUpgrade for RefundStoredCalls<T, Currency>
+where
+	T: frame_system::Config,
+	Currency: ReservableCurrency<T::AccountId>,
+{
+	fn on_runtime_upgrade() -> Weight {
+		let key = storage_version_key();
+		if unhashed::get_or_default::<StorageVersion>(&key) >= STORAGE_VERSION {
+			log::info!(target: "runtime::multisig", "Stored multisig calls were removed already");
+			return T::DbWeight::get().reads(1)
+		}
+
+		let calls = storage_key_iter::<
+			[u8; 32],
+			StoredCall<T::AccountId, Currency::Balance>,
+			Identity,
+		>(MULTISIG_PALLET, b"Calls")
+		.drain();
+
+		let (mut removed, mut refunded) = (0u64, Currency::Balance::zero());
+		for (_, (_, depositor, deposit)) in calls {
+			let missing = Currency::unreserve(&depositor, deposit);
+			refunded += deposit - missing;
+			removed += 1;
+		}
+
+		log::info!(
+			target: "runtime::multisig",
+			"Removed {} stored multisig calls, returning {:?} of deposits",
+			removed,
+			refunded,
+		);
+
+		unhashed::put(&key, &STORAGE_VERSION);
+		T::DbWeight::get().reads_writes(removed + 2, removed.saturating_mul(2) + 1)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use frame_support::{parameter_types, storage::migration::put_storage_value};
+	use parity_scale_codec::Encode;
+	use sp_core::H256;
+	use sp_runtime::{
+		testing::Header,
+		traits::{BlakeTwo256, IdentityLookup},
+	};
+
+	type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+	type Block = frame_system::mocking::MockBlock<Test>;
+
+	frame_support::construct_runtime!(
+		pub enum Test where
+			Block = Block,
+			NodeBlock = Block,
+			UncheckedExtrinsic = UncheckedExtrinsic,
+		{
+			System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+			Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+		}
+	);
+
+	parameter_types! {
+		pub const BlockHashCount: u32 = 250;
+	}
+
+	impl frame_system::Config for Test {
+		type BaseCallFilter = frame_support::traits::Everything;
+		type BlockWeights = ();
+		type BlockLength = ();
+		type DbWeight = ();
+		type Origin = Origin;
+		type Call = Call;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<u64>;
+		type Header = Header;
+		type Event = Event;
+		type BlockHashCount = BlockHashCount;
+		type Version = ();
+		type PalletInfo = PalletInfo;
+		type AccountData = pallet_balances::AccountData<u64>;
+		type OnNewAccount = ();
+		type OnKilledAccount = ();
+		type SystemWeightInfo = ();
+		type SS58Prefix = ();
+		type OnSetCode = ();
+		type MaxConsumers = frame_support::traits::ConstU32<16>;
+	}
+
+	parameter_types! {
+		pub const ExistentialDeposit: u64 = 1;
+	}
+
+	impl pallet_balances::Config for Test {
+		type Balance = u64;
+		type Event = Event;
+		type DustRemoval = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type AccountStore = System;
+		type MaxLocks = ();
+		type MaxReserves = ();
+		type ReserveIdentifier = [u8; 8];
+		type WeightInfo = ();
+	}
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		pallet_balances::GenesisConfig::<Test> { balances: vec![(1, 100), (2, 100)] }
+			.assimilate_storage(&mut t)
+			.unwrap();
+		t.into()
+	}
+
+	fn store_call(hash: [u8; 32], depositor: u64, deposit: u64) {
+		Balances::reserve(&depositor, deposit).unwrap();
+		let call = Call::System(frame_system::Call::remark { remark: vec![1; 8] }).encode();
+		put_storage_value(MULTISIG_PALLET, b"Calls", &hash, (call, depositor, deposit));
+	}
+
+	#[test]
+	fn stored_calls_are_removed_and_refunded() {
+		new_test_ext().execute_with(|| {
+			store_call([1; 32], 1, 10);
+			store_call([2; 32], 1, 5);
+			store_call([3; 32], 2, 20);
+
+			RefundStoredCalls::<Test, Balances>::on_runtime_upgrade();
+
+			assert_eq!(Balances::reserved_balance(1), 0);
+			assert_eq!(Balances::reserved_balance(2), 0);
+			assert_eq!(Balances::free_balance(1), 100);
+			assert_eq!(Balances::free_balance(2), 100);
+			type Stored = StoredCall<u64, u64>;
+			assert_eq!(
+				storage_key_iter::<[u8; 32], Stored, Identity>(MULTISIG_PALLET, b"Calls").count(),
+				0
+			);
+			assert_eq!(unhashed::get(&storage_version_key()), Some(STORAGE_VERSION));
+		});
+	}
+
+	#[test]
+	fn stored_calls_are_kept_once_migrated() {
+		new_test_ext().execute_with(|| {
+			unhashed::put(&storage_version_key(), &STORAGE_VERSION);
+			store_call([1; 32], 1, 10);
+
+			RefundStoredCalls::<Test, Balances>::on_runtime_upgrade();
+
+			assert_eq!(Balances::reserved_balance(1), 10);
+			type Stored = StoredCall<u64, u64>;
+			assert_eq!(
+				storage_key_iter::<[u8; 32], Stored, Identity>(MULTISIG_PALLET, b"Calls").count(),
+				1
+			);
+		});
+	}
+}
@@ -21,10 +21,11 @@ use frame_support::{
 	dispatch::DispatchResult,
 	ensure,
 	pallet_prelude::Weight,
-	traits::{Currency, EnsureOrigin, Get, ReservableCurrency},
+	traits::{Currency, EnsureOrigin, Get, NamedReservableCurrency, ReservableCurrency},
+	BoundedVec,
 };
 use frame_system::{self, ensure_signed};
-use primitives::v1::{HeadData, Id as ParaId, ValidationCode, LOWEST_PUBLIC_ID};
+use primitives::v1::{Hash, HeadData, Id as ParaId, ValidationCode, LOWEST_PUBLIC_ID};
 use runtime_parachains::{
 	configuration, ensure_parachain,
 	paras::{self, ParaGenesisArgs},
@@ -34,14 +35,14 @@ use sp_std::{prelude::*, result};
 
 use crate::traits::{OnSwap, Registrar};
 pub use pallet::*;
-use parity_scale_codec::{Decode, Encode};
+use parity_scale_codec::{Decode, Encode, MaxEncodedLen};
 use scale_info::TypeInfo;
 use sp_runtime::{
 	traits::{CheckedSub, Saturating},
 	RuntimeDebug,
 };
 
-#[derive(Encode, Decode, Clone, PartialEq, Eq, Default, RuntimeDebug, TypeInfo)]
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Default, RuntimeDebug, TypeInfo, MaxEncodedLen)]
 pub struct ParaInfo<Account, Balance> {
 	/// The account that has placed a deposit for registering this para.
 	pub(crate) manager: Account,
@@ -51,9 +52,37 @@ pub struct ParaInfo<Account, Balance> {
 	locked: bool,
 }
 
+/// Metadata of a para attested by the `ParaRegisterOrigin`, so that explorers can label the para
+/// without its team setting an identity and paying its deposits.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct ParaMetadata<Bytes> {
+	/// The name of the para.
+	pub name: Bytes,
+	/// The website of the para.
+	pub website: Bytes,
+	/// The hash of the contact details of the team, which are kept off chain.
+	pub contact_hash: Hash,
+}
+
 type BalanceOf<T> =
 	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 
+type BoundedMetadataOf<T> = ParaMetadata<BoundedVec<u8, <T as Config>::MaxMetadataLength>>;
+
+sp_api::decl_runtime_apis! {
+	/// The API for querying the attested metadata of paras.
+	pub trait ParaMetadataApi {
+		/// The metadata of `para`, if it has been attested.
+		fn para_metadata(para: ParaId) -> Option<ParaMetadata<Vec<u8>>>;
+
+		/// The metadata of all paras which have some.
+		fn all_para_metadata() -> Vec<(ParaId, ParaMetadata<Vec<u8>>)>;
+	}
+}
+
+/// The name under which registration deposits are reserved.
+pub const PARA_DEPOSIT_ID: [u8; 8] = *b"pararegi";
+
 pub trait WeightInfo {
 	fn reserve() -> Weight;
 	fn register() -> Weight;
@@ -89,7 +118,7 @@ pub mod pallet {
 
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
-	#[pallet::without_storage_info]
+	#[pallet::storage_version(migration::STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	#[pallet::config]
@@ -109,7 +138,9 @@ pub mod pallet {
 			+ Into<result::Result<Origin, <Self as Config>::Origin>>;
 
 		/// The system's currency for parathread payment.
-		type Currency: ReservableCurrency<Self::AccountId>;
+		///
+		/// Deposits are reserved under the name [`PARA_DEPOSIT_ID`].
+		type Currency: NamedReservableCurrency<Self::AccountId, ReserveIdentifier = [u8; 8]>;
 
 		/// Runtime hook for when a parachain and parathread swap.
 		type OnSwap: crate::traits::OnSwap;
@@ -123,6 +154,10 @@ pub mod pallet {
 		#[pallet::constant]
 		type DataDepositPerByte: Get<BalanceOf<Self>>;
 
+		/// The maximum length of the name and of the website in the metadata of a para.
+		#[pallet::constant]
+		type MaxMetadataLength: Get<u32>;
+
 		/// Weight Information for the Extrinsics in the Pallet
 		type WeightInfo: WeightInfo;
 	}
@@ -133,6 +168,8 @@ pub mod pallet {
 		Registered(ParaId, T::AccountId),
 		Deregistered(ParaId),
 		Reserved(ParaId, T::AccountId),
+		MetadataSet(ParaId),
+		MetadataCleared(ParaId),
 	}
 
 	#[pallet::error]
@@ -166,6 +203,8 @@ pub mod pallet {
 		/// Cannot perform a parachain slot / lifecycle swap. Check that the state of both paras are
 		/// correct for the swap to work.
 		CannotSwap,
+		/// The name or website of the metadata is longer than `MaxMetadataLength`.
+		MetadataTooLong,
 	}
 
 	/// Pending swap operations.
@@ -184,6 +223,10 @@ pub mod pallet {
 	#[pallet::storage]
 	pub type NextFreeParaId<T> = StorageValue<_, ParaId, ValueQuery>;
 
+	/// The metadata attested for paras by the `ParaRegisterOrigin`.
+	#[pallet::storage]
+	pub type MetadataOf<T: Config> = StorageMap<_, Twox64Concat, ParaId, BoundedMetadataOf<T>>;
+
 	#[pallet::genesis_config]
 	pub struct GenesisConfig {
 		pub next_free_para_id: ParaId,
@@ -204,7 +247,11 @@ pub mod pallet {
 	}
 
 	#[pallet::hooks]
-	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_runtime_upgrade() -> Weight {
+			migration::migrate_to_latest::<T>()
+		}
+	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
@@ -351,6 +398,42 @@ pub mod pallet {
 			NextFreeParaId::<T>::set(id + 1);
 			Ok(())
 		}
+
+		/// Attest the metadata of a registered para, replacing any previous metadata.
+		///
+		/// No deposit is taken, the origin vouches for the metadata instead. The metadata is
+		/// removed when the para is deregistered.
+		///
+		/// Can only be called by the `ParaRegisterOrigin`.
+		#[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+		pub fn set_metadata(
+			origin: OriginFor<T>,
+			para: ParaId,
+			name: Vec<u8>,
+			website: Vec<u8>,
+			contact_hash: Hash,
+		) -> DispatchResult {
+			T::ParaRegisterOrigin::ensure_origin(origin)?;
+			ensure!(Paras::<T>::contains_key(para), Error::<T>::NotRegistered);
+
+			let name = name.try_into().map_err(|_| Error::<T>::MetadataTooLong)?;
+			let website = website.try_into().map_err(|_| Error::<T>::MetadataTooLong)?;
+			MetadataOf::<T>::insert(para, ParaMetadata { name, website, contact_hash });
+
+			Self::deposit_event(Event::<T>::MetadataSet(para));
+			Ok(())
+		}
+
+		/// Remove the metadata of a para.
+		///
+		/// Can only be called by the `ParaRegisterOrigin`.
+		#[pallet::weight(T::DbWeight::get().writes(1))]
+		pub fn clear_metadata(origin: OriginFor<T>, para: ParaId) -> DispatchResult {
+			T::ParaRegisterOrigin::ensure_origin(origin)?;
+			MetadataOf::<T>::remove(para);
+			Self::deposit_event(Event::<T>::MetadataCleared(para));
+			Ok(())
+		}
 	}
 }
 
@@ -456,6 +539,26 @@ impl<T: Config> Registrar for Pallet<T> {
 }
 
 impl<T: Config> Pallet<T> {
+	/// The metadata of `para`, if it has been attested.
+	pub fn para_metadata(para: ParaId) -> Option<ParaMetadata<Vec<u8>>> {
+		MetadataOf::<T>::get(para).map(Self::unbounded_metadata)
+	}
+
+	/// The metadata of all paras which have some.
+	pub fn all_para_metadata() -> Vec<(ParaId, ParaMetadata<Vec<u8>>)> {
+		MetadataOf::<T>::iter()
+			.map(|(para, metadata)| (para, Self::unbounded_metadata(metadata)))
+			.collect()
+	}
+
+	fn unbounded_metadata(metadata: BoundedMetadataOf<T>) -> ParaMetadata<Vec<u8>> {
+		ParaMetadata {
+			name: metadata.name.into_inner(),
+			website: metadata.website.into_inner(),
+			contact_hash: metadata.contact_hash,
+		}
+	}
+
 	/// Ensure the origin is one of Root, the `para` owner, or the `para` itself.
 	/// If the origin is the `para` owner, the `para` must be unlocked.
 	fn ensure_origin_para_or_owner(
@@ -492,7 +595,7 @@ impl<T: Config> Pallet<T> {
 		ensure!(paras::Pallet::<T>::lifecycle(id).is_none(), Error::<T>::AlreadyRegistered);
 
 		let deposit = deposit_override.unwrap_or_else(T::ParaDeposit::get);
-		<T as Config>::Currency::reserve(&who, deposit)?;
+		<T as Config>::Currency::reserve_named(&PARA_DEPOSIT_ID, &who, deposit)?;
 		let info = ParaInfo { manager: who.clone(), deposit, locked: false };
 
 		Paras::<T>::insert(id, info);
@@ -524,9 +627,9 @@ impl<T: Config> Pallet<T> {
 		let deposit = deposit_override.unwrap_or(deposit);
 
 		if let Some(additional) = deposit.checked_sub(&deposited) {
-			<T as Config>::Currency::reserve(&who, additional)?;
+			<T as Config>::Currency::reserve_named(&PARA_DEPOSIT_ID, &who, additional)?;
 		} else if let Some(rebate) = deposited.checked_sub(&deposit) {
-			<T as Config>::Currency::unreserve(&who, rebate);
+			<T as Config>::Currency::unreserve_named(&PARA_DEPOSIT_ID, &who, rebate);
 		};
 		let info = ParaInfo { manager: who.clone(), deposit, locked: false };
 
@@ -549,10 +652,11 @@ impl<T: Config> Pallet<T> {
 			.map_err(|_| Error::<T>::CannotDeregister)?;
 
 		if let Some(info) = Paras::<T>::take(&id) {
-			<T as Config>::Currency::unreserve(&info.manager, info.deposit);
+			<T as Config>::Currency::unreserve_named(&PARA_DEPOSIT_ID, &info.manager, info.deposit);
 		}
 
 		PendingSwap::<T>::remove(id);
+		MetadataOf::<T>::remove(id);
 		Self::deposit_event(Event::<T>::Deregistered(id));
 		Ok(())
 	}
@@ -591,6 +695,51 @@ impl<T: Config> Pallet<T> {
 	}
 }
 
+pub mod migration {
+	use super::*;
+	use frame_support::traits::StorageVersion;
+
+	/// The current storage version.
+	pub const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
+	/// Migrates the pallet storage to the most recent version, checking and setting the
+	/// `StorageVersion`.
+	pub fn migrate_to_latest<T: Config>() -> Weight {
+		let mut weight = 0;
+		if StorageVersion::get::<Pallet<T>>() < 1 {
+			weight += migrate_to_v1::<T>();
+			StorageVersion::new(1).put::<Pallet<T>>();
+		}
+		weight
+	}
+
+	/// Move the registration deposits to the named reserve [`PARA_DEPOSIT_ID`].
+	pub fn migrate_to_v1<T: Config>() -> Weight {
+		let mut paras = 0;
+		for (id, info) in Paras::<T>::iter() {
+			let missing = <T as Config>::Currency::unreserve(&info.manager, info.deposit);
+			let deposit = info.deposit.saturating_sub(missing);
+			// Only fails if the manager has too many named reserves, in which case the deposit is
+			// reserved without a name again, as it was before the migration.
+			if let Err(error) =
+				<T as Config>::Currency::reserve_named(&PARA_DEPOSIT_ID, &info.manager, deposit)
+			{
+				log::error!(
+					target: "runtime::registrar",
+					"Failed to move the deposit of para {:?} to the named reserve: {:?}",
+					id,
+					error,
+				);
+				let res = <T as Config>::Currency::reserve(&info.manager, deposit);
+				debug_assert!(res.is_ok());
+			}
+			paras += 1;
+		}
+
+		T::DbWeight::get().reads_writes(paras + 1, paras)
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -678,6 +827,7 @@ mod tests {
 
 	parameter_types! {
 		pub const ExistentialDeposit: Balance = 1;
+		pub const MaxReserves: u32 = 10;
 	}
 
 	impl pallet_balances::Config for Test {
@@ -687,7 +837,7 @@ mod tests {
 		type ExistentialDeposit = ExistentialDeposit;
 		type AccountStore = System;
 		type MaxLocks = ();
-		type MaxReserves = ();
+		type MaxReserves = MaxReserves;
 		type ReserveIdentifier = [u8; 8];
 		type WeightInfo = ();
 	}
@@ -715,6 +865,7 @@ mod tests {
 		pub const ParaDeposit: Balance = 10;
 		pub const DataDepositPerByte: Balance = 1;
 		pub const MaxRetries: u32 = 3;
+		pub const MaxMetadataLength: u32 = 32;
 	}
 
 	impl Config for Test {
@@ -725,6 +876,7 @@ mod tests {
 		type OnSwap = MockSwap;
 		type ParaDeposit = ParaDeposit;
 		type DataDepositPerByte = DataDepositPerByte;
+		type MaxMetadataLength = MaxMetadataLength;
 		type WeightInfo = TestWeightInfo;
 	}
 
@@ -863,6 +1015,25 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn deposits_are_migrated_to_named_reserve() {
+		new_test_ext().execute_with(|| {
+			run_to_block(1);
+			let deposit = <Test as Config>::ParaDeposit::get();
+			assert_ok!(Registrar::reserve(Origin::signed(1)));
+			assert_eq!(Balances::reserved_balance_named(&PARA_DEPOSIT_ID, &1), deposit);
+
+			// Turn the deposit into an anonymous reserve, as it was before the migration.
+			Balances::unreserve_named(&PARA_DEPOSIT_ID, &1, deposit);
+			assert_ok!(Balances::reserve(&1, deposit));
+			frame_support::traits::StorageVersion::new(0).put::<Registrar>();
+
+			migration::migrate_to_latest::<Test>();
+			assert_eq!(Balances::reserved_balance_named(&PARA_DEPOSIT_ID, &1), deposit);
+			assert_eq!(Balances::reserved_balance(&1), deposit);
+		});
+	}
+
 	#[test]
 	fn register_works() {
 		new_test_ext().execute_with(|| {
@@ -990,6 +1161,54 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn metadata_is_attested_by_the_register_origin() {
+		new_test_ext().execute_with(|| {
+			run_to_block(1);
+			let para_id = LOWEST_PUBLIC_ID;
+			let set_metadata = |origin, name: &[u8]| {
+				Registrar::set_metadata(
+					origin,
+					para_id,
+					name.to_vec(),
+					b"https://para.example".to_vec(),
+					Hash::repeat_byte(1),
+				)
+			};
+
+			assert_noop!(set_metadata(Origin::root(), b"Para"), Error::<Test>::NotRegistered);
+			assert_ok!(Registrar::reserve(Origin::signed(1)));
+			assert_noop!(set_metadata(Origin::signed(1), b"Para"), BadOrigin);
+			assert_noop!(set_metadata(Origin::root(), &[0; 33]), Error::<Test>::MetadataTooLong);
+
+			assert_ok!(set_metadata(Origin::root(), b"Para"));
+			let metadata = ParaMetadata {
+				name: b"Para".to_vec(),
+				website: b"https://para.example".to_vec(),
+				contact_hash: Hash::repeat_byte(1),
+			};
+			assert_eq!(Registrar::para_metadata(para_id), Some(metadata.clone()));
+			assert_eq!(Registrar::all_para_metadata(), vec![(para_id, metadata)]);
+			// Only the registration deposit is reserved.
+			assert_eq!(Balances::reserved_balance(&1), <Test as Config>::ParaDeposit::get());
+
+			assert_ok!(Registrar::clear_metadata(Origin::root(), para_id));
+			assert_eq!(Registrar::para_metadata(para_id), None);
+
+			// The metadata goes away with the para.
+			assert_ok!(Registrar::register(
+				Origin::signed(1),
+				para_id,
+				test_genesis_head(32),
+				test_validation_code(32),
+			));
+			assert_ok!(set_metadata(Origin::root(), b"Para"));
+			run_to_session(2);
+			assert_ok!(Registrar::deregister(Origin::root(), para_id));
+			assert!(Registrar::all_para_metadata().is_empty());
+		});
+	}
+
 	#[test]
 	fn deregister_handles_basic_errors() {
 		new_test_ext().execute_with(|| {
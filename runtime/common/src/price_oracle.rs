@@ -0,0 +1,607 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Pallet for the prices of SEL and other assets in USD, posted by whitelisted feeders.
+//!
+//! Feeders are added and removed by the `ManagerOrigin`. They post prices with `feed`, either
+//! themselves or through the offchain worker of a node holding their key of type [`KEY_TYPE`]. The
+//! price of an asset is the median of the prices its feeders posted within the last `StalePeriod`
+//! blocks, and is unavailable while fewer than `MinFeeds` of them are that recent.
+//!
+//! The offchain worker reads the HTTP endpoints to fetch prices from as a SCALE-encoded
+//! `Vec<(AssetId, Vec<u8>)>` of assets and URLs from the persistent offchain storage under
+//! [`ENDPOINTS_KEY`]. Each URL must respond with the price in USD as a decimal number in plain
+//! text, e.g. `0.0512`. Prices are fed about twice per `StalePeriod`.
+
+use frame_support::{pallet_prelude::*, traits::EnsureOrigin};
+use frame_system::{
+	offchain::{AppCrypto, CreateSignedTransaction, SendSignedTransaction, Signer},
+	pallet_prelude::*,
+};
+pub use pallet::*;
+use sp_core::crypto::KeyTypeId;
+use sp_runtime::{
+	offchain::{http, storage::StorageValueRef, Duration},
+	traits::Saturating,
+	FixedPointNumber, FixedU128,
+};
+use sp_std::prelude::*;
+
+/// The price of an asset in USD.
+pub type Price = FixedU128;
+
+/// The key type of the keys the offchain worker signs feeds with.
+pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"orcl");
+
+/// The key of the HTTP endpoints in the persistent offchain storage.
+pub const ENDPOINTS_KEY: &[u8] = b"price-oracle::endpoints";
+
+/// The key of the block prices were last fed at in the persistent offchain storage.
+const LAST_FEED_KEY: &[u8] = b"price-oracle::last-feed";
+
+/// The time an HTTP request may take.
+const HTTP_TIMEOUT_MS: u64 = 5_000;
+
+/// The keys the offchain worker signs feeds with.
+pub mod crypto {
+	use super::KEY_TYPE;
+	use sp_core::sr25519::Signature as Sr25519Signature;
+	use sp_runtime::{
+		app_crypto::{app_crypto, sr25519},
+		traits::Verify,
+		MultiSignature, MultiSigner,
+	};
+
+	app_crypto!(sr25519, KEY_TYPE);
+
+	/// Signs feeds with an sr25519 key of type [`KEY_TYPE`].
+	pub struct OracleAuthId;
+
+	impl frame_system::offchain::AppCrypto<MultiSigner, MultiSignature> for OracleAuthId {
+		type RuntimeAppPublic = Public;
+		type GenericSignature = sp_core::sr25519::Signature;
+		type GenericPublic = sp_core::sr25519::Public;
+	}
+
+	impl frame_system::offchain::AppCrypto<<Sr25519Signature as Verify>::Signer, Sr25519Signature>
+		for OracleAuthId
+	{
+		type RuntimeAppPublic = Public;
+		type GenericSignature = sp_core::sr25519::Signature;
+		type GenericPublic = sp_core::sr25519::Public;
+	}
+}
+
+/// A price and the block it was posted at.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct TimestampedPrice<BlockNumber> {
+	/// The price in USD.
+	pub price: Price,
+	/// The block the price was posted at.
+	pub posted: BlockNumber,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: CreateSignedTransaction<Call<Self>> + frame_system::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The identifier of an asset.
+		type AssetId: Parameter + Member + Copy + Ord + MaxEncodedLen;
+
+		/// The origin which may add and remove feeders.
+		type ManagerOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The keys the offchain worker signs feeds with.
+		type AuthorityId: AppCrypto<Self::Public, Self::Signature>;
+
+		/// The maximum number of feeders.
+		#[pallet::constant]
+		type MaxFeeders: Get<u32>;
+
+		/// The maximum number of prices posted with a single feed.
+		#[pallet::constant]
+		type MaxFeedAssets: Get<u32>;
+
+		/// The minimum number of recent prices needed for the price of an asset.
+		#[pallet::constant]
+		type MinFeeds: Get<u32>;
+
+		/// The number of blocks after which a posted price is no longer considered.
+		#[pallet::constant]
+		type StalePeriod: Get<Self::BlockNumber>;
+	}
+
+	/// The sorted accounts which may post prices.
+	#[pallet::storage]
+	#[pallet::getter(fn feeders)]
+	pub type Feeders<T: Config> =
+		StorageValue<_, BoundedVec<T::AccountId, T::MaxFeeders>, ValueQuery>;
+
+	/// The prices posted by each feeder.
+	#[pallet::storage]
+	pub type RawPrices<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Twox64Concat,
+		T::AssetId,
+		TimestampedPrice<T::BlockNumber>,
+		OptionQuery,
+	>;
+
+	/// The median of the recent prices posted for each asset.
+	#[pallet::storage]
+	pub type Prices<T: Config> =
+		StorageMap<_, Twox64Concat, T::AssetId, TimestampedPrice<T::BlockNumber>, OptionQuery>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// An account was allowed to post prices.
+		FeederAdded { who: T::AccountId },
+		/// An account was no longer allowed to post prices, and its prices were removed.
+		FeederRemoved { who: T::AccountId },
+		/// The price of an asset changed. `None` if too few recent prices were posted.
+		PriceUpdated { asset: T::AssetId, price: Option<Price> },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The account is a feeder already.
+		AlreadyFeeder,
+		/// The account is not a feeder.
+		NotFeeder,
+		/// The maximum number of feeders was reached.
+		TooManyFeeders,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn offchain_worker(now: BlockNumberFor<T>) {
+			if let Err(error) = Self::fetch_and_feed(now) {
+				log::debug!(target: "runtime::price-oracle", "Not feeding prices: {}", error);
+			}
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Allow `who` to post prices.
+		#[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+		pub fn add_feeder(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+			T::ManagerOrigin::ensure_origin(origin)?;
+
+			Feeders::<T>::try_mutate(|feeders| -> DispatchResult {
+				let position =
+					feeders.binary_search(&who).err().ok_or(Error::<T>::AlreadyFeeder)?;
+				feeders
+					.try_insert(position, who.clone())
+					.map_err(|_| Error::<T>::TooManyFeeders)?;
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::<T>::FeederAdded { who });
+			Ok(())
+		}
+
+		/// Disallow `who` to post prices and remove the prices it posted.
+		///
+		/// The prices of assets are updated with the next feed.
+		#[pallet::weight(
+			T::DbWeight::get().reads_writes(1, 1 + T::MaxFeedAssets::get() as Weight)
+		)]
+		pub fn remove_feeder(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+			T::ManagerOrigin::ensure_origin(origin)?;
+
+			Feeders::<T>::try_mutate(|feeders| -> DispatchResult {
+				let position = feeders.binary_search(&who).map_err(|_| Error::<T>::NotFeeder)?;
+				feeders.remove(position);
+				Ok(())
+			})?;
+			let _ = RawPrices::<T>::remove_prefix(&who, None);
+
+			Self::deposit_event(Event::<T>::FeederRemoved { who });
+			Ok(())
+		}
+
+		/// Post the current prices of assets, and update their median price.
+		#[pallet::weight(Pallet::<T>::feed_weight(prices.len() as u32))]
+		pub fn feed(
+			origin: OriginFor<T>,
+			prices: BoundedVec<(T::AssetId, Price), T::MaxFeedAssets>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let feeders = Feeders::<T>::get();
+			ensure!(feeders.binary_search(&who).is_ok(), Error::<T>::NotFeeder);
+
+			let now = frame_system::Pallet::<T>::block_number();
+			for (asset, price) in prices {
+				RawPrices::<T>::insert(&who, asset, TimestampedPrice { price, posted: now });
+				Self::update_price(&feeders, asset, now);
+			}
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The current price of `asset`, unless too few recent prices were posted.
+	pub fn price(asset: T::AssetId) -> Option<Price> {
+		let price = Prices::<T>::get(asset)?;
+		let now = frame_system::Pallet::<T>::block_number();
+		Self::is_recent(price.posted, now).then(|| price.price)
+	}
+
+	fn is_recent(posted: T::BlockNumber, now: T::BlockNumber) -> bool {
+		posted.saturating_add(T::StalePeriod::get()) > now
+	}
+
+	fn feed_weight(assets: u32) -> Weight {
+		let assets = assets as Weight;
+		let reads = assets.saturating_mul(T::MaxFeeders::get() as Weight + 1);
+		T::DbWeight::get().reads_writes(reads.saturating_add(1), assets.saturating_mul(2))
+	}
+
+	/// Set the price of `asset` to the median of the recent prices of `feeders`.
+	fn update_price(feeders: &[T::AccountId], asset: T::AssetId, now: T::BlockNumber) {
+		let mut recent: Vec<_> = feeders
+			.iter()
+			.filter_map(|feeder| RawPrices::<T>::get(feeder, asset))
+			.filter(|price| Self::is_recent(price.posted, now))
+			.collect();
+
+		let median = if recent.len() < T::MinFeeds::get().max(1) as usize {
+			None
+		} else {
+			recent.sort_by_key(|price| price.price);
+			Some(recent[recent.len() / 2])
+		};
+
+		if Prices::<T>::get(asset).map(|price| price.price) != median.map(|price| price.price) {
+			Self::deposit_event(Event::<T>::PriceUpdated {
+				asset,
+				price: median.map(|price| price.price),
+			});
+		}
+		Prices::<T>::set(asset, median);
+	}
+
+	/// Fetch the prices from the configured endpoints and feed them, if due.
+	fn fetch_and_feed(now: T::BlockNumber) -> Result<(), &'static str> {
+		let endpoints = StorageValueRef::persistent(ENDPOINTS_KEY)
+			.get::<Vec<(T::AssetId, Vec<u8>)>>()
+			.map_err(|_| "endpoints undecodable")?
+			.ok_or("no endpoints configured")?;
+
+		let signer = Signer::<T, T::AuthorityId>::any_account();
+		if !signer.can_sign() {
+			return Err("no oracle key in the keystore")
+		}
+
+		let last_feed = StorageValueRef::persistent(LAST_FEED_KEY);
+		let interval = T::StalePeriod::get() / 2u32.into();
+		let due = |last: Result<Option<T::BlockNumber>, _>| match last {
+			Ok(Some(last)) if last.saturating_add(interval) > now => Err(()),
+			_ => Ok(now),
+		};
+		last_feed.mutate(due).map_err(|_| "not due")?;
+
+		let mut prices = BoundedVec::<_, T::MaxFeedAssets>::default();
+		for (asset, url) in endpoints {
+			match Self::fetch_price(&url) {
+				Ok(price) => prices.try_push((asset, price)).map_err(|_| "too many endpoints")?,
+				Err(error) => log::warn!(
+					target: "runtime::price-oracle",
+					"Fetching the price of {:?} failed: {}",
+					asset,
+					error,
+				),
+			}
+		}
+		if prices.is_empty() {
+			return Err("no prices fetched")
+		}
+
+		match signer.send_signed_transaction(|_| Call::feed { prices: prices.clone() }) {
+			Some((_, Ok(()))) => Ok(()),
+			_ => Err("submitting the feed failed"),
+		}
+	}
+
+	/// Fetch a price from the endpoint at `url`.
+	fn fetch_price(url: &[u8]) -> Result<Price, &'static str> {
+		let url = sp_std::str::from_utf8(url).map_err(|_| "invalid url")?;
+		let deadline = sp_io::offchain::timestamp().add(Duration::from_millis(HTTP_TIMEOUT_MS));
+		let response = http::Request::get(url)
+			.deadline(deadline)
+			.send()
+			.map_err(|_| "request failed")?
+			.try_wait(deadline)
+			.map_err(|_| "request timed out")?
+			.map_err(|_| "request failed")?;
+		if response.code != 200 {
+			return Err("unexpected response status")
+		}
+
+		parse_price(&response.body().collect::<Vec<u8>>()).ok_or("invalid price")
+	}
+}
+
+/// Parse a non-negative decimal number with at most 18 decimal places.
+fn parse_price(body: &[u8]) -> Option<Price> {
+	let text = sp_std::str::from_utf8(body).ok()?.trim();
+	let (integer, fraction) = text.split_once('.').unwrap_or((text, ""));
+	let digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+	if !digits(integer) || !(fraction.is_empty() || digits(fraction)) || fraction.len() > 18 {
+		return None
+	}
+
+	let integer: u128 = integer.parse().ok()?;
+	let fraction: u128 = if fraction.is_empty() { 0 } else { fraction.parse().ok()? };
+	let scale = 10u128.pow(18 - fraction.len() as u32);
+	integer
+		.checked_mul(Price::DIV)?
+		.checked_add(fraction * scale)
+		.map(Price::from_inner)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use crate::price_oracle;
+	use frame_support::{assert_noop, assert_ok, parameter_types};
+	use frame_system::EnsureRoot;
+	use sp_core::{
+		offchain::{testing, OffchainDbExt, OffchainWorkerExt, TransactionPoolExt},
+		sr25519::{Public, Signature},
+		H256,
+	};
+	use sp_keystore::{testing::KeyStore, KeystoreExt, SyncCryptoStore};
+	use sp_runtime::{
+		testing::{Header, TestXt},
+		traits::{BadOrigin, BlakeTwo256, Extrinsic as ExtrinsicT, IdentityLookup},
+	};
+	use std::sync::Arc;
+
+	type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+	type Block = frame_system::mocking::MockBlock<Test>;
+	type Extrinsic = TestXt<Call, ()>;
+
+	frame_support::construct_runtime!(
+		pub enum Test where
+			Block = Block,
+			NodeBlock = Block,
+			UncheckedExtrinsic = UncheckedExtrinsic,
+		{
+			System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+			PriceOracle: price_oracle::{Pallet, Call, Storage, Event<T>},
+		}
+	);
+
+	parameter_types! {
+		pub const BlockHashCount: u32 = 250;
+	}
+
+	impl frame_system::Config for Test {
+		type BaseCallFilter = frame_support::traits::Everything;
+		type BlockWeights = ();
+		type BlockLength = ();
+		type DbWeight = ();
+		type Origin = Origin;
+		type Call = Call;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = Public;
+		type Lookup = IdentityLookup<Public>;
+		type Header = Header;
+		type Event = Event;
+		type BlockHashCount = BlockHashCount;
+		type Version = ();
+		type PalletInfo = PalletInfo;
+		type AccountData = ();
+		type OnNewAccount = ();
+		type OnKilledAccount = ();
+		type SystemWeightInfo = ();
+		type SS58Prefix = ();
+		type OnSetCode = ();
+		type MaxConsumers = frame_support::traits::ConstU32<16>;
+	}
+
+	impl frame_system::offchain::SigningTypes for Test {
+		type Public = Public;
+		type Signature = Signature;
+	}
+
+	impl<LocalCall> frame_system::offchain::SendTransactionTypes<LocalCall> for Test
+	where
+		Call: From<LocalCall>,
+	{
+		type OverarchingCall = Call;
+		type Extrinsic = Extrinsic;
+	}
+
+	impl<LocalCall> frame_system::offchain::CreateSignedTransaction<LocalCall> for Test
+	where
+		Call: From<LocalCall>,
+	{
+		fn create_transaction<C: AppCrypto<Public, Signature>>(
+			call: Call,
+			_public: Public,
+			_account: Public,
+			nonce: u64,
+		) -> Option<(Call, <Extrinsic as ExtrinsicT>::SignaturePayload)> {
+			Some((call, (nonce, ())))
+		}
+	}
+
+	parameter_types! {
+		pub const MaxFeeders: u32 = 3;
+		pub const MaxFeedAssets: u32 = 2;
+		pub const MinFeeds: u32 = 2;
+		pub const StalePeriod: u64 = 10;
+	}
+
+	impl Config for Test {
+		type Event = Event;
+		type AssetId = u32;
+		type ManagerOrigin = EnsureRoot<Public>;
+		type AuthorityId = crypto::OracleAuthId;
+		type MaxFeeders = MaxFeeders;
+		type MaxFeedAssets = MaxFeedAssets;
+		type MinFeeds = MinFeeds;
+		type StalePeriod = StalePeriod;
+	}
+
+	const SEL: u32 = 0;
+
+	fn account(id: u8) -> Public {
+		Public::from_raw([id; 32])
+	}
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		let mut ext = sp_io::TestExternalities::new(t);
+		ext.execute_with(|| {
+			System::set_block_number(1);
+			for id in 1..=3 {
+				assert_ok!(PriceOracle::add_feeder(Origin::root(), account(id)));
+			}
+		});
+		ext
+	}
+
+	fn feed(id: u8, price: Price) {
+		let prices = vec![(SEL, price)].try_into().unwrap();
+		assert_ok!(PriceOracle::feed(Origin::signed(account(id)), prices));
+	}
+
+	#[test]
+	fn feeders_are_managed() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(
+				PriceOracle::add_feeder(Origin::signed(account(1)), account(4)),
+				BadOrigin
+			);
+			assert_noop!(
+				PriceOracle::add_feeder(Origin::root(), account(1)),
+				Error::<Test>::AlreadyFeeder
+			);
+			assert_noop!(
+				PriceOracle::add_feeder(Origin::root(), account(4)),
+				Error::<Test>::TooManyFeeders
+			);
+
+			feed(3, Price::from_inner(1));
+			assert_ok!(PriceOracle::remove_feeder(Origin::root(), account(3)));
+			assert!(RawPrices::<Test>::get(account(3), SEL).is_none());
+			assert_noop!(
+				PriceOracle::remove_feeder(Origin::root(), account(3)),
+				Error::<Test>::NotFeeder
+			);
+			let prices = vec![(SEL, Price::from_inner(1))].try_into().unwrap();
+			assert_noop!(
+				PriceOracle::feed(Origin::signed(account(3)), prices),
+				Error::<Test>::NotFeeder
+			);
+		});
+	}
+
+	#[test]
+	fn price_is_the_median_of_recent_feeds() {
+		new_test_ext().execute_with(|| {
+			feed(1, Price::saturating_from_rational(5, 100));
+			assert_eq!(PriceOracle::price(SEL), None);
+
+			feed(2, Price::saturating_from_rational(7, 100));
+			feed(3, Price::saturating_from_rational(6, 100));
+			assert_eq!(PriceOracle::price(SEL), Some(Price::saturating_from_rational(6, 100)));
+
+			// The feeds of 1 and 3 go stale, so the one of 2 alone is not enough.
+			System::set_block_number(5);
+			feed(2, Price::saturating_from_rational(8, 100));
+			System::set_block_number(11);
+			assert_eq!(PriceOracle::price(SEL), None);
+			feed(2, Price::saturating_from_rational(8, 100));
+			assert_eq!(PriceOracle::price(SEL), None);
+
+			feed(1, Price::saturating_from_rational(9, 100));
+			assert_eq!(PriceOracle::price(SEL), Some(Price::saturating_from_rational(9, 100)));
+		});
+	}
+
+	#[test]
+	fn prices_are_parsed() {
+		assert_eq!(parse_price(b"12"), Some(Price::saturating_from_integer(12)));
+		assert_eq!(parse_price(b" 0.0512\n"), Some(Price::saturating_from_rational(512, 10_000)));
+		assert_eq!(parse_price(b"1.000000000000000001"), Some(Price::from_inner(Price::DIV + 1)));
+		assert_eq!(parse_price(b"1.0000000000000000001"), None);
+		assert_eq!(parse_price(b"-1"), None);
+		assert_eq!(parse_price(b".5"), None);
+		assert_eq!(parse_price(b"1."), Some(Price::saturating_from_integer(1)));
+		assert_eq!(parse_price(b"{\"usd\": 1}"), None);
+	}
+
+	#[test]
+	fn offchain_worker_feeds_fetched_prices() {
+		let (offchain, offchain_state) = testing::TestOffchainExt::new();
+		let (pool, pool_state) = testing::TestTransactionPoolExt::new();
+		let keystore = KeyStore::new();
+		SyncCryptoStore::sr25519_generate_new(&keystore, KEY_TYPE, Some("//Feeder")).unwrap();
+
+		let mut ext = new_test_ext();
+		ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+		ext.register_extension(OffchainDbExt::new(offchain));
+		ext.register_extension(TransactionPoolExt::new(pool));
+		ext.register_extension(KeystoreExt(Arc::new(keystore)));
+
+		offchain_state.write().expect_request(testing::PendingRequest {
+			method: "GET".into(),
+			uri: "https://prices.example/sel-usd".into(),
+			response: Some(b"0.05".to_vec()),
+			sent: true,
+			..Default::default()
+		});
+
+		ext.execute_with(|| {
+			let endpoints = vec![(SEL, b"https://prices.example/sel-usd".to_vec())];
+			StorageValueRef::persistent(ENDPOINTS_KEY).set(&endpoints);
+
+			PriceOracle::offchain_worker(1);
+
+			let tx = pool_state.write().transactions.pop().unwrap();
+			let tx = Extrinsic::decode(&mut &*tx).unwrap();
+			assert_eq!(tx.signature.unwrap().0, 0);
+			let prices = vec![(SEL, Price::saturating_from_rational(5, 100))].try_into().unwrap();
+			assert_eq!(tx.call, Call::PriceOracle(price_oracle::Call::feed { prices }));
+
+			// Not due again before half of the stale period passed.
+			PriceOracle::offchain_worker(2);
+			assert!(pool_state.read().transactions.is_empty());
+		});
+	}
+}
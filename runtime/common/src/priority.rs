@@ -0,0 +1,99 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Priority lanes of the transaction pool.
+//!
+//! The priority range is split into one disjoint range per [`Lane`], so a transaction is never
+//! outbid by one of a lower lane, whatever tip it pays or score it claims. Within a lane,
+//! transactions keep the order of the priority their signed extensions or `ValidateUnsigned`
+//! implementation gave them.
+
+use sp_runtime::transaction_validity::{TransactionPriority, TransactionValidity};
+
+/// The lanes of the transaction pool, from lowest to highest priority.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Lane {
+	/// All signed transactions, except for election solutions.
+	Normal,
+	/// Signed and unsigned solutions of the election provider.
+	ElectionSolution,
+	/// Dispute statements and reports of validator misbehaviour.
+	Dispute,
+	/// Data validators submit for the operation of parachains, like PVF pre-checking votes and
+	/// heartbeats.
+	ParachainData,
+}
+
+const LANES: TransactionPriority = 4;
+const LANE_WIDTH: TransactionPriority = TransactionPriority::max_value() / LANES;
+
+impl Lane {
+	/// The lowest priority of a transaction in this lane.
+	pub const fn min_priority(self) -> TransactionPriority {
+		LANE_WIDTH * self as TransactionPriority
+	}
+
+	/// The highest priority of a transaction in this lane.
+	pub const fn max_priority(self) -> TransactionPriority {
+		self.min_priority() + (LANE_WIDTH - 1)
+	}
+
+	/// Scale `priority` into the range of this lane, keeping its order relative to the priority
+	/// of other transactions of the lane.
+	pub fn priority(self, priority: TransactionPriority) -> TransactionPriority {
+		self.min_priority() + (priority / LANES).min(LANE_WIDTH - 1)
+	}
+}
+
+/// Move the priority of a valid transaction into `lane`.
+pub fn in_lane(validity: TransactionValidity, lane: Lane) -> TransactionValidity {
+	validity.map(|mut valid| {
+		valid.priority = lane.priority(valid.priority);
+		valid
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_runtime::transaction_validity::ValidTransaction;
+
+	fn priority_in(lane: Lane, priority: TransactionPriority) -> TransactionPriority {
+		let valid = ValidTransaction { priority, ..Default::default() };
+		in_lane(Ok(valid), lane).unwrap().priority
+	}
+
+	#[test]
+	fn lanes_do_not_overlap() {
+		let lanes = [Lane::Normal, Lane::ElectionSolution, Lane::Dispute, Lane::ParachainData];
+		for pair in lanes.windows(2) {
+			let (lower, higher) = (pair[0], pair[1]);
+			assert!(lower.max_priority() < higher.min_priority());
+			assert!(priority_in(lower, TransactionPriority::max_value()) < priority_in(higher, 0));
+		}
+		assert_eq!(Lane::Normal.min_priority(), 0);
+		assert!(Lane::ParachainData.max_priority() <= TransactionPriority::max_value());
+	}
+
+	#[test]
+	fn order_within_a_lane_is_kept() {
+		for lane in [Lane::Normal, Lane::ElectionSolution, Lane::Dispute, Lane::ParachainData] {
+			assert!(priority_in(lane, 100) < priority_in(lane, 1_000));
+			assert!(priority_in(lane, 0) >= lane.min_priority());
+			assert!(priority_in(lane, TransactionPriority::max_value()) <= lane.max_priority());
+		}
+	}
+}
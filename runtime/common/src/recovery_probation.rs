@@ -0,0 +1,437 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Pallet hardening social recovery with `pallet-recovery`.
+//!
+//! Recoveries are claimed through this pallet, which puts the recovered account on probation
+//! for `ProbationPeriod` blocks. During probation, the rescuer can only dispatch calls allowed by
+//! `ProbationFilter` as the recovered account, and the original owner of the account can revoke
+//! the rescuer's access with `cancel_recovered`, which is meant to be usable by a proxy limited
+//! to cancellations. Runtimes are expected to filter out the calls of `pallet-recovery` rejected
+//! by [`Pallet::recovery_call_allowed`], which also enforces a `MinDelayPeriod` on new recovery
+//! configurations.
+
+use frame_support::{
+	dispatch::{GetDispatchInfo, PostDispatchInfo},
+	pallet_prelude::*,
+	traits::{Contains, OriginTrait},
+};
+use frame_system::pallet_prelude::*;
+pub use pallet::*;
+use sp_runtime::traits::{Dispatchable, Saturating};
+use sp_std::prelude::*;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + pallet_recovery::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The overarching call type.
+		type Call: Parameter
+			+ Dispatchable<Origin = Self::Origin, PostInfo = PostDispatchInfo>
+			+ GetDispatchInfo
+			+ IsType<<Self as frame_system::Config>::Call>;
+
+		/// The number of blocks a recovered account stays on probation.
+		#[pallet::constant]
+		type ProbationPeriod: Get<Self::BlockNumber>;
+
+		/// The minimum delay of new recovery configurations.
+		#[pallet::constant]
+		type MinDelayPeriod: Get<Self::BlockNumber>;
+
+		/// The calls a rescuer may dispatch as an account on probation.
+		type ProbationFilter: Contains<<Self as Config>::Call>;
+	}
+
+	/// The block at which the probation of a recovered account ends.
+	#[pallet::storage]
+	pub type Probation<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, T::BlockNumber, OptionQuery>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A recovery was claimed and the account is on probation until `probation_ends`.
+		RecoveryClaimed {
+			lost: T::AccountId,
+			rescuer: T::AccountId,
+			probation_ends: T::BlockNumber,
+		},
+		/// A call was dispatched by the rescuer as a recovered account.
+		RecoveredCallDispatched {
+			lost: T::AccountId,
+			rescuer: T::AccountId,
+			result: DispatchResult,
+		},
+		/// The owner of a recovered account revoked the access of the rescuer.
+		RecoveryCancelled { lost: T::AccountId, rescuer: T::AccountId, during_probation: bool },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The account was not recovered by the given rescuer.
+		NotRecovered,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Claim the recovery of `account` as with `pallet_recovery::claim_recovery`, putting the
+		/// account on probation.
+		///
+		/// The origin must be the rescuer who initiated the recovery.
+		#[pallet::weight({
+			let claim = pallet_recovery::Call::<T>::claim_recovery { account: account.clone() };
+			claim.get_dispatch_info().weight.saturating_add(T::DbWeight::get().writes(1))
+		})]
+		pub fn claim_recovery(origin: OriginFor<T>, account: T::AccountId) -> DispatchResult {
+			let rescuer = ensure_signed(origin.clone())?;
+			pallet_recovery::Pallet::<T>::claim_recovery(origin, account.clone())?;
+
+			let probation_ends =
+				frame_system::Pallet::<T>::block_number().saturating_add(T::ProbationPeriod::get());
+			Probation::<T>::insert(&account, probation_ends);
+			Self::deposit_event(Event::<T>::RecoveryClaimed {
+				lost: account,
+				rescuer,
+				probation_ends,
+			});
+			Ok(())
+		}
+
+		/// Dispatch `call` as the recovered `account`.
+		///
+		/// The origin must be the rescuer of `account`. While `account` is on probation, only
+		/// calls allowed by `ProbationFilter` can be dispatched, including calls nested in `call`.
+		/// The result of the call is reported in `RecoveredCallDispatched`.
+		#[pallet::weight({
+			let dispatch_info = call.get_dispatch_info();
+			(
+				dispatch_info.weight.saturating_add(T::DbWeight::get().reads(2)),
+				dispatch_info.class,
+			)
+		})]
+		pub fn as_recovered(
+			origin: OriginFor<T>,
+			account: T::AccountId,
+			call: Box<<T as Config>::Call>,
+		) -> DispatchResult {
+			let rescuer = ensure_signed(origin)?;
+			ensure!(
+				pallet_recovery::Proxy::<T>::get(&rescuer).as_ref() == Some(&account),
+				Error::<T>::NotRecovered
+			);
+
+			let mut origin: T::Origin = frame_system::RawOrigin::Signed(account.clone()).into();
+			if Self::in_probation(&account) {
+				origin.add_filter(|c: &<T as frame_system::Config>::Call| {
+					T::ProbationFilter::contains(<T as Config>::Call::from_ref(c))
+				});
+			}
+			let result = call.dispatch(origin).map(|_| ()).map_err(|e| e.error);
+			Self::deposit_event(Event::<T>::RecoveredCallDispatched {
+				lost: account,
+				rescuer,
+				result,
+			});
+			Ok(())
+		}
+
+		/// Revoke the access of `rescuer` to the recovered origin account.
+		///
+		/// The origin must be the recovered account, usually through a proxy.
+		#[pallet::weight(T::DbWeight::get().reads_writes(2, 3))]
+		pub fn cancel_recovered(origin: OriginFor<T>, rescuer: T::AccountId) -> DispatchResult {
+			let lost = ensure_signed(origin)?;
+			ensure!(
+				pallet_recovery::Proxy::<T>::get(&rescuer).as_ref() == Some(&lost),
+				Error::<T>::NotRecovered
+			);
+
+			let during_probation = Self::in_probation(&lost);
+			pallet_recovery::Proxy::<T>::remove(&rescuer);
+			frame_system::Pallet::<T>::dec_consumers(&rescuer);
+			Probation::<T>::remove(&lost);
+			Self::deposit_event(Event::<T>::RecoveryCancelled { lost, rescuer, during_probation });
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Whether the recovered `account` is on probation.
+	pub fn in_probation(account: &T::AccountId) -> bool {
+		Probation::<T>::get(account)
+			.map_or(false, |ends| frame_system::Pallet::<T>::block_number() < ends)
+	}
+
+	/// Whether `call` may be dispatched directly, or has to go through this pallet instead.
+	///
+	/// Recoveries must be claimed through this pallet, accounts on probation can only be used
+	/// through this pallet and new recovery configurations must have at least `MinDelayPeriod`.
+	pub fn recovery_call_allowed(call: &pallet_recovery::Call<T>) -> bool {
+		match call {
+			pallet_recovery::Call::create_recovery { delay_period, .. } =>
+				*delay_period >= T::MinDelayPeriod::get(),
+			pallet_recovery::Call::claim_recovery { .. } => false,
+			pallet_recovery::Call::as_recovered { account, .. } => !Self::in_probation(account),
+			_ => true,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use crate::recovery_probation;
+	use frame_support::{assert_noop, assert_ok, parameter_types};
+	use sp_core::H256;
+	use sp_runtime::{
+		testing::Header,
+		traits::{BlakeTwo256, IdentityLookup},
+	};
+
+	type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+	type Block = frame_system::mocking::MockBlock<Test>;
+
+	frame_support::construct_runtime!(
+		pub enum Test where
+			Block = Block,
+			NodeBlock = Block,
+			UncheckedExtrinsic = UncheckedExtrinsic,
+		{
+			System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+			Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+			Recovery: pallet_recovery::{Pallet, Call, Storage, Event<T>},
+			RecoveryProbation: recovery_probation::{Pallet, Call, Storage, Event<T>},
+		}
+	);
+
+	parameter_types! {
+		pub const BlockHashCount: u32 = 250;
+	}
+
+	impl frame_system::Config for Test {
+		type BaseCallFilter = frame_support::traits::Everything;
+		type BlockWeights = ();
+		type BlockLength = ();
+		type DbWeight = ();
+		type Origin = Origin;
+		type Call = Call;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<u64>;
+		type Header = Header;
+		type Event = Event;
+		type BlockHashCount = BlockHashCount;
+		type Version = ();
+		type PalletInfo = PalletInfo;
+		type AccountData = pallet_balances::AccountData<u64>;
+		type OnNewAccount = ();
+		type OnKilledAccount = ();
+		type SystemWeightInfo = ();
+		type SS58Prefix = ();
+		type OnSetCode = ();
+		type MaxConsumers = frame_support::traits::ConstU32<16>;
+	}
+
+	parameter_types! {
+		pub const ExistentialDeposit: u64 = 1;
+	}
+
+	impl pallet_balances::Config for Test {
+		type Balance = u64;
+		type Event = Event;
+		type DustRemoval = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type AccountStore = System;
+		type MaxLocks = ();
+		type MaxReserves = ();
+		type ReserveIdentifier = [u8; 8];
+		type WeightInfo = ();
+	}
+
+	parameter_types! {
+		pub const ConfigDepositBase: u64 = 10;
+		pub const FriendDepositFactor: u64 = 1;
+		pub const MaxFriends: u16 = 3;
+		pub const RecoveryDeposit: u64 = 10;
+	}
+
+	impl pallet_recovery::Config for Test {
+		type Event = Event;
+		type Call = Call;
+		type Currency = Balances;
+		type ConfigDepositBase = ConfigDepositBase;
+		type FriendDepositFactor = FriendDepositFactor;
+		type MaxFriends = MaxFriends;
+		type RecoveryDeposit = RecoveryDeposit;
+	}
+
+	parameter_types! {
+		pub const ProbationPeriod: u64 = 20;
+		pub const MinDelayPeriod: u64 = 10;
+	}
+
+	pub struct NoTransfers;
+	impl Contains<Call> for NoTransfers {
+		fn contains(c: &Call) -> bool {
+			!matches!(c, Call::Balances(..))
+		}
+	}
+
+	impl Config for Test {
+		type Event = Event;
+		type Call = Call;
+		type ProbationPeriod = ProbationPeriod;
+		type MinDelayPeriod = MinDelayPeriod;
+		type ProbationFilter = NoTransfers;
+	}
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		pallet_balances::GenesisConfig::<Test> {
+			balances: vec![(1, 100), (2, 100), (3, 100), (4, 100)],
+		}
+		.assimilate_storage(&mut t)
+		.unwrap();
+		let mut ext = sp_io::TestExternalities::new(t);
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+
+	/// Let friends 2 and 3 recover account 1 for rescuer 4.
+	fn recover() {
+		assert_ok!(Recovery::create_recovery(Origin::signed(1), vec![2, 3], 2, 10));
+		assert_ok!(Recovery::initiate_recovery(Origin::signed(4), 1));
+		assert_ok!(Recovery::vouch_recovery(Origin::signed(2), 1, 4));
+		assert_ok!(Recovery::vouch_recovery(Origin::signed(3), 1, 4));
+		System::set_block_number(11);
+		assert_ok!(RecoveryProbation::claim_recovery(Origin::signed(4), 1));
+	}
+
+	fn transfer() -> Box<Call> {
+		Box::new(Call::Balances(pallet_balances::Call::transfer { dest: 4, value: 10 }))
+	}
+
+	fn remark() -> Box<Call> {
+		Box::new(Call::System(frame_system::Call::remark { remark: vec![] }))
+	}
+
+	#[test]
+	fn recovered_account_is_limited_during_probation() {
+		new_test_ext().execute_with(|| {
+			recover();
+			assert_eq!(Probation::<Test>::get(1), Some(31));
+			assert!(!RecoveryProbation::recovery_call_allowed(
+				&pallet_recovery::Call::as_recovered { account: 1, call: transfer() }
+			));
+
+			assert_ok!(RecoveryProbation::as_recovered(Origin::signed(4), 1, remark()));
+			assert_ok!(RecoveryProbation::as_recovered(Origin::signed(4), 1, transfer()));
+			System::assert_last_event(
+				recovery_probation::Event::RecoveredCallDispatched {
+					lost: 1,
+					rescuer: 4,
+					result: Err(frame_system::Error::<Test>::CallFiltered.into()),
+				}
+				.into(),
+			);
+			assert_eq!(Balances::free_balance(4), 90);
+			assert_noop!(
+				RecoveryProbation::as_recovered(Origin::signed(3), 1, remark()),
+				Error::<Test>::NotRecovered
+			);
+
+			System::set_block_number(31);
+			assert!(RecoveryProbation::recovery_call_allowed(
+				&pallet_recovery::Call::as_recovered { account: 1, call: transfer() }
+			));
+			assert_ok!(RecoveryProbation::as_recovered(Origin::signed(4), 1, transfer()));
+			System::assert_last_event(
+				recovery_probation::Event::RecoveredCallDispatched {
+					lost: 1,
+					rescuer: 4,
+					result: Ok(()),
+				}
+				.into(),
+			);
+			assert_eq!(Balances::free_balance(4), 100);
+		});
+	}
+
+	#[test]
+	fn owner_can_cancel_recovered() {
+		new_test_ext().execute_with(|| {
+			recover();
+			assert_noop!(
+				RecoveryProbation::cancel_recovered(Origin::signed(2), 4),
+				Error::<Test>::NotRecovered
+			);
+
+			assert_ok!(RecoveryProbation::cancel_recovered(Origin::signed(1), 4));
+			assert_eq!(pallet_recovery::Proxy::<Test>::get(4), None);
+			assert_eq!(Probation::<Test>::get(1), None);
+			System::assert_last_event(
+				recovery_probation::Event::RecoveryCancelled {
+					lost: 1,
+					rescuer: 4,
+					during_probation: true,
+				}
+				.into(),
+			);
+			assert_noop!(
+				RecoveryProbation::as_recovered(Origin::signed(4), 1, remark()),
+				Error::<Test>::NotRecovered
+			);
+		});
+	}
+
+	#[test]
+	fn direct_recovery_calls_are_restricted() {
+		new_test_ext().execute_with(|| {
+			assert!(!RecoveryProbation::recovery_call_allowed(
+				&pallet_recovery::Call::create_recovery {
+					friends: vec![2, 3],
+					threshold: 2,
+					delay_period: 9,
+				}
+			));
+			assert!(RecoveryProbation::recovery_call_allowed(
+				&pallet_recovery::Call::create_recovery {
+					friends: vec![2, 3],
+					threshold: 2,
+					delay_period: 10,
+				}
+			));
+			assert!(!RecoveryProbation::recovery_call_allowed(
+				&pallet_recovery::Call::claim_recovery { account: 1 }
+			));
+		});
+	}
+}
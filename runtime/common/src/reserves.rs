@@ -0,0 +1,160 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime API listing the reserved balance of an account by the name it is reserved under.
+
+use frame_support::traits::ReservableCurrency;
+use parity_scale_codec::{Codec, Decode, Encode};
+use scale_info::TypeInfo;
+use sp_runtime::{
+	traits::{Saturating, Zero},
+	RuntimeDebug,
+};
+use sp_std::prelude::*;
+
+/// The reserved balance of an account.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct AccountReserves<ReserveIdentifier, Balance> {
+	/// The balance reserved under a name, by name.
+	pub named: Vec<(ReserveIdentifier, Balance)>,
+	/// The balance reserved without a name, e.g. by pallets of Substrate which don't support
+	/// named reserves, like identity, multisig and proxy deposits.
+	pub unnamed: Balance,
+}
+
+sp_api::decl_runtime_apis! {
+	/// The API for querying the reserved balance of accounts.
+	pub trait ReservesApi<AccountId, ReserveIdentifier, Balance> where
+		AccountId: Codec,
+		ReserveIdentifier: Codec,
+		Balance: Codec,
+	{
+		/// Get the reserved balance of `who` by the name it is reserved under.
+		fn reserves(who: AccountId) -> AccountReserves<ReserveIdentifier, Balance>;
+	}
+}
+
+/// Collect the reserves of `who` from `pallet-balances`.
+pub fn reserves<T: pallet_balances::Config>(
+	who: &T::AccountId,
+) -> AccountReserves<T::ReserveIdentifier, T::Balance> {
+	let named: Vec<_> = <pallet_balances::Pallet<T>>::reserves(who)
+		.into_iter()
+		.map(|reserve| (reserve.id, reserve.amount))
+		.collect();
+	let total_named = named
+		.iter()
+		.fold(T::Balance::zero(), |total, (_, amount)| total.saturating_add(*amount));
+	let unnamed = <pallet_balances::Pallet<T> as ReservableCurrency<_>>::reserved_balance(who)
+		.saturating_sub(total_named);
+
+	AccountReserves { named, unnamed }
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use frame_support::{parameter_types, traits::NamedReservableCurrency};
+	use sp_core::H256;
+	use sp_runtime::{
+		testing::Header,
+		traits::{BlakeTwo256, IdentityLookup},
+	};
+
+	type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+	type Block = frame_system::mocking::MockBlock<Test>;
+
+	frame_support::construct_runtime!(
+		pub enum Test where
+			Block = Block,
+			NodeBlock = Block,
+			UncheckedExtrinsic = UncheckedExtrinsic,
+		{
+			System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+			Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+		}
+	);
+
+	parameter_types! {
+		pub const BlockHashCount: u32 = 250;
+	}
+
+	impl frame_system::Config for Test {
+		type BaseCallFilter = frame_support::traits::Everything;
+		type BlockWeights = ();
+		type BlockLength = ();
+		type DbWeight = ();
+		type Origin = Origin;
+		type Call = Call;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<u64>;
+		type Header = Header;
+		type Event = Event;
+		type BlockHashCount = BlockHashCount;
+		type Version = ();
+		type PalletInfo = PalletInfo;
+		type AccountData = pallet_balances::AccountData<u64>;
+		type OnNewAccount = ();
+		type OnKilledAccount = ();
+		type SystemWeightInfo = ();
+		type SS58Prefix = ();
+		type OnSetCode = ();
+		type MaxConsumers = frame_support::traits::ConstU32<16>;
+	}
+
+	parameter_types! {
+		pub const ExistentialDeposit: u64 = 1;
+		pub const MaxReserves: u32 = 4;
+	}
+
+	impl pallet_balances::Config for Test {
+		type Balance = u64;
+		type Event = Event;
+		type DustRemoval = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type AccountStore = System;
+		type MaxLocks = ();
+		type MaxReserves = MaxReserves;
+		type ReserveIdentifier = [u8; 8];
+		type WeightInfo = ();
+	}
+
+	#[test]
+	fn reserves_are_listed_by_name() {
+		let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		pallet_balances::GenesisConfig::<Test> { balances: vec![(1, 100)] }
+			.assimilate_storage(&mut t)
+			.unwrap();
+		sp_io::TestExternalities::new(t).execute_with(|| {
+			assert_eq!(reserves::<Test>(&1), AccountReserves { named: vec![], unnamed: 0 });
+
+			Balances::reserve_named(b"pararegi", &1, 10).unwrap();
+			Balances::reserve_named(b"hrmpdepo", &1, 20).unwrap();
+			Balances::reserve(&1, 5).unwrap();
+
+			let reserves = reserves::<Test>(&1);
+			assert_eq!(reserves.unnamed, 5);
+			assert_eq!(reserves.named.len(), 2);
+			assert!(reserves.named.contains(&(*b"pararegi", 10)));
+			assert!(reserves.named.contains(&(*b"hrmpdepo", 20)));
+		});
+	}
+}
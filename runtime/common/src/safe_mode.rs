@@ -0,0 +1,280 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Pallet putting the chain into safe mode.
+//!
+//! As a response to critical bugs, `ForceOrigin` can restrict the chain to the calls allowed by
+//! `SafeCalls` for a bounded number of blocks, typically the ones needed to keep consensus
+//! running and for governance to deploy a fix. Runtimes are expected to filter out the calls
+//! rejected by [`Pallet::allows`] in their `BaseCallFilter`. Safe mode ends by itself after at
+//! most `MaxDuration` blocks, unless `ForceOrigin` ends it earlier or enters it again.
+
+use frame_support::{pallet_prelude::*, traits::Contains};
+use frame_system::pallet_prelude::*;
+pub use pallet::*;
+use sp_runtime::traits::{Saturating, Zero};
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The origin which may enter and exit safe mode.
+		type ForceOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The maximum number of blocks safe mode can be entered for at once.
+		#[pallet::constant]
+		type MaxDuration: Get<Self::BlockNumber>;
+
+		/// The calls which may be dispatched while safe mode is active.
+		type SafeCalls: Contains<<Self as frame_system::Config>::Call>;
+	}
+
+	/// The block at which the active safe mode ends, if any.
+	#[pallet::storage]
+	pub type ActiveUntil<T: Config> = StorageValue<_, T::BlockNumber, OptionQuery>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// Safe mode was entered and lasts until block `until`.
+		Entered { until: T::BlockNumber },
+		/// Safe mode was ended by `ForceOrigin`.
+		Exited,
+		/// Safe mode ended after its duration passed.
+		Expired,
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// Safe mode can not be entered for zero blocks.
+		ZeroDuration,
+		/// The duration is longer than `MaxDuration`.
+		DurationTooLong,
+		/// Safe mode is not active.
+		NotActive,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(now: BlockNumberFor<T>) -> Weight {
+			match ActiveUntil::<T>::get() {
+				Some(until) if until <= now => {
+					ActiveUntil::<T>::kill();
+					Self::deposit_event(Event::<T>::Expired);
+					T::DbWeight::get().reads_writes(1, 1)
+				},
+				_ => T::DbWeight::get().reads(1),
+			}
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Enter safe mode for `duration` blocks.
+		///
+		/// If safe mode is already active, it ends `duration` blocks from now instead, which can
+		/// extend or shorten it. The origin must be `ForceOrigin`.
+		#[pallet::weight(T::DbWeight::get().writes(1))]
+		pub fn enter(origin: OriginFor<T>, duration: T::BlockNumber) -> DispatchResult {
+			T::ForceOrigin::ensure_origin(origin)?;
+			ensure!(!duration.is_zero(), Error::<T>::ZeroDuration);
+			ensure!(duration <= T::MaxDuration::get(), Error::<T>::DurationTooLong);
+
+			let until = frame_system::Pallet::<T>::block_number().saturating_add(duration);
+			ActiveUntil::<T>::put(until);
+			Self::deposit_event(Event::<T>::Entered { until });
+			Ok(())
+		}
+
+		/// End safe mode early.
+		///
+		/// The origin must be `ForceOrigin`.
+		#[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+		pub fn exit(origin: OriginFor<T>) -> DispatchResult {
+			T::ForceOrigin::ensure_origin(origin)?;
+			ensure!(Self::is_active(), Error::<T>::NotActive);
+
+			ActiveUntil::<T>::kill();
+			Self::deposit_event(Event::<T>::Exited);
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Whether safe mode is active.
+	pub fn is_active() -> bool {
+		ActiveUntil::<T>::exists()
+	}
+
+	/// Whether `call` may be dispatched, which is only restricted while safe mode is active.
+	pub fn allows(call: &<T as frame_system::Config>::Call) -> bool {
+		!Self::is_active() || T::SafeCalls::contains(call)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use crate::safe_mode;
+	use frame_support::{assert_noop, assert_ok, ord_parameter_types, parameter_types};
+	use frame_system::EnsureSignedBy;
+	use sp_core::H256;
+	use sp_runtime::{
+		testing::Header,
+		traits::{BadOrigin, BlakeTwo256, IdentityLookup},
+	};
+
+	type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+	type Block = frame_system::mocking::MockBlock<Test>;
+
+	frame_support::construct_runtime!(
+		pub enum Test where
+			Block = Block,
+			NodeBlock = Block,
+			UncheckedExtrinsic = UncheckedExtrinsic,
+		{
+			System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+			SafeMode: safe_mode::{Pallet, Call, Storage, Event<T>},
+		}
+	);
+
+	parameter_types! {
+		pub const BlockHashCount: u32 = 250;
+	}
+
+	pub struct BaseFilter;
+	impl Contains<Call> for BaseFilter {
+		fn contains(call: &Call) -> bool {
+			SafeMode::allows(call)
+		}
+	}
+
+	impl frame_system::Config for Test {
+		type BaseCallFilter = BaseFilter;
+		type BlockWeights = ();
+		type BlockLength = ();
+		type DbWeight = ();
+		type Origin = Origin;
+		type Call = Call;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<u64>;
+		type Header = Header;
+		type Event = Event;
+		type BlockHashCount = BlockHashCount;
+		type Version = ();
+		type PalletInfo = PalletInfo;
+		type AccountData = ();
+		type OnNewAccount = ();
+		type OnKilledAccount = ();
+		type SystemWeightInfo = ();
+		type SS58Prefix = ();
+		type OnSetCode = ();
+		type MaxConsumers = frame_support::traits::ConstU32<16>;
+	}
+
+	pub struct SafeCalls;
+	impl Contains<Call> for SafeCalls {
+		fn contains(call: &Call) -> bool {
+			matches!(call, Call::SafeMode(_))
+		}
+	}
+
+	ord_parameter_types! {
+		pub const Committee: u64 = 1;
+	}
+
+	parameter_types! {
+		pub const MaxDuration: u64 = 10;
+	}
+
+	impl Config for Test {
+		type Event = Event;
+		type ForceOrigin = EnsureSignedBy<Committee, u64>;
+		type MaxDuration = MaxDuration;
+		type SafeCalls = SafeCalls;
+	}
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		let mut ext = sp_io::TestExternalities::new(t);
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+
+	fn remark() -> Call {
+		Call::System(frame_system::Call::remark { remark: Vec::new() })
+	}
+
+	#[test]
+	fn enter_is_bounded() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(SafeMode::enter(Origin::signed(2), 5), BadOrigin);
+			assert_noop!(SafeMode::enter(Origin::signed(1), 0), Error::<Test>::ZeroDuration);
+			assert_noop!(SafeMode::enter(Origin::signed(1), 11), Error::<Test>::DurationTooLong);
+
+			assert_ok!(SafeMode::enter(Origin::signed(1), 10));
+			System::assert_last_event(Event::SafeMode(safe_mode::Event::Entered { until: 11 }));
+		});
+	}
+
+	#[test]
+	fn only_safe_calls_are_allowed_while_active() {
+		new_test_ext().execute_with(|| {
+			assert!(SafeMode::allows(&remark()));
+
+			assert_ok!(SafeMode::enter(Origin::signed(1), 5));
+			assert!(!SafeMode::allows(&remark()));
+			assert!(SafeMode::allows(&Call::SafeMode(safe_mode::Call::exit {})));
+
+			assert_ok!(SafeMode::exit(Origin::signed(1)));
+			assert!(SafeMode::allows(&remark()));
+			System::assert_last_event(Event::SafeMode(safe_mode::Event::Exited));
+			assert_noop!(SafeMode::exit(Origin::signed(1)), Error::<Test>::NotActive);
+		});
+	}
+
+	#[test]
+	fn safe_mode_expires() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(SafeMode::enter(Origin::signed(1), 5));
+			// Entering again moves the end relative to the current block.
+			System::set_block_number(3);
+			assert_ok!(SafeMode::enter(Origin::signed(1), 5));
+
+			SafeMode::on_initialize(7);
+			assert!(SafeMode::is_active());
+
+			SafeMode::on_initialize(8);
+			assert!(!SafeMode::is_active());
+			System::assert_last_event(Event::SafeMode(safe_mode::Event::Expired));
+		});
+	}
+}
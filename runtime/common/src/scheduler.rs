@@ -0,0 +1,290 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Helpers for scheduling calls through `pallet-scheduler`.
+
+use frame_support::traits::{
+	schedule::{DispatchTime, MaybeHashed, Named, Period, Priority},
+	Get,
+};
+use pallet_scheduler::{Agenda, TaskAddress};
+use parity_scale_codec::Encode;
+use sp_runtime::{
+	traits::{One, Saturating},
+	DispatchError,
+};
+use sp_std::{marker::PhantomData, prelude::*};
+
+type CallOf<T> = <T as pallet_scheduler::Config>::Call;
+
+/// The number of blocks a task is postponed by at most before scheduling is attempted regardless
+/// of the agenda being full.
+pub const MAX_POSTPONEMENT: u32 = 256;
+
+/// Schedules named tasks with `pallet-scheduler`, but never puts more than
+/// `MaxScheduledPerBlock` tasks into the agenda of a single block.
+///
+/// A task due in a block with a full agenda is postponed to the first following block with room,
+/// so that governance enactments are delayed rather than starving other tasks of that block.
+pub struct PostponeOnFullAgenda<T>(PhantomData<T>);
+
+impl<T: pallet_scheduler::Config> PostponeOnFullAgenda<T> {
+	/// The first block at or after `when` whose agenda has room for another task.
+	fn first_block_with_room(when: DispatchTime<T::BlockNumber>) -> DispatchTime<T::BlockNumber> {
+		let mut when = match when {
+			DispatchTime::At(when) => when,
+			// Mirrors how the scheduler resolves relative dispatch times.
+			DispatchTime::After(after) => <frame_system::Pallet<T>>::block_number()
+				.saturating_add(after)
+				.saturating_add(One::one()),
+		};
+
+		let max_per_block = T::MaxScheduledPerBlock::get() as usize;
+		for _ in 0..MAX_POSTPONEMENT {
+			if Agenda::<T>::decode_len(when).unwrap_or(0) < max_per_block {
+				break
+			}
+			when = when.saturating_add(One::one());
+		}
+
+		DispatchTime::At(when)
+	}
+}
+
+impl<T: pallet_scheduler::Config> Named<T::BlockNumber, CallOf<T>, T::PalletsOrigin>
+	for PostponeOnFullAgenda<T>
+{
+	type Address = TaskAddress<T::BlockNumber>;
+
+	fn schedule_named(
+		id: Vec<u8>,
+		when: DispatchTime<T::BlockNumber>,
+		maybe_periodic: Option<Period<T::BlockNumber>>,
+		priority: Priority,
+		origin: T::PalletsOrigin,
+		call: CallOf<T>,
+	) -> Result<Self::Address, ()> {
+		<pallet_scheduler::Pallet<T> as Named<_, _, _>>::schedule_named(
+			id,
+			Self::first_block_with_room(when),
+			maybe_periodic,
+			priority,
+			origin,
+			call,
+		)
+	}
+
+	fn cancel_named(id: Vec<u8>) -> Result<(), ()> {
+		<pallet_scheduler::Pallet<T> as Named<_, CallOf<T>, _>>::cancel_named(id)
+	}
+
+	fn reschedule_named(
+		id: Vec<u8>,
+		when: DispatchTime<T::BlockNumber>,
+	) -> Result<Self::Address, DispatchError> {
+		<pallet_scheduler::Pallet<T> as Named<_, CallOf<T>, _>>::reschedule_named(
+			id,
+			Self::first_block_with_room(when),
+		)
+	}
+
+	fn next_dispatch_time(id: Vec<u8>) -> Result<T::BlockNumber, ()> {
+		<pallet_scheduler::Pallet<T> as Named<_, CallOf<T>, _>>::next_dispatch_time(id)
+	}
+}
+
+/// Whether `call` schedules a call which is larger than `max_size` bytes when encoded, without
+/// going through the preimage pallet.
+///
+/// Runtimes use this to require large calls to be noted as a preimage and scheduled by hash,
+/// which keeps the agenda small and its weight predictable.
+pub fn exceeds_inline_call_size<T: pallet_scheduler::Config>(
+	call: &pallet_scheduler::Call<T>,
+	max_size: u32,
+) -> bool {
+	use pallet_scheduler::Call::*;
+
+	match call {
+		schedule { call, .. } |
+		schedule_named { call, .. } |
+		schedule_after { call, .. } |
+		schedule_named_after { call, .. } => match call.as_ref() {
+			MaybeHashed::Value(call) => call.encoded_size() > max_size as usize,
+			MaybeHashed::Hash(_) => false,
+		},
+		_ => false,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use frame_support::{
+		parameter_types,
+		traits::{EqualPrivilegeOnly, Everything},
+		weights::Weight,
+	};
+	use frame_system::{EnsureRoot, RawOrigin};
+	use sp_core::H256;
+	use sp_runtime::{
+		testing::Header,
+		traits::{BlakeTwo256, IdentityLookup},
+	};
+
+	type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+	type Block = frame_system::mocking::MockBlock<Test>;
+
+	frame_support::construct_runtime!(
+		pub enum Test where
+			Block = Block,
+			NodeBlock = Block,
+			UncheckedExtrinsic = UncheckedExtrinsic,
+		{
+			System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+			Scheduler: pallet_scheduler::{Pallet, Call, Storage, Event<T>},
+		}
+	);
+
+	parameter_types! {
+		pub const BlockHashCount: u32 = 250;
+	}
+
+	impl frame_system::Config for Test {
+		type BaseCallFilter = Everything;
+		type BlockWeights = ();
+		type BlockLength = ();
+		type DbWeight = ();
+		type Origin = Origin;
+		type Call = Call;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<u64>;
+		type Header = Header;
+		type Event = Event;
+		type BlockHashCount = BlockHashCount;
+		type Version = ();
+		type PalletInfo = PalletInfo;
+		type AccountData = ();
+		type OnNewAccount = ();
+		type OnKilledAccount = ();
+		type SystemWeightInfo = ();
+		type SS58Prefix = ();
+		type OnSetCode = ();
+		type MaxConsumers = frame_support::traits::ConstU32<16>;
+	}
+
+	parameter_types! {
+		pub const MaximumSchedulerWeight: Weight = 1_000_000;
+		pub const MaxScheduledPerBlock: u32 = 2;
+		pub const NoPreimagePostponement: Option<u64> = Some(10);
+	}
+
+	impl pallet_scheduler::Config for Test {
+		type Event = Event;
+		type Origin = Origin;
+		type PalletsOrigin = OriginCaller;
+		type Call = Call;
+		type MaximumWeight = MaximumSchedulerWeight;
+		type ScheduleOrigin = EnsureRoot<u64>;
+		type MaxScheduledPerBlock = MaxScheduledPerBlock;
+		type WeightInfo = ();
+		type OriginPrivilegeCmp = EqualPrivilegeOnly;
+		type PreimageProvider = ();
+		type NoPreimagePostponement = NoPreimagePostponement;
+	}
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		let mut ext = sp_io::TestExternalities::new(t);
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+
+	fn remark(len: usize) -> Call {
+		Call::System(frame_system::Call::remark { remark: vec![0; len] })
+	}
+
+	fn schedule(id: u8, when: DispatchTime<u64>) -> TaskAddress<u64> {
+		PostponeOnFullAgenda::<Test>::schedule_named(
+			vec![id],
+			when,
+			None,
+			0,
+			RawOrigin::Root.into(),
+			remark(1),
+		)
+		.unwrap()
+	}
+
+	#[test]
+	fn full_agenda_postpones_tasks() {
+		new_test_ext().execute_with(|| {
+			assert_eq!(schedule(0, DispatchTime::At(5)), (5, 0));
+			assert_eq!(schedule(1, DispatchTime::At(5)), (5, 1));
+			assert_eq!(schedule(2, DispatchTime::At(5)), (6, 0));
+			// Relative dispatch times are resolved before looking for room.
+			assert_eq!(schedule(3, DispatchTime::After(4)), (6, 1));
+			assert_eq!(schedule(4, DispatchTime::At(6)), (7, 0));
+
+			assert_eq!(
+				<PostponeOnFullAgenda<Test> as Named<_, CallOf<Test>, _>>::next_dispatch_time(
+					vec![4]
+				),
+				Ok(7),
+			);
+		});
+	}
+
+	#[test]
+	fn rescheduling_respects_full_agenda() {
+		new_test_ext().execute_with(|| {
+			schedule(0, DispatchTime::At(5));
+			schedule(1, DispatchTime::At(5));
+			schedule(2, DispatchTime::At(8));
+
+			assert_eq!(
+				<PostponeOnFullAgenda<Test> as Named<_, CallOf<Test>, _>>::reschedule_named(
+					vec![2],
+					DispatchTime::At(5),
+				),
+				Ok((6, 0)),
+			);
+		});
+	}
+
+	#[test]
+	fn inline_call_size_is_bounded() {
+		let schedule_call =
+			|call: MaybeHashed<Call, H256>| pallet_scheduler::Call::<Test>::schedule {
+				when: 5,
+				maybe_periodic: None,
+				priority: 0,
+				call: Box::new(call),
+			};
+
+		assert!(!exceeds_inline_call_size(&schedule_call(MaybeHashed::Value(remark(8))), 128));
+		assert!(exceeds_inline_call_size(&schedule_call(MaybeHashed::Value(remark(256))), 128));
+		assert!(!exceeds_inline_call_size(&schedule_call(MaybeHashed::Hash(H256::zero())), 128));
+		assert!(!exceeds_inline_call_size(
+			&pallet_scheduler::Call::<Test>::cancel { when: 5, index: 0 },
+			128
+		));
+	}
+}
@@ -0,0 +1,111 @@
+// Copyright 2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Dry runs of slashing.
+//!
+//! [`simulate_offences`] reports synthetic offences of the given slash fractions, committed in
+//! the current session by validators of the active era, to staking inside a storage transaction
+//! which is rolled back, and returns the slashes staking queued for them. The slashes account for
+//! the exposure of every nominator, the slashes already applied in the era and the invulnerables,
+//! exactly as for a real offence, so slashing parameters can be checked against the live state
+//! through [`SlashSimulationApi`] ahead of an incident.
+//!
+//! Slashes are read from the queue of unapplied slashes, so staking must defer slashes by at least
+//! one era.
+
+use frame_support::{
+	storage::{with_transaction, TransactionOutcome},
+	weights::Weight,
+};
+use pallet_staking::{BalanceOf, EraIndex, Exposure, ExposureOf, UnappliedSlashes};
+use parity_scale_codec::{Codec, Decode, Encode};
+use scale_info::TypeInfo;
+use sp_runtime::{traits::Convert, Perbill, RuntimeDebug};
+use sp_staking::offence::{DisableStrategy, OffenceDetails, OnOffenceHandler};
+use sp_std::{collections::btree_map::BTreeMap, prelude::*};
+
+sp_api::decl_runtime_apis! {
+	/// The API for dry runs of slashing.
+	pub trait SlashSimulationApi<AccountId, Balance> where AccountId: Codec, Balance: Codec {
+		/// The slashes staking would queue if each of the validators in `offences` committed an
+		/// offence with the given slash fraction in the current session. Nothing is applied.
+		fn simulate_offences(
+			offences: Vec<(AccountId, Perbill)>,
+		) -> Vec<SimulatedSlash<AccountId, Balance>>;
+	}
+}
+
+/// A slash computed by staking for a synthetic offence.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct SimulatedSlash<AccountId, Balance> {
+	/// The era the slash would be applied at.
+	pub apply_at: EraIndex,
+	/// The stash of the offending validator.
+	pub validator: AccountId,
+	/// The amount slashed from the own stake of the validator.
+	pub own: Balance,
+	/// The amount slashed from each of the nominators of the validator.
+	pub others: Vec<(AccountId, Balance)>,
+}
+
+/// The slashes `Staking` would queue for the `offences` of validators of the active era, each
+/// with its slash fraction, committed in the current session.
+///
+/// Offenders outside of the active era and offences staking does not slash for, such as those of
+/// invulnerables or those no more severe than a slash already applied in the era, have no slash.
+/// The state is left untouched.
+pub fn simulate_offences<T, Staking>(
+	offences: Vec<(T::AccountId, Perbill)>,
+) -> Vec<SimulatedSlash<T::AccountId, BalanceOf<T>>>
+where
+	T: pallet_staking::Config + pallet_session::Config,
+	Staking: OnOffenceHandler<
+		T::AccountId,
+		(T::AccountId, Exposure<T::AccountId, BalanceOf<T>>),
+		Weight,
+	>,
+{
+	let (offenders, slash_fractions): (Vec<_>, Vec<_>) = offences
+		.into_iter()
+		.filter_map(|(stash, slash_fraction)| {
+			let exposure = ExposureOf::<T>::convert(stash.clone())?;
+			let details = OffenceDetails { offender: (stash, exposure), reporters: Vec::new() };
+			Some((details, slash_fraction))
+		})
+		.unzip();
+	let session = pallet_session::Pallet::<T>::current_index();
+
+	with_transaction(|| {
+		let queued: BTreeMap<_, _> = UnappliedSlashes::<T>::iter()
+			.map(|(era, slashes)| (era, slashes.len()))
+			.collect();
+
+		Staking::on_offence(&offenders, &slash_fractions, session, DisableStrategy::Never);
+
+		let slashes = UnappliedSlashes::<T>::iter()
+			.flat_map(|(era, slashes)| {
+				let skip = queued.get(&era).copied().unwrap_or_default();
+				slashes.into_iter().skip(skip).map(move |slash| SimulatedSlash {
+					apply_at: era,
+					validator: slash.validator,
+					own: slash.own,
+					others: slash.others,
+				})
+			})
+			.collect();
+		TransactionOutcome::Rollback(slashes)
+	})
+}
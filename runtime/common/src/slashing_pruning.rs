@@ -0,0 +1,417 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Pruning of the slashing state of `pallet-staking` beyond the bonding duration.
+//!
+//! Staking only prunes the slashing spans of a stash when it is slashed again, so the spans of a
+//! stash slashed once are kept for as long as it stays bonded, and slashes deferred to an era
+//! which is never started are never removed. This migration removes the spans which ended
+//! `BondingDuration` eras or more before the active era along with their slash records, and the
+//! unapplied slashes of those eras.
+//!
+//! `pallet-staking` tracks its releases in a storage item of its own and leaves its pallet
+//! `StorageVersion` at 0. The migration bumps it to 1 and is skipped once it is set. It can be
+//! removed after the next release.
+
+use frame_support::{
+	storage::{
+		migration::{get_storage_value, storage_key_iter},
+		storage_prefix, unhashed,
+	},
+	traits::{Get, OnRuntimeUpgrade, StorageVersion, STORAGE_VERSION_STORAGE_KEY_POSTFIX},
+	weights::Weight,
+	StorageHasher, Twox64Concat,
+};
+use parity_scale_codec::{Decode, Encode};
+use sp_staking::EraIndex;
+use sp_std::{marker::PhantomData, ops::Range, prelude::*};
+
+/// The name `pallet-staking` is declared with in `construct_runtime`.
+pub const STAKING_PALLET: &[u8] = b"Staking";
+
+/// The pallet `StorageVersion` of `pallet-staking` once its slashing state was pruned.
+pub const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
+/// The index of a slashing span of a stash.
+type SpanIndex = u32;
+
+/// The layout of `pallet_staking::ActiveEraInfo`.
+#[derive(Encode, Decode)]
+struct ActiveEraInfo {
+	index: EraIndex,
+	start: Option<u64>,
+}
+
+/// The layout of `pallet_staking::slashing::SlashingSpans`, whose fields are private.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+struct SlashingSpans {
+	/// The index of the ongoing span.
+	span_index: SpanIndex,
+	/// The era the ongoing span started at.
+	last_start: EraIndex,
+	/// The last era a non-zero slash was applied at.
+	last_nonzero_slash: EraIndex,
+	/// The lengths of the prior spans, most recent first.
+	prior: Vec<EraIndex>,
+}
+
+impl SlashingSpans {
+	/// Removes the prior spans which ended at or before `window_start`, like staking does when
+	/// the stash is slashed, and returns the indices of the removed spans.
+	fn prune(&mut self, window_start: EraIndex) -> Range<SpanIndex> {
+		let mut end = self.last_start;
+		let kept = self
+			.prior
+			.iter()
+			.take_while(|length| {
+				let in_window = end > window_start;
+				end = end.saturating_sub(**length);
+				in_window
+			})
+			.count();
+
+		let earliest = self.span_index.saturating_sub(self.prior.len() as SpanIndex);
+		self.prior.truncate(kept);
+		self.last_start = self.last_start.max(window_start);
+		earliest..self.span_index.saturating_sub(kept as SpanIndex)
+	}
+}
+
+/// The layout of `pallet_staking::UnappliedSlash`.
+#[derive(Encode, Decode)]
+struct UnappliedSlash<AccountId, Balance> {
+	validator: AccountId,
+	own: Balance,
+	others: Vec<(AccountId, Balance)>,
+	reporters: Vec<AccountId>,
+	payout: Balance,
+}
+
+/// The key `pallet-staking` stores its pallet `StorageVersion` at.
+fn storage_version_key() -> [u8; 32] {
+	storage_prefix(STAKING_PALLET, STORAGE_VERSION_STORAGE_KEY_POSTFIX)
+}
+
+fn staking_key(item: &[u8], key: impl Encode) -> Vec<u8> {
+	[&storage_prefix(STAKING_PALLET, item)[..], &Twox64Concat::hash(&key.encode())].concat()
+}
+
+/// Removes the slashing spans, span slash records and unapplied slashes of `pallet-staking`
+/// beyond the bonding duration, unless the pallet `StorageVersion` of staking is
+/// [`STORAGE_VERSION`] already. `Balance` and `BondingDuration` must be the balance type and
+/// bonding duration staking is configured with.
+pub struct PruneSlashingState<T, Balance, BondingDuration>(
+	PhantomData<(T, Balance, BondingDuration)>,
+);
+
+impl<T, Balance, BondingDuration> PruneSlashingState<T, Balance, BondingDuration>
+where
+	T: frame_system::Config,
+	Balance: Decode,
+	BondingDuration: Get<EraIndex>,
+{
+	/// The first era of the bonding duration ending with the active era, if there is one.
+	fn window_start() -> Option<EraIndex> {
+		let active_era = get_storage_value::<ActiveEraInfo>(STAKING_PALLET, b"ActiveEra", b"")?;
+		Some(active_era.index.saturating_sub(BondingDuration::get()))
+	}
+
+	/// Checks that no stash has more slashing spans than the eras it is bonded for, and that
+	/// there are no unapplied slashes before the bonding duration.
+	pub fn check_bounded() -> Result<(), &'static str> {
+		let window_start = match Self::window_start() {
+			Some(window_start) => window_start,
+			None => return Ok(()),
+		};
+
+		let spans = storage_key_iter::<T::AccountId, SlashingSpans, Twox64Concat>(
+			STAKING_PALLET,
+			b"SlashingSpans",
+		);
+		for (_, spans) in spans {
+			if spans.prior.len() as EraIndex > BondingDuration::get() {
+				return Err("a stash has more slashing spans than eras bonded")
+			}
+		}
+
+		let mut eras = storage_key_iter::<
+			EraIndex,
+			Vec<UnappliedSlash<T::AccountId, Balance>>,
+			Twox64Concat,
+		>(STAKING_PALLET, b"UnappliedSlashes")
+		.map(|(era, _)| era);
+		if eras.any(|era| era < window_start) {
+			return Err("slashes before the bonding duration are unapplied")
+		}
+		Ok(())
+	}
+}
+
+impl<T, Balance, BondingDuration> OnRuntimeUpgrade
+	for PruneSlashingState<T, Balance, BondingDuration>
+where
+	T: frame_system::Config,
+	Balance: Decode,
+	BondingDuration: Get<EraIndex>,
+{
+	fn on_runtime_upgrade() -> Weight {
+		let key = storage_version_key();
+		if unhashed::get_or_default::<StorageVersion>(&key) >= STORAGE_VERSION {
+			log::info!(target: "runtime::staking", "Slashing state was pruned already");
+			return T::DbWeight::get().reads(1)
+		}
+		unhashed::put(&key, &STORAGE_VERSION);
+
+		let window_start = match Self::window_start() {
+			Some(window_start) => window_start,
+			None => return T::DbWeight::get().reads_writes(2, 1),
+		};
+
+		let spans: Vec<_> = storage_key_iter::<T::AccountId, SlashingSpans, Twox64Concat>(
+			STAKING_PALLET,
+			b"SlashingSpans",
+		)
+		.collect();
+		let (stashes, mut pruned_stashes, mut pruned_spans) = (spans.len() as u64, 0u64, 0u64);
+		for (stash, mut spans) in spans {
+			let pruned = spans.prune(window_start);
+			if pruned.is_empty() {
+				continue
+			}
+
+			for index in pruned.clone() {
+				unhashed::kill(&staking_key(b"SpanSlash", (&stash, index)));
+			}
+			unhashed::put(&staking_key(b"SlashingSpans", &stash), &spans);
+			pruned_stashes += 1;
+			pruned_spans += pruned.len() as u64;
+		}
+
+		let eras: Vec<_> = storage_key_iter::<
+			EraIndex,
+			Vec<UnappliedSlash<T::AccountId, Balance>>,
+			Twox64Concat,
+		>(STAKING_PALLET, b"UnappliedSlashes")
+		.map(|(era, _)| era)
+		.collect();
+		let unapplied = eras.len() as u64;
+		let mut pruned_eras = 0u64;
+		for era in eras.into_iter().filter(|era| *era < window_start) {
+			unhashed::kill(&staking_key(b"UnappliedSlashes", era));
+			pruned_eras += 1;
+		}
+
+		log::info!(
+			target: "runtime::staking",
+			"Pruned {} slashing spans of {} stashes and the unapplied slashes of {} eras",
+			pruned_spans,
+			pruned_stashes,
+			pruned_eras,
+		);
+
+		T::DbWeight::get()
+			.reads_writes(stashes + unapplied + 2, pruned_stashes + pruned_spans + pruned_eras + 1)
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade() -> Result<(), &'static str> {
+		Self::check_bounded()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use frame_support::{parameter_types, storage::migration::put_storage_value};
+	use sp_core::H256;
+	use sp_runtime::{
+		testing::Header,
+		traits::{BlakeTwo256, IdentityLookup},
+	};
+
+	type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+	type Block = frame_system::mocking::MockBlock<Test>;
+
+	frame_support::construct_runtime!(
+		pub enum Test where
+			Block = Block,
+			NodeBlock = Block,
+			UncheckedExtrinsic = UncheckedExtrinsic,
+		{
+			System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		}
+	);
+
+	parameter_types! {
+		pub const BlockHashCount: u32 = 250;
+		pub const BondingDuration: EraIndex = 4;
+	}
+
+	impl frame_system::Config for Test {
+		type BaseCallFilter = frame_support::traits::Everything;
+		type BlockWeights = ();
+		type BlockLength = ();
+		type DbWeight = ();
+		type Origin = Origin;
+		type Call = Call;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<u64>;
+		type Header = Header;
+		type Event = Event;
+		type BlockHashCount = BlockHashCount;
+		type Version = ();
+		type PalletInfo = PalletInfo;
+		type AccountData = ();
+		type OnNewAccount = ();
+		type OnKilledAccount = ();
+		type SystemWeightInfo = ();
+		type SS58Prefix = ();
+		type OnSetCode = ();
+		type MaxConsumers = frame_support::traits::ConstU32<16>;
+	}
+
+	type Prune = PruneSlashingState<Test, u64, BondingDuration>;
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		frame_system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
+	}
+
+	fn set_active_era(index: EraIndex) {
+		let active_era = ActiveEraInfo { index, start: None };
+		put_storage_value(STAKING_PALLET, b"ActiveEra", b"", active_era);
+	}
+
+	fn put_spans(stash: u64, spans: &SlashingSpans) {
+		unhashed::put(&staking_key(b"SlashingSpans", stash), spans);
+		// A record of every span, which is `SpanRecord { slashed, paid_out }` in staking.
+		let earliest = spans.span_index - spans.prior.len() as SpanIndex;
+		for index in earliest..=spans.span_index {
+			unhashed::put(&staking_key(b"SpanSlash", (stash, index)), &(1u64, 0u64));
+		}
+	}
+
+	fn spans(stash: u64) -> Option<SlashingSpans> {
+		unhashed::get(&staking_key(b"SlashingSpans", stash))
+	}
+
+	fn has_span_record(stash: u64, index: SpanIndex) -> bool {
+		unhashed::exists(&staking_key(b"SpanSlash", (stash, index)))
+	}
+
+	fn put_unapplied_slash(era: EraIndex) {
+		let slash = UnappliedSlash {
+			validator: 1u64,
+			own: 1u64,
+			others: vec![],
+			reporters: vec![],
+			payout: 0,
+		};
+		unhashed::put(&staking_key(b"UnappliedSlashes", era), &vec![slash]);
+	}
+
+	#[test]
+	fn spans_before_the_bonding_duration_are_pruned() {
+		new_test_ext().execute_with(|| {
+			set_active_era(10);
+			// The ongoing span 7 started at era 10, spans 6 to 3 at eras 8, 5, 3 and 1. Spans 4 and
+			// 3 ended at eras 5 and 3, before the bonding duration starting at era 6.
+			put_spans(
+				1,
+				&SlashingSpans {
+					span_index: 7,
+					last_start: 10,
+					last_nonzero_slash: 10,
+					prior: vec![2, 3, 2, 2],
+				},
+			);
+			// Only slashed in the bonding duration.
+			let recent = SlashingSpans {
+				span_index: 1,
+				last_start: 9,
+				last_nonzero_slash: 9,
+				prior: vec![2],
+			};
+			put_spans(2, &recent);
+			assert_eq!(Prune::check_bounded(), Ok(()));
+
+			Prune::on_runtime_upgrade();
+
+			assert_eq!(
+				spans(1),
+				Some(SlashingSpans {
+					span_index: 7,
+					last_start: 10,
+					last_nonzero_slash: 10,
+					prior: vec![2, 3],
+				}),
+			);
+			assert!(!has_span_record(1, 3) && !has_span_record(1, 4));
+			assert!((5..=7).all(|index| has_span_record(1, index)));
+			assert_eq!(spans(2), Some(recent));
+			assert!(has_span_record(2, 0) && has_span_record(2, 1));
+		});
+	}
+
+	#[test]
+	fn unapplied_slashes_before_the_bonding_duration_are_pruned() {
+		new_test_ext().execute_with(|| {
+			set_active_era(10);
+			put_unapplied_slash(5);
+			put_unapplied_slash(6);
+			put_unapplied_slash(12);
+			assert!(Prune::check_bounded().is_err());
+
+			Prune::on_runtime_upgrade();
+
+			assert!(!unhashed::exists(&staking_key(b"UnappliedSlashes", 5u32)));
+			assert!(unhashed::exists(&staking_key(b"UnappliedSlashes", 6u32)));
+			assert!(unhashed::exists(&staking_key(b"UnappliedSlashes", 12u32)));
+			assert_eq!(Prune::check_bounded(), Ok(()));
+		});
+	}
+
+	#[test]
+	fn nothing_is_pruned_without_an_active_era() {
+		new_test_ext().execute_with(|| {
+			put_unapplied_slash(0);
+
+			Prune::on_runtime_upgrade();
+
+			assert!(unhashed::exists(&staking_key(b"UnappliedSlashes", 0u32)));
+		});
+	}
+
+	#[test]
+	fn nothing_is_pruned_once_migrated() {
+		new_test_ext().execute_with(|| {
+			set_active_era(10);
+			put_unapplied_slash(5);
+
+			Prune::on_runtime_upgrade();
+			assert_eq!(unhashed::get(&storage_version_key()), Some(STORAGE_VERSION));
+
+			put_unapplied_slash(5);
+			Prune::on_runtime_upgrade();
+
+			assert!(unhashed::exists(&staking_key(b"UnappliedSlashes", 5u32)));
+		});
+	}
+}
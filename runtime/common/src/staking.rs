@@ -0,0 +1,181 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime API exposing the validators and unclaimed rewards of `pallet-staking`, so that wallets
+//! can show staking dashboards without iterating storage keys over RPC.
+
+use pallet_staking::{BalanceOf, EraIndex, Exposure, ValidatorPrefs};
+use parity_scale_codec::{Codec, Decode, Encode};
+use scale_info::TypeInfo;
+use sp_runtime::{
+	traits::{AtLeast32BitUnsigned, Saturating, Zero},
+	Perbill, RuntimeDebug,
+};
+use sp_std::prelude::*;
+
+/// A validator of the active era.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct ValidatorInfo<AccountId, Balance> {
+	/// The era the exposure and preferences are for.
+	pub era: EraIndex,
+	/// The stake backing the validator, by itself and by each of its nominators.
+	pub exposure: Exposure<AccountId, Balance>,
+	/// The commission and whether the validator accepts new nominations.
+	pub prefs: ValidatorPrefs,
+}
+
+/// The unclaimed reward of an account for backing a validator in an era.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct PendingReward<AccountId, Balance> {
+	/// The era the reward was earned in.
+	pub era: EraIndex,
+	/// The validator whose payout pays the reward.
+	pub validator: AccountId,
+	/// The amount of the reward.
+	pub amount: Balance,
+}
+
+sp_api::decl_runtime_apis! {
+	/// The API for querying validators and rewards in `pallet-staking`.
+	pub trait StakingApi<AccountId, Balance> where AccountId: Codec, Balance: Codec {
+		/// The index of the active era, if one has started.
+		fn active_era() -> Option<EraIndex>;
+
+		/// The exposure and preferences of the validator `stash` in the active era, if it is
+		/// elected.
+		fn validator_info(stash: AccountId) -> Option<ValidatorInfo<AccountId, Balance>>;
+
+		/// The rewards of `stash` as a validator or nominator which can still be paid out.
+		fn pending_rewards(stash: AccountId) -> Vec<PendingReward<AccountId, Balance>>;
+	}
+}
+
+/// The index of the active era.
+pub fn active_era<T: pallet_staking::Config>() -> Option<EraIndex> {
+	pallet_staking::Pallet::<T>::active_era().map(|era| era.index)
+}
+
+/// The exposure and preferences of `stash` in the active era.
+pub fn validator_info<T: pallet_staking::Config>(
+	stash: T::AccountId,
+) -> Option<ValidatorInfo<T::AccountId, BalanceOf<T>>> {
+	let era = active_era::<T>()?;
+	if !pallet_staking::ErasStakers::<T>::contains_key(era, &stash) {
+		return None
+	}
+
+	let exposure = pallet_staking::Pallet::<T>::eras_stakers(era, &stash);
+	let prefs = pallet_staking::Pallet::<T>::eras_validator_prefs(era, &stash);
+	Some(ValidatorInfo { era, exposure, prefs })
+}
+
+/// The rewards of `stash` which `payout_stakers` would still pay, i.e. those of the eras within the
+/// history depth which the validator has not claimed yet.
+///
+/// As with `payout_stakers`, nominators are only rewarded for the clipped exposure.
+pub fn pending_rewards<T: pallet_staking::Config>(
+	stash: T::AccountId,
+) -> Vec<PendingReward<T::AccountId, BalanceOf<T>>> {
+	let current_era = match pallet_staking::Pallet::<T>::current_era() {
+		Some(era) => era,
+		None => return Vec::new(),
+	};
+	let first_era = current_era.saturating_sub(pallet_staking::Pallet::<T>::history_depth());
+
+	let mut rewards = Vec::new();
+	for era in first_era..current_era {
+		let era_payout = match pallet_staking::Pallet::<T>::eras_validator_reward(era) {
+			Some(payout) => payout,
+			None => continue,
+		};
+		let points = pallet_staking::Pallet::<T>::eras_reward_points(era);
+
+		for (validator, exposure) in pallet_staking::ErasStakersClipped::<T>::iter_prefix(era) {
+			let stake = if validator == stash {
+				exposure.own
+			} else {
+				match exposure.others.iter().find(|nominator| nominator.who == stash) {
+					Some(nominator) => nominator.value,
+					None => continue,
+				}
+			};
+
+			let validator_points = points.individual.get(&validator).copied().unwrap_or(0);
+			if validator_points == 0 || is_claimed::<T>(&validator, era) {
+				continue
+			}
+
+			let commission =
+				pallet_staking::Pallet::<T>::eras_validator_prefs(era, &validator).commission;
+			let amount = reward_share(
+				Perbill::from_rational(validator_points, points.total) * era_payout,
+				commission,
+				stake,
+				exposure.total,
+				validator == stash,
+			);
+			if !amount.is_zero() {
+				rewards.push(PendingReward { era, validator, amount });
+			}
+		}
+	}
+
+	rewards
+}
+
+/// Whether the reward of `validator` for `era` was paid out, or can no longer be because the
+/// validator is not bonded anymore.
+fn is_claimed<T: pallet_staking::Config>(validator: &T::AccountId, era: EraIndex) -> bool {
+	pallet_staking::Pallet::<T>::bonded(validator)
+		.and_then(|controller| pallet_staking::Pallet::<T>::ledger(&controller))
+		.map_or(true, |ledger| ledger.claimed_rewards.binary_search(&era).is_ok())
+}
+
+/// The share of `stake` in the `validator_payout`, split as `payout_stakers` does: the validator
+/// takes its `commission` first and the rest is shared pro rata among the `total` stake.
+fn reward_share<Balance: AtLeast32BitUnsigned + Copy>(
+	validator_payout: Balance,
+	commission: Perbill,
+	stake: Balance,
+	total: Balance,
+	is_validator: bool,
+) -> Balance {
+	let commission_payout = commission * validator_payout;
+	let leftover = validator_payout.saturating_sub(commission_payout);
+	let stake_payout = Perbill::from_rational(stake, total) * leftover;
+
+	if is_validator {
+		commission_payout.saturating_add(stake_payout)
+	} else {
+		stake_payout
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn commission_goes_to_the_validator() {
+		let commission = Perbill::from_percent(10);
+		// The validator has 250 of the 1000 staked.
+		assert_eq!(reward_share(1000u64, commission, 250, 1000, true), 100 + 225);
+		assert_eq!(reward_share(1000u64, commission, 750, 1000, false), 675);
+
+		assert_eq!(reward_share(1000u64, Perbill::zero(), 250, 1000, true), 250);
+		assert_eq!(reward_share(1000u64, Perbill::one(), 750, 1000, false), 0);
+	}
+}
@@ -0,0 +1,312 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Migration to retire `pallet-tips`.
+//!
+//! Runs once the pallet has been removed from the runtime. Every open tip which gathered enough
+//! tips to close is paid out from the treasury as `pallet-tips` would have done, every other tip
+//! is closed without payment, and all finder deposits are returned. Finally, all storage of the
+//! pallet is removed.
+//!
+//! The storage of the pallet, including its `StorageVersion`, is the one-shot flag of the
+//! migration: it is skipped once nothing is left under the `Tips` prefix. It can be removed
+//! after the next release.
+
+use frame_support::{
+	storage::{migration::storage_key_iter, unhashed},
+	traits::{
+		Currency, ExistenceRequirement::KeepAlive, Get, OnRuntimeUpgrade, ReservableCurrency,
+	},
+	weights::Weight,
+	Twox64Concat,
+};
+use parity_scale_codec::{Decode, Encode};
+use sp_runtime::{traits::Zero, Percent};
+use sp_std::{marker::PhantomData, prelude::*};
+
+/// The name `pallet-tips` was declared with in `construct_runtime`.
+pub const TIPS_PALLET: &[u8] = b"Tips";
+
+type BalanceOf<T> = <<T as pallet_treasury::Config>::Currency as Currency<
+	<T as frame_system::Config>::AccountId,
+>>::Balance;
+
+/// The layout of `pallet_tips::OpenTip`, whose fields are private.
+#[derive(Encode, Decode)]
+struct OpenTip<AccountId, Balance, BlockNumber, Hash> {
+	reason: Hash,
+	who: AccountId,
+	finder: AccountId,
+	deposit: Balance,
+	closes: Option<BlockNumber>,
+	tips: Vec<(AccountId, Balance)>,
+	finders_fee: bool,
+}
+
+/// Pays out or closes all open tips, refunds finder deposits and removes the storage of
+/// `pallet-tips`, unless it was removed already. `FindersFee` must be the `TipFindersFee` the
+/// pallet was configured with.
+pub struct RetireTips<T, FindersFee>(PhantomData<(T, FindersFee)>);
+
+impl<T, FindersFee> OnRuntimeUpgrade for RetireTips<T, FindersFee>
+where
+	T: pallet_treasury::Config,
+	FindersFee: Get<Percent>,
+{
+	fn on_runtime_upgrade() -> Weight {
+		let prefix = sp_io::hashing::twox_128(TIPS_PALLET);
+		if !sp_io::storage::next_key(&prefix).map_or(false, |key| key.starts_with(&prefix)) {
+			log::info!(target: "runtime::tips", "Tips pallet was retired already");
+			return T::DbWeight::get().reads(1)
+		}
+
+		let tips: Vec<_> = storage_key_iter::<
+			T::Hash,
+			OpenTip<T::AccountId, BalanceOf<T>, T::BlockNumber, T::Hash>,
+			Twox64Concat,
+		>(TIPS_PALLET, b"Tips")
+		.map(|(_, tip)| tip)
+		.collect();
+
+		let treasury = <pallet_treasury::Pallet<T>>::account_id();
+		let (mut paid, mut closed) = (0u64, 0u64);
+		for tip in tips {
+			if !tip.deposit.is_zero() {
+				let _ = T::Currency::unreserve(&tip.finder, tip.deposit);
+			}
+
+			if tip.closes.is_none() || tip.tips.is_empty() {
+				closed += 1;
+				continue
+			}
+
+			// Pay the median tip, like `pallet-tips` does when a tip is closed.
+			let mut tips = tip.tips;
+			tips.sort_by_key(|(_, value)| *value);
+			let mut payout = tips[tips.len() / 2].1.min(<pallet_treasury::Pallet<T>>::pot());
+			if tip.finders_fee && tip.finder != tip.who {
+				let finders_fee = FindersFee::get() * payout;
+				payout -= finders_fee;
+				let _ = T::Currency::transfer(&treasury, &tip.finder, finders_fee, KeepAlive);
+			}
+			let _ = T::Currency::transfer(&treasury, &tip.who, payout, KeepAlive);
+			paid += 1;
+		}
+
+		let removed = match unhashed::kill_prefix(&prefix, None) {
+			sp_io::KillStorageResult::AllRemoved(n) |
+			sp_io::KillStorageResult::SomeRemaining(n) => n as u64,
+		};
+
+		log::info!(
+			target: "runtime::tips",
+			"Retired tips pallet: {} tips paid out, {} closed, {} keys removed",
+			paid,
+			closed,
+			removed,
+		);
+
+		let tips = paid + closed;
+		T::DbWeight::get().reads_writes(tips + 2, tips.saturating_mul(4).saturating_add(removed))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use frame_support::{
+		parameter_types,
+		storage::migration::{get_storage_value, put_storage_value},
+		weights::constants::RocksDbWeight,
+		PalletId, StorageHasher,
+	};
+	use frame_system::EnsureRoot;
+	use sp_core::H256;
+	use sp_runtime::{
+		testing::Header,
+		traits::{BlakeTwo256, IdentityLookup},
+		Permill,
+	};
+
+	type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+	type Block = frame_system::mocking::MockBlock<Test>;
+
+	frame_support::construct_runtime!(
+		pub enum Test where
+			Block = Block,
+			NodeBlock = Block,
+			UncheckedExtrinsic = UncheckedExtrinsic,
+		{
+			System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+			Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+			Treasury: pallet_treasury::{Pallet, Call, Storage, Config, Event<T>},
+		}
+	);
+
+	parameter_types! {
+		pub const BlockHashCount: u32 = 250;
+	}
+
+	impl frame_system::Config for Test {
+		type BaseCallFilter = frame_support::traits::Everything;
+		type BlockWeights = ();
+		type BlockLength = ();
+		type DbWeight = RocksDbWeight;
+		type Origin = Origin;
+		type Call = Call;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<u64>;
+		type Header = Header;
+		type Event = Event;
+		type BlockHashCount = BlockHashCount;
+		type Version = ();
+		type PalletInfo = PalletInfo;
+		type AccountData = pallet_balances::AccountData<u64>;
+		type OnNewAccount = ();
+		type OnKilledAccount = ();
+		type SystemWeightInfo = ();
+		type SS58Prefix = ();
+		type OnSetCode = ();
+		type MaxConsumers = frame_support::traits::ConstU32<16>;
+	}
+
+	parameter_types! {
+		pub const ExistentialDeposit: u64 = 1;
+	}
+
+	impl pallet_balances::Config for Test {
+		type Balance = u64;
+		type Event = Event;
+		type DustRemoval = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type AccountStore = System;
+		type MaxLocks = ();
+		type MaxReserves = ();
+		type ReserveIdentifier = [u8; 8];
+		type WeightInfo = ();
+	}
+
+	parameter_types! {
+		pub const ProposalBond: Permill = Permill::from_percent(5);
+		pub const ProposalBondMinimum: u64 = 1;
+		pub const SpendPeriod: u64 = 2;
+		pub const Burn: Permill = Permill::from_percent(50);
+		pub const TreasuryPalletId: PalletId = PalletId(*b"py/trsry");
+		pub const MaxApprovals: u32 = 100;
+		pub const TipFindersFee: Percent = Percent::from_percent(20);
+	}
+
+	impl pallet_treasury::Config for Test {
+		type PalletId = TreasuryPalletId;
+		type Currency = Balances;
+		type ApproveOrigin = EnsureRoot<u64>;
+		type RejectOrigin = EnsureRoot<u64>;
+		type Event = Event;
+		type OnSlash = ();
+		type ProposalBond = ProposalBond;
+		type ProposalBondMinimum = ProposalBondMinimum;
+		type ProposalBondMaximum = ();
+		type SpendPeriod = SpendPeriod;
+		type Burn = Burn;
+		type BurnDestination = ();
+		type SpendFunds = ();
+		type MaxApprovals = MaxApprovals;
+		type WeightInfo = ();
+	}
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		pallet_balances::GenesisConfig::<Test> {
+			balances: vec![(1, 100), (2, 100), (Treasury::account_id(), 1_001)],
+		}
+		.assimilate_storage(&mut t)
+		.unwrap();
+		t.into()
+	}
+
+	fn put_tip(hash: H256, tip: OpenTip<u64, u64, u64, H256>) {
+		Balances::reserve(&tip.finder, tip.deposit).unwrap();
+		let key = Twox64Concat::hash(&hash.encode());
+		put_storage_value(TIPS_PALLET, b"Tips", &key, tip);
+		put_storage_value(TIPS_PALLET, b"Reasons", hash.as_bytes(), b"reason".to_vec());
+	}
+
+	#[test]
+	fn open_tips_are_paid_out_or_closed() {
+		new_test_ext().execute_with(|| {
+			// Closing, reported by 1 for 2 with a finders fee.
+			put_tip(
+				H256::repeat_byte(1),
+				OpenTip {
+					reason: H256::repeat_byte(1),
+					who: 2,
+					finder: 1,
+					deposit: 10,
+					closes: Some(5),
+					tips: vec![(10, 100), (11, 50), (12, 200)],
+					finders_fee: true,
+				},
+			);
+			// Not enough tippers yet.
+			put_tip(
+				H256::repeat_byte(2),
+				OpenTip {
+					reason: H256::repeat_byte(2),
+					who: 1,
+					finder: 2,
+					deposit: 20,
+					closes: None,
+					tips: vec![(10, 100)],
+					finders_fee: true,
+				},
+			);
+
+			RetireTips::<Test, TipFindersFee>::on_runtime_upgrade();
+
+			assert_eq!(Balances::reserved_balance(1), 0);
+			assert_eq!(Balances::reserved_balance(2), 0);
+			// The median tip of 100, of which 20 go to the finder.
+			assert_eq!(Balances::free_balance(1), 120);
+			assert_eq!(Balances::free_balance(2), 180);
+			assert_eq!(Treasury::pot(), 900);
+
+			let reason = H256::repeat_byte(1);
+			assert!(
+				get_storage_value::<Vec<u8>>(TIPS_PALLET, b"Reasons", reason.as_bytes()).is_none()
+			);
+			type Tip = OpenTip<u64, u64, u64, H256>;
+			assert_eq!(
+				storage_key_iter::<H256, Tip, Twox64Concat>(TIPS_PALLET, b"Tips").count(),
+				0
+			);
+		});
+	}
+
+	#[test]
+	fn retired_tips_are_skipped() {
+		new_test_ext().execute_with(|| {
+			assert_eq!(
+				RetireTips::<Test, TipFindersFee>::on_runtime_upgrade(),
+				RocksDbWeight::get().reads(1)
+			);
+		});
+	}
+}
@@ -0,0 +1,560 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Treasury payouts to accounts on child parachains.
+//!
+//! The `ApproveOrigin` approves payouts of the treasury to an account on a parachain, e.g. to
+//! reward the collators of the ecosystem. Approved payouts are paid as part of the spends of the
+//! treasury: the amount is moved into the sovereign account of the parachain, which holds the
+//! reserve of the native token there, and an XCM message asks the parachain to deposit the
+//! reserve-backed token into the account, after paying for its execution out of it.
+//!
+//! Payouts which the remaining budget can't cover wait for the next spend period. Payouts to paras
+//! which are no longer parachains, or whose message can't be sent, are dropped and their funds
+//! stay in the treasury.
+
+use crate::traits::Registrar;
+use frame_support::{
+	pallet_prelude::*,
+	traits::{Currency, Imbalance},
+};
+use frame_system::pallet_prelude::*;
+pub use pallet::*;
+use pallet_treasury::{BalanceOf, PositiveImbalanceOf, SpendFunds};
+use primitives::v1::Id as ParaId;
+use sp_runtime::traits::{AccountIdConversion, Saturating, UniqueSaturatedInto};
+use sp_std::prelude::*;
+use xcm::latest::prelude::*;
+
+/// An index of a payout.
+pub type PayoutIndex = u32;
+
+pub trait WeightInfo {
+	fn approve_payout() -> Weight;
+	fn remove_payout() -> Weight;
+	fn spend_funds(p: u32) -> Weight;
+}
+
+pub struct TestWeightInfo;
+impl WeightInfo for TestWeightInfo {
+	fn approve_payout() -> Weight {
+		0
+	}
+	fn remove_payout() -> Weight {
+		0
+	}
+	fn spend_funds(_p: u32) -> Weight {
+		0
+	}
+}
+
+/// A payout of the treasury to an account on a parachain.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct RemotePayout<Balance> {
+	/// The parachain the account is on.
+	pub para: ParaId,
+	/// The 32-byte account on the parachain.
+	pub beneficiary: [u8; 32],
+	/// The amount paid.
+	pub amount: Balance,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::without_storage_info]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + pallet_treasury::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The origin approving payouts, usually the `ApproveOrigin` of the treasury.
+		type ApproveOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The registrar, used to check that payouts go to parachains.
+		type Registrar: Registrar<AccountId = Self::AccountId>;
+
+		/// The router sending the messages to the parachains.
+		type XcmSender: SendXcm;
+
+		/// The maximum number of approved payouts waiting to be paid.
+		#[pallet::constant]
+		type MaxPayouts: Get<u32>;
+
+		/// Weight information for the extrinsics and spends of this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	/// The number of payouts that have been approved.
+	#[pallet::storage]
+	#[pallet::getter(fn payout_count)]
+	pub type PayoutCount<T> = StorageValue<_, PayoutIndex, ValueQuery>;
+
+	/// The approved payouts which have not been paid yet.
+	#[pallet::storage]
+	#[pallet::getter(fn payouts)]
+	pub type Payouts<T: Config> =
+		StorageMap<_, Twox64Concat, PayoutIndex, RemotePayout<BalanceOf<T>>, OptionQuery>;
+
+	/// The indices of the approved payouts, in the order they are paid.
+	#[pallet::storage]
+	#[pallet::getter(fn approvals)]
+	pub type Approvals<T: Config> =
+		StorageValue<_, BoundedVec<PayoutIndex, T::MaxPayouts>, ValueQuery>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A payout to `beneficiary` on `para` has been approved.
+		PayoutApproved {
+			index: PayoutIndex,
+			para: ParaId,
+			beneficiary: [u8; 32],
+			amount: BalanceOf<T>,
+		},
+		/// An approved payout has been removed before being paid.
+		PayoutRemoved { index: PayoutIndex },
+		/// A payout has been sent to its parachain.
+		PayoutSent { index: PayoutIndex },
+		/// A payout has been dropped as its parachain couldn't be reached. The funds stay in the
+		/// treasury.
+		PayoutDropped { index: PayoutIndex },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The para is not a parachain.
+		NotParachain,
+		/// Too many payouts are waiting to be paid.
+		TooManyApprovals,
+		/// There is no approved payout with this index.
+		UnknownPayout,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Approve paying `amount` of the treasury to `beneficiary` on the parachain `para` with
+		/// the next spends of the treasury.
+		///
+		/// The origin must be `ApproveOrigin`.
+		#[pallet::weight(<T as Config>::WeightInfo::approve_payout())]
+		pub fn approve_payout(
+			origin: OriginFor<T>,
+			para: ParaId,
+			beneficiary: [u8; 32],
+			#[pallet::compact] amount: BalanceOf<T>,
+		) -> DispatchResult {
+			<T as Config>::ApproveOrigin::ensure_origin(origin)?;
+			ensure!(T::Registrar::is_parachain(para), Error::<T>::NotParachain);
+
+			let index = Self::payout_count();
+			Approvals::<T>::try_append(index).map_err(|()| Error::<T>::TooManyApprovals)?;
+			PayoutCount::<T>::put(index + 1);
+			Payouts::<T>::insert(index, RemotePayout { para, beneficiary, amount });
+
+			Self::deposit_event(Event::<T>::PayoutApproved { index, para, beneficiary, amount });
+			Ok(())
+		}
+
+		/// Remove an approved payout which has not been paid yet.
+		///
+		/// The origin must be `ApproveOrigin`.
+		#[pallet::weight(<T as Config>::WeightInfo::remove_payout())]
+		pub fn remove_payout(origin: OriginFor<T>, index: PayoutIndex) -> DispatchResult {
+			<T as Config>::ApproveOrigin::ensure_origin(origin)?;
+			Payouts::<T>::take(index).ok_or(Error::<T>::UnknownPayout)?;
+			Approvals::<T>::mutate(|approvals| approvals.retain(|i| *i != index));
+
+			Self::deposit_event(Event::<T>::PayoutRemoved { index });
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Send the message depositing `payout` into its beneficiary, as seen from the parachain.
+	fn send_payout(payout: &RemotePayout<BalanceOf<T>>) -> SendResult {
+		let amount: u128 = payout.amount.unique_saturated_into();
+		let asset: MultiAsset = (Parent, amount).into();
+		let message = Xcm(vec![
+			ReserveAssetDeposited(asset.clone().into()),
+			ClearOrigin,
+			BuyExecution { fees: asset, weight_limit: Unlimited },
+			DepositAsset {
+				assets: Wild(All),
+				max_assets: 1,
+				beneficiary: MultiLocation {
+					parents: 0,
+					interior: X1(AccountId32 { network: Any, id: payout.beneficiary }),
+				},
+			},
+		]);
+		let dest = MultiLocation { parents: 0, interior: X1(Parachain(payout.para.into())) };
+		T::XcmSender::send_xcm(dest, message)
+	}
+}
+
+impl<T: Config> SpendFunds<T> for Pallet<T> {
+	fn spend_funds(
+		budget_remaining: &mut BalanceOf<T>,
+		imbalance: &mut PositiveImbalanceOf<T>,
+		total_weight: &mut Weight,
+		missed_any: &mut bool,
+	) {
+		let approvals = Approvals::<T>::get();
+		*total_weight += <T as Config>::WeightInfo::spend_funds(approvals.len() as u32);
+
+		let mut waiting = Vec::new();
+		for index in approvals.into_inner() {
+			let payout = match Payouts::<T>::get(index) {
+				Some(payout) => payout,
+				None => continue,
+			};
+			if payout.amount > *budget_remaining {
+				*missed_any = true;
+				waiting.push(index);
+				continue
+			}
+
+			Payouts::<T>::remove(index);
+			if !T::Registrar::is_parachain(payout.para) || Self::send_payout(&payout).is_err() {
+				Self::deposit_event(Event::<T>::PayoutDropped { index });
+				continue
+			}
+
+			*budget_remaining = budget_remaining.saturating_sub(payout.amount);
+			let sovereign: T::AccountId = payout.para.into_account();
+			imbalance.subsume(T::Currency::deposit_creating(&sovereign, payout.amount));
+			Self::deposit_event(Event::<T>::PayoutSent { index });
+		}
+
+		let waiting: BoundedVec<_, T::MaxPayouts> =
+			waiting.try_into().expect("at most as many as were approved; qed");
+		Approvals::<T>::put(waiting);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{mock::TestRegistrar, treasury_xcm_payouts};
+	use ::test_helpers::{dummy_head_data, dummy_validation_code};
+	use frame_support::{assert_noop, assert_ok, parameter_types, traits::OnInitialize, PalletId};
+	use frame_system::EnsureRoot;
+	use sp_core::H256;
+	use sp_runtime::{
+		testing::Header,
+		traits::{BlakeTwo256, IdentityLookup},
+		Permill,
+	};
+	use std::cell::RefCell;
+
+	type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+	type Block = frame_system::mocking::MockBlock<Test>;
+
+	frame_support::construct_runtime!(
+		pub enum Test where
+			Block = Block,
+			NodeBlock = Block,
+			UncheckedExtrinsic = UncheckedExtrinsic,
+		{
+			System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+			Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+			Treasury: pallet_treasury::{Pallet, Call, Storage, Config, Event<T>},
+			TreasuryXcmPayouts: treasury_xcm_payouts::{Pallet, Call, Storage, Event<T>},
+		}
+	);
+
+	parameter_types! {
+		pub const BlockHashCount: u32 = 250;
+	}
+
+	impl frame_system::Config for Test {
+		type BaseCallFilter = frame_support::traits::Everything;
+		type BlockWeights = ();
+		type BlockLength = ();
+		type DbWeight = ();
+		type Origin = Origin;
+		type Call = Call;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<u64>;
+		type Header = Header;
+		type Event = Event;
+		type BlockHashCount = BlockHashCount;
+		type Version = ();
+		type PalletInfo = PalletInfo;
+		type AccountData = pallet_balances::AccountData<u64>;
+		type OnNewAccount = ();
+		type OnKilledAccount = ();
+		type SystemWeightInfo = ();
+		type SS58Prefix = ();
+		type OnSetCode = ();
+		type MaxConsumers = frame_support::traits::ConstU32<16>;
+	}
+
+	parameter_types! {
+		pub const ExistentialDeposit: u64 = 1;
+	}
+
+	impl pallet_balances::Config for Test {
+		type Balance = u64;
+		type Event = Event;
+		type DustRemoval = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type AccountStore = System;
+		type MaxLocks = ();
+		type MaxReserves = ();
+		type ReserveIdentifier = [u8; 8];
+		type WeightInfo = ();
+	}
+
+	parameter_types! {
+		pub const ProposalBond: Permill = Permill::from_percent(5);
+		pub const ProposalBondMinimum: u64 = 1;
+		pub const SpendPeriod: u64 = 2;
+		pub const Burn: Permill = Permill::zero();
+		pub const TreasuryPalletId: PalletId = PalletId(*b"py/trsry");
+		pub const MaxApprovals: u32 = 100;
+		pub const MaxPayouts: u32 = 10;
+	}
+
+	impl pallet_treasury::Config for Test {
+		type PalletId = TreasuryPalletId;
+		type Currency = Balances;
+		type ApproveOrigin = EnsureRoot<u64>;
+		type RejectOrigin = EnsureRoot<u64>;
+		type Event = Event;
+		type OnSlash = ();
+		type ProposalBond = ProposalBond;
+		type ProposalBondMinimum = ProposalBondMinimum;
+		type ProposalBondMaximum = ();
+		type SpendPeriod = SpendPeriod;
+		type Burn = Burn;
+		type BurnDestination = ();
+		type SpendFunds = TreasuryXcmPayouts;
+		type MaxApprovals = MaxApprovals;
+		type WeightInfo = ();
+	}
+
+	thread_local! {
+		static SENT_XCM: RefCell<Vec<(MultiLocation, Xcm<()>)>> = RefCell::new(Vec::new());
+	}
+
+	/// Records the messages sent to parachain 2000, and can't reach any other destination.
+	pub struct TestSendXcm;
+	impl SendXcm for TestSendXcm {
+		fn send_xcm(dest: impl Into<MultiLocation>, msg: Xcm<()>) -> SendResult {
+			let dest = dest.into();
+			if dest != (MultiLocation { parents: 0, interior: X1(Parachain(2000)) }) {
+				return Err(SendError::CannotReachDestination(dest, msg))
+			}
+			SENT_XCM.with(|q| q.borrow_mut().push((dest, msg)));
+			Ok(())
+		}
+	}
+
+	impl Config for Test {
+		type Event = Event;
+		type ApproveOrigin = EnsureRoot<u64>;
+		type Registrar = TestRegistrar<Test>;
+		type XcmSender = TestSendXcm;
+		type MaxPayouts = MaxPayouts;
+		type WeightInfo = TestWeightInfo;
+	}
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		pallet_balances::GenesisConfig::<Test> { balances: vec![(Treasury::account_id(), 1_001)] }
+			.assimilate_storage(&mut t)
+			.unwrap();
+		let mut ext: sp_io::TestExternalities = t.into();
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+
+	fn parachain(id: u32) -> ParaId {
+		let para = ParaId::from(id);
+		assert_ok!(TestRegistrar::<Test>::register(
+			1,
+			para,
+			dummy_head_data(),
+			dummy_validation_code()
+		));
+		assert_ok!(TestRegistrar::<Test>::make_parachain(para));
+		para
+	}
+
+	fn sovereign(para: ParaId) -> u64 {
+		para.into_account()
+	}
+
+	#[test]
+	fn approved_payouts_are_sent_with_the_spends() {
+		new_test_ext().execute_with(|| {
+			let para = parachain(2000);
+			assert_noop!(
+				TreasuryXcmPayouts::approve_payout(Origin::signed(1), para, [1; 32], 100),
+				DispatchError::BadOrigin,
+			);
+			assert_noop!(
+				TreasuryXcmPayouts::approve_payout(Origin::root(), 3000.into(), [1; 32], 100),
+				Error::<Test>::NotParachain,
+			);
+
+			assert_ok!(TreasuryXcmPayouts::approve_payout(Origin::root(), para, [1; 32], 100));
+			// More than the pot, so it waits for the next spends.
+			assert_ok!(TreasuryXcmPayouts::approve_payout(Origin::root(), para, [2; 32], 1_000));
+			assert_eq!(TreasuryXcmPayouts::approvals().into_inner(), vec![0, 1]);
+
+			Treasury::on_initialize(2);
+
+			assert_eq!(Balances::free_balance(sovereign(para)), 100);
+			assert_eq!(Treasury::pot(), 900);
+			assert_eq!(TreasuryXcmPayouts::approvals().into_inner(), vec![1]);
+			assert!(TreasuryXcmPayouts::payouts(0).is_none());
+
+			let asset: MultiAsset = (Parent, 100u128).into();
+			let sent = SENT_XCM.with(|q| q.borrow().clone());
+			assert_eq!(
+				sent,
+				vec![(
+					MultiLocation { parents: 0, interior: X1(Parachain(2000)) },
+					Xcm(vec![
+						ReserveAssetDeposited(asset.clone().into()),
+						ClearOrigin,
+						BuyExecution { fees: asset, weight_limit: Unlimited },
+						DepositAsset {
+							assets: Wild(All),
+							max_assets: 1,
+							beneficiary: MultiLocation {
+								parents: 0,
+								interior: X1(AccountId32 { network: Any, id: [1; 32] }),
+							},
+						},
+					]),
+				)],
+			);
+
+			assert_ok!(TreasuryXcmPayouts::remove_payout(Origin::root(), 1));
+			assert!(TreasuryXcmPayouts::approvals().is_empty());
+			assert_noop!(
+				TreasuryXcmPayouts::remove_payout(Origin::root(), 1),
+				Error::<Test>::UnknownPayout,
+			);
+		});
+	}
+
+	#[test]
+	fn payouts_to_unreachable_parachains_are_dropped() {
+		new_test_ext().execute_with(|| {
+			let para = parachain(3000);
+			assert_ok!(TreasuryXcmPayouts::approve_payout(Origin::root(), para, [1; 32], 100));
+
+			Treasury::on_initialize(2);
+
+			assert_eq!(Balances::free_balance(sovereign(para)), 0);
+			assert_eq!(Treasury::pot(), 1_000);
+			assert!(TreasuryXcmPayouts::approvals().is_empty());
+			let dropped =
+				Event::TreasuryXcmPayouts(treasury_xcm_payouts::Event::PayoutDropped { index: 0 });
+			assert!(System::events().iter().any(|record| record.event == dropped));
+		});
+	}
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking {
+	use super::{Pallet as TreasuryXcmPayouts, *};
+	use frame_support::assert_ok;
+	use sp_runtime::traits::Bounded;
+
+	use frame_benchmarking::{account, benchmarks};
+
+	fn parachain<T: Config>(id: u32) -> ParaId {
+		let para = ParaId::from(id);
+		assert_ok!(T::Registrar::register(
+			account("manager", id, 0),
+			para,
+			T::Registrar::worst_head_data(),
+			T::Registrar::worst_validation_code(),
+		));
+		T::Registrar::execute_pending_transitions();
+		assert_ok!(T::Registrar::make_parachain(para));
+		T::Registrar::execute_pending_transitions();
+		para
+	}
+
+	benchmarks! {
+		approve_payout {
+			let para = parachain::<T>(2000);
+			let origin = <T as Config>::ApproveOrigin::successful_origin();
+		}: _<T::Origin>(origin, para, [1; 32], T::Currency::minimum_balance())
+		verify {
+			assert_eq!(Approvals::<T>::get().len(), 1);
+		}
+
+		remove_payout {
+			let para = parachain::<T>(2000);
+			let origin = <T as Config>::ApproveOrigin::successful_origin();
+			TreasuryXcmPayouts::<T>::approve_payout(
+				origin.clone(),
+				para,
+				[1; 32],
+				T::Currency::minimum_balance(),
+			)?;
+		}: _<T::Origin>(origin, 0)
+		verify {
+			assert!(Approvals::<T>::get().is_empty());
+		}
+
+		spend_funds {
+			let p in 0 .. T::MaxPayouts::get();
+			let para = parachain::<T>(2000);
+			let origin = <T as Config>::ApproveOrigin::successful_origin();
+			for i in 0 .. p {
+				TreasuryXcmPayouts::<T>::approve_payout(
+					origin.clone(),
+					para,
+					[i as u8; 32],
+					T::Currency::minimum_balance(),
+				)?;
+			}
+			let mut budget = BalanceOf::<T>::max_value();
+			let mut imbalance = PositiveImbalanceOf::<T>::zero();
+			let mut weight = 0;
+			let mut missed_any = false;
+		}: {
+			<TreasuryXcmPayouts<T> as SpendFunds<T>>::spend_funds(
+				&mut budget,
+				&mut imbalance,
+				&mut weight,
+				&mut missed_any,
+			);
+		}
+		verify {
+			assert!(Approvals::<T>::get().is_empty());
+		}
+	}
+}
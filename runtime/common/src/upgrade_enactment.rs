@@ -0,0 +1,349 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Pallet enacting runtime upgrades a fixed number of blocks after they are approved.
+//!
+//! Instead of replacing the code at once through `frame_system::set_code`, `UpgradeOrigin`
+//! schedules the new code through [`Pallet::schedule_code_upgrade`]. The code is checked and the
+//! `UpgradeScheduled` event announces its spec version and the block it is enacted at, giving
+//! exchanges and other tooling `EnactmentDelay` blocks to prepare. Only one upgrade can be
+//! scheduled at a time, and `UpgradeOrigin` can cancel it before it is enacted.
+
+use frame_support::{pallet_prelude::*, weights::DispatchClass};
+use frame_system::{pallet_prelude::*, SetCode};
+pub use pallet::*;
+use parity_scale_codec::Decode;
+use sp_runtime::traits::Saturating;
+use sp_std::prelude::*;
+use sp_version::RuntimeVersion;
+
+/// An upgrade waiting to be enacted.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct ScheduledUpgrade<BlockNumber> {
+	/// The spec version of the new code.
+	pub spec_version: u32,
+	/// The block at which the new code is enacted.
+	pub at: BlockNumber,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The origin which may schedule and cancel upgrades.
+		type UpgradeOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The number of blocks between scheduling an upgrade and enacting it.
+		#[pallet::constant]
+		type EnactmentDelay: Get<Self::BlockNumber>;
+	}
+
+	/// The upgrade waiting to be enacted, if any.
+	#[pallet::storage]
+	pub type Scheduled<T: Config> = StorageValue<_, ScheduledUpgrade<T::BlockNumber>, OptionQuery>;
+
+	/// The code of the upgrade waiting to be enacted.
+	///
+	/// Kept apart from [`Scheduled`] so that it is only read when the upgrade is enacted.
+	#[pallet::storage]
+	pub type ScheduledCode<T: Config> = StorageValue<_, Vec<u8>, ValueQuery>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The code with `spec_version` is enacted at block `at`.
+		UpgradeScheduled { spec_version: u32, at: T::BlockNumber },
+		/// The code with `spec_version` was enacted.
+		UpgradeEnacted { spec_version: u32 },
+		/// The upgrade to `spec_version` was cancelled.
+		UpgradeCancelled { spec_version: u32 },
+		/// The code with `spec_version` could not be enacted anymore, and was dropped.
+		UpgradeFailed { spec_version: u32, error: DispatchError },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// Another upgrade is already scheduled.
+		AlreadyScheduled,
+		/// No upgrade is scheduled.
+		NotScheduled,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(now: BlockNumberFor<T>) -> Weight {
+			match Scheduled::<T>::get() {
+				Some(upgrade) if upgrade.at <= now => {
+					Self::enact(upgrade.spec_version);
+					T::BlockWeights::get().max_block
+				},
+				_ => T::DbWeight::get().reads(1),
+			}
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Schedule `code` to replace the runtime `EnactmentDelay` blocks from now.
+		///
+		/// The code is checked like `frame_system::set_code` does. The origin must be
+		/// `UpgradeOrigin`.
+		#[pallet::weight((T::BlockWeights::get().max_block, DispatchClass::Operational))]
+		pub fn schedule_code_upgrade(origin: OriginFor<T>, code: Vec<u8>) -> DispatchResult {
+			T::UpgradeOrigin::ensure_origin(origin)?;
+			ensure!(!Scheduled::<T>::exists(), Error::<T>::AlreadyScheduled);
+
+			let spec_version = Self::check_code(&code)?;
+			let at =
+				frame_system::Pallet::<T>::block_number().saturating_add(T::EnactmentDelay::get());
+			Scheduled::<T>::put(ScheduledUpgrade { spec_version, at });
+			ScheduledCode::<T>::put(code);
+			Self::deposit_event(Event::<T>::UpgradeScheduled { spec_version, at });
+			Ok(())
+		}
+
+		/// Cancel the scheduled upgrade.
+		///
+		/// The origin must be `UpgradeOrigin`.
+		#[pallet::weight(T::DbWeight::get().reads_writes(1, 2))]
+		pub fn cancel_upgrade(origin: OriginFor<T>) -> DispatchResult {
+			T::UpgradeOrigin::ensure_origin(origin)?;
+			let upgrade = Scheduled::<T>::take().ok_or(Error::<T>::NotScheduled)?;
+
+			ScheduledCode::<T>::kill();
+			let spec_version = upgrade.spec_version;
+			Self::deposit_event(Event::<T>::UpgradeCancelled { spec_version });
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The spec version of `code`, which has to be a valid upgrade of the current runtime.
+	fn check_code(code: &[u8]) -> Result<u32, DispatchError> {
+		let new = sp_io::misc::runtime_version(code)
+			.and_then(|v| RuntimeVersion::decode(&mut &v[..]).ok())
+			.ok_or(frame_system::Error::<T>::FailedToExtractRuntimeVersion)?;
+		let current = T::Version::get();
+
+		ensure!(new.spec_name == current.spec_name, frame_system::Error::<T>::InvalidSpecName);
+		ensure!(
+			new.spec_version > current.spec_version,
+			frame_system::Error::<T>::SpecVersionNeedsToIncrease
+		);
+		Ok(new.spec_version)
+	}
+
+	/// Replace the runtime with the scheduled code.
+	///
+	/// The code is checked again, as the runtime may have been upgraded in another way since.
+	fn enact(spec_version: u32) {
+		Scheduled::<T>::kill();
+		let code = ScheduledCode::<T>::take();
+
+		match Self::check_code(&code).and_then(|_| T::OnSetCode::set_code(code)) {
+			Ok(()) => Self::deposit_event(Event::<T>::UpgradeEnacted { spec_version }),
+			Err(error) => {
+				log::warn!(
+					target: "runtime::upgrade_enactment",
+					"failed to enact upgrade to spec version {}: {:?}",
+					spec_version,
+					error,
+				);
+				Self::deposit_event(Event::<T>::UpgradeFailed { spec_version, error });
+			},
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use crate::upgrade_enactment;
+	use frame_support::{assert_noop, assert_ok, ord_parameter_types, parameter_types};
+	use frame_system::EnsureSignedBy;
+	use sp_core::{
+		storage::well_known_keys,
+		traits::{Externalities, ReadRuntimeVersion, ReadRuntimeVersionExt},
+		H256,
+	};
+	use sp_runtime::{
+		testing::Header,
+		traits::{BadOrigin, BlakeTwo256, IdentityLookup},
+	};
+
+	type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+	type Block = frame_system::mocking::MockBlock<Test>;
+
+	frame_support::construct_runtime!(
+		pub enum Test where
+			Block = Block,
+			NodeBlock = Block,
+			UncheckedExtrinsic = UncheckedExtrinsic,
+		{
+			System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+			UpgradeEnactment: upgrade_enactment::{Pallet, Call, Storage, Event<T>},
+		}
+	);
+
+	parameter_types! {
+		pub const BlockHashCount: u32 = 250;
+		pub Version: RuntimeVersion = RuntimeVersion {
+			spec_name: sp_version::create_runtime_str!("test"),
+			spec_version: 1,
+			..Default::default()
+		};
+	}
+
+	impl frame_system::Config for Test {
+		type BaseCallFilter = frame_support::traits::Everything;
+		type BlockWeights = ();
+		type BlockLength = ();
+		type DbWeight = ();
+		type Origin = Origin;
+		type Call = Call;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<u64>;
+		type Header = Header;
+		type Event = Event;
+		type BlockHashCount = BlockHashCount;
+		type Version = Version;
+		type PalletInfo = PalletInfo;
+		type AccountData = ();
+		type OnNewAccount = ();
+		type OnKilledAccount = ();
+		type SystemWeightInfo = ();
+		type SS58Prefix = ();
+		type OnSetCode = ();
+		type MaxConsumers = frame_support::traits::ConstU32<16>;
+	}
+
+	ord_parameter_types! {
+		pub const Governance: u64 = 1;
+	}
+
+	parameter_types! {
+		pub const EnactmentDelay: u64 = 10;
+	}
+
+	impl Config for Test {
+		type Event = Event;
+		type UpgradeOrigin = EnsureSignedBy<Governance, u64>;
+		type EnactmentDelay = EnactmentDelay;
+	}
+
+	/// Reports the same runtime version for any code.
+	struct FixedVersion(Vec<u8>);
+	impl ReadRuntimeVersion for FixedVersion {
+		fn read_runtime_version(
+			&self,
+			_: &[u8],
+			_: &mut dyn Externalities,
+		) -> Result<Vec<u8>, String> {
+			Ok(self.0.clone())
+		}
+	}
+
+	/// Test externalities in which any code has the given spec version.
+	fn new_test_ext(code_spec_version: u32) -> sp_io::TestExternalities {
+		let t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		let mut ext = sp_io::TestExternalities::new(t);
+		let version = RuntimeVersion { spec_version: code_spec_version, ..Version::get() };
+		ext.register_extension(ReadRuntimeVersionExt::new(FixedVersion(version.encode())));
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+
+	#[test]
+	fn schedule_announces_upgrade() {
+		new_test_ext(2).execute_with(|| {
+			assert_noop!(
+				UpgradeEnactment::schedule_code_upgrade(Origin::signed(2), vec![1]),
+				BadOrigin
+			);
+
+			assert_ok!(UpgradeEnactment::schedule_code_upgrade(Origin::signed(1), vec![1]));
+			System::assert_last_event(Event::UpgradeEnactment(
+				upgrade_enactment::Event::UpgradeScheduled { spec_version: 2, at: 11 },
+			));
+			assert_noop!(
+				UpgradeEnactment::schedule_code_upgrade(Origin::signed(1), vec![2]),
+				Error::<Test>::AlreadyScheduled
+			);
+		});
+	}
+
+	#[test]
+	fn spec_version_has_to_increase() {
+		new_test_ext(1).execute_with(|| {
+			assert_noop!(
+				UpgradeEnactment::schedule_code_upgrade(Origin::signed(1), vec![1]),
+				frame_system::Error::<Test>::SpecVersionNeedsToIncrease
+			);
+		});
+	}
+
+	#[test]
+	fn upgrade_is_enacted_after_delay() {
+		new_test_ext(2).execute_with(|| {
+			assert_ok!(UpgradeEnactment::schedule_code_upgrade(Origin::signed(1), vec![1, 2, 3]));
+
+			UpgradeEnactment::on_initialize(10);
+			assert!(sp_io::storage::get(well_known_keys::CODE).is_none());
+
+			UpgradeEnactment::on_initialize(11);
+			assert_eq!(sp_io::storage::get(well_known_keys::CODE), Some(vec![1, 2, 3]));
+			System::assert_last_event(Event::UpgradeEnactment(
+				upgrade_enactment::Event::UpgradeEnacted { spec_version: 2 },
+			));
+			assert!(Scheduled::<Test>::get().is_none());
+			assert!(!ScheduledCode::<Test>::exists());
+		});
+	}
+
+	#[test]
+	fn cancelled_upgrade_is_not_enacted() {
+		new_test_ext(2).execute_with(|| {
+			assert_noop!(
+				UpgradeEnactment::cancel_upgrade(Origin::signed(1)),
+				Error::<Test>::NotScheduled
+			);
+			assert_ok!(UpgradeEnactment::schedule_code_upgrade(Origin::signed(1), vec![1]));
+
+			assert_ok!(UpgradeEnactment::cancel_upgrade(Origin::signed(1)));
+			System::assert_last_event(Event::UpgradeEnactment(
+				upgrade_enactment::Event::UpgradeCancelled { spec_version: 2 },
+			));
+
+			UpgradeEnactment::on_initialize(11);
+			assert!(sp_io::storage::get(well_known_keys::CODE).is_none());
+		});
+	}
+}
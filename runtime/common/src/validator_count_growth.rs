@@ -0,0 +1,360 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Pallet growing the number of validators on a schedule.
+//!
+//! Every `StepEras` eras, the validator count of staking is increased by `StepSize`, up to
+//! `MaxValidatorCount`. The schedule is fixed by the runtime, so the active set grows in a
+//! predictable way, but `ControlOrigin` can pause it. After resuming, the next step happens
+//! `StepEras` eras later. The validator count can still be set through staking, the schedule
+//! keeps growing it from whatever it is. Once `MaxValidatorCount` is reached, the schedule
+//! pauses itself.
+
+use frame_support::{pallet_prelude::*, traits::EnsureOrigin};
+use frame_system::pallet_prelude::*;
+pub use pallet::*;
+use pallet_staking::EraIndex;
+
+/// The staking state the schedule acts on.
+pub trait ValidatorCountTarget {
+	/// The index of the active era, if any.
+	fn active_era() -> Option<EraIndex>;
+
+	/// The number of validators elected.
+	fn validator_count() -> u32;
+
+	/// Set the number of validators elected.
+	fn set_validator_count(count: u32);
+}
+
+impl<T: pallet_staking::Config> ValidatorCountTarget for pallet_staking::Pallet<T> {
+	fn active_era() -> Option<EraIndex> {
+		pallet_staking::ActiveEra::<T>::get().map(|era| era.index)
+	}
+
+	fn validator_count() -> u32 {
+		pallet_staking::ValidatorCount::<T>::get()
+	}
+
+	fn set_validator_count(count: u32) {
+		pallet_staking::ValidatorCount::<T>::put(count)
+	}
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The staking state to grow the validator count of.
+		type Staking: ValidatorCountTarget;
+
+		/// The origin which may pause and resume the schedule.
+		type ControlOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The number of eras between two steps.
+		#[pallet::constant]
+		type StepEras: Get<EraIndex>;
+
+		/// The number of validators added by a step.
+		#[pallet::constant]
+		type StepSize: Get<u32>;
+
+		/// The validator count the schedule stops at.
+		#[pallet::constant]
+		type MaxValidatorCount: Get<u32>;
+	}
+
+	/// The era at which the next step happens. Set on the first block of an era after the
+	/// schedule started or was resumed.
+	#[pallet::storage]
+	pub type NextStep<T: Config> = StorageValue<_, EraIndex, OptionQuery>;
+
+	/// Whether the schedule is paused.
+	#[pallet::storage]
+	pub type Paused<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The validator count was increased at the start of `era`.
+		ValidatorCountIncreased { era: EraIndex, old: u32, new: u32 },
+		/// The validator count reached `MaxValidatorCount`, the schedule is complete.
+		ScheduleCompleted,
+		/// The schedule was paused.
+		Paused,
+		/// The schedule was resumed.
+		Resumed,
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The schedule is already paused.
+		AlreadyPaused,
+		/// The schedule is not paused.
+		NotPaused,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(_: BlockNumberFor<T>) -> Weight {
+			if Paused::<T>::get() {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let era = match T::Staking::active_era() {
+				Some(era) => era,
+				None => return T::DbWeight::get().reads(2),
+			};
+			let next_step = match NextStep::<T>::get() {
+				Some(next_step) => next_step,
+				None => {
+					NextStep::<T>::put(era.saturating_add(T::StepEras::get()));
+					return T::DbWeight::get().reads_writes(3, 1)
+				},
+			};
+			if era < next_step {
+				return T::DbWeight::get().reads(3)
+			}
+
+			Self::step(era);
+			T::DbWeight::get().reads_writes(4, 2)
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Pause the schedule.
+		///
+		/// The origin must be `ControlOrigin`.
+		#[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+		pub fn pause(origin: OriginFor<T>) -> DispatchResult {
+			T::ControlOrigin::ensure_origin(origin)?;
+			ensure!(!Paused::<T>::get(), Error::<T>::AlreadyPaused);
+
+			Paused::<T>::put(true);
+			Self::deposit_event(Event::<T>::Paused);
+			Ok(())
+		}
+
+		/// Resume the schedule, with the next step `StepEras` eras from now.
+		///
+		/// The origin must be `ControlOrigin`.
+		#[pallet::weight(T::DbWeight::get().reads_writes(1, 2))]
+		pub fn resume(origin: OriginFor<T>) -> DispatchResult {
+			T::ControlOrigin::ensure_origin(origin)?;
+			ensure!(Paused::<T>::get(), Error::<T>::NotPaused);
+
+			Paused::<T>::kill();
+			NextStep::<T>::kill();
+			Self::deposit_event(Event::<T>::Resumed);
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Increase the validator count at the start of `era` and schedule the next step.
+	fn step(era: EraIndex) {
+		NextStep::<T>::put(era.saturating_add(T::StepEras::get()));
+
+		let max = T::MaxValidatorCount::get();
+		let old = T::Staking::validator_count();
+		let new = old.saturating_add(T::StepSize::get()).min(max);
+		if new > old {
+			T::Staking::set_validator_count(new);
+			Self::deposit_event(Event::<T>::ValidatorCountIncreased { era, old, new });
+		}
+		if new >= max {
+			Paused::<T>::put(true);
+			Self::deposit_event(Event::<T>::ScheduleCompleted);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use crate::validator_count_growth;
+	use frame_support::{assert_noop, assert_ok, parameter_types};
+	use frame_system::EnsureRoot;
+	use sp_core::H256;
+	use sp_runtime::{
+		testing::Header,
+		traits::{BadOrigin, BlakeTwo256, IdentityLookup},
+	};
+
+	type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+	type Block = frame_system::mocking::MockBlock<Test>;
+
+	frame_support::construct_runtime!(
+		pub enum Test where
+			Block = Block,
+			NodeBlock = Block,
+			UncheckedExtrinsic = UncheckedExtrinsic,
+		{
+			System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+			Growth: validator_count_growth::{Pallet, Call, Storage, Event<T>},
+		}
+	);
+
+	parameter_types! {
+		pub const BlockHashCount: u32 = 250;
+	}
+
+	impl frame_system::Config for Test {
+		type BaseCallFilter = frame_support::traits::Everything;
+		type BlockWeights = ();
+		type BlockLength = ();
+		type DbWeight = ();
+		type Origin = Origin;
+		type Call = Call;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<u64>;
+		type Header = Header;
+		type Event = Event;
+		type BlockHashCount = BlockHashCount;
+		type Version = ();
+		type PalletInfo = PalletInfo;
+		type AccountData = ();
+		type OnNewAccount = ();
+		type OnKilledAccount = ();
+		type SystemWeightInfo = ();
+		type SS58Prefix = ();
+		type OnSetCode = ();
+		type MaxConsumers = frame_support::traits::ConstU32<16>;
+	}
+
+	parameter_types! {
+		pub static ActiveEra: Option<EraIndex> = None;
+		pub static ValidatorCount: u32 = 10;
+	}
+
+	pub struct TestStaking;
+	impl ValidatorCountTarget for TestStaking {
+		fn active_era() -> Option<EraIndex> {
+			ActiveEra::get()
+		}
+
+		fn validator_count() -> u32 {
+			ValidatorCount::get()
+		}
+
+		fn set_validator_count(count: u32) {
+			ValidatorCount::set(count)
+		}
+	}
+
+	parameter_types! {
+		pub const StepEras: EraIndex = 2;
+		pub const StepSize: u32 = 5;
+		pub const MaxValidatorCount: u32 = 22;
+	}
+
+	impl Config for Test {
+		type Event = Event;
+		type Staking = TestStaking;
+		type ControlOrigin = EnsureRoot<u64>;
+		type StepEras = StepEras;
+		type StepSize = StepSize;
+		type MaxValidatorCount = MaxValidatorCount;
+	}
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		let mut ext = sp_io::TestExternalities::new(t);
+		ext.execute_with(|| {
+			System::set_block_number(1);
+			ActiveEra::set(None);
+			ValidatorCount::set(10);
+		});
+		ext
+	}
+
+	fn start_era(era: EraIndex) {
+		ActiveEra::set(Some(era));
+		Growth::on_initialize(System::block_number());
+	}
+
+	#[test]
+	fn validator_count_grows_up_to_the_cap() {
+		new_test_ext().execute_with(|| {
+			Growth::on_initialize(1);
+			assert_eq!(NextStep::<Test>::get(), None);
+
+			start_era(0);
+			assert_eq!(NextStep::<Test>::get(), Some(2));
+			start_era(1);
+			assert_eq!(ValidatorCount::get(), 10);
+
+			start_era(2);
+			assert_eq!(ValidatorCount::get(), 15);
+			System::assert_last_event(Event::Growth(
+				validator_count_growth::Event::ValidatorCountIncreased { era: 2, old: 10, new: 15 },
+			));
+
+			start_era(4);
+			assert_eq!(ValidatorCount::get(), 20);
+
+			start_era(6);
+			assert_eq!(ValidatorCount::get(), 22);
+			System::assert_last_event(Event::Growth(
+				validator_count_growth::Event::ScheduleCompleted,
+			));
+			assert!(Paused::<Test>::get());
+
+			start_era(8);
+			assert_eq!(ValidatorCount::get(), 22);
+		});
+	}
+
+	#[test]
+	fn paused_schedule_restarts_when_resumed() {
+		new_test_ext().execute_with(|| {
+			start_era(0);
+
+			assert_noop!(Growth::resume(Origin::root()), Error::<Test>::NotPaused);
+			assert_noop!(Growth::pause(Origin::signed(1)), BadOrigin);
+			assert_ok!(Growth::pause(Origin::root()));
+			assert_noop!(Growth::pause(Origin::root()), Error::<Test>::AlreadyPaused);
+
+			start_era(2);
+			start_era(3);
+			assert_eq!(ValidatorCount::get(), 10);
+
+			assert_ok!(Growth::resume(Origin::root()));
+			start_era(3);
+			assert_eq!(NextStep::<Test>::get(), Some(5));
+			start_era(4);
+			assert_eq!(ValidatorCount::get(), 10);
+			start_era(5);
+			assert_eq!(ValidatorCount::get(), 15);
+		});
+	}
+}
@@ -0,0 +1,90 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Staged disabling of offending validators.
+//!
+//! The offences pallet passes offences through [`StagedDisabling`] on their way to staking, which
+//! disables offenders in stages of increasing severity:
+//!
+//! 1. Every offender of the current session stops backing parachain candidates for the rest of
+//!    the session. Disputes lost by validators have the same effect, through
+//!    [`runtime_parachains::disputes::DisableBackers`].
+//! 2. Offenders disabled by staking, that is slashed ones, also stop authoring blocks, but only
+//!    while less than `AuthoringLimit` of the validators are disabled for authoring. Offenders
+//!    beyond the limit keep authoring, so offences cannot stall block production.
+//! 3. Staking forces a new era once more than its `OffendingValidatorsThreshold` of the validators
+//!    offended.
+//!
+//! Disabled validators keep voting on finality, since GRANDPA does not consult the disabled
+//! validators of the session pallet.
+
+use frame_support::{traits::Get, weights::Weight};
+use pallet_session::historical::IdentificationTuple;
+use primitives::v1::ValidatorIndex;
+use runtime_parachains::shared;
+use sp_runtime::Perbill;
+use sp_staking::{
+	offence::{DisableStrategy, OffenceDetails, OnOffenceHandler},
+	SessionIndex,
+};
+use sp_std::marker::PhantomData;
+
+/// An [`OnOffenceHandler`] disabling the offenders in stages before passing the offences on to
+/// `Inner`, which is staking.
+pub struct StagedDisabling<T, Inner, AuthoringLimit>(PhantomData<(T, Inner, AuthoringLimit)>);
+
+impl<T, Inner, AuthoringLimit, Reporter> OnOffenceHandler<Reporter, IdentificationTuple<T>, Weight>
+	for StagedDisabling<T, Inner, AuthoringLimit>
+where
+	T: pallet_session::historical::Config + shared::Config,
+	Inner: OnOffenceHandler<Reporter, IdentificationTuple<T>, Weight>,
+	AuthoringLimit: Get<Perbill>,
+{
+	fn on_offence(
+		offenders: &[OffenceDetails<Reporter, IdentificationTuple<T>>],
+		slash_fraction: &[Perbill],
+		session: SessionIndex,
+		disable_strategy: DisableStrategy,
+	) -> Weight {
+		let validators = pallet_session::Pallet::<T>::validators();
+		let mut weight = T::DbWeight::get().reads(3);
+
+		if session == pallet_session::Pallet::<T>::current_index() {
+			for details in offenders {
+				if let Some(index) = validators.iter().position(|v| *v == details.offender.0) {
+					let index = ValidatorIndex(index as _);
+					shared::Pallet::<T>::disable_backer_in_validator_set(index);
+					weight = weight.saturating_add(T::DbWeight::get().reads_writes(3, 1));
+				}
+			}
+		}
+
+		let disabled_authors = pallet_session::Pallet::<T>::disabled_validators().len() as u32;
+		let disable_strategy =
+			if disabled_authors >= AuthoringLimit::get() * validators.len() as u32 {
+				DisableStrategy::Never
+			} else {
+				disable_strategy
+			};
+
+		weight.saturating_add(Inner::on_offence(
+			offenders,
+			slash_fraction,
+			session,
+			disable_strategy,
+		))
+	}
+}
@@ -0,0 +1,376 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Pallet tracking the active validator operators as a group of their own.
+//!
+//! Once enabled by governance, every active validator which opted in is an operator, until they
+//! leave the active set or opt out. Changes of the operators are passed on to
+//! `MembershipChanged`, usually a collective of their own, so that a threshold of the operators
+//! running the network can approve parachain emergency measures without having to be elected
+//! individually. The operators are also exposed through `SortedMembers`.
+//!
+//! The set of operators is refreshed at the first block of every session.
+
+use frame_support::{
+	pallet_prelude::*,
+	traits::{ChangeMembers, EnsureOrigin, SortedMembers, ValidatorSet},
+};
+use frame_system::pallet_prelude::*;
+pub use pallet::*;
+use sp_staking::SessionIndex;
+use sp_std::prelude::*;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The active validators.
+		type ValidatorSet: ValidatorSet<Self::AccountId, ValidatorId = Self::AccountId>;
+
+		/// Notified of changes of the operators.
+		type MembershipChanged: ChangeMembers<Self::AccountId>;
+
+		/// The origin which may enable or disable the operators.
+		type ManagerOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The maximum number of operators which may opt in.
+		#[pallet::constant]
+		type MaxOperators: Get<u32>;
+	}
+
+	/// Whether active validators which opted in are operators.
+	#[pallet::storage]
+	#[pallet::getter(fn enabled)]
+	pub type Enabled<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+	/// The sorted accounts which opted in to being operators while validating.
+	#[pallet::storage]
+	#[pallet::getter(fn opted_in)]
+	pub type OptedIn<T: Config> =
+		StorageValue<_, BoundedVec<T::AccountId, T::MaxOperators>, ValueQuery>;
+
+	/// The sorted current operators.
+	#[pallet::storage]
+	#[pallet::getter(fn operators)]
+	pub type Operators<T: Config> =
+		StorageValue<_, BoundedVec<T::AccountId, T::MaxOperators>, ValueQuery>;
+
+	/// The session the operators were last refreshed in.
+	#[pallet::storage]
+	pub type LastSession<T: Config> = StorageValue<_, SessionIndex, ValueQuery>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The operators were enabled or disabled.
+		EnabledSet { enabled: bool },
+		/// A validator opted in.
+		OptedIn { who: T::AccountId },
+		/// An account opted out.
+		OptedOut { who: T::AccountId },
+		/// The operators changed.
+		OperatorsChanged { incoming: Vec<T::AccountId>, outgoing: Vec<T::AccountId> },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// Only active validators may opt in.
+		NotValidator,
+		/// The account has opted in already.
+		AlreadyOptedIn,
+		/// The account has not opted in.
+		NotOptedIn,
+		/// Too many accounts have opted in.
+		TooManyOperators,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(_: BlockNumberFor<T>) -> Weight {
+			let session_index = T::ValidatorSet::session_index();
+			if session_index == LastSession::<T>::get() {
+				return T::DbWeight::get().reads(2)
+			}
+
+			LastSession::<T>::put(session_index);
+			Self::refresh_operators();
+			// Reading the session index and validators, and updating the operators.
+			T::DbWeight::get().reads_writes(5, 2)
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Enable or disable the operators.
+		#[pallet::weight(T::DbWeight::get().reads_writes(4, 2))]
+		pub fn set_enabled(origin: OriginFor<T>, enabled: bool) -> DispatchResult {
+			T::ManagerOrigin::ensure_origin(origin)?;
+
+			Enabled::<T>::put(enabled);
+			Self::refresh_operators();
+			Self::deposit_event(Event::<T>::EnabledSet { enabled });
+			Ok(())
+		}
+
+		/// Opt in to being an operator while the sender is an active validator.
+		///
+		/// Takes effect at the start of the next session.
+		#[pallet::weight(T::DbWeight::get().reads_writes(2, 1))]
+		pub fn opt_in(origin: OriginFor<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(T::ValidatorSet::validators().contains(&who), Error::<T>::NotValidator);
+
+			OptedIn::<T>::try_mutate(|opted_in| -> DispatchResult {
+				let position =
+					opted_in.binary_search(&who).err().ok_or(Error::<T>::AlreadyOptedIn)?;
+				opted_in
+					.try_insert(position, who.clone())
+					.map_err(|_| Error::<T>::TooManyOperators)?;
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::<T>::OptedIn { who });
+			Ok(())
+		}
+
+		/// Opt out of being an operator.
+		///
+		/// The sender stops being an operator immediately.
+		#[pallet::weight(T::DbWeight::get().reads_writes(4, 2))]
+		pub fn opt_out(origin: OriginFor<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			OptedIn::<T>::try_mutate(|opted_in| -> DispatchResult {
+				let position = opted_in.binary_search(&who).map_err(|_| Error::<T>::NotOptedIn)?;
+				opted_in.remove(position);
+				Ok(())
+			})?;
+
+			Self::refresh_operators();
+			Self::deposit_event(Event::<T>::OptedOut { who });
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Recompute the operators from the active validators which opted in.
+	fn refresh_operators() {
+		let new_operators: BoundedVec<_, T::MaxOperators> = if Enabled::<T>::get() {
+			let validators = T::ValidatorSet::validators();
+			let operators: Vec<_> =
+				OptedIn::<T>::get().into_iter().filter(|who| validators.contains(who)).collect();
+			operators.try_into().expect("at most as many as the opted in accounts; qed")
+		} else {
+			BoundedVec::default()
+		};
+
+		let old_operators = Operators::<T>::get();
+		if new_operators == old_operators {
+			return
+		}
+
+		let (incoming, outgoing) =
+			<T::MembershipChanged as ChangeMembers<_>>::compute_members_diff_sorted(
+				&new_operators,
+				&old_operators,
+			);
+		T::MembershipChanged::change_members_sorted(&incoming, &outgoing, &new_operators);
+		Operators::<T>::put(new_operators);
+		Self::deposit_event(Event::<T>::OperatorsChanged { incoming, outgoing });
+	}
+}
+
+/// The current operators, e.g. for use with `EnsureSignedBy`.
+impl<T: Config> SortedMembers<T::AccountId> for Pallet<T> {
+	fn sorted_members() -> Vec<T::AccountId> {
+		Operators::<T>::get().into_inner()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use crate::validator_operators;
+	use frame_support::{assert_noop, assert_ok, parameter_types, traits::OnInitialize};
+	use frame_system::EnsureRoot;
+	use sp_core::H256;
+	use sp_runtime::{
+		testing::Header,
+		traits::{BadOrigin, BlakeTwo256, IdentityLookup},
+	};
+	use std::cell::RefCell;
+
+	type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+	type Block = frame_system::mocking::MockBlock<Test>;
+
+	frame_support::construct_runtime!(
+		pub enum Test where
+			Block = Block,
+			NodeBlock = Block,
+			UncheckedExtrinsic = UncheckedExtrinsic,
+		{
+			System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+			ValidatorOperators: validator_operators::{Pallet, Call, Storage, Event<T>},
+		}
+	);
+
+	parameter_types! {
+		pub const BlockHashCount: u32 = 250;
+	}
+
+	impl frame_system::Config for Test {
+		type BaseCallFilter = frame_support::traits::Everything;
+		type BlockWeights = ();
+		type BlockLength = ();
+		type DbWeight = ();
+		type Origin = Origin;
+		type Call = Call;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<u64>;
+		type Header = Header;
+		type Event = Event;
+		type BlockHashCount = BlockHashCount;
+		type Version = ();
+		type PalletInfo = PalletInfo;
+		type AccountData = ();
+		type OnNewAccount = ();
+		type OnKilledAccount = ();
+		type SystemWeightInfo = ();
+		type SS58Prefix = ();
+		type OnSetCode = ();
+		type MaxConsumers = frame_support::traits::ConstU32<16>;
+	}
+
+	thread_local! {
+		static SESSION: RefCell<(SessionIndex, Vec<u64>)> = RefCell::new((0, vec![1, 2, 3]));
+		static MEMBERS: RefCell<Vec<u64>> = RefCell::new(Vec::new());
+	}
+
+	fn new_session(validators: Vec<u64>) {
+		SESSION.with(|s| {
+			let mut s = s.borrow_mut();
+			*s = (s.0 + 1, validators);
+		});
+		ValidatorOperators::on_initialize(System::block_number());
+	}
+
+	fn members() -> Vec<u64> {
+		MEMBERS.with(|m| m.borrow().clone())
+	}
+
+	pub struct TestValidatorSet;
+	impl ValidatorSet<u64> for TestValidatorSet {
+		type ValidatorId = u64;
+		type ValidatorIdOf = sp_runtime::traits::ConvertInto;
+
+		fn session_index() -> SessionIndex {
+			SESSION.with(|s| s.borrow().0)
+		}
+
+		fn validators() -> Vec<u64> {
+			SESSION.with(|s| s.borrow().1.clone())
+		}
+	}
+
+	pub struct TestCollective;
+	impl ChangeMembers<u64> for TestCollective {
+		fn change_members_sorted(incoming: &[u64], outgoing: &[u64], new: &[u64]) {
+			MEMBERS.with(|m| {
+				let mut m = m.borrow_mut();
+				let mut expected: Vec<_> =
+					m.iter().filter(|x| !outgoing.contains(x)).chain(incoming).copied().collect();
+				expected.sort();
+				assert_eq!(expected, new);
+				*m = new.to_vec();
+			});
+		}
+	}
+
+	parameter_types! {
+		pub const MaxOperators: u32 = 3;
+	}
+
+	impl Config for Test {
+		type Event = Event;
+		type ValidatorSet = TestValidatorSet;
+		type MembershipChanged = TestCollective;
+		type ManagerOrigin = EnsureRoot<u64>;
+		type MaxOperators = MaxOperators;
+	}
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		let mut ext = sp_io::TestExternalities::new(t);
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+
+	#[test]
+	fn opted_in_validators_are_operators_when_enabled() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(
+				ValidatorOperators::opt_in(Origin::signed(4)),
+				Error::<Test>::NotValidator
+			);
+			assert_ok!(ValidatorOperators::opt_in(Origin::signed(2)));
+			assert_ok!(ValidatorOperators::opt_in(Origin::signed(1)));
+			assert_noop!(
+				ValidatorOperators::opt_in(Origin::signed(1)),
+				Error::<Test>::AlreadyOptedIn
+			);
+
+			// Not enabled yet.
+			new_session(vec![1, 2, 3]);
+			assert_eq!(members(), Vec::<u64>::new());
+
+			assert_noop!(ValidatorOperators::set_enabled(Origin::signed(1), true), BadOrigin);
+			assert_ok!(ValidatorOperators::set_enabled(Origin::root(), true));
+			assert_eq!(members(), vec![1, 2]);
+			assert_eq!(ValidatorOperators::sorted_members(), vec![1, 2]);
+
+			// Leaving the active set removes the operator at the next session.
+			new_session(vec![1, 3]);
+			assert_eq!(members(), vec![1]);
+			new_session(vec![1, 2, 3]);
+			assert_eq!(members(), vec![1, 2]);
+
+			// Opting out takes effect immediately.
+			assert_ok!(ValidatorOperators::opt_out(Origin::signed(1)));
+			assert_noop!(ValidatorOperators::opt_out(Origin::signed(1)), Error::<Test>::NotOptedIn);
+			assert_eq!(members(), vec![2]);
+			assert_eq!(ValidatorOperators::sorted_members(), vec![2]);
+
+			assert_ok!(ValidatorOperators::set_enabled(Origin::root(), false));
+			assert_eq!(ValidatorOperators::operators(), Vec::<u64>::new());
+			assert_eq!(members(), Vec::<u64>::new());
+		});
+	}
+}
@@ -22,7 +22,9 @@ use crate::shared;
 use frame_support::{pallet_prelude::*, weights::constants::WEIGHT_PER_MILLIS};
 use frame_system::pallet_prelude::*;
 use parity_scale_codec::{Decode, Encode};
-use primitives::v1::{Balance, SessionIndex, MAX_CODE_SIZE, MAX_HEAD_DATA_SIZE, MAX_POV_SIZE};
+use primitives::v1::{
+	Balance, ExecutorParams, SessionIndex, MAX_CODE_SIZE, MAX_HEAD_DATA_SIZE, MAX_POV_SIZE,
+};
 use sp_runtime::traits::Zero;
 use sp_std::prelude::*;
 
@@ -38,6 +40,11 @@ pub mod migration;
 
 const LOG_TARGET: &str = "runtime::configuration";
 
+/// The maximum number of wasm pages the heap of a PVF can be configured with.
+///
+/// 32-bit wasm can address at most 65536 pages, some of which are needed for the stack and data.
+const MAX_HEAP_PAGES: u32 = 65536 - 32;
+
 /// All configuration of the runtime with respect to parachains and parathreads.
 #[derive(Clone, Encode, Decode, PartialEq, sp_core::RuntimeDebug, scale_info::TypeInfo)]
 #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
@@ -200,11 +207,17 @@ pub struct HostConfiguration<BlockNumber> {
 	/// Must be at least 1.
 	pub no_show_slots: u32,
 	/// The number of delay tranches in total.
+	///
+	/// Must be at least 1.
 	pub n_delay_tranches: u32,
 	/// The width of the zeroth delay tranche for approval assignments. This many delay tranches
 	/// beyond 0 are all consolidated to form a wide 0 tranche.
+	///
+	/// The sum with `n_delay_tranches` must not overflow a `u32`.
 	pub zeroth_delay_tranche_width: u32,
 	/// The number of validators needed to approve a block.
+	///
+	/// Must be at least 1.
 	pub needed_approvals: u32,
 	/// The number of samples to do of the `RelayVRFModulo` approval assignment criterion.
 	pub relay_vrf_modulo_samples: u32,
@@ -239,6 +252,16 @@ pub struct HostConfiguration<BlockNumber> {
 	/// This value should be greater than [`chain_availability_period`] and
 	/// [`thread_availability_period`].
 	pub minimum_validation_upgrade_delay: BlockNumber,
+	/// The parameters of the environment PVFs are prepared and executed in.
+	///
+	/// A change takes effect for the session the configuration gets applied in.
+	pub executor_params: ExecutorParams,
+	/// The maximum number of blocks relay chain finality may lag behind the parent of a block
+	/// before the block stops backing new candidates, or `None` for no limit.
+	///
+	/// Pausing backing lets the outstanding availability and approval work drain during
+	/// incidents instead of growing without bound. If `Some`, must be at least 1.
+	pub max_finality_lag: Option<BlockNumber>,
 }
 
 impl<BlockNumber: Default + From<u32>> Default for HostConfiguration<BlockNumber> {
@@ -263,9 +286,9 @@ impl<BlockNumber: Default + From<u32>> Default for HostConfiguration<BlockNumber
 			dispute_post_conclusion_acceptance_period: 100.into(),
 			dispute_max_spam_slots: 2,
 			dispute_conclusion_by_time_out_period: 200.into(),
-			n_delay_tranches: Default::default(),
+			n_delay_tranches: 1u32.into(),
 			zeroth_delay_tranche_width: Default::default(),
-			needed_approvals: Default::default(),
+			needed_approvals: 1u32.into(),
 			relay_vrf_modulo_samples: Default::default(),
 			max_upward_queue_count: Default::default(),
 			max_upward_queue_size: Default::default(),
@@ -287,6 +310,8 @@ impl<BlockNumber: Default + From<u32>> Default for HostConfiguration<BlockNumber
 			pvf_checking_enabled: false,
 			pvf_voting_ttl: 2u32.into(),
 			minimum_validation_upgrade_delay: 2.into(),
+			executor_params: Default::default(),
+			max_finality_lag: None,
 		}
 	}
 }
@@ -302,6 +327,12 @@ pub enum InconsistentError<BlockNumber> {
 	ZeroThreadAvailabilityPeriod,
 	/// `no_show_slots` is set to zero.
 	ZeroNoShowSlots,
+	/// `n_delay_tranches` is set to zero.
+	ZeroDelayTranches,
+	/// `n_delay_tranches` plus `zeroth_delay_tranche_width` overflows.
+	DelayTranchesOverflow,
+	/// `needed_approvals` is set to zero.
+	ZeroNeededApprovals,
 	/// `max_code_size` exceeds the hard limit of `MAX_CODE_SIZE`.
 	MaxCodeSizeExceedHardLimit { max_code_size: u32 },
 	/// `max_head_data_size` exceeds the hard limit of `MAX_HEAD_DATA_SIZE`.
@@ -326,6 +357,10 @@ pub enum InconsistentError<BlockNumber> {
 	MaxHrmpOutboundChannelsExceeded,
 	/// Maximum number of HRMP inbound channels exceeded.
 	MaxHrmpInboundChannelsExceeded,
+	/// `executor_params` has a zero or out of bounds member.
+	InvalidExecutorParams,
+	/// `max_finality_lag` is set to zero.
+	ZeroMaxFinalityLag,
 }
 
 impl<BlockNumber> HostConfiguration<BlockNumber>
@@ -356,6 +391,18 @@ where
 			return Err(ZeroNoShowSlots)
 		}
 
+		if self.n_delay_tranches.is_zero() {
+			return Err(ZeroDelayTranches)
+		}
+
+		if self.n_delay_tranches.checked_add(self.zeroth_delay_tranche_width).is_none() {
+			return Err(DelayTranchesOverflow)
+		}
+
+		if self.needed_approvals.is_zero() {
+			return Err(ZeroNeededApprovals)
+		}
+
 		if self.max_code_size > MAX_CODE_SIZE {
 			return Err(MaxCodeSizeExceedHardLimit { max_code_size: self.max_code_size })
 		}
@@ -403,6 +450,20 @@ where
 			return Err(MaxHrmpInboundChannelsExceeded)
 		}
 
+		let executor_params = &self.executor_params;
+		if executor_params.heap_pages.is_zero() ||
+			executor_params.heap_pages > MAX_HEAP_PAGES ||
+			executor_params.stack_logical_max.is_zero() ||
+			executor_params.stack_native_max.is_zero() ||
+			executor_params.execution_timeout_percent.is_zero()
+		{
+			return Err(InvalidExecutorParams)
+		}
+
+		if self.max_finality_lag.as_ref().map_or(false, Zero::is_zero) {
+			return Err(ZeroMaxFinalityLag)
+		}
+
 		Ok(())
 	}
 
@@ -1103,6 +1164,36 @@ pub mod pallet {
 			})
 		}
 
+		/// Set the parameters of the environment PVFs are prepared and executed in.
+		#[pallet::weight((
+			// The parameters are of fixed size, so a u32 setter is a good enough approximation.
+			T::WeightInfo::set_config_with_u32(),
+			DispatchClass::Operational,
+		))]
+		pub fn set_executor_params(origin: OriginFor<T>, new: ExecutorParams) -> DispatchResult {
+			ensure_root(origin)?;
+			Self::schedule_config_update(|config| {
+				config.executor_params = new;
+			})
+		}
+
+		/// Set the maximum number of blocks finality may lag behind before new candidates stop
+		/// being backed.
+		#[pallet::weight((
+			// The block number is a `u32` in practice, so this is the matching setter weight.
+			T::WeightInfo::set_config_with_option_u32(),
+			DispatchClass::Operational,
+		))]
+		pub fn set_max_finality_lag(
+			origin: OriginFor<T>,
+			new: Option<T::BlockNumber>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			Self::schedule_config_update(|config| {
+				config.max_finality_lag = new;
+			})
+		}
+
 		/// Setting this to true will disable consistency checks for the configuration setters.
 		/// Use with caution.
 		#[pallet::weight((
@@ -239,6 +239,36 @@ fn invariants() {
 			Configuration::set_no_show_slots(Origin::root(), 0),
 			Error::<Test>::InvalidNewValue
 		);
+		assert_err!(
+			Configuration::set_n_delay_tranches(Origin::root(), 0),
+			Error::<Test>::InvalidNewValue
+		);
+		assert_err!(
+			Configuration::set_zeroth_delay_tranche_width(Origin::root(), u32::MAX),
+			Error::<Test>::InvalidNewValue
+		);
+		assert_err!(
+			Configuration::set_needed_approvals(Origin::root(), 0),
+			Error::<Test>::InvalidNewValue
+		);
+		assert_err!(
+			Configuration::set_max_finality_lag(Origin::root(), Some(0)),
+			Error::<Test>::InvalidNewValue
+		);
+		assert_err!(
+			Configuration::set_executor_params(
+				Origin::root(),
+				ExecutorParams { heap_pages: 0, ..Default::default() },
+			),
+			Error::<Test>::InvalidNewValue
+		);
+		assert_err!(
+			Configuration::set_executor_params(
+				Origin::root(),
+				ExecutorParams { execution_timeout_percent: 0, ..Default::default() },
+			),
+			Error::<Test>::InvalidNewValue
+		);
 
 		<Configuration as Store>::ActiveConfig::put(HostConfiguration {
 			chain_availability_period: 10,
@@ -336,6 +366,13 @@ fn setting_pending_config_members() {
 			pvf_checking_enabled: true,
 			pvf_voting_ttl: 3,
 			minimum_validation_upgrade_delay: 20,
+			executor_params: ExecutorParams {
+				heap_pages: 4096,
+				stack_logical_max: 1024,
+				stack_native_max: 1024 * 1024,
+				execution_timeout_percent: 150,
+			},
+			max_finality_lag: Some(64),
 		};
 
 		assert!(<Configuration as Store>::PendingConfig::get(shared::SESSION_DELAY).is_none());
@@ -500,6 +537,8 @@ fn setting_pending_config_members() {
 		Configuration::set_pvf_checking_enabled(Origin::root(), new_config.pvf_checking_enabled)
 			.unwrap();
 		Configuration::set_pvf_voting_ttl(Origin::root(), new_config.pvf_voting_ttl).unwrap();
+		Configuration::set_executor_params(Origin::root(), new_config.executor_params).unwrap();
+		Configuration::set_max_finality_lag(Origin::root(), new_config.max_finality_lag).unwrap();
 
 		assert_eq!(
 			<Configuration as Store>::PendingConfigs::get(),
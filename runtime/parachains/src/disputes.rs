@@ -16,9 +16,15 @@
 
 //! Runtime component for handling disputes of parachain candidates.
 
-use crate::{configuration, initializer::SessionChangeNotification, session_info};
+use crate::{configuration, initializer::SessionChangeNotification, session_info, shared};
 use bitvec::{bitvec, order::Lsb0 as BitOrderLsb0};
-use frame_support::{ensure, traits::Get, weights::Weight};
+use frame_support::{
+	ensure,
+	storage::{with_transaction, TransactionOutcome},
+	traits::{EnsureOrigin, Get},
+	transactional,
+	weights::Weight,
+};
 use frame_system::pallet_prelude::*;
 use parity_scale_codec::{Decode, Encode};
 use primitives::v1::{
@@ -33,7 +39,7 @@ use sp_runtime::{
 	traits::{AppVerify, One, Saturating, Zero},
 	DispatchError, RuntimeDebug, SaturatedConversion,
 };
-use sp_std::{cmp::Ordering, prelude::*};
+use sp_std::{cmp::Ordering, marker::PhantomData, prelude::*};
 
 #[cfg(test)]
 #[allow(unused_imports)]
@@ -45,6 +51,8 @@ mod tests;
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
 
+const LOG_TARGET: &str = "runtime::disputes";
+
 /// Whether the dispute is local or remote.
 #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
 pub enum DisputeLocation {
@@ -104,6 +112,42 @@ impl PunishValidators for () {
 	fn punish_inconclusive(_: SessionIndex, _: impl IntoIterator<Item = ValidatorIndex>) {}
 }
 
+/// Punishes validators on the losing side of a dispute about a candidate of the current session
+/// by stopping them from backing candidates for the rest of the session.
+///
+/// Validators of inconclusive disputes are not punished, as they may merely have been offline.
+pub struct DisableBackers<T>(PhantomData<T>);
+
+impl<T: shared::Config> DisableBackers<T> {
+	fn disable(session: SessionIndex, validators: impl IntoIterator<Item = ValidatorIndex>) {
+		if session != <shared::Pallet<T>>::session_index() {
+			return
+		}
+
+		for validator in validators {
+			<shared::Pallet<T>>::disable_backer(validator);
+		}
+	}
+}
+
+impl<T: shared::Config> PunishValidators for DisableBackers<T> {
+	fn punish_for_invalid(
+		session: SessionIndex,
+		validators: impl IntoIterator<Item = ValidatorIndex>,
+	) {
+		Self::disable(session, validators)
+	}
+
+	fn punish_against_valid(
+		session: SessionIndex,
+		validators: impl IntoIterator<Item = ValidatorIndex>,
+	) {
+		Self::disable(session, validators)
+	}
+
+	fn punish_inconclusive(_: SessionIndex, _: impl IntoIterator<Item = ValidatorIndex>) {}
+}
+
 /// Binary discriminator to determine if the expensive signature
 /// checks are necessary.
 #[derive(Clone, Copy)]
@@ -392,6 +436,7 @@ where
 
 pub trait WeightInfo {
 	fn force_unfreeze() -> Weight;
+	fn include_dispute_statement_set(v: u32) -> Weight;
 }
 
 pub struct TestWeightInfo;
@@ -399,6 +444,9 @@ impl WeightInfo for TestWeightInfo {
 	fn force_unfreeze() -> Weight {
 		0
 	}
+	fn include_dispute_statement_set(_v: u32) -> Weight {
+		0
+	}
 }
 
 pub use pallet::*;
@@ -408,11 +456,22 @@ pub mod pallet {
 	use frame_support::pallet_prelude::*;
 
 	#[pallet::config]
-	pub trait Config: frame_system::Config + configuration::Config + session_info::Config {
+	pub trait Config:
+		frame_system::Config
+		+ configuration::Config
+		+ session_info::Config
+		+ frame_system::offchain::SendTransactionTypes<Call<Self>>
+	{
 		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
 		type RewardValidators: RewardValidators;
 		type PunishValidators: PunishValidators;
 
+		/// The priority of dispute statements submitted as unsigned transactions.
+		type UnsignedPriority: Get<TransactionPriority>;
+
+		/// The origin which may unfreeze the chain after a dispute concluded against a candidate.
+		type ForceOrigin: EnsureOrigin<<Self as frame_system::Config>::Origin>;
+
 		/// Weight information for extrinsics in this pallet.
 		type WeightInfo: WeightInfo;
 	}
@@ -499,19 +558,145 @@ pub mod pallet {
 		PotentialSpam,
 		/// A dispute where there are only votes on one side.
 		SingleSidedDispute,
+		/// A dispute statement set submitted as a transaction contains no new statements.
+		UnusableDisputeStatementSet,
 	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 		#[pallet::weight(<T as Config>::WeightInfo::force_unfreeze())]
 		pub fn force_unfreeze(origin: OriginFor<T>) -> DispatchResult {
-			ensure_root(origin)?;
+			T::ForceOrigin::ensure_origin(origin)?;
 			Frozen::<T>::set(None);
 			Ok(())
 		}
+
+		/// Includes a set of statements on a dispute which was submitted by a validator
+		/// as a transaction rather than included in the parachains inherent.
+		///
+		/// Statements already known to the chain are ignored. Fails if none are left.
+		#[pallet::weight(
+			<T as Config>::WeightInfo::include_dispute_statement_set(set.statements.len() as u32)
+		)]
+		#[transactional]
+		pub fn include_dispute_statement_set(
+			origin: OriginFor<T>,
+			set: DisputeStatementSet,
+		) -> DispatchResult {
+			ensure_none(origin)?;
+
+			let config = <configuration::Pallet<T>>::config();
+			let checked = Self::filter_dispute_data(
+				&set,
+				config.dispute_post_conclusion_acceptance_period,
+				config.dispute_max_spam_slots,
+				VerifyDisputeSignatures::Yes,
+			)
+			.filter_statement_set(set)
+			.ok_or(Error::<T>::UnusableDisputeStatementSet)?;
+
+			Self::process_checked_multi_dispute_data(vec![checked])?;
+			Ok(())
+		}
+	}
+
+	#[pallet::validate_unsigned]
+	impl<T: Config> ValidateUnsigned for Pallet<T> {
+		type Call = Call<T>;
+
+		fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+			let set = match call {
+				Call::include_dispute_statement_set { set } => set,
+				_ => return InvalidTransaction::Call.into(),
+			};
+
+			if set.statements.is_empty() {
+				return InvalidTransaction::Custom(INVALID_TX_NO_STATEMENTS).into()
+			}
+
+			let session_info = match <session_info::Pallet<T>>::session_info(set.session) {
+				Some(s) => s,
+				None => return InvalidTransaction::Stale.into(),
+			};
+
+			// Dispute statement sets on any dispute which concluded
+			// before this point are to be rejected.
+			let config = <configuration::Pallet<T>>::config();
+			let now = <frame_system::Pallet<T>>::block_number();
+			let oldest_accepted =
+				now.saturating_sub(config.dispute_post_conclusion_acceptance_period);
+			if let Some(dispute) = <Disputes<T>>::get(&set.session, &set.candidate_hash) {
+				if dispute.concluded_at.as_ref().map_or(false, |c| c < &oldest_accepted) {
+					return InvalidTransaction::Stale.into()
+				}
+			}
+
+			for (statement, validator_index, signature) in &set.statements {
+				let validator_public = match session_info.validators.get(validator_index.0 as usize)
+				{
+					Some(pk) => pk,
+					None => return InvalidTransaction::Custom(INVALID_TX_BAD_VALIDATOR_IDX).into(),
+				};
+
+				if check_signature(
+					validator_public,
+					set.candidate_hash,
+					set.session,
+					statement,
+					signature,
+				)
+				.is_err()
+				{
+					return InvalidTransaction::BadProof.into()
+				}
+			}
+
+			// Signatures were checked above, the filter only has to drop known statements.
+			let new_statements =
+				match Self::new_dispute_statements(set, VerifyDisputeSignatures::Skip) {
+					Some(new_statements) => new_statements,
+					None => return InvalidTransaction::Custom(INVALID_TX_NO_NEW_STATEMENTS).into(),
+				};
+
+			// Every statement is a tag of its own, so that a statement gossiped in several sets
+			// is only included once.
+			let mut tx = ValidTransaction::with_tag_prefix("DisputeStatement")
+				.priority(T::UnsignedPriority::get())
+				.longevity(
+					config.dispute_conclusion_by_time_out_period.saturated_into::<u64>().max(1),
+				)
+				.propagate(true);
+			for (statement, validator_index, _) in &new_statements.as_ref().statements {
+				tx = tx.and_provides((
+					set.session,
+					set.candidate_hash,
+					validator_index,
+					statement.indicates_validity(),
+				));
+			}
+
+			tx.build()
+		}
+
+		fn pre_dispatch(call: &Self::Call) -> Result<(), TransactionValidityError> {
+			// Sets without new statements would fail when dispatched, and be included for free.
+			// Signatures are verified when dispatched.
+			match call {
+				Call::include_dispute_statement_set { set } =>
+					Self::new_dispute_statements(set, VerifyDisputeSignatures::Skip)
+						.map(|_| ())
+						.ok_or(InvalidTransaction::Custom(INVALID_TX_NO_NEW_STATEMENTS).into()),
+				_ => Err(InvalidTransaction::Call.into()),
+			}
+		}
 	}
 }
 
+// custom transaction error codes
+const INVALID_TX_BAD_VALIDATOR_IDX: u8 = 1;
+const INVALID_TX_NO_STATEMENTS: u8 = 2;
+const INVALID_TX_NO_NEW_STATEMENTS: u8 = 3;
+
 bitflags::bitflags! {
 	#[derive(Default)]
 	struct DisputeStateFlags: u8 {
@@ -894,6 +1079,39 @@ impl<T: Config> Pallet<T> {
 		Ok(fresh)
 	}
 
+	/// Submits a set of dispute statements as an unsigned transaction into the memory pool.
+	///
+	/// This function expects an offchain context and cannot be callable from the on-chain logic.
+	pub(crate) fn submit_dispute_statement_set(set: DisputeStatementSet) {
+		use frame_system::offchain::SubmitTransaction;
+
+		if let Err(e) = SubmitTransaction::<T, Call<T>>::submit_unsigned_transaction(
+			Call::include_dispute_statement_set { set }.into(),
+		) {
+			log::error!(target: LOG_TARGET, "Error submitting dispute statement set: {:?}", e);
+		}
+	}
+
+	// The statements of `set` which are not known to the chain yet, if any.
+	//
+	// Unlike `filter_dispute_data`, this leaves the spam slots untouched, so that it can be used
+	// to validate a transaction before dispatching it.
+	fn new_dispute_statements(
+		set: &DisputeStatementSet,
+		verify_sigs: VerifyDisputeSignatures,
+	) -> Option<CheckedDisputeStatementSet> {
+		let config = <configuration::Pallet<T>>::config();
+		with_transaction(|| {
+			let filter = Self::filter_dispute_data(
+				set,
+				config.dispute_post_conclusion_acceptance_period,
+				config.dispute_max_spam_slots,
+				verify_sigs,
+			);
+			TransactionOutcome::Rollback(filter.filter_statement_set(set.clone()))
+		})
+	}
+
 	// Given a statement set, this produces a filter to be applied to the statement set.
 	// It either removes the entire dispute statement set or some specific votes from it.
 	//
@@ -17,13 +17,13 @@
 use super::*;
 
 use frame_benchmarking::benchmarks;
-use frame_system::RawOrigin;
 use sp_runtime::traits::One;
 
 benchmarks! {
 	force_unfreeze {
 		Frozen::<T>::set(Some(One::one()));
-	}: _(RawOrigin::Root)
+		let origin = T::ForceOrigin::successful_origin();
+	}: _<T::Origin>(origin)
 	verify {
 		assert!(Frozen::<T>::get().is_none())
 	}
@@ -27,9 +27,11 @@ use crate::{
 use frame_support::{
 	assert_err, assert_noop, assert_ok,
 	traits::{OnFinalize, OnInitialize},
+	unsigned::ValidateUnsigned,
 };
 use primitives::v1::BlockNumber;
 use sp_core::{crypto::CryptoType, Pair};
+use sp_runtime::transaction_validity::{InvalidTransaction, TransactionSource};
 
 /// Filtering updates the spam slots, as such update them.
 fn update_spam_slots(stmts: MultiDisputeStatementSet) -> CheckedMultiDisputeStatementSet {
@@ -2451,3 +2453,93 @@ fn import_ignores_single_sided() {
 		assert!(statements.is_empty());
 	})
 }
+
+#[test]
+fn include_dispute_statement_set_validates_and_imports() {
+	new_test_ext(Default::default()).execute_with(|| {
+		let v0 = <ValidatorId as CryptoType>::Pair::generate().0;
+		let v1 = <ValidatorId as CryptoType>::Pair::generate().0;
+
+		run_to_block(3, |b| {
+			// a new session at each block
+			Some((
+				true,
+				b,
+				vec![(&0, v0.public()), (&1, v1.public())],
+				Some(vec![(&0, v0.public()), (&1, v1.public())]),
+			))
+		});
+
+		let candidate_hash = CandidateHash(sp_core::H256::repeat_byte(1));
+		let payload = |valid| {
+			ExplicitDisputeStatement { valid, candidate_hash, session: 1 }.signing_payload()
+		};
+
+		let set = DisputeStatementSet {
+			candidate_hash,
+			session: 1,
+			statements: vec![
+				(
+					DisputeStatement::Valid(ValidDisputeStatementKind::Explicit),
+					ValidatorIndex(0),
+					v0.sign(&payload(true)),
+				),
+				(
+					DisputeStatement::Invalid(InvalidDisputeStatementKind::Explicit),
+					ValidatorIndex(1),
+					v1.sign(&payload(false)),
+				),
+			],
+		};
+
+		let validity = Pallet::<Test>::validate_unsigned(
+			TransactionSource::External,
+			&Call::include_dispute_statement_set { set: set.clone() },
+		)
+		.unwrap();
+		assert_eq!(validity.provides.len(), 2);
+		assert_ok!(Pallet::<Test>::pre_dispatch(&Call::include_dispute_statement_set {
+			set: set.clone()
+		}));
+		// Validation leaves the spam slots untouched.
+		assert_eq!(SpamSlots::<Test>::get(1), None);
+
+		// A statement signed by another validator is rejected.
+		let mut forged = set.clone();
+		forged.statements[1].2 = v0.sign(&payload(false));
+		assert_eq!(
+			Pallet::<Test>::validate_unsigned(
+				TransactionSource::External,
+				&Call::include_dispute_statement_set { set: forged },
+			),
+			Err(InvalidTransaction::BadProof.into()),
+		);
+
+		assert_ok!(Pallet::<Test>::include_dispute_statement_set(
+			frame_system::RawOrigin::None.into(),
+			set.clone(),
+		));
+		assert!(Disputes::<Test>::get(1, candidate_hash).is_some());
+
+		// Statements known to the chain are not imported twice.
+		let no_new_statements = InvalidTransaction::Custom(INVALID_TX_NO_NEW_STATEMENTS);
+		assert_eq!(
+			Pallet::<Test>::validate_unsigned(
+				TransactionSource::External,
+				&Call::include_dispute_statement_set { set: set.clone() },
+			),
+			Err(no_new_statements.into()),
+		);
+		assert_eq!(
+			Pallet::<Test>::pre_dispatch(&Call::include_dispute_statement_set { set: set.clone() }),
+			Err(no_new_statements.into()),
+		);
+		assert_noop!(
+			Pallet::<Test>::include_dispute_statement_set(
+				frame_system::RawOrigin::None.into(),
+				set,
+			),
+			Error::<Test>::UnusableDisputeStatementSet,
+		);
+	})
+}
@@ -18,7 +18,7 @@ use crate::{
 	configuration::{self, HostConfiguration},
 	dmp, ensure_parachain, initializer, paras,
 };
-use frame_support::{pallet_prelude::*, traits::ReservableCurrency};
+use frame_support::{pallet_prelude::*, traits::NamedReservableCurrency};
 use frame_system::pallet_prelude::*;
 use parity_scale_codec::{Decode, Encode};
 use primitives::v1::{
@@ -26,7 +26,9 @@ use primitives::v1::{
 	SessionIndex,
 };
 use scale_info::TypeInfo;
-use sp_runtime::traits::{AccountIdConversion, BlakeTwo256, Hash as HashT, UniqueSaturatedInto};
+use sp_runtime::traits::{
+	AccountIdConversion, BlakeTwo256, Hash as HashT, Saturating, UniqueSaturatedInto,
+};
 use sp_std::{
 	collections::{btree_map::BTreeMap, btree_set::BTreeSet},
 	fmt, mem,
@@ -43,12 +45,17 @@ pub const HRMP_MAX_INBOUND_CHANNELS_BOUND: u32 = 128;
 /// Same as [`HRMP_MAX_INBOUND_CHANNELS_BOUND`], but for outbound channels.
 pub const HRMP_MAX_OUTBOUND_CHANNELS_BOUND: u32 = 128;
 
+pub mod migration;
+
 #[cfg(test)]
 pub(crate) mod tests;
 
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
 
+/// The name under which the deposits for HRMP channels are reserved.
+pub const HRMP_DEPOSIT_ID: [u8; 8] = *b"hrmpdepo";
+
 pub trait WeightInfo {
 	fn hrmp_init_open_channel() -> Weight;
 	fn hrmp_accept_open_channel() -> Weight;
@@ -106,6 +113,8 @@ pub struct HrmpOpenChannelRequest {
 	pub max_capacity: u32,
 	/// The maximum total size of the messages that can be pending in the channel at once.
 	pub max_total_size: u32,
+	/// The amount that the recipient supplied when confirming this request, zero until then.
+	pub recipient_deposit: Balance,
 }
 
 /// A metadata of an HRMP channel.
@@ -231,6 +240,7 @@ pub mod pallet {
 
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(migration::STORAGE_VERSION)]
 	#[pallet::without_storage_info]
 	pub struct Pallet<T>(_);
 
@@ -250,7 +260,9 @@ pub mod pallet {
 		/// NOTE that this Currency instance will be charged with the amounts defined in the
 		/// `Configuration` pallet. Specifically, that means that the `Balance` of the `Currency`
 		/// implementation should be the same as `Balance` as used in the `Configuration`.
-		type Currency: ReservableCurrency<Self::AccountId>;
+		///
+		/// Deposits are reserved under the name [`HRMP_DEPOSIT_ID`].
+		type Currency: NamedReservableCurrency<Self::AccountId, ReserveIdentifier = [u8; 8]>;
 
 		/// Something that provides the weight of this pallet.
 		type WeightInfo: WeightInfo;
@@ -269,6 +281,10 @@ pub mod pallet {
 		OpenChannelAccepted(ParaId, ParaId),
 		/// HRMP channel closed. `[by_parachain, channel_id]`
 		ChannelClosed(ParaId, HrmpChannelId),
+		/// A deposit for an HRMP channel or channel request was returned. `[para, amount]`
+		DepositRefunded(ParaId, Balance),
+		/// The remaining HRMP metadata of an offboarded para was removed. `[para]`
+		OffboardedParaCleaned(ParaId),
 	}
 
 	#[pallet::error]
@@ -414,6 +430,12 @@ pub mod pallet {
 	pub type HrmpChannelDigests<T: Config> =
 		StorageMap<_, Twox64Concat, ParaId, Vec<(T::BlockNumber, Vec<ParaId>)>, ValueQuery>;
 
+	/// Offboarded paras which may still have an HRMP watermark or channel digests.
+	///
+	/// These are removed lazily, one para per block.
+	#[pallet::storage]
+	pub type HrmpOffboardedParas<T: Config> = StorageValue<_, Vec<ParaId>, ValueQuery>;
+
 	/// Preopen the given HRMP channels.
 	///
 	/// The values in the tuple corresponds to
@@ -446,6 +468,13 @@ pub mod pallet {
 		}
 	}
 
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_runtime_upgrade() -> Weight {
+			migration::migrate_to_latest::<T>()
+		}
+	}
+
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 		/// Initiate opening a channel from a parachain to a given recipient with given channel
@@ -535,8 +564,7 @@ pub mod pallet {
 		#[pallet::weight(<T as Config>::WeightInfo::force_process_hrmp_open(*_channels))]
 		pub fn force_process_hrmp_open(origin: OriginFor<T>, _channels: u32) -> DispatchResult {
 			ensure_root(origin)?;
-			let host_config = configuration::Pallet::<T>::config();
-			Self::process_hrmp_open_channel_requests(&host_config);
+			Self::process_hrmp_open_channel_requests();
 			Ok(())
 		}
 
@@ -582,7 +610,6 @@ pub mod pallet {
 
 #[cfg(feature = "std")]
 fn initialize_storage<T: Config>(preopen_hrmp_channels: &[(ParaId, ParaId, u32, u32)]) {
-	let host_config = configuration::Pallet::<T>::config();
 	for &(sender, recipient, max_capacity, max_message_size) in preopen_hrmp_channels {
 		if let Err(err) =
 			preopen_hrmp_channel::<T>(sender, recipient, max_capacity, max_message_size)
@@ -590,7 +617,7 @@ fn initialize_storage<T: Config>(preopen_hrmp_channels: &[(ParaId, ParaId, u32,
 			panic!("failed to initialize the genesis storage: {:?}", err);
 		}
 	}
-	<Pallet<T>>::process_hrmp_open_channel_requests(&host_config);
+	<Pallet<T>>::process_hrmp_open_channel_requests();
 }
 
 #[cfg(feature = "std")]
@@ -609,7 +636,7 @@ fn preopen_hrmp_channel<T: Config>(
 impl<T: Config> Pallet<T> {
 	/// Block initialization logic, called by initializer.
 	pub(crate) fn initializer_initialize(_now: T::BlockNumber) -> Weight {
-		0
+		Self::clean_offboarded_para()
 	}
 
 	/// Block finalization logic, called by initializer.
@@ -617,11 +644,11 @@ impl<T: Config> Pallet<T> {
 
 	/// Called by the initializer to note that a new session has started.
 	pub(crate) fn initializer_on_new_session(
-		notification: &initializer::SessionChangeNotification<T::BlockNumber>,
+		_notification: &initializer::SessionChangeNotification<T::BlockNumber>,
 		outgoing_paras: &[ParaId],
 	) -> Weight {
-		let w1 = Self::perform_outgoing_para_cleanup(&notification.prev_config, outgoing_paras);
-		Self::process_hrmp_open_channel_requests(&notification.prev_config);
+		let w1 = Self::perform_outgoing_para_cleanup(outgoing_paras);
+		Self::process_hrmp_open_channel_requests();
 		Self::process_hrmp_close_channel_requests();
 		w1.saturating_add(<T as Config>::WeightInfo::force_process_hrmp_open(
 			outgoing_paras.len() as u32
@@ -633,11 +660,12 @@ impl<T: Config> Pallet<T> {
 
 	/// Iterate over all paras that were noted for offboarding and remove all the data
 	/// associated with them.
-	fn perform_outgoing_para_cleanup(
-		config: &HostConfiguration<T::BlockNumber>,
-		outgoing: &[ParaId],
-	) -> Weight {
-		let mut w = Self::clean_open_channel_requests(config, outgoing);
+	fn perform_outgoing_para_cleanup(outgoing: &[ParaId]) -> Weight {
+		let mut w = Self::clean_open_channel_requests(outgoing);
+		if !outgoing.is_empty() {
+			<Self as Store>::HrmpOffboardedParas::mutate(|v| v.extend_from_slice(outgoing));
+			w = w.saturating_add(T::DbWeight::get().writes(1));
+		}
 		for outgoing_para in outgoing {
 			Self::clean_hrmp_after_outgoing(outgoing_para);
 
@@ -658,10 +686,7 @@ impl<T: Config> Pallet<T> {
 	// Go over the HRMP open channel requests and remove all in which offboarding paras participate.
 	//
 	// This will also perform the refunds for the counterparty if it doesn't offboard.
-	pub(crate) fn clean_open_channel_requests(
-		config: &HostConfiguration<T::BlockNumber>,
-		outgoing: &[ParaId],
-	) -> Weight {
+	pub(crate) fn clean_open_channel_requests(outgoing: &[ParaId]) -> Weight {
 		// First collect all the channel ids of the open requests in which there is at least one
 		// party presents in the outgoing list.
 		//
@@ -686,23 +711,14 @@ impl<T: Config> Pallet<T> {
 
 			// Return the deposit of the sender, but only if it is not the para being offboarded.
 			if !outgoing.contains(&req_id.sender) {
-				T::Currency::unreserve(
-					&req_id.sender.into_account(),
-					req_data.sender_deposit.unique_saturated_into(),
-				);
+				Self::refund_deposit(req_id.sender, req_data.sender_deposit);
 			}
 
-			// If the request was confirmed, then it means it was confirmed in the finished session.
-			// Therefore, the config's hrmp_recipient_deposit represents the actual value of the
-			// deposit.
-			//
-			// We still want to refund the deposit only if the para is not being offboarded.
+			// If the request was confirmed, return the deposit of the recipient, again only if it is
+			// not the para being offboarded.
 			if req_data.confirmed {
 				if !outgoing.contains(&req_id.recipient) {
-					T::Currency::unreserve(
-						&req_id.recipient.into_account(),
-						config.hrmp_recipient_deposit.unique_saturated_into(),
-					);
+					Self::refund_deposit(req_id.recipient, req_data.recipient_deposit);
 				}
 				Self::decrease_accepted_channel_request_count(req_id.recipient);
 			}
@@ -735,7 +751,7 @@ impl<T: Config> Pallet<T> {
 	///
 	/// - prune the stale requests
 	/// - enact the confirmed requests
-	fn process_hrmp_open_channel_requests(config: &HostConfiguration<T::BlockNumber>) {
+	fn process_hrmp_open_channel_requests() {
 		let mut open_req_channels = <Self as Store>::HrmpOpenChannelRequestsList::get();
 		if open_req_channels.is_empty() {
 			return
@@ -764,7 +780,7 @@ impl<T: Config> Pallet<T> {
 						&channel_id,
 						HrmpChannel {
 							sender_deposit: request.sender_deposit,
-							recipient_deposit: config.hrmp_recipient_deposit,
+							recipient_deposit: request.recipient_deposit,
 							max_capacity: request.max_capacity,
 							max_total_size: request.max_total_size,
 							max_message_size: request.max_message_size,
@@ -816,14 +832,8 @@ impl<T: Config> Pallet<T> {
 		if let Some(HrmpChannel { sender_deposit, recipient_deposit, .. }) =
 			<Self as Store>::HrmpChannels::take(channel_id)
 		{
-			T::Currency::unreserve(
-				&channel_id.sender.into_account(),
-				sender_deposit.unique_saturated_into(),
-			);
-			T::Currency::unreserve(
-				&channel_id.recipient.into_account(),
-				recipient_deposit.unique_saturated_into(),
-			);
+			Self::refund_deposit(channel_id.sender, sender_deposit);
+			Self::refund_deposit(channel_id.recipient, recipient_deposit);
 		}
 
 		<Self as Store>::HrmpChannelContents::remove(channel_id);
@@ -840,6 +850,39 @@ impl<T: Config> Pallet<T> {
 		});
 	}
 
+	/// Return the given deposit of a para, noting the amount which was actually returned in an
+	/// event.
+	fn refund_deposit(para: ParaId, deposit: Balance) {
+		let deposit = deposit.unique_saturated_into();
+		let missing = T::Currency::unreserve_named(&HRMP_DEPOSIT_ID, &para.into_account(), deposit);
+		let refunded: Balance = deposit.saturating_sub(missing).unique_saturated_into();
+		if refunded > 0 {
+			Self::deposit_event(Event::DepositRefunded(para, refunded));
+		}
+	}
+
+	/// Remove the HRMP watermark and the channel digests left behind by one offboarded para.
+	///
+	/// These are not needed to close the channels of the para, so they are removed after its
+	/// offboarding, a single para per block, to keep the session change light.
+	fn clean_offboarded_para() -> Weight {
+		let mut offboarded = <Self as Store>::HrmpOffboardedParas::get();
+		let para = match offboarded.pop() {
+			Some(para) => para,
+			None => return T::DbWeight::get().reads(1),
+		};
+		<Self as Store>::HrmpOffboardedParas::put(offboarded);
+
+		// The para could have been registered again in the meantime.
+		if !<paras::Pallet<T>>::is_valid_para(para) {
+			<Self as Store>::HrmpWatermarks::remove(&para);
+			<Self as Store>::HrmpChannelDigests::remove(&para);
+			Self::deposit_event(Event::OffboardedParaCleaned(para));
+		}
+
+		T::DbWeight::get().reads_writes(2, 4)
+	}
+
 	/// Check that the candidate of the given recipient controls the HRMP watermark properly.
 	pub(crate) fn check_hrmp_watermark(
 		recipient: ParaId,
@@ -1136,7 +1179,8 @@ impl<T: Config> Pallet<T> {
 			Error::<T>::OpenHrmpChannelLimitExceeded,
 		);
 
-		T::Currency::reserve(
+		T::Currency::reserve_named(
+			&HRMP_DEPOSIT_ID,
 			&origin.into_account(),
 			config.hrmp_sender_deposit.unique_saturated_into(),
 		)?;
@@ -1153,6 +1197,7 @@ impl<T: Config> Pallet<T> {
 				max_capacity: proposed_max_capacity,
 				max_message_size: proposed_max_message_size,
 				max_total_size: config.hrmp_channel_max_total_size,
+				recipient_deposit: 0,
 			},
 		);
 		<Self as Store>::HrmpOpenChannelRequestsList::append(channel_id);
@@ -1209,7 +1254,8 @@ impl<T: Config> Pallet<T> {
 			Error::<T>::AcceptHrmpChannelLimitExceeded,
 		);
 
-		T::Currency::reserve(
+		T::Currency::reserve_named(
+			&HRMP_DEPOSIT_ID,
 			&origin.into_account(),
 			config.hrmp_recipient_deposit.unique_saturated_into(),
 		)?;
@@ -1217,6 +1263,7 @@ impl<T: Config> Pallet<T> {
 		// persist the updated open channel request and then increment the number of accepted
 		// channels.
 		channel_req.confirmed = true;
+		channel_req.recipient_deposit = config.hrmp_recipient_deposit;
 		<Self as Store>::HrmpOpenChannelRequests::insert(&channel_id, channel_req);
 		<Self as Store>::HrmpAcceptedChannelRequestCount::insert(&origin, accepted_cnt + 1);
 
@@ -1263,10 +1310,7 @@ impl<T: Config> Pallet<T> {
 
 		// Unreserve the sender's deposit. The recipient could not have left their deposit because
 		// we ensured that the request is not confirmed.
-		T::Currency::unreserve(
-			&channel_id.sender.into_account(),
-			open_channel_req.sender_deposit.unique_saturated_into(),
-		);
+		Self::refund_deposit(channel_id.sender, open_channel_req.sender_deposit);
 
 		Ok(())
 	}
@@ -1458,9 +1502,14 @@ impl<T: Config> Pallet<T> {
 		);
 
 		// A HRMP watermark can be None for an onboarded parachain. However, an offboarded parachain
-		// cannot have an HRMP watermark: it should've been cleanup.
+		// cannot have an HRMP watermark: it should've been cleanup, unless it is yet to be cleaned
+		// up lazily.
+		let offboarded = <Self as Store>::HrmpOffboardedParas::get();
 		assert_contains_only_onboarded(
-			<Self as Store>::HrmpWatermarks::iter().map(|(k, _)| k).collect::<Vec<_>>(),
+			<Self as Store>::HrmpWatermarks::iter()
+				.map(|(k, _)| k)
+				.filter(|k| !offboarded.contains(k))
+				.collect::<Vec<_>>(),
 			"HRMP watermarks should contain only onboarded paras",
 		);
 
@@ -1522,7 +1571,10 @@ impl<T: Config> Pallet<T> {
 			.for_each(|v| assert_is_sorted(&v, "HrmpIngressChannelsIndex"));
 
 		assert_contains_only_onboarded(
-			<Self as Store>::HrmpChannelDigests::iter().map(|(k, _)| k).collect::<Vec<_>>(),
+			<Self as Store>::HrmpChannelDigests::iter()
+				.map(|(k, _)| k)
+				.filter(|k| !offboarded.contains(k))
+				.collect::<Vec<_>>(),
 			"HRMP channel digests should contain only onboarded paras",
 		);
 		for (_digest_for_para, digest) in <Self as Store>::HrmpChannelDigests::iter() {
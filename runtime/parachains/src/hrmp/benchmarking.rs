@@ -105,7 +105,7 @@ where
 		return output
 	}
 
-	Hrmp::<T>::process_hrmp_open_channel_requests(&Configuration::<T>::config());
+	Hrmp::<T>::process_hrmp_open_channel_requests();
 	if matches!(until, ParachainSetupStep::Established) {
 		return output
 	}
@@ -290,9 +290,8 @@ frame_benchmarking::benchmarks! {
 
 		assert_eq!(HrmpOpenChannelRequestsList::<T>::decode_len().unwrap_or_default() as u32, c);
 		let outgoing = (0..c).map(|id| (id + PREFIX_1).into()).collect::<Vec<ParaId>>();
-		let config = Configuration::<T>::config();
 	}: {
-		Hrmp::<T>::clean_open_channel_requests(&config, &outgoing);
+		Hrmp::<T>::clean_open_channel_requests(&outgoing);
 	} verify {
 		assert_eq!(HrmpOpenChannelRequestsList::<T>::decode_len().unwrap_or_default() as u32, 0);
 	}
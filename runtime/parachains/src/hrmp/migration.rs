@@ -0,0 +1,109 @@
+// Copyright 2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A module that is responsible for migration of storage.
+
+use crate::{
+	configuration,
+	hrmp::{Config, HrmpOpenChannelRequest, Pallet, Store, HRMP_DEPOSIT_ID},
+};
+use frame_support::{
+	pallet_prelude::*,
+	traits::{NamedReservableCurrency, ReservableCurrency, StorageVersion},
+	weights::Weight,
+};
+use primitives::v1::{Balance, Id as ParaId, SessionIndex};
+use sp_runtime::traits::{AccountIdConversion, Saturating, UniqueSaturatedInto};
+
+/// The current storage version.
+pub const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
+/// Migrates the pallet storage to the most recent version, checking and setting the `StorageVersion`.
+pub fn migrate_to_latest<T: Config>() -> Weight {
+	let mut weight = 0;
+	if StorageVersion::get::<Pallet<T>>() < 1 {
+		weight += migrate_to_v1::<T>();
+		StorageVersion::new(1).put::<Pallet<T>>();
+	}
+	weight
+}
+
+/// The layout of [`HrmpOpenChannelRequest`] before the recipient deposit was recorded.
+#[derive(Encode, Decode)]
+pub(super) struct HrmpOpenChannelRequestV0 {
+	pub(super) confirmed: bool,
+	pub(super) _age: SessionIndex,
+	pub(super) sender_deposit: Balance,
+	pub(super) max_message_size: u32,
+	pub(super) max_capacity: u32,
+	pub(super) max_total_size: u32,
+}
+
+/// Record the deposit of the recipient in open channel requests, and move the deposits of open
+/// channels and open channel requests to the named reserve [`HRMP_DEPOSIT_ID`].
+pub fn migrate_to_v1<T: Config>() -> Weight {
+	// A request is confirmed and enacted within a single session, so the recipient of a confirmed
+	// request reserved the deposit of the active configuration.
+	let recipient_deposit = <configuration::Pallet<T>>::config().hrmp_recipient_deposit;
+	let mut requests = 0;
+	<Pallet<T> as Store>::HrmpOpenChannelRequests::translate::<HrmpOpenChannelRequestV0, _>(
+		|_, request| {
+			requests += 1;
+			Some(HrmpOpenChannelRequest {
+				confirmed: request.confirmed,
+				_age: request._age,
+				sender_deposit: request.sender_deposit,
+				max_message_size: request.max_message_size,
+				max_capacity: request.max_capacity,
+				max_total_size: request.max_total_size,
+				recipient_deposit: if request.confirmed { recipient_deposit } else { 0 },
+			})
+		},
+	);
+
+	let mut deposits = 0;
+	let mut rename = |para: ParaId, deposit: Balance| {
+		let who: T::AccountId = para.into_account();
+		let deposit = deposit.unique_saturated_into();
+		let deposit = deposit.saturating_sub(T::Currency::unreserve(&who, deposit));
+		// Only fails if the account has too many named reserves, in which case the deposit is
+		// reserved without a name again, as it was before the migration.
+		if let Err(error) = T::Currency::reserve_named(&HRMP_DEPOSIT_ID, &who, deposit) {
+			log::error!(
+				target: "runtime::hrmp",
+				"Failed to move a channel deposit of para {:?} to the named reserve: {:?}",
+				para,
+				error,
+			);
+			let res = T::Currency::reserve(&who, deposit);
+			debug_assert!(res.is_ok());
+		}
+		deposits += 1;
+	};
+
+	for (id, request) in <Pallet<T> as Store>::HrmpOpenChannelRequests::iter() {
+		rename(id.sender, request.sender_deposit);
+		if request.confirmed {
+			rename(id.recipient, request.recipient_deposit);
+		}
+	}
+	for (id, channel) in <Pallet<T> as Store>::HrmpChannels::iter() {
+		rename(id.sender, channel.sender_deposit);
+		rename(id.recipient, channel.recipient_deposit);
+	}
+
+	T::DbWeight::get().reads_writes(requests + deposits + 1, requests + deposits)
+}
@@ -19,7 +19,10 @@ use crate::mock::{
 	new_test_ext, Configuration, Event as MockEvent, Hrmp, MockGenesisConfig, Paras, ParasShared,
 	System, Test,
 };
-use frame_support::{assert_noop, assert_ok, traits::Currency as _};
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{Currency as _, ReservableCurrency as _, StorageVersion},
+};
 use primitives::v1::BlockNumber;
 use std::collections::BTreeMap;
 
@@ -445,6 +448,47 @@ fn verify_externally_accessible() {
 	});
 }
 
+#[test]
+fn relay_state_proof_keys_cover_open_channels() {
+	use crate::runtime_api_impl::v1::relay_state_proof_keys;
+	use primitives::v1::well_known_keys;
+
+	let para_a = 20.into();
+	let para_b = 21.into();
+	let para_c = 22.into();
+
+	new_test_ext(GenesisConfigBuilder::default().build()).execute_with(|| {
+		register_parachain(para_a);
+		register_parachain(para_b);
+		register_parachain(para_c);
+		run_to_block(5, Some(vec![4, 5]));
+		Hrmp::init_open_channel(para_a, para_b, 2, 8).unwrap();
+		Hrmp::accept_open_channel(para_b, para_a).unwrap();
+		Hrmp::init_open_channel(para_b, para_c, 2, 8).unwrap();
+		Hrmp::accept_open_channel(para_c, para_b).unwrap();
+		run_to_block(8, Some(vec![8]));
+
+		let a_to_b =
+			well_known_keys::hrmp_channels(HrmpChannelId { sender: para_a, recipient: para_b });
+		let b_to_c =
+			well_known_keys::hrmp_channels(HrmpChannelId { sender: para_b, recipient: para_c });
+
+		let keys = relay_state_proof_keys::<Test>(para_b);
+		assert!(keys.contains(&well_known_keys::ACTIVE_CONFIG.to_vec()));
+		assert!(keys.contains(&well_known_keys::dmq_mqc_head(para_b)));
+		assert!(keys.contains(&a_to_b));
+		assert!(keys.contains(&b_to_c));
+
+		// The channels are readable through the keys the proof is made of.
+		assert!(sp_io::storage::get(&a_to_b).is_some());
+		assert!(sp_io::storage::get(&b_to_c).is_some());
+
+		let keys = relay_state_proof_keys::<Test>(para_a);
+		assert!(keys.contains(&a_to_b));
+		assert!(!keys.contains(&b_to_c));
+	});
+}
+
 #[test]
 fn charging_deposits() {
 	let para_a = 32.into();
@@ -499,6 +543,12 @@ fn refund_deposit_on_normal_closure() {
 		run_to_block(10, Some(vec![10]));
 		assert_eq!(<Test as Config>::Currency::free_balance(&para_a.into_account()), 100);
 		assert_eq!(<Test as Config>::Currency::free_balance(&para_b.into_account()), 110);
+		assert!(System::events()
+			.iter()
+			.any(|record| record.event == MockEvent::Hrmp(Event::DepositRefunded(para_a, 20))));
+		assert!(System::events()
+			.iter()
+			.any(|record| record.event == MockEvent::Hrmp(Event::DepositRefunded(para_b, 15))));
 	});
 }
 
@@ -536,6 +586,104 @@ fn refund_deposit_on_offboarding() {
 	});
 }
 
+#[test]
+fn offboarded_paras_are_cleaned_lazily() {
+	let para_a = 32.into();
+	let para_b = 64.into();
+	let para_c = 96.into();
+
+	new_test_ext(GenesisConfigBuilder::default().build()).execute_with(|| {
+		register_parachain(para_a);
+		register_parachain(para_b);
+		register_parachain(para_c);
+		run_to_block(5, Some(vec![4, 5]));
+
+		// Let two of the paras advance their watermarks, then deregister them both.
+		let _ = Hrmp::prune_hrmp(para_b, 4);
+		let _ = Hrmp::prune_hrmp(para_c, 4);
+		deregister_parachain(para_b);
+		deregister_parachain(para_c);
+		run_to_block(7, Some(vec![6, 7]));
+
+		// Only one of the paras was cleaned up in the first block after the offboarding.
+		assert!(!Paras::is_valid_para(para_b));
+		assert!(!Paras::is_valid_para(para_c));
+		assert_eq!(<Hrmp as Store>::HrmpWatermarks::iter().count(), 1);
+		assert_eq!(<Hrmp as Store>::HrmpOffboardedParas::get().len(), 1);
+		Hrmp::assert_storage_consistency_exhaustive();
+
+		run_to_block(8, None);
+		assert_eq!(<Hrmp as Store>::HrmpWatermarks::iter().count(), 0);
+		assert!(<Hrmp as Store>::HrmpOffboardedParas::get().is_empty());
+		Hrmp::assert_storage_consistency_exhaustive();
+		for para in [para_b, para_c] {
+			assert!(System::events()
+				.iter()
+				.any(|record| record.event == MockEvent::Hrmp(Event::OffboardedParaCleaned(para))));
+		}
+	});
+}
+
+#[test]
+fn deposits_are_migrated_to_named_reserve() {
+	let para_a = 32.into();
+	let para_b = 64.into();
+	let para_c = 96.into();
+
+	let mut genesis = GenesisConfigBuilder::default();
+	genesis.hrmp_sender_deposit = 20;
+	genesis.hrmp_recipient_deposit = 15;
+	new_test_ext(genesis.build()).execute_with(|| {
+		// Open a channel from A to B and request one from C to A.
+		register_parachain_with_balance(para_a, 100);
+		register_parachain_with_balance(para_b, 110);
+		register_parachain_with_balance(para_c, 120);
+		run_to_block(5, Some(vec![4, 5]));
+		Hrmp::init_open_channel(para_a, para_b, 2, 8).unwrap();
+		Hrmp::accept_open_channel(para_b, para_a).unwrap();
+		run_to_block(8, Some(vec![8]));
+		Hrmp::init_open_channel(para_c, para_a, 2, 8).unwrap();
+		Hrmp::accept_open_channel(para_a, para_c).unwrap();
+
+		// Turn the deposits into anonymous reserves, as they were before the migration.
+		for (para, deposit) in [(para_a, 35), (para_b, 15), (para_c, 20)] {
+			let who = para.into_account();
+			<Test as Config>::Currency::unreserve_named(&HRMP_DEPOSIT_ID, &who, deposit);
+			<Test as Config>::Currency::reserve(&who, deposit).unwrap();
+		}
+		// Store the request from C in the layout without the recipient deposit.
+		let request_id = HrmpChannelId { sender: para_c, recipient: para_a };
+		let request = <Hrmp as Store>::HrmpOpenChannelRequests::get(&request_id).unwrap();
+		frame_support::storage::unhashed::put(
+			&<Hrmp as Store>::HrmpOpenChannelRequests::hashed_key_for(&request_id),
+			&migration::HrmpOpenChannelRequestV0 {
+				confirmed: request.confirmed,
+				_age: request._age,
+				sender_deposit: request.sender_deposit,
+				max_message_size: request.max_message_size,
+				max_capacity: request.max_capacity,
+				max_total_size: request.max_total_size,
+			},
+		);
+		StorageVersion::new(0).put::<Pallet<Test>>();
+
+		migration::migrate_to_latest::<Test>();
+
+		let request = <Hrmp as Store>::HrmpOpenChannelRequests::get(&request_id).unwrap();
+		assert_eq!(request.recipient_deposit, 15);
+
+		for (para, deposit) in [(para_a, 35), (para_b, 15), (para_c, 20)] {
+			let who = para.into_account();
+			assert_eq!(
+				<Test as Config>::Currency::reserved_balance_named(&HRMP_DEPOSIT_ID, &who),
+				deposit
+			);
+			assert_eq!(<Test as Config>::Currency::reserved_balance(&who), deposit);
+		}
+		assert_eq!(StorageVersion::get::<Pallet<Test>>(), 1);
+	});
+}
+
 #[test]
 fn no_dangling_open_requests() {
 	let para_a = 32.into();
@@ -27,14 +27,20 @@ use crate::{
 use bitvec::{order::Lsb0 as BitOrderLsb0, vec::BitVec};
 use frame_support::pallet_prelude::*;
 use parity_scale_codec::{Decode, Encode};
-use primitives::v1::{
-	AvailabilityBitfield, BackedCandidate, CandidateCommitments, CandidateDescriptor,
-	CandidateHash, CandidateReceipt, CommittedCandidateReceipt, CoreIndex, GroupIndex, Hash,
-	HeadData, Id as ParaId, SigningContext, UncheckedSignedAvailabilityBitfields, ValidatorId,
-	ValidatorIndex, ValidityAttestation,
+use primitives::{
+	para_usage::{CandidateUsage, ParaUsage},
+	v1::{
+		AvailabilityBitfield, BackedCandidate, CandidateCommitments, CandidateDescriptor,
+		CandidateHash, CandidateReceipt, CommittedCandidateReceipt, CoreIndex, GroupIndex, Hash,
+		HeadData, Id as ParaId, SigningContext, UncheckedSignedAvailabilityBitfields, ValidatorId,
+		ValidatorIndex, ValidityAttestation,
+	},
 };
 use scale_info::TypeInfo;
-use sp_runtime::{traits::One, DispatchError};
+use sp_runtime::{
+	traits::{Hash as HashT, One, Saturating},
+	DispatchError,
+};
 use sp_std::{collections::btree_set::BTreeSet, prelude::*};
 
 pub use pallet::*;
@@ -149,6 +155,20 @@ pub trait RewardValidators {
 	fn reward_bitfields(validators: impl IntoIterator<Item = ValidatorIndex>);
 }
 
+impl<A: RewardValidators, B: RewardValidators> RewardValidators for (A, B) {
+	fn reward_backing(validators: impl IntoIterator<Item = ValidatorIndex>) {
+		let validators: Vec<_> = validators.into_iter().collect();
+		A::reward_backing(validators.iter().copied());
+		B::reward_backing(validators);
+	}
+
+	fn reward_bitfields(validators: impl IntoIterator<Item = ValidatorIndex>) {
+		let validators: Vec<_> = validators.into_iter().collect();
+		A::reward_bitfields(validators.iter().copied());
+		B::reward_bitfields(validators);
+	}
+}
+
 /// Helper return type for `process_candidates`.
 #[derive(Encode, Decode, PartialEq, TypeInfo)]
 #[cfg_attr(test, derive(Debug))]
@@ -172,6 +192,28 @@ pub fn minimum_backing_votes(n_validators: usize) -> usize {
 	sp_std::cmp::min(n_validators, 2)
 }
 
+/// Number of backing votes of a candidate by members of its backing group which are not disabled
+/// for backing. Votes of disabled validators do not count towards a valid backing.
+///
+/// `disabled` must be sorted, as [`shared::Pallet::disabled_backers`] is.
+pub(crate) fn enabled_backing_votes<H>(
+	backed_candidate: &BackedCandidate<H>,
+	group_validators: &[ValidatorIndex],
+	disabled: &[ValidatorIndex],
+) -> usize {
+	backed_candidate
+		.validator_indices
+		.iter()
+		.enumerate()
+		.filter(|(_, signed)| **signed)
+		.filter(|(bit_idx, _)| {
+			group_validators
+				.get(*bit_idx)
+				.map_or(false, |vi| disabled.binary_search(vi).is_err())
+		})
+		.count()
+}
+
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
@@ -205,6 +247,9 @@ pub mod pallet {
 		CandidateIncluded(CandidateReceipt<T::Hash>, HeadData, CoreIndex, GroupIndex),
 		/// A candidate timed out. `[candidate, head_data]`
 		CandidateTimedOut(CandidateReceipt<T::Hash>, HeadData, CoreIndex),
+		/// The resources used by a backed candidate, indexed by the [`Pallet::usage_topic`] of its
+		/// para. `[candidate_hash, para, usage, backing_group]`
+		CandidateUsageRecorded(CandidateHash, ParaId, CandidateUsage, GroupIndex),
 	}
 
 	#[pallet::error]
@@ -287,6 +332,11 @@ pub mod pallet {
 	pub(crate) type PendingAvailabilityCommitments<T: Config> =
 		StorageMap<_, Twox64Concat, ParaId, CandidateCommitments>;
 
+	/// The resources used by the candidates of each para backed in the current session.
+	#[pallet::storage]
+	pub(crate) type SessionUsage<T: Config> =
+		StorageMap<_, Twox64Concat, ParaId, ParaUsage, ValueQuery>;
+
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {}
 }
@@ -311,6 +361,22 @@ impl<T: Config> Pallet<T> {
 		for _ in <PendingAvailabilityCommitments<T>>::drain() {}
 		for _ in <PendingAvailability<T>>::drain() {}
 		for _ in <AvailabilityBitfields<T>>::drain() {}
+		for _ in <SessionUsage<T>>::drain() {}
+	}
+
+	/// Whether relay chain finality lags too far behind the block built at `now` for any new
+	/// candidates to be backed, according to the configured `max_finality_lag`.
+	///
+	/// The lag is measured between the parent block and `finalized_number`, the latest finalized
+	/// block known to the block author.
+	pub(crate) fn finality_lag_exceeded(
+		now: T::BlockNumber,
+		finalized_number: T::BlockNumber,
+	) -> bool {
+		let config = <configuration::Pallet<T>>::config();
+		config.max_finality_lag.map_or(false, |max_finality_lag| {
+			now.saturating_sub(One::one()).saturating_sub(finalized_number) > max_finality_lag
+		})
 	}
 
 	/// Extract the freed cores based on cores that became available.
@@ -474,6 +540,7 @@ impl<T: Config> Pallet<T> {
 		}
 
 		let validators = shared::Pallet::<T>::active_validator_keys();
+		let disabled_backers = shared::Pallet::<T>::disabled_backers();
 		let parent_hash = <frame_system::Pallet<T>>::parent_hash();
 
 		// At the moment we assume (and in fact enforce, below) that the relay-parent is always one
@@ -589,6 +656,15 @@ impl<T: Config> Pallet<T> {
 								},
 							}
 
+							ensure!(
+								enabled_backing_votes(
+									&backed_candidate,
+									&group_vals,
+									&disabled_backers,
+								) >= minimum_backing_votes(group_vals.len()),
+								Error::<T>::InsufficientBacking,
+							);
+
 							let mut backer_idx_and_attestation =
 								Vec::<(ValidatorIndex, ValidityAttestation)>::with_capacity(
 									backed_candidate.validator_indices.count_ones(),
@@ -657,6 +733,15 @@ impl<T: Config> Pallet<T> {
 
 			let candidate_hash = candidate.candidate.hash();
 
+			let usage = CandidateUsage {
+				commitments_size: candidate.candidate.commitments.encoded_size() as u32,
+				code_size: <paras::Pallet<T>>::code_size(
+					&candidate.descriptor().validation_code_hash,
+				)
+				.unwrap_or(0),
+			};
+			Self::record_usage(candidate_hash, para_id, usage, group);
+
 			let (descriptor, commitments) =
 				(candidate.candidate.descriptor, candidate.candidate.commitments);
 
@@ -682,6 +767,32 @@ impl<T: Config> Pallet<T> {
 		})
 	}
 
+	/// Account for the resources used by a backed candidate in the session usage of its para, and
+	/// deposit an event about them indexed by the para.
+	fn record_usage(
+		candidate_hash: CandidateHash,
+		para_id: ParaId,
+		usage: CandidateUsage,
+		group: GroupIndex,
+	) {
+		<SessionUsage<T>>::mutate(&para_id, |total| total.record(usage));
+
+		let event: <T as Config>::Event =
+			Event::<T>::CandidateUsageRecorded(candidate_hash, para_id, usage, group).into();
+		let topic = Self::usage_topic(para_id);
+		<frame_system::Pallet<T>>::deposit_event_indexed(&[topic], event.into());
+	}
+
+	/// The topic the usage events of a para are indexed by.
+	pub fn usage_topic(para_id: ParaId) -> T::Hash {
+		T::Hashing::hash_of(&(b"para_usage", para_id))
+	}
+
+	/// The resources used by the candidates of each para backed so far in the current session.
+	pub fn session_usage() -> Vec<(ParaId, ParaUsage)> {
+		<SessionUsage<T>>::iter().collect()
+	}
+
 	/// Run the acceptance criteria checks on the given candidate commitments.
 	pub(crate) fn check_validation_outputs_for_runtime_api(
 		para_id: ParaId,
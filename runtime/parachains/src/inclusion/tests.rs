@@ -1430,6 +1430,41 @@ fn candidate_checks() {
 				Error::<Test>::ParaHeadMismatch
 			);
 		}
+
+		// votes of validators disabled for backing do not count.
+		{
+			let mut candidate = TestCandidateBuilder {
+				para_id: chain_a,
+				relay_parent: System::parent_hash(),
+				pov_hash: Hash::repeat_byte(1),
+				persisted_validation_data_hash: make_vdata_hash(chain_a).unwrap(),
+				hrmp_watermark: RELAY_PARENT_NUM,
+				..Default::default()
+			}
+			.build();
+			collator_sign_candidate(Sr25519Keyring::One, &mut candidate);
+
+			let backed = block_on(back_candidate(
+				candidate,
+				&validators,
+				group_validators(GroupIndex::from(0)).unwrap().as_ref(),
+				&keystore,
+				&signing_context,
+				BackingKind::Threshold,
+			));
+
+			shared::Pallet::<Test>::disable_backer(ValidatorIndex(0));
+
+			assert_noop!(
+				ParaInclusion::process_candidates(
+					Default::default(),
+					vec![backed],
+					vec![chain_a_assignment.clone()],
+					&group_validators,
+				),
+				Error::<Test>::InsufficientBacking
+			);
+		}
 	});
 }
 
@@ -1651,6 +1686,34 @@ fn backing_works() {
 			assure_candidate_sorting(candidate_receipt_with_backing_validator_indices)
 		);
 
+		let code_size = dummy_validation_code().0.len() as u64;
+		assert_eq!(
+			<SessionUsage<Test>>::get(&chain_a),
+			ParaUsage {
+				candidates: 1,
+				commitments_size: candidate_a.commitments.encoded_size() as u64,
+				code_size,
+			},
+		);
+		assert_eq!(ParaInclusion::session_usage().len(), 3);
+		let usage_events = System::events()
+			.into_iter()
+			.filter(|record| record.topics.contains(&ParaInclusion::usage_topic(chain_a)))
+			.map(|record| record.event)
+			.collect::<Vec<_>>();
+		assert_eq!(
+			usage_events,
+			vec![crate::mock::Event::ParaInclusion(Event::<Test>::CandidateUsageRecorded(
+				candidate_a.hash(),
+				chain_a,
+				CandidateUsage {
+					commitments_size: candidate_a.commitments.encoded_size() as u32,
+					code_size: code_size as u32,
+				},
+				GroupIndex::from(0),
+			))],
+		);
+
 		let backers = {
 			let num_backers = minimum_backing_votes(group_validators(GroupIndex(0)).unwrap().len());
 			backing_bitfield(&(0..num_backers).collect::<Vec<_>>())
@@ -1902,6 +1965,7 @@ fn session_change_wipes() {
 			},
 		);
 		<PendingAvailabilityCommitments<Test>>::insert(&chain_b, candidate.commitments);
+		<SessionUsage<Test>>::insert(&chain_a, ParaUsage { candidates: 1, ..Default::default() });
 
 		run_to_block(11, |_| None);
 
@@ -1942,6 +2006,26 @@ fn session_change_wipes() {
 		assert!(<AvailabilityBitfields<Test>>::iter().collect::<Vec<_>>().is_empty());
 		assert!(<PendingAvailability<Test>>::iter().collect::<Vec<_>>().is_empty());
 		assert!(<PendingAvailabilityCommitments<Test>>::iter().collect::<Vec<_>>().is_empty());
+		assert!(<SessionUsage<Test>>::iter().collect::<Vec<_>>().is_empty());
+	});
+}
+
+#[test]
+fn reward_validators_tuple_forwards_to_both() {
+	type Rewards = (crate::mock::TestRewardValidators, crate::mock::TestRewardValidators);
+
+	new_test_ext(MockGenesisConfig::default()).execute_with(|| {
+		<Rewards as RewardValidators>::reward_backing(vec![ValidatorIndex(0), ValidatorIndex(2)]);
+		<Rewards as RewardValidators>::reward_bitfields(vec![ValidatorIndex(1)]);
+
+		let backing = crate::mock::backing_rewards();
+		assert_eq!(backing.len(), 2);
+		assert_eq!(backing.get(&ValidatorIndex(0)), Some(&2));
+		assert_eq!(backing.get(&ValidatorIndex(2)), Some(&2));
+
+		let availability = crate::mock::availability_rewards();
+		assert_eq!(availability.len(), 1);
+		assert_eq!(availability.get(&ValidatorIndex(1)), Some(&2));
 	});
 }
 
@@ -116,11 +116,12 @@ impl frame_system::Config for Test {
 
 parameter_types! {
 	pub static ExistentialDeposit: u64 = 0;
+	pub const MaxReserves: u32 = 10;
 }
 
 impl pallet_balances::Config for Test {
 	type MaxLocks = ();
-	type MaxReserves = ();
+	type MaxReserves = MaxReserves;
 	type ReserveIdentifier = [u8; 8];
 	type Balance = Balance;
 	type Event = Event;
@@ -244,6 +245,8 @@ impl crate::disputes::Config for Test {
 	type Event = Event;
 	type RewardValidators = Self;
 	type PunishValidators = Self;
+	type UnsignedPriority = ParasUnsignedPriority;
+	type ForceOrigin = frame_system::EnsureRoot<u64>;
 	type WeightInfo = crate::disputes::TestWeightInfo;
 }
 
@@ -68,8 +68,10 @@
 //! Moreover, a brand new validation code should go through the PVF pre-checking process.
 //!
 //! Once the para is in one of the two stable states, it can switch to the other stable state or to
-//! initiate offboarding process. The result of offboarding is removal of all data related to that
-//! para.
+//! initiate offboarding process. While offboarding, no new candidates of the para are scheduled.
+//! The offboarding is enacted at a session boundary, when the para's queues are drained. Its head
+//! and validation code are then retained for the code retention period, so that the para can be
+//! registered again with its latest state, after which they are purged as well.
 //!
 //! # PVF Pre-checking
 //!
@@ -110,7 +112,7 @@ use crate::{configuration, initializer::SessionChangeNotification, shared};
 use bitvec::{order::Lsb0 as BitOrderLsb0, vec::BitVec};
 use frame_support::{pallet_prelude::*, traits::EstimateNextSessionRotation};
 use frame_system::pallet_prelude::*;
-use parity_scale_codec::{Decode, Encode};
+use parity_scale_codec::{Compact, Decode, Encode};
 use primitives::{
 	v1::{
 		ConsensusLog, HeadData, Id as ParaId, SessionIndex, UpgradeGoAhead, UpgradeRestriction,
@@ -171,6 +173,17 @@ pub struct ParaPastCodeMeta<N> {
 	last_pruned: Option<N>,
 }
 
+/// The state of an offboarded para, retained for a potential re-registration.
+#[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug, TypeInfo)]
+pub struct RetiredPara<N> {
+	/// The last head-data of the para.
+	pub head: HeadData,
+	/// The hash of the last validation code of the para.
+	pub code_hash: ValidationCodeHash,
+	/// The relay-chain block number at which the state gets purged.
+	pub purge_at: N,
+}
+
 /// The possible states of a para, to take into account delayed lifecycle changes.
 ///
 /// If the para is in a "transition state", it is expected that the parachain is
@@ -487,6 +500,12 @@ pub mod pallet {
 		/// The given validation code was rejected by the PVF pre-checking vote.
 		/// `code_hash` `para_id`
 		PvfCheckRejected(ValidationCodeHash, ParaId),
+		/// A para stopped being scheduled and will be offboarded. `para_id` `session_index`
+		OffboardingScheduled(ParaId, SessionIndex),
+		/// A para has been offboarded, its head and code are retained. `para_id`
+		ParaOffboarded(ParaId),
+		/// The retained head and code of an offboarded para have been purged. `para_id`
+		RetiredParaPurged(ParaId),
 	}
 
 	#[pallet::error]
@@ -648,6 +667,20 @@ pub mod pallet {
 	pub(super) type ActionsQueue<T: Config> =
 		StorageMap<_, Twox64Concat, SessionIndex, Vec<ParaId>, ValueQuery>;
 
+	/// The head and code of offboarded paras, retained for the code retention period.
+	#[pallet::storage]
+	#[pallet::getter(fn retired_para)]
+	pub(super) type RetiredParas<T: Config> =
+		StorageMap<_, Twox64Concat, ParaId, RetiredPara<T::BlockNumber>>;
+
+	/// The offboarded paras along with the relay-chain block at which their retained state gets
+	/// purged.
+	///
+	/// Ordered ascending by block number.
+	#[pallet::storage]
+	pub(super) type RetiredParasPruning<T: Config> =
+		StorageValue<_, Vec<(ParaId, T::BlockNumber)>, ValueQuery>;
+
 	/// Upcoming paras instantiation arguments.
 	///
 	/// NOTE that after PVF pre-checking is enabled the para genesis arg will have it's code set
@@ -1024,7 +1057,7 @@ impl<T: Config> Pallet<T> {
 	/// Called by the initializer to initialize the paras pallet.
 	pub(crate) fn initializer_initialize(now: T::BlockNumber) -> Weight {
 		let weight = Self::prune_old_code(now);
-		weight + Self::process_scheduled_upgrade_changes(now)
+		weight + Self::process_scheduled_upgrade_changes(now) + Self::purge_retired_paras(now)
 	}
 
 	/// Called by the initializer to finalize the paras pallet.
@@ -1058,6 +1091,15 @@ impl<T: Config> Pallet<T> {
 		})
 	}
 
+	/// The size of the validation code with the given hash, without reading the code itself.
+	pub(crate) fn code_size(code_hash: &ValidationCodeHash) -> Option<u32> {
+		// The code is stored as a compactly length-prefixed byte vector, so its length is the
+		// prefix, which takes at most 5 bytes.
+		let mut prefix = [0u8; 5];
+		sp_io::storage::read(&CodeByHash::<T>::hashed_key_for(code_hash), &mut prefix, 0)?;
+		Compact::<u32>::decode(&mut &prefix[..]).ok().map(|len| len.0)
+	}
+
 	// Apply all para actions queued for the given session index.
 	//
 	// The actions to take are based on the lifecycle of of the paras.
@@ -1070,6 +1112,7 @@ impl<T: Config> Pallet<T> {
 		let actions = ActionsQueue::<T>::take(session);
 		let mut parachains = ParachainsCache::new();
 		let now = <frame_system::Pallet<T>>::block_number();
+		let code_retention_period = configuration::Pallet::<T>::config().code_retention_period;
 		let mut outgoing = Vec::new();
 
 		for para in actions {
@@ -1097,7 +1140,7 @@ impl<T: Config> Pallet<T> {
 				Some(ParaLifecycle::OffboardingParathread) => {
 					parachains.remove(para);
 
-					<Self as Store>::Heads::remove(&para);
+					let removed_head = <Self as Store>::Heads::take(&para);
 					<Self as Store>::FutureCodeUpgrades::remove(&para);
 					<Self as Store>::UpgradeGoAheadSignal::remove(&para);
 					<Self as Store>::UpgradeRestrictionSignal::remove(&para);
@@ -1112,6 +1155,11 @@ impl<T: Config> Pallet<T> {
 						Self::note_past_code(para, now, now, removed_code_hash);
 					}
 
+					if let (Some(head), Some(code_hash)) = (removed_head, removed_code_hash) {
+						Self::retire_para(para, head, code_hash, now + code_retention_period);
+					}
+
+					Self::deposit_event(Event::ParaOffboarded(para));
 					outgoing.push(para);
 				},
 			}
@@ -1173,6 +1221,60 @@ impl<T: Config> Pallet<T> {
 		T::DbWeight::get().reads_writes(2, 3)
 	}
 
+	// retain the head and the code of an offboarded para until `purge_at`.
+	//
+	// The retained code is referenced on its own, independently of the past code, since the past
+	// code pruning does not know about re-registrations.
+	fn retire_para(
+		id: ParaId,
+		head: HeadData,
+		code_hash: ValidationCodeHash,
+		purge_at: T::BlockNumber,
+	) -> Weight {
+		<Self as Store>::CodeByHashRefs::mutate(&code_hash, |refs| *refs += 1);
+		<Self as Store>::RetiredParas::insert(&id, RetiredPara { head, code_hash, purge_at });
+		<Self as Store>::RetiredParasPruning::mutate(|pruning| {
+			let insert_idx =
+				pruning.binary_search_by_key(&purge_at, |&(_, b)| b).unwrap_or_else(|idx| idx);
+			pruning.insert(insert_idx, (id, purge_at));
+		});
+
+		T::DbWeight::get().reads_writes(2, 3)
+	}
+
+	// remove the retained state of the given para, if any, releasing its code.
+	fn remove_retired_para(id: ParaId) -> Weight {
+		match <Self as Store>::RetiredParas::take(&id) {
+			Some(retired) =>
+				T::DbWeight::get().reads_writes(1, 1) + Self::decrease_code_ref(&retired.code_hash),
+			None => T::DbWeight::get().reads(1),
+		}
+	}
+
+	// purges the retained state of the offboarded paras whose retention period is over.
+	fn purge_retired_paras(now: T::BlockNumber) -> Weight {
+		let mut weight = T::DbWeight::get().reads(1);
+		let due = <Self as Store>::RetiredParasPruning::mutate(|pruning| {
+			let up_to_idx = pruning.iter().take_while(|&(_, at)| at <= &now).count();
+			pruning.drain(..up_to_idx).collect::<Vec<_>>()
+		});
+		if due.is_empty() {
+			return weight
+		}
+
+		weight += T::DbWeight::get().writes(1);
+		for (para_id, purge_at) in due {
+			weight += T::DbWeight::get().reads(1);
+			// The para might have been registered again, and maybe offboarded once more since.
+			if Self::retired_para(&para_id).map_or(false, |retired| retired.purge_at == purge_at) {
+				weight += Self::remove_retired_para(para_id);
+				Self::deposit_event(Event::RetiredParaPurged(para_id));
+			}
+		}
+
+		weight
+	}
+
 	// looks at old code metadata, compares them to the current acceptance window, and prunes those
 	// that are too old.
 	fn prune_old_code(now: T::BlockNumber) -> Weight {
@@ -1482,6 +1584,9 @@ impl<T: Config> Pallet<T> {
 		ensure!(!genesis_data.validation_code.0.is_empty(), Error::<T>::CannotOnboard);
 		ParaLifecycles::<T>::insert(&id, ParaLifecycle::Onboarding);
 
+		// The state retained from a previous registration is superseded by the genesis data.
+		Self::remove_retired_para(id);
+
 		// HACK: here we are doing something nasty.
 		//
 		// In order to fix the [soaking issue] we insert the code eagerly here. When the onboarding
@@ -1570,6 +1675,8 @@ impl<T: Config> Pallet<T> {
 			}
 		});
 
+		Self::deposit_event(Event::OffboardingScheduled(id, scheduled_session));
+
 		Ok(())
 	}
 
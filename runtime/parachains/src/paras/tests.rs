@@ -29,7 +29,8 @@ use test_helpers::{dummy_head_data, dummy_validation_code};
 use crate::{
 	configuration::HostConfiguration,
 	mock::{
-		new_test_ext, Configuration, MockGenesisConfig, Origin, Paras, ParasShared, System, Test,
+		new_test_ext, Configuration, Event as MockEvent, MockGenesisConfig, Origin, Paras,
+		ParasShared, System, Test,
 	},
 };
 
@@ -818,6 +819,120 @@ fn full_parachain_cleanup_storage() {
 	});
 }
 
+#[test]
+fn offboarded_para_state_is_retained_then_purged() {
+	let code_retention_period = 20;
+
+	let code = ValidationCode(vec![1, 2, 3]);
+	let head = HeadData(vec![4, 5, 6]);
+	let paras = vec![(
+		0u32.into(),
+		ParaGenesisArgs {
+			parachain: true,
+			genesis_head: head.clone(),
+			validation_code: code.clone(),
+		},
+	)];
+
+	let genesis_config = MockGenesisConfig {
+		paras: GenesisConfig { paras, ..Default::default() },
+		configuration: crate::configuration::GenesisConfig {
+			config: HostConfiguration {
+				code_retention_period,
+				pvf_checking_enabled: false,
+				..Default::default()
+			},
+			..Default::default()
+		},
+		..Default::default()
+	};
+
+	new_test_ext(genesis_config).execute_with(|| {
+		let para_id = ParaId::from(0);
+
+		run_to_block(2, None);
+		assert_ok!(Paras::schedule_para_cleanup(para_id));
+		assert!(frame_system::Pallet::<Test>::events()
+			.iter()
+			.any(|r| r.event == MockEvent::Paras(Event::OffboardingScheduled(para_id, 2))));
+
+		// The para is offboarded at the block 3, but its head and code are retained.
+		run_to_block(4, Some(vec![3, 4]));
+		assert!(Paras::lifecycle(para_id).is_none());
+		assert!(Paras::para_head(&para_id).is_none());
+		assert_eq!(
+			Paras::retired_para(&para_id),
+			Some(RetiredPara { head, code_hash: code.hash(), purge_at: 3 + code_retention_period }),
+		);
+		assert_eq!(<Paras as Store>::CodeByHashRefs::get(code.hash()), 2);
+		assert!(frame_system::Pallet::<Test>::events()
+			.iter()
+			.any(|r| r.event == MockEvent::Paras(Event::ParaOffboarded(para_id))));
+
+		// The retained state is purged at the end of the retention period, the past code a block
+		// later.
+		run_to_block(3 + code_retention_period, None);
+		assert!(Paras::retired_para(&para_id).is_none());
+		assert!(<Paras as Store>::RetiredParasPruning::get().is_empty());
+		assert_eq!(<Paras as Store>::CodeByHashRefs::get(code.hash()), 1);
+		assert!(frame_system::Pallet::<Test>::events()
+			.iter()
+			.any(|r| r.event == MockEvent::Paras(Event::RetiredParaPurged(para_id))));
+
+		run_to_block(3 + code_retention_period + 1, None);
+		check_code_is_not_stored(&code);
+	});
+}
+
+#[test]
+fn registering_again_supersedes_retained_state() {
+	let code = ValidationCode(vec![1, 2, 3]);
+	let paras = vec![(
+		0u32.into(),
+		ParaGenesisArgs {
+			parachain: true,
+			genesis_head: dummy_head_data(),
+			validation_code: code.clone(),
+		},
+	)];
+
+	let genesis_config = MockGenesisConfig {
+		paras: GenesisConfig { paras, ..Default::default() },
+		configuration: crate::configuration::GenesisConfig {
+			config: HostConfiguration {
+				code_retention_period: 20,
+				pvf_checking_enabled: false,
+				..Default::default()
+			},
+			..Default::default()
+		},
+		..Default::default()
+	};
+
+	new_test_ext(genesis_config).execute_with(|| {
+		let para_id = ParaId::from(0);
+
+		assert_ok!(Paras::schedule_para_cleanup(para_id));
+		run_to_block(3, Some(vec![2, 3]));
+		assert!(Paras::retired_para(&para_id).is_some());
+
+		let new_code = ValidationCode(vec![7, 8, 9]);
+		assert_ok!(Paras::schedule_para_initialize(
+			para_id,
+			ParaGenesisArgs {
+				parachain: true,
+				genesis_head: dummy_head_data(),
+				validation_code: new_code.clone(),
+			},
+		));
+		assert!(Paras::retired_para(&para_id).is_none());
+
+		// Only the past code keeps the old code around now.
+		assert_eq!(<Paras as Store>::CodeByHashRefs::get(code.hash()), 1);
+		check_code_is_stored(&new_code);
+	});
+}
+
 #[test]
 fn para_incoming_at_session() {
 	let code_a = ValidationCode(vec![2]);
@@ -25,7 +25,7 @@ use crate::{
 	configuration,
 	disputes::{DisputesHandler, VerifyDisputeSignatures},
 	inclusion,
-	inclusion::{CandidateCheckContext, FullCheck},
+	inclusion::{enabled_backing_votes, minimum_backing_votes, CandidateCheckContext, FullCheck},
 	initializer,
 	metrics::METRICS,
 	scheduler::{self, CoreAssignment, FreedReason},
@@ -41,11 +41,11 @@ use frame_system::pallet_prelude::*;
 use pallet_babe::{self, CurrentBlockRandomness};
 use primitives::v1::{
 	BackedCandidate, CandidateHash, CandidateReceipt, CheckedDisputeStatementSet,
-	CheckedMultiDisputeStatementSet, CoreIndex, DisputeStatementSet,
+	CheckedMultiDisputeStatementSet, CoreIndex, DisputeStatementSet, GroupIndex,
 	InherentData as ParachainsInherentData, MultiDisputeStatementSet, ScrapedOnChainVotes,
 	SessionIndex, SigningContext, UncheckedSignedAvailabilityBitfield,
 	UncheckedSignedAvailabilityBitfields, ValidatorId, ValidatorIndex, ValidityAttestation,
-	PARACHAINS_INHERENT_IDENTIFIER,
+	FINALIZED_NUMBER_INHERENT_IDENTIFIER, PARACHAINS_INHERENT_IDENTIFIER,
 };
 use rand::{seq::SliceRandom, SeedableRng};
 
@@ -335,7 +335,7 @@ impl<T: Config> Pallet<T> {
 
 		let current_session = <shared::Pallet<T>>::session_index();
 
-		let max_block_weight = <T as frame_system::Config>::BlockWeights::get().max_block;
+		let max_block_weight = max_inherent_weight::<T>();
 
 		METRICS.on_before_filter(candidates_weight + bitfields_weight + disputes_weight);
 
@@ -567,6 +567,13 @@ impl<T: Config> Pallet<T> {
 			disputes.len()
 		);
 
+		let finalized_number = data
+			.get_data::<T::BlockNumber>(&FINALIZED_NUMBER_INHERENT_IDENTIFIER)
+			.unwrap_or_else(|_| {
+				log::warn!(target: LOG_TARGET, "Finalized block number failed to decode");
+				None
+			});
+
 		let parent_hash = <frame_system::Pallet<T>>::parent_hash();
 
 		if parent_hash != parent_header.hash() {
@@ -580,7 +587,7 @@ impl<T: Config> Pallet<T> {
 		let current_session = <shared::Pallet<T>>::session_index();
 		let expected_bits = <scheduler::Pallet<T>>::availability_cores().len();
 		let validator_public = shared::Pallet::<T>::active_validator_keys();
-		let max_block_weight = <T as frame_system::Config>::BlockWeights::get().max_block;
+		let max_block_weight = max_inherent_weight::<T>();
 
 		let entropy = compute_entropy::<T>(parent_hash);
 		let mut rng = rand_chacha::ChaChaRng::from_seed(entropy.into());
@@ -709,6 +716,21 @@ impl<T: Config> Pallet<T> {
 			let parent_storage_root = parent_header.state_root().clone();
 
 			let check_ctx = CandidateCheckContext::<T>::new(now, relay_parent_number);
+			let disabled = <shared::Pallet<T>>::disabled_backers();
+			let scheduled_groups = scheduled
+				.iter()
+				.map(|assignment| (assignment.para_id, assignment.group_idx))
+				.collect::<BTreeMap<ParaId, GroupIndex>>();
+			let lacks_enabled_backing =
+				move |backed_candidate: &BackedCandidate<<T as frame_system::Config>::Hash>| {
+					scheduled_groups
+						.get(&backed_candidate.descriptor().para_id)
+						.and_then(|group| <scheduler::Pallet<T>>::group_validators(*group))
+						.map_or(false, |group_vals| {
+							enabled_backing_votes(backed_candidate, &group_vals, &disabled) <
+								minimum_backing_votes(group_vals.len())
+						})
+				};
 			let backed_candidates = sanitize_backed_candidates::<T, _>(
 				parent_hash,
 				backed_candidates,
@@ -717,6 +739,8 @@ impl<T: Config> Pallet<T> {
 				      -> bool {
 					// never include a concluded-invalid candidate
 					concluded_invalid_disputes.contains(&backed_candidate.hash()) ||
+							// nor one lacking backing by validators not disabled for backing
+							lacks_enabled_backing(backed_candidate) ||
 							// Instead of checking the candidates with code upgrades twice
 							// move the checking up here and skip it in the training wheels fallback.
 							// That way we avoid possible duplicate checks while assuring all
@@ -739,6 +763,21 @@ impl<T: Config> Pallet<T> {
 			))
 		});
 
+		// Stop backing new candidates while finality is lagging, so that the availability and
+		// approval work already pending can drain instead of growing further.
+		let now = <frame_system::Pallet<T>>::block_number();
+		if finalized_number.map_or(false, |finalized_number| {
+			<inclusion::Pallet<T>>::finality_lag_exceeded(now, finalized_number)
+		}) {
+			log::warn!(
+				target: LOG_TARGET,
+				"Finality lags behind by more than the configured maximum, \
+					dropping {} backed candidates",
+				backed_candidates.len(),
+			);
+			backed_candidates.clear();
+		}
+
 		// Assure the maximum block weight is adhered, by limiting bitfields and backed
 		// candidates. Dispute statement sets were already limited before.
 		let actual_weight = apply_weight_limit::<T>(
@@ -859,6 +898,17 @@ fn random_sel<X, F: Fn(&X) -> Weight>(
 /// Assumes disputes are already filtered by the time this is called.
 ///
 /// Returns the total weight consumed by `bitfields` and `candidates`.
+/// The most weight the inherent may consume: the `max_extrinsic` weight of the `Mandatory` class
+/// if the runtime limits it, so that space is left for `Operational` extrinsics, or else the whole
+/// block.
+fn max_inherent_weight<T: frame_system::Config>() -> Weight {
+	let weights = <T as frame_system::Config>::BlockWeights::get();
+	weights
+		.get(DispatchClass::Mandatory)
+		.max_extrinsic
+		.map_or(weights.max_block, |max| max.min(weights.max_block))
+}
+
 fn apply_weight_limit<T: Config + inclusion::Config>(
 	candidates: &mut Vec<BackedCandidate<<T>::Hash>>,
 	bitfields: &mut UncheckedSignedAvailabilityBitfields,
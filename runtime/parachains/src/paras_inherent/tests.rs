@@ -136,6 +136,54 @@ mod enter {
 		});
 	}
 
+	#[test]
+	// Validate that backed candidates are dropped in `create_inherent` once finality lags behind
+	// the parent block by more than the configured maximum, while bitfields are still processed.
+	fn finality_lag_pauses_backing() {
+		new_test_ext(MockGenesisConfig::default()).execute_with(|| {
+			let mut backed_and_concluding = BTreeMap::new();
+			backed_and_concluding.insert(0, 1);
+			backed_and_concluding.insert(1, 1);
+
+			let scenario = make_inherent_data(TestConfig {
+				dispute_statements: BTreeMap::new(),
+				dispute_sessions: vec![], // No disputes
+				backed_and_concluding,
+				num_validators_per_core: 1,
+				code_upgrade: None,
+			});
+
+			let expected_para_inherent_data = scenario.data.clone();
+			assert_eq!(expected_para_inherent_data.bitfields.len(), 2);
+			assert_eq!(expected_para_inherent_data.backed_candidates.len(), 2);
+
+			configuration::ActiveConfig::<Test>::mutate(|config| {
+				config.max_finality_lag = Some(1);
+			});
+			// The parent block is well beyond genesis.
+			assert!(<frame_system::Pallet<Test>>::block_number() > 2);
+
+			let mut inherent_data = InherentData::new();
+			inherent_data
+				.put_data(PARACHAINS_INHERENT_IDENTIFIER, &expected_para_inherent_data)
+				.unwrap();
+
+			// Without the finalized number, the lag is unknown and nothing is filtered out.
+			assert_eq!(
+				Pallet::<Test>::create_inherent_inner(&inherent_data.clone()).unwrap(),
+				expected_para_inherent_data
+			);
+
+			// Only genesis is finalized, so finality lags too far behind.
+			inherent_data.put_data(FINALIZED_NUMBER_INHERENT_IDENTIFIER, &0u32).unwrap();
+
+			let limit_inherent_data =
+				Pallet::<Test>::create_inherent_inner(&inherent_data.clone()).unwrap();
+			assert!(limit_inherent_data.backed_candidates.is_empty());
+			assert_eq!(limit_inherent_data.bitfields, expected_para_inherent_data.bitfields);
+		});
+	}
+
 	#[test]
 	fn test_session_is_tracked_in_on_chain_scraping() {
 		use crate::disputes::run_to_block;
@@ -66,6 +66,44 @@ where
 	fn reward_bitfields(_validators: impl IntoIterator<Item = ValidatorIndex>) {}
 }
 
+/// Reports validators participating in parachains as live to a liveness tracker `L`, such as
+/// `pallet-im-online`.
+///
+/// Both backing statements and availability bitfields count as participation. Validators
+/// noted this way are considered online for the current session, so they don't need to
+/// submit a heartbeat and are not reported for unresponsiveness. Only validators which
+/// neither author blocks nor take part in parachain consensus are left to prove liveness
+/// through heartbeats.
+pub struct NoteParticipationAsLiveness<C, L>(sp_std::marker::PhantomData<(C, L)>);
+
+impl<C, L> NoteParticipationAsLiveness<C, L>
+where
+	C: pallet_staking::Config + shared::Config,
+	L: pallet_authorship::EventHandler<C::AccountId, C::BlockNumber>,
+{
+	fn note_participation(indirect_indices: impl IntoIterator<Item = ValidatorIndex>) {
+		let validators = C::SessionInterface::validators();
+
+		for validator in validators_to_reward::<C, _, _>(&validators, indirect_indices) {
+			L::note_author(validator.clone());
+		}
+	}
+}
+
+impl<C, L> crate::inclusion::RewardValidators for NoteParticipationAsLiveness<C, L>
+where
+	C: pallet_staking::Config + shared::Config,
+	L: pallet_authorship::EventHandler<C::AccountId, C::BlockNumber>,
+{
+	fn reward_backing(indirect_indices: impl IntoIterator<Item = ValidatorIndex>) {
+		Self::note_participation(indirect_indices)
+	}
+
+	fn reward_bitfields(indirect_indices: impl IntoIterator<Item = ValidatorIndex>) {
+		Self::note_participation(indirect_indices)
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -18,13 +18,14 @@
 //! functions.
 
 use crate::{
-	configuration, dmp, hrmp, inclusion, initializer, paras, paras_inherent, scheduler,
+	configuration, disputes, dmp, hrmp, inclusion, initializer, paras, paras_inherent, scheduler,
 	session_info, shared,
 };
 use primitives::{
 	v1::{
-		AuthorityDiscoveryId, CandidateEvent, CommittedCandidateReceipt, CoreIndex, CoreOccupied,
-		CoreState, GroupIndex, GroupRotationInfo, Hash, Id as ParaId, InboundDownwardMessage,
+		well_known_keys, AuthorityDiscoveryId, CandidateEvent, CommittedCandidateReceipt,
+		CoreIndex, CoreOccupied, CoreState, DisputeStatementSet, ExecutorParams, GroupIndex,
+		GroupRotationInfo, Hash, HrmpChannelId, Id as ParaId, InboundDownwardMessage,
 		InboundHrmpMessage, OccupiedCore, OccupiedCoreAssumption, PersistedValidationData,
 		ScheduledCore, ScrapedOnChainVotes, SessionIndex, ValidationCode, ValidationCodeHash,
 		ValidatorId, ValidatorIndex, ValidatorSignature,
@@ -333,13 +334,14 @@ where
 	<frame_system::Pallet<T>>::read_events_no_consensus()
 		.into_iter()
 		.filter_map(|record| extract_event(record.event))
-		.map(|event| match event {
+		.filter_map(|event| match event {
 			RawEvent::<T>::CandidateBacked(c, h, core, group) =>
-				CandidateEvent::CandidateBacked(c, h, core, group),
+				Some(CandidateEvent::CandidateBacked(c, h, core, group)),
 			RawEvent::<T>::CandidateIncluded(c, h, core, group) =>
-				CandidateEvent::CandidateIncluded(c, h, core, group),
+				Some(CandidateEvent::CandidateIncluded(c, h, core, group)),
 			RawEvent::<T>::CandidateTimedOut(c, h, core) =>
-				CandidateEvent::CandidateTimedOut(c, h, core),
+				Some(CandidateEvent::CandidateTimedOut(c, h, core)),
+			RawEvent::<T>::CandidateUsageRecorded(..) => None,
 			RawEvent::<T>::__Ignore(_, _) => unreachable!("__Ignore cannot be used"),
 		})
 		.collect()
@@ -350,6 +352,13 @@ pub fn session_info<T: session_info::Config>(index: SessionIndex) -> Option<Sess
 	<session_info::Pallet<T>>::session_info(index)
 }
 
+/// Get the executor parameters for the given session, if stored.
+pub fn session_executor_params<T: session_info::Config>(
+	index: SessionIndex,
+) -> Option<ExecutorParams> {
+	<session_info::Pallet<T>>::session_executor_params(index)
+}
+
 /// Implementation for the `dmq_contents` function of the runtime API.
 pub fn dmq_contents<T: dmp::Config>(
 	recipient: ParaId,
@@ -364,6 +373,30 @@ pub fn inbound_hrmp_channels_contents<T: hrmp::Config>(
 	<hrmp::Pallet<T>>::inbound_hrmp_channels_contents(recipient)
 }
 
+/// Implementation for the `relay_state_proof_keys` function of the runtime API.
+pub fn relay_state_proof_keys<T: hrmp::Config>(para_id: ParaId) -> Vec<Vec<u8>> {
+	let ingress_channels = hrmp::HrmpIngressChannelsIndex::<T>::get(para_id);
+	let egress_channels = hrmp::HrmpEgressChannelsIndex::<T>::get(para_id);
+
+	let mut keys = vec![
+		well_known_keys::CURRENT_SLOT.to_vec(),
+		well_known_keys::ACTIVE_CONFIG.to_vec(),
+		well_known_keys::dmq_mqc_head(para_id),
+		well_known_keys::relay_dispatch_queue_size(para_id),
+		well_known_keys::hrmp_ingress_channel_index(para_id),
+		well_known_keys::hrmp_egress_channel_index(para_id),
+		well_known_keys::upgrade_go_ahead_signal(para_id),
+		well_known_keys::upgrade_restriction_signal(para_id),
+	];
+	keys.extend(ingress_channels.into_iter().map(|sender| {
+		well_known_keys::hrmp_channels(HrmpChannelId { sender, recipient: para_id })
+	}));
+	keys.extend(egress_channels.into_iter().map(|recipient| {
+		well_known_keys::hrmp_channels(HrmpChannelId { sender: para_id, recipient })
+	}));
+	keys
+}
+
 /// Implementation for the `validation_code_by_hash` function of the runtime API.
 pub fn validation_code_by_hash<T: paras::Config>(
 	hash: ValidationCodeHash,
@@ -384,6 +417,11 @@ pub fn submit_pvf_check_statement<T: paras::Config>(
 	<paras::Pallet<T>>::submit_pvf_check_statement(stmt, signature)
 }
 
+/// Submits a set of dispute statements. See [`disputes::Pallet::submit_dispute_statement_set`].
+pub fn submit_dispute_statement_set<T: disputes::Config>(set: DisputeStatementSet) {
+	<disputes::Pallet<T>>::submit_dispute_statement_set(set)
+}
+
 /// Returns the list of all PVF code hashes that require pre-checking. See
 /// [`paras::Pallet::pvfs_require_precheck`].
 pub fn pvfs_require_precheck<T: paras::Config>() -> Vec<ValidationCodeHash> {
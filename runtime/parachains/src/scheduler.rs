@@ -352,6 +352,11 @@ impl<T: Config> Pallet<T> {
 			return
 		}
 
+		// Offboarding parathreads don't accept new claims.
+		if <paras::Pallet<T>>::lifecycle(claim.0).map_or(false, |l| l.is_offboarding()) {
+			return
+		}
+
 		let config = <configuration::Pallet<T>>::config();
 		let queue_max_size = config.parathread_cores * config.scheduling_lookahead;
 
@@ -479,8 +484,10 @@ impl<T: Config> Pallet<T> {
 				let core = CoreIndex(core_index as u32);
 
 				let core_assignment = if core_index < parachains.len() {
-					// parachain core.
-					Some(CoreAssignment {
+					// parachain core. Offboarding parachains are not scheduled anymore.
+					let offboarding = <paras::Pallet<T>>::lifecycle(parachains[core_index])
+						.map_or(false, |lifecycle| lifecycle.is_offboarding());
+					(!offboarding).then(|| CoreAssignment {
 						kind: AssignmentKind::Parachain,
 						para_id: parachains[core_index],
 						core: core.clone(),
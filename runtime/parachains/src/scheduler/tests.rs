@@ -1358,6 +1358,12 @@ fn session_change_requires_reschedule_dropping_removed_paras() {
 
 		assert_ok!(Paras::schedule_para_cleanup(chain_b));
 
+		// The offboarding parachain is not scheduled anymore.
+		Scheduler::clear();
+		Scheduler::schedule(Vec::new(), 1);
+		assert_eq!(Scheduler::scheduled().len(), 1);
+		assert_eq!(Scheduler::scheduled()[0].para_id, chain_a);
+
 		run_to_end_of_block(2, |number| match number {
 			2 => Some(SessionChangeNotification {
 				new_config: default_config(),
@@ -25,7 +25,7 @@ use crate::{
 };
 use frame_support::{pallet_prelude::*, traits::OneSessionHandler};
 use primitives::{
-	v1::{AssignmentId, AuthorityDiscoveryId, SessionIndex},
+	v1::{AssignmentId, AuthorityDiscoveryId, ExecutorParams, SessionIndex},
 	v2::SessionInfo,
 };
 use sp_std::vec::Vec;
@@ -78,6 +78,12 @@ pub mod pallet {
 	#[pallet::getter(fn session_info)]
 	pub(crate) type Sessions<T: Config> = StorageMap<_, Identity, SessionIndex, SessionInfo>;
 
+	/// Executor parameters in effect for each session, over the same window as `Sessions`.
+	#[pallet::storage]
+	#[pallet::getter(fn session_executor_params)]
+	pub(crate) type SessionExecutorParams<T: Config> =
+		StorageMap<_, Identity, SessionIndex, ExecutorParams>;
+
 	#[pallet::hooks]
 	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
 		fn on_runtime_upgrade() -> Weight {
@@ -120,6 +126,7 @@ impl<T: Config> Pallet<T> {
 		let n_delay_tranches = config.n_delay_tranches;
 		let no_show_slots = config.no_show_slots;
 		let needed_approvals = config.needed_approvals;
+		let executor_params = config.executor_params;
 
 		let new_session_index = notification.session_index;
 		let random_seed = notification.random_seed;
@@ -132,6 +139,7 @@ impl<T: Config> Pallet<T> {
 		if old_earliest_stored_session != 0 || Sessions::<T>::get(0).is_some() {
 			for idx in old_earliest_stored_session..new_earliest_stored_session {
 				Sessions::<T>::remove(&idx);
+				SessionExecutorParams::<T>::remove(&idx);
 			}
 			// update `EarliestStoredSession` based on `config.dispute_period`
 			EarliestStoredSession::<T>::set(new_earliest_stored_session);
@@ -156,6 +164,7 @@ impl<T: Config> Pallet<T> {
 			dispute_period,
 		};
 		Sessions::<T>::insert(&new_session_index, &new_session_info);
+		SessionExecutorParams::<T>::insert(&new_session_index, executor_params);
 	}
 
 	/// Called by the initializer to initialize the session info pallet.
@@ -162,6 +162,26 @@ fn session_info_is_based_on_config() {
 	})
 }
 
+#[test]
+fn executor_params_are_based_on_config() {
+	new_test_ext(genesis_config()).execute_with(|| {
+		run_to_block(1, new_session_every_block);
+		assert_eq!(SessionExecutorParams::<Test>::get(&1), Some(ExecutorParams::default()));
+
+		let executor_params = ExecutorParams { heap_pages: 4096, ..Default::default() };
+		Configuration::set_executor_params(Origin::root(), executor_params).unwrap();
+		// 2 sessions later
+		run_to_block(3, new_session_every_block);
+		assert_eq!(SessionExecutorParams::<Test>::get(&2), Some(ExecutorParams::default()));
+		assert_eq!(SessionExecutorParams::<Test>::get(&3), Some(executor_params));
+
+		// Pruned along with the session info
+		run_to_block(10, new_session_every_block);
+		assert!(Sessions::<Test>::get(&1).is_none());
+		assert!(SessionExecutorParams::<Test>::get(&1).is_none());
+	})
+}
+
 #[test]
 fn session_info_active_subsets() {
 	let unscrambled = vec![
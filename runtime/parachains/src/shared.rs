@@ -68,6 +68,16 @@ pub mod pallet {
 	#[pallet::getter(fn active_validator_keys)]
 	pub(super) type ActiveValidatorKeys<T: Config> = StorageValue<_, Vec<ValidatorId>, ValueQuery>;
 
+	/// The validators which may not back candidates for the rest of the session, because they
+	/// were involved in an offence or lost a dispute. Indices are into the active validators and
+	/// sorted.
+	///
+	/// Unlike validators disabled in the session pallet, they still author blocks and vote on
+	/// finality.
+	#[pallet::storage]
+	#[pallet::getter(fn disabled_backers)]
+	pub(super) type DisabledBackers<T: Config> = StorageValue<_, Vec<ValidatorIndex>, ValueQuery>;
+
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {}
 }
@@ -109,6 +119,7 @@ impl<T: Config> Pallet<T> {
 
 		ActiveValidatorIndices::<T>::set(shuffled_indices);
 		ActiveValidatorKeys::<T>::set(active_validator_keys.clone());
+		DisabledBackers::<T>::kill();
 
 		active_validator_keys
 	}
@@ -118,6 +129,29 @@ impl<T: Config> Pallet<T> {
 		Self::session_index().saturating_add(SESSION_DELAY)
 	}
 
+	/// Stop the active validator with the given index from backing candidates for the rest of the
+	/// session.
+	pub fn disable_backer(index: ValidatorIndex) {
+		if index.0 as usize >= ActiveValidatorKeys::<T>::decode_len().unwrap_or(0) {
+			return
+		}
+
+		DisabledBackers::<T>::mutate(|disabled| {
+			if let Err(position) = disabled.binary_search(&index) {
+				disabled.insert(position, index);
+			}
+		});
+	}
+
+	/// Stop the validator with the given index in the broader validator set from backing
+	/// candidates for the rest of the session. Nothing happens if it is not an active validator.
+	pub fn disable_backer_in_validator_set(index: ValidatorIndex) {
+		let active = ActiveValidatorIndices::<T>::get().iter().position(|i| *i == index);
+		if let Some(active) = active {
+			Self::disable_backer(ValidatorIndex(active as _));
+		}
+	}
+
 	/// Test function for setting the current session index.
 	#[cfg(any(feature = "std", feature = "runtime-benchmarks", test))]
 	pub fn set_session_index(index: SessionIndex) {
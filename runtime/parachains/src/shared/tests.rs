@@ -97,3 +97,34 @@ fn sets_truncates_and_shuffles_validators() {
 		);
 	});
 }
+
+#[test]
+fn disabled_backers_are_sorted_and_wiped_on_new_session() {
+	let validators = vec![
+		Sr25519Keyring::Alice,
+		Sr25519Keyring::Bob,
+		Sr25519Keyring::Charlie,
+		Sr25519Keyring::Dave,
+		Sr25519Keyring::Ferdie,
+	];
+
+	let mut config = HostConfiguration::default();
+	config.max_validators = Some(2);
+
+	let pubkeys = validator_pubkeys(&validators);
+
+	new_test_ext(MockGenesisConfig::default()).execute_with(|| {
+		ParasShared::initializer_on_new_session(1, [1; 32], &config, pubkeys.clone());
+
+		// Ferdie and Bob are the active validators 0 and 1, the others are not active.
+		ParasShared::disable_backer_in_validator_set(ValidatorIndex(1));
+		ParasShared::disable_backer_in_validator_set(ValidatorIndex(0));
+		ParasShared::disable_backer(ValidatorIndex(0));
+		ParasShared::disable_backer(ValidatorIndex(0));
+		ParasShared::disable_backer(ValidatorIndex(2));
+		assert_eq!(ParasShared::disabled_backers(), vec![ValidatorIndex(0), ValidatorIndex(1)]);
+
+		ParasShared::initializer_on_new_session(2, [1; 32], &config, pubkeys);
+		assert!(ParasShared::disabled_backers().is_empty());
+	});
+}
@@ -0,0 +1,39 @@
+// Copyright 2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! The externalities the fuzz targets of the Selendra runtime run against.
+
+use frame_support::traits::GenesisBuild;
+use runtime_parachains::configuration::{self, HostConfiguration};
+use selendra_runtime::Runtime;
+use sp_runtime::BuildStorage;
+
+/// Externalities holding the genesis state of `frame-system` and the default host configuration,
+/// which is the least state the runtime's XCM executor and inherent sanitization need.
+pub fn new_ext() -> sp_io::TestExternalities {
+	let mut storage = frame_system::GenesisConfig::default()
+		.build_storage::<Runtime>()
+		.expect("the genesis of frame-system builds; qed");
+	<configuration::GenesisConfig<Runtime> as GenesisBuild<Runtime>>::assimilate_storage(
+		&configuration::GenesisConfig { config: HostConfiguration::default() },
+		&mut storage,
+	)
+	.expect("the genesis of the configuration builds; qed");
+
+	let mut ext = sp_io::TestExternalities::new(storage);
+	ext.execute_with(|| frame_system::Pallet::<Runtime>::set_block_number(1));
+	ext
+}
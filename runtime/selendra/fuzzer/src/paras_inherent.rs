@@ -0,0 +1,55 @@
+// Copyright 2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Decodes parachains inherent data as block authors provide it and runs it through the
+//! sanitization `create_inherent` applies before the inherent is included in a block.
+
+use codec::Decode;
+use frame_support::inherent::ProvideInherent;
+use honggfuzz::fuzz;
+use primitives::v1::{
+	Header, InherentData as ParachainsInherentData, PARACHAINS_INHERENT_IDENTIFIER,
+};
+use selendra_runtime::{ParaInherent, Runtime};
+use selendra_runtime_fuzzer::new_ext;
+use sp_inherents::InherentData;
+use sp_runtime::traits::{Header as HeaderT, One};
+
+fn main() {
+	loop {
+		fuzz!(|data: &[u8]| {
+			let inherent = match ParachainsInherentData::<Header>::decode(&mut &data[..]) {
+				Ok(inherent) => inherent,
+				Err(_) => return,
+			};
+
+			new_ext().execute_with(|| {
+				// The inherent data is only considered if it builds on the parent of the block.
+				let parent = &inherent.parent_header;
+				frame_system::Pallet::<Runtime>::initialize(
+					&(parent.number() + One::one()),
+					&parent.hash(),
+					&Default::default(),
+				);
+
+				let mut data = InherentData::new();
+				data.put_data(PARACHAINS_INHERENT_IDENTIFIER, &inherent)
+					.expect("the identifier is not taken yet; qed");
+				let _ = <ParaInherent as ProvideInherent>::create_inherent(&data);
+			});
+		});
+	}
+}
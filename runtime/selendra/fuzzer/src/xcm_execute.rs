@@ -0,0 +1,51 @@
+// Copyright 2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Decodes XCM messages as the runtime receives them from parachains and executes them with the
+//! runtime's XCM configuration.
+
+use codec::DecodeLimit;
+use honggfuzz::fuzz;
+use selendra_runtime::{xcm_config::XcmConfig, Call, Runtime};
+use selendra_runtime_fuzzer::new_ext;
+use sp_runtime::traits::Get;
+use std::convert::TryFrom;
+use xcm::{latest::prelude::*, VersionedXcm, MAX_XCM_DECODE_DEPTH};
+use xcm_executor::XcmExecutor;
+
+fn main() {
+	loop {
+		fuzz!(|data: &[u8]| {
+			let (para, message) = match <(u32, VersionedXcm<Call>)>::decode_all_with_depth_limit(
+				MAX_XCM_DECODE_DEPTH,
+				&mut &data[..],
+			) {
+				Ok(decoded) => decoded,
+				Err(_) => return,
+			};
+			let message = match Xcm::<Call>::try_from(message) {
+				Ok(message) => message,
+				Err(_) => return,
+			};
+
+			new_ext().execute_with(|| {
+				let weight_limit = <Runtime as frame_system::Config>::BlockWeights::get().max_block;
+				let _ =
+					XcmExecutor::<XcmConfig>::execute_xcm(Parachain(para), message, weight_limit);
+			});
+		});
+	}
+}
@@ -22,9 +22,14 @@
 
 use pallet_transaction_payment::CurrencyAdapter;
 use runtime_common::{
-	impls::DealWithFees, paras_registrar, prod_or_fast, slots, BlockHashCount, BlockLength,
-	BlockWeights, CurrencyToVote, OffchainSolutionLengthLimit, OffchainSolutionWeightLimit,
-	RocksDbWeight, SlowAdjustingFeeUpdate,
+	auctions, authorship_stats, batch_transfer, bounty_expiry, candidacy_renewal, crowdloan,
+	equivocation_reports, fee_multiplier, impls::DealWithFees, indices_wind_down, mandatory_weight,
+	meta_tx, multisig_calls, paras_registrar, price_oracle, priority::Lane, prod_or_fast,
+	recovery_probation, safe_mode, scheduler, slash_simulation, slashing_pruning, slots,
+	tips_retirement, treasury_xcm_payouts, upgrade_enactment, validator_count_growth,
+	validator_disabling, validator_operators, BlockHashCount, BlockLength, BlockWeights,
+	CurrencyToVote, OffchainSolutionLengthLimit, OffchainSolutionWeightLimit, RocksDbWeight,
+	SlowAdjustingFeeUpdate,
 };
 
 use runtime_parachains::{
@@ -91,7 +96,7 @@ pub use pallet_timestamp::Call as TimestampCall;
 pub use sp_runtime::BuildStorage;
 
 /// Constant values used within the runtime.
-use selendra_runtime_constants::{currency::*, fee::*, time::*};
+use selendra_runtime_constants::{currency::*, election::MAX_NOMINATIONS, fee::*, time::*};
 
 // Weights used in the runtime.
 mod weights;
@@ -100,6 +105,10 @@ mod bag_thresholds;
 
 pub mod xcm_config;
 
+// Genesis configs of development and test chains.
+#[cfg(feature = "std")]
+pub mod genesis_config_presets;
+
 // Make the WASM binary available.
 #[cfg(feature = "std")]
 include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
@@ -110,13 +119,13 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	spec_name: create_runtime_str!("selendra"),
 	impl_name: create_runtime_str!("selendra-chain"),
 	authoring_version: 1,
-	spec_version: 203,
+	spec_version: 204,
 	impl_version: 0,
 	#[cfg(not(feature = "disable-runtime-api"))]
 	apis: RUNTIME_API_VERSIONS,
 	#[cfg(feature = "disable-runtime-api")]
 	apis: version::create_apis_vec![[]],
-	transaction_version: 4,
+	transaction_version: 5,
 	state_version: 0,
 };
 
@@ -137,13 +146,28 @@ pub struct BaseFilter;
 impl Contains<Call> for BaseFilter {
 	fn contains(call: &Call) -> bool {
 		match call {
+			// Only the calls in `SafeModeCalls` are allowed while the chain is in safe mode.
+			_ if !SafeMode::allows(call) => false,
+			// Indices are being wound down, so no new ones may be claimed.
+			Call::Indices(pallet_indices::Call::claim { .. }) => false,
+			// Large calls have to be scheduled through a preimage.
+			Call::Scheduler(call)
+				if scheduler::exceeds_inline_call_size(call, MaxInlineScheduledCallSize::get()) =>
+				false,
+			// Recoveries are claimed and used on probation through `RecoveryProbation`.
+			Call::Recovery(call) if !RecoveryProbation::recovery_call_allowed(call) => false,
+			// Calls are no longer stored, signatories approve their hash instead.
+			Call::Multisig(pallet_multisig::Call::as_multi { store_call: true, .. }) => false,
 			// These modules are all allowed to be called by transactions:
 			Call::Democracy(_) |
 			Call::Council(_) |
 			Call::TechnicalCommittee(_) |
 			Call::TechnicalMembership(_) |
+			Call::ValidatorOperators(_) |
+			Call::OperatorCommittee(_) |
 			Call::Treasury(_) |
 			Call::PhragmenElection(_) |
+			Call::CandidacyRenewal(_) |
 			Call::System(_) |
 			Call::Scheduler(_) |
 			Call::Preimage(_) |
@@ -151,6 +175,10 @@ impl Contains<Call> for BaseFilter {
 			Call::Babe(_) |
 			Call::Timestamp(_) |
 			Call::Balances(_) |
+			Call::BatchTransfer(_) |
+			Call::MetaTx(_) |
+			Call::TreasuryXcmPayouts(_) |
+			Call::FeeMultiplier(_) |
 			Call::Authorship(_) |
 			Call::Staking(_) |
 			Call::Session(_) |
@@ -162,7 +190,7 @@ impl Contains<Call> for BaseFilter {
 			Call::Proxy(_) |
 			Call::Multisig(_) |
 			Call::Bounties(_) |
-			Call::Tips(_) |
+			Call::BountyExpiry(_) |
 			Call::ElectionProviderMultiPhase(_) |
 			Call::Configuration(_) |
 			Call::ParasShared(_) |
@@ -175,9 +203,17 @@ impl Contains<Call> for BaseFilter {
 			Call::Ump(_) |
 			Call::Hrmp(_) |
 			Call::Slots(_) |
+			Call::Auctions(_) |
+			Call::Crowdloan(_) |
 			Call::Registrar(_) |
 			Call::Recovery(_) |
+			Call::RecoveryProbation(_) |
 			Call::BagsList(_) |
+			Call::IndicesWindDown(_) |
+			Call::SafeMode(_) |
+			Call::ValidatorCountGrowth(_) |
+			Call::UpgradeEnactment(_) |
+			Call::PriceOracle(_) |
 			Call::XcmPallet(_) => true,
 			// All pallets are allowed, but exhaustive match is defensive
 			// in the case of adding new pallets.
@@ -192,7 +228,9 @@ type MoreThanHalfCouncil = EnsureOneOf<
 
 parameter_types! {
 	pub const Version: RuntimeVersion = VERSION;
-	pub const SS58Prefix: u16 = 204;
+	// The registered prefix of Selendra. It lives in storage, so that governance can switch
+	// back with `system.set_storage` should wallets not be ready for it.
+	pub storage SS58Prefix: u16 = 204;
 }
 
 impl frame_system::Config for Runtime {
@@ -226,6 +264,8 @@ parameter_types! {
 	pub MaximumSchedulerWeight: Weight = Perbill::from_percent(80) *
 		BlockWeights::get().max_block;
 	pub const MaxScheduledPerBlock: u32 = 50;
+	/// Calls larger than this have to be noted as a preimage and scheduled by hash.
+	pub const MaxInlineScheduledCallSize: u32 = 128;
 	pub const NoPreimagePostponement: Option<u32> = Some(10);
 }
 
@@ -319,8 +359,11 @@ impl pallet_babe::Config for Runtime {
 		pallet_babe::AuthorityId,
 	)>>::IdentificationTuple;
 
-	type HandleEquivocation =
-		pallet_babe::EquivocationHandler<Self::KeyOwnerIdentification, Offences, ReportLongevity>;
+	type HandleEquivocation = pallet_babe::EquivocationHandler<
+		Self::KeyOwnerIdentification,
+		equivocation_reports::RecordReports<Self, Offences>,
+		ReportLongevity,
+	>;
 
 	type WeightInfo = ();
 
@@ -339,6 +382,15 @@ impl pallet_indices::Config for Runtime {
 	type WeightInfo = weights::pallet_indices::WeightInfo<Runtime>;
 }
 
+parameter_types! {
+	pub const MaxIndexRefundsPerBlock: u32 = 32;
+}
+
+impl indices_wind_down::Config for Runtime {
+	type Event = Event;
+	type MaxRefundsPerBlock = MaxIndexRefundsPerBlock;
+}
+
 parameter_types! {
 	pub const ExistentialDeposit: Balance = EXISTENTIAL_DEPOSIT;
 	pub const MaxLocks: u32 = 50;
@@ -347,7 +399,8 @@ parameter_types! {
 
 impl pallet_balances::Config for Runtime {
 	type Balance = Balance;
-	type DustRemoval = ();
+	// Balances of reaped accounts go to the treasury rather than being burned.
+	type DustRemoval = Treasury;
 	type Event = Event;
 	type ExistentialDeposit = ExistentialDeposit;
 	type AccountStore = System;
@@ -357,6 +410,32 @@ impl pallet_balances::Config for Runtime {
 	type WeightInfo = weights::pallet_balances::WeightInfo<Runtime>;
 }
 
+parameter_types! {
+	pub const MaxBatchTransfers: u32 = 500;
+}
+
+impl batch_transfer::Config for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type MaxTransfers = MaxBatchTransfers;
+	type WeightInfo = weights::runtime_common_batch_transfer::WeightInfo<Runtime>;
+}
+
+parameter_types! {
+	pub const MaxRelayedCalls: u32 = 10;
+	pub const MetaTxRatePeriod: BlockNumber = 1 * HOURS;
+}
+
+impl meta_tx::Config for Runtime {
+	type Event = Event;
+	type Call = Call;
+	type Signature = Signature;
+	type Signer = <Signature as Verify>::Signer;
+	type MaxRelayedCalls = MaxRelayedCalls;
+	type RatePeriod = MetaTxRatePeriod;
+	type WeightInfo = weights::runtime_common_meta_tx::WeightInfo<Runtime>;
+}
+
 parameter_types! {
 	pub const TransactionByteFee: Balance = 500 * NANO;
 	/// This value increases the priority of `Operational` transactions by adding
@@ -365,13 +444,24 @@ parameter_types! {
 }
 
 impl pallet_transaction_payment::Config for Runtime {
-	type OnChargeTransaction = CurrencyAdapter<Balances, DealWithFees<Runtime>>;
+	type OnChargeTransaction =
+		CurrencyAdapter<Balances, fee_multiplier::RecordFees<Runtime, DealWithFees<Runtime>>>;
 	type TransactionByteFee = TransactionByteFee;
 	type OperationalFeeMultiplier = OperationalFeeMultiplier;
 	type WeightToFee = WeightToFee;
 	type FeeMultiplierUpdate = SlowAdjustingFeeUpdate<Self>;
 }
 
+parameter_types! {
+	pub const FeeMultiplierHistoryDepth: u32 = 1 * HOURS;
+}
+
+impl fee_multiplier::Config for Runtime {
+	type Event = Event;
+	type HistoryDepth = FeeMultiplierHistoryDepth;
+	type FullnessOrigin = EnsureRoot<AccountId>;
+}
+
 parameter_types! {
 	pub const MinimumPeriod: u64 = SLOT_DURATION / 2;
 }
@@ -453,14 +543,39 @@ parameter_types! {
 	pub const VoterSnapshotPerBlock: u32 = 22_500;
 }
 
+// `generate_solution_type` takes the number of votes as a literal, so there is a solution type for
+// each value `MAX_NOMINATIONS` can take.
+#[cfg(not(any(feature = "max-nominations-24", feature = "max-nominations-32")))]
 sp_npos_elections::generate_solution_type!(
 	#[compact]
-	pub struct NposCompactSolution16::<
+	pub struct NposCompactSolution::<
 		VoterIndex = u32,
 		TargetIndex = u16,
 		Accuracy = sp_runtime::PerU16,
 	>(16)
 );
+#[cfg(feature = "max-nominations-24")]
+sp_npos_elections::generate_solution_type!(
+	#[compact]
+	pub struct NposCompactSolution::<
+		VoterIndex = u32,
+		TargetIndex = u16,
+		Accuracy = sp_runtime::PerU16,
+	>(24)
+);
+#[cfg(feature = "max-nominations-32")]
+sp_npos_elections::generate_solution_type!(
+	#[compact]
+	pub struct NposCompactSolution::<
+		VoterIndex = u32,
+		TargetIndex = u16,
+		Accuracy = sp_runtime::PerU16,
+	>(32)
+);
+
+const_assert!(
+	<NposCompactSolution as sp_npos_elections::NposSolution>::LIMIT == MAX_NOMINATIONS as usize
+);
 
 impl pallet_election_provider_multi_phase::Config for Runtime {
 	type Event = Event;
@@ -482,14 +597,13 @@ impl pallet_election_provider_multi_phase::Config for Runtime {
 	type OffchainRepeat = OffchainRepeat;
 	type MinerTxPriority = NposSolutionPriority;
 	type DataProvider = Staking;
-	type Solution = NposCompactSolution16;
+	type Solution = NposCompactSolution;
 	type Fallback = pallet_election_provider_multi_phase::NoFallback<Self>;
 	type GovernanceFallback =
 		frame_election_provider_support::onchain::OnChainSequentialPhragmen<Self>;
-	type Solver = frame_election_provider_support::SequentialPhragmen<
+	type Solver = runtime_common::elections::OffchainConfiguredSolver<
 		AccountId,
 		pallet_election_provider_multi_phase::SolutionAccuracyOf<Self>,
-		(),
 	>;
 	type BenchmarkingConfig = runtime_common::elections::BenchmarkConfig;
 	type ForceOrigin = EnsureOneOf<
@@ -530,9 +644,9 @@ parameter_types! {
 	pub const SlashDeferDuration: sp_staking::EraIndex = 12;
 	pub const RewardCurve: &'static PiecewiseLinear<'static> = &REWARD_CURVE;
 	pub const MaxNominatorRewardedPerValidator: u32 = 256;
+	// Offenders stop authoring up to this share of validators, beyond it a new era is forced.
 	pub const OffendingValidatorsThreshold: Perbill = Perbill::from_percent(17);
-	// 16
-	pub const MaxNominations: u32 = <NposCompactSolution16 as sp_npos_elections::NposSolution>::LIMIT as u32;
+	pub const MaxNominations: u32 = MAX_NOMINATIONS;
 }
 
 type SlashCancelOrigin = EnsureOneOf<
@@ -572,6 +686,45 @@ impl pallet_staking::Config for Runtime {
 	type WeightInfo = weights::pallet_staking::WeightInfo<Runtime>;
 }
 
+parameter_types! {
+	// Five more validators every 60 eras (30 days), up to 300.
+	pub const ValidatorCountStepEras: sp_staking::EraIndex = 60;
+	pub const ValidatorCountStepSize: u32 = 5;
+	pub const MaxScheduledValidatorCount: u32 = 300;
+}
+
+impl validator_count_growth::Config for Runtime {
+	type Event = Event;
+	type Staking = Staking;
+	type ControlOrigin = MoreThanHalfCouncil;
+	type StepEras = ValidatorCountStepEras;
+	type StepSize = ValidatorCountStepSize;
+	type MaxValidatorCount = MaxScheduledValidatorCount;
+}
+
+parameter_types! {
+	// As long as the staking rewards can be claimed.
+	pub const AuthorshipStatsHistoryDepth: sp_staking::EraIndex = 84;
+}
+
+impl authorship_stats::Config for Runtime {
+	type FindAuthor = Babe;
+	type ValidatorSet = Session;
+	type Staking = Staking;
+	type HistoryDepth = AuthorshipStatsHistoryDepth;
+}
+
+parameter_types! {
+	pub const MandatoryWeightHistoryDepth: u32 = 1 * HOURS;
+	pub const MandatoryWeightAlertThreshold: Perbill = Perbill::from_percent(50);
+}
+
+impl mandatory_weight::Config for Runtime {
+	type Event = Event;
+	type HistoryDepth = MandatoryWeightHistoryDepth;
+	type AlertThreshold = MandatoryWeightAlertThreshold;
+}
+
 parameter_types! {
 	pub const BasicDeposit: Balance = deposit(1, 258);
 	pub const FieldDeposit: Balance = deposit(0, 66);
@@ -664,7 +817,7 @@ impl pallet_democracy::Config for Runtime {
 	type PreimageByteDeposit = PreimageByteDeposit;
 	type OperationalPreimageOrigin = pallet_collective::EnsureMember<AccountId, CouncilCollective>;
 	type Slash = Treasury;
-	type Scheduler = Scheduler;
+	type Scheduler = scheduler::PostponeOnFullAgenda<Runtime>;
 	type PalletsOrigin = OriginCaller;
 	type MaxVotes = MaxVotes;
 	type WeightInfo = weights::pallet_democracy::WeightInfo<Runtime>;
@@ -709,7 +862,7 @@ impl pallet_elections_phragmen::Config for Runtime {
 	type Event = Event;
 	type PalletId = PhragmenElectionPalletId;
 	type Currency = Balances;
-	type ChangeMembers = Council;
+	type ChangeMembers = CandidacyRenewal;
 	type InitializeMembers = Council;
 	type CurrencyToVote = frame_support::traits::U128CurrencyToVote;
 	type CandidacyBond = CandidacyBond;
@@ -723,6 +876,16 @@ impl pallet_elections_phragmen::Config for Runtime {
 	type WeightInfo = weights::pallet_elections_phragmen::WeightInfo<Runtime>;
 }
 
+parameter_types! {
+	pub const MaxAutoRenewals: u32 = 64;
+}
+
+impl candidacy_renewal::Config for Runtime {
+	type Event = Event;
+	type ChangeMembers = Council;
+	type MaxAutoRenewals = MaxAutoRenewals;
+}
+
 parameter_types! {
 	pub const TechnicalMotionDuration: BlockNumber = 7 * DAYS;
 	pub const TechnicalMaxProposals: u32 = 100;
@@ -755,16 +918,106 @@ impl pallet_membership::Config<pallet_membership::Instance1> for Runtime {
 }
 
 parameter_types! {
-	pub const ProposalBond: Permill = Permill::from_percent(5);
-	pub const ProposalBondMinimum: Balance = 1 * UNITS;
-	pub const ProposalBondMaximum: Balance = 50 * UNITS;
+	pub const MaxSafeModeDuration: BlockNumber = 7 * DAYS;
+}
+
+/// The calls allowed while the chain is in safe mode: those keeping consensus, staking and the
+/// parachains running, and governance.
+pub struct SafeModeCalls;
+impl Contains<Call> for SafeModeCalls {
+	fn contains(c: &Call) -> bool {
+		matches!(
+			c,
+			Call::System(_) |
+				Call::Babe(_) |
+				Call::Timestamp(_) |
+				Call::Authorship(_) |
+				Call::Staking(_) |
+				Call::Session(_) |
+				Call::Grandpa(_) |
+				Call::ImOnline(_) |
+				Call::ElectionProviderMultiPhase(_) |
+				Call::BagsList(_) |
+				Call::Configuration(_) |
+				Call::ParasShared(_) |
+				Call::ParaInclusion(_) |
+				Call::ParaInherent(_) |
+				Call::Paras(_) |
+				Call::Initializer(_) |
+				Call::ParasDisputes(_) |
+				Call::Democracy(_) |
+				Call::Council(_) |
+				Call::TechnicalCommittee(_) |
+				Call::TechnicalMembership(_) |
+				Call::PhragmenElection(_) |
+				Call::Scheduler(_) |
+				Call::Preimage(_) |
+				Call::SafeMode(_) |
+				Call::UpgradeEnactment(_)
+		)
+	}
+}
+
+impl safe_mode::Config for Runtime {
+	type Event = Event;
+	type ForceOrigin = EnsureOneOf<
+		pallet_collective::EnsureProportionAtLeast<AccountId, TechnicalCollective, 2, 3>,
+		EnsureRoot<AccountId>,
+	>;
+	type MaxDuration = MaxSafeModeDuration;
+	type SafeCalls = SafeModeCalls;
+}
+
+parameter_types! {
+	pub UpgradeEnactmentDelay: BlockNumber = prod_or_fast!(1 * DAYS, 1 * MINUTES, "SEL_UPGRADE_ENACTMENT_DELAY");
+}
+
+impl upgrade_enactment::Config for Runtime {
+	type Event = Event;
+	type UpgradeOrigin = EnsureRoot<AccountId>;
+	type EnactmentDelay = UpgradeEnactmentDelay;
+}
+
+parameter_types! {
+	pub const MaxValidatorOperators: u32 = 50;
+	pub OperatorMotionDuration: BlockNumber = prod_or_fast!(1 * DAYS, 2 * MINUTES, "SEL_OPERATOR_MOTION_DURATION");
+	pub const OperatorMaxProposals: u32 = 10;
+}
+
+impl validator_operators::Config for Runtime {
+	type Event = Event;
+	type ValidatorSet = Session;
+	type MembershipChanged = OperatorCommittee;
+	type ManagerOrigin = MoreThanHalfCouncil;
+	type MaxOperators = MaxValidatorOperators;
+}
+
+/// The active validator operators, while they are enabled by the council.
+pub type OperatorCollective = pallet_collective::Instance3;
+impl pallet_collective::Config<OperatorCollective> for Runtime {
+	type Origin = Origin;
+	type Proposal = Call;
+	type Event = Event;
+	type MotionDuration = OperatorMotionDuration;
+	type MaxProposals = OperatorMaxProposals;
+	type MaxMembers = MaxValidatorOperators;
+	type DefaultVote = pallet_collective::MoreThanMajorityThenPrimeDefaultVote;
+	type WeightInfo = weights::pallet_collective_technical_committee::WeightInfo<Runtime>;
+}
+
+parameter_types! {
+	// The proposal bond parameters live in storage, so that governance can adjust them with
+	// `system.set_storage` without a runtime upgrade. The bond is capped at
+	// `ProposalBondMaximum` and returned to the proposer once an approved proposal is paid out.
+	pub storage ProposalBond: Permill = Permill::from_percent(5);
+	pub storage ProposalBondMinimum: Balance = 1 * UNITS;
+	pub storage ProposalBondMaximum: Balance = 50 * UNITS;
 	pub const SpendPeriod: BlockNumber = 3 * DAYS;
 	pub const Burn: Permill = Permill::from_perthousand(2);
 	pub const TreasuryPalletId: PalletId = PalletId(*b"py/trsry");
 
-	pub const TipCountdown: BlockNumber = 1 * DAYS;
+	/// The finder's fee of the retired tips pallet, used to pay out open tips on its removal.
 	pub const TipFindersFee: Percent = Percent::from_percent(20);
-	pub const TipReportDepositBase: Balance = 5000 * CENTS;
 	pub const DataDepositPerByte: Balance = 100 * CENTS;
 	pub const BountyDepositBase: Balance = 5000 * CENTS;
 	pub const BountyDepositPayoutDelay: BlockNumber = 4 * DAYS;
@@ -797,7 +1050,7 @@ impl pallet_treasury::Config for Runtime {
 	type SpendPeriod = SpendPeriod;
 	type Burn = Burn;
 	type BurnDestination = ();
-	type SpendFunds = Bounties;
+	type SpendFunds = (Bounties, TreasuryXcmPayouts);
 	type MaxApprovals = MaxApprovals;
 	type WeightInfo = weights::pallet_treasury::WeightInfo<Runtime>;
 }
@@ -815,21 +1068,33 @@ impl pallet_bounties::Config for Runtime {
 	type WeightInfo = weights::pallet_bounties::WeightInfo<Runtime>;
 }
 
-impl pallet_tips::Config for Runtime {
+parameter_types! {
+	pub const ExpiredCuratorSlash: Permill = Permill::from_percent(50);
+	pub const MaxBountySweepsPerBlock: u32 = 16;
+}
+
+impl bounty_expiry::Config for Runtime {
 	type Event = Event;
-	type DataDepositPerByte = DataDepositPerByte;
-	type MaximumReasonLength = MaximumReasonLength;
-	type Tippers = PhragmenElection;
-	type TipCountdown = TipCountdown;
-	type TipFindersFee = TipFindersFee;
-	type TipReportDepositBase = TipReportDepositBase;
-	type WeightInfo = weights::pallet_tips::WeightInfo<Runtime>;
+	type CuratorSlash = ExpiredCuratorSlash;
+	type MaxSweepsPerBlock = MaxBountySweepsPerBlock;
+	type HorizonOrigin = MoreThanHalfCouncil;
 }
 
 impl pallet_offences::Config for Runtime {
 	type Event = Event;
 	type IdentificationTuple = pallet_session::historical::IdentificationTuple<Self>;
-	type OnOffenceHandler = Staking;
+	type OnOffenceHandler =
+		validator_disabling::StagedDisabling<Self, Staking, OffendingValidatorsThreshold>;
+}
+
+parameter_types! {
+	/// Equivocation reports are valid for `ReportLongevity` slots, which is at most as many blocks.
+	pub EquivocationReportWindow: BlockNumber = ReportLongevity::get() as BlockNumber;
+}
+
+impl equivocation_reports::Config for Runtime {
+	type Event = Event;
+	type ReportWindow = EquivocationReportWindow;
 }
 
 impl pallet_authority_discovery::Config for Runtime {
@@ -871,7 +1136,7 @@ impl pallet_grandpa::Config for Runtime {
 
 	type HandleEquivocation = pallet_grandpa::EquivocationHandler<
 		Self::KeyOwnerIdentification,
-		Offences,
+		equivocation_reports::RecordReports<Self, Offences>,
 		ReportLongevity,
 	>;
 
@@ -940,6 +1205,25 @@ where
 	type OverarchingCall = Call;
 }
 
+parameter_types! {
+	pub const MaxPriceFeeders: u32 = 32;
+	pub const MaxPriceFeedAssets: u32 = 16;
+	pub const MinPriceFeeds: u32 = 3;
+	pub const PriceStalePeriod: BlockNumber = 1 * HOURS;
+}
+
+impl price_oracle::Config for Runtime {
+	type Event = Event;
+	// `0` is SEL, other assets are numbered as they are added.
+	type AssetId = u32;
+	type ManagerOrigin = MoreThanHalfCouncil;
+	type AuthorityId = price_oracle::crypto::OracleAuthId;
+	type MaxFeeders = MaxPriceFeeders;
+	type MaxFeedAssets = MaxPriceFeedAssets;
+	type MinFeeds = MinPriceFeeds;
+	type StalePeriod = PriceStalePeriod;
+}
+
 parameter_types! {
 	pub const ParathreadDeposit: Balance = 500 * UNITS;
 	pub const MaxRetries: u32 = 3;
@@ -1000,6 +1284,19 @@ impl pallet_recovery::Config for Runtime {
 	type RecoveryDeposit = RecoveryDeposit;
 }
 
+parameter_types! {
+	pub const MinRecoveryDelay: BlockNumber = 1 * DAYS;
+	pub const RecoveryProbationPeriod: BlockNumber = 7 * DAYS;
+}
+
+impl recovery_probation::Config for Runtime {
+	type Event = Event;
+	type Call = Call;
+	type ProbationPeriod = RecoveryProbationPeriod;
+	type MinDelayPeriod = MinRecoveryDelay;
+	type ProbationFilter = ProbationCalls;
+}
+
 parameter_types! {
 	// One storage item; key size 32, value size 8; .
 	pub const ProxyDepositBase: Balance = deposit(1, 8);
@@ -1033,6 +1330,7 @@ pub enum ProxyType {
 	// Skip 4 as it is now removed (was SudoBalances)
 	IdentityJudgement = 5,
 	CancelProxy = 6,
+	Auction = 7,
 }
 
 #[cfg(test)]
@@ -1098,7 +1396,7 @@ impl InstanceFilter<Call> for ProxyType {
 				Call::TechnicalMembership(..) |
 				Call::Treasury(..) |
 				Call::Bounties(..) |
-				Call::Tips(..) |
+				Call::TreasuryXcmPayouts(..) |
 				Call::Vesting(pallet_vesting::Call::vest{..}) |
 				Call::Vesting(pallet_vesting::Call::vest_other{..}) |
 				// Specifically omitting Vesting `vested_transfer`, and `force_vested_transfer`
@@ -1110,13 +1408,16 @@ impl InstanceFilter<Call> for ProxyType {
 				Call::Recovery(pallet_recovery::Call::close_recovery {..}) |
 				Call::Recovery(pallet_recovery::Call::remove_recovery {..}) |
 				Call::Recovery(pallet_recovery::Call::cancel_recovered {..}) |
+				Call::RecoveryProbation(..) |
 				Call::Proxy(..) |
 				Call::Multisig(..) |
 				Call::Registrar(paras_registrar::Call::register {..}) |
 				Call::Registrar(paras_registrar::Call::deregister {..}) |
 				// Specifically omitting Registrar `swap`
 				Call::Registrar(paras_registrar::Call::reserve {..}) |
-				Call::Slots(..) | // Specifically omitting the entire XCM Pallet
+				Call::Slots(..) |
+				Call::Auctions(..) |
+				Call::Crowdloan(..) | // Specifically omitting the entire XCM Pallet
 				Call::BagsList(..)
 			),
 			ProxyType::Governance => matches!(
@@ -1125,7 +1426,8 @@ impl InstanceFilter<Call> for ProxyType {
 					Call::Council(..) | Call::TechnicalCommittee(..) |
 					Call::PhragmenElection(..) |
 					Call::Treasury(..) | Call::Bounties(..) |
-					Call::Tips(..) | Call::Utility(..)
+					Call::TreasuryXcmPayouts(..) |
+					Call::Utility(..)
 			),
 			ProxyType::Staking => {
 				matches!(c, Call::Staking(..) | Call::Session(..) | Call::Utility(..))
@@ -1134,9 +1436,15 @@ impl InstanceFilter<Call> for ProxyType {
 				c,
 				Call::Identity(pallet_identity::Call::provide_judgement { .. }) | Call::Utility(..)
 			),
-			ProxyType::CancelProxy => {
-				matches!(c, Call::Proxy(pallet_proxy::Call::reject_announcement { .. }))
-			}
+			ProxyType::CancelProxy => matches!(
+				c,
+				Call::Proxy(pallet_proxy::Call::reject_announcement { .. }) |
+					Call::RecoveryProbation(recovery_probation::Call::cancel_recovered { .. })
+			),
+			ProxyType::Auction => matches!(
+				c,
+				Call::Auctions(..) | Call::Crowdloan(..) | Call::Registrar(..) | Call::Slots(..)
+			),
 		}
 	}
 	fn is_superset(&self, o: &Self) -> bool {
@@ -1150,6 +1458,36 @@ impl InstanceFilter<Call> for ProxyType {
 	}
 }
 
+/// The calls a rescuer may dispatch as a recovered account on probation.
+///
+/// Proxies, multisigs and recoveries dispatch with a new origin which does not carry the
+/// probation filter, so they are rejected along with changes of the staking payee and
+/// controller. Calls wrapped by the utility pallet are checked one by one.
+pub struct ProbationCalls;
+impl Contains<Call> for ProbationCalls {
+	fn contains(c: &Call) -> bool {
+		match c {
+			Call::Proxy(..) |
+			Call::Multisig(..) |
+			Call::Recovery(..) |
+			Call::RecoveryProbation(recovery_probation::Call::as_recovered { .. }) |
+			Call::Staking(
+				pallet_staking::Call::bond { .. } |
+				pallet_staking::Call::set_payee { .. } |
+				pallet_staking::Call::set_controller { .. },
+			) => false,
+			Call::Utility(
+				pallet_utility::Call::batch { calls } | pallet_utility::Call::batch_all { calls },
+			) => calls.iter().all(Self::contains),
+			Call::Utility(
+				pallet_utility::Call::as_derivative { call, .. } |
+				pallet_utility::Call::dispatch_as { call, .. },
+			) => Self::contains(call),
+			_ => ProxyType::NonTransfer.filter(c),
+		}
+	}
+}
+
 impl pallet_proxy::Config for Runtime {
 	type Event = Event;
 	type Call = Call;
@@ -1175,20 +1513,34 @@ impl parachains_shared::Config for Runtime {}
 
 impl parachains_session_info::Config for Runtime {}
 
+/// Validators are rewarded with era points for backing candidates.
+#[cfg(not(feature = "parachain-liveness"))]
+type ParachainRewards = parachains_reward_points::RewardValidatorsWithEraPoints<Runtime>;
+
+/// Validators are rewarded with era points for backing candidates, and any participation in
+/// parachain consensus counts as liveness for `ImOnline`, which spares them the heartbeat.
+#[cfg(feature = "parachain-liveness")]
+type ParachainRewards = (
+	parachains_reward_points::RewardValidatorsWithEraPoints<Runtime>,
+	parachains_reward_points::NoteParticipationAsLiveness<Runtime, ImOnline>,
+);
+
 impl parachains_inclusion::Config for Runtime {
 	type Event = Event;
 	type DisputesHandler = ParasDisputes;
-	type RewardValidators = parachains_reward_points::RewardValidatorsWithEraPoints<Runtime>;
+	type RewardValidators = ParachainRewards;
 }
 
 parameter_types! {
-	pub const ParasUnsignedPriority: TransactionPriority = TransactionPriority::max_value();
+	/// The priority of unsigned transactions maintaining parachain consensus, such as PVF
+	/// pre-checking votes and dispute statements gossiped outside of the parachains inherent.
+	pub const ConsensusMaintenancePriority: TransactionPriority = TransactionPriority::max_value();
 }
 
 impl parachains_paras::Config for Runtime {
 	type Event = Event;
 	type WeightInfo = weights::runtime_parachains_paras::WeightInfo<Runtime>;
-	type UnsignedPriority = ParasUnsignedPriority;
+	type UnsignedPriority = ConsensusMaintenancePriority;
 	type NextSessionRotation = Babe;
 }
 
@@ -1229,7 +1581,13 @@ impl parachains_initializer::Config for Runtime {
 impl parachains_disputes::Config for Runtime {
 	type Event = Event;
 	type RewardValidators = ();
-	type PunishValidators = ();
+	type PunishValidators = parachains_disputes::DisableBackers<Runtime>;
+	type UnsignedPriority = ConsensusMaintenancePriority;
+	/// Two thirds of the validator operators can unfreeze the chain after a dispute, as can root.
+	type ForceOrigin = EnsureOneOf<
+		EnsureRoot<AccountId>,
+		pallet_collective::EnsureProportionAtLeast<AccountId, OperatorCollective, 2, 3>,
+	>;
 	type WeightInfo = weights::runtime_parachains_disputes::WeightInfo<Runtime>;
 }
 
@@ -1238,6 +1596,8 @@ parameter_types! {
 	// `ParaId`s.
 	pub const ParaDeposit: Balance = 100 * UNITS;
 	pub const ParaDataByteDeposit: Balance = deposit(0, 1);
+	// Enough for the name and website of a para, longer ones are better kept off chain.
+	pub const MaxParaMetadataLength: u32 = 64;
 }
 
 impl paras_registrar::Config for Runtime {
@@ -1248,9 +1608,10 @@ impl paras_registrar::Config for Runtime {
 		pallet_collective::EnsureProportionAtLeast<AccountId, TechnicalCollective, 2, 3>,
 	>;
 	type Currency = Balances;
-	type OnSwap = Slots;
+	type OnSwap = (Crowdloan, Slots);
 	type ParaDeposit = ParaDeposit;
 	type DataDepositPerByte = ParaDataByteDeposit;
+	type MaxMetadataLength = MaxParaMetadataLength;
 	type WeightInfo = weights::runtime_common_paras_registrar::WeightInfo<Runtime>;
 }
 
@@ -1269,6 +1630,58 @@ impl slots::Config for Runtime {
 	type WeightInfo = weights::runtime_common_slots::WeightInfo<Runtime>;
 }
 
+parameter_types! {
+	// Bids may end the auction at any point of its last 5 days.
+	pub const EndingPeriod: BlockNumber = 5 * DAYS;
+	// The winning bids are sampled every two minutes of the ending period.
+	pub const SampleLength: BlockNumber = 2 * MINUTES;
+}
+
+impl auctions::Config for Runtime {
+	type Event = Event;
+	type Leaser = Slots;
+	type Registrar = Registrar;
+	type EndingPeriod = EndingPeriod;
+	type SampleLength = SampleLength;
+	type Randomness = pallet_babe::RandomnessFromOneEpochAgo<Runtime>;
+	type InitiateOrigin = MoreThanHalfCouncil;
+	type WeightInfo = weights::runtime_common_auctions::WeightInfo<Runtime>;
+}
+
+parameter_types! {
+	pub const CrowdloanId: PalletId = PalletId(*b"py/cfund");
+	pub const SubmissionDeposit: Balance = 100 * UNITS;
+	pub const MinContribution: Balance = 5 * UNITS;
+	pub const RemoveKeysLimit: u32 = 1000;
+	// Allow 32 bytes for an additional memo to a crowdloan.
+	pub const MaxMemoLength: u8 = 32;
+}
+
+impl crowdloan::Config for Runtime {
+	type Event = Event;
+	type PalletId = CrowdloanId;
+	type SubmissionDeposit = SubmissionDeposit;
+	type MinContribution = MinContribution;
+	type RemoveKeysLimit = RemoveKeysLimit;
+	type Registrar = Registrar;
+	type Auctioneer = Auctions;
+	type MaxMemoLength = MaxMemoLength;
+	type WeightInfo = weights::runtime_common_crowdloan::WeightInfo<Runtime>;
+}
+
+parameter_types! {
+	pub const MaxTreasuryXcmPayouts: u32 = 100;
+}
+
+impl treasury_xcm_payouts::Config for Runtime {
+	type Event = Event;
+	type ApproveOrigin = ApproveOrigin;
+	type Registrar = Registrar;
+	type XcmSender = xcm_config::XcmRouter;
+	type MaxPayouts = MaxTreasuryXcmPayouts;
+	type WeightInfo = weights::runtime_common_treasury_xcm_payouts::WeightInfo<Runtime>;
+}
+
 construct_runtime! {
 	pub enum Runtime where
 		Block = Block,
@@ -1284,7 +1697,9 @@ construct_runtime! {
 		Timestamp: pallet_timestamp::{Pallet, Call, Storage, Inherent} = 2,
 		Indices: pallet_indices::{Pallet, Call, Storage, Config<T>, Event<T>} = 3,
 		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>} = 4,
+		BatchTransfer: batch_transfer::{Pallet, Call, Event<T>} = 26,
 		TransactionPayment: pallet_transaction_payment::{Pallet, Storage} = 33,
+		FeeMultiplier: fee_multiplier::{Pallet, Call, Storage, Event<T>} = 23,
 
 		// Consensus support.
 		Authorship: pallet_authorship::{Pallet, Call, Storage} = 5,
@@ -1301,7 +1716,11 @@ construct_runtime! {
 		Council: pallet_collective::<Instance1>::{Pallet, Call, Storage, Origin<T>, Event<T>, Config<T>} = 14,
 		TechnicalCommittee: pallet_collective::<Instance2>::{Pallet, Call, Storage, Origin<T>, Event<T>, Config<T>} = 15,
 		PhragmenElection: pallet_elections_phragmen::{Pallet, Call, Storage, Event<T>, Config<T>} = 16,
+		// Must come after `PhragmenElection`.
+		CandidacyRenewal: candidacy_renewal::{Pallet, Call, Storage, Event<T>} = 19,
 		TechnicalMembership: pallet_membership::<Instance1>::{Pallet, Call, Storage, Event<T>, Config<T>} = 17,
+		ValidatorOperators: validator_operators::{Pallet, Call, Storage, Event<T>} = 20,
+		OperatorCommittee: pallet_collective::<Instance3>::{Pallet, Call, Storage, Origin<T>, Event<T>} = 64,
 		Treasury: pallet_treasury::{Pallet, Call, Storage, Config, Event<T>} = 18,
 
 		// Utility module.
@@ -1312,6 +1731,7 @@ construct_runtime! {
 
 		// Social recovery module.
 		Recovery: pallet_recovery::{Pallet, Call, Storage, Event<T>} = 27,
+		RecoveryProbation: recovery_probation::{Pallet, Call, Storage, Event<T>} = 22,
 
 		// Vesting. Usable initially, but removed once all vesting is finished.
 		Vesting: pallet_vesting::{Pallet, Call, Storage, Event<T>, Config<T>} = 28,
@@ -1330,9 +1750,9 @@ construct_runtime! {
 
 		// Bounties module.
 		Bounties: pallet_bounties::{Pallet, Call, Storage, Event<T>} = 35,
+		BountyExpiry: bounty_expiry::{Pallet, Call, Storage, Event<T>} = 21,
 
-		// Tips module.
-		Tips: pallet_tips::{Pallet, Call, Storage, Event<T>} = 36,
+		// Index 36 was used by the tips pallet, retired in favour of treasury spends.
 
 		// Election pallet. Only works with staking, but placed here to maintain indices.
 		ElectionProviderMultiPhase: pallet_election_provider_multi_phase::{Pallet, Call, Storage, Event<T>, ValidateUnsigned} = 37,
@@ -1340,6 +1760,38 @@ construct_runtime! {
 		// Provides a semi-sorted list of nominators for staking.
 		BagsList: pallet_bags_list::{Pallet, Call, Storage, Event<T>} = 39,
 
+		// Releases all account indices and refunds their deposits.
+		IndicesWindDown: indices_wind_down::{Pallet, Call, Storage, Event<T>} = 40,
+
+		// Restricts the chain to the calls in `SafeModeCalls` as an incident response.
+		SafeMode: safe_mode::{Pallet, Call, Storage, Event<T>} = 41,
+
+		// Grows the validator count of staking on a schedule.
+		ValidatorCountGrowth: validator_count_growth::{Pallet, Call, Storage, Event<T>} = 42,
+
+		// Index 43 was used by the EVM payee pallet, retired as the runtimes have no EVM.
+
+		// Enacts runtime upgrades a fixed delay after they are approved.
+		UpgradeEnactment: upgrade_enactment::{Pallet, Call, Storage, Event<T>} = 44,
+
+		// Counts the equivocation reports of BABE and GRANDPA and names their reporters.
+		EquivocationReports: equivocation_reports::{Pallet, Storage, Event<T>} = 45,
+
+		// Counts the blocks authored by every validator per session and era.
+		AuthorshipStats: authorship_stats::{Pallet, Storage} = 46,
+
+		// Records the weight of the mandatory work of initializing blocks.
+		MandatoryWeight: mandatory_weight::{Pallet, Storage, Event<T>} = 47,
+
+		// Dispatches calls signed by users and submitted by relayers paying their fees.
+		MetaTx: meta_tx::{Pallet, Call, Storage, Event<T>} = 48,
+
+		// Pays approved treasury spends to accounts on parachains.
+		TreasuryXcmPayouts: treasury_xcm_payouts::{Pallet, Call, Storage, Event<T>} = 49,
+
+		// Prices of SEL and other assets.
+		PriceOracle: price_oracle::{Pallet, Call, Storage, Event<T>} = 38,
+
 		// Parachains pallets. Start indices at 50 to leave room.
 		ParachainsOrigin: parachains_origin::{Pallet, Origin} = 50,
 		Configuration: parachains_configuration::{Pallet, Call, Storage, Config<T>} = 51,
@@ -1347,17 +1799,19 @@ construct_runtime! {
 		ParaInclusion: parachains_inclusion::{Pallet, Call, Storage, Event<T>} = 53,
 		ParaInherent: parachains_paras_inherent::{Pallet, Call, Storage, Inherent} = 54,
 		ParaScheduler: parachains_scheduler::{Pallet, Storage} = 55,
-		Paras: parachains_paras::{Pallet, Call, Storage, Event, Config} = 56,
+		Paras: parachains_paras::{Pallet, Call, Storage, Event, Config, ValidateUnsigned} = 56,
 		Initializer: parachains_initializer::{Pallet, Call, Storage} = 57,
 		Dmp: parachains_dmp::{Pallet, Call, Storage} = 58,
 		Ump: parachains_ump::{Pallet, Call, Storage, Event} = 59,
 		Hrmp: parachains_hrmp::{Pallet, Call, Storage, Event<T>, Config} = 60,
 		ParaSessionInfo: parachains_session_info::{Pallet, Storage} = 61,
-		ParasDisputes: parachains_disputes::{Pallet, Call, Storage, Event<T>} = 62,
+		ParasDisputes: parachains_disputes::{Pallet, Call, Storage, Event<T>, ValidateUnsigned} = 62,
 
 		// Parachain Onboarding Pallets. Start indices at 70 to leave room.
 		Registrar: paras_registrar::{Pallet, Call, Storage, Event<T>} = 70,
 		Slots: slots::{Pallet, Call, Storage, Event<T>} = 71,
+		Auctions: auctions::{Pallet, Call, Storage, Event<T>} = 72,
+		Crowdloan: crowdloan::{Pallet, Call, Storage, Event<T>} = 73,
 
 		// Pallet for sending XCM.
 		XcmPallet: pallet_xcm::{Pallet, Call, Storage, Event<T>, Origin, Config} = 99,
@@ -1393,12 +1847,37 @@ pub type Executive = frame_executive::Executive<
 	Block,
 	frame_system::ChainContext<Runtime>,
 	Runtime,
-	AllPalletsWithSystem,
-	(),
+	mandatory_weight::MeasureOnInitialize<Runtime, AllPalletsWithSystem>,
+	// One-shot migrations, to be removed after the next release.
+	(
+		tips_retirement::RetireTips<Runtime, TipFindersFee>,
+		multisig_calls::RefundStoredCalls<Runtime, Balances>,
+		slashing_pruning::PruneSlashingState<Runtime, Balance, BondingDuration>,
+	),
 >;
 /// The payload being signed in transactions.
 pub type SignedPayload = generic::SignedPayload<Call, SignedExtra>;
 
+/// The lane of the transaction pool `xt` is prioritised in.
+fn transaction_lane(xt: &UncheckedExtrinsic) -> Lane {
+	match &xt.function {
+		Call::ImOnline(pallet_im_online::Call::heartbeat { .. }) |
+		Call::Paras(parachains_paras::Call::include_pvf_check_statement { .. }) => Lane::ParachainData,
+		Call::Babe(
+			pallet_babe::Call::report_equivocation { .. } |
+			pallet_babe::Call::report_equivocation_unsigned { .. },
+		) |
+		Call::Grandpa(
+			pallet_grandpa::Call::report_equivocation { .. } |
+			pallet_grandpa::Call::report_equivocation_unsigned { .. },
+		) => Lane::Dispute,
+		Call::ElectionProviderMultiPhase(
+			EPMCall::submit { .. } | EPMCall::submit_unsigned { .. },
+		) => Lane::ElectionSolution,
+		_ => Lane::Normal,
+	}
+}
+
 #[cfg(feature = "runtime-benchmarks")]
 #[macro_use]
 extern crate frame_benchmarking;
@@ -1409,7 +1888,12 @@ mod benches {
 		// Selendra
 		// NOTE: Make sure to prefix these with `runtime_common::` so
 		// the that path resolves correctly in the generated file.
+		[runtime_common::auctions, Auctions]
+		[runtime_common::crowdloan, Crowdloan]
+		[runtime_common::batch_transfer, BatchTransfer]
+		[runtime_common::meta_tx, MetaTx]
 		[runtime_common::slots, Slots]
+		[runtime_common::treasury_xcm_payouts, TreasuryXcmPayouts]
 		[runtime_common::paras_registrar, Registrar]
 		[runtime_parachains::configuration, Configuration]
 		[runtime_parachains::disputes, ParasDisputes]
@@ -1440,7 +1924,6 @@ mod benches {
 		[pallet_staking, Staking]
 		[frame_system, SystemBench::<Runtime>]
 		[pallet_timestamp, Timestamp]
-		[pallet_tips, Tips]
 		[pallet_treasury, Treasury]
 		[pallet_utility, Utility]
 		[pallet_vesting, Vesting]
@@ -1496,7 +1979,9 @@ sp_api::impl_runtime_apis! {
 			tx: <Block as BlockT>::Extrinsic,
 			block_hash: <Block as BlockT>::Hash,
 		) -> TransactionValidity {
-			Executive::validate_transaction(source, tx, block_hash)
+			let lane = transaction_lane(&tx);
+			let validity = Executive::validate_transaction(source, tx, block_hash);
+			runtime_common::priority::in_lane(validity, lane)
 		}
 	}
 
@@ -1762,6 +2247,160 @@ sp_api::impl_runtime_apis! {
 		}
 	}
 
+	impl primitives::fees::FeeMultiplierApi<Block, Balance, BlockNumber> for Runtime {
+		fn next_fee_multiplier() -> primitives::fees::Multiplier {
+			TransactionPayment::next_fee_multiplier()
+		}
+		fn target_block_fullness() -> primitives::fees::Perquintill {
+			FeeMultiplier::target_block_fullness()
+		}
+		fn projected_fees() -> Vec<(primitives::fees::StandardTransaction, Balance)> {
+			fee_multiplier::projected_fees::<Runtime>()
+		}
+		fn multiplier_history() -> Vec<(BlockNumber, primitives::fees::Multiplier)> {
+			FeeMultiplier::multiplier_history()
+		}
+		fn fee_history(count: u32) -> Vec<primitives::fees::BlockFees<BlockNumber, Balance>> {
+			FeeMultiplier::fee_history(count)
+		}
+	}
+
+	impl primitives::equivocation::EquivocationReportsApi<Block> for Runtime {
+		fn report_counts() -> Vec<(primitives::equivocation::Kind, u32)> {
+			EquivocationReports::report_counts()
+		}
+	}
+
+	impl primitives::disputes::DisputeStatementSubmissionApi<Block> for Runtime {
+		fn submit_dispute_statement_set(set: primitives::v1::DisputeStatementSet) {
+			parachains_runtime_api_impl::submit_dispute_statement_set::<Runtime>(set)
+		}
+	}
+
+	impl primitives::executor_params::ExecutorParamsApi<Block> for Runtime {
+		fn session_executor_params(
+			session_index: SessionIndex,
+		) -> Option<primitives::v1::ExecutorParams> {
+			parachains_runtime_api_impl::session_executor_params::<Runtime>(session_index)
+		}
+	}
+
+	impl primitives::relay_state_proof::RelayStateProofApi<Block> for Runtime {
+		fn relay_state_proof_keys(para_id: ParaId) -> Vec<Vec<u8>> {
+			parachains_runtime_api_impl::relay_state_proof_keys::<Runtime>(para_id)
+		}
+	}
+
+	impl primitives::para_usage::ParaUsageApi<Block> for Runtime {
+		fn session_usage() -> Vec<(ParaId, primitives::para_usage::ParaUsage)> {
+			ParaInclusion::session_usage()
+		}
+	}
+
+	impl primitives::address::AddressFormatApi<Block> for Runtime {
+		fn ss58_prefix() -> u16 {
+			SS58Prefix::get()
+		}
+		fn legacy_ss58_prefixes() -> Vec<u16> {
+			vec![primitives::address::LEGACY_SS58_PREFIX]
+		}
+	}
+
+	impl primitives::build_info::BuildInfoApi<Block> for Runtime {
+		fn build_info() -> primitives::build_info::BuildInfo {
+			primitives::build_info::BuildInfo {
+				git_commit: option_env!("SELENDRA_RUNTIME_GIT_COMMIT").unwrap_or("unknown").into(),
+				profile: env!("SELENDRA_RUNTIME_PROFILE").into(),
+				builder: option_env!("SELENDRA_RUNTIME_BUILDER").map(Into::into),
+			}
+		}
+	}
+
+	impl primitives::events::EventsSchemaApi<Block> for Runtime {
+		fn events_schema() -> primitives::events::EventsSchema {
+			runtime_common::events_schema::events_schema(Runtime::metadata(), VERSION.spec_version)
+		}
+	}
+
+	impl primitives::events::BlockEventsApi<Block> for Runtime {
+		fn block_events(events: Vec<u8>) -> Option<Vec<primitives::events::BlockEvent>> {
+			runtime_common::events_schema::block_events::<Runtime>(&events)
+		}
+	}
+
+	impl runtime_common::democracy::DemocracyApi<Block, AccountId, Balance, BlockNumber> for Runtime {
+		fn voting_power(
+			who: AccountId,
+		) -> runtime_common::democracy::VotingPower<AccountId, Balance, BlockNumber> {
+			runtime_common::democracy::voting_power::<Runtime>(who)
+		}
+	}
+
+	impl runtime_common::governance::GovernanceApi<Block, AccountId, Balance, BlockNumber, Hash> for Runtime {
+		fn governance_activity(
+		) -> runtime_common::governance::GovernanceActivity<AccountId, Balance, BlockNumber, Hash> {
+			runtime_common::governance::governance_activity::<Runtime, CouncilCollective>()
+		}
+	}
+
+	impl runtime_common::reserves::ReservesApi<Block, AccountId, [u8; 8], Balance> for Runtime {
+		fn reserves(who: AccountId) -> runtime_common::reserves::AccountReserves<[u8; 8], Balance> {
+			runtime_common::reserves::reserves::<Runtime>(&who)
+		}
+	}
+
+	impl authorship_stats::AuthorshipStatsApi<Block, AccountId> for Runtime {
+		fn session_authored_blocks(session: SessionIndex) -> Vec<(AccountId, u32)> {
+			AuthorshipStats::session_authored_blocks(session)
+		}
+
+		fn era_authored_blocks(era: sp_staking::EraIndex) -> Vec<(AccountId, u32)> {
+			AuthorshipStats::era_authored_blocks(era)
+		}
+	}
+
+	impl mandatory_weight::MandatoryWeightApi<Block, BlockNumber> for Runtime {
+		fn mandatory_weight_history() -> Vec<mandatory_weight::MandatoryWeight<BlockNumber>> {
+			MandatoryWeight::history()
+		}
+	}
+
+	impl slash_simulation::SlashSimulationApi<Block, AccountId, Balance> for Runtime {
+		fn simulate_offences(
+			offences: Vec<(AccountId, Perbill)>,
+		) -> Vec<slash_simulation::SimulatedSlash<AccountId, Balance>> {
+			slash_simulation::simulate_offences::<Runtime, Staking>(offences)
+		}
+	}
+
+	impl runtime_common::staking::StakingApi<Block, AccountId, Balance> for Runtime {
+		fn active_era() -> Option<pallet_staking::EraIndex> {
+			runtime_common::staking::active_era::<Runtime>()
+		}
+
+		fn validator_info(
+			stash: AccountId,
+		) -> Option<runtime_common::staking::ValidatorInfo<AccountId, Balance>> {
+			runtime_common::staking::validator_info::<Runtime>(stash)
+		}
+
+		fn pending_rewards(
+			stash: AccountId,
+		) -> Vec<runtime_common::staking::PendingReward<AccountId, Balance>> {
+			runtime_common::staking::pending_rewards::<Runtime>(stash)
+		}
+	}
+
+	impl paras_registrar::ParaMetadataApi<Block> for Runtime {
+		fn para_metadata(para: ParaId) -> Option<paras_registrar::ParaMetadata<Vec<u8>>> {
+			Registrar::para_metadata(para)
+		}
+
+		fn all_para_metadata() -> Vec<(ParaId, paras_registrar::ParaMetadata<Vec<u8>>)> {
+			Registrar::all_para_metadata()
+		}
+	}
+
 	#[cfg(feature = "try-runtime")]
 	impl frame_try_runtime::TryRuntime<Block> for Runtime {
 		fn on_runtime_upgrade() -> (Weight, Weight) {
@@ -1914,6 +2553,46 @@ mod test_fees {
 		test_with_multiplier(Multiplier::saturating_from_rational(1, 1_000_000_000u128));
 	}
 
+	#[test]
+	fn transfer_weight_matches_fee_calibration() {
+		let call = pallet_balances::Call::<Runtime>::transfer_keep_alive {
+			dest: Charlie.to_account_id().into(),
+			value: Default::default(),
+		};
+		let weight = call.get_dispatch_info().weight + runtime_common::ExtrinsicBaseWeight::get();
+		// If this fails, the benchmarked weight of a transfer changed. Update `TRANSFER_WEIGHT`
+		// so that transfers keep costing `TRANSFER_FEE`.
+		assert_eq_error_rate!(weight, TRANSFER_WEIGHT, TRANSFER_WEIGHT / 20);
+	}
+
+	#[test]
+	fn fees_of_representative_extrinsics() {
+		let fee_of = |call: Call| {
+			let info = call.get_dispatch_info();
+			let len = call.encoded_size() as u32;
+			sp_io::TestExternalities::new_empty()
+				.execute_with(|| TransactionPayment::compute_fee(len, &info, 0))
+		};
+
+		let transfer = fee_of(Call::Balances(pallet_balances::Call::transfer_keep_alive {
+			dest: Charlie.to_account_id().into(),
+			value: 1 * UNITS,
+		}));
+		let bond = fee_of(Call::Staking(pallet_staking::Call::bond {
+			controller: Charlie.to_account_id().into(),
+			value: 1 * UNITS,
+			payee: pallet_staking::RewardDestination::Staked,
+		}));
+		let nominate = fee_of(Call::Staking(pallet_staking::Call::nominate {
+			targets: vec![Charlie.to_account_id().into()],
+		}));
+
+		// Fees at a multiplier of one, including the length fee.
+		assert_eq_error_rate!(transfer, 102 * CENTS / 100, transfer / 20);
+		assert_eq_error_rate!(bond, 263 * CENTS / 100, bond / 20);
+		assert_eq_error_rate!(nominate, 428 * CENTS / 100, nominate / 20);
+	}
+
 	#[test]
 	fn full_block_council_election_cost() {
 		// the number of voters needed to consume almost a full block in council election, and how
@@ -1995,4 +2674,163 @@ mod test {
 			If the limit is too strong, maybe consider increase the limit",
 		);
 	}
+
+	#[test]
+	fn events_schema_keeps_pallet_indices() {
+		let schema =
+			runtime_common::events_schema::events_schema(Runtime::metadata(), VERSION.spec_version);
+		assert_eq!(schema.spec_version, VERSION.spec_version);
+
+		// Events and errors of past blocks are decoded with these indices, so they must not change.
+		let indices = [
+			(0, "System"),
+			(3, "Indices"),
+			(4, "Balances"),
+			(6, "Staking"),
+			(7, "Offences"),
+			(8, "Session"),
+			(10, "Grandpa"),
+			(11, "ImOnline"),
+			(13, "Democracy"),
+			(14, "Council"),
+			(15, "TechnicalCommittee"),
+			(16, "PhragmenElection"),
+			(17, "TechnicalMembership"),
+			(18, "Treasury"),
+			(19, "CandidacyRenewal"),
+			(20, "ValidatorOperators"),
+			(21, "BountyExpiry"),
+			(22, "RecoveryProbation"),
+			(23, "FeeMultiplier"),
+			(24, "Utility"),
+			(25, "Identity"),
+			(26, "BatchTransfer"),
+			(27, "Recovery"),
+			(28, "Vesting"),
+			(29, "Scheduler"),
+			(30, "Proxy"),
+			(31, "Multisig"),
+			(32, "Preimage"),
+			(35, "Bounties"),
+			(37, "ElectionProviderMultiPhase"),
+			(38, "PriceOracle"),
+			(39, "BagsList"),
+			(40, "IndicesWindDown"),
+			(41, "SafeMode"),
+			(42, "ValidatorCountGrowth"),
+			(44, "UpgradeEnactment"),
+			(45, "EquivocationReports"),
+			(47, "MandatoryWeight"),
+			(48, "MetaTx"),
+			(49, "TreasuryXcmPayouts"),
+			(53, "ParaInclusion"),
+			(56, "Paras"),
+			(59, "Ump"),
+			(60, "Hrmp"),
+			(62, "ParasDisputes"),
+			(64, "OperatorCommittee"),
+			(70, "Registrar"),
+			(71, "Slots"),
+			(72, "Auctions"),
+			(73, "Crowdloan"),
+			(99, "XcmPallet"),
+		];
+		for (index, name) in indices {
+			assert_eq!(schema.pallet(index).map(|pallet| pallet.name.as_str()), Some(name));
+		}
+
+		let balances = schema.pallet(4).unwrap();
+		assert!(balances.events.iter().any(|event| event.name == "Transfer"));
+		assert!(balances.errors.iter().any(|error| error.name == "InsufficientBalance"));
+		assert!(schema.pallets.windows(2).all(|pair| pair[0].index < pair[1].index));
+	}
+
+	#[test]
+	fn dust_goes_to_treasury() {
+		use frame_support::{
+			assert_ok,
+			traits::{Currency, ExistenceRequirement::AllowDeath},
+		};
+		use keyring::Sr25519Keyring::{Alice, Bob};
+
+		let mut t = frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+		pallet_balances::GenesisConfig::<Runtime> {
+			balances: vec![(Alice.to_account_id(), 1 * UNITS), (Treasury::account_id(), 1 * UNITS)],
+		}
+		.assimilate_storage(&mut t)
+		.unwrap();
+		sp_io::TestExternalities::new(t).execute_with(|| {
+			let dust = EXISTENTIAL_DEPOSIT / 2;
+			assert_ok!(<Balances as Currency<_>>::transfer(
+				&Alice.to_account_id(),
+				&Bob.to_account_id(),
+				1 * UNITS - dust,
+				AllowDeath,
+			));
+
+			assert_eq!(Balances::free_balance(Alice.to_account_id()), 0);
+			assert_eq!(Balances::free_balance(Treasury::account_id()), 1 * UNITS + dust);
+		});
+	}
+
+	#[test]
+	fn rescuer_cannot_add_proxy_during_probation() {
+		use frame_support::assert_ok;
+		use keyring::Sr25519Keyring::{Alice, Bob, Charlie, Dave};
+
+		let (lost, rescuer) = (Alice.to_account_id(), Dave.to_account_id());
+		let mut friends = vec![Bob.to_account_id(), Charlie.to_account_id()];
+		friends.sort();
+
+		let mut t = frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+		pallet_balances::GenesisConfig::<Runtime> {
+			balances: vec![
+				(lost.clone(), 100 * UNITS),
+				(friends[0].clone(), 100 * UNITS),
+				(friends[1].clone(), 100 * UNITS),
+				(rescuer.clone(), 100 * UNITS),
+			],
+		}
+		.assimilate_storage(&mut t)
+		.unwrap();
+		sp_io::TestExternalities::new(t).execute_with(|| {
+			System::set_block_number(1);
+			assert_ok!(Recovery::create_recovery(
+				Origin::signed(lost.clone()),
+				friends.clone(),
+				2,
+				MinRecoveryDelay::get(),
+			));
+			assert_ok!(Recovery::initiate_recovery(Origin::signed(rescuer.clone()), lost.clone()));
+			for friend in &friends {
+				assert_ok!(Recovery::vouch_recovery(
+					Origin::signed(friend.clone()),
+					lost.clone(),
+					rescuer.clone(),
+				));
+			}
+			System::set_block_number(1 + MinRecoveryDelay::get());
+			assert_ok!(RecoveryProbation::claim_recovery(
+				Origin::signed(rescuer.clone()),
+				lost.clone()
+			));
+
+			// A proxy of the recovered account would dispatch without the probation filter.
+			let add_proxy = Call::Proxy(pallet_proxy::Call::add_proxy {
+				delegate: rescuer.clone(),
+				proxy_type: ProxyType::Any,
+				delay: 0,
+			});
+			let batch =
+				Call::Utility(pallet_utility::Call::batch { calls: vec![add_proxy.clone()] });
+			for call in [add_proxy, batch] {
+				assert_ok!(RecoveryProbation::as_recovered(
+					Origin::signed(rescuer.clone()),
+					lost.clone(),
+					Box::new(call),
+				));
+				assert!(Proxy::proxies(&lost).0.is_empty());
+			}
+		});
+	}
 }
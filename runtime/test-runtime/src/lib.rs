@@ -468,6 +468,8 @@ impl parachains_disputes::Config for Runtime {
 	type Event = Event;
 	type RewardValidators = ();
 	type PunishValidators = ();
+	type UnsignedPriority = ParasUnsignedPriority;
+	type ForceOrigin = frame_system::EnsureRoot<AccountId>;
 	type WeightInfo = parachains_disputes::TestWeightInfo;
 }
 
@@ -668,7 +670,7 @@ construct_runtime! {
 		ParaInclusion: parachains_inclusion::{Pallet, Call, Storage, Event<T>},
 		ParaInherent: parachains_paras_inherent::{Pallet, Call, Storage, Inherent},
 		Initializer: parachains_initializer::{Pallet, Call, Storage},
-		Paras: parachains_paras::{Pallet, Call, Storage, Event},
+		Paras: parachains_paras::{Pallet, Call, Storage, Event, ValidateUnsigned},
 		ParasShared: parachains_shared::{Pallet, Call, Storage},
 		Scheduler: parachains_scheduler::{Pallet, Storage},
 		ParasSudoWrapper: paras_sudo_wrapper::{Pallet, Call},
@@ -678,7 +680,7 @@ construct_runtime! {
 		Ump: parachains_ump::{Pallet, Call, Storage, Event},
 		Dmp: parachains_dmp::{Pallet, Call, Storage},
 		Xcm: pallet_xcm::{Pallet, Call, Event<T>, Origin},
-		ParasDisputes: parachains_disputes::{Pallet, Storage, Event<T>},
+		ParasDisputes: parachains_disputes::{Pallet, Call, Storage, Event<T>, ValidateUnsigned},
 
 		Sudo: pallet_sudo::{Pallet, Call, Storage, Config<T>, Event<T>},
 
@@ -787,6 +789,20 @@ sp_api::impl_runtime_apis! {
 		}
 	}
 
+	impl primitives::executor_params::ExecutorParamsApi<Block> for Runtime {
+		fn session_executor_params(
+			session_index: SessionIndex,
+		) -> Option<primitives::v1::ExecutorParams> {
+			runtime_impl::session_executor_params::<Runtime>(session_index)
+		}
+	}
+
+	impl primitives::relay_state_proof::RelayStateProofApi<Block> for Runtime {
+		fn relay_state_proof_keys(para_id: ParaId) -> Vec<Vec<u8>> {
+			runtime_impl::relay_state_proof_keys::<Runtime>(para_id)
+		}
+	}
+
 	impl primitives::v2::ParachainHost<Block, Hash, BlockNumber> for Runtime {
 		fn validators() -> Vec<ValidatorId> {
 			runtime_impl::validators::<Runtime>()